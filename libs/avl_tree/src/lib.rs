@@ -11,6 +11,32 @@
 //!
 //! # できること
 //!
+//! ## 順序統計量
+//!
+//! ソート済みに保って使うと、各ノードが部分木の大きさを持っているので、順序統計量がどれも
+//! $O(\log n)$ で求まります。
+//!
+//! - $k$ 番目の要素: [`get`](AvlTree::get)
+//! - $k$ 番目の要素の削除: [`remove`](AvlTree::remove)
+//! - `x` 未満の要素の個数: [`lower_bound`](AvlTree::lower_bound)
+//! - 挿入: [`partition_point`](AvlTree::partition_point) で位置を求めて [`insert`](AvlTree::insert)
+//!
+//! ```
+//! # use avl_tree::AvlTree;
+//! let mut avl = AvlTree::new();
+//! for x in [5, 1, 4, 1, 3].iter().copied() {
+//!     // 等しい要素の後ろに挿入します。
+//!     avl.insert(avl.upper_bound(&x), x);
+//! }
+//! assert_eq!(avl.get(2), Some(&3));
+//! assert_eq!(avl.lower_bound(&4), 3);
+//! assert_eq!(avl.remove(0), Some(1));
+//! assert_eq!(avl.lower_bound(&4), 2);
+//! ```
+//!
+//! キーで引く連想配列、集合として使うなら、これを包んだ [`AvlMap`], [`AvlSet`] が便利です。
+//!
+//!
 //! ## 二分探索してインデックスを返す系
 //!
 //! - [`binary_search`](AvlTree::binary_search)
@@ -29,10 +55,19 @@
 //!
 //! - [`insert`](AvlTree::insert)
 //! - [`remove`](AvlTree::remove)
+//! - [`insert_or_replace_by`](AvlTree::insert_or_replace_by)
+//! - [`get_or_insert_with_by`](AvlTree::get_or_insert_with_by)
 //! - [`append`](AvlTree::append)
 //! - [`split_off`](AvlTree::split_off)
 //!
 //!
+//! ## 構築系
+//!
+//! - [`from_sorted_iter`](AvlTree::from_sorted_iter)
+//! - [`from_sorted_iter_by`](AvlTree::from_sorted_iter_by)
+//! - [`rebuild_balanced`](AvlTree::rebuild_balanced)
+//!
+//!
 //! ## 先頭・末尾系
 //!
 //! - [`front`](AvlTree::front)
@@ -46,15 +81,25 @@
 //! ## 走査系
 //!
 //! - [`iter`](AvlTree::iter)
+//! - [`range`](AvlTree::range)
+//! - [`range_by`](AvlTree::range_by)
 //! - [`into_iter`](AvlTree::into_iter)
+mod map;
+mod set;
+
+pub use map::AvlMap;
+pub use set::AvlSet;
 use std::borrow::Borrow;
 use std::cmp::Ordering;
 use std::fmt::Debug;
 use std::hash::Hash;
 use std::iter::successors;
 use std::iter::FromIterator;
+use std::mem::replace;
 use std::mem::swap;
+use std::ops::Bound;
 use std::ops::Index;
+use std::ops::RangeBounds;
 
 /// AVL 木本体です。
 #[derive(Clone)]
@@ -75,6 +120,91 @@ impl<T> AvlTree<T> {
         Self::default()
     }
 
+    /// ソート済みの列から、回転をせずに完全にバランスした木を構築します。
+    ///
+    /// # Requirements
+    ///
+    /// `iter` がソート済みであること。デバッグビルドでは検査されます。
+    ///
+    ///
+    /// # Complexity
+    ///
+    /// $O(n)$
+    ///
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use avl_tree::AvlTree;
+    /// let avl = AvlTree::from_sorted_iter(vec![1, 1, 2, 3, 5]);
+    /// assert_eq!(avl, [1, 1, 2, 3, 5][..]);
+    /// assert_eq!(avl.lower_bound(&2), 2);
+    /// ```
+    pub fn from_sorted_iter<I: IntoIterator<Item = T>>(iter: I) -> Self
+    where
+        T: Ord,
+    {
+        Self::from_sorted_iter_by(iter, T::cmp)
+    }
+
+    /// 比較関数 `compare` でソート済みの列から、回転をせずに完全にバランスした木を構築します。
+    ///
+    /// # Requirements
+    ///
+    /// `iter` が `compare` に関してソート済みであること。デバッグビルドでは検査されます。
+    ///
+    ///
+    /// # Complexity
+    ///
+    /// $O(n)$
+    ///
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use avl_tree::AvlTree;
+    /// let avl = AvlTree::from_sorted_iter_by(vec![5, 3, 2, 1, 1], |x, y| y.cmp(x));
+    /// assert_eq!(avl, [5, 3, 2, 1, 1][..]);
+    /// ```
+    pub fn from_sorted_iter_by<I: IntoIterator<Item = T>>(
+        iter: I,
+        mut compare: impl FnMut(&T, &T) -> Ordering,
+    ) -> Self {
+        let values = iter.into_iter().collect::<Vec<_>>();
+        debug_assert!(
+            values
+                .windows(2)
+                .all(|w| compare(&w[0], &w[1]) != Ordering::Greater),
+            "the input of `from_sorted_iter` is not sorted"
+        );
+        values.into_iter().collect()
+    }
+
+    /// 木を平坦化して、完全にバランスした木に組み直します。ノードは再利用されます。
+    ///
+    /// # Complexity
+    ///
+    /// $O(n)$
+    ///
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use avl_tree::AvlTree;
+    /// let mut avl = (0..10).collect::<AvlTree<_>>();
+    /// for i in (0..10).step_by(2).rev() {
+    ///     avl.remove(i);
+    /// }
+    /// avl.rebuild_balanced();
+    /// assert_eq!(avl, [1, 3, 5, 7, 9][..]);
+    /// ```
+    pub fn rebuild_balanced(&mut self) {
+        let mut nodes = Vec::with_capacity(self.len());
+        flatten(self.root.take(), &mut nodes);
+        let len = nodes.len();
+        self.root = build(&mut nodes.into_iter(), len);
+    }
+
     /// 空列であれば `true` を返します。
     ///
     /// # Examples
@@ -483,6 +613,72 @@ impl<T> AvlTree<T> {
         self.binary_search_by(|x| x.borrow().cmp(value))
     }
 
+    /// `f` が `Equal` を返す要素があれば `value` で置き換えて古い要素を返し、なければ `value`
+    /// をソート順を保つ位置に挿入して `None` を返します。
+    ///
+    /// # Requirements
+    ///
+    /// [`binary_search_by`](AvlTree::binary_search_by) と同じです。
+    ///
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use avl_tree::AvlTree;
+    /// let mut avl: AvlTree<_> = vec![(1, 'a'), (3, 'c')].into_iter().collect();
+    /// assert_eq!(
+    ///     avl.insert_or_replace_by((2, 'b'), |&(k, _)| k.cmp(&2)),
+    ///     None
+    /// );
+    /// assert_eq!(
+    ///     avl.insert_or_replace_by((3, 'd'), |&(k, _)| k.cmp(&3)),
+    ///     Some((3, 'c'))
+    /// );
+    /// assert_eq!(avl, [(1, 'a'), (2, 'b'), (3, 'd')][..]);
+    /// ```
+    pub fn insert_or_replace_by(&mut self, value: T, f: impl FnMut(&T) -> Ordering) -> Option<T> {
+        match self.binary_search_by(f) {
+            Ok(index) => Some(replace(self.get_mut(index).unwrap(), value)),
+            Err(index) => {
+                self.insert(index, value);
+                None
+            }
+        }
+    }
+
+    /// `f` が `Equal` を返す要素があればその可変参照を、なければ `default()`
+    /// をソート順を保つ位置に挿入してその可変参照を返します。
+    ///
+    /// # Requirements
+    ///
+    /// [`binary_search_by`](AvlTree::binary_search_by) と同じです。
+    ///
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use avl_tree::AvlTree;
+    /// let mut avl = AvlTree::<(char, u32)>::new();
+    /// for c in "abracadabra".chars() {
+    ///     avl.get_or_insert_with_by(|&(k, _)| k.cmp(&c), || (c, 0)).1 += 1;
+    /// }
+    /// assert_eq!(avl, [('a', 5), ('b', 2), ('c', 1), ('d', 1), ('r', 2)][..]);
+    /// ```
+    pub fn get_or_insert_with_by(
+        &mut self,
+        f: impl FnMut(&T) -> Ordering,
+        default: impl FnOnce() -> T,
+    ) -> &mut T {
+        let index = match self.binary_search_by(f) {
+            Ok(index) => index,
+            Err(index) => {
+                self.insert(index, default());
+                index
+            }
+        };
+        self.get_mut(index).unwrap()
+    }
+
     pub fn partition_point(&self, mut is_right: impl FnMut(&T) -> bool) -> usize {
         partition_point(self.root.as_deref(), |node| is_right(&node.value))
     }
@@ -567,6 +763,74 @@ impl<T> AvlTree<T> {
             rstack: successors(self.root.as_deref(), |current| current.right.as_deref()).collect(),
         }
     }
+
+    /// `range` に含まれる要素を前から順に走査するイテレータを返します。
+    ///
+    /// 両端の探索に $O(\log n)$、要素 1 つあたり償却 $O(1)$ かかります。
+    /// 始点が終点よりも後ろにあるときは、パニックせずに空のイテレータを返します。
+    ///
+    /// # Requirements
+    ///
+    /// 要素が全てソート済みであること。
+    ///
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use avl_tree::AvlTree;
+    /// let a: AvlTree<_> = [10, 11, 13, 13, 15].iter().copied().collect();
+    /// assert!(a.range(11..15).eq(&[11, 13, 13]));
+    /// assert!(a.range(12..=15).rev().eq(&[15, 13, 13]));
+    /// assert!(a.range(..).eq(&a));
+    /// assert_eq!(a.range(14..15).next(), None);
+    /// ```
+    pub fn range<Q: Ord>(&self, range: impl RangeBounds<Q>) -> Iter<'_, T>
+    where
+        T: Borrow<Q>,
+    {
+        self.range_by(|x| compare_with_range(x.borrow(), &range))
+    }
+
+    /// 比較関数 `f` が [`Equal`](Ordering::Equal) を返す要素を前から順に走査するイテレータを返します。
+    ///
+    /// `f` は範囲より前の要素に [`Less`](Ordering::Less) を、後ろの要素に [`Greater`](Ordering::Greater)
+    /// を返すものとします。（[`binary_search_by`](AvlTree::binary_search_by) と同じ規約です。）
+    ///
+    /// # Requirements
+    ///
+    /// `f` の値が `Less`, `Equal`, `Greater` の順に並んでいること。
+    ///
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use avl_tree::AvlTree;
+    /// let a: AvlTree<_> = [(1, 'a'), (2, 'b'), (2, 'c'), (3, 'd')]
+    ///     .iter()
+    ///     .copied()
+    ///     .collect();
+    /// let b: Vec<_> = a.range_by(|&(k, _)| k.cmp(&2)).map(|&(_, c)| c).collect();
+    /// assert_eq!(b, vec!['b', 'c']);
+    /// ```
+    pub fn range_by(&self, mut f: impl FnMut(&T) -> Ordering) -> Iter<'_, T> {
+        let start = partition_point(self.root.as_deref(), |node| {
+            f(&node.value) != Ordering::Less
+        });
+        let end = partition_point(self.root.as_deref(), |node| {
+            f(&node.value) == Ordering::Greater
+        });
+        if end <= start {
+            return Iter {
+                stack: Vec::new(),
+                rstack: Vec::new(),
+            };
+        }
+        let root = self.root.as_deref().unwrap();
+        Iter {
+            stack: forward_stack(root, start),
+            rstack: backward_stack(root, end - 1),
+        }
+    }
 }
 
 impl<T> Default for AvlTree<T> {
@@ -641,26 +905,10 @@ impl<T> Index<usize> for AvlTree<T> {
 }
 impl<T> FromIterator<T> for AvlTree<T> {
     fn from_iter<I: IntoIterator<Item = T>>(iter: I) -> Self {
-        fn from_slice_of_nodes<T>(nodes: &mut [Option<Box<Node<T>>>]) -> Option<Box<Node<T>>> {
-            if nodes.is_empty() {
-                None
-            } else {
-                let i = nodes.len() / 2;
-                Some(merge_with_root(
-                    from_slice_of_nodes(&mut nodes[..i]),
-                    nodes[i].take().unwrap(),
-                    from_slice_of_nodes(&mut nodes[i + 1..]),
-                ))
-            }
-        }
+        let values = iter.into_iter().collect::<Vec<_>>();
+        let len = values.len();
         Self {
-            root: from_slice_of_nodes(
-                iter.into_iter()
-                    .map(new)
-                    .map(Some)
-                    .collect::<Vec<_>>()
-                    .as_mut_slice(),
-            ),
+            root: build(&mut values.into_iter().map(new), len),
         }
     }
 }
@@ -750,8 +998,14 @@ fn len<T>(tree: Option<&Node<T>>) -> usize {
 fn ht<T>(tree: Option<&Node<T>>) -> u8 {
     tree.as_ref().map_or(0, |node| node.ht)
 }
+#[cfg(test)]
+thread_local! {
+    static ROTATIONS: std::cell::Cell<usize> = std::cell::Cell::new(0);
+}
 fn balance<T>(node: &mut Box<Node<T>>) {
     fn rotate_left<T>(node: &mut Box<Node<T>>) {
+        #[cfg(test)]
+        ROTATIONS.with(|c| c.set(c.get() + 1));
         let mut x = node.left.take().unwrap();
         let y = x.right.take();
         swap(node, &mut x);
@@ -761,6 +1015,8 @@ fn balance<T>(node: &mut Box<Node<T>>) {
         node.update();
     }
     fn rotate_right<T>(node: &mut Box<Node<T>>) {
+        #[cfg(test)]
+        ROTATIONS.with(|c| c.set(c.get() + 1));
         let mut x = node.right.take().unwrap();
         let y = x.left.take();
         swap(node, &mut x);
@@ -785,11 +1041,31 @@ fn balance<T>(node: &mut Box<Node<T>>) {
         node.update();
     }
 }
+fn build<T>(nodes: &mut impl Iterator<Item = Box<Node<T>>>, len: usize) -> Option<Box<Node<T>>> {
+    (len != 0).then(|| {
+        let left = build(nodes, len / 2);
+        let mut root = nodes.next().unwrap();
+        root.left = left;
+        root.right = build(nodes, len - len / 2 - 1);
+        root.update();
+        root
+    })
+}
+fn flatten<T>(tree: Option<Box<Node<T>>>, nodes: &mut Vec<Box<Node<T>>>) {
+    if let Some(mut root) = tree {
+        flatten(root.left.take(), nodes);
+        let right = root.right.take();
+        nodes.push(root);
+        flatten(right, nodes);
+    }
+}
 fn merge_with_root<T>(
     mut left: Option<Box<Node<T>>>,
     mut center: Box<Node<T>>,
     mut right: Option<Box<Node<T>>>,
 ) -> Box<Node<T>> {
+    #[cfg(any(test, feature = "count-ops"))]
+    op_counter::tick();
     match ht(left.as_deref()).cmp(&ht(right.as_deref())) {
         Ordering::Less => {
             let mut root = right.take().unwrap();
@@ -825,6 +1101,8 @@ fn split_delete<T>(
     mut root: Box<Node<T>>,
     index: usize,
 ) -> (Option<Box<Node<T>>>, Box<Node<T>>, Option<Box<Node<T>>>) {
+    #[cfg(any(test, feature = "count-ops"))]
+    op_counter::tick();
     debug_assert!((0..root.len).contains(&index));
     let left = root.left.take();
     let right = root.right.take();
@@ -906,9 +1184,76 @@ fn get_mut<T>(node: &mut Node<T>, index: usize) -> &mut Node<T> {
     }
 }
 
+/// `x` が `range` より前なら `Less` を、含まれるなら `Equal` を、後ろなら `Greater` を返します。
+fn compare_with_range<Q: Ord + ?Sized>(x: &Q, range: &impl RangeBounds<Q>) -> Ordering {
+    let is_left = match range.start_bound() {
+        Bound::Included(start) => x < start,
+        Bound::Excluded(start) => x <= start,
+        Bound::Unbounded => false,
+    };
+    let is_right = match range.end_bound() {
+        Bound::Included(end) => end < x,
+        Bound::Excluded(end) => end <= x,
+        Bound::Unbounded => false,
+    };
+    if is_left {
+        Ordering::Less
+    } else if is_right {
+        Ordering::Greater
+    } else {
+        Ordering::Equal
+    }
+}
+
+/// `index` 番目の要素から前向きに走査するときの `Iter::stack` を作ります。
+fn forward_stack<T>(mut node: &Node<T>, mut index: usize) -> Vec<&Node<T>> {
+    let mut stack = Vec::new();
+    loop {
+        let lsize = len(node.left.as_deref());
+        match lsize.cmp(&index) {
+            Ordering::Less => {
+                index -= lsize + 1;
+                node = node.right.as_deref().unwrap();
+            }
+            Ordering::Equal => {
+                stack.push(node);
+                return stack;
+            }
+            Ordering::Greater => {
+                stack.push(node);
+                node = node.left.as_deref().unwrap();
+            }
+        }
+    }
+}
+/// `index` 番目の要素から後ろ向きに走査するときの `Iter::rstack` を作ります。
+fn backward_stack<T>(mut node: &Node<T>, mut index: usize) -> Vec<&Node<T>> {
+    let mut rstack = Vec::new();
+    loop {
+        let lsize = len(node.left.as_deref());
+        match lsize.cmp(&index) {
+            Ordering::Less => {
+                index -= lsize + 1;
+                rstack.push(node);
+                node = node.right.as_deref().unwrap();
+            }
+            Ordering::Equal => {
+                rstack.push(node);
+                return rstack;
+            }
+            Ordering::Greater => node = node.left.as_deref().unwrap(),
+        }
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
+    use rand::rngs::StdRng;
+    use rand::Rng;
+    use rand::SeedableRng;
+    use std::collections::BTreeMap;
+    use std::collections::BTreeSet;
 
     #[test]
     fn test_from_iter() {
@@ -1034,6 +1379,63 @@ mod tests {
         }
     }
 
+    #[test]
+    fn test_order_statistics() {
+        let mut rng = StdRng::seed_from_u64(42);
+        for _ in 0..100 {
+            let mut avl = AvlTree::new();
+            let mut vec = Vec::new();
+            for id in 0..200 {
+                match rng.gen_range(0..4) {
+                    // 比較関数が Equal を返さないので、等しいキーは挿入順に並びます。
+                    0 | 1 => {
+                        let key = rng.gen_range(0..10_u32);
+                        let i = avl.partition_point(|&(k, _)| key < k);
+                        avl.insert(i, (key, id));
+                        let i = vec.partition_point(|&(k, _)| k <= key);
+                        vec.insert(i, (key, id));
+                    }
+                    2 => {
+                        let n = rng.gen_range(0..=vec.len());
+                        let expected = (n < vec.len()).then(|| vec.remove(n));
+                        assert_eq!(avl.remove(n), expected);
+                    }
+                    _ => {
+                        let n = rng.gen_range(0..=vec.len());
+                        assert_eq!(avl.get(n), vec.get(n));
+                        let key = rng.gen_range(0..=10_u32);
+                        let rank = vec.iter().filter(|&&(k, _)| k < key).count();
+                        assert_eq!(avl.partition_point(|&(k, _)| key <= k), rank);
+                    }
+                }
+                assert_eq!(avl.len(), vec.len());
+            }
+            assert!(avl.iter().eq(&vec));
+        }
+    }
+
+    #[test]
+    fn test_insert_or_replace_by() {
+        let mut rng = StdRng::seed_from_u64(42);
+        for _ in 0..100 {
+            let mut avl = AvlTree::new();
+            let mut map = BTreeMap::new();
+            for id in 0..100 {
+                let key = rng.gen_range(0..20_u32);
+                if rng.gen() {
+                    let result = avl.insert_or_replace_by((key, id), |&(k, _)| k.cmp(&key));
+                    assert_eq!(result, map.insert(key, id).map(|v| (key, v)));
+                } else {
+                    let value = avl.get_or_insert_with_by(|&(k, _)| k.cmp(&key), || (key, id));
+                    value.1 += 100;
+                    *map.entry(key).or_insert(id) += 100;
+                }
+                assert!(avl.iter().copied().eq(map.iter().map(|(&k, &v)| (k, v))));
+            }
+            validate(avl.root.as_deref());
+        }
+    }
+
     #[test]
     fn test_get_mut() {
         for n in 0..=10 {
@@ -1181,4 +1583,231 @@ mod tests {
             }
         }
     }
+
+    #[test]
+    fn test_range() {
+        let mut rng = StdRng::seed_from_u64(42);
+        for _ in 0..200 {
+            let n: usize = rng.gen_range(0..40);
+            let set = (0..n)
+                .map(|_| rng.gen_range(0..60_i32))
+                .collect::<BTreeSet<_>>();
+            let avl = set.iter().copied().collect::<AvlTree<_>>();
+            for _ in 0..20 {
+                let mut l = rng.gen_range(-5..65_i32);
+                let mut r = rng.gen_range(-5..65_i32);
+                if l > r {
+                    swap(&mut l, &mut r);
+                }
+                assert!(avl.range(l..r).eq(set.range(l..r)));
+                assert!(avl.range(l..=r).eq(set.range(l..=r)));
+                assert!(avl.range(l..).eq(set.range(l..)));
+                assert!(avl.range(..r).eq(set.range(..r)));
+                assert!(avl.range(..=r).rev().eq(set.range(..=r).rev()));
+                assert!(avl.range(l..r).rev().eq(set.range(l..r).rev()));
+                let bounds = (Bound::Excluded(l), Bound::Included(r));
+                assert!(avl.range(bounds).eq(set.range(bounds)));
+                assert_eq!(
+                    avl.range(l..r).count(),
+                    avl.lower_bound(&r) - avl.lower_bound(&l)
+                );
+            }
+            assert!(avl.range(..).eq(&set));
+            assert!(avl.range(-10..100).rev().eq(set.iter().rev()));
+            assert_eq!(avl.range(100..200).next(), None);
+            assert_eq!(avl.range(30..30).next(), None);
+            // 始点が終点より後ろでもパニックしません。
+            let bounds = (Bound::Included(40), Bound::Excluded(20));
+            assert_eq!(avl.range(bounds).next(), None);
+        }
+    }
+
+    #[test]
+    fn test_range_duplicates() {
+        let mut rng = StdRng::seed_from_u64(42);
+        for _ in 0..200 {
+            let n: usize = rng.gen_range(0..40);
+            let mut vec = (0..n).map(|_| rng.gen_range(0..10_i32)).collect::<Vec<_>>();
+            vec.sort_unstable();
+            let avl = vec.iter().copied().collect::<AvlTree<_>>();
+            let l = rng.gen_range(-1..11_i32);
+            let r = rng.gen_range(l..12_i32);
+            let expected = vec.iter().filter(|&&x| l <= x && x < r).collect::<Vec<_>>();
+            assert!(avl.range(l..r).eq(expected.iter().copied()));
+            assert!(avl.range(l..r).rev().eq(expected.iter().rev().copied()));
+            let expected = vec.iter().filter(|&&x| x == l).collect::<Vec<_>>();
+            assert!(avl.range_by(|x| x.cmp(&l)).eq(expected));
+        }
+    }
+
+    #[test]
+    fn test_range_next_next_back() {
+        let avl = (0..8).collect::<AvlTree<_>>();
+        for l in 0..=8 {
+            for r in l..=8 {
+                let k = r - l;
+                for bs in 0..1 << (k + 1) {
+                    let mut result = avl.range(l..r);
+                    let mut expected = (l..r).collect::<Vec<_>>().into_iter();
+                    for i in 0..=k {
+                        if bs >> i & 1 == 0 {
+                            assert_eq!(result.next().copied(), expected.next());
+                        } else {
+                            assert_eq!(result.next_back().copied(), expected.next_back());
+                        }
+                    }
+                }
+            }
+        }
+    }
+
+    fn validate<T>(tree: Option<&Node<T>>) -> (usize, u8) {
+        tree.map_or((0, 0), |node| {
+            let (llen, lht) = validate(node.left.as_deref());
+            let (rlen, rht) = validate(node.right.as_deref());
+            assert!(lht.max(rht) - lht.min(rht) <= 1);
+            assert_eq!(node.len, llen + 1 + rlen);
+            assert_eq!(node.ht, 1 + lht.max(rht));
+            (node.len, node.ht)
+        })
+    }
+
+    fn optimal_height(n: usize) -> u8 {
+        (usize::BITS - n.leading_zeros()) as u8
+    }
+
+    #[test]
+    fn test_from_sorted_iter() {
+        for n in 0..=100 {
+            let before = ROTATIONS.with(|c| c.get());
+            let result = AvlTree::from_sorted_iter(0..n);
+            assert_eq!(ROTATIONS.with(|c| c.get()), before);
+            assert_eq!(validate(result.root.as_deref()), (n, optimal_height(n)));
+            assert!(result.iter().copied().eq(0..n));
+        }
+        let before = ROTATIONS.with(|c| c.get());
+        let _ = (0..100).fold(AvlTree::new(), |mut avl, i| {
+            avl.insert(i, i);
+            avl
+        });
+        assert!(ROTATIONS.with(|c| c.get()) > before);
+    }
+
+    #[test]
+    fn test_from_sorted_iter_by() {
+        for n in 0..=10 {
+            let result = AvlTree::from_sorted_iter_by((0..n).rev(), |x, y| y.cmp(x));
+            assert_eq!(validate(result.root.as_deref()), (n, optimal_height(n)));
+            assert!(result.iter().copied().eq((0..n).rev()));
+        }
+    }
+
+    #[test]
+    #[cfg(debug_assertions)]
+    #[should_panic(expected = "the input of `from_sorted_iter` is not sorted")]
+    fn test_from_sorted_iter_unsorted() {
+        AvlTree::from_sorted_iter(vec![0, 2, 1]);
+    }
+
+    #[test]
+    fn test_rebuild_balanced() {
+        for n in 0..=20 {
+            for k in 0..=n {
+                let mut result = (0..n).collect::<AvlTree<_>>();
+                let mut expected = (0..n).collect::<Vec<_>>();
+                for _ in 0..k {
+                    result.remove(result.len() / 3);
+                    expected.remove(expected.len() / 3);
+                }
+                let before = ROTATIONS.with(|c| c.get());
+                result.rebuild_balanced();
+                assert_eq!(ROTATIONS.with(|c| c.get()), before);
+                let (len, ht) = validate(result.root.as_deref());
+                assert_eq!((len, ht), (n - k, optimal_height(n - k)));
+                assert_eq!(result.iter().copied().collect::<Vec<_>>(), expected);
+            }
+        }
+    }
+
+    #[test]
+    fn test_from_sorted_iter_then_mixed_operations() {
+        for n in 0..=10 {
+            let mut bulk = AvlTree::from_sorted_iter(0..n);
+            let mut inserted = AvlTree::new();
+            for i in 0..n {
+                inserted.insert(i, i);
+            }
+            let mut expected = (0..n).collect::<Vec<_>>();
+            for i in 0..3 * n {
+                let value = n + i;
+                if i % 3 == 2 {
+                    let index = i * 7 % expected.len();
+                    let removed = expected.remove(index);
+                    assert_eq!(bulk.remove(index), Some(removed));
+                    assert_eq!(inserted.remove(index), Some(removed));
+                } else {
+                    let index = bulk.lower_bound(&value);
+                    bulk.insert(index, value);
+                    inserted.insert(index, value);
+                    expected.insert(index, value);
+                }
+                validate(bulk.root.as_deref());
+                assert_eq!(bulk, inserted);
+                assert_eq!(bulk, expected[..]);
+            }
+        }
+    }
+
+    // 長さ n になるまでランダムな位置に挿入したときの演算回数を数えます。
+    fn count_insert(n: usize, mut insert: impl FnMut(usize, usize)) -> u64 {
+        let mut x = 42_usize;
+        let counter = op_counter::OpCounter::start();
+        for i in 0..n {
+            x ^= x << 13;
+            x ^= x >> 7;
+            x ^= x << 17;
+            insert(x % (i + 1), i);
+        }
+        counter.get()
+    }
+
+    #[test]
+    fn test_insert_complexity() {
+        let ns = [1 << 10, 1 << 11, 1 << 12, 1 << 13];
+        let counts = ns
+            .iter()
+            .map(|&n| {
+                let mut avl = AvlTree::new();
+                count_insert(n, |index, value| avl.insert(index, value))
+            })
+            .collect::<Vec<_>>();
+        // O(n log n)
+        op_counter::assert_complexity(&ns, &counts, 1.3);
+    }
+
+    #[test]
+    #[should_panic(expected = "exceeds the bound")]
+    fn test_insert_complexity_canary() {
+        // 後ろをずらす配列への挿入
+        let ns = [1 << 10, 1 << 11, 1 << 12, 1 << 13];
+        let counts = ns
+            .iter()
+            .map(|&n| {
+                let mut vec = Vec::new();
+                count_insert(n, |index, value| {
+                    op_counter::add((vec.len() - index) as u64 + 1);
+                    vec.insert(index, value);
+                })
+            })
+            .collect::<Vec<_>>();
+        op_counter::assert_complexity(&ns, &counts, 1.3);
+    }
+
+    #[test]
+    fn test_no_overhead() {
+        assert_eq!(
+            std::mem::size_of::<AvlTree<u64>>(),
+            std::mem::size_of::<Option<Box<Node<u64>>>>()
+        );
+    }
 }
@@ -0,0 +1,299 @@
+use crate::compare_with_range;
+use crate::AvlTree;
+use crate::Iter;
+use std::borrow::Borrow;
+use std::fmt::Debug;
+use std::iter::FromIterator;
+use std::iter::Map;
+use std::mem::replace;
+use std::ops::RangeBounds;
+
+/// [`AvlTree`] をキーでソートされたペアの列として使う順序付き連想配列です。
+///
+/// [`BTreeMap`](std::collections::BTreeMap) とほぼ同じ使い方ができるうえに、
+/// [`nth`](AvlMap::nth) と [`rank`](AvlMap::rank) が $O(\log n)$ でできます。
+///
+/// # Examples
+///
+/// ```
+/// use avl_tree::AvlMap;
+/// let mut map = AvlMap::new();
+/// map.insert(3, "c");
+/// map.insert(1, "a");
+/// assert_eq!(map.insert(3, "C"), Some("c"));
+/// map.insert(2, "b");
+///
+/// assert_eq!(map.get(&3), Some(&"C"));
+/// assert_eq!(map.nth(1), Some((&2, &"b")));
+/// assert_eq!(map.rank(&3), 2);
+/// assert_eq!(map.remove(&1), Some("a"));
+/// assert_eq!(format!("{:?}", map), r#"{2: "b", 3: "C"}"#);
+/// ```
+#[derive(Clone)]
+pub struct AvlMap<K, V> {
+    tree: AvlTree<(K, V)>,
+}
+impl<K: Ord, V> AvlMap<K, V> {
+    /// 空の連想配列を構築します。
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// 要素数を返します。
+    pub fn len(&self) -> usize {
+        self.tree.len()
+    }
+
+    /// 空ならば `true` を返します。
+    pub fn is_empty(&self) -> bool {
+        self.tree.is_empty()
+    }
+
+    /// キー `key` に値 `value` を対応させます。すでにあれば値を置き換えて古い値を返します。
+    pub fn insert(&mut self, key: K, value: V) -> Option<V> {
+        match self.tree.binary_search_by(|(k, _)| k.cmp(&key)) {
+            Ok(index) => Some(replace(&mut self.tree.get_mut(index).unwrap().1, value)),
+            Err(index) => {
+                self.tree.insert(index, (key, value));
+                None
+            }
+        }
+    }
+
+    /// キー `key` があれば削除して値を返します。
+    pub fn remove<Q: Ord + ?Sized>(&mut self, key: &Q) -> Option<V>
+    where
+        K: Borrow<Q>,
+    {
+        let index = self.search(key).ok()?;
+        self.tree.remove(index).map(|(_, v)| v)
+    }
+
+    /// キー `key` に対応する値を返します。
+    pub fn get<Q: Ord + ?Sized>(&self, key: &Q) -> Option<&V>
+    where
+        K: Borrow<Q>,
+    {
+        let index = self.search(key).ok()?;
+        Some(&self.tree[index].1)
+    }
+
+    /// キー `key` に対応する値の可変参照を返します。
+    pub fn get_mut<Q: Ord + ?Sized>(&mut self, key: &Q) -> Option<&mut V>
+    where
+        K: Borrow<Q>,
+    {
+        let index = self.search(key).ok()?;
+        self.tree.get_mut(index).map(|(_, v)| v)
+    }
+
+    /// キー `key` があれば `true` を返します。
+    pub fn contains_key<Q: Ord + ?Sized>(&self, key: &Q) -> bool
+    where
+        K: Borrow<Q>,
+    {
+        self.search(key).is_ok()
+    }
+
+    /// 最小のキーとその値を返します。
+    pub fn first_key_value(&self) -> Option<(&K, &V)> {
+        self.tree.front().map(|(k, v)| (k, v))
+    }
+
+    /// 最大のキーとその値を返します。
+    pub fn last_key_value(&self) -> Option<(&K, &V)> {
+        self.tree.back().map(|(k, v)| (k, v))
+    }
+
+    /// 小さい方から `n` 番目 (0-indexed) のキーとその値を返します。
+    ///
+    /// # Complexity
+    ///
+    /// $O(\log n)$
+    pub fn nth(&self, n: usize) -> Option<(&K, &V)> {
+        self.tree.get(n).map(|(k, v)| (k, v))
+    }
+
+    /// `key` 未満のキーの個数を返します。
+    ///
+    /// # Complexity
+    ///
+    /// $O(\log n)$
+    pub fn rank<Q: Ord + ?Sized>(&self, key: &Q) -> usize
+    where
+        K: Borrow<Q>,
+    {
+        self.tree.partition_point(|(k, _)| key <= k.borrow())
+    }
+
+    /// キーの昇順に走査するイテレータを返します。
+    pub fn iter(&self) -> impl DoubleEndedIterator<Item = (&K, &V)> {
+        self.tree.iter().map(|(k, v)| (k, v))
+    }
+
+    /// キーを昇順に走査するイテレータを返します。
+    pub fn keys(&self) -> impl DoubleEndedIterator<Item = &K> {
+        self.tree.iter().map(|(k, _)| k)
+    }
+
+    /// 値をキーの昇順に走査するイテレータを返します。
+    pub fn values(&self) -> impl DoubleEndedIterator<Item = &V> {
+        self.tree.iter().map(|(_, v)| v)
+    }
+
+    /// キーが `range` に含まれる要素を昇順に走査するイテレータを返します。
+    ///
+    /// # Complexity
+    ///
+    /// $O(\log n + k)$
+    pub fn range<Q: Ord + ?Sized>(
+        &self,
+        range: impl RangeBounds<Q>,
+    ) -> impl DoubleEndedIterator<Item = (&K, &V)>
+    where
+        K: Borrow<Q>,
+    {
+        self.tree
+            .range_by(|(k, _)| compare_with_range(k.borrow(), &range))
+            .map(|(k, v)| (k, v))
+    }
+
+    fn search<Q: Ord + ?Sized>(&self, key: &Q) -> Result<usize, usize>
+    where
+        K: Borrow<Q>,
+    {
+        self.tree.binary_search_by(|(k, _)| k.borrow().cmp(key))
+    }
+}
+
+impl<K, V> Default for AvlMap<K, V> {
+    fn default() -> Self {
+        Self {
+            tree: AvlTree::new(),
+        }
+    }
+}
+impl<K: Debug, V: Debug> Debug for AvlMap<K, V> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_map()
+            .entries(self.tree.iter().map(|(k, v)| (k, v)))
+            .finish()
+    }
+}
+impl<K: PartialEq, V: PartialEq> PartialEq for AvlMap<K, V> {
+    fn eq(&self, other: &Self) -> bool {
+        self.tree == other.tree
+    }
+}
+impl<K: Eq, V: Eq> Eq for AvlMap<K, V> {}
+impl<K: Ord, V> FromIterator<(K, V)> for AvlMap<K, V> {
+    fn from_iter<I: IntoIterator<Item = (K, V)>>(iter: I) -> Self {
+        let mut map = Self::new();
+        map.extend(iter);
+        map
+    }
+}
+impl<K: Ord, V> Extend<(K, V)> for AvlMap<K, V> {
+    fn extend<I: IntoIterator<Item = (K, V)>>(&mut self, iter: I) {
+        for (k, v) in iter {
+            self.insert(k, v);
+        }
+    }
+}
+impl<K, V> IntoIterator for AvlMap<K, V> {
+    type IntoIter = crate::IntoIter<(K, V)>;
+    type Item = (K, V);
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.tree.into_iter()
+    }
+}
+impl<'a, K, V> IntoIterator for &'a AvlMap<K, V> {
+    type IntoIter = Map<Iter<'a, (K, V)>, fn(&'a (K, V)) -> (&'a K, &'a V)>;
+    type Item = (&'a K, &'a V);
+
+    fn into_iter(self) -> Self::IntoIter {
+        let f: fn(&'a (K, V)) -> (&'a K, &'a V) = |(k, v)| (k, v);
+        self.tree.iter().map(f)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rand::rngs::StdRng;
+    use rand::Rng;
+    use rand::SeedableRng;
+    use std::collections::BTreeMap;
+
+    #[test]
+    fn test_map() {
+        let mut rng = StdRng::seed_from_u64(42);
+        for _ in 0..100 {
+            let mut map = AvlMap::new();
+            let mut expected = BTreeMap::new();
+            for _ in 0..200 {
+                let key = rng.gen_range(0..30_u32);
+                match rng.gen_range(0..6) {
+                    0 | 1 => {
+                        let value = rng.gen_range(0..100_u32);
+                        assert_eq!(map.insert(key, value), expected.insert(key, value));
+                    }
+                    2 => assert_eq!(map.remove(&key), expected.remove(&key)),
+                    3 => {
+                        if let Some(value) = map.get_mut(&key) {
+                            *value += 1;
+                        }
+                        if let Some(value) = expected.get_mut(&key) {
+                            *value += 1;
+                        }
+                    }
+                    4 => {
+                        // 順序統計量はソート済みの Vec と比べます。
+                        let sorted = expected.iter().collect::<Vec<_>>();
+                        let n = rng.gen_range(0..=sorted.len());
+                        assert_eq!(map.nth(n), sorted.get(n).copied());
+                        let rank = sorted.partition_point(|&(&k, _)| k < key);
+                        assert_eq!(map.rank(&key), rank);
+                    }
+                    _ => {
+                        let r = rng.gen_range(key..=30);
+                        assert!(map.range(key..r).eq(expected.range(key..r)));
+                        assert!(map.range(..=key).rev().eq(expected.range(..=key).rev()));
+                    }
+                }
+                assert_eq!(map.len(), expected.len());
+                assert_eq!(map.get(&key), expected.get(&key));
+                assert_eq!(map.contains_key(&key), expected.contains_key(&key));
+                assert_eq!(map.first_key_value(), expected.iter().next());
+                assert_eq!(map.last_key_value(), expected.iter().next_back());
+            }
+            assert!(map.iter().eq(expected.iter()));
+            let mut pairs = Vec::new();
+            for (k, v) in &map {
+                pairs.push((k, v));
+            }
+            assert!(pairs.into_iter().eq(&expected));
+            assert!((&map).into_iter().rev().eq(expected.iter().rev()));
+            assert!(map.keys().eq(expected.keys()));
+            assert!(map.values().eq(expected.values()));
+            assert_eq!(format!("{:?}", map), format!("{:?}", expected));
+            assert_eq!(
+                map.clone().into_iter().collect::<BTreeMap<_, _>>(),
+                expected
+            );
+            let collected = expected.clone().into_iter().collect::<AvlMap<_, _>>();
+            assert_eq!(collected, map);
+        }
+    }
+
+    #[test]
+    fn test_borrow() {
+        let mut map = AvlMap::new();
+        map.insert("b".to_owned(), 2);
+        map.insert("a".to_owned(), 1);
+        assert_eq!(map.get("a"), Some(&1));
+        assert_eq!(map.rank("b"), 1);
+        assert!(map.remove("b").is_some());
+    }
+}
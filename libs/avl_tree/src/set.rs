@@ -0,0 +1,245 @@
+use crate::compare_with_range;
+use crate::AvlTree;
+use crate::Iter;
+use std::borrow::Borrow;
+use std::fmt::Debug;
+use std::iter::FromIterator;
+use std::ops::RangeBounds;
+
+/// [`AvlTree`] をソートされた列として使う順序付き集合です。
+///
+/// [`BTreeSet`](std::collections::BTreeSet) とほぼ同じ使い方ができるうえに、
+/// [`nth`](AvlSet::nth) と [`rank`](AvlSet::rank) が $O(\log n)$ でできます。
+///
+/// # Examples
+///
+/// ```
+/// use avl_tree::AvlSet;
+/// let mut set: AvlSet<_> = [3, 1, 4, 1, 5].iter().copied().collect();
+/// assert_eq!(set.len(), 4);
+/// assert!(!set.insert(4));
+/// assert!(set.contains(&5));
+///
+/// assert_eq!(set.nth(2), Some(&4));
+/// assert_eq!(set.rank(&4), 2);
+/// assert!(set.remove(&1));
+/// assert_eq!(format!("{:?}", set), "{3, 4, 5}");
+/// ```
+#[derive(Clone)]
+pub struct AvlSet<K> {
+    tree: AvlTree<K>,
+}
+impl<K: Ord> AvlSet<K> {
+    /// 空の集合を構築します。
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// 要素数を返します。
+    pub fn len(&self) -> usize {
+        self.tree.len()
+    }
+
+    /// 空ならば `true` を返します。
+    pub fn is_empty(&self) -> bool {
+        self.tree.is_empty()
+    }
+
+    /// `key` を挿入します。すでにあれば何もせずに `false` を返します。
+    pub fn insert(&mut self, key: K) -> bool {
+        match self.tree.binary_search(&key) {
+            Ok(_) => false,
+            Err(index) => {
+                self.tree.insert(index, key);
+                true
+            }
+        }
+    }
+
+    /// `key` があれば削除して `true` を返します。
+    pub fn remove<Q: Ord + ?Sized>(&mut self, key: &Q) -> bool
+    where
+        K: Borrow<Q>,
+    {
+        match self.search(key) {
+            Ok(index) => {
+                self.tree.remove(index);
+                true
+            }
+            Err(_) => false,
+        }
+    }
+
+    /// `key` があれば `true` を返します。
+    pub fn contains<Q: Ord + ?Sized>(&self, key: &Q) -> bool
+    where
+        K: Borrow<Q>,
+    {
+        self.search(key).is_ok()
+    }
+
+    /// `key` と等しい要素を返します。
+    pub fn get<Q: Ord + ?Sized>(&self, key: &Q) -> Option<&K>
+    where
+        K: Borrow<Q>,
+    {
+        let index = self.search(key).ok()?;
+        self.tree.get(index)
+    }
+
+    /// 最小の要素を返します。
+    pub fn first(&self) -> Option<&K> {
+        self.tree.front()
+    }
+
+    /// 最大の要素を返します。
+    pub fn last(&self) -> Option<&K> {
+        self.tree.back()
+    }
+
+    /// 小さい方から `n` 番目 (0-indexed) の要素を返します。
+    ///
+    /// # Complexity
+    ///
+    /// $O(\log n)$
+    pub fn nth(&self, n: usize) -> Option<&K> {
+        self.tree.get(n)
+    }
+
+    /// `key` 未満の要素の個数を返します。
+    ///
+    /// # Complexity
+    ///
+    /// $O(\log n)$
+    pub fn rank<Q: Ord + ?Sized>(&self, key: &Q) -> usize
+    where
+        K: Borrow<Q>,
+    {
+        self.tree.partition_point(|k| key <= k.borrow())
+    }
+
+    /// 昇順に走査するイテレータを返します。
+    pub fn iter(&self) -> Iter<'_, K> {
+        self.tree.iter()
+    }
+
+    /// `range` に含まれる要素を昇順に走査するイテレータを返します。
+    ///
+    /// # Complexity
+    ///
+    /// $O(\log n + k)$
+    pub fn range<Q: Ord + ?Sized>(&self, range: impl RangeBounds<Q>) -> Iter<'_, K>
+    where
+        K: Borrow<Q>,
+    {
+        self.tree
+            .range_by(|k| compare_with_range(k.borrow(), &range))
+    }
+
+    fn search<Q: Ord + ?Sized>(&self, key: &Q) -> Result<usize, usize>
+    where
+        K: Borrow<Q>,
+    {
+        self.tree.binary_search_by(|k| k.borrow().cmp(key))
+    }
+}
+
+impl<K> Default for AvlSet<K> {
+    fn default() -> Self {
+        Self {
+            tree: AvlTree::new(),
+        }
+    }
+}
+impl<K: Debug> Debug for AvlSet<K> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_set().entries(&self.tree).finish()
+    }
+}
+impl<K: PartialEq> PartialEq for AvlSet<K> {
+    fn eq(&self, other: &Self) -> bool {
+        self.tree == other.tree
+    }
+}
+impl<K: Eq> Eq for AvlSet<K> {}
+impl<K: Ord> FromIterator<K> for AvlSet<K> {
+    fn from_iter<I: IntoIterator<Item = K>>(iter: I) -> Self {
+        let mut set = Self::new();
+        set.extend(iter);
+        set
+    }
+}
+impl<K: Ord> Extend<K> for AvlSet<K> {
+    fn extend<I: IntoIterator<Item = K>>(&mut self, iter: I) {
+        for k in iter {
+            self.insert(k);
+        }
+    }
+}
+impl<K> IntoIterator for AvlSet<K> {
+    type IntoIter = crate::IntoIter<K>;
+    type Item = K;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.tree.into_iter()
+    }
+}
+impl<'a, K> IntoIterator for &'a AvlSet<K> {
+    type IntoIter = Iter<'a, K>;
+    type Item = &'a K;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.tree.iter()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rand::rngs::StdRng;
+    use rand::Rng;
+    use rand::SeedableRng;
+    use std::collections::BTreeSet;
+
+    #[test]
+    fn test_set() {
+        let mut rng = StdRng::seed_from_u64(42);
+        for _ in 0..100 {
+            let mut set = AvlSet::new();
+            let mut expected = BTreeSet::new();
+            for _ in 0..200 {
+                let key = rng.gen_range(0..30_u32);
+                match rng.gen_range(0..4) {
+                    0 | 1 => assert_eq!(set.insert(key), expected.insert(key)),
+                    2 => assert_eq!(set.remove(&key), expected.remove(&key)),
+                    _ => {
+                        let sorted = expected.iter().collect::<Vec<_>>();
+                        let n = rng.gen_range(0..=sorted.len());
+                        assert_eq!(set.nth(n), sorted.get(n).copied());
+                        let rank = sorted.partition_point(|&&k| k < key);
+                        assert_eq!(set.rank(&key), rank);
+                        let r = rng.gen_range(key..=30);
+                        assert!(set.range(key..r).eq(expected.range(key..r)));
+                    }
+                }
+                assert_eq!(set.len(), expected.len());
+                assert_eq!(set.contains(&key), expected.contains(&key));
+                assert_eq!(set.get(&key), expected.get(&key));
+                assert_eq!(set.first(), expected.iter().next());
+                assert_eq!(set.last(), expected.iter().next_back());
+            }
+            assert!(set.iter().eq(&expected));
+            assert_eq!(format!("{:?}", set), format!("{:?}", expected));
+            let mut extended = AvlSet::new();
+            extended.extend(expected.iter().rev().copied());
+            assert_eq!(extended, set);
+        }
+    }
+
+    #[test]
+    fn test_borrow() {
+        let set: AvlSet<String> = ["x", "y"].iter().map(|s| s.to_string()).collect();
+        assert!(set.contains("y"));
+        assert_eq!(set.rank("xx"), 1);
+    }
+}
@@ -0,0 +1,184 @@
+//! Two-coloring of an undirected graph, or an odd cycle if impossible.
+//!
+//! # Examples
+//!
+//! ```
+//! use bipartition::bipartition;
+//! assert_eq!(bipartition(4, &[(1, 2), (3, 2)]), Ok(vec![0, 0, 1, 0]));
+//! let cycle = bipartition(4, &[(0, 1), (1, 2), (2, 0), (2, 3)]).unwrap_err();
+//! assert_eq!(cycle.len(), 3);
+//! assert_eq!(bipartition(2, &[(1, 1)]), Err(vec![1]));
+//! ```
+
+/// Returns a two-coloring of the graph with `n` vertices and undirected edges `edges`, or the
+/// vertices of an odd cycle.
+///
+/// - The coloring gives different colors, $0$ or $1$, to the endpoints of each edge, and the
+/// smallest vertex of each connected component is colored $0$. So it is unique.
+/// - The cycle $\[v _ 0, \dots, v _ {k - 1}\]$ has odd $k$, and the edges $v _ i v _ {i + 1}$ and
+/// $v _ {k - 1} v _ 0$ exist. A self-loop gives a cycle of length $1$.
+///
+/// # Complexity
+///
+/// $O(n + m)$
+pub fn bipartition(n: usize, edges: &[(usize, usize)]) -> Result<Vec<u8>, Vec<usize>> {
+    let mut g = vec![Vec::new(); n];
+    for &(u, v) in edges {
+        g[u].push(v);
+        if u != v {
+            g[v].push(u);
+        }
+    }
+    let mut color = vec![u8::MAX; n];
+    let mut parent = vec![usize::MAX; n];
+    let mut queue = Vec::with_capacity(n);
+    for s in 0..n {
+        if color[s] != u8::MAX {
+            continue;
+        }
+        color[s] = 0;
+        queue.clear();
+        queue.push(s);
+        let mut head = 0;
+        while let Some(&x) = queue.get(head) {
+            head += 1;
+            for &y in &g[x] {
+                if color[y] == u8::MAX {
+                    color[y] = color[x] ^ 1;
+                    parent[y] = x;
+                    queue.push(y);
+                } else if color[y] == color[x] {
+                    return Err(odd_cycle(&parent, x, y));
+                }
+            }
+        }
+    }
+    Ok(color)
+}
+
+// The endpoints of an edge in a BFS tree with the same color have the same depth, so they reach
+// their lowest common ancestor at the same time.
+fn odd_cycle(parent: &[usize], mut x: usize, mut y: usize) -> Vec<usize> {
+    let mut left = vec![x];
+    let mut right = vec![y];
+    while x != y {
+        x = parent[x];
+        y = parent[y];
+        left.push(x);
+        right.push(y);
+    }
+    right.pop();
+    left.extend(right.into_iter().rev());
+    left
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rand::rngs::StdRng;
+    use rand::Rng;
+    use rand::SeedableRng;
+    use std::collections::HashSet;
+
+    fn verify_cycle(edges: &[(usize, usize)], cycle: &[usize]) {
+        let edges = edges
+            .iter()
+            .flat_map(|&(u, v)| [(u, v), (v, u)])
+            .collect::<HashSet<_>>();
+        assert_eq!(cycle.len() % 2, 1, "{:?}", cycle);
+        for i in 0..cycle.len() {
+            let e = (cycle[i], cycle[(i + 1) % cycle.len()]);
+            assert!(edges.contains(&e), "{:?} is not an edge", e);
+        }
+    }
+
+    fn verify_coloring(n: usize, edges: &[(usize, usize)], color: &[u8]) {
+        assert_eq!(color.len(), n);
+        for &(u, v) in edges {
+            assert_ne!(color[u], color[v]);
+        }
+        // The smallest vertex of each component
+        let mut uf = (0..n).collect::<Vec<_>>();
+        fn find(uf: &mut [usize], x: usize) -> usize {
+            if uf[x] == x {
+                x
+            } else {
+                let r = find(uf, uf[x]);
+                uf[x] = r;
+                r
+            }
+        }
+        for &(u, v) in edges {
+            let (u, v) = (find(&mut uf, u), find(&mut uf, v));
+            uf[u.max(v)] = u.min(v);
+        }
+        for (x, &c) in color.iter().enumerate() {
+            if find(&mut uf, x) == x {
+                assert_eq!(c, 0);
+            }
+        }
+    }
+
+    #[test]
+    fn test_bipartite() {
+        let mut rng = StdRng::seed_from_u64(42);
+        for _ in 0..300 {
+            let n = rng.gen_range(1..=30);
+            let side = (0..n).map(|_| rng.gen_bool(0.5)).collect::<Vec<_>>();
+            let edges = (0..rng.gen_range(0..=2 * n))
+                .map(|_| (rng.gen_range(0..n), rng.gen_range(0..n)))
+                .filter(|&(u, v)| side[u] != side[v])
+                .collect::<Vec<_>>();
+            // Parallel edges
+            let mut edges = edges.iter().flat_map(|&e| [e, e]).collect::<Vec<_>>();
+            edges.truncate(edges.len() - edges.len() / 3);
+            let color = bipartition(n, &edges).unwrap();
+            verify_coloring(n, &edges, &color);
+            // Determinism under relabeling the edges
+            let mut shuffled = edges.iter().map(|&(u, v)| (v, u)).collect::<Vec<_>>();
+            shuffled.reverse();
+            assert_eq!(bipartition(n, &shuffled), Ok(color));
+        }
+    }
+
+    #[test]
+    fn test_not_bipartite() {
+        let mut rng = StdRng::seed_from_u64(42);
+        let mut tested = 0;
+        while tested < 300 {
+            let n = rng.gen_range(1..=30);
+            let edges = (0..rng.gen_range(0..=2 * n))
+                .map(|_| (rng.gen_range(0..n), rng.gen_range(0..n)))
+                .collect::<Vec<_>>();
+            match bipartition(n, &edges) {
+                Ok(color) => verify_coloring(n, &edges, &color),
+                Err(cycle) => {
+                    verify_cycle(&edges, &cycle);
+                    tested += 1;
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn test_long_odd_cycle() {
+        let n = 200_001;
+        let edges = (0..n).map(|i| (i, (i + 1) % n)).collect::<Vec<_>>();
+        let cycle = bipartition(n, &edges).unwrap_err();
+        assert_eq!(cycle.len(), n);
+        verify_cycle(&edges, &cycle);
+    }
+
+    #[test]
+    fn test_small() {
+        assert_eq!(bipartition(0, &[]), Ok(vec![]));
+        assert_eq!(bipartition(3, &[]), Ok(vec![0, 0, 0]));
+        assert_eq!(bipartition(3, &[(2, 2)]), Err(vec![2]));
+        assert_eq!(bipartition(2, &[(0, 1), (1, 0)]), Ok(vec![0, 1]));
+        // Disconnected, and only the second component is odd
+        let edges = [(0, 1), (2, 3), (3, 4), (4, 2)];
+        let cycle = bipartition(5, &edges).unwrap_err();
+        verify_cycle(&edges, &cycle);
+        assert_eq!(cycle.len(), 3);
+    }
+}
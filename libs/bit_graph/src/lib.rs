@@ -0,0 +1,256 @@
+//! Reachability on a dense directed graph with adjacency bitsets.
+//!
+//! Every row of the adjacency matrix is a [`BitVec`], so expanding a frontier costs $O(V / w)$
+//! per vertex instead of its degree, where $w = 64$.
+//!
+//! A vertex always reaches itself, even without a self-loop.
+//!
+//! # Examples
+//!
+//! ```
+//! use bit_graph::BitGraph;
+//! let graph = BitGraph::new(4, &[(0, 1), (1, 2), (2, 1)]);
+//! assert_eq!(graph.bfs_from(1).ones().collect::<Vec<_>>(), vec![1, 2]);
+//! let closure = graph.transitive_closure();
+//! assert_eq!(closure[0].ones().collect::<Vec<_>>(), vec![0, 1, 2]);
+//! assert_eq!(closure[3].ones().collect::<Vec<_>>(), vec![3]);
+//! assert_eq!(graph.count_reachable_pairs(), 4);
+//! ```
+//!
+//! For sparse graphs, [`Reachability`] answers queries without the adjacency matrix.
+
+mod reachability;
+
+pub use bitvec::BitVec;
+pub use reachability::Reachability;
+pub use reachability::ReachabilityMode;
+use scc::Scc;
+
+/// A directed graph stored as adjacency bitsets.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct BitGraph {
+    adj: Vec<BitVec>,
+    edges: Vec<(usize, usize)>,
+}
+impl BitGraph {
+    /// Constructs a graph with `n` vertices and directed edges `edges`.
+    ///
+    /// # Panics
+    ///
+    /// If an endpoint is out of `0..n`.
+    pub fn new(n: usize, edges: &[(usize, usize)]) -> Self {
+        let mut adj = vec![BitVec::new(n); n];
+        for &(u, v) in edges {
+            assert!(
+                u < n && v < n,
+                "edge ({}, {}) is out of the graph with {} vertices",
+                u,
+                v,
+                n
+            );
+            adj[u].set(v);
+        }
+        Self {
+            adj,
+            edges: edges.to_vec(),
+        }
+    }
+
+    /// Returns the number of the vertices.
+    pub fn len(&self) -> usize {
+        self.adj.len()
+    }
+
+    /// Returns `true` if there are no vertices.
+    pub fn is_empty(&self) -> bool {
+        self.adj.is_empty()
+    }
+
+    /// Returns the out-neighbors of `v`.
+    pub fn adj(&self, v: usize) -> &BitVec {
+        &self.adj[v]
+    }
+
+    /// Returns the set of the vertices reachable from `v`.
+    ///
+    /// # Complexity
+    ///
+    /// $O(V ^ 2 / w)$
+    pub fn bfs_from(&self, v: usize) -> BitVec {
+        let mut visited = BitVec::new(self.len());
+        visited.set(v);
+        let mut frontier = visited.clone();
+        while frontier.count_ones() != 0 {
+            let mut next = BitVec::new(self.len());
+            for u in frontier.ones() {
+                next |= &self.adj[u];
+            }
+            // `next` minus `visited`
+            next |= &visited;
+            next ^= &visited;
+            visited |= &next;
+            frontier = next;
+        }
+        visited
+    }
+
+    /// Returns the sets of the vertices reachable from each vertex.
+    ///
+    /// The sets are computed once per strongly connected component, in the reverse topological
+    /// order of the condensation.
+    ///
+    /// # Complexity
+    ///
+    /// $O(V + E + C (V + E_C / w))$, where $C$ and $E_C$ are the numbers of the vertices and the
+    /// edges of the condensation
+    pub fn transitive_closure(&self) -> Vec<BitVec> {
+        let (scc, reach) = self.component_closure();
+        (0..self.len())
+            .map(|v| reach[scc.cmp_of(v)].clone())
+            .collect()
+    }
+
+    /// Returns the number of the pairs $(u, v)$ of distinct vertices such that $v$ is reachable
+    /// from $u$.
+    ///
+    /// # Complexity
+    ///
+    /// The same as [`transitive_closure`](Self::transitive_closure)
+    pub fn count_reachable_pairs(&self) -> u64 {
+        let (scc, reach) = self.component_closure();
+        let members = scc.quotient_set();
+        reach
+            .iter()
+            .zip(&members)
+            .map(|(reach, members)| (members.len() * reach.count_ones()) as u64)
+            .sum::<u64>()
+            - self.len() as u64
+    }
+
+    fn component_closure(&self) -> (Scc, Vec<BitVec>) {
+        let mut scc = Scc::new(self.len());
+        for &(u, v) in &self.edges {
+            scc.add_edge(u, v);
+        }
+        scc.build();
+        let quotient = scc.quotient_graph();
+        let mut reach = vec![BitVec::new(self.len()); scc.cmp_count()];
+        for (v, &c) in scc.cmp_ofs().iter().enumerate() {
+            reach[c].set(v);
+        }
+        // The edges of the condensation go from a smaller index to a larger one.
+        for c in (0..scc.cmp_count()).rev() {
+            let (head, tail) = reach.split_at_mut(c + 1);
+            for &d in &quotient[c] {
+                head[c] |= &tail[d - c - 1];
+            }
+        }
+        (scc, reach)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rand::rngs::StdRng;
+    use rand::Rng;
+    use rand::SeedableRng;
+
+    fn random_edges(rng: &mut StdRng, n: usize, m: usize) -> Vec<(usize, usize)> {
+        (0..m)
+            .map(|_| (rng.gen_range(0..n), rng.gen_range(0..n)))
+            .collect()
+    }
+
+    fn brute_bfs(n: usize, edges: &[(usize, usize)], s: usize) -> Vec<usize> {
+        let mut g = vec![Vec::new(); n];
+        for &(u, v) in edges {
+            g[u].push(v);
+        }
+        let mut visited = vec![false; n];
+        visited[s] = true;
+        let mut stack = vec![s];
+        while let Some(u) = stack.pop() {
+            for &v in &g[u] {
+                if !visited[v] {
+                    visited[v] = true;
+                    stack.push(v);
+                }
+            }
+        }
+        (0..n).filter(|&v| visited[v]).collect()
+    }
+
+    fn verify(n: usize, edges: &[(usize, usize)]) {
+        let graph = BitGraph::new(n, edges);
+        let closure = graph.transitive_closure();
+        let mut pairs = 0;
+        for (s, row) in closure.iter().enumerate() {
+            let expected = brute_bfs(n, edges, s);
+            assert_eq!(graph.bfs_from(s).ones().collect::<Vec<_>>(), expected);
+            assert_eq!(row.ones().collect::<Vec<_>>(), expected);
+            pairs += expected.len() as u64 - 1;
+        }
+        assert_eq!(graph.count_reachable_pairs(), pairs);
+    }
+
+    #[test]
+    fn test_random() {
+        let mut rng = StdRng::seed_from_u64(42);
+        for _ in 0..200 {
+            let n = rng.gen_range(1..=150);
+            let m = rng.gen_range(0..=2 * n);
+            let edges = random_edges(&mut rng, n, m);
+            verify(n, &edges);
+        }
+    }
+
+    #[test]
+    fn test_dag() {
+        let mut rng = StdRng::seed_from_u64(42);
+        for _ in 0..100 {
+            let n = rng.gen_range(1..=100);
+            let m = rng.gen_range(0..=3 * n);
+            let perm = {
+                let mut perm = (0..n).collect::<Vec<_>>();
+                for i in 1..n {
+                    perm.swap(i, rng.gen_range(0..=i));
+                }
+                perm
+            };
+            let edges = random_edges(&mut rng, n, m)
+                .into_iter()
+                .filter(|&(u, v)| u < v)
+                .map(|(u, v)| (perm[u], perm[v]))
+                .collect::<Vec<_>>();
+            verify(n, &edges);
+        }
+    }
+
+    #[test]
+    fn test_cycle() {
+        for n in 1..=130 {
+            let edges = (0..n).map(|i| (i, (i + 1) % n)).collect::<Vec<_>>();
+            let graph = BitGraph::new(n, &edges);
+            for row in graph.transitive_closure() {
+                assert_eq!(row.count_ones(), n);
+            }
+            assert_eq!(graph.count_reachable_pairs(), (n * (n - 1)) as u64);
+            verify(n, &edges);
+        }
+    }
+
+    #[test]
+    fn test_empty() {
+        let graph = BitGraph::new(0, &[]);
+        assert!(graph.is_empty());
+        assert!(graph.transitive_closure().is_empty());
+        assert_eq!(graph.count_reachable_pairs(), 0);
+    }
+
+    #[test]
+    #[should_panic(expected = "edge (0, 3) is out of the graph with 3 vertices")]
+    fn test_out_of_range() {
+        BitGraph::new(3, &[(0, 3)]);
+    }
+}
@@ -0,0 +1,314 @@
+use bitvec::BitVec;
+use scc::Scc;
+use std::collections::HashMap;
+
+/// How [`Reachability::build`] answers the queries.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ReachabilityMode<'a> {
+    /// Stores the transitive closure of the condensation, and answers any query by one bit
+    /// lookup. It takes [`Reachability::closure_bytes`] bytes of memory.
+    Closure,
+    /// Answers only the given pairs $(u, v)$. It processes the distinct sources in batches of 64,
+    /// each by one pass over the condensation with a `u64` per component, so it takes $O(V + E)$
+    /// memory.
+    Offline(&'a [(usize, usize)]),
+}
+
+/// Reachability queries on a directed graph, through the condensation.
+///
+/// A vertex always reaches itself.
+///
+/// # Examples
+///
+/// ```
+/// use bit_graph::Reachability;
+/// use bit_graph::ReachabilityMode;
+/// let edges = [(0, 1), (1, 2), (2, 1), (3, 0)];
+/// let closure = Reachability::build(4, &edges, ReachabilityMode::Closure);
+/// assert!(closure.query(0, 2));
+/// assert!(closure.query(2, 1));
+/// assert!(!closure.query(1, 0));
+///
+/// let queries = [(3, 2), (2, 3)];
+/// let offline = Reachability::build(4, &edges, ReachabilityMode::Offline(&queries));
+/// assert!(offline.query(3, 2));
+/// assert!(!offline.query(2, 3));
+/// ```
+#[derive(Debug, Clone)]
+pub struct Reachability {
+    cmp_of: Vec<usize>,
+    answers: Answers,
+}
+#[derive(Debug, Clone)]
+enum Answers {
+    Closure(Vec<BitVec>),
+    Offline(HashMap<(usize, usize), bool>),
+}
+impl Reachability {
+    /// Preprocesses a graph with `n` vertices and directed edges `edges`.
+    ///
+    /// # Panics
+    ///
+    /// If an endpoint of an edge or a query is out of `0..n`.
+    ///
+    /// # Complexity
+    ///
+    /// - [`Closure`](ReachabilityMode::Closure): $O(V + E + C E_C / w)$
+    /// - [`Offline`](ReachabilityMode::Offline): $O(Q + (V + E) \lceil S / w \rceil)$
+    ///
+    /// where $C$ and $E_C$ are the numbers of the vertices and the edges of the condensation, $Q$
+    /// is the number of the queries, $S$ is the number of their distinct sources, and $w = 64$.
+    pub fn build(n: usize, edges: &[(usize, usize)], mode: ReachabilityMode) -> Self {
+        let mut scc = Scc::new(n);
+        for &(u, v) in edges {
+            assert!(
+                u < n && v < n,
+                "edge ({}, {}) is out of the graph with {} vertices",
+                u,
+                v,
+                n
+            );
+            scc.add_edge(u, v);
+        }
+        scc.build();
+        // The edges of the condensation go from a smaller index to a larger one.
+        let quotient = scc.quotient_graph();
+        let c = scc.cmp_count();
+        let cmp_of = scc.cmp_ofs().to_vec();
+        let answers = match mode {
+            ReachabilityMode::Closure => {
+                let mut reach = (0..c)
+                    .map(|i| {
+                        let mut row = BitVec::new(c);
+                        row.set(i);
+                        row
+                    })
+                    .collect::<Vec<_>>();
+                for i in (0..c).rev() {
+                    let (head, tail) = reach.split_at_mut(i + 1);
+                    for &j in &quotient[i] {
+                        head[i] |= &tail[j - i - 1];
+                    }
+                }
+                Answers::Closure(reach)
+            }
+            ReachabilityMode::Offline(queries) => {
+                for &(u, v) in queries {
+                    assert!(
+                        u < n && v < n,
+                        "query ({}, {}) is out of the graph with {} vertices",
+                        u,
+                        v,
+                        n
+                    );
+                }
+                // The queries sorted by the source component are answered batch by batch.
+                let mut sorted = queries.to_vec();
+                sorted.sort_unstable_by_key(|&(u, _)| cmp_of[u]);
+                let mut sources = sorted.iter().map(|&(u, _)| cmp_of[u]).collect::<Vec<_>>();
+                sources.dedup();
+                let mut sorted = sorted.into_iter().peekable();
+                let mut answers = HashMap::new();
+                let mut bits = vec![0_u64; c];
+                for batch in sources.chunks(64) {
+                    bits.iter_mut().for_each(|x| *x = 0);
+                    for (k, &s) in batch.iter().enumerate() {
+                        bits[s] |= 1 << k;
+                    }
+                    // Only the components after the first source can be reached.
+                    for i in batch[0]..c {
+                        let x = bits[i];
+                        if x != 0 {
+                            for &j in &quotient[i] {
+                                bits[j] |= x;
+                            }
+                        }
+                    }
+                    for (k, &s) in batch.iter().enumerate() {
+                        while let Some((u, v)) = sorted.next_if(|&(u, _)| cmp_of[u] == s) {
+                            answers.insert((u, v), bits[cmp_of[v]] >> k & 1 == 1);
+                        }
+                    }
+                }
+                Answers::Offline(answers)
+            }
+        };
+        Self { cmp_of, answers }
+    }
+
+    /// Returns the number of the bytes that [`Closure`](ReachabilityMode::Closure) takes for
+    /// `n` vertices at most.
+    ///
+    /// It is $n \lceil n / 64 \rceil \cdot 8$, about $3 \times 10 ^ 8$ for $n = 5 \times 10 ^ 4$.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use bit_graph::Reachability;
+    /// assert_eq!(Reachability::closure_bytes(100), 1600);
+    /// ```
+    pub fn closure_bytes(n: usize) -> usize {
+        n * ((n + 63) / 64) * 8
+    }
+
+    /// Returns `true` if `v` is reachable from `u`.
+    ///
+    /// # Panics
+    ///
+    /// - If `u` or `v` is out of range.
+    /// - In the [`Offline`](ReachabilityMode::Offline) mode, if $(u, v)$ was not given.
+    pub fn query(&self, u: usize, v: usize) -> bool {
+        let n = self.cmp_of.len();
+        assert!(
+            u < n && v < n,
+            "query ({}, {}) is out of the graph with {} vertices",
+            u,
+            v,
+            n
+        );
+        match &self.answers {
+            Answers::Closure(reach) => reach[self.cmp_of[u]].test(self.cmp_of[v]),
+            Answers::Offline(answers) => *answers
+                .get(&(u, v))
+                .unwrap_or_else(|| panic!("query ({}, {}) was not given in advance", u, v)),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rand::rngs::StdRng;
+    use rand::Rng;
+    use rand::SeedableRng;
+
+    fn brute_bfs(n: usize, edges: &[(usize, usize)], s: usize) -> Vec<bool> {
+        let mut g = vec![Vec::new(); n];
+        for &(u, v) in edges {
+            g[u].push(v);
+        }
+        let mut visited = vec![false; n];
+        visited[s] = true;
+        let mut stack = vec![s];
+        while let Some(u) = stack.pop() {
+            for &v in &g[u] {
+                if !visited[v] {
+                    visited[v] = true;
+                    stack.push(v);
+                }
+            }
+        }
+        visited
+    }
+
+    fn random_dag(rng: &mut StdRng, n: usize, m: usize) -> Vec<(usize, usize)> {
+        let mut perm = (0..n).collect::<Vec<_>>();
+        for i in 1..n {
+            perm.swap(i, rng.gen_range(0..=i));
+        }
+        (0..m)
+            .map(|_| (rng.gen_range(0..n), rng.gen_range(0..n)))
+            .filter(|&(u, v)| u < v)
+            .map(|(u, v)| (perm[u], perm[v]))
+            .collect()
+    }
+
+    fn verify(n: usize, edges: &[(usize, usize)], queries: &[(usize, usize)]) {
+        let closure = Reachability::build(n, edges, ReachabilityMode::Closure);
+        let offline = Reachability::build(n, edges, ReachabilityMode::Offline(queries));
+        let reach = (0..n).map(|s| brute_bfs(n, edges, s)).collect::<Vec<_>>();
+        for (u, row) in reach.iter().enumerate() {
+            for (v, &expected) in row.iter().enumerate() {
+                assert_eq!(closure.query(u, v), expected, "{:?}", (u, v));
+            }
+        }
+        for &(u, v) in queries {
+            assert_eq!(offline.query(u, v), reach[u][v], "{:?}", (u, v));
+        }
+    }
+
+    fn random_queries(rng: &mut StdRng, n: usize) -> Vec<(usize, usize)> {
+        let q = rng.gen_range(0..=3 * n);
+        (0..q)
+            .map(|_| {
+                let u = rng.gen_range(0..n);
+                // Self-queries sometimes.
+                let v = if rng.gen_ratio(1, 10) { u } else { rng.gen_range(0..n) };
+                (u, v)
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_dag() {
+        let mut rng = StdRng::seed_from_u64(42);
+        for _ in 0..100 {
+            let n = rng.gen_range(1..=150);
+            let m = rng.gen_range(0..=3 * n);
+            let edges = random_dag(&mut rng, n, m);
+            let queries = random_queries(&mut rng, n);
+            verify(n, &edges, &queries);
+        }
+    }
+
+    #[test]
+    fn test_cyclic() {
+        let mut rng = StdRng::seed_from_u64(42);
+        for _ in 0..100 {
+            let n = rng.gen_range(1..=150);
+            let m = rng.gen_range(0..=2 * n);
+            let edges = (0..m)
+                .map(|_| (rng.gen_range(0..n), rng.gen_range(0..n)))
+                .collect::<Vec<_>>();
+            let queries = random_queries(&mut rng, n);
+            verify(n, &edges, &queries);
+        }
+    }
+
+    #[test]
+    fn test_same_component() {
+        // Two cycles 0 -> 1 -> 2 -> 0 and 3 -> 4 -> 3, joined by 2 -> 3.
+        let edges = [(0, 1), (1, 2), (2, 0), (3, 4), (4, 3), (2, 3)];
+        let queries = (0..5)
+            .flat_map(|u| (0..5).map(move |v| (u, v)))
+            .collect::<Vec<_>>();
+        verify(5, &edges, &queries);
+        let offline = Reachability::build(5, &edges, ReachabilityMode::Offline(&queries));
+        assert!(offline.query(2, 1));
+        assert!(offline.query(4, 4));
+        assert!(!offline.query(3, 0));
+    }
+
+    #[test]
+    fn test_many_sources() {
+        // More than 64 distinct sources on a path.
+        let n = 200;
+        let edges = (0..n - 1).map(|i| (i, i + 1)).collect::<Vec<_>>();
+        let queries = (0..n)
+            .flat_map(|u| [(u, (u * 7) % n), ((u * 7) % n, u)])
+            .collect::<Vec<_>>();
+        let offline = Reachability::build(n, &edges, ReachabilityMode::Offline(&queries));
+        for &(u, v) in &queries {
+            assert_eq!(offline.query(u, v), u <= v);
+        }
+    }
+
+    #[test]
+    fn test_empty() {
+        Reachability::build(0, &[], ReachabilityMode::Closure);
+        Reachability::build(0, &[], ReachabilityMode::Offline(&[]));
+        assert_eq!(Reachability::closure_bytes(0), 0);
+    }
+
+    #[test]
+    #[should_panic(expected = "query (0, 2) was not given in advance")]
+    fn test_offline_unknown_query() {
+        Reachability::build(3, &[(0, 1)], ReachabilityMode::Offline(&[(0, 1)])).query(0, 2);
+    }
+
+    #[test]
+    #[should_panic(expected = "query (0, 3) is out of the graph with 3 vertices")]
+    fn test_query_out_of_range() {
+        Reachability::build(3, &[], ReachabilityMode::Closure).query(0, 3);
+    }
+}
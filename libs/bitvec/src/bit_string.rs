@@ -0,0 +1,585 @@
+//! 2 進数や 16 進数の文字列で与えられる巨大な非負整数です。
+
+use super::BitVec;
+use std::cmp::Ordering;
+use std::fmt;
+use std::ops::AddAssign;
+use std::ops::BitAndAssign;
+use std::ops::BitOrAssign;
+use std::ops::BitXorAssign;
+use std::ops::ShlAssign;
+use std::ops::ShrAssign;
+use std::ops::SubAssign;
+
+/// 非負整数を [`u64`] のベクターに詰め込んだものです。
+///
+/// 掛け算はありませんが、足し算、引き算、比較、シフト、ビット演算がすべてワード単位で、O ( n / 64 )
+/// でできます。
+///
+/// # 使い方
+///
+/// [`BitVec`] と同じく、演算は複合代入だけで、右辺は参照です。
+///
+/// ```
+/// # use bitvec::BitString;
+/// let mut x = BitString::from_bin_str("1011");
+/// x += &BitString::from_hex_str("f");
+/// assert_eq!(x, BitString::from(26));
+///
+/// // 引き算の結果は非負でなければいけません。
+/// x -= &BitString::from(10);
+/// assert_eq!(format!("{:b}", x), "10000");
+///
+/// // 2 進数の左シフトなので、2 の冪を掛けます。
+/// x <<= 100;
+/// assert_eq!(x.bit_len(), 105);
+/// x >>= 103;
+/// assert_eq!(format!("{:x}", x), "2");
+///
+/// // 長さの違うものどうしのビット演算もできます。
+/// let mut y = BitString::from_bin_str("110011");
+/// y &= &BitString::from_bin_str("1010");
+/// assert_eq!(y, BitString::from(2));
+/// ```
+#[derive(Clone, Default, Hash, PartialEq, Eq)]
+pub struct BitString {
+    // 下位から順に並べたもので、末尾は 0 ではありません。
+    words: Vec<u64>,
+}
+impl BitString {
+    /// 0 を返します。
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// "01" 文字列から構築します。先頭が最上位で、空文字列は 0 です。
+    ///
+    /// # Panics
+    ///
+    /// '0', '1' 以外の文字があるときです。
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use bitvec::BitString;
+    /// assert_eq!(BitString::from_bin_str("00101"), BitString::from(5));
+    /// ```
+    pub fn from_bin_str(s: &str) -> Self {
+        Self::from_digits(s, 1)
+    }
+
+    /// 16 進数の文字列から構築します。大文字と小文字のどちらも使えて、空文字列は 0 です。
+    ///
+    /// # Panics
+    ///
+    /// 16 進数の数字以外の文字があるときです。
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use bitvec::BitString;
+    /// assert_eq!(BitString::from_hex_str("fF"), BitString::from(255));
+    /// ```
+    pub fn from_hex_str(s: &str) -> Self {
+        Self::from_digits(s, 4)
+    }
+
+    /// 0 ならば `true` を返します。
+    pub fn is_zero(&self) -> bool {
+        self.words.is_empty()
+    }
+
+    /// 2 進数で表したときの桁数を返します。0 の桁数は 0 です。
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use bitvec::BitString;
+    /// assert_eq!(BitString::from(0).bit_len(), 0);
+    /// assert_eq!(BitString::from(5).bit_len(), 3);
+    /// ```
+    pub fn bit_len(&self) -> usize {
+        self.words
+            .last()
+            .map_or(0, |&x| self.words.len() * 64 - x.leading_zeros() as usize)
+    }
+
+    /// 下から `i` 桁目が 1 ならば `true` を返します。
+    pub fn test(&self, i: usize) -> bool {
+        self.words
+            .get(i / 64)
+            .map_or(false, |&x| x >> (i % 64) & 1 == 1)
+    }
+
+    /// 1 の桁の個数を返します。
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use bitvec::BitString;
+    /// assert_eq!(BitString::from_bin_str("101101").count_ones(), 4);
+    /// ```
+    pub fn count_ones(&self) -> usize {
+        self.words.iter().map(|x| x.count_ones() as usize).sum()
+    }
+
+    /// [`u128`] に収まれば変換します。
+    pub fn to_u128(&self) -> Option<u128> {
+        match self.words[..] {
+            [] => Some(0),
+            [x] => Some(u128::from(x)),
+            [x, y] => Some(u128::from(x) | u128::from(y) << 64),
+            _ => None,
+        }
+    }
+
+    /// 下から `i` 桁目を `i` 番目とする長さ `len` の [`BitVec`] に変換します。
+    ///
+    /// # Panics
+    ///
+    /// [`bit_len`](Self::bit_len) が `len` よりも大きいときです。
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use bitvec::BitString;
+    /// # use bitvec::BitVec;
+    /// let bv = BitString::from_bin_str("110").to_bitvec(5);
+    /// assert_eq!(bv, BitVec::from_01str("01100"));
+    /// ```
+    pub fn to_bitvec(&self, len: usize) -> BitVec {
+        assert!(
+            self.bit_len() <= len,
+            "{} bits do not fit in a bitvec of length {}",
+            self.bit_len(),
+            len
+        );
+        let mut vec = self.words.clone();
+        vec.resize((len + 63) / 64, 0);
+        BitVec { vec, len }
+    }
+
+    fn from_digits(s: &str, bits: usize) -> Self {
+        let mut words = vec![0; (s.len() * bits + 63) / 64];
+        for (i, c) in s.bytes().rev().enumerate() {
+            let d = char::from(c)
+                .to_digit(1 << bits)
+                .unwrap_or_else(|| panic!("invalid digit {:?} in {:?}", char::from(c), s))
+                as u64;
+            // 64 は bits の倍数なので、数字はワードをまたぎません。
+            words[i * bits / 64] |= d << (i * bits % 64);
+        }
+        Self::from_words(words)
+    }
+
+    fn from_words(mut words: Vec<u64>) -> Self {
+        while words.last() == Some(&0) {
+            words.pop();
+        }
+        Self { words }
+    }
+
+    fn normalize(&mut self) {
+        while self.words.last() == Some(&0) {
+            self.words.pop();
+        }
+    }
+
+    fn write_digits(&self, f: &mut fmt::Formatter<'_>, bits: usize) -> fmt::Result {
+        let len = ((self.bit_len() + bits - 1) / bits).max(1);
+        let s = (0..len)
+            .rev()
+            .map(|i| {
+                let d = self
+                    .words
+                    .get(i * bits / 64)
+                    .map_or(0, |&x| x >> (i * bits % 64) & ((1 << bits) - 1));
+                std::char::from_digit(d as u32, 1 << bits).unwrap()
+            })
+            .collect::<String>();
+        f.pad_integral(true, if bits == 1 { "0b" } else { "0x" }, &s)
+    }
+}
+
+impl From<u128> for BitString {
+    fn from(x: u128) -> Self {
+        Self::from_words(vec![x as u64, (x >> 64) as u64])
+    }
+}
+impl From<&BitVec> for BitString {
+    fn from(bv: &BitVec) -> Self {
+        Self::from_words(bv.vec.clone())
+    }
+}
+
+impl Ord for BitString {
+    fn cmp(&self, other: &Self) -> Ordering {
+        self.words
+            .len()
+            .cmp(&other.words.len())
+            .then_with(|| self.words.iter().rev().cmp(other.words.iter().rev()))
+    }
+}
+impl PartialOrd for BitString {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl AddAssign<&Self> for BitString {
+    fn add_assign(&mut self, rhs: &Self) {
+        if self.words.len() < rhs.words.len() {
+            self.words.resize(rhs.words.len(), 0);
+        }
+        let mut carry = false;
+        for (i, x) in self.words.iter_mut().enumerate() {
+            let y = rhs.words.get(i).copied().unwrap_or(0);
+            if i >= rhs.words.len() && !carry {
+                break;
+            }
+            let (z, c0) = x.overflowing_add(y);
+            let (z, c1) = z.overflowing_add(u64::from(carry));
+            *x = z;
+            carry = c0 || c1;
+        }
+        if carry {
+            self.words.push(1);
+        }
+    }
+}
+impl SubAssign<&Self> for BitString {
+    /// # Panics
+    ///
+    /// 結果が負になるときです。
+    fn sub_assign(&mut self, rhs: &Self) {
+        assert!(
+            &*self >= rhs,
+            "attempt to subtract a larger bit string: {:b} - {:b}",
+            self,
+            rhs
+        );
+        let mut borrow = false;
+        for (i, x) in self.words.iter_mut().enumerate() {
+            let y = rhs.words.get(i).copied().unwrap_or(0);
+            if i >= rhs.words.len() && !borrow {
+                break;
+            }
+            let (z, b0) = x.overflowing_sub(y);
+            let (z, b1) = z.overflowing_sub(u64::from(borrow));
+            *x = z;
+            borrow = b0 || b1;
+        }
+        self.normalize();
+    }
+}
+
+impl BitAndAssign<&Self> for BitString {
+    fn bitand_assign(&mut self, rhs: &Self) {
+        self.words.truncate(rhs.words.len());
+        self.words
+            .iter_mut()
+            .zip(&rhs.words)
+            .for_each(|(x, &y)| *x &= y);
+        self.normalize();
+    }
+}
+impl BitOrAssign<&Self> for BitString {
+    fn bitor_assign(&mut self, rhs: &Self) {
+        if self.words.len() < rhs.words.len() {
+            self.words.resize(rhs.words.len(), 0);
+        }
+        self.words
+            .iter_mut()
+            .zip(&rhs.words)
+            .for_each(|(x, &y)| *x |= y);
+    }
+}
+impl BitXorAssign<&Self> for BitString {
+    fn bitxor_assign(&mut self, rhs: &Self) {
+        if self.words.len() < rhs.words.len() {
+            self.words.resize(rhs.words.len(), 0);
+        }
+        self.words
+            .iter_mut()
+            .zip(&rhs.words)
+            .for_each(|(x, &y)| *x ^= y);
+        self.normalize();
+    }
+}
+
+impl ShlAssign<usize> for BitString {
+    fn shl_assign(&mut self, shift: usize) {
+        if self.is_zero() {
+            return;
+        }
+        let (q, r) = (shift / 64, shift % 64);
+        let mut words = vec![0; q];
+        words.reserve(self.words.len() + 1);
+        if r == 0 {
+            words.extend_from_slice(&self.words);
+        } else {
+            let mut carry = 0;
+            for &x in &self.words {
+                words.push(x << r | carry);
+                carry = x >> (64 - r);
+            }
+            words.push(carry);
+        }
+        *self = Self::from_words(words);
+    }
+}
+impl ShrAssign<usize> for BitString {
+    fn shr_assign(&mut self, shift: usize) {
+        let (q, r) = (shift / 64, shift % 64);
+        if self.words.len() <= q {
+            self.words.clear();
+            return;
+        }
+        self.words.drain(..q);
+        if r != 0 {
+            for i in 0..self.words.len() {
+                let upper = self.words.get(i + 1).map_or(0, |&y| y << (64 - r));
+                self.words[i] = self.words[i] >> r | upper;
+            }
+        }
+        self.normalize();
+    }
+}
+
+impl fmt::Binary for BitString {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self.write_digits(f, 1)
+    }
+}
+impl fmt::LowerHex for BitString {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self.write_digits(f, 4)
+    }
+}
+impl fmt::Debug for BitString {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{:#b}", self)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rand::prelude::StdRng;
+    use rand::Rng;
+    use rand::SeedableRng;
+
+    // 和が u128 に収まるように、2^127 未満にします。
+    fn random_u128(rng: &mut StdRng) -> u128 {
+        let bits: u32 = rng.gen_range(1..=126);
+        match rng.gen_range(0..4) {
+            // 1 が続くもの
+            0 => (1_u128 << bits) - 1,
+            1 => 1_u128 << bits,
+            _ => rng.gen::<u128>() >> (128 - bits),
+        }
+    }
+
+    #[test]
+    fn test_u128() {
+        let mut rng = StdRng::seed_from_u64(42);
+        for _ in 0..20000 {
+            let x = random_u128(&mut rng);
+            let y = random_u128(&mut rng);
+            let bx = BitString::from(x);
+            let by = BitString::from(y);
+            assert_eq!(BitString::from_bin_str(&format!("{:b}", x)), bx);
+            assert_eq!(BitString::from_hex_str(&format!("{:X}", x)), bx);
+            assert_eq!(format!("{:b}", bx), format!("{:b}", x));
+            assert_eq!(format!("{:x}", bx), format!("{:x}", x));
+            assert_eq!(bx.to_u128(), Some(x));
+            assert_eq!(bx.cmp(&by), x.cmp(&y));
+            assert_eq!(bx.count_ones(), x.count_ones() as usize);
+            assert_eq!(bx.bit_len(), (128 - x.leading_zeros()) as usize);
+
+            let mut z = bx.clone();
+            z += &by;
+            assert_eq!(z.to_u128(), Some(x + y));
+            if x >= y {
+                let mut z = bx.clone();
+                z -= &by;
+                assert_eq!(z.to_u128(), Some(x - y));
+            }
+            let mut z = bx.clone();
+            z &= &by;
+            assert_eq!(z.to_u128(), Some(x & y));
+            let mut z = bx.clone();
+            z |= &by;
+            assert_eq!(z.to_u128(), Some(x | y));
+            let mut z = bx.clone();
+            z ^= &by;
+            assert_eq!(z.to_u128(), Some(x ^ y));
+
+            let k = rng.gen_range(0..=x.leading_zeros().min(127) as usize);
+            let mut z = bx.clone();
+            z <<= k;
+            assert_eq!(z.to_u128(), Some(x << k));
+            let k = rng.gen_range(0..=200);
+            let mut z = bx.clone();
+            z >>= k;
+            assert_eq!(z.to_u128(), Some(x.checked_shr(k as u32).unwrap_or(0)));
+        }
+    }
+
+    // 下位から並べた bool の配列で、筆算します。
+    fn to_bools(x: &BitString) -> Vec<bool> {
+        (0..x.bit_len()).map(|i| x.test(i)).collect()
+    }
+
+    fn from_bools(a: &[bool]) -> BitString {
+        let s = a
+            .iter()
+            .rev()
+            .map(|&b| if b { '1' } else { '0' })
+            .collect::<String>();
+        BitString::from_bin_str(&s)
+    }
+
+    fn add_bools(a: &[bool], b: &[bool]) -> Vec<bool> {
+        let mut c = Vec::new();
+        let mut carry = 0;
+        for i in 0..a.len().max(b.len()) {
+            let s = carry
+                + u8::from(a.get(i).copied().unwrap_or(false))
+                + u8::from(b.get(i).copied().unwrap_or(false));
+            c.push(s % 2 == 1);
+            carry = s / 2;
+        }
+        c.push(carry == 1);
+        c
+    }
+
+    fn sub_bools(a: &[bool], b: &[bool]) -> Vec<bool> {
+        let mut c = Vec::new();
+        let mut borrow = 0;
+        for (i, &x) in a.iter().enumerate() {
+            let d = i8::from(x) - i8::from(b.get(i).copied().unwrap_or(false)) - borrow;
+            c.push(d.rem_euclid(2) == 1);
+            borrow = i8::from(d < 0);
+        }
+        assert_eq!(borrow, 0);
+        c
+    }
+
+    fn random_long(rng: &mut StdRng) -> BitString {
+        let len = rng.gen_range(0..=1000);
+        let s = match rng.gen_range(0..4) {
+            // 繰り上がりや繰り下がりが全体に伝わるもの
+            0 => "1".repeat(len),
+            1 => format!("1{}", "0".repeat(len)),
+            _ => (0..len)
+                .map(|_| if rng.gen() { '1' } else { '0' })
+                .collect(),
+        };
+        BitString::from_bin_str(&s)
+    }
+
+    #[test]
+    fn test_long() {
+        let mut rng = StdRng::seed_from_u64(42);
+        for _ in 0..2000 {
+            let x = random_long(&mut rng);
+            let y = random_long(&mut rng);
+            let (a, b) = (to_bools(&x), to_bools(&y));
+            assert_eq!(from_bools(&a), x);
+
+            let mut z = x.clone();
+            z += &y;
+            assert_eq!(z, from_bools(&add_bools(&a, &b)));
+            let (big, small) = if x >= y { (&x, &y) } else { (&y, &x) };
+            let mut z = big.clone();
+            z -= small;
+            assert_eq!(z, from_bools(&sub_bools(&to_bools(big), &to_bools(small))));
+            // 比較は引き算と整合します。
+            let mut w = z.clone();
+            w += small;
+            assert_eq!(&w, big);
+            assert_eq!(
+                x.cmp(&y),
+                a.len()
+                    .cmp(&b.len())
+                    .then_with(|| a.iter().rev().cmp(b.iter().rev()))
+            );
+
+            let bit = |f: fn(bool, bool) -> bool| {
+                (0..a.len().max(b.len()))
+                    .map(|i| {
+                        f(
+                            a.get(i).copied().unwrap_or(false),
+                            b.get(i).copied().unwrap_or(false),
+                        )
+                    })
+                    .collect::<Vec<_>>()
+            };
+            let mut z = x.clone();
+            z &= &y;
+            assert_eq!(z, from_bools(&bit(|p, q| p & q)));
+            let mut z = x.clone();
+            z |= &y;
+            assert_eq!(z, from_bools(&bit(|p, q| p | q)));
+            let mut z = x.clone();
+            z ^= &y;
+            assert_eq!(z, from_bools(&bit(|p, q| p ^ q)));
+
+            let k = rng.gen_range(0..=300);
+            let mut z = x.clone();
+            z <<= k;
+            let mut c = vec![false; k];
+            c.extend_from_slice(&a);
+            assert_eq!(z, from_bools(&c));
+            let mut z = x.clone();
+            z >>= k;
+            assert_eq!(z, from_bools(a.get(k..).unwrap_or(&[])));
+            assert_eq!(x.count_ones(), a.iter().filter(|&&b| b).count());
+        }
+    }
+
+    #[test]
+    fn test_carry_through() {
+        for n in [1, 63, 64, 65, 127, 128, 1000] {
+            let ones = BitString::from_bin_str(&"1".repeat(n));
+            let power = BitString::from_bin_str(&format!("1{}", "0".repeat(n)));
+            let one = BitString::from(1);
+            let mut x = ones.clone();
+            x += &one;
+            assert_eq!(x, power);
+            x -= &one;
+            assert_eq!(x, ones);
+            x -= &ones;
+            assert!(x.is_zero());
+            assert_eq!(format!("{:b}", x), "0");
+        }
+    }
+
+    #[test]
+    fn test_bitvec() {
+        let mut rng = StdRng::seed_from_u64(42);
+        for _ in 0..200 {
+            let x = random_long(&mut rng);
+            let len = x.bit_len() + rng.gen_range(0..=100_usize);
+            let bv = x.to_bitvec(len);
+            assert_eq!(bv.len(), len);
+            assert_eq!(bv.count_ones(), x.count_ones());
+            assert!((0..len).all(|i| bv.test(i) == x.test(i)));
+            assert_eq!(BitString::from(&bv), x);
+        }
+    }
+
+    #[test]
+    #[should_panic(expected = "attempt to subtract a larger bit string: 10 - 11")]
+    fn test_negative() {
+        let mut x = BitString::from(2);
+        x -= &BitString::from(3);
+    }
+
+    #[test]
+    #[should_panic(expected = "invalid digit '2' in \"102\"")]
+    fn test_invalid_digit() {
+        BitString::from_bin_str("102");
+    }
+}
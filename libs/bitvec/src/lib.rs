@@ -1,7 +1,10 @@
 //! Boolean 配列を [`u64`] のベクターに詰め込みます。
 //!
 //! [詳しくは `BitVec` のドキュメントをご覧ください。](BitVec)
+//!
+//! 2 進数や 16 進数で与えられる巨大な非負整数の足し算などには [`BitString`] を使います。
 
+pub use bit_string::BitString;
 use std::fmt::Debug;
 use std::iter::FromIterator;
 use std::mem::replace;
@@ -11,6 +14,8 @@ use std::ops::BitXorAssign;
 use std::ops::ShlAssign;
 use std::ops::ShrAssign;
 
+mod bit_string;
+
 /// Boolean 配列を [`u64`] のベクターに詰め込んだものです。
 ///
 /// # 使い方
@@ -198,6 +203,41 @@ impl BitVec {
         self.vec[i / 64] &= !(1_u64 << (i % 64));
     }
 
+    /// 立っているビットの個数を返します。
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use bitvec::BitVec;
+    /// let bv = BitVec::from_01str("01101");
+    /// assert_eq!(bv.count_ones(), 3);
+    /// ```
+    pub fn count_ones(&self) -> usize {
+        self.vec.iter().map(|x| x.count_ones() as usize).sum()
+    }
+
+    /// 立っているビットの番号を昇順に返すイテレータを作ります。
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use bitvec::BitVec;
+    /// let bv = BitVec::from_01str("01101");
+    /// assert_eq!(bv.ones().collect::<Vec<_>>(), vec![1, 2, 4]);
+    /// ```
+    pub fn ones(&self) -> impl Iterator<Item = usize> + '_ {
+        self.vec.iter().enumerate().flat_map(|(i, &x)| {
+            let mut x = x;
+            std::iter::from_fn(move || {
+                (x != 0).then(|| {
+                    let j = x.trailing_zeros() as usize;
+                    x &= x - 1;
+                    i * 64 + j
+                })
+            })
+        })
+    }
+
     /// 指定したフォーマットの [`String`] に変換します。
     pub fn format(&self, t: char, f: char) -> String {
         self.iter().map(|b| if b { t } else { f }).collect()
@@ -411,6 +451,19 @@ mod tests {
         }
     }
 
+    #[test]
+    fn test_ones() {
+        let mut rng = StdRng::seed_from_u64(42);
+        for _ in 0..2000 {
+            let n = rng.gen_range(0..=256);
+            let a = generate_random(&mut rng, n);
+            let bv = a.iter().copied().collect::<BitVec>();
+            let expected = (0..n).filter(|&i| a[i]).collect::<Vec<_>>();
+            assert_eq!(bv.ones().collect::<Vec<_>>(), expected);
+            assert_eq!(bv.count_ones(), expected.len());
+        }
+    }
+
     #[test]
     fn test_shl() {
         let mut rng = StdRng::seed_from_u64(42);
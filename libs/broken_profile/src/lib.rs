@@ -0,0 +1,351 @@
+//! Dynamic programming on broken profiles over a grid.
+//!
+//! The cells are visited in row-major order. Before visiting the cell $(i, j)$, the digit $k$ of
+//! the profile describes the boundary below the cell $(i, k)$ for $k < j$, and below the cell
+//! $(i - 1, k)$ for $k \ge j$. So a transition at $(i, j)$ reads the digit $j$, which comes from
+//! the cell above, and writes the digit $j$, which goes to the cell below.
+//!
+//! # Examples
+//!
+//! ```
+//! use broken_profile::count_domino_tilings;
+//! use broken_profile::count_domino_tilings_blocked;
+//! assert_eq!(count_domino_tilings::<u64>(8, 8), 12988816);
+//! // ...
+//! // .#.
+//! // ...
+//! let mut blocked = vec![vec![false; 3]; 3];
+//! blocked[1][1] = true;
+//! assert_eq!(count_domino_tilings_blocked::<u64>(&blocked), 2);
+//! blocked[0][0] = true;
+//! assert_eq!(count_domino_tilings_blocked::<u64>(&blocked), 0);
+//! ```
+use std::ops::AddAssign;
+
+/// Profiles of a grid of width $W$ with the digits in $\[0, B)$, encoded as integers in
+/// $\[0, B ^ W)$.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct BrokenProfile {
+    h: usize,
+    w: usize,
+    base: usize,
+    pow: Vec<usize>,
+}
+impl BrokenProfile {
+    /// Returns the profiles of an $H \times W$ grid with the digits in $\[0, B)$.
+    ///
+    /// # Panics
+    ///
+    /// - $B = 0$.
+    /// - $B ^ W$ overflows.
+    pub fn new(h: usize, w: usize, base: usize) -> Self {
+        assert_ne!(base, 0, "the base must be positive");
+        let mut pow = vec![1_usize];
+        for _ in 0..w {
+            let next = pow.last().unwrap().checked_mul(base);
+            pow.push(next.unwrap_or_else(|| panic!("{}^{} profiles are too many", base, w)));
+        }
+        Self { h, w, base, pow }
+    }
+
+    /// Returns the number of the profiles $B ^ W$.
+    pub fn len(&self) -> usize {
+        self.pow[self.w]
+    }
+
+    /// Returns `false`, since there is always the profile $0$.
+    pub fn is_empty(&self) -> bool {
+        false
+    }
+
+    /// Returns the digit $j$ of `profile`.
+    pub fn digit(&self, profile: usize, j: usize) -> usize {
+        profile / self.pow[j] % self.base
+    }
+
+    /// Returns `profile` with the digit $j$ replaced by `digit`.
+    pub fn with_digit(&self, profile: usize, j: usize, digit: usize) -> usize {
+        debug_assert!(digit < self.base);
+        profile - self.digit(profile, j) * self.pow[j] + digit * self.pow[j]
+    }
+
+    /// Runs the DP from the counts `init` of the profiles before the cell $(0, 0)$ and returns
+    /// the counts after the last cell.
+    ///
+    /// `transition(i, j, profile, emit)` calls `emit(next)` for each profile `next` reachable
+    /// from `profile` by visiting the cell $(i, j)$, possibly with repetition.
+    ///
+    /// # Complexity
+    ///
+    /// $O(HWB ^ W)$ calls of `transition`, skipping the profiles with the count zero
+    pub fn run<T>(
+        &self,
+        init: Vec<T>,
+        mut transition: impl FnMut(usize, usize, usize, &mut dyn FnMut(usize)),
+    ) -> Vec<T>
+    where
+        T: Copy + AddAssign + PartialEq + From<u8>,
+    {
+        assert_eq!(
+            init.len(),
+            self.len(),
+            "`init` has {} counts for {} profiles",
+            init.len(),
+            self.len()
+        );
+        let zero = T::from(0);
+        let mut current = init;
+        let mut next = vec![zero; self.len()];
+        for i in 0..self.h {
+            for j in 0..self.w {
+                for (profile, &count) in current.iter().enumerate() {
+                    if count != zero {
+                        transition(i, j, profile, &mut |to| next[to] += count);
+                    }
+                }
+                std::mem::swap(&mut current, &mut next);
+                next.iter_mut().for_each(|x| *x = zero);
+            }
+        }
+        current
+    }
+}
+
+/// Returns the number of the tilings of an $H \times W$ grid with dominoes.
+///
+/// # Complexity
+///
+/// $O(HW2 ^ {\min(H, W)})$
+pub fn count_domino_tilings<T>(h: usize, w: usize) -> T
+where
+    T: Copy + AddAssign + PartialEq + From<u8>,
+{
+    count_domino_tilings_blocked(&vec![vec![false; w]; h])
+}
+
+/// Returns the number of the tilings of the unblocked cells with dominoes, where
+/// `blocked[i][j]` is `true` if the cell $(i, j)$ is blocked.
+///
+/// The grid is transposed if it is wider than tall, so the profiles have $\min(H, W)$ bits. The
+/// bit $j$ of the profile is set if the cell in the column $j$ is already covered.
+///
+/// # Complexity
+///
+/// $O(HW2 ^ {\min(H, W)})$
+pub fn count_domino_tilings_blocked<T>(blocked: &[Vec<bool>]) -> T
+where
+    T: Copy + AddAssign + PartialEq + From<u8>,
+{
+    let h = blocked.len();
+    let w = blocked.first().map_or(0, Vec::len);
+    assert!(
+        blocked.iter().all(|row| row.len() == w),
+        "the rows of the grid have different lengths"
+    );
+    let (h, w, blocked) = if w <= h {
+        (h, w, blocked.to_vec())
+    } else {
+        let transposed = (0..w)
+            .map(|j| (0..h).map(|i| blocked[i][j]).collect::<Vec<_>>())
+            .collect::<Vec<_>>();
+        (w, h, transposed)
+    };
+    let profile = BrokenProfile::new(h, w, 2);
+    let mut init = vec![T::from(0); profile.len()];
+    init[0] = T::from(1);
+    let result = profile.run(init, |i, j, p, emit| {
+        if p >> j & 1 == 1 {
+            // Covered from above or from the left.
+            emit(p & !(1 << j));
+        } else if !blocked[i][j] {
+            if i + 1 < h && !blocked[i + 1][j] {
+                emit(p | 1 << j);
+            }
+            if j + 1 < w && !blocked[i][j + 1] && p >> (j + 1) & 1 == 0 {
+                emit(p | 1 << (j + 1));
+            }
+        } else {
+            emit(p);
+        }
+    });
+    result[0]
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rand::rngs::StdRng;
+    use rand::Rng;
+    use rand::SeedableRng;
+
+    type Fp = fp::Fp<998244353>;
+
+    fn brute_tilings(blocked: &[Vec<bool>]) -> u64 {
+        fn dfs(covered: &mut Vec<Vec<bool>>) -> u64 {
+            let h = covered.len();
+            let w = covered[0].len();
+            let Some((i, j)) = (0..h)
+                .flat_map(|i| (0..w).map(move |j| (i, j)))
+                .find(|&(i, j)| !covered[i][j])
+            else {
+                return 1;
+            };
+            let mut count = 0;
+            for (ni, nj) in [(i + 1, j), (i, j + 1)] {
+                if ni < h && nj < w && !covered[ni][nj] {
+                    covered[i][j] = true;
+                    covered[ni][nj] = true;
+                    count += dfs(covered);
+                    covered[i][j] = false;
+                    covered[ni][nj] = false;
+                }
+            }
+            count
+        }
+        if blocked.is_empty() || blocked[0].is_empty() {
+            return 1;
+        }
+        dfs(&mut blocked.to_vec())
+    }
+
+    // A row-by-row DP over the cells of the next row covered by vertical dominoes.
+    fn row_by_row_tilings(h: usize, w: usize) -> u64 {
+        fn fill(w: usize, j: usize, from: usize, to: usize, next: &mut Vec<u64>, count: u64) {
+            if j == w {
+                next[to] += count;
+            } else if from >> j & 1 == 1 {
+                fill(w, j + 1, from, to, next, count);
+            } else {
+                fill(w, j + 1, from, to | 1 << j, next, count);
+                if j + 1 < w && from >> (j + 1) & 1 == 0 {
+                    fill(w, j + 2, from, to, next, count);
+                }
+            }
+        }
+        let mut dp = vec![0; 1 << w];
+        dp[0] = 1;
+        for _ in 0..h {
+            let mut next = vec![0; 1 << w];
+            for (from, &count) in dp.iter().enumerate() {
+                if count != 0 {
+                    fill(w, 0, from, 0, &mut next, count);
+                }
+            }
+            dp = next;
+        }
+        dp[0]
+    }
+
+    #[test]
+    fn test_domino_known() {
+        // Fibonacci numbers
+        let mut fib = [1_u64, 1];
+        for n in 1..=40 {
+            fib = [fib[1], fib[0] + fib[1]];
+            assert_eq!(count_domino_tilings::<u64>(2, n), fib[0]);
+            assert_eq!(count_domino_tilings::<u64>(n, 2), fib[0]);
+        }
+        // $a _ n = 4 a _ {n - 1} - a _ {n - 2}$ for $3 \times 2n$
+        let mut a = [1_u64, 3];
+        for n in 1..=15 {
+            assert_eq!(count_domino_tilings::<u64>(3, 2 * n), a[1]);
+            assert_eq!(count_domino_tilings::<u64>(3, 2 * n - 1), 0);
+            a = [a[1], 4 * a[1] - a[0]];
+        }
+        let four = [1, 1, 5, 11, 36, 95, 281, 781, 2245, 6336, 18061];
+        for (n, &expected) in four.iter().enumerate() {
+            assert_eq!(count_domino_tilings::<u64>(4, n), expected);
+        }
+        assert_eq!(count_domino_tilings::<u64>(6, 6), 6728);
+        assert_eq!(count_domino_tilings::<u64>(8, 8), 12988816);
+        assert_eq!(count_domino_tilings::<u64>(0, 0), 1);
+        assert_eq!(count_domino_tilings::<u64>(1, 1), 0);
+        for h in 1..=9 {
+            for w in 1..=9 {
+                assert_eq!(count_domino_tilings::<u64>(h, w), row_by_row_tilings(h, w));
+            }
+        }
+    }
+
+    #[test]
+    fn test_domino_fp() {
+        // The number of tilings of $12 \times 12$ is $53060477521960000$.
+        assert_eq!(
+            count_domino_tilings::<Fp>(12, 12),
+            Fp::from(53060477521960000_u64)
+        );
+        assert_eq!(count_domino_tilings::<u64>(12, 12), 53060477521960000);
+    }
+
+    #[test]
+    fn test_domino_blocked_random() {
+        let mut rng = StdRng::seed_from_u64(42);
+        for _ in 0..300 {
+            let h = rng.gen_range(0..=6);
+            let w = rng.gen_range(0..=6);
+            let density = rng.gen_range(0.0..0.4);
+            let blocked = (0..h)
+                .map(|_| (0..w).map(|_| rng.gen_bool(density)).collect::<Vec<_>>())
+                .collect::<Vec<_>>();
+            let expected = brute_tilings(&blocked);
+            assert_eq!(count_domino_tilings_blocked::<u64>(&blocked), expected);
+            assert_eq!(
+                count_domino_tilings_blocked::<Fp>(&blocked),
+                Fp::from(expected)
+            );
+        }
+    }
+
+    #[test]
+    fn test_three_colorings() {
+        fn brute(h: usize, w: usize) -> u64 {
+            (0..3_usize.pow((h * w) as u32))
+                .filter(|&x| {
+                    let c = |i: usize, j: usize| x / 3_usize.pow((i * w + j) as u32) % 3;
+                    (0..h).all(|i| {
+                        (0..w).all(|j| {
+                            (i == 0 || c(i - 1, j) != c(i, j)) && (j == 0 || c(i, j - 1) != c(i, j))
+                        })
+                    })
+                })
+                .count() as u64
+        }
+        for h in 1..=3 {
+            for w in 1..=4 {
+                // The digit $j$ is the color of the last visited cell in the column $j$.
+                let profile = BrokenProfile::new(h, w, 3);
+                let mut init = vec![0_u64; profile.len()];
+                init[0] = 1;
+                let result = profile.run(init, |i, j, p, emit| {
+                    for c in 0..3 {
+                        let above = i > 0 && profile.digit(p, j) == c;
+                        let left = j > 0 && profile.digit(p, j - 1) == c;
+                        if !above && !left {
+                            emit(profile.with_digit(p, j, c));
+                        }
+                    }
+                });
+                assert_eq!(result.iter().sum::<u64>(), brute(h, w));
+            }
+        }
+    }
+
+    #[test]
+    fn test_digits() {
+        let profile = BrokenProfile::new(2, 4, 3);
+        assert_eq!(profile.len(), 81);
+        let p = 2 + 3 + 2 * 27;
+        assert_eq!(
+            (0..4).map(|j| profile.digit(p, j)).collect::<Vec<_>>(),
+            vec![2, 1, 0, 2]
+        );
+        assert_eq!(profile.with_digit(p, 1, 0), 2 + 2 * 27);
+        assert_eq!(profile.with_digit(p, 2, 2), p + 2 * 9);
+    }
+
+    #[test]
+    #[should_panic(expected = "3^41 profiles are too many")]
+    fn test_too_many_profiles() {
+        BrokenProfile::new(1, 41, 3);
+    }
+}
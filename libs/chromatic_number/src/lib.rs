@@ -0,0 +1,267 @@
+//! Computes the chromatic number of a small graph.
+//!
+//! Let $I(S)$ be the number of independent sets contained in $S$. By inclusion–exclusion, the
+//! number of $k$-tuples of independent sets covering all the vertices $V$ is
+//!
+//! $$
+//! c _ k = \sum _ { S \subseteq V } (-1) ^ { |V \setminus S| } I(S) ^ k,
+//! $$
+//!
+//! and the chromatic number is the smallest $k$ with $c _ k > 0$. Since $c _ k$ is huge, it is
+//! computed modulo a random prime $p \in \[2 ^ {61}, 2 ^ {62})$, so the answer may be too large
+//! only if $p$ divides $c _ k$ for the correct $k$, which happens with negligible probability.
+//!
+//! # Examples
+//!
+//! ```
+//! use chromatic_number::chromatic_number;
+//! // A cycle of length 5.
+//! let adj = [0b10010, 0b00101, 0b01010, 0b10100, 0b01001];
+//! assert_eq!(chromatic_number(&adj, 5), 3);
+//! ```
+/// Returns the chromatic number of a graph with the modulus chosen by a seed from
+/// [`salt::next_seed`].
+///
+/// # Requirements
+///
+/// - `adj[i]` is the bit set of the neighbors of the vertex $i$, which is symmetric and does not
+///   contain $i$ itself.
+/// - $n \le 25$ or so.
+///
+/// # Complexity
+///
+/// $O(2 ^ n n)$
+pub fn chromatic_number(adj: &[u64], n: usize) -> usize {
+    chromatic_number_with_seed(adj, n, salt::next_seed())
+}
+
+/// Returns the chromatic number of a graph with the modulus chosen by `seed`.
+pub fn chromatic_number_with_seed(adj: &[u64], n: usize, seed: u64) -> usize {
+    assert_eq!(
+        adj.len(),
+        n,
+        "`adj` has {} rows for {} vertices",
+        adj.len(),
+        n
+    );
+    assert!(n < 64, "too many vertices: {}", n);
+    assert!(
+        adj.iter().all(|&a| a >> n == 0),
+        "`adj` has a vertex out of range"
+    );
+    if n == 0 {
+        return 0;
+    }
+    let p = random_prime(seed);
+    let mut count = vec![0_u64; 1 << n];
+    count[0] = 1;
+    for s in 1_usize..1 << n {
+        let v = s.trailing_zeros() as usize;
+        let t = s & !(1 << v);
+        count[s] = u64::from(count[t] == 1 && adj[v] & t as u64 == 0);
+    }
+    zeta::add(&mut count);
+    let mut pow = vec![1; 1 << n];
+    for k in 1..n {
+        let mut sum = 0;
+        for (s, (x, &c)) in pow.iter_mut().zip(&count).enumerate() {
+            *x = mul(*x, c, p);
+            if (n - s.count_ones() as usize) % 2 == 0 {
+                sum = add(sum, *x, p);
+            } else {
+                sum = add(sum, p - *x, p);
+            }
+        }
+        if sum != 0 {
+            return k;
+        }
+    }
+    n
+}
+
+fn add(x: u64, y: u64, p: u64) -> u64 {
+    let z = x + y;
+    if z >= p {
+        z - p
+    } else {
+        z
+    }
+}
+
+fn mul(x: u64, y: u64, p: u64) -> u64 {
+    (u128::from(x) * u128::from(y) % u128::from(p)) as u64
+}
+
+fn pow(mut x: u64, mut e: u64, p: u64) -> u64 {
+    let mut result = 1;
+    while e != 0 {
+        if e & 1 == 1 {
+            result = mul(result, x, p);
+        }
+        x = mul(x, x, p);
+        e >>= 1;
+    }
+    result
+}
+
+// Miller–Rabin test, which is deterministic with these bases for $p < 2 ^ {64}$.
+fn is_prime(p: u64) -> bool {
+    if p < 2 {
+        return false;
+    }
+    const BASES: [u64; 12] = [2, 3, 5, 7, 11, 13, 17, 19, 23, 29, 31, 37];
+    for &a in &BASES {
+        if p % a == 0 {
+            return p == a;
+        }
+    }
+    let s = (p - 1).trailing_zeros();
+    let d = (p - 1) >> s;
+    BASES.iter().all(|&a| {
+        let mut x = pow(a, d, p);
+        if x == 1 || x == p - 1 {
+            return true;
+        }
+        for _ in 1..s {
+            x = mul(x, x, p);
+            if x == p - 1 {
+                return true;
+            }
+        }
+        false
+    })
+}
+
+fn random_prime(mut seed: u64) -> u64 {
+    loop {
+        seed = splitmix64(seed);
+        let p = (1 << 61) | (seed >> 3) | 1;
+        if is_prime(p) {
+            return p;
+        }
+    }
+}
+
+fn splitmix64(x: u64) -> u64 {
+    let mut x = x.wrapping_add(0x9e37_79b9_7f4a_7c15);
+    x = (x ^ (x >> 30)).wrapping_mul(0xbf58_476d_1ce4_e5b9);
+    x = (x ^ (x >> 27)).wrapping_mul(0x94d0_49bb_1331_11eb);
+    x ^ (x >> 31)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rand::rngs::StdRng;
+    use rand::Rng;
+    use rand::SeedableRng;
+
+    fn brute(adj: &[u64]) -> usize {
+        fn colorable(i: usize, k: usize, adj: &[u64], color: &mut Vec<usize>) -> bool {
+            if i == adj.len() {
+                return true;
+            }
+            // Trying at most one new color breaks the symmetry.
+            let used = color.iter().max().map_or(0, |&c| c + 1);
+            for c in 0..k.min(used + 1) {
+                if (0..i).all(|j| adj[i] >> j & 1 == 0 || color[j] != c) {
+                    color.push(c);
+                    if colorable(i + 1, k, adj, color) {
+                        return true;
+                    }
+                    color.pop();
+                }
+            }
+            false
+        }
+        (0..=adj.len())
+            .find(|&k| colorable(0, k, adj, &mut Vec::new()))
+            .unwrap()
+    }
+
+    fn from_edges(n: usize, edges: &[(usize, usize)]) -> Vec<u64> {
+        let mut adj = vec![0; n];
+        for &(u, v) in edges {
+            adj[u] |= 1 << v;
+            adj[v] |= 1 << u;
+        }
+        adj
+    }
+
+    #[test]
+    fn test_random() {
+        let mut rng = StdRng::seed_from_u64(42);
+        for _ in 0..500 {
+            let n = rng.gen_range(0..=10);
+            let density = rng.gen_range(0.0..1.0);
+            let edges = (0..n)
+                .flat_map(|i| (0..i).map(move |j| (i, j)))
+                .filter(|_| rng.gen_bool(density))
+                .collect::<Vec<_>>();
+            let adj = from_edges(n, &edges);
+            let expected = brute(&adj);
+            assert_eq!(chromatic_number(&adj, n), expected);
+            assert_eq!(chromatic_number_with_seed(&adj, n, rng.gen()), expected);
+        }
+    }
+
+    #[test]
+    fn test_known_graphs() {
+        for n in 0..=12 {
+            let complete = (0..n)
+                .flat_map(|i| (0..i).map(move |j| (i, j)))
+                .collect::<Vec<_>>();
+            assert_eq!(chromatic_number(&from_edges(n, &complete), n), n);
+            assert_eq!(chromatic_number(&from_edges(n, &[]), n), n.min(1));
+        }
+        for n in 3..=20 {
+            let cycle = (0..n).map(|i| (i, (i + 1) % n)).collect::<Vec<_>>();
+            let expected = if n % 2 == 0 { 2 } else { 3 };
+            assert_eq!(chromatic_number(&from_edges(n, &cycle), n), expected);
+        }
+        // The complete bipartite graph $K _ {a, b}$.
+        for a in 1..=8 {
+            for b in 1..=8 {
+                let edges = (0..a)
+                    .flat_map(|i| (a..a + b).map(move |j| (i, j)))
+                    .collect::<Vec<_>>();
+                assert_eq!(chromatic_number(&from_edges(a + b, &edges), a + b), 2);
+            }
+        }
+        let petersen = (0..5)
+            .flat_map(|i| [(i, (i + 1) % 5), (i, i + 5), (i + 5, (i + 2) % 5 + 5)])
+            .collect::<Vec<_>>();
+        assert_eq!(chromatic_number(&from_edges(10, &petersen), 10), 3);
+        // The Grötzsch graph is triangle-free and 4-chromatic.
+        let grotzsch = (0..5)
+            .flat_map(|i| {
+                [
+                    (i, (i + 1) % 5),
+                    (i + 5, (i + 1) % 5),
+                    (i + 5, (i + 4) % 5),
+                    (i + 5, 10),
+                ]
+            })
+            .collect::<Vec<_>>();
+        assert_eq!(chromatic_number(&from_edges(11, &grotzsch), 11), 4);
+    }
+
+    #[test]
+    fn test_is_prime() {
+        let sieve = (0..10000_u64)
+            .map(|p| p >= 2 && (2..p).take_while(|d| d * d <= p).all(|d| p % d != 0))
+            .collect::<Vec<_>>();
+        for (p, &expected) in sieve.iter().enumerate() {
+            assert_eq!(is_prime(p as u64), expected);
+        }
+        assert!(is_prime((1 << 61) - 1));
+        assert!(!is_prime(3_215_031_751));
+        assert!(is_prime(random_prime(42)));
+    }
+
+    #[test]
+    #[should_panic(expected = "`adj` has 2 rows for 3 vertices")]
+    fn test_wrong_len() {
+        chromatic_number(&[0, 0], 3);
+    }
+}
@@ -23,6 +23,7 @@
 //!   - ログなし: [`VecCht`]
 //!     - 前挿入は、いらないですか…
 //!     - カーソルを１つだけ持っています。２つ以上はいらないですかね……どうしてもならオブジェクトごと２つ作ればできなくはないです。
+//!   - 直線のキュー（スライド窓）: [`MonotoneWindowCht`]
 //! - マーカー
 //!   - トレイト（ユーザーが実装する必要なし）: [`ConvexOrConcave`]
 //!   - 凸: [`Convex`]
@@ -63,6 +64,9 @@ use std::ops::Add;
 use std::ops::Mul;
 use std::ops::Neg;
 use std::ops::Sub;
+pub use window::MonotoneWindowCht;
+
+mod window;
 
 /// [`BTreeCht`], [`VecCht`] が凸関数を管理するか、凹関数を管理するかを表すマーカーのトレイト
 pub trait ConvexOrConcave: Copy {
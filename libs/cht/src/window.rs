@@ -0,0 +1,411 @@
+//! 直線を後ろから追加し、前から削除できる CHT です。
+
+/// 直線の集合を、追加の古い順に並んだキューとして管理し、最小値を求めます。
+///
+/// 区間 `[l_i, r_i)` が単調に動く DP、dp\[i\] = min { dp\[j\] + w(j, i) | l_i ≤ j < r_i } で、w が
+/// 変数分離できるときに使います。
+///
+/// # 実装
+///
+/// 後ろに追加した直線をまとめて捨てると、それよりも前の直線が削除されたあとで必要になる直線も捨ててしまうので、
+/// 二つのスタックでキューを作ります。後ろのスタックは普通の CHT で、直線そのものの列も持ちます。
+/// 前のスタックが空のときに削除すると、後ろのスタックの直線を新しい順に前のスタックに積み直します。
+/// 前のスタックは追加の前の状態に戻せる CHT で、削除はその巻き戻しです。
+///
+/// # 制約
+///
+/// - 追加する直線の傾きは、キューにある直線の傾きのうち最小のもの以下です。
+/// - 傾きと切片の絶対値は 2^62 未満で、最小値を求める x での値は i64 に収まります。
+///
+/// # 計算量
+///
+/// - 追加、削除: ならし O ( log n )
+/// - 最小値: O ( log n )、または [`MonotoneWindowCht::query_min_monotone`] で x が単調ならば
+///   ほぼ O ( 1 )
+///
+/// # Examples
+///
+/// ```
+/// use cht::MonotoneWindowCht;
+///
+/// let mut cht = MonotoneWindowCht::new();
+/// cht.push_back_line(2, 0);
+/// cht.push_back_line(0, 3);
+/// cht.push_back_line(-1, 5);
+/// assert_eq!(cht.query_min(1), 2);
+/// assert_eq!(cht.query_min(4), 1);
+///
+/// // 2x が削除されます。
+/// cht.pop_front_line();
+/// assert_eq!(cht.query_min(1), 3);
+/// ```
+#[derive(Clone, Debug, Default)]
+pub struct MonotoneWindowCht {
+    // 後ろのスタックの直線を古い順に並べたものと、その下側の包絡線
+    back_lines: Vec<Line>,
+    back: RollbackHull,
+    // 前のスタックの直線を新しい順に並べたものと、その x を -x にした直線たちの下側の包絡線
+    front_lines: Vec<Line>,
+    front: RollbackHull,
+}
+impl MonotoneWindowCht {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// 直線の本数を返します。
+    pub fn len(&self) -> usize {
+        self.back_lines.len() + self.front_lines.len()
+    }
+
+    /// 直線がなければ `true` を返します。
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// 直線 ax + b を後ろに追加します。
+    pub fn push_back_line(&mut self, a: i64, b: i64) {
+        if let Some(last) = self.back_lines.last().or_else(|| self.front_lines.first()) {
+            assert!(
+                a <= last.a,
+                "the slopes must be non-increasing: {} after {}",
+                a,
+                last.a
+            );
+        }
+        let line = Line { a, b };
+        self.back_lines.push(line);
+        self.back.push(line);
+    }
+
+    /// 最も古い直線を削除して、その傾きと切片を返します。空のときには `None` を返します。
+    pub fn pop_front_line(&mut self) -> Option<(i64, i64)> {
+        if self.front_lines.is_empty() {
+            self.back = RollbackHull::default();
+            for line in self.back_lines.drain(..).rev() {
+                self.front.push(line.reflect());
+                self.front_lines.push(line);
+            }
+        }
+        let line = self.front_lines.pop()?;
+        self.front.undo();
+        Some((line.a, line.b))
+    }
+
+    /// x での最小値を二分探索で求めます。
+    pub fn query_min(&self, x: i64) -> i64 {
+        assert!(!self.is_empty(), "cannot query an empty cht");
+        [(&self.back, x), (&self.front, -x)]
+            .iter()
+            .filter(|(hull, _)| hull.len > 0)
+            .map(|&(hull, x)| hull.lines[hull.argmin(x)].eval(x))
+            .min()
+            .unwrap()
+    }
+
+    /// x での最小値を、前回の位置から包絡線をたどって求めます。
+    ///
+    /// x が単調に動くときには、たどる回数はならしてほぼ定数回です。
+    pub fn query_min_monotone(&mut self, x: i64) -> i64 {
+        assert!(!self.is_empty(), "cannot query an empty cht");
+        [(&mut self.back, x), (&mut self.front, -x)]
+            .iter_mut()
+            .filter(|(hull, _)| hull.len > 0)
+            .map(|(hull, x)| {
+                let i = hull.walk(*x);
+                hull.lines[i].eval(*x)
+            })
+            .min()
+            .unwrap()
+    }
+}
+
+#[derive(Clone, Copy, Debug, Default, Hash, PartialEq, Eq)]
+struct Line {
+    a: i64,
+    b: i64,
+}
+impl Line {
+    fn eval(self, x: i64) -> i64 {
+        self.a * x + self.b
+    }
+
+    // x を -x にします。
+    fn reflect(self) -> Self {
+        Self {
+            a: -self.a,
+            b: self.b,
+        }
+    }
+}
+
+// 傾きが広義単調減少に追加される直線たちの下側の包絡線です。
+//
+// 追加のときには、不要になる直線たちを二分探索で求めて、その先頭だけを上書きし、`len` を縮めます。
+// 上書きした直線と元の `len` を覚えておけば、O(1) で元に戻せます。
+#[derive(Clone, Debug, Default)]
+struct RollbackHull {
+    lines: Vec<Line>,
+    len: usize,
+    // 追加前の `len` と、上書きした位置と直線
+    history: Vec<(usize, Option<(usize, Line)>)>,
+    cursor: usize,
+}
+impl RollbackHull {
+    fn push(&mut self, line: Line) {
+        let mut end = self.len;
+        if end > 0 && self.lines[end - 1].a == line.a {
+            if self.lines[end - 1].b <= line.b {
+                // 不要なので何もしませんが、巻き戻しのために記録します。
+                self.history.push((self.len, None));
+                return;
+            }
+            end -= 1;
+        }
+        // `lines[i]` が `lines[i - 1]` と `line` より下に出ないことは i について単調です。
+        let mut ng = 0;
+        let mut ok = end;
+        while ok - ng > 1 {
+            let mid = (ng + ok) / 2;
+            if is_unnecessary(self.lines[mid - 1], self.lines[mid], line) {
+                ok = mid;
+            } else {
+                ng = mid;
+            }
+        }
+        let overwritten = if ok == self.lines.len() {
+            self.lines.push(line);
+            None
+        } else {
+            Some((ok, std::mem::replace(&mut self.lines[ok], line)))
+        };
+        self.history.push((self.len, overwritten));
+        self.len = ok + 1;
+    }
+
+    fn undo(&mut self) {
+        let (len, overwritten) = self.history.pop().unwrap();
+        if let Some((i, line)) = overwritten {
+            self.lines[i] = line;
+        }
+        self.len = len;
+    }
+
+    // x で最小となる位置のうち最初のものです。
+    fn argmin(&self, x: i64) -> usize {
+        let mut lo = 0;
+        let mut hi = self.len - 1;
+        while lo < hi {
+            let mid = (lo + hi) / 2;
+            if self.lines[mid].eval(x) <= self.lines[mid + 1].eval(x) {
+                hi = mid;
+            } else {
+                lo = mid + 1;
+            }
+        }
+        lo
+    }
+
+    fn walk(&mut self, x: i64) -> usize {
+        let lines = &self.lines[..self.len];
+        let mut i = self.cursor.min(lines.len() - 1);
+        while i > 0 && lines[i - 1].eval(x) <= lines[i].eval(x) {
+            i -= 1;
+        }
+        while i + 1 < lines.len() && lines[i + 1].eval(x) < lines[i].eval(x) {
+            i += 1;
+        }
+        self.cursor = i;
+        i
+    }
+}
+
+// 傾きが l0.a > l1.a > l2.a のとき、l1 が l0 と l2 より下に出ないかどうかです。
+fn is_unnecessary(l0: Line, l1: Line, l2: Line) -> bool {
+    // l0 と l2 の交点が、l0 と l1 の交点以左
+    let lhs = (l2.b as i128 - l0.b as i128) * (l0.a as i128 - l1.a as i128);
+    let rhs = (l1.b as i128 - l0.b as i128) * (l0.a as i128 - l2.a as i128);
+    lhs <= rhs
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rand::prelude::StdRng;
+    use rand::Rng;
+    use rand::SeedableRng;
+    use std::collections::VecDeque;
+
+    #[test]
+    fn test_interleaved() {
+        let mut rng = StdRng::seed_from_u64(42);
+        for _ in 0..200 {
+            let mut cht = MonotoneWindowCht::new();
+            let mut brute = VecDeque::new();
+            let mut a: i64 = rng.gen_range(-10..=10);
+            let bound: i64 = if rng.gen_bool(0.5) { 10 } else { 1_000_000 };
+            for _ in 0..300 {
+                match rng.gen_range(0..3) {
+                    0 => {
+                        a -= rng.gen_range(0..=2_i64);
+                        let b = rng.gen_range(-bound..=bound);
+                        cht.push_back_line(a, b);
+                        brute.push_back((a, b));
+                    }
+                    1 => assert_eq!(cht.pop_front_line(), brute.pop_front()),
+                    _ => {
+                        if brute.is_empty() {
+                            continue;
+                        }
+                        let x = rng.gen_range(-bound..=bound);
+                        let expected = brute.iter().map(|&(a, b)| a * x + b).min().unwrap();
+                        assert_eq!(cht.query_min(x), expected);
+                    }
+                }
+                assert_eq!(cht.len(), brute.len());
+            }
+        }
+    }
+
+    #[test]
+    fn test_interleaved_monotone() {
+        let mut rng = StdRng::seed_from_u64(42);
+        for _ in 0..200 {
+            let mut cht = MonotoneWindowCht::new();
+            let mut brute = VecDeque::new();
+            let mut a: i64 = rng.gen_range(-10..=10);
+            let mut x: i64 = rng.gen_range(-100..=0);
+            let increasing = rng.gen_bool(0.5);
+            for _ in 0..300 {
+                match rng.gen_range(0..3) {
+                    0 => {
+                        a -= rng.gen_range(0..=2_i64);
+                        let b = rng.gen_range(-100..=100);
+                        cht.push_back_line(a, b);
+                        brute.push_back((a, b));
+                    }
+                    1 => assert_eq!(cht.pop_front_line(), brute.pop_front()),
+                    _ => {
+                        if brute.is_empty() {
+                            continue;
+                        }
+                        x += if increasing { 1 } else { -1 } * rng.gen_range(0..=3_i64);
+                        let expected = brute.iter().map(|&(a, b)| a * x + b).min().unwrap();
+                        assert_eq!(cht.query_min_monotone(x), expected);
+                    }
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn test_equal_slopes() {
+        let mut cht = MonotoneWindowCht::new();
+        cht.push_back_line(0, 5);
+        cht.push_back_line(0, 3);
+        cht.push_back_line(0, 4);
+        assert_eq!(cht.query_min(0), 3);
+        assert_eq!(cht.pop_front_line(), Some((0, 5)));
+        assert_eq!(cht.query_min(0), 3);
+        assert_eq!(cht.pop_front_line(), Some((0, 3)));
+        assert_eq!(cht.query_min(0), 4);
+        cht.push_back_line(0, 1);
+        assert_eq!(cht.query_min(0), 1);
+        assert_eq!(cht.pop_front_line(), Some((0, 4)));
+        assert_eq!(cht.pop_front_line(), Some((0, 1)));
+        assert_eq!(cht.pop_front_line(), None);
+        assert!(cht.is_empty());
+    }
+
+    #[test]
+    fn test_large_coefficients() {
+        // 交点の比較が i64 に収まらない大きさです。
+        let mut rng = StdRng::seed_from_u64(42);
+        let max = (1 << 61) - 1;
+        for _ in 0..200 {
+            let n = rng.gen_range(1..=30);
+            let mut slopes = (0..n)
+                .map(|_| rng.gen_range(-max..=max))
+                .collect::<Vec<i64>>();
+            slopes.sort_unstable_by(|x, y| y.cmp(x));
+            let mut cht = MonotoneWindowCht::new();
+            let mut brute = VecDeque::new();
+            for a in slopes {
+                let b = rng.gen_range(-max..=max);
+                cht.push_back_line(a, b);
+                brute.push_back((a, b));
+                if rng.gen_bool(0.3) {
+                    assert_eq!(cht.pop_front_line(), brute.pop_front());
+                }
+                if brute.is_empty() {
+                    continue;
+                }
+                for x in -1..=1 {
+                    let expected = brute.iter().map(|&(a, b)| a * x + b).min().unwrap();
+                    assert_eq!(cht.query_min(x), expected);
+                }
+            }
+        }
+    }
+
+    #[test]
+    #[should_panic(expected = "the slopes must be non-increasing")]
+    fn test_increasing_slope() {
+        let mut cht = MonotoneWindowCht::new();
+        cht.push_back_line(0, 0);
+        cht.push_back_line(1, 0);
+    }
+
+    // 長さ K 以下の区間に分けて、区間の和を s として s^2 + C の和を最小化します。
+    // dp[i] = min { dp[j] + (S[i] - S[j])^2 + C | i - K <= j < i }
+    fn batching_brute(a: &[i64], k: usize, c: i64) -> i64 {
+        let n = a.len();
+        let mut s = vec![0; n + 1];
+        for i in 0..n {
+            s[i + 1] = s[i] + a[i];
+        }
+        let mut dp = vec![0; n + 1];
+        for i in 1..=n {
+            dp[i] = (i.saturating_sub(k)..i)
+                .map(|j| dp[j] + (s[i] - s[j]).pow(2) + c)
+                .min()
+                .unwrap();
+        }
+        dp[n]
+    }
+
+    fn batching(a: &[i64], k: usize, c: i64, monotone: bool) -> i64 {
+        let n = a.len();
+        let mut s = vec![0; n + 1];
+        for i in 0..n {
+            s[i + 1] = s[i] + a[i];
+        }
+        let mut dp = vec![0; n + 1];
+        let mut cht = MonotoneWindowCht::new();
+        for i in 1..=n {
+            // S[j] が単調増加なので、傾き -2S[j] は単調減少です。
+            cht.push_back_line(-2 * s[i - 1], dp[i - 1] + s[i - 1] * s[i - 1]);
+            if cht.len() > k {
+                cht.pop_front_line();
+            }
+            let min = if monotone { cht.query_min_monotone(s[i]) } else { cht.query_min(s[i]) };
+            dp[i] = min + s[i] * s[i] + c;
+        }
+        dp[n]
+    }
+
+    #[test]
+    fn test_batching_dp() {
+        let mut rng = StdRng::seed_from_u64(42);
+        for _ in 0..300 {
+            let n = rng.gen_range(1..=80);
+            let k = rng.gen_range(1..=n);
+            let max: i64 = if rng.gen_bool(0.5) { 3 } else { 1000 };
+            let a = (0..n).map(|_| rng.gen_range(0..=max)).collect::<Vec<_>>();
+            let c = rng.gen_range(0..=max * max * 4);
+            let expected = batching_brute(&a, k, c);
+            assert_eq!(batching(&a, k, c, false), expected);
+            assert_eq!(batching(&a, k, c, true), expected);
+        }
+    }
+}
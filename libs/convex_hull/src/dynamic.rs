@@ -0,0 +1,429 @@
+//! 点の追加に対応した凸包です。
+//!
+//! 上側凸包と下側凸包を、それぞれ x 座標をキーとする `BTreeMap` で管理します。点を追加するときは、
+//! 凸包の外側にあれば、新しい点に隠される隣の頂点を消していきます。各点は高々 1 回しか消えないので、
+//! 償却 $O(\log n)$ です。
+//!
+//! 同一直線上の点は頂点として持たず、同じ x 座標の点は上側では最も上の、下側では最も下の点だけを
+//! 持ちます。
+//!
+//! 途中の計算は `i128` で行うので、座標の絶対値が $2 ^ {62}$ 未満であれば溢れません。
+
+use crate::predicates::cross;
+use crate::predicates::sub;
+use std::cmp::Ordering;
+use std::collections::BTreeMap;
+use std::ops::Bound;
+
+type Point = [i64; 2];
+
+/// 点の追加に対応した凸包です。
+///
+/// # Examples
+///
+/// ```
+/// use convex_hull::DynamicHull;
+/// let mut hull = DynamicHull::new();
+/// assert!(hull.insert([0, 0]));
+/// assert!(hull.insert([4, 0]));
+/// assert!(hull.insert([0, 4]));
+/// // 辺上の点は凸包を広げません。
+/// assert!(!hull.insert([2, 2]));
+/// assert!(hull.is_inside([1, 1]));
+/// assert!(!hull.is_inside([3, 3]));
+/// assert_eq!(hull.max_dot([1, 1]), Some(4));
+/// assert_eq!(hull.vertices(), vec![[0, 0], [0, 4], [4, 0]]);
+/// ```
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct DynamicHull {
+    upper: HalfHull,
+    // (x, -y) の上側凸包です。
+    lower: HalfHull,
+}
+impl DynamicHull {
+    /// 空の凸包を作ります。
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// 点が 1 つもなければ true を返します。
+    pub fn is_empty(&self) -> bool {
+        self.upper.vertices.is_empty()
+    }
+
+    /// 点を追加して、凸包が広がったら (つまり点が凸包の外側にあったら) true を返します。
+    ///
+    /// # Complexity
+    ///
+    /// 償却 $O(\log n)$
+    pub fn insert(&mut self, p: Point) -> bool {
+        let upper = self.upper.insert(p);
+        let lower = self.lower.insert(flip(p));
+        upper || lower
+    }
+
+    /// 点が凸包の内部または境界上にあれば true を返します。
+    ///
+    /// 凸包が線分や 1 点に退化しているときは、その上にあるかどうかです。
+    ///
+    /// # Complexity
+    ///
+    /// $O(\log n)$
+    pub fn is_inside(&self, p: Point) -> bool {
+        self.upper.is_below(p) && self.lower.is_below(flip(p))
+    }
+
+    /// 凸包の点 $p$ についての内積 $\langle d, p \rangle$ の最大値を返します。
+    ///
+    /// 空のときは `None` を返します。
+    ///
+    /// # Complexity
+    ///
+    /// $O(\log n)$
+    pub fn max_dot(&self, d: Point) -> Option<i128> {
+        let [a, b] = d;
+        let [a, b] = [i128::from(a), i128::from(b)];
+        match b.cmp(&0) {
+            Ordering::Greater => self.upper.max_dot(a, b),
+            Ordering::Less => self.lower.max_dot(a, -b),
+            Ordering::Equal => {
+                let x = if 0 <= a {
+                    self.upper.vertices.keys().next_back()
+                } else {
+                    self.upper.vertices.keys().next()
+                };
+                x.map(|&x| a * i128::from(x))
+            }
+        }
+    }
+
+    /// 凸包の頂点を [`convex_hull`](crate::convex_hull) と同じく、辞書順最小の頂点から時計回りに
+    /// 返します。
+    ///
+    /// # Complexity
+    ///
+    /// $O(n)$
+    pub fn vertices(&self) -> Vec<Point> {
+        let mut lower = self.lower.vertices.iter().map(|(&x, &y)| [x, -y]);
+        let first = match lower.next() {
+            None => return Vec::new(),
+            Some(first) => first,
+        };
+        let mut result = vec![first];
+        for (&x, &y) in &self.upper.vertices {
+            if [x, y] != first {
+                result.push([x, y]);
+            }
+        }
+        for p in lower.rev() {
+            if Some(&p) != result.last() {
+                result.push(p);
+            }
+        }
+        result
+    }
+}
+
+fn flip([x, y]: Point) -> Point {
+    [x, -y]
+}
+
+/// 辺の傾き dy / dx (dx > 0) です。傾きの大きい順に並びます。
+#[derive(Debug, Clone, Copy)]
+struct Slope {
+    dy: i128,
+    dx: i128,
+}
+impl Slope {
+    fn new(p: Point, q: Point) -> Self {
+        let [dx, dy] = sub(q, p);
+        Self { dy, dx }
+    }
+}
+impl PartialEq for Slope {
+    fn eq(&self, other: &Self) -> bool {
+        self.cmp(other) == Ordering::Equal
+    }
+}
+impl Eq for Slope {}
+impl PartialOrd for Slope {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+impl Ord for Slope {
+    fn cmp(&self, other: &Self) -> Ordering {
+        (other.dy * self.dx).cmp(&(self.dy * other.dx))
+    }
+}
+
+/// 上側凸包です。
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+struct HalfHull {
+    // x 座標から y 座標へのマップ
+    vertices: BTreeMap<i64, i64>,
+    // 辺の傾きから、辺の左端の x 座標へのマップ
+    edges: BTreeMap<Slope, i64>,
+}
+impl HalfHull {
+    fn prev(&self, x: i64) -> Option<Point> {
+        self.vertices.range(..x).next_back().map(|(&x, &y)| [x, y])
+    }
+
+    fn next(&self, x: i64) -> Option<Point> {
+        self.vertices
+            .range((Bound::Excluded(x), Bound::Unbounded))
+            .next()
+            .map(|(&x, &y)| [x, y])
+    }
+
+    // 凸包の x 座標の範囲にあって、凸包の上でなければ true を返します。
+    fn is_below(&self, p: Point) -> bool {
+        let [x, y] = p;
+        if let Some(&y1) = self.vertices.get(&x) {
+            return y <= y1;
+        }
+        match (self.prev(x), self.next(x)) {
+            (Some(a), Some(b)) => cross(sub(b, a), sub(p, a)) <= 0,
+            _ => false,
+        }
+    }
+
+    // b > 0 とします。
+    fn max_dot(&self, a: i128, b: i128) -> Option<i128> {
+        // 傾きが -a / b 以下の最初の辺の左端が最適です。
+        let query = Slope { dy: -a, dx: b };
+        let x = match self.edges.range(query..).next() {
+            Some((_, &x)) => x,
+            None => *self.vertices.keys().next_back()?,
+        };
+        let y = self.vertices[&x];
+        Some(a * i128::from(x) + b * i128::from(y))
+    }
+
+    fn insert(&mut self, p: Point) -> bool {
+        if self.is_below(p) {
+            return false;
+        }
+        let x = p[0];
+        if self.vertices.contains_key(&x) {
+            self.unlink(x);
+        }
+        while let Some(b) = self.prev(x) {
+            match self.prev(b[0]) {
+                Some(a) if 0 <= cross(sub(b, a), sub(p, a)) => self.unlink(b[0]),
+                _ => break,
+            }
+        }
+        while let Some(c) = self.next(x) {
+            match self.next(c[0]) {
+                Some(d) if 0 <= cross(sub(c, p), sub(d, p)) => self.unlink(c[0]),
+                _ => break,
+            }
+        }
+        self.link(p);
+        true
+    }
+
+    // 頂点を消して、両隣をつなぎます。
+    fn unlink(&mut self, x: i64) {
+        let p = [x, self.vertices[&x]];
+        let prev = self.prev(x);
+        let next = self.next(x);
+        if let Some(a) = prev {
+            self.edges.remove(&Slope::new(a, p));
+        }
+        if let Some(b) = next {
+            self.edges.remove(&Slope::new(p, b));
+        }
+        if let (Some(a), Some(b)) = (prev, next) {
+            self.edges.insert(Slope::new(a, b), a[0]);
+        }
+        self.vertices.remove(&x);
+    }
+
+    // 両隣の間に頂点を入れます。
+    fn link(&mut self, p: Point) {
+        let [x, y] = p;
+        let prev = self.prev(x);
+        let next = self.next(x);
+        if let (Some(a), Some(b)) = (prev, next) {
+            self.edges.remove(&Slope::new(a, b));
+        }
+        if let Some(a) = prev {
+            self.edges.insert(Slope::new(a, p), a[0]);
+        }
+        if let Some(b) = next {
+            self.edges.insert(Slope::new(p, b), x);
+        }
+        self.vertices.insert(x, y);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::convex_hull;
+    use crate::on_segment;
+    use crate::orient;
+    use rand::rngs::StdRng;
+    use rand::Rng;
+    use rand::SeedableRng;
+
+    // 静的な凸包で答えを計算します。
+    struct Brute {
+        hull: Vec<Point>,
+    }
+    impl Brute {
+        fn new(points: &[Point]) -> Self {
+            let mut sorted = points.to_vec();
+            sorted.sort_unstable();
+            sorted.dedup();
+            let hull = if sorted.len() <= 1 { sorted } else { convex_hull(&sorted) };
+            Self { hull }
+        }
+
+        fn is_inside(&self, p: Point) -> bool {
+            match self.hull.len() {
+                0 => false,
+                1 => self.hull[0] == p,
+                2 => on_segment(p, self.hull[0], self.hull[1]),
+                n => (0..n).all(|i| orient(self.hull[i], self.hull[(i + 1) % n], p) <= 0),
+            }
+        }
+
+        fn max_dot(&self, [a, b]: Point) -> Option<i128> {
+            self.hull
+                .iter()
+                .map(|&[x, y]| i128::from(a) * i128::from(x) + i128::from(b) * i128::from(y))
+                .max()
+        }
+
+        fn sorted_vertices(&self) -> Vec<Point> {
+            let mut hull = self.hull.clone();
+            hull.sort_unstable();
+            hull
+        }
+    }
+
+    fn validate(hull: &DynamicHull, points: &[Point], rng: &mut StdRng, coord_max: i64) {
+        let brute = Brute::new(points);
+        let mut vertices = hull.vertices();
+        if vertices.len() >= 3 {
+            assert_eq!(vertices[0], *vertices.iter().min().unwrap());
+            assert!(crate::is_convex(&vertices));
+        }
+        vertices.sort_unstable();
+        assert_eq!(vertices, brute.sorted_vertices());
+        for _ in 0..10 {
+            let p = [
+                rng.gen_range(-coord_max - 1..=coord_max + 1),
+                rng.gen_range(-coord_max - 1..=coord_max + 1),
+            ];
+            assert_eq!(hull.is_inside(p), brute.is_inside(p), "p = {:?}", p);
+            let d = [rng.gen_range(-3..=3_i64), rng.gen_range(-3..=3_i64)];
+            assert_eq!(hull.max_dot(d), brute.max_dot(d), "d = {:?}", d);
+        }
+        for &p in &brute.hull {
+            assert!(hull.is_inside(p));
+        }
+    }
+
+    fn test_order(points: &[Point], coord_max: i64) {
+        let mut rng = StdRng::seed_from_u64(42);
+        let mut hull = DynamicHull::new();
+        for (i, &p) in points.iter().enumerate() {
+            let grows = !hull.is_inside(p);
+            assert_eq!(hull.insert(p), grows);
+            validate(&hull, &points[..=i], &mut rng, coord_max);
+        }
+    }
+
+    #[test]
+    fn test_random() {
+        let mut rng = StdRng::seed_from_u64(42);
+        for _ in 0..300 {
+            let coord_max: i64 = if rng.gen() { 3 } else { 30 };
+            let n: usize = rng.gen_range(0..40);
+            let mut points = (0..n)
+                .map(|_| {
+                    [
+                        rng.gen_range(-coord_max..=coord_max),
+                        rng.gen_range(-coord_max..=coord_max),
+                    ]
+                })
+                .collect::<Vec<_>>();
+            test_order(&points, coord_max);
+            points.sort_unstable();
+            test_order(&points, coord_max);
+            points.reverse();
+            test_order(&points, coord_max);
+        }
+    }
+
+    #[test]
+    fn test_collinear() {
+        let mut rng = StdRng::seed_from_u64(42);
+        for _ in 0..100 {
+            let n: usize = rng.gen_range(1..20);
+            let [dx, dy] = [rng.gen_range(-2..=2_i64), rng.gen_range(-2..=2_i64)];
+            let points = (0..n)
+                .map(|_| {
+                    let t = rng.gen_range(-5..=5_i64);
+                    [t * dx, t * dy]
+                })
+                .collect::<Vec<_>>();
+            test_order(&points, 10);
+        }
+    }
+
+    #[test]
+    fn test_convex_position_inside_out() {
+        // 放物線上の点を中央から外側へ追加するので、毎回凸包が広がります。
+        let points = (0..30_i64)
+            .map(|i| {
+                let x = if i % 2 == 0 { i / 2 } else { -(i + 1) / 2 };
+                [x, -x * x]
+            })
+            .collect::<Vec<_>>();
+        let mut hull = DynamicHull::new();
+        for &p in &points {
+            assert!(hull.insert(p));
+        }
+        assert_eq!(hull.vertices().len(), points.len());
+        test_order(&points, 300);
+    }
+
+    #[test]
+    fn test_large_coordinates() {
+        let mut rng = StdRng::seed_from_u64(42);
+        let coord_max = 1_000_000_000;
+        let mut hull = DynamicHull::new();
+        let mut points = Vec::new();
+        for _ in 0..200 {
+            let p = [
+                rng.gen_range(-coord_max..=coord_max),
+                rng.gen_range(-coord_max..=coord_max),
+            ];
+            hull.insert(p);
+            points.push(p);
+        }
+        let brute = Brute::new(&points);
+        let mut vertices = hull.vertices();
+        vertices.sort_unstable();
+        assert_eq!(vertices, brute.sorted_vertices());
+        for &d in &[[1, 0], [0, 1], [-3, 2], [5, -7], [i64::MIN, i64::MAX]] {
+            assert_eq!(hull.max_dot(d), brute.max_dot(d));
+        }
+    }
+
+    #[test]
+    fn test_empty() {
+        let hull = DynamicHull::new();
+        assert!(hull.is_empty());
+        assert!(!hull.is_inside([0, 0]));
+        assert_eq!(hull.max_dot([1, 2]), None);
+        assert_eq!(hull.max_dot([0, 0]), None);
+        assert!(hull.vertices().is_empty());
+    }
+}
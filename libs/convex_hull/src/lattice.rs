@@ -0,0 +1,256 @@
+//! 整数座標の多角形に含まれる格子点の数え上げです。
+//!
+//! Pick の定理 $2S = 2I + B - 2$ を使います。ここで $S$ は面積、$I$ は内部の格子点の数、$B$
+//! は境界上の格子点の数です。$B$ は辺ごとに座標の差の最大公約数を足したものなので、どれも
+//! $O(n \log C)$ で求まります。
+//!
+//! 座標の絶対値は 1e9 程度を想定して、途中の計算は `i128` で行います。
+//!
+//! # 退化した場合
+//!
+//! すべての頂点が同一直線上にある (面積が 0 の) ときは、頂点を含む最小の線分 (または点) とみなし、
+//! その上の格子点をすべて境界に数えます。内部の格子点は 0 個です。
+
+use crate::predicates::cross;
+use crate::predicates::gcd;
+use crate::predicates::sub;
+
+type Point = [i64; 2];
+
+/// 多角形に含まれる格子点の数と面積です。
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub struct LatticePoints {
+    /// 内部 (境界を除く) の格子点の数
+    pub interior: u128,
+    /// 境界上の格子点の数
+    pub boundary: u128,
+    /// 面積の 2 倍
+    pub doubled_area: u128,
+}
+impl LatticePoints {
+    /// 境界も含めた格子点の数を返します。
+    pub fn total(&self) -> u128 {
+        self.interior + self.boundary
+    }
+}
+
+/// 線分 ab (端点を含む) の上の格子点の数を返します。
+///
+/// a = b のときは 1 です。
+///
+/// # Examples
+///
+/// ```
+/// use convex_hull::boundary_lattice_points;
+/// assert_eq!(boundary_lattice_points([0, 0], [4, 6]), 3);
+/// assert_eq!(boundary_lattice_points([1, 1], [1, 1]), 1);
+/// ```
+pub fn boundary_lattice_points(a: Point, b: Point) -> u128 {
+    let [dx, dy] = sub(b, a);
+    gcd(dx, dy) as u128 + 1
+}
+
+/// 三角形 abc (境界を含む) の格子点を数えます。
+///
+/// 頂点の向きはどちらでも構いません。同一直線上にあるときは、モジュールのドキュメントのとおり線分と
+/// みなします。
+///
+/// # Examples
+///
+/// ```
+/// use convex_hull::points_in_triangle;
+/// use convex_hull::LatticePoints;
+/// assert_eq!(points_in_triangle([0, 0], [4, 0], [0, 4]), LatticePoints {
+///     interior: 3,
+///     boundary: 12,
+///     doubled_area: 16
+/// });
+/// ```
+pub fn points_in_triangle(a: Point, b: Point, c: Point) -> LatticePoints {
+    points_in_convex_polygon(&[a, b, c])
+}
+
+/// 頂点を順に並べた多角形 (境界を含む) の格子点を数えます。
+///
+/// 頂点の向きはどちらでも構いません。同一直線上に並ぶ頂点や、重複した頂点があっても構いません。
+/// Pick の定理は凸でなくても単純多角形ならば成り立つので、単純多角形ならば正しく数えます。空のときは
+/// すべて 0 です。
+///
+/// # Examples
+///
+/// ```
+/// use convex_hull::points_in_convex_polygon;
+/// let result = points_in_convex_polygon(&[[0, 0], [2, 0], [2, 2], [0, 2]]);
+/// assert_eq!(result.interior, 1);
+/// assert_eq!(result.boundary, 8);
+/// assert_eq!(result.total(), 9);
+/// ```
+pub fn points_in_convex_polygon(vertices: &[Point]) -> LatticePoints {
+    let Some(&origin) = vertices.first() else {
+        return LatticePoints::default();
+    };
+    let n = vertices.len();
+    let doubled_area = (0..n)
+        .map(|i| cross(sub(vertices[i], origin), sub(vertices[(i + 1) % n], origin)))
+        .sum::<i128>()
+        .unsigned_abs();
+    if doubled_area == 0 {
+        // 同一直線上なので、辞書順が直線上の順序と一致します。
+        let lo = *vertices.iter().min().unwrap();
+        let hi = *vertices.iter().max().unwrap();
+        return LatticePoints {
+            interior: 0,
+            boundary: boundary_lattice_points(lo, hi),
+            doubled_area: 0,
+        };
+    }
+    let boundary = (0..n)
+        .map(|i| boundary_lattice_points(vertices[i], vertices[(i + 1) % n]) - 1)
+        .sum::<u128>();
+    LatticePoints {
+        interior: (doubled_area + 2 - boundary) / 2,
+        boundary,
+        doubled_area,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::convex_hull;
+    use crate::on_segment;
+    use crate::orient;
+    use rand::prelude::*;
+
+    // 頂点を順に並べた凸多角形の格子点を、外接長方形の中をすべて調べて数えます。
+    fn brute(vertices: &[Point]) -> (u128, u128) {
+        let n = vertices.len();
+        let edges = (0..n)
+            .map(|i| (vertices[i], vertices[(i + 1) % n]))
+            .collect::<Vec<_>>();
+        let [x_min, y_min] = [0, 1].map(|k| vertices.iter().map(|p| p[k]).min().unwrap());
+        let [x_max, y_max] = [0, 1].map(|k| vertices.iter().map(|p| p[k]).max().unwrap());
+        let mut interior = 0;
+        let mut boundary = 0;
+        for x in x_min..=x_max {
+            for y in y_min..=y_max {
+                let p = [x, y];
+                if edges.iter().any(|&(a, b)| on_segment(p, a, b)) {
+                    boundary += 1;
+                } else {
+                    let signs = edges.iter().map(|&(a, b)| orient(a, b, p));
+                    if signs.clone().all(|s| s > 0) || signs.clone().all(|s| s < 0) {
+                        interior += 1;
+                    }
+                }
+            }
+        }
+        (interior, boundary)
+    }
+
+    fn check(vertices: &[Point]) {
+        let result = points_in_convex_polygon(vertices);
+        let (interior, boundary) = brute(vertices);
+        assert_eq!(
+            (result.interior, result.boundary),
+            (interior, boundary),
+            "{:?}",
+            vertices
+        );
+        // Pick の定理
+        if result.doubled_area != 0 {
+            assert_eq!(
+                result.doubled_area + 2,
+                2 * result.interior + result.boundary
+            );
+        }
+    }
+
+    #[test]
+    fn test_triangle_random() {
+        let mut rng = StdRng::seed_from_u64(42);
+        for _ in 0..300 {
+            let mut random_point = || [rng.gen_range(0..200), rng.gen_range(0..200)];
+            let [a, b, c] = [random_point(), random_point(), random_point()];
+            let result = points_in_triangle(a, b, c);
+            check(&[a, b, c]);
+            for [p, q, r] in [[b, c, a], [c, b, a], [a, c, b]] {
+                assert_eq!(points_in_triangle(p, q, r), result);
+            }
+        }
+    }
+
+    #[test]
+    fn test_triangle_degenerate() {
+        let mut rng = StdRng::seed_from_u64(42);
+        for _ in 0..300 {
+            let a: Point = [rng.gen_range(0..200), rng.gen_range(0..200)];
+            let step: Point = [rng.gen_range(-20..=20), rng.gen_range(-20..=20)];
+            let [b, c] = [(); 2].map(|()| {
+                let k: i64 = rng.gen_range(0..=9);
+                [a[0] + step[0] * k, a[1] + step[1] * k]
+            });
+            let result = points_in_triangle(a, b, c);
+            assert_eq!(result.doubled_area, 0);
+            assert_eq!(result.interior, 0);
+            // 3 点の線分の和集合
+            let (_, boundary) = brute(&[a, b, c]);
+            assert_eq!(result.boundary, boundary, "{:?}", [a, b, c]);
+        }
+    }
+
+    #[test]
+    fn test_convex_polygon_random() {
+        let mut rng = StdRng::seed_from_u64(42);
+        for _ in 0..200 {
+            let n = rng.gen_range(1..=12);
+            let points = (0..n)
+                .map(|_| [rng.gen_range(0..200), rng.gen_range(0..200)])
+                .collect::<Vec<_>>();
+            let mut hull = convex_hull(&points);
+            if rng.gen() {
+                hull.reverse();
+            }
+            // 辺の途中に格子点を足します。
+            let mut vertices = Vec::new();
+            for i in 0..hull.len() {
+                let (a, b) = (hull[i], hull[(i + 1) % hull.len()]);
+                vertices.push(a);
+                let g = gcd((b[0] - a[0]) as i128, (b[1] - a[1]) as i128) as i64;
+                if g >= 2 && rng.gen_ratio(1, 2) {
+                    let k = rng.gen_range(1..g);
+                    vertices.push([a[0] + (b[0] - a[0]) / g * k, a[1] + (b[1] - a[1]) / g * k]);
+                }
+            }
+            check(&vertices);
+        }
+    }
+
+    #[test]
+    fn test_edge_cases() {
+        assert_eq!(points_in_convex_polygon(&[]), LatticePoints::default());
+        let single = points_in_convex_polygon(&[[3, 4]]);
+        assert_eq!(single.total(), 1);
+        assert_eq!(single.boundary, 1);
+        let double = points_in_convex_polygon(&[[3, 4], [3, 4], [3, 4]]);
+        assert_eq!(double, single);
+        let segment = points_in_convex_polygon(&[[0, 0], [6, 3]]);
+        assert_eq!(segment.boundary, 4);
+        assert_eq!(boundary_lattice_points([5, 0], [-5, 0]), 11);
+    }
+
+    #[test]
+    fn test_large_coordinates() {
+        const MAX: i64 = 1_000_000_000;
+        let result = points_in_triangle([-MAX, -MAX], [MAX, -MAX], [-MAX, MAX]);
+        // 直角二等辺三角形: 斜辺上の格子点は 2 MAX + 1 個です。
+        let side = 2 * MAX as u128;
+        assert_eq!(result.doubled_area, side * side);
+        assert_eq!(result.boundary, 3 * side);
+        assert_eq!(result.total(), (side + 1) * (side + 2) / 2);
+        let square =
+            points_in_convex_polygon(&[[-MAX, -MAX], [MAX, -MAX], [MAX, MAX], [-MAX, MAX]]);
+        assert_eq!(square.total(), (side + 1) * (side + 1));
+        assert_eq!(square.interior, (side - 1) * (side - 1));
+    }
+}
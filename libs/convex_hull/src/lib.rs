@@ -1,3 +1,17 @@
+mod dynamic;
+mod lattice;
+mod predicates;
+
+pub use dynamic::DynamicHull;
+pub use lattice::boundary_lattice_points;
+pub use lattice::points_in_convex_polygon;
+pub use lattice::points_in_triangle;
+pub use lattice::LatticePoints;
+pub use predicates::on_segment;
+pub use predicates::orient;
+pub use predicates::segments_intersect;
+pub use predicates::IntersectionKind;
+
 type Point = [i64; 2];
 
 /// |p0 - p1| ^ 2 を求めます。
@@ -0,0 +1,336 @@
+//! 整数座標の点と線分についての、誤差のない判定です。
+//!
+//! 座標は `i64` で受け取り、途中の計算はすべて `i128` で行います。座標の絶対値が 1e9 程度でも
+//! 外積は 1e18 を超えるので、`i64` では足りません。
+
+type Point = [i64; 2];
+
+/// 線分どうしの共通部分の種類です。
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum IntersectionKind {
+    /// 共通部分がありません。
+    None,
+    /// ちょうど 1 点で交わります。端点で接する場合や、長さ 0 の線分も含みます。
+    ///
+    /// 交点は `[num[0] / den, num[1] / den]` です。`den` は正で、`num[0]`, `num[1]`, `den` の最大公約数は 1
+    /// なので、同じ点は同じ表現になります。
+    Point { num: [i128; 2], den: i128 },
+    /// 正の長さの線分を共有します。その両端点を辞書順に返します。
+    Overlap([Point; 2]),
+}
+
+/// det(b - a, c - a) の符号を返します。
+///
+/// a, b, c が反時計回りならば 1、時計回りならば -1、同一直線上ならば 0 です。
+///
+/// # Examples
+///
+/// ```
+/// use convex_hull::orient;
+/// assert_eq!(orient([0, 0], [1, 0], [0, 1]), 1);
+/// assert_eq!(orient([0, 0], [0, 1], [1, 0]), -1);
+/// assert_eq!(orient([0, 0], [1, 1], [2, 2]), 0);
+/// ```
+pub fn orient(a: Point, b: Point, c: Point) -> i8 {
+    cross(sub(b, a), sub(c, a)).signum() as i8
+}
+
+/// 点 p が線分 ab (端点を含む) の上にあれば true を返します。
+///
+/// a = b のときは p = a かどうかです。
+///
+/// # Examples
+///
+/// ```
+/// use convex_hull::on_segment;
+/// assert!(on_segment([1, 1], [0, 0], [2, 2]));
+/// assert!(on_segment([2, 2], [0, 0], [2, 2]));
+/// assert!(!on_segment([3, 3], [0, 0], [2, 2]));
+/// assert!(!on_segment([1, 0], [0, 0], [2, 2]));
+/// ```
+pub fn on_segment(p: Point, a: Point, b: Point) -> bool {
+    orient(a, b, p) == 0 && (0..2).all(|i| a[i].min(b[i]) <= p[i] && p[i] <= a[i].max(b[i]))
+}
+
+/// 線分 ab と線分 cd (どちらも端点を含む) の共通部分を求めます。
+///
+/// 長さ 0 の線分は 1 点として扱います。
+///
+/// # Examples
+///
+/// ```
+/// use convex_hull::segments_intersect;
+/// use convex_hull::IntersectionKind;
+/// // 対角線は (1/2, 1/2) で交わります。
+/// assert_eq!(
+///     segments_intersect([0, 0], [1, 1], [0, 1], [1, 0]),
+///     IntersectionKind::Point {
+///         num: [1, 1],
+///         den: 2
+///     }
+/// );
+/// // 端点で接します。
+/// assert_eq!(
+///     segments_intersect([0, 0], [1, 1], [1, 1], [2, 0]),
+///     IntersectionKind::Point {
+///         num: [1, 1],
+///         den: 1
+///     }
+/// );
+/// // 同一直線上で重なります。
+/// assert_eq!(
+///     segments_intersect([0, 0], [4, 2], [6, 3], [2, 1]),
+///     IntersectionKind::Overlap([[2, 1], [4, 2]])
+/// );
+/// assert_eq!(
+///     segments_intersect([0, 0], [1, 0], [2, 0], [3, 0]),
+///     IntersectionKind::None
+/// );
+/// ```
+pub fn segments_intersect(a: Point, b: Point, c: Point, d: Point) -> IntersectionKind {
+    if a == b {
+        return if on_segment(a, c, d) { lattice_point(a) } else { IntersectionKind::None };
+    }
+    if c == d {
+        return if on_segment(c, a, b) { lattice_point(c) } else { IntersectionKind::None };
+    }
+    let o1 = orient(a, b, c);
+    let o2 = orient(a, b, d);
+    if o1 == 0 && o2 == 0 {
+        // 同一直線上なので、辞書順が直線上の順序と一致します。
+        let lo = a.min(b).max(c.min(d));
+        let hi = a.max(b).min(c.max(d));
+        return match lo.cmp(&hi) {
+            std::cmp::Ordering::Less => IntersectionKind::Overlap([lo, hi]),
+            std::cmp::Ordering::Equal => lattice_point(lo),
+            std::cmp::Ordering::Greater => IntersectionKind::None,
+        };
+    }
+    let o3 = orient(c, d, a);
+    let o4 = orient(c, d, b);
+    if o1 * o2 > 0 || o3 * o4 > 0 {
+        return IntersectionKind::None;
+    }
+    // 平行ならば o1 = o2 ≠ 0 なので、ここでは den ≠ 0 です。
+    // 交点は a + (b - a) t, t = det(c - a, d - c) / det(b - a, d - c) です。
+    let ab = sub(b, a);
+    let cd = sub(d, c);
+    let mut den = cross(ab, cd);
+    let mut t = cross(sub(c, a), cd);
+    if den < 0 {
+        den = -den;
+        t = -t;
+    }
+    let num = [
+        a[0] as i128 * den + ab[0] * t,
+        a[1] as i128 * den + ab[1] * t,
+    ];
+    let g = gcd(gcd(num[0], num[1]), den);
+    IntersectionKind::Point {
+        num: [num[0] / g, num[1] / g],
+        den: den / g,
+    }
+}
+
+fn lattice_point(p: Point) -> IntersectionKind {
+    IntersectionKind::Point {
+        num: [p[0] as i128, p[1] as i128],
+        den: 1,
+    }
+}
+
+pub(crate) fn sub(p: Point, q: Point) -> [i128; 2] {
+    [p[0] as i128 - q[0] as i128, p[1] as i128 - q[1] as i128]
+}
+
+pub(crate) fn cross(p: [i128; 2], q: [i128; 2]) -> i128 {
+    p[0] * q[1] - p[1] * q[0]
+}
+
+pub(crate) fn gcd(x: i128, y: i128) -> i128 {
+    let (mut x, mut y) = (x.abs(), y.abs());
+    while y != 0 {
+        x %= y;
+        std::mem::swap(&mut x, &mut y);
+    }
+    x
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rand::prelude::*;
+
+    // 分数の点 [x, y] / den
+    type Frac = ([i128; 2], i128);
+
+    fn frac(p: Point) -> Frac {
+        ([p[0] as i128, p[1] as i128], 1)
+    }
+
+    fn normalize((num, den): Frac) -> Frac {
+        let g = gcd(gcd(num[0], num[1]), den) * den.signum();
+        ([num[0] / g, num[1] / g], den / g)
+    }
+
+    // 分数の点が線分 ab の上にあるかを、分母を払って判定します。
+    fn frac_on_segment((num, den): Frac, a: Point, b: Point) -> bool {
+        let ap = [num[0] - a[0] as i128 * den, num[1] - a[1] as i128 * den];
+        cross(sub(b, a), ap) == 0
+            && (0..2).all(|i| {
+                // den は負のこともあります。
+                let x = a[i] as i128 * den;
+                let y = b[i] as i128 * den;
+                (x.min(y)..=x.max(y)).contains(&num[i])
+            })
+    }
+
+    // 共通部分は凸なので、その端点は 4 つの端点と直線どうしの交点のどれかです。
+    fn brute(a: Point, b: Point, c: Point, d: Point) -> IntersectionKind {
+        let mut candidates = vec![frac(a), frac(b), frac(c), frac(d)];
+        let ab = sub(b, a);
+        let cd = sub(d, c);
+        let den = cross(ab, cd);
+        if den != 0 {
+            let t = cross(sub(c, a), cd);
+            candidates.push((
+                [
+                    a[0] as i128 * den + ab[0] * t,
+                    a[1] as i128 * den + ab[1] * t,
+                ],
+                den,
+            ));
+        }
+        let mut common = candidates
+            .into_iter()
+            .filter(|&p| frac_on_segment(p, a, b) && frac_on_segment(p, c, d))
+            .map(normalize)
+            .collect::<Vec<_>>();
+        common.sort_unstable_by_key(|&(num, den)| (num, den));
+        common.dedup();
+        match common.len() {
+            0 => IntersectionKind::None,
+            1 => IntersectionKind::Point {
+                num: common[0].0,
+                den: common[0].1,
+            },
+            _ => {
+                // 重なりの端点は格子点である 4 つの端点のどれかです。
+                let mut ends = common
+                    .iter()
+                    .filter(|&&(_, den)| den == 1)
+                    .map(|&(num, _)| [num[0] as i64, num[1] as i64])
+                    .collect::<Vec<_>>();
+                ends.sort_unstable();
+                IntersectionKind::Overlap([ends[0], *ends.last().unwrap()])
+            }
+        }
+    }
+
+    // 線分上の格子点をすべて列挙します。
+    fn rasterize(a: Point, b: Point) -> Vec<Point> {
+        let dx = b[0] - a[0];
+        let dy = b[1] - a[1];
+        let g = gcd(dx as i128, dy as i128).max(1) as i64;
+        let mut points = (0..=g)
+            .map(|k| [a[0] + dx / g * k, a[1] + dy / g * k])
+            .collect::<Vec<_>>();
+        points.sort_unstable();
+        points.dedup();
+        points
+    }
+
+    #[test]
+    fn test_small_grid_exhaustive() {
+        let points = (0..6)
+            .flat_map(|x| (0..6).map(move |y| [x, y]))
+            .collect::<Vec<_>>();
+        let segments = points
+            .iter()
+            .flat_map(|&a| points.iter().map(move |&b| (a, b)))
+            .map(|(a, b)| (a, b, rasterize(a, b)))
+            .collect::<Vec<_>>();
+        for &(a, b, ref raster_ab) in &segments {
+            for &p in &points {
+                assert_eq!(on_segment(p, a, b), raster_ab.binary_search(&p).is_ok());
+            }
+            for &(c, d, ref raster_cd) in &segments {
+                let result = segments_intersect(a, b, c, d);
+                assert_eq!(result, brute(a, b, c, d), "{:?}", (a, b, c, d));
+                assert_eq!(result, segments_intersect(c, d, b, a));
+                let shared = raster_ab
+                    .iter()
+                    .copied()
+                    .filter(|p| raster_cd.binary_search(p).is_ok())
+                    .collect::<Vec<_>>();
+                match result {
+                    IntersectionKind::None => assert!(shared.is_empty()),
+                    IntersectionKind::Point { num, den } => {
+                        let lattice = [num[0] as i64, num[1] as i64];
+                        assert_eq!(shared, if den == 1 { vec![lattice] } else { vec![] });
+                    }
+                    IntersectionKind::Overlap([p, q]) => {
+                        assert!(p < q);
+                        assert_eq!(shared, rasterize(p, q));
+                    }
+                }
+            }
+        }
+    }
+
+    // f64 で計算して、誤差で符号が変わりうるときだけ厳密に計算します。
+    fn orient_filtered(a: Point, b: Point, c: Point) -> i8 {
+        let [ax, ay] = [a[0] as f64, a[1] as f64];
+        let l = (b[0] as f64 - ax) * (c[1] as f64 - ay);
+        let r = (c[0] as f64 - ax) * (b[1] as f64 - ay);
+        let det = l - r;
+        if det.abs() > (l.abs() + r.abs()) * 1e-12 {
+            det.signum() as i8
+        } else {
+            let exact = (b[0] as i128 - a[0] as i128) * (c[1] as i128 - a[1] as i128)
+                - (c[0] as i128 - a[0] as i128) * (b[1] as i128 - a[1] as i128);
+            exact.signum() as i8
+        }
+    }
+
+    #[test]
+    fn test_large_coordinates() {
+        const MAX: i64 = 1_000_000_000;
+        let mut rng = StdRng::seed_from_u64(42);
+        let random_point =
+            |rng: &mut StdRng| [rng.gen_range(-MAX..=MAX), rng.gen_range(-MAX..=MAX)];
+        for _ in 0..100_000 {
+            let a = random_point(&mut rng);
+            let b = random_point(&mut rng);
+            // 退化した配置を作ります。
+            let [c, d] = match rng.gen_range(0..5) {
+                0 => [random_point(&mut rng), random_point(&mut rng)],
+                1 => [a, random_point(&mut rng)],
+                2 => {
+                    // 直線 ab 上の点
+                    let g = gcd((b[0] - a[0]) as i128, (b[1] - a[1]) as i128).max(1) as i64;
+                    let step = [(b[0] - a[0]) / g, (b[1] - a[1]) / g];
+                    let mut on_line = || {
+                        let k = rng.gen_range(-g..=2 * g);
+                        [a[0] + step[0] * k, a[1] + step[1] * k]
+                    };
+                    [on_line(), on_line()]
+                }
+                3 => {
+                    let c = random_point(&mut rng);
+                    [c, c]
+                }
+                _ => [b, b],
+            };
+            for [p, q, r] in [[a, b, c], [a, b, d], [c, d, a], [c, d, b]] {
+                assert_eq!(orient(p, q, r), orient_filtered(p, q, r));
+            }
+            let result = segments_intersect(a, b, c, d);
+            assert_eq!(result, brute(a, b, c, d));
+            if let IntersectionKind::Point { num, den } = result {
+                assert!(den > 0);
+                assert_eq!(gcd(gcd(num[0], num[1]), den), 1);
+            }
+        }
+    }
+}
@@ -0,0 +1,305 @@
+use std::cmp::Ordering;
+
+const NONE: usize = usize::MAX;
+
+/// DFS 森に対する有向辺の種類です。
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum EdgeKind {
+    /// 木辺
+    Tree,
+    /// 祖先 (自分自身を含む) への辺
+    Back,
+    /// 木辺でない、子孫への辺
+    Forward,
+    /// それ以外、つまり探索済みの別の部分木への辺
+    Cross,
+}
+
+/// [`dfs_classify`] の結果です。
+///
+/// 時刻は行きがけと帰りがけで共通に数えるので、`u` が `v` の祖先であることと、区間
+/// `entry(v)..exit(v)` が `entry(u)..exit(u)` に含まれることが同値です。
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct EdgeClassification {
+    edges: Vec<(usize, usize)>,
+    kinds: Vec<EdgeKind>,
+    parent: Vec<Option<usize>>,
+    entry: Vec<usize>,
+    exit: Vec<usize>,
+    // 強連結成分の番号
+    scc: Vec<usize>,
+}
+impl EdgeClassification {
+    /// 辺 `e` の種類を返します。
+    pub fn kind(&self, e: usize) -> EdgeKind {
+        self.kinds[e]
+    }
+
+    /// 辺の種類を、辺の番号の順に返します。
+    pub fn kinds(&self) -> &[EdgeKind] {
+        &self.kinds
+    }
+
+    /// 種類が `kind` である辺の本数を返します。
+    pub fn count(&self, kind: EdgeKind) -> usize {
+        self.kinds.iter().filter(|&&k| k == kind).count()
+    }
+
+    /// DFS 森における `v` の親を返します。根ならば `None` です。
+    pub fn parent(&self, v: usize) -> Option<usize> {
+        self.parent[v]
+    }
+
+    /// `v` の行きがけの時刻を返します。
+    pub fn entry(&self, v: usize) -> usize {
+        self.entry[v]
+    }
+
+    /// `v` の帰りがけの時刻を返します。
+    pub fn exit(&self, v: usize) -> usize {
+        self.exit[v]
+    }
+
+    /// DFS 森において `u` が `v` の祖先 (自分自身を含む) ならば `true` を返します。
+    ///
+    /// # Complexity
+    ///
+    /// $O(1)$
+    pub fn is_ancestor(&self, u: usize, v: usize) -> bool {
+        self.entry[u] <= self.entry[v] && self.exit[v] <= self.exit[u]
+    }
+
+    /// 辺 `e` を通る閉路があれば `true` を返します。
+    ///
+    /// # Complexity
+    ///
+    /// $O(1)$
+    pub fn has_cycle_through_edge(&self, e: usize) -> bool {
+        let (u, v) = self.edges[e];
+        self.scc[u] == self.scc[v]
+    }
+}
+
+/// 有向グラフを DFS して、辺を分類します。
+///
+/// 始点は `order_hint` の頂点をその順に、続けて残りの頂点を番号の昇順に試します。各頂点から出る辺は、
+/// `edges` での順に辿ります。したがって、結果は入力だけから決まります。
+///
+/// # Panics
+///
+/// 頂点番号が `n` 以上のとき
+///
+/// # Complexity
+///
+/// $O(n + m)$
+///
+/// # Examples
+///
+/// ```
+/// use dfs::dfs_classify;
+/// use dfs::EdgeKind;
+/// let edges = [(0, 1), (1, 2), (2, 0), (0, 2), (3, 1)];
+/// let result = dfs_classify(4, &edges, &[]);
+/// assert_eq!(result.kinds(), &[
+///     EdgeKind::Tree,
+///     EdgeKind::Tree,
+///     EdgeKind::Back,
+///     EdgeKind::Forward,
+///     EdgeKind::Cross,
+/// ]);
+/// assert_eq!(result.parent(2), Some(1));
+/// assert!(result.is_ancestor(0, 2));
+/// assert!(result.has_cycle_through_edge(3));
+/// assert!(!result.has_cycle_through_edge(4));
+/// ```
+pub fn dfs_classify(
+    n: usize,
+    edges: &[(usize, usize)],
+    order_hint: &[usize],
+) -> EdgeClassification {
+    let mut g = vec![Vec::new(); n];
+    for (e, &(u, v)) in edges.iter().enumerate() {
+        assert!(
+            u < n && v < n,
+            "edge ({}, {}) out of range for {} vertices",
+            u,
+            v,
+            n
+        );
+        g[u].push(e);
+    }
+    let mut kinds = vec![EdgeKind::Tree; edges.len()];
+    let mut parent = vec![None; n];
+    let mut entry = vec![NONE; n];
+    let mut exit = vec![NONE; n];
+    let mut scc = vec![NONE; n];
+    // Tarjan の強連結成分分解を同時にします。
+    let mut low = vec![NONE; n];
+    let mut open = Vec::new();
+    let mut scc_count = 0;
+    let mut clock = 0;
+    for root in order_hint.iter().copied().chain(0..n) {
+        if entry[root] != NONE {
+            continue;
+        }
+        entry[root] = clock;
+        low[root] = clock;
+        clock += 1;
+        open.push(root);
+        let mut stack = vec![(root, 0)];
+        while let Some((v, i)) = stack.last_mut() {
+            let v = *v;
+            if let Some(&e) = g[v].get(*i) {
+                *i += 1;
+                let w = edges[e].1;
+                kinds[e] = if entry[w] == NONE {
+                    parent[w] = Some(v);
+                    entry[w] = clock;
+                    low[w] = clock;
+                    clock += 1;
+                    open.push(w);
+                    stack.push((w, 0));
+                    EdgeKind::Tree
+                } else {
+                    // 強連結成分が確定していない頂点への辺だけが low を更新します。
+                    if scc[w] == NONE {
+                        low[v] = low[v].min(entry[w]);
+                    }
+                    match (exit[w] == NONE, entry[v].cmp(&entry[w])) {
+                        (true, _) => EdgeKind::Back,
+                        (false, Ordering::Less) => EdgeKind::Forward,
+                        (false, _) => EdgeKind::Cross,
+                    }
+                };
+            } else {
+                stack.pop();
+                exit[v] = clock;
+                clock += 1;
+                if let Some(&(p, _)) = stack.last() {
+                    low[p] = low[p].min(low[v]);
+                }
+                if low[v] == entry[v] {
+                    loop {
+                        let w = open.pop().unwrap();
+                        scc[w] = scc_count;
+                        if w == v {
+                            break;
+                        }
+                    }
+                    scc_count += 1;
+                }
+            }
+        }
+    }
+    EdgeClassification {
+        edges: edges.to_vec(),
+        kinds,
+        parent,
+        entry,
+        exit,
+        scc,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::calc_reachability;
+    use rand::prelude::*;
+
+    fn random_digraph(rng: &mut StdRng) -> (usize, Vec<(usize, usize)>) {
+        let n = rng.gen_range(1..=8_usize);
+        let m = rng.gen_range(0..=16_usize);
+        // 自己ループと多重辺も出ます。
+        let edges = (0..m)
+            .map(|_| (rng.gen_range(0..n), rng.gen_range(0..n)))
+            .collect();
+        (n, edges)
+    }
+
+    #[test]
+    fn test_classification() {
+        let mut rng = StdRng::seed_from_u64(42);
+        for _ in 0..2000 {
+            let (n, edges) = random_digraph(&mut rng);
+            let mut order_hint = (0..n).collect::<Vec<_>>();
+            order_hint.shuffle(&mut rng);
+            order_hint.truncate(rng.gen_range(0..=n));
+            let result = dfs_classify(n, &edges, &order_hint);
+            assert_eq!(result, dfs_classify(n, &edges, &order_hint));
+
+            // 時刻は 0..2n の順列です。
+            let mut times = (0..n)
+                .flat_map(|v| [result.entry(v), result.exit(v)])
+                .collect::<Vec<_>>();
+            times.sort_unstable();
+            assert_eq!(times, (0..2 * n).collect::<Vec<_>>());
+            if let Some(&root) = order_hint.first() {
+                assert_eq!(result.entry(root), 0);
+            }
+
+            for (e, &(u, v)) in edges.iter().enumerate() {
+                match result.kind(e) {
+                    EdgeKind::Tree => assert_eq!(result.parent(v), Some(u)),
+                    EdgeKind::Back => assert!(result.is_ancestor(v, u)),
+                    EdgeKind::Forward => {
+                        assert!(u != v && result.is_ancestor(u, v));
+                    }
+                    // 白い頂点への辺は木辺になるので、横断辺の先は探索済みです。
+                    EdgeKind::Cross => assert!(result.exit(v) < result.entry(u)),
+                }
+            }
+            // 根以外の頂点はちょうど 1 本の木辺で入られます。
+            for v in 0..n {
+                let tree_in = (0..edges.len())
+                    .filter(|&e| result.kind(e) == EdgeKind::Tree && edges[e].1 == v)
+                    .count();
+                assert_eq!(tree_in, usize::from(result.parent(v).is_some()));
+                if let Some(p) = result.parent(v) {
+                    assert!(result.is_ancestor(p, v));
+                }
+            }
+            let total = [
+                EdgeKind::Tree,
+                EdgeKind::Back,
+                EdgeKind::Forward,
+                EdgeKind::Cross,
+            ]
+            .iter()
+            .map(|&kind| result.count(kind))
+            .sum::<usize>();
+            assert_eq!(total, edges.len());
+
+            let mut g = vec![Vec::new(); n];
+            for &(u, v) in &edges {
+                g[u].push(v);
+            }
+            for (e, &(u, v)) in edges.iter().enumerate() {
+                let expected = calc_reachability(v, &g)[u];
+                assert_eq!(result.has_cycle_through_edge(e), expected);
+            }
+            // 閉路があることと後退辺があることは同値です。
+            let has_cycle = (0..edges.len()).any(|e| result.has_cycle_through_edge(e));
+            assert_eq!(has_cycle, result.count(EdgeKind::Back) > 0);
+        }
+    }
+
+    #[test]
+    fn test_self_loops_and_parallel_edges() {
+        let edges = [(0, 0), (0, 1), (0, 1), (1, 0), (1, 1)];
+        let result = dfs_classify(2, &edges, &[]);
+        assert_eq!(result.kinds(), &[
+            EdgeKind::Back,
+            EdgeKind::Tree,
+            EdgeKind::Forward,
+            EdgeKind::Back,
+            EdgeKind::Back,
+        ]);
+        assert!((0..5).all(|e| result.has_cycle_through_edge(e)));
+
+        let result = dfs_classify(2, &[(0, 1), (0, 1)], &[1]);
+        assert_eq!(result.kinds(), &[EdgeKind::Cross, EdgeKind::Cross]);
+        assert_eq!(result.parent(0), None);
+        assert!(!result.has_cycle_through_edge(0));
+    }
+}
@@ -1,3 +1,8 @@
+mod classify;
+
+pub use classify::dfs_classify;
+pub use classify::EdgeClassification;
+pub use classify::EdgeKind;
 use std::mem::replace;
 
 /// 一点からの到達可能性配列を返します。
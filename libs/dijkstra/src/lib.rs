@@ -0,0 +1,325 @@
+//! Dijkstra's algorithm with the number of the shortest paths and the lexicographically smallest
+//! one.
+//!
+//! A graph is given by adjacency lists `g[x] = [(y, w), ...]` of directed edges $x \to y$ of
+//! weight $w$.
+//!
+//! # Zero-weight edges
+//!
+//! [`dijkstra`] accepts nonnegative weights, but [`dijkstra_count`] and
+//! [`lexicographically_smallest_shortest_path`] require positive weights and panic otherwise.
+//! A zero-weight cycle gives infinitely many shortest paths, and even without one the counts
+//! depend on the order of popping the vertices at the same distance.
+//!
+//! # Examples
+//!
+//! ```
+//! use dijkstra::dijkstra_count;
+//! use dijkstra::lexicographically_smallest_shortest_path;
+//! use fp::Fp;
+//! let g = vec![vec![(2, 1), (1, 1)], vec![(3, 1)], vec![(3, 1)], vec![]];
+//! let (dist, count) = dijkstra_count::<u32, 998244353>(&g, 0);
+//! assert_eq!(dist, vec![Some(0), Some(1), Some(1), Some(2)]);
+//! assert_eq!(count[3], Fp::new(2));
+//! assert_eq!(
+//!     lexicographically_smallest_shortest_path(&g, 0, 3),
+//!     Some(vec![0, 1, 3])
+//! );
+//! ```
+
+use fp::Fp;
+use std::cmp::Reverse;
+use std::collections::BinaryHeap;
+use std::fmt::Debug;
+use std::ops::Add;
+
+/// The weight of an edge.
+pub trait Weight: Copy + Ord + Debug + Add<Output = Self> {
+    /// $0$
+    const ZERO: Self;
+}
+macro_rules! impl_weight {
+    ($($t:ty),*) => {$(
+        impl Weight for $t {
+            const ZERO: Self = 0;
+        }
+    )*};
+}
+impl_weight!(u8, u16, u32, u64, u128, usize, i8, i16, i32, i64, i128, isize);
+
+/// Returns the distances from `s`, or `None` for unreachable vertices.
+///
+/// # Panics
+///
+/// If some weight is negative.
+///
+/// # Complexity
+///
+/// $O((V + E) \log E)$
+pub fn dijkstra<W: Weight>(g: &[Vec<(usize, W)>], s: usize) -> Vec<Option<W>> {
+    assert_weights(g, W::ZERO);
+    run(g, s, |_, _, _| {})
+}
+
+/// Returns the distances from `s` and the numbers of the shortest paths from `s` modulo $P$.
+///
+/// The count of an unreachable vertex is $0$, and that of `s` is $1$.
+///
+/// # Panics
+///
+/// If some weight is not positive.
+///
+/// # Complexity
+///
+/// $O((V + E) \log E)$
+pub fn dijkstra_count<W: Weight, const P: u64>(
+    g: &[Vec<(usize, W)>],
+    s: usize,
+) -> (Vec<Option<W>>, Vec<Fp<P>>) {
+    assert_positive_weights(g);
+    let mut count = vec![Fp::new(0); g.len()];
+    count[s] = Fp::new(1);
+    // Every predecessor of `y` is popped before `y` because the weights are positive.
+    let dist = run(g, s, |x, y, improved| {
+        let cx = count[x];
+        if improved {
+            count[y] = cx;
+        } else {
+            count[y] += cx;
+        }
+    });
+    (dist, count)
+}
+
+/// Returns the lexicographically smallest vertex sequence among the shortest paths from `s` to
+/// `t`, or `None` if `t` is unreachable.
+///
+/// Choosing the smallest next vertex greedily along tight edges may run into a dead end, so it
+/// first marks the vertices from which `t` is reachable along tight edges, in the decreasing
+/// order of the distance.
+///
+/// # Panics
+///
+/// If some weight is not positive.
+///
+/// # Complexity
+///
+/// $O((V + E) \log E)$
+pub fn lexicographically_smallest_shortest_path<W: Weight>(
+    g: &[Vec<(usize, W)>],
+    s: usize,
+    t: usize,
+) -> Option<Vec<usize>> {
+    assert_positive_weights(g);
+    let dist = run(g, s, |_, _, _| {});
+    dist[t]?;
+    let tight = |x: usize, y: usize, w: W| dist[x].map(|dx| dx + w) == dist[y];
+    let mut order = (0..g.len())
+        .filter(|&x| dist[x].is_some())
+        .collect::<Vec<_>>();
+    order.sort_by_key(|&x| Reverse(dist[x]));
+    let mut to_t = vec![false; g.len()];
+    to_t[t] = true;
+    for &x in &order {
+        if g[x].iter().any(|&(y, w)| to_t[y] && tight(x, y, w)) {
+            to_t[x] = true;
+        }
+    }
+    let mut path = vec![s];
+    let mut x = s;
+    while x != t {
+        x = g[x]
+            .iter()
+            .filter(|&&(y, w)| to_t[y] && tight(x, y, w))
+            .map(|&(y, _)| y)
+            .min()
+            .unwrap();
+        path.push(x);
+    }
+    Some(path)
+}
+
+// Calls `relax(x, y, improved)` for each edge $x \to y$ on a shortest path found so far, where
+// `improved` tells whether the distance of `y` has been strictly improved.
+fn run<W: Weight>(
+    g: &[Vec<(usize, W)>],
+    s: usize,
+    mut relax: impl FnMut(usize, usize, bool),
+) -> Vec<Option<W>> {
+    let mut dist = vec![None; g.len()];
+    dist[s] = Some(W::ZERO);
+    let mut heap = BinaryHeap::from(vec![(Reverse(W::ZERO), s)]);
+    while let Some((Reverse(dx), x)) = heap.pop() {
+        if dist[x] != Some(dx) {
+            continue;
+        }
+        for &(y, w) in &g[x] {
+            let dy = dx + w;
+            match dist[y] {
+                Some(old) if old < dy => {}
+                Some(old) if old == dy => relax(x, y, false),
+                _ => {
+                    dist[y] = Some(dy);
+                    heap.push((Reverse(dy), y));
+                    relax(x, y, true);
+                }
+            }
+        }
+    }
+    dist
+}
+
+fn assert_weights<W: Weight>(g: &[Vec<(usize, W)>], min: W) {
+    for (x, edges) in g.iter().enumerate() {
+        for &(y, w) in edges {
+            assert!(
+                min <= w,
+                "edge ({}, {}) has a weight {:?} less than {:?}",
+                x,
+                y,
+                w,
+                min
+            );
+        }
+    }
+}
+
+fn assert_positive_weights<W: Weight>(g: &[Vec<(usize, W)>]) {
+    for (x, edges) in g.iter().enumerate() {
+        for &(y, w) in edges {
+            assert!(
+                W::ZERO < w,
+                "edge ({}, {}) has a non-positive weight {:?}",
+                x,
+                y,
+                w
+            );
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rand::rngs::StdRng;
+    use rand::Rng;
+    use rand::SeedableRng;
+
+    const P: u64 = 998244353;
+
+    fn random_graph(rng: &mut StdRng, n: usize, m: usize, max: u32) -> Vec<Vec<(usize, u32)>> {
+        let mut g = vec![Vec::new(); n];
+        for _ in 0..m {
+            let x = rng.gen_range(0..n);
+            let y = rng.gen_range(0..n);
+            g[x].push((y, rng.gen_range(1..=max)));
+        }
+        g
+    }
+
+    // Enumerates all the paths from `s`, which are simple if they are shortest.
+    fn brute(g: &[Vec<(usize, u32)>], s: usize) -> Vec<Vec<(u32, Vec<usize>)>> {
+        fn dfs(
+            g: &[Vec<(usize, u32)>],
+            path: &mut Vec<usize>,
+            d: u32,
+            paths: &mut Vec<Vec<(u32, Vec<usize>)>>,
+        ) {
+            let x = *path.last().unwrap();
+            paths[x].push((d, path.clone()));
+            for &(y, w) in &g[x] {
+                if !path.contains(&y) {
+                    path.push(y);
+                    dfs(g, path, d + w, paths);
+                    path.pop();
+                }
+            }
+        }
+        let mut paths = vec![Vec::new(); g.len()];
+        dfs(g, &mut vec![s], 0, &mut paths);
+        paths
+    }
+
+    #[test]
+    fn test_random() {
+        let mut rng = StdRng::seed_from_u64(42);
+        for _ in 0..300 {
+            let n = rng.gen_range(1..=7);
+            let m = rng.gen_range(0..=n * n);
+            let max = rng.gen_range(1..=3);
+            let g = random_graph(&mut rng, n, m, max);
+            let s = rng.gen_range(0..n);
+            let paths = brute(&g, s);
+            let (dist, count) = dijkstra_count::<u32, P>(&g, s);
+            assert_eq!(dijkstra(&g, s), dist);
+            for t in 0..n {
+                let expected_dist = paths[t].iter().map(|&(d, _)| d).min();
+                let shortest = paths[t]
+                    .iter()
+                    .filter(|&(d, _)| Some(*d) == expected_dist)
+                    .map(|(_, path)| path.clone())
+                    .collect::<Vec<_>>();
+                assert_eq!(dist[t], expected_dist);
+                assert_eq!(count[t], Fp::new(shortest.len() as u64));
+                assert_eq!(
+                    lexicographically_smallest_shortest_path(&g, s, t),
+                    shortest.into_iter().min()
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn test_many_ties() {
+        // A chain of diamonds has $2 ^ k$ shortest paths.
+        let k = 40;
+        let mut g = vec![Vec::new(); 3 * k + 1];
+        for i in 0..k {
+            g[3 * i].push((3 * i + 2, 1_u64));
+            g[3 * i].push((3 * i + 1, 1));
+            g[3 * i + 1].push((3 * i + 3, 1));
+            g[3 * i + 2].push((3 * i + 3, 1));
+        }
+        let (dist, count) = dijkstra_count::<u64, P>(&g, 0);
+        assert_eq!(dist[3 * k], Some(2 * k as u64));
+        assert_eq!(count[3 * k], Fp::new(2).pow(k as u64));
+        let expected = (0..k).flat_map(|i| [3 * i, 3 * i + 1]).chain([3 * k]);
+        assert_eq!(
+            lexicographically_smallest_shortest_path(&g, 0, 3 * k),
+            Some(expected.collect())
+        );
+    }
+
+    #[test]
+    fn test_dead_end() {
+        // The greedy choice of 1 leads nowhere.
+        let g = vec![
+            vec![(1, 1), (2, 1)],
+            vec![(4, 5)],
+            vec![(3, 1)],
+            vec![],
+            vec![],
+        ];
+        assert_eq!(
+            lexicographically_smallest_shortest_path(&g, 0, 3),
+            Some(vec![0, 2, 3])
+        );
+        assert_eq!(lexicographically_smallest_shortest_path(&g, 3, 0), None);
+        assert_eq!(
+            lexicographically_smallest_shortest_path(&g, 0, 0),
+            Some(vec![0])
+        );
+    }
+
+    #[test]
+    #[should_panic(expected = "edge (0, 1) has a non-positive weight 0")]
+    fn test_zero_weight() {
+        dijkstra_count::<u32, P>(&[vec![(1, 0)], vec![]], 0);
+    }
+
+    #[test]
+    #[should_panic(expected = "edge (0, 1) has a weight -1 less than 0")]
+    fn test_negative_weight() {
+        dijkstra(&[vec![(1, -1)], vec![]], 0);
+    }
+}
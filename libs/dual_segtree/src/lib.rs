@@ -31,12 +31,12 @@
 //! seg.apply(0..1, &[0, 3]); // +3
 //! assert_eq!(seg.collect_vec(), vec![[-2, 1], [0, 0]]);
 //! ```
+use open::resolve_range;
 use std::collections::VecDeque;
 use std::fmt::Debug;
 use std::iter::repeat_with;
 use std::iter::FromIterator;
 use std::mem::replace;
-use std::ops::Bound;
 use std::ops::Range;
 use std::ops::RangeBounds;
 
@@ -86,25 +86,29 @@ impl<O: Ops> DualSegtree<O> {
     }
 
     /// `range` に `x` を作用させます。（右作用）
+    ///
+    /// 作用は呼んだ順に合成されます。つまり、各要素 `y` は `op(op(y, x_1), x_2)` のように、先に呼んだ
+    /// 作用ほど内側になります。`op` が可換でなくても構いません。
+    ///
+    /// 区間の両端の祖先の遅延を先に伝播しておくので、`x` を置くノードの祖先には遅延がありません。
+    /// そのため、どのノードでも、遅延は祖先のものほど新しくなります。
     pub fn apply(&mut self, range: impl RangeBounds<usize>, x: &O::Value) {
-        let Range { mut start, mut end } = into_slice_range(self.len(), range);
-        if end < start {
-            dual_segtree_index_order_fail(start, end);
-        }
-        if self.len() < end {
-            dual_segtree_end_index_len_fail(end, self.len());
-        }
+        let Range { mut start, mut end } = resolve_range(self.len(), range);
         start += self.len();
         end += self.len();
         self.thrust(start);
         self.thrust(end);
         while start != end {
             if start % 2 == 1 {
+                #[cfg(any(test, feature = "count-ops"))]
+                op_counter::tick();
                 O::op_assign_from_right(&mut self.table[start], x.clone());
                 start += 1;
             }
             if end % 2 == 1 {
                 end -= 1;
+                #[cfg(any(test, feature = "count-ops"))]
+                op_counter::tick();
                 O::op_assign_from_right(&mut self.table[end], x.clone());
             }
             start /= 2;
@@ -143,16 +147,52 @@ impl<O: Ops> DualSegtree<O> {
         self.get_mut(i).clone()
     }
 
+    /// `i` 番目の要素を、遅延している作用を伝播せずに計算して返します。
+    ///
+    /// 葉の値に祖先の値を深いほうから順に右から作用させます。`&self` で呼べる代わりに、
+    /// 毎回 $O(\log n)$ 回の演算とクローンをします。
+    pub fn peek(&self, i: usize) -> O::Value
+    where
+        O::Value: Clone,
+    {
+        if self.len() <= i {
+            dual_segtree_index_out_of_range_fail(i, self.len())
+        }
+        let i = self.len() + i;
+        (1..=self.lg())
+            .map(|p| i >> p)
+            .take_while(|&j| j != 0)
+            .fold(self.table[i].clone(), |acc, j| {
+                #[cfg(any(test, feature = "count-ops"))]
+                op_counter::tick();
+                O::op(acc, self.table[j].clone())
+            })
+    }
+
+    /// すべての作用を伝播して、スライスとして返します。$O(n)$ です。
+    ///
+    /// 伝播したあとなので、二分探索などにそのまま使えます。
+    pub fn as_slice(&mut self) -> &[O::Value] {
+        update_all::<O>(&mut self.table);
+        let len = self.len();
+        &self.table[len..]
+    }
+
+    /// すべての作用を伝播して、先頭から順に参照を返すイテレータを作ります。
+    pub fn iter(&mut self) -> std::slice::Iter<'_, O::Value> {
+        self.as_slice().iter()
+    }
+
     /// [`Vec`] に変換します。
     pub fn collect_vec(&mut self) -> Vec<O::Value> {
-        update_all::<O>(&mut self.table);
-        self.table[self.len()..].to_vec()
+        self.as_slice().to_vec()
     }
 
     /// [`Vec`] に変換します。
     pub fn into_vec(mut self) -> Vec<O::Value> {
         update_all::<O>(&mut self.table);
-        self.table[self.len()..].to_vec()
+        let len = self.len();
+        self.table.split_off(len)
     }
 
     fn lg(&self) -> u32 {
@@ -167,6 +207,8 @@ impl<O: Ops> DualSegtree<O> {
     }
 
     fn push(&mut self, i: usize) {
+        #[cfg(any(test, feature = "count-ops"))]
+        op_counter::tick();
         let x = replace(&mut self.table[i], O::identity());
         self.table[2 * i..2 * i + 2]
             .iter_mut()
@@ -194,47 +236,10 @@ impl<O: Ops> Debug for DualSegtree<O> {
     }
 }
 ////////////////////////////////////////////////////////////////////////////////
-// プライベート - RangeBounds 関連
+// プライベート - パニック
 ////////////////////////////////////////////////////////////////////////////////
-#[allow(clippy::redundant_closure)]
-fn into_slice_range(len: usize, range: impl RangeBounds<usize>) -> Range<usize> {
-    let start = match range.start_bound() {
-        Bound::Included(&start) => start,
-        Bound::Excluded(&start) => start
-            .checked_add(1)
-            .unwrap_or_else(|| slice_start_index_overflow_fail()),
-        Bound::Unbounded => 0,
-    };
-    let end = match range.end_bound() {
-        Bound::Included(&end) => end
-            .checked_add(1)
-            .unwrap_or_else(|| slice_end_index_overflow_fail()),
-        Bound::Excluded(&end) => end,
-        Bound::Unbounded => len,
-    };
-    start..end
-}
-
 fn dual_segtree_index_out_of_range_fail(index: usize, len: usize) -> ! {
-    panic!(
-        "index {} out of range for dual segtree of length {}",
-        index, len
-    );
-}
-fn dual_segtree_end_index_len_fail(index: usize, len: usize) -> ! {
-    panic!(
-        "range end index {} out of range for dual segtree of length {}",
-        index, len
-    );
-}
-fn dual_segtree_index_order_fail(start: usize, end: usize) -> ! {
-    panic!("dual segtree index starts at {} but ends at {}", start, end);
-}
-fn slice_start_index_overflow_fail() -> ! {
-    panic!("attempted to index slice from after maximum usize");
-}
-fn slice_end_index_overflow_fail() -> ! {
-    panic!("attempted to index slice up to maximum usize");
+    panic!("index {} out of range for length {}", index, len);
 }
 
 ////////////////////////////////////////////////////////////////////////////////
@@ -253,6 +258,14 @@ impl<O: Ops> FromIterator<O::Value> for DualSegtree<O> {
         }
     }
 }
+impl<O: Ops> IntoIterator for DualSegtree<O> {
+    type IntoIter = std::vec::IntoIter<O::Value>;
+    type Item = O::Value;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.into_vec().into_iter()
+    }
+}
 
 #[cfg(test)]
 mod tests {
@@ -261,6 +274,7 @@ mod tests {
     use rand::prelude::StdRng;
     use rand::Rng;
     use rand::SeedableRng;
+    use randtools::BiasedSubRange;
     use std::iter::repeat_with;
     use std::mem::swap;
     use std::ops::Range;
@@ -277,9 +291,10 @@ mod tests {
         }
 
         pub fn apply(&mut self, range: Range<usize>, x: &O::Value) {
-            self.table[range]
-                .iter_mut()
-                .for_each(|y| O::op_assign_from_right(y, x.clone()));
+            self.table[range].iter_mut().for_each(|y| {
+                op_counter::tick();
+                O::op_assign_from_right(y, x.clone())
+            });
         }
 
         pub fn get_cloned(&self, i: usize) -> O::Value
@@ -316,18 +331,14 @@ mod tests {
             for _ in 0..20 {
                 match rng.gen_range(0..3) {
                     0 | 1 => {
-                        let mut l = rng.gen_range(0..n);
-                        let mut r = rng.gen_range(0..n);
-                        if l > r {
-                            swap(&mut l, &mut r);
-                            r += 1;
-                        }
+                        let range = rng.sample(BiasedSubRange(0..n));
                         let x = new_value(&mut rng);
-                        seg.apply(l..r, &x);
-                        brute.apply(l..r, &x);
+                        seg.apply(range.clone(), &x);
+                        brute.apply(range, &x);
                     }
                     2 => {
                         let i = rng.gen_range(0..n);
+                        assert_eq!(seg.peek(i), brute.get_cloned(i));
                         let result = seg.get_cloned(i);
                         let expected = brute.get_cloned(i);
                         assert_eq!(result, expected);
@@ -336,6 +347,253 @@ mod tests {
                 }
                 assert_eq!(seg.silent_collect(), brute.table);
             }
+            assert_eq!(seg.iter().cloned().collect::<Vec<_>>(), brute.table);
+            assert_eq!(seg.as_slice(), brute.table.as_slice());
+            assert_eq!(seg.into_iter().collect::<Vec<_>>(), brute.table);
+        }
+    }
+
+    enum AddOps {}
+    impl Ops for AddOps {
+        type Value = i64;
+
+        fn op(lhs: i64, rhs: i64) -> i64 {
+            lhs + rhs
+        }
+
+        fn identity() -> i64 {
+            0
+        }
+    }
+
+    // 長さ n の配列に n 回ランダムな区間作用をしたときの演算回数を数えます。
+    fn count_apply(n: usize, mut apply: impl FnMut(Range<usize>, &i64)) -> u64 {
+        let mut rng = StdRng::seed_from_u64(42);
+        let counter = op_counter::OpCounter::start();
+        for _ in 0..n {
+            let mut l = rng.gen_range(0..=n);
+            let mut r = rng.gen_range(0..=n);
+            if l > r {
+                swap(&mut l, &mut r);
+            }
+            apply(l..r, &rng.gen_range(-10..=10));
+        }
+        counter.get()
+    }
+
+    #[test]
+    fn test_apply_complexity() {
+        let ns = [1 << 10, 1 << 11, 1 << 12, 1 << 13];
+        let counts = ns
+            .iter()
+            .map(|&n| {
+                let mut seg = DualSegtree::<AddOps>::new(vec![0; n]);
+                count_apply(n, |range, x| seg.apply(range, x))
+            })
+            .collect::<Vec<_>>();
+        // O(n log n)
+        op_counter::assert_complexity(&ns, &counts, 1.3);
+    }
+
+    #[test]
+    #[should_panic(expected = "exceeds the bound")]
+    fn test_apply_complexity_canary() {
+        let ns = [1 << 10, 1 << 11, 1 << 12, 1 << 13];
+        let counts = ns
+            .iter()
+            .map(|&n| {
+                let mut brute = Brute::<AddOps>::new(vec![0; n]);
+                count_apply(n, |range, x| brute.apply(range, x))
+            })
+            .collect::<Vec<_>>();
+        op_counter::assert_complexity(&ns, &counts, 1.3);
+    }
+
+    #[test]
+    fn test_no_overhead() {
+        // 数える機能があってもなくても、配列だけを持ちます。
+        assert_eq!(
+            std::mem::size_of::<DualSegtree<AddOps>>(),
+            std::mem::size_of::<Vec<i64>>()
+        );
+    }
+
+    #[test]
+    #[should_panic(expected = "range starts at 3 but ends at 2")]
+    fn test_apply_reversed_range() {
+        let (start, end) = (3, 2);
+        DualSegtree::<AddOps>::new(vec![0; 5]).apply(start..end, &1);
+    }
+
+    #[test]
+    #[should_panic(expected = "range end index 6 out of range for length 5")]
+    fn test_apply_out_of_range() {
+        DualSegtree::<AddOps>::new(vec![0; 5]).apply(..=5, &1);
+    }
+
+    #[test]
+    #[should_panic(expected = "attempted to index up to maximum usize")]
+    fn test_apply_included_usize_max() {
+        DualSegtree::<AddOps>::new(vec![0; 5]).apply(0..=usize::MAX, &1);
+    }
+
+    #[test]
+    fn test_peek() {
+        // 伝播せずに読むので、途中で状態が変わりません。
+        let mut rng = StdRng::seed_from_u64(42);
+        for _ in 0..200 {
+            let n = rng.gen_range(1..=50);
+            let vec = repeat_with(|| rng.gen_range(-10..=10))
+                .take(n)
+                .collect::<Vec<i64>>();
+            let mut seg = DualSegtree::<AddOps>::new(vec.iter().copied());
+            let mut brute = Brute::<AddOps>::new(vec.iter().copied());
+            for _ in 0..20 {
+                let range = rng.sample(BiasedSubRange(0..n));
+                let x = rng.gen_range(-10..=10);
+                seg.apply(range.clone(), &x);
+                brute.apply(range, &x);
+                let before = seg.table.clone();
+                let result = (0..n).map(|i| seg.peek(i)).collect::<Vec<_>>();
+                assert_eq!(result, brute.table);
+                assert_eq!(seg.table, before);
+            }
+        }
+    }
+
+    #[test]
+    fn test_peek_complexity() {
+        let ns = [1 << 10, 1 << 11, 1 << 12, 1 << 13];
+        let counts = ns
+            .iter()
+            .map(|&n| {
+                let seg = DualSegtree::<AddOps>::new(vec![0; n]);
+                let counter = op_counter::OpCounter::start();
+                (0..n).for_each(|i| {
+                    seg.peek(i);
+                });
+                counter.get()
+            })
+            .collect::<Vec<_>>();
+        // O(n log n)
+        op_counter::assert_complexity(&ns, &counts, 1.3);
+    }
+
+    #[test]
+    #[should_panic(expected = "index 5 out of range for length 5")]
+    fn test_peek_out_of_range() {
+        DualSegtree::<AddOps>::new(vec![0; 5]).peek(5);
+    }
+
+    #[test]
+    #[should_panic(expected = "index 5 out of range for length 5")]
+    fn test_get_out_of_range() {
+        DualSegtree::<AddOps>::new(vec![0; 5]).get(5);
+    }
+
+    #[test]
+    fn test_as_slice_binary_search() {
+        // 単調増加な配列に区間加算をしても単調なので、二分探索できます。
+        let mut seg = DualSegtree::<AddOps>::new(vec![0, 1, 2, 3, 4, 5, 6, 7]);
+        seg.apply(3.., &10);
+        seg.apply(5.., &10);
+        assert_eq!(seg.as_slice(), &[0, 1, 2, 13, 14, 25, 26, 27]);
+        assert_eq!(seg.as_slice().partition_point(|&x| x < 20), 5);
+        // 伝播したあとも作用できます。
+        seg.apply(..2, &-1);
+        assert_eq!(seg.iter().copied().collect::<Vec<_>>(), vec![
+            -1, 0, 2, 13, 14, 25, 26, 27
+        ]);
+        assert_eq!(seg.into_iter().sum::<i64>(), 106);
+    }
+
+    #[test]
+    fn test_empty_iter() {
+        let mut seg = DualSegtree::<AddOps>::new(vec![]);
+        assert!(seg.as_slice().is_empty());
+        assert_eq!(seg.iter().count(), 0);
+        assert_eq!(seg.into_iter().count(), 0);
+    }
+
+    // 2x2 行列の積は可換でなく、どの順に合成したかが結果に現れます。
+    const Q: u32 = 7;
+    enum MatrixOps {}
+    impl Ops for MatrixOps {
+        type Value = [[u32; 2]; 2];
+
+        fn op(lhs: Self::Value, rhs: Self::Value) -> Self::Value {
+            let mut result = [[0; 2]; 2];
+            for (i, row) in result.iter_mut().enumerate() {
+                for (j, x) in row.iter_mut().enumerate() {
+                    *x = (lhs[i][0] * rhs[0][j] + lhs[i][1] * rhs[1][j]) % Q;
+                }
+            }
+            result
+        }
+
+        fn identity() -> Self::Value {
+            [[1, 0], [0, 1]]
+        }
+    }
+
+    fn random_matrix(rng: &mut StdRng) -> [[u32; 2]; 2] {
+        [[rng.gen_range(0..Q), rng.gen_range(0..Q)], [
+            rng.gen_range(0..Q),
+            rng.gen_range(0..Q),
+        ]]
+    }
+
+    #[test]
+    fn test_matrix_random() {
+        let mut rng = StdRng::seed_from_u64(42);
+        for _ in 0..300 {
+            let n = rng.gen_range(1..=40);
+            let vec = repeat_with(|| random_matrix(&mut rng))
+                .take(n)
+                .collect::<Vec<_>>();
+            let mut seg = DualSegtree::<MatrixOps>::new(vec.iter().copied());
+            let mut brute = Brute::<MatrixOps>::new(vec.iter().copied());
+            for _ in 0..40 {
+                let range = rng.sample(BiasedSubRange(0..n));
+                let x = random_matrix(&mut rng);
+                seg.apply(range.clone(), &x);
+                brute.apply(range, &x);
+                if rng.gen_ratio(1, 4) {
+                    let i = rng.gen_range(0..n);
+                    assert_eq!(seg.get_copied(i), brute.get_cloned(i));
+                }
+                assert_eq!(seg.silent_collect(), brute.table);
+            }
+        }
+    }
+
+    #[test]
+    fn test_matrix_exhaustive() {
+        // 長さ 6 以下で、3 回の区間作用のすべての組み合わせを試します。
+        let mut rng = StdRng::seed_from_u64(42);
+        for n in 1..=6 {
+            let ranges = (0..=n)
+                .flat_map(|l| (l..=n).map(move |r| l..r))
+                .collect::<Vec<_>>();
+            for a in &ranges {
+                for b in &ranges {
+                    for c in &ranges {
+                        let vec = repeat_with(|| random_matrix(&mut rng))
+                            .take(n)
+                            .collect::<Vec<_>>();
+                        let mut seg = DualSegtree::<MatrixOps>::new(vec.iter().copied());
+                        let mut brute = Brute::<MatrixOps>::new(vec.iter().copied());
+                        for range in [a, b, c] {
+                            let x = random_matrix(&mut rng);
+                            seg.apply(range.clone(), &x);
+                            brute.apply(range.clone(), &x);
+                        }
+                        let result = (0..n).map(|i| seg.peek(i)).collect::<Vec<_>>();
+                        assert_eq!(result, brute.table);
+                        assert_eq!(seg.collect_vec(), brute.table);
+                    }
+                }
+            }
         }
     }
 }
@@ -0,0 +1,376 @@
+//! Eertree (palindromic tree) with series links, and palindromic factorizations.
+//!
+//! Each node of [`Eertree`] is a distinct palindromic substring, and its suffix link is the
+//! longest proper palindromic suffix. The lengths of the palindromic suffixes of a palindrome form
+//! $O(\log n)$ arithmetic progressions, and the series link jumps to the first palindromic suffix
+//! of the next progression.
+//!
+//! # Factorizations
+//!
+//! [`min_palindromic_factorization`] and [`count_palindromic_factorizations`] run the DP over the
+//! palindromic suffixes of each prefix in $O(n \log n)$ time by aggregating each progression at
+//! once.
+//!
+//! # Examples
+//!
+//! ```
+//! use eertree::min_palindromic_factorization;
+//! use eertree::Eertree;
+//! let mut eertree = Eertree::new();
+//! for c in "abacaba".bytes() {
+//!     eertree.push(c);
+//! }
+//! // a, b, c, aba, aca, bacab, abacaba
+//! assert_eq!(eertree.count_distinct(), 7);
+//! assert_eq!(eertree.palindrome_len(eertree.last()), 7);
+//!
+//! // abaab = a + baab
+//! assert_eq!(min_palindromic_factorization(b"abaab"), [0, 1, 2, 1, 2, 2]);
+//! ```
+
+use fp::Fp;
+use std::collections::HashMap;
+use std::hash::Hash;
+
+/// The node of the imaginary palindrome of length $-1$.
+pub const IMAGINARY: usize = 0;
+/// The node of the empty palindrome.
+pub const EMPTY: usize = 1;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+struct Node {
+    len: isize,
+    link: usize,
+    diff: usize,
+    series_link: usize,
+}
+
+/// An eertree of a string, which is extended to the right one letter at a time.
+///
+/// The nodes are numbered in the order of creation, where [`IMAGINARY`] and [`EMPTY`] are the two
+/// roots.
+#[derive(Debug, Clone)]
+pub struct Eertree<T> {
+    s: Vec<T>,
+    nodes: Vec<Node>,
+    children: HashMap<(usize, T), usize>,
+    last: usize,
+}
+impl<T: Copy + Eq + Hash> Eertree<T> {
+    /// Constructs the eertree of the empty string.
+    pub fn new() -> Self {
+        let root = |len| Node {
+            len,
+            link: IMAGINARY,
+            diff: 0,
+            series_link: IMAGINARY,
+        };
+        Self {
+            s: Vec::new(),
+            nodes: vec![root(-1), root(0)],
+            children: HashMap::new(),
+            last: EMPTY,
+        }
+    }
+
+    /// Appends a letter, and returns the node of the longest palindromic suffix.
+    ///
+    /// # Complexity
+    ///
+    /// Amortized $O(1)$ hash map operations.
+    pub fn push(&mut self, c: T) -> usize {
+        let i = self.s.len();
+        self.s.push(c);
+        let v = self.extendable(self.last, i);
+        if let Some(&u) = self.children.get(&(v, c)) {
+            self.last = u;
+            return u;
+        }
+        let len = self.nodes[v].len + 2;
+        let link = if len == 1 {
+            EMPTY
+        } else {
+            let w = self.extendable(self.nodes[v].link, i);
+            self.children[&(w, c)]
+        };
+        let diff = (len - self.nodes[link].len) as usize;
+        let series_link =
+            if diff == self.nodes[link].diff { self.nodes[link].series_link } else { link };
+        let u = self.nodes.len();
+        self.nodes.push(Node {
+            len,
+            link,
+            diff,
+            series_link,
+        });
+        self.children.insert((v, c), u);
+        self.last = u;
+        u
+    }
+
+    /// Returns the node of the longest palindromic suffix of the whole string.
+    pub fn last(&self) -> usize {
+        self.last
+    }
+
+    /// Returns the number of the distinct nonempty palindromic substrings.
+    pub fn count_distinct(&self) -> usize {
+        self.nodes.len() - 2
+    }
+
+    /// Returns the length of the palindrome of `v`, which is zero for both the roots.
+    pub fn palindrome_len(&self, v: usize) -> usize {
+        self.nodes[v].len.max(0) as usize
+    }
+
+    /// Returns the node of the longest proper palindromic suffix of `v`.
+    pub fn suffix_link(&self, v: usize) -> usize {
+        self.nodes[v].link
+    }
+
+    /// Returns the difference of the lengths of `v` and its suffix link.
+    pub fn diff(&self, v: usize) -> usize {
+        self.nodes[v].diff
+    }
+
+    /// Returns the node of the longest palindromic suffix of `v` whose `diff` differs from that of
+    /// `v`.
+    pub fn series_link(&self, v: usize) -> usize {
+        self.nodes[v].series_link
+    }
+
+    /// Returns the child of `v` with the letter `c` on both sides.
+    pub fn child(&self, v: usize, c: T) -> Option<usize> {
+        self.children.get(&(v, c)).copied()
+    }
+
+    // The longest palindromic suffix in the chain of `v` that `s[i]` extends on both sides.
+    fn extendable(&self, mut v: usize, i: usize) -> usize {
+        loop {
+            let len = self.nodes[v].len;
+            // The imaginary root always stops here.
+            if len < i as isize && self.s[i - (len + 1) as usize] == self.s[i] {
+                return v;
+            }
+            v = self.nodes[v].link;
+        }
+    }
+}
+
+impl<T: Copy + Eq + Hash> Default for Eertree<T> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Returns the minimum number of the palindromes to partition each prefix of `s`.
+///
+/// The $i$-th element is for the prefix of length $i$.
+///
+/// # Complexity
+///
+/// $O(n \log n)$
+///
+/// # Examples
+///
+/// ```
+/// use eertree::min_palindromic_factorization;
+/// assert_eq!(min_palindromic_factorization(b"abcb"), [0, 1, 2, 3, 2]);
+/// ```
+pub fn min_palindromic_factorization<T: Copy + Eq + Hash>(s: &[T]) -> Vec<usize> {
+    factorize(s, 0, usize::MAX, usize::min, |x| x + 1)
+}
+
+/// Returns the number of the partitions of each prefix of `s` into palindromes.
+///
+/// The $i$-th element is for the prefix of length $i$.
+///
+/// # Complexity
+///
+/// $O(n \log n)$
+///
+/// # Examples
+///
+/// ```
+/// use eertree::count_palindromic_factorizations;
+/// use fp::fp;
+/// // aaa = a + a + a = aa + a = a + aa = aaa
+/// assert_eq!(count_palindromic_factorizations::<_, 998244353>(b"aaa"), [
+///     fp!(1),
+///     fp!(1),
+///     fp!(2),
+///     fp!(4)
+/// ]);
+/// ```
+pub fn count_palindromic_factorizations<T: Copy + Eq + Hash, const P: u64>(s: &[T]) -> Vec<Fp<P>> {
+    factorize(s, Fp::new(1), Fp::new(0), |x, y| x + y, |x| x)
+}
+
+// `dp[i]` is the aggregate of `extend(dp[j])` over the palindromes `s[j..i]`.
+//
+// `series[v]` is the aggregate of `dp[i - len]` over the lengths `len` of the progression of `v`,
+// that is, the palindromic suffixes from `v` to right before `series_link(v)`. When `v` and its
+// suffix link `u` are in the same progression, `series[u]` was last updated at `i - diff(v)`, and
+// it covers the same starting points except that of the shortest palindrome, so only that one is
+// added.
+fn factorize<T: Copy + Eq + Hash, V: Copy>(
+    s: &[T],
+    empty: V,
+    none: V,
+    combine: impl Fn(V, V) -> V,
+    extend: impl Fn(V) -> V,
+) -> Vec<V> {
+    let mut eertree = Eertree::new();
+    let mut dp = vec![none; s.len() + 1];
+    dp[0] = empty;
+    let mut series = vec![none; 2];
+    for (i, &c) in s.iter().enumerate() {
+        let i = i + 1;
+        let mut v = eertree.push(c);
+        series.resize(eertree.nodes.len(), none);
+        while eertree.palindrome_len(v) > 0 {
+            let Node {
+                link,
+                diff,
+                series_link,
+                ..
+            } = eertree.nodes[v];
+            series[v] = dp[i - (eertree.palindrome_len(series_link) + diff)];
+            if diff == eertree.nodes[link].diff {
+                series[v] = combine(series[v], series[link]);
+            }
+            dp[i] = combine(dp[i], extend(series[v]));
+            v = series_link;
+        }
+    }
+    dp
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rand::rngs::StdRng;
+    use rand::Rng;
+    use rand::SeedableRng;
+    use std::collections::HashSet;
+
+    const P: u64 = 998244353;
+
+    fn is_palindrome(s: &[u8]) -> bool {
+        s.iter().eq(s.iter().rev())
+    }
+
+    fn random_strings(rng: &mut StdRng) -> Vec<Vec<u8>> {
+        let mut strings = vec![b"".to_vec(), b"a".to_vec(), b"abcabcabcabc".to_vec()];
+        strings.push(vec![b'a'; 300]);
+        for _ in 0..100 {
+            let n = rng.gen_range(0..=300);
+            let sigma: u8 = rng.gen_range(1..=3);
+            strings.push((0..n).map(|_| b'a' + rng.gen_range(0..sigma)).collect());
+        }
+        strings
+    }
+
+    #[test]
+    fn test_factorizations() {
+        let mut rng = StdRng::seed_from_u64(42);
+        for s in random_strings(&mut rng) {
+            let n = s.len();
+            let mut min = vec![usize::MAX; n + 1];
+            let mut count = vec![Fp::<P>::new(0); n + 1];
+            min[0] = 0;
+            count[0] = Fp::new(1);
+            for i in 1..=n {
+                for j in 0..i {
+                    if is_palindrome(&s[j..i]) {
+                        min[i] = min[i].min(min[j] + 1);
+                        count[i] = count[i] + count[j];
+                    }
+                }
+            }
+            assert_eq!(min_palindromic_factorization(&s), min);
+            assert_eq!(count_palindromic_factorizations(&s), count);
+        }
+    }
+
+    #[test]
+    fn test_no_long_palindromes() {
+        let s = b"abcabcabcabc";
+        assert_eq!(
+            min_palindromic_factorization(s),
+            (0..=s.len()).collect::<Vec<_>>()
+        );
+        assert_eq!(count_palindromic_factorizations::<_, P>(s), vec![
+            Fp::new(1);
+            s.len() + 1
+        ]);
+    }
+
+    #[test]
+    fn test_eertree() {
+        let mut rng = StdRng::seed_from_u64(42);
+        for s in random_strings(&mut rng) {
+            let mut eertree = Eertree::new();
+            let mut distinct = HashSet::new();
+            for i in 1..=s.len() {
+                let v = eertree.push(s[i - 1]);
+                assert_eq!(v, eertree.last());
+                distinct.extend((0..i).map(|j| &s[j..i]).filter(|t| is_palindrome(t)));
+                assert_eq!(eertree.count_distinct(), distinct.len());
+
+                // The palindromic suffixes in the decreasing order of lengths
+                let suffixes = (0..=i)
+                    .filter(|&j| is_palindrome(&s[j..i]))
+                    .map(|j| i - j)
+                    .collect::<Vec<_>>();
+                let mut chain = Vec::new();
+                let mut u = v;
+                while u != IMAGINARY {
+                    chain.push(eertree.palindrome_len(u));
+                    let link = eertree.suffix_link(u);
+                    if u != EMPTY {
+                        assert_eq!(
+                            eertree.diff(u),
+                            eertree.palindrome_len(u) - eertree.palindrome_len(link)
+                        );
+                    }
+                    u = link;
+                }
+                assert_eq!(chain, suffixes);
+
+                // The series link skips exactly the suffixes of the same difference.
+                let mut u = v;
+                while u != EMPTY {
+                    let mut w = u;
+                    while eertree.diff(eertree.suffix_link(w)) == eertree.diff(u) {
+                        w = eertree.suffix_link(w);
+                    }
+                    assert_eq!(eertree.series_link(u), eertree.suffix_link(w));
+                    u = eertree.series_link(u);
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn test_series_count() {
+        // The number of the progressions visited is $O(\log n)$.
+        for s in [
+            vec![b'a'; 1 << 12],
+            (0..1 << 12)
+                .map(|i| b'a' + (i as u32).trailing_zeros() as u8)
+                .collect(),
+        ] {
+            let mut eertree = Eertree::new();
+            for &c in &s {
+                let mut v = eertree.push(c);
+                let mut progressions = 0;
+                while eertree.palindrome_len(v) > 0 {
+                    progressions += 1;
+                    v = eertree.series_link(v);
+                }
+                assert!(progressions <= 2 * 13);
+            }
+        }
+    }
+}
@@ -0,0 +1,463 @@
+//! Fenwick trees (binary indexed trees) over a commutative group.
+//!
+//! - [`Fenwick`]: point additions and range sums
+//! - [`DualFenwick`]: range additions and point queries, by the difference array
+//! - [`RangeFenwick`]: range additions and range sums, by two Fenwick trees
+//!
+//! The value type needs `+`, `-` and `From<u32>`, which are enough for `i64` and `Fp`. The zero is
+//! `T::from(0)`, and [`RangeFenwick`] also needs `*` to multiply by an index.
+//!
+//! # Examples
+//!
+//! ```
+//! use fenwick::RangeFenwick;
+//! let mut fenwick = RangeFenwick::<i64>::from_slice(&[3, 1, 4, 1, 5]);
+//! fenwick.add(1..4, 10);
+//! assert_eq!(fenwick.sum(..), 44);
+//! assert_eq!(fenwick.sum(3..), 16);
+//! ```
+use open::resolve_range;
+use std::ops::Add;
+use std::ops::Mul;
+use std::ops::Range;
+use std::ops::RangeBounds;
+use std::ops::Sub;
+
+/// A Fenwick tree for point additions and range sums.
+///
+/// # Examples
+///
+/// ```
+/// use fenwick::Fenwick;
+/// let mut fenwick = Fenwick::<i64>::new(5);
+/// fenwick.add(1, 3);
+/// fenwick.add(3, 4);
+/// assert_eq!(fenwick.sum(..), 7);
+/// assert_eq!(fenwick.sum(2..), 4);
+/// assert_eq!(fenwick.prefix_sum(2), 3);
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct Fenwick<T> {
+    table: Vec<T>,
+}
+impl<T> Fenwick<T>
+where
+    T: Copy + Add<Output = T> + Sub<Output = T> + From<u32>,
+{
+    /// Constructs a tree of `n` zeros.
+    pub fn new(n: usize) -> Self {
+        Self {
+            table: vec![T::from(0); n],
+        }
+    }
+
+    /// Constructs a tree from a slice in $O(n)$.
+    pub fn from_slice(values: &[T]) -> Self {
+        let mut table = values.to_vec();
+        for i in 0..table.len() {
+            let j = i | (i + 1);
+            if j < table.len() {
+                table[j] = table[j] + table[i];
+            }
+        }
+        Self { table }
+    }
+
+    /// Returns the number of the elements.
+    pub fn len(&self) -> usize {
+        self.table.len()
+    }
+
+    /// Returns `true` if there are no elements.
+    pub fn is_empty(&self) -> bool {
+        self.table.is_empty()
+    }
+
+    /// Adds `x` to the `i`-th element.
+    ///
+    /// # Panics
+    ///
+    /// If `i` is out of range.
+    pub fn add(&mut self, mut i: usize, x: T) {
+        if self.len() <= i {
+            fenwick_index_fail(i, self.len());
+        }
+        while i < self.len() {
+            self.table[i] = self.table[i] + x;
+            i |= i + 1;
+        }
+    }
+
+    /// Returns the sum of the first `r` elements.
+    ///
+    /// # Panics
+    ///
+    /// If $r > n$.
+    pub fn prefix_sum(&self, mut r: usize) -> T {
+        if self.len() < r {
+            fenwick_end_fail(r, self.len());
+        }
+        let mut sum = T::from(0);
+        while r > 0 {
+            sum = sum + self.table[r - 1];
+            r &= r - 1;
+        }
+        sum
+    }
+
+    /// Returns the sum of the elements in `range`.
+    pub fn sum(&self, range: impl RangeBounds<usize>) -> T {
+        let Range { start, end } = resolve_range(self.len(), range);
+        self.prefix_sum(end) - self.prefix_sum(start)
+    }
+}
+
+/// A Fenwick tree for range additions and point queries.
+///
+/// It stores the differences of the adjacent elements.
+///
+/// # Examples
+///
+/// ```
+/// use fenwick::DualFenwick;
+/// let mut fenwick = DualFenwick::<i64>::new(5);
+/// fenwick.add(1..4, 3);
+/// fenwick.add(3.., 4);
+/// assert_eq!(fenwick.collect_vec(), vec![0, 3, 3, 7, 4]);
+/// assert_eq!(fenwick.get(3), 7);
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct DualFenwick<T> {
+    diff: Fenwick<T>,
+}
+impl<T> DualFenwick<T>
+where
+    T: Copy + Add<Output = T> + Sub<Output = T> + From<u32>,
+{
+    /// Constructs a tree of `n` zeros.
+    pub fn new(n: usize) -> Self {
+        Self {
+            diff: Fenwick::new(n),
+        }
+    }
+
+    /// Constructs a tree from a slice in $O(n)$.
+    pub fn from_slice(values: &[T]) -> Self {
+        Self {
+            diff: Fenwick::from_slice(&differences(values)),
+        }
+    }
+
+    /// Returns the number of the elements.
+    pub fn len(&self) -> usize {
+        self.diff.len()
+    }
+
+    /// Returns `true` if there are no elements.
+    pub fn is_empty(&self) -> bool {
+        self.diff.is_empty()
+    }
+
+    /// Adds `x` to every element in `range`.
+    pub fn add(&mut self, range: impl RangeBounds<usize>, x: T) {
+        let Range { start, end } = resolve_range(self.len(), range);
+        if start < end {
+            self.diff.add(start, x);
+            if end < self.len() {
+                self.diff.add(end, T::from(0) - x);
+            }
+        }
+    }
+
+    /// Returns the `i`-th element.
+    ///
+    /// # Panics
+    ///
+    /// If `i` is out of range.
+    pub fn get(&self, i: usize) -> T {
+        if self.len() <= i {
+            fenwick_index_fail(i, self.len());
+        }
+        self.diff.prefix_sum(i + 1)
+    }
+
+    /// Returns all the elements in $O(n)$.
+    pub fn collect_vec(&self) -> Vec<T> {
+        // The inverse of `from_slice`.
+        let mut values = self.diff.table.clone();
+        for i in (0..values.len()).rev() {
+            let j = i | (i + 1);
+            if j < values.len() {
+                values[j] = values[j] - values[i];
+            }
+        }
+        let mut sum = T::from(0);
+        for x in &mut values {
+            sum = sum + *x;
+            *x = sum;
+        }
+        values
+    }
+}
+
+/// A pair of Fenwick trees for range additions and range sums.
+///
+/// Let $d _ k$ be the differences of the adjacent elements. Then the sum of the first $i$ elements
+/// is
+///
+/// $$
+/// \sum _ {k < i} (i - k) d _ k = i \sum _ {k < i} d _ k - \sum _ {k < i} k d _ k,
+/// $$
+///
+/// so it keeps $d _ k$ and $k d _ k$ in two trees.
+///
+/// # Examples
+///
+/// ```
+/// use fenwick::RangeFenwick;
+/// let mut fenwick = RangeFenwick::<i64>::new(5);
+/// fenwick.add(1..4, 3);
+/// fenwick.add(3.., 4);
+/// // [0, 3, 3, 7, 4]
+/// assert_eq!(fenwick.sum(..), 17);
+/// assert_eq!(fenwick.sum(2..4), 10);
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct RangeFenwick<T> {
+    diff: Fenwick<T>,
+    weighted: Fenwick<T>,
+}
+impl<T> RangeFenwick<T>
+where
+    T: Copy + Add<Output = T> + Sub<Output = T> + Mul<Output = T> + From<u32>,
+{
+    /// Constructs a tree of `n` zeros.
+    ///
+    /// # Panics
+    ///
+    /// If $n \ge 2 ^ {32}$.
+    pub fn new(n: usize) -> Self {
+        assert_index_fits(n);
+        Self {
+            diff: Fenwick::new(n),
+            weighted: Fenwick::new(n),
+        }
+    }
+
+    /// Constructs a tree from a slice in $O(n)$.
+    ///
+    /// # Panics
+    ///
+    /// If $n \ge 2 ^ {32}$.
+    pub fn from_slice(values: &[T]) -> Self {
+        assert_index_fits(values.len());
+        let diff = differences(values);
+        let weighted = diff
+            .iter()
+            .enumerate()
+            .map(|(k, &d)| T::from(k as u32) * d)
+            .collect::<Vec<_>>();
+        Self {
+            diff: Fenwick::from_slice(&diff),
+            weighted: Fenwick::from_slice(&weighted),
+        }
+    }
+
+    /// Returns the number of the elements.
+    pub fn len(&self) -> usize {
+        self.diff.len()
+    }
+
+    /// Returns `true` if there are no elements.
+    pub fn is_empty(&self) -> bool {
+        self.diff.is_empty()
+    }
+
+    /// Adds `x` to every element in `range`.
+    pub fn add(&mut self, range: impl RangeBounds<usize>, x: T) {
+        let Range { start, end } = resolve_range(self.len(), range);
+        if start < end {
+            self.diff.add(start, x);
+            self.weighted.add(start, T::from(start as u32) * x);
+            // The differences at $n$ never appear in the prefix sums.
+            if end < self.len() {
+                self.diff.add(end, T::from(0) - x);
+                self.weighted.add(end, T::from(0) - T::from(end as u32) * x);
+            }
+        }
+    }
+
+    /// Returns the sum of the first `r` elements.
+    ///
+    /// # Panics
+    ///
+    /// If $r > n$.
+    pub fn prefix_sum(&self, r: usize) -> T {
+        T::from(r as u32) * self.diff.prefix_sum(r) - self.weighted.prefix_sum(r)
+    }
+
+    /// Returns the sum of the elements in `range`.
+    pub fn sum(&self, range: impl RangeBounds<usize>) -> T {
+        let Range { start, end } = resolve_range(self.len(), range);
+        self.prefix_sum(end) - self.prefix_sum(start)
+    }
+}
+
+fn differences<T: Copy + Sub<Output = T> + From<u32>>(values: &[T]) -> Vec<T> {
+    let mut last = T::from(0);
+    values
+        .iter()
+        .map(|&x| {
+            let d = x - last;
+            last = x;
+            d
+        })
+        .collect()
+}
+
+fn assert_index_fits(n: usize) {
+    assert!(
+        (n as u64) < 1 << 32,
+        "length {} is too large for indices of u32",
+        n
+    );
+}
+
+#[cold]
+#[inline(never)]
+fn fenwick_index_fail(index: usize, len: usize) -> ! {
+    panic!("index {} out of range for length {}", index, len);
+}
+#[cold]
+#[inline(never)]
+fn fenwick_end_fail(end: usize, len: usize) -> ! {
+    panic!("range end index {} out of range for length {}", end, len);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rand::rngs::StdRng;
+    use rand::Rng;
+    use rand::SeedableRng;
+    use randtools::BiasedSubRange;
+
+    type Fp = fp::Fp<998244353>;
+
+    fn test_base<T>(mut gen: impl FnMut(&mut StdRng) -> T)
+    where
+        T: Copy
+            + Add<Output = T>
+            + Sub<Output = T>
+            + Mul<Output = T>
+            + From<u32>
+            + PartialEq
+            + std::fmt::Debug,
+    {
+        let sum = |a: &[T]| a.iter().fold(T::from(0), |acc, &x| acc + x);
+        let mut rng = StdRng::seed_from_u64(42);
+        for _ in 0..200 {
+            let n = rng.gen_range(0..=40);
+            let mut vec = (0..n).map(|_| gen(&mut rng)).collect::<Vec<_>>();
+            let mut fenwick = Fenwick::from_slice(&vec);
+            let mut dual = DualFenwick::from_slice(&vec);
+            let mut range = RangeFenwick::from_slice(&vec);
+            for _ in 0..100 {
+                match rng.gen_range(0..4) {
+                    0 if n != 0 => {
+                        let i = rng.gen_range(0..n);
+                        let x = gen(&mut rng);
+                        vec[i] = vec[i] + x;
+                        fenwick.add(i, x);
+                        dual.add(i..=i, x);
+                        range.add(i..=i, x);
+                    }
+                    1 => {
+                        let r = rng.sample(BiasedSubRange(0..n));
+                        let x = gen(&mut rng);
+                        for y in &mut vec[r.clone()] {
+                            *y = *y + x;
+                        }
+                        dual.add(r.clone(), x);
+                        range.add(r.clone(), x);
+                        for i in r {
+                            fenwick.add(i, x);
+                        }
+                    }
+                    2 => {
+                        let r = rng.sample(BiasedSubRange(0..n));
+                        let expected = sum(&vec[r.clone()]);
+                        assert_eq!(fenwick.sum(r.clone()), expected);
+                        assert_eq!(range.sum(r.clone()), expected);
+                        assert_eq!(range.prefix_sum(r.end), sum(&vec[..r.end]));
+                    }
+                    _ => {
+                        if n != 0 {
+                            let i = rng.gen_range(0..n);
+                            assert_eq!(dual.get(i), vec[i]);
+                        }
+                    }
+                }
+            }
+            assert_eq!(dual.collect_vec(), vec);
+        }
+    }
+
+    #[test]
+    fn test_i64() {
+        test_base::<i64>(|rng| rng.gen_range(-100..=100));
+    }
+
+    #[test]
+    fn test_fp() {
+        test_base::<Fp>(|rng| Fp::new(rng.gen_range(0..998244353)));
+    }
+
+    #[test]
+    fn test_new() {
+        let mut fenwick = Fenwick::<i64>::new(3);
+        let mut dual = DualFenwick::<i64>::new(3);
+        let mut range = RangeFenwick::<i64>::new(3);
+        assert_eq!(fenwick.sum(..), 0);
+        assert_eq!(dual.collect_vec(), vec![0; 3]);
+        assert_eq!(range.sum(..), 0);
+        fenwick.add(2, 5);
+        dual.add(2.., 5);
+        range.add(2.., 5);
+        assert_eq!(fenwick.sum(2..), 5);
+        assert_eq!(dual.get(2), 5);
+        assert_eq!(range.sum(1..), 5);
+        assert!(Fenwick::<i64>::new(0).is_empty());
+    }
+
+    #[test]
+    #[should_panic(expected = "index 3 out of range for length 3")]
+    fn test_add_out_of_range() {
+        Fenwick::<i64>::new(3).add(3, 1);
+    }
+
+    #[test]
+    #[should_panic(expected = "index 3 out of range for length 3")]
+    fn test_get_out_of_range() {
+        DualFenwick::<i64>::new(3).get(3);
+    }
+
+    #[test]
+    #[should_panic(expected = "range end index 4 out of range for length 3")]
+    fn test_sum_out_of_range() {
+        RangeFenwick::<i64>::new(3).sum(1..4);
+    }
+
+    #[test]
+    #[should_panic(expected = "range starts at 2 but ends at 1")]
+    fn test_add_reversed_range() {
+        let (start, end) = (2, 1);
+        DualFenwick::<i64>::new(3).add(start..end, 1);
+    }
+
+    #[test]
+    #[should_panic(expected = "range end index 4 out of range for length 3")]
+    fn test_prefix_sum_out_of_range() {
+        Fenwick::<i64>::new(3).prefix_sum(4);
+    }
+}
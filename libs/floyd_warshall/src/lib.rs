@@ -0,0 +1,380 @@
+//! All-pairs shortest paths by the Floyd–Warshall algorithm, with negative edges and path
+//! reconstruction.
+//!
+//! A graph is given by the number of the vertices and directed edges `(u, v, w)` of weight $w$.
+//! Parallel edges and self-loops are allowed.
+//!
+//! # Unreachable pairs and negative cycles
+//!
+//! The distances are [`Distance`]s, not sentinel integers:
+//!
+//! * [`Distance::Unreachable`]: there is no walk,
+//! * [`Distance::NegInf`]: some walk passes through a negative cycle, so walks are arbitrarily
+//!   short,
+//! * [`Distance::Finite`]: otherwise.
+//!
+//! Internally, an unreachable pair is `None` and the relaxation adds in
+//! [`i64::saturating_add`]. Around a negative cycle the tentative distances may decrease
+//! exponentially, but they saturate at [`i64::MIN`] and stay negative, and such pairs are
+//! overwritten by [`Distance::NegInf`] in the second pass anyway.
+//!
+//! # Examples
+//!
+//! ```
+//! use floyd_warshall::apsp;
+//! use floyd_warshall::Distance;
+//! let apsp = apsp(4, &[(0, 1, 4), (1, 2, -2), (0, 2, 3), (3, 3, -1)]);
+//! assert_eq!(apsp.dist(0, 2), Distance::Finite(2));
+//! assert_eq!(apsp.reconstruct(0, 2), Some(vec![0, 1, 2]));
+//! assert_eq!(apsp.dist(2, 0), Distance::Unreachable);
+//! assert_eq!(apsp.dist(3, 3), Distance::NegInf);
+//! assert!(apsp.has_negative_cycle());
+//! ```
+
+/// The distance of a pair of vertices.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Distance {
+    /// There is a walk through a negative cycle.
+    NegInf,
+    /// The length of a shortest path.
+    Finite(i64),
+    /// There is no walk.
+    Unreachable,
+}
+impl Distance {
+    /// Returns the finite distance, or `None`.
+    pub fn finite(self) -> Option<i64> {
+        match self {
+            Distance::Finite(d) => Some(d),
+            _ => None,
+        }
+    }
+}
+
+/// The result of [`apsp`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ApspResult {
+    n: usize,
+    dist: Vec<Option<i64>>,
+    // The vertex next to `u` on a shortest path from `u` to `v`.
+    next: Vec<usize>,
+    neg_inf: Vec<bool>,
+}
+impl ApspResult {
+    /// Returns the number of the vertices.
+    pub fn len(&self) -> usize {
+        self.n
+    }
+
+    /// Returns `true` if there are no vertices.
+    pub fn is_empty(&self) -> bool {
+        self.n == 0
+    }
+
+    /// Returns the distance from `u` to `v`.
+    pub fn dist(&self, u: usize, v: usize) -> Distance {
+        let i = u * self.n + v;
+        match self.dist[i] {
+            None => Distance::Unreachable,
+            Some(_) if self.neg_inf[i] => Distance::NegInf,
+            Some(d) => Distance::Finite(d),
+        }
+    }
+
+    /// Returns `true` if some vertex is on a negative cycle.
+    pub fn has_negative_cycle(&self) -> bool {
+        (0..self.n).any(|u| self.neg_inf[u * self.n + u])
+    }
+
+    /// Returns the vertices of a shortest path from `u` to `v`, or `None` if the distance is not
+    /// finite.
+    ///
+    /// The path is simple and has the length [`dist(u, v)`](Self::dist), and it is `[u]` if
+    /// $u = v$.
+    ///
+    /// # Complexity
+    ///
+    /// $O(L)$, where $L$ is the number of the vertices of the path.
+    pub fn reconstruct(&self, u: usize, v: usize) -> Option<Vec<usize>> {
+        self.dist(u, v).finite()?;
+        let mut path = vec![u];
+        let mut x = u;
+        while x != v {
+            x = self.next[x * self.n + v];
+            path.push(x);
+        }
+        Some(path)
+    }
+}
+
+/// Returns the shortest distances of all the pairs.
+///
+/// # Requirements
+///
+/// The length of every simple path fits in `i64`.
+///
+/// # Panics
+///
+/// If some endpoint is out of range.
+///
+/// # Complexity
+///
+/// $O(n^3 + m)$
+pub fn apsp(n: usize, edges: &[(usize, usize, i64)]) -> ApspResult {
+    let mut dist = vec![None; n * n];
+    let mut next = vec![!0; n * n];
+    for u in 0..n {
+        dist[u * n + u] = Some(0);
+        next[u * n + u] = u;
+    }
+    for &(u, v, w) in edges {
+        assert!(
+            u < n && v < n,
+            "edge ({}, {}) is out of range for {} vertices",
+            u,
+            v,
+            n
+        );
+        let i = u * n + v;
+        if dist[i].map_or(true, |d| w < d) {
+            dist[i] = Some(w);
+            next[i] = v;
+        }
+    }
+    // The row `k` changes in the phase `k` only if `k` is on a negative cycle, so a copy is used.
+    let mut row_k = vec![None; n];
+    for k in 0..n {
+        row_k.copy_from_slice(&dist[k * n..(k + 1) * n]);
+        for u in 0..n {
+            let Some(duk) = dist[u * n + k] else {
+                continue;
+            };
+            let next_uk = next[u * n + k];
+            let dist_u = &mut dist[u * n..(u + 1) * n];
+            let next_u = &mut next[u * n..(u + 1) * n];
+            for v in 0..n {
+                let Some(dkv) = row_k[v] else {
+                    continue;
+                };
+                let d = duk.saturating_add(dkv);
+                if dist_u[v].map_or(true, |old| d < old) {
+                    dist_u[v] = Some(d);
+                    next_u[v] = next_uk;
+                }
+            }
+        }
+    }
+    // A pair is negative infinity if and only if there is a walk through a vertex on a negative
+    // cycle.
+    let on_cycle = (0..n)
+        .filter(|&k| dist[k * n + k].map_or(false, |d| d < 0))
+        .collect::<Vec<_>>();
+    let mut neg_inf = vec![false; n * n];
+    for &k in &on_cycle {
+        for u in (0..n).filter(|&u| dist[u * n + k].is_some()) {
+            for v in (0..n).filter(|&v| dist[k * n + v].is_some()) {
+                neg_inf[u * n + v] = true;
+            }
+        }
+    }
+    ApspResult {
+        n,
+        dist,
+        next,
+        neg_inf,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rand::rngs::StdRng;
+    use rand::Rng;
+    use rand::SeedableRng;
+
+    fn random_edges(
+        rng: &mut StdRng,
+        n: usize,
+        m: usize,
+        min: i64,
+        max: i64,
+    ) -> Vec<(usize, usize, i64)> {
+        (0..m)
+            .map(|_| {
+                (
+                    rng.gen_range(0..n),
+                    rng.gen_range(0..n),
+                    rng.gen_range(min..=max),
+                )
+            })
+            .collect()
+    }
+
+    fn bellman_ford(n: usize, edges: &[(usize, usize, i64)], s: usize) -> Vec<Distance> {
+        let mut dist = vec![None; n];
+        dist[s] = Some(0_i64);
+        for _ in 0..n {
+            for &(u, v, w) in edges {
+                if let Some(du) = dist[u] {
+                    if dist[v].map_or(true, |dv| du + w < dv) {
+                        dist[v] = Some(du + w);
+                    }
+                }
+            }
+        }
+        // The vertices still improved after $n$ rounds are reachable from a negative cycle, and so
+        // are those reachable from them.
+        let mut neg_inf = vec![false; n];
+        for _ in 0..n {
+            for &(u, v, w) in edges {
+                if let Some(du) = dist[u] {
+                    if neg_inf[u] || dist[v].map_or(true, |dv| du + w < dv) {
+                        neg_inf[v] = true;
+                    }
+                }
+            }
+        }
+        (0..n)
+            .map(|v| match dist[v] {
+                None => Distance::Unreachable,
+                Some(_) if neg_inf[v] => Distance::NegInf,
+                Some(d) => Distance::Finite(d),
+            })
+            .collect()
+    }
+
+    fn path_length(edges: &[(usize, usize, i64)], path: &[usize]) -> i64 {
+        path.windows(2)
+            .map(|w| {
+                edges
+                    .iter()
+                    .filter(|&&(u, v, _)| (u, v) == (w[0], w[1]))
+                    .map(|&(_, _, w)| w)
+                    .min()
+                    .unwrap()
+            })
+            .sum()
+    }
+
+    fn check(n: usize, edges: &[(usize, usize, i64)]) {
+        let result = apsp(n, edges);
+        for s in 0..n {
+            let expected = bellman_ford(n, edges, s);
+            for (t, &expected) in expected.iter().enumerate() {
+                assert_eq!(result.dist(s, t), expected, "{:?} {} -> {}", edges, s, t);
+                let path = result.reconstruct(s, t);
+                match expected {
+                    Distance::Finite(d) => {
+                        let path = path.unwrap();
+                        assert_eq!((path[0], path[path.len() - 1]), (s, t));
+                        assert!(path.len() <= n);
+                        assert_eq!(path_length(edges, &path), d);
+                    }
+                    _ => assert_eq!(path, None),
+                }
+            }
+        }
+        assert_eq!(
+            result.has_negative_cycle(),
+            (0..n).any(|s| result.dist(s, s) == Distance::NegInf)
+        );
+    }
+
+    #[test]
+    fn test_random() {
+        let mut rng = StdRng::seed_from_u64(42);
+        for _ in 0..500 {
+            let n = rng.gen_range(0..=8);
+            let m = if n == 0 { 0 } else { rng.gen_range(0..=2 * n) };
+            let min = rng.gen_range(-5..=0);
+            let max = rng.gen_range(0..=5);
+            let edges = random_edges(&mut rng, n, m, min, max);
+            check(n, &edges);
+        }
+    }
+
+    #[test]
+    fn test_random_large() {
+        let mut rng = StdRng::seed_from_u64(42);
+        for _ in 0..5 {
+            let n = rng.gen_range(30..=60);
+            let m = rng.gen_range(n..=4 * n);
+            let min = rng.gen_range(-3..=0);
+            let edges = random_edges(&mut rng, n, m, min, 1_000_000);
+            check(n, &edges);
+        }
+    }
+
+    #[test]
+    fn test_disconnected() {
+        let edges = [(0, 1, 5), (2, 3, -5)];
+        let result = apsp(5, &edges);
+        assert_eq!(result.dist(0, 1), Distance::Finite(5));
+        assert_eq!(result.dist(2, 3), Distance::Finite(-5));
+        assert_eq!(result.dist(1, 0), Distance::Unreachable);
+        assert_eq!(result.dist(0, 3), Distance::Unreachable);
+        assert_eq!(result.dist(4, 4), Distance::Finite(0));
+        assert_eq!(result.reconstruct(4, 4), Some(vec![4]));
+        assert_eq!(result.reconstruct(0, 4), None);
+        assert!(!result.has_negative_cycle());
+        check(5, &edges);
+    }
+
+    #[test]
+    fn test_negative_self_loop() {
+        // 0 -> 1 -> 2, and a negative self-loop at 1.
+        let edges = [(0, 1, 1), (1, 2, 1), (1, 1, -1), (2, 2, 3)];
+        let result = apsp(4, &edges);
+        assert!(result.has_negative_cycle());
+        assert_eq!(result.dist(0, 0), Distance::Finite(0));
+        assert_eq!(result.dist(0, 1), Distance::NegInf);
+        assert_eq!(result.dist(0, 2), Distance::NegInf);
+        assert_eq!(result.dist(1, 1), Distance::NegInf);
+        assert_eq!(result.dist(2, 2), Distance::Finite(0));
+        assert_eq!(result.dist(2, 1), Distance::Unreachable);
+        assert_eq!(result.reconstruct(0, 2), None);
+        check(4, &edges);
+    }
+
+    #[test]
+    fn test_large_weights() {
+        // The tentative distances around the negative cycle would overflow without saturation.
+        let n = 70;
+        let mut edges = (0..n - 1)
+            .map(|i| (i, i + 1, -(1 << 56)))
+            .collect::<Vec<_>>();
+        edges.push((n - 1, 0, 0));
+        edges.push((n, n, 0));
+        let result = apsp(n + 1, &edges);
+        for u in 0..n {
+            for v in 0..n {
+                assert_eq!(result.dist(u, v), Distance::NegInf);
+            }
+        }
+        assert_eq!(result.dist(n, n), Distance::Finite(0));
+        assert_eq!(result.dist(0, n), Distance::Unreachable);
+
+        let edges = [
+            (0, 1, i64::MAX / 2),
+            (1, 2, i64::MAX / 2),
+            (2, 0, i64::MIN / 2),
+        ];
+        let result = apsp(3, &edges);
+        assert_eq!(result.dist(0, 2), Distance::Finite(i64::MAX / 2 * 2));
+        assert_eq!(result.dist(2, 1), Distance::Finite(-1));
+        assert_eq!(result.reconstruct(2, 1), Some(vec![2, 0, 1]));
+    }
+
+    #[test]
+    fn test_zero_cycle() {
+        let edges = [(0, 1, 0), (1, 0, 0), (1, 2, 3), (0, 2, 3)];
+        check(3, &edges);
+        assert_eq!(apsp(3, &edges).reconstruct(1, 0), Some(vec![1, 0]));
+    }
+
+    #[test]
+    #[should_panic(expected = "edge (0, 2) is out of range for 2 vertices")]
+    fn test_out_of_range() {
+        apsp(2, &[(0, 2, 1)]);
+    }
+}
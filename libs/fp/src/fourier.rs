@@ -1,6 +1,10 @@
 use super::mod_inv;
+use super::recast_slice;
 use super::Fp;
 use super::PrimitiveRoot;
+use std::any::Any;
+use std::cell::RefCell;
+use std::collections::HashMap;
 
 const P1: u64 = 924844033;
 const P2: u64 = 998244353;
@@ -47,20 +51,44 @@ where
     a
 }
 
-/// Multiplies two polynomials.
+/// Multiplies two polynomials for any modulus $P$, such as $10^9 + 7$.
+///
+/// The product is computed modulo three NTT-friendly primes, and restored by Garner's algorithm,
+/// which is exact since every coefficient of the product over the integers is less than
+/// $2^{21} (P - 1)^2$, which is less than the product of the three primes for $P < 2^{31}$. If $P$
+/// is one of the three primes, the product is computed only once.
+///
+/// # Requirements
+/// - $n + m - 1 \le 2^{21}$
+/// - $P < 2^{31}$
+///
+/// # Examples
+/// ```
+/// use fp::any_mod_fps_mul;
+/// use fp::fp;
+/// use fp::Fp;
+/// type F = Fp<1000000007>;
+/// let a: Vec<F> = vec![fp!(-1), fp!(1)];
+/// let b: Vec<F> = vec![fp!(-1), fp!(-1)];
+/// assert_eq!(any_mod_fps_mul(&a, &b), vec![fp!(1), fp!(0), fp!(-1)]);
+/// ```
 pub fn any_mod_fps_mul<const P: u64>(a: &[Fp<P>], b: &[Fp<P>]) -> Vec<Fp<P>> {
-    let v1 = fps_mul(
-        a.iter().map(|&x| F1::new(x.value())).collect::<Vec<_>>(),
-        b.iter().map(|&x| F1::new(x.value())).collect::<Vec<_>>(),
-    );
-    let v2 = fps_mul(
-        a.iter().map(|&x| F2::new(x.value())).collect::<Vec<_>>(),
-        b.iter().map(|&x| F2::new(x.value())).collect::<Vec<_>>(),
-    );
-    let v3 = fps_mul(
-        a.iter().map(|&x| F3::new(x.value())).collect::<Vec<_>>(),
-        b.iter().map(|&x| F3::new(x.value())).collect::<Vec<_>>(),
+    if a.is_empty() || b.is_empty() {
+        return vec![];
+    }
+    assert!(
+        a.len() + b.len() - 1 <= 1 << 21,
+        "The length of the product must be at most 2^21."
     );
+    match P {
+        P1 => return recast_slice(&fps_mul(recast_slice::<P, P1>(a), recast_slice::<P, P1>(b))),
+        P2 => return recast_slice(&fps_mul(recast_slice::<P, P2>(a), recast_slice::<P, P2>(b))),
+        P3 => return recast_slice(&fps_mul(recast_slice::<P, P3>(a), recast_slice::<P, P3>(b))),
+        _ => {}
+    }
+    let v1 = fps_mul(recast_slice::<P, P1>(a), recast_slice::<P, P1>(b));
+    let v2 = fps_mul(recast_slice::<P, P2>(a), recast_slice::<P, P2>(b));
+    let v3 = fps_mul(recast_slice::<P, P3>(a), recast_slice::<P, P3>(b));
     v1.into_iter()
         .zip(v2)
         .zip(v3)
@@ -68,6 +96,212 @@ pub fn any_mod_fps_mul<const P: u64>(a: &[Fp<P>], b: &[Fp<P>]) -> Vec<Fp<P>> {
         .collect::<Vec<_>>()
 }
 
+/// Multiplies two polynomials over the integers, wrapping modulo $2^{64}$.
+///
+/// The product is computed modulo the three NTT-friendly primes of [`any_mod_fps_mul`], whose
+/// product is about $9.3 \times 10^{26}$. Every coefficient is exact if it is less than $2^{64}$,
+/// and otherwise it wraps around as with [`u64::wrapping_mul`] and [`u64::wrapping_add`] as long as
+/// the exact coefficient is less than the product of the primes. This is always the case if the
+/// inputs are less than $2^{32}$.
+///
+/// # Requirements
+/// - $n + m - 1 \le 2^{21}$
+///
+/// # Examples
+/// ```
+/// use fp::convolution_u64;
+/// assert_eq!(convolution_u64(&[1, 2], &[3, 4]), vec![3, 10, 8]);
+/// // 2^64 wraps around.
+/// assert_eq!(convolution_u64(&[1 << 32], &[1 << 32, 1]), vec![0, 1 << 32]);
+/// assert_eq!(convolution_u64(&[], &[1]), vec![]);
+/// ```
+pub fn convolution_u64(a: &[u64], b: &[u64]) -> Vec<u64> {
+    convolution_crt(a, b, |x, p| x % p)
+        .into_iter()
+        .map(|x| x as u64)
+        .collect()
+}
+
+/// Multiplies two polynomials over the integers, wrapping modulo $2^{64}$.
+///
+/// Same as [`convolution_u64`], but the exact coefficient is restored if its absolute value is less
+/// than half the product of the primes, about $4.6 \times 10^{26}$, and then wraps around. This is
+/// always the case if the absolute values of the inputs are at most $2^{31}$.
+///
+/// # Requirements
+/// - $n + m - 1 \le 2^{21}$
+///
+/// # Examples
+/// ```
+/// use fp::convolution_i64;
+/// assert_eq!(convolution_i64(&[1, -1], &[1, 1]), vec![1, 0, -1]);
+/// assert_eq!(convolution_i64(&[1 << 40], &[-(1 << 40)]), vec![0]);
+/// ```
+pub fn convolution_i64(a: &[i64], b: &[i64]) -> Vec<i64> {
+    const M: u128 = P1 as u128 * P2 as u128 * P3 as u128;
+    convolution_crt(a, b, |x, p| x.rem_euclid(p as i64) as u64)
+        .into_iter()
+        .map(|x| if x < M / 2 { x as i64 } else { (x as i128 - M as i128) as i64 })
+        .collect()
+}
+
+// Returns the product modulo `P1 * P2 * P3`, where `residue(x, p)` is the remainder of `x` by `p`.
+fn convolution_crt<T: Copy>(a: &[T], b: &[T], residue: impl Fn(T, u64) -> u64) -> Vec<u128> {
+    if a.is_empty() || b.is_empty() {
+        return vec![];
+    }
+    assert!(
+        a.len() + b.len() - 1 <= 1 << 21,
+        "The length of the product must be at most 2^21."
+    );
+    fn mul<T: Copy, const P: u64>(a: &[T], b: &[T], residue: &impl Fn(T, u64) -> u64) -> Vec<Fp<P>>
+    where
+        (): PrimitiveRoot<P>,
+    {
+        let a = a
+            .iter()
+            .map(|&x| Fp::new(residue(x, P)))
+            .collect::<Vec<_>>();
+        let b = b
+            .iter()
+            .map(|&x| Fp::new(residue(x, P)))
+            .collect::<Vec<_>>();
+        fps_mul(a, b)
+    }
+    let v1 = mul::<T, P1>(a, b, &residue);
+    let v2 = mul::<T, P2>(a, b, &residue);
+    let v3 = mul::<T, P3>(a, b, &residue);
+    v1.into_iter()
+        .zip(v2)
+        .zip(v3)
+        .map(|((e1, e2), e3)| garner_u128(e1, e2, e3))
+        .collect()
+}
+
+/// Twiddle factors of [`fft`] and [`ifft`], computed once for each power-of-two length.
+///
+/// [`fft`] and [`ifft`] use a thread-local plan, so an explicit plan is needed only to control its
+/// lifetime.
+///
+/// # Examples
+///
+/// ```
+/// use fp::fft_with_plan;
+/// use fp::fp;
+/// use fp::ifft_with_plan;
+/// use fp::Fp;
+/// use fp::NttPlan;
+/// type F = Fp<998244353>;
+/// let mut plan = NttPlan::new();
+/// let mut f: Vec<F> = vec![fp!(1000), fp!(100), fp!(10), fp!(1)];
+/// fft_with_plan(&mut f, &mut plan);
+/// ifft_with_plan(&mut f, &mut plan);
+/// assert_eq!(f, vec![fp!(1000), fp!(100), fp!(10), fp!(1)]);
+/// ```
+#[derive(Debug, Clone, PartialEq)]
+pub struct NttPlan<const P: u64> {
+    // `roots[k][j]` is $\omega ^ j$ for a primitive $2 ^ k$-th root $\omega$ and $j < 2 ^ k / 4$.
+    roots: Vec<Vec<Fp<P>>>,
+    inv_roots: Vec<Vec<Fp<P>>>,
+}
+impl<const P: u64> NttPlan<P>
+where
+    (): PrimitiveRoot<P>,
+{
+    /// Constructs an empty plan.
+    pub fn new() -> Self {
+        Self {
+            roots: Vec::new(),
+            inv_roots: Vec::new(),
+        }
+    }
+
+    /// Computes the twiddle factors for all the lengths up to $n$ that are not computed yet.
+    ///
+    /// # Requirements
+    ///
+    /// - $n$ is a power of two.
+    /// - $n | (p - 1)$
+    pub fn reserve(&mut self, n: usize) {
+        assert!(
+            n.is_power_of_two(),
+            "The length must be a power of two, but it is {}. Pad it with zeros.",
+            n
+        );
+        assert!(
+            (P - 1) % n as u64 == 0,
+            "The length {} must divide P - 1.",
+            n
+        );
+        for k in self.roots.len()..=n.trailing_zeros() as usize {
+            #[cfg(test)]
+            tests::TABLE_BUILDS.with(|c| c.set(c.get() + 1));
+            let root = <() as PrimitiveRoot<P>>::VALUE.pow((P - 1) >> k);
+            let quarter = (1 << k) / 4;
+            self.roots.push(powers(root, quarter));
+            self.inv_roots.push(powers(root.inv(), quarter));
+        }
+    }
+
+    /// Same as [`fft_with_plan`].
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use fp::fp;
+    /// use fp::Fp;
+    /// use fp::NttPlan;
+    /// type F = Fp<998244353>;
+    /// let mut plan = NttPlan::new();
+    /// let mut f: Vec<F> = vec![fp!(1), fp!(2), fp!(3), fp!(4)];
+    /// plan.fft(&mut f);
+    /// assert_eq!(f[0], fp!(10));
+    /// plan.ifft(&mut f);
+    /// assert_eq!(f, vec![fp!(1), fp!(2), fp!(3), fp!(4)]);
+    /// ```
+    pub fn fft(&mut self, f: &mut [Fp<P>]) {
+        fft_with_plan(f, self);
+    }
+
+    /// Same as [`ifft_with_plan`].
+    pub fn ifft(&mut self, f: &mut [Fp<P>]) {
+        ifft_with_plan(f, self);
+    }
+}
+impl<const P: u64> Default for NttPlan<P>
+where
+    (): PrimitiveRoot<P>,
+{
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+fn powers<const P: u64>(root: Fp<P>, len: usize) -> Vec<Fp<P>> {
+    std::iter::successors(Some(Fp::new(1)), |&c| Some(c * root))
+        .take(len)
+        .collect()
+}
+
+thread_local! {
+    static PLANS: RefCell<HashMap<u64, Box<dyn Any>>> = RefCell::new(HashMap::new());
+}
+
+fn with_thread_local_plan<const P: u64, R>(f: impl FnOnce(&mut NttPlan<P>) -> R) -> R
+where
+    (): PrimitiveRoot<P>,
+{
+    PLANS.with(|plans| {
+        let mut plans = plans.borrow_mut();
+        let plan = plans
+            .entry(P)
+            .or_insert_with(|| Box::new(NttPlan::<P>::new()))
+            .downcast_mut::<NttPlan<P>>()
+            .unwrap();
+        f(plan)
+    })
+}
+
 /// Fast Fourier transform.
 /// # Requirements
 ///
@@ -98,25 +332,32 @@ pub fn any_mod_fps_mul<const P: u64>(a: &[Fp<P>], b: &[Fp<P>]) -> Vec<Fp<P>> {
 /// ]);
 /// ```
 pub fn fft<const P: u64>(f: &mut [Fp<P>])
+where
+    (): PrimitiveRoot<P>,
+{
+    with_thread_local_plan(|plan| fft_with_plan(f, plan));
+}
+
+/// [`fft`] with an explicit [`NttPlan`].
+pub fn fft_with_plan<const P: u64>(f: &mut [Fp<P>], plan: &mut NttPlan<P>)
 where
     (): PrimitiveRoot<P>,
 {
     let n = f.len();
-    assert!(n.is_power_of_two());
-    assert!((P - 1) % n as u64 == 0);
-    let mut root = <() as PrimitiveRoot<P>>::VALUE.pow((P - 1) / f.len() as u64);
+    plan.reserve(n);
     let fourth = <() as PrimitiveRoot<P>>::VALUE.pow((P - 1) / 4);
     let mut fft_len = n;
     while 4 <= fft_len {
         let quarter = fft_len / 4;
+        let roots = &plan.roots[fft_len.trailing_zeros() as usize];
         for f in f.chunks_mut(fft_len) {
-            let mut c = Fp::new(1);
             for (((i, j), k), l) in (0..)
                 .zip(quarter..)
                 .zip(quarter * 2..)
                 .zip(quarter * 3..)
                 .take(quarter)
             {
+                let c = roots[i];
                 let c2 = c * c;
                 let x = f[i] + f[k];
                 let y = f[j] + f[l];
@@ -126,11 +367,8 @@ where
                 f[j] = c2 * (x - y);
                 f[k] = c * (z + w);
                 f[l] = c2 * c * (z - w);
-                c *= root;
             }
         }
-        root *= root;
-        root *= root;
         fft_len = quarter;
     }
     if fft_len == 2 {
@@ -172,16 +410,19 @@ where
 /// assert_eq!(f, vec![fp!(1000), fp!(100), fp!(10), fp!(1)]);
 /// ```
 pub fn ifft<const P: u64>(f: &mut [Fp<P>])
+where
+    (): PrimitiveRoot<P>,
+{
+    with_thread_local_plan(|plan| ifft_with_plan(f, plan));
+}
+
+/// [`ifft`] with an explicit [`NttPlan`].
+pub fn ifft_with_plan<const P: u64>(f: &mut [Fp<P>], plan: &mut NttPlan<P>)
 where
     (): PrimitiveRoot<P>,
 {
     let n = f.len();
-    assert!(n.is_power_of_two());
-    let root = <() as PrimitiveRoot<P>>::VALUE.pow((P - 1) / f.len() as u64);
-    let mut roots = std::iter::successors(Some(root.inv()), |x| Some(x * x))
-        .take(n.trailing_zeros() as usize + 1)
-        .collect::<Vec<_>>();
-    roots.reverse();
+    plan.reserve(n);
     let fourth = <() as PrimitiveRoot<P>>::VALUE.pow((P - 1) / 4).inv();
     let mut quarter = 1_usize;
     if n.trailing_zeros() % 2 == 1 {
@@ -195,15 +436,15 @@ where
     }
     while quarter != n {
         let fft_len = quarter * 4;
-        let root = roots[fft_len.trailing_zeros() as usize];
+        let roots = &plan.inv_roots[fft_len.trailing_zeros() as usize];
         for f in f.chunks_mut(fft_len) {
-            let mut c = Fp::new(1);
             for (((i, j), k), l) in (0..)
                 .zip(quarter..)
                 .zip(quarter * 2..)
                 .zip(quarter * 3..)
                 .take(quarter)
             {
+                let c = roots[i];
                 let c2 = c * c;
                 let x = f[i] + c2 * f[j];
                 let y = f[i] - c2 * f[j];
@@ -213,7 +454,6 @@ where
                 f[j] = y + w;
                 f[k] = x - z;
                 f[l] = y - w;
-                c *= root;
             }
         }
         quarter = fft_len;
@@ -223,19 +463,130 @@ where
 }
 
 /// Restore the original value from the remainder of the division by `P1`, `P2`, and `P3`.
-fn garner<const P: u64>(x1: Fp<P1>, x2: Fp<P2>, x3: Fp<P3>) -> Fp<P> {
+fn garner<const P: u64>(x1: F1, x2: F2, x3: F3) -> Fp<P> {
     let (x1, x2, x3) = (x1.value(), x2.value(), x3.value());
     let x2 = ((x2 + (P2 - x1)) * mod_inv::<P2>(P1)) % P2;
     let x3 = (((x3 + (P3 - x1)) * mod_inv::<P3>(P1) % P3 + (P3 - x2)) * mod_inv::<P3>(P2)) % P3;
     Fp::new(x1 + P1 * (x2 + P2 * x3 % P))
 }
 
+/// Restore the original value modulo `P1 * P2 * P3`.
+fn garner_u128(x1: F1, x2: F2, x3: F3) -> u128 {
+    let (x1, x2, x3) = (x1.value(), x2.value(), x3.value());
+    let x2 = ((x2 + (P2 - x1)) * mod_inv::<P2>(P1)) % P2;
+    let x3 = (((x3 + (P3 - x1)) * mod_inv::<P3>(P1) % P3 + (P3 - x2)) * mod_inv::<P3>(P2)) % P3;
+    x1 as u128 + P1 as u128 * (x2 as u128 + P2 as u128 * x3 as u128)
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
     use rand::rngs::StdRng;
     use rand::Rng;
     use rand::SeedableRng;
+    use std::cell::Cell;
+
+    thread_local! {
+        pub(super) static TABLE_BUILDS: Cell<usize> = Cell::new(0);
+    }
+
+    // The implementation before introducing `NttPlan`.
+    fn fft_recomputing<const P: u64>(f: &mut [Fp<P>])
+    where
+        (): PrimitiveRoot<P>,
+    {
+        let n = f.len();
+        assert!(n.is_power_of_two());
+        assert!((P - 1) % n as u64 == 0);
+        let mut root = <() as PrimitiveRoot<P>>::VALUE.pow((P - 1) / f.len() as u64);
+        let fourth = <() as PrimitiveRoot<P>>::VALUE.pow((P - 1) / 4);
+        let mut fft_len = n;
+        while 4 <= fft_len {
+            let quarter = fft_len / 4;
+            for f in f.chunks_mut(fft_len) {
+                let mut c = Fp::new(1);
+                for (((i, j), k), l) in (0..)
+                    .zip(quarter..)
+                    .zip(quarter * 2..)
+                    .zip(quarter * 3..)
+                    .take(quarter)
+                {
+                    let c2 = c * c;
+                    let x = f[i] + f[k];
+                    let y = f[j] + f[l];
+                    let z = f[i] - f[k];
+                    let w = fourth * (f[j] - f[l]);
+                    f[i] = x + y;
+                    f[j] = c2 * (x - y);
+                    f[k] = c * (z + w);
+                    f[l] = c2 * c * (z - w);
+                    c *= root;
+                }
+            }
+            root *= root;
+            root *= root;
+            fft_len = quarter;
+        }
+        if fft_len == 2 {
+            for f in f.chunks_mut(2) {
+                let x = f[0];
+                let y = f[1];
+                f[0] = x + y;
+                f[1] = x - y;
+            }
+        }
+    }
+
+    fn ifft_recomputing<const P: u64>(f: &mut [Fp<P>])
+    where
+        (): PrimitiveRoot<P>,
+    {
+        let n = f.len();
+        assert!(n.is_power_of_two());
+        let root = <() as PrimitiveRoot<P>>::VALUE.pow((P - 1) / f.len() as u64);
+        let mut roots = std::iter::successors(Some(root.inv()), |x| Some(x * x))
+            .take(n.trailing_zeros() as usize + 1)
+            .collect::<Vec<_>>();
+        roots.reverse();
+        let fourth = <() as PrimitiveRoot<P>>::VALUE.pow((P - 1) / 4).inv();
+        let mut quarter = 1_usize;
+        if n.trailing_zeros() % 2 == 1 {
+            for f in f.chunks_mut(2) {
+                let x = f[0];
+                let y = f[1];
+                f[0] = x + y;
+                f[1] = x - y;
+            }
+            quarter = 2;
+        }
+        while quarter != n {
+            let fft_len = quarter * 4;
+            let root = roots[fft_len.trailing_zeros() as usize];
+            for f in f.chunks_mut(fft_len) {
+                let mut c = Fp::new(1);
+                for (((i, j), k), l) in (0..)
+                    .zip(quarter..)
+                    .zip(quarter * 2..)
+                    .zip(quarter * 3..)
+                    .take(quarter)
+                {
+                    let c2 = c * c;
+                    let x = f[i] + c2 * f[j];
+                    let y = f[i] - c2 * f[j];
+                    let z = c * (f[k] + c2 * f[l]);
+                    let w = fourth * c * (f[k] - c2 * f[l]);
+                    f[i] = x + z;
+                    f[j] = y + w;
+                    f[k] = x - z;
+                    f[l] = y - w;
+                    c *= root;
+                }
+            }
+            quarter = fft_len;
+        }
+        let d = Fp::from(f.len()).inv();
+        f.iter_mut().for_each(|x| *x *= d);
+    }
 
     fn naive_mul<const P: u64>(a: &[Fp<P>], b: &[Fp<P>]) -> Vec<Fp<P>> {
         let mut c = vec![Fp::new(0); a.len() + b.len() - 1];
@@ -283,6 +634,151 @@ mod tests {
         }
     }
 
+    #[test]
+    fn test_any_mod_fps_mul_ntt_friendly() {
+        fn check<const P: u64>(rng: &mut StdRng)
+        where
+            (): PrimitiveRoot<P>,
+        {
+            for _ in 0..20 {
+                let n = rng.gen_range(1..=100);
+                let m = rng.gen_range(1..=100);
+                let a: Vec<Fp<P>> = (0..n).map(|_| Fp::new(rng.gen_range(0..P))).collect();
+                let b: Vec<Fp<P>> = (0..m).map(|_| Fp::new(rng.gen_range(0..P))).collect();
+                assert_eq!(any_mod_fps_mul(&a, &b), fps_mul(&a, &b));
+            }
+        }
+        let mut rng = StdRng::seed_from_u64(42);
+        check::<P1>(&mut rng);
+        check::<P2>(&mut rng);
+        check::<P3>(&mut rng);
+    }
+
+    // All the coefficients are $P - 1$, the worst case for Garner's algorithm, and the $k$-th
+    // coefficient of the product is the number of the pairs times $(P - 1)^2 = 1$.
+    fn check_any_mod_fps_mul_max<const P: u64>(n: usize) {
+        let a = vec![Fp::<P>::new(P - 1); n];
+        let result = any_mod_fps_mul(&a, &a);
+        assert_eq!(result.len(), 2 * n - 1);
+        for (k, &x) in result.iter().enumerate() {
+            assert_eq!(x, Fp::new((k.min(2 * n - 2 - k) + 1) as u64));
+        }
+    }
+
+    #[test]
+    fn test_any_mod_fps_mul_max() {
+        check_any_mod_fps_mul_max::<1000000007>(1 << 12);
+        check_any_mod_fps_mul_max::<2147483647>(1 << 12);
+        check_any_mod_fps_mul_max::<3>(100);
+    }
+
+    #[test]
+    #[ignore]
+    fn test_any_mod_fps_mul_max_capacity() {
+        check_any_mod_fps_mul_max::<1000000007>(1 << 20);
+        check_any_mod_fps_mul_max::<2147483647>(1 << 20);
+    }
+
+    #[test]
+    #[should_panic(expected = "The length of the product must be at most 2^21.")]
+    fn test_any_mod_fps_mul_too_long() {
+        let a = vec![Fp::<1000000007>::new(1); (1 << 20) + 1];
+        any_mod_fps_mul(&a, &a);
+    }
+
+    fn naive_convolution_u128(a: &[u64], b: &[u64]) -> Vec<u128> {
+        if a.is_empty() || b.is_empty() {
+            return vec![];
+        }
+        let mut c = vec![0_u128; a.len() + b.len() - 1];
+        for (i, &a) in a.iter().enumerate() {
+            for (j, &b) in b.iter().enumerate() {
+                c[i + j] += a as u128 * b as u128;
+            }
+        }
+        c
+    }
+
+    #[test]
+    fn test_convolution_u64_random() {
+        let mut rng = StdRng::seed_from_u64(42);
+        for _ in 0..200 {
+            let n = rng.gen_range(0..=60);
+            let m = rng.gen_range(0..=60);
+            let max: u64 = match rng.gen_range(0..3) {
+                0 => 10,
+                1 => 1 << 20,
+                _ => u32::MAX as u64,
+            };
+            let a = (0..n).map(|_| rng.gen_range(0..=max)).collect::<Vec<_>>();
+            let b = (0..m).map(|_| rng.gen_range(0..=max)).collect::<Vec<_>>();
+            let expected = naive_convolution_u128(&a, &b)
+                .into_iter()
+                .map(|x| x as u64)
+                .collect::<Vec<_>>();
+            assert_eq!(convolution_u64(&a, &b), expected);
+        }
+    }
+
+    #[test]
+    fn test_convolution_u64_wrap() {
+        // The middle coefficient is $2^{12} (2^{32} - 1)^2$, which exceeds $2^{64}$.
+        let n = 1 << 12;
+        let a = vec![u32::MAX as u64; n];
+        let result = convolution_u64(&a, &a);
+        let expected = naive_convolution_u128(&a, &a);
+        assert!(expected[n - 1] > u64::MAX as u128);
+        assert_eq!(result.len(), 2 * n - 1);
+        for (x, y) in result.into_iter().zip(expected) {
+            assert_eq!(x, y as u64);
+        }
+        // An input larger than the primes
+        assert_eq!(convolution_u64(&[u64::MAX, 1], &[1, 1]), vec![
+            u64::MAX,
+            0,
+            1
+        ]);
+    }
+
+    #[test]
+    fn test_convolution_i64_random() {
+        let mut rng = StdRng::seed_from_u64(42);
+        for _ in 0..200 {
+            let n = rng.gen_range(0..=60);
+            let m = rng.gen_range(0..=60);
+            let max: i64 = match rng.gen_range(0..3) {
+                0 => 10,
+                1 => 1 << 20,
+                _ => 1 << 31,
+            };
+            let a = (0..n)
+                .map(|_| rng.gen_range(-max..=max))
+                .collect::<Vec<_>>();
+            let b = (0..m)
+                .map(|_| rng.gen_range(-max..=max))
+                .collect::<Vec<_>>();
+            if n == 0 || m == 0 {
+                assert!(convolution_i64(&a, &b).is_empty());
+                continue;
+            }
+            let mut expected = vec![0_i128; n + m - 1];
+            for (i, &a) in a.iter().enumerate() {
+                for (j, &b) in b.iter().enumerate() {
+                    expected[i + j] += a as i128 * b as i128;
+                }
+            }
+            let expected = expected.into_iter().map(|x| x as i64).collect::<Vec<_>>();
+            assert_eq!(convolution_i64(&a, &b), expected);
+        }
+    }
+
+    #[test]
+    fn test_convolution_empty() {
+        assert!(convolution_u64(&[], &[]).is_empty());
+        assert!(convolution_u64(&[1, 2], &[]).is_empty());
+        assert!(convolution_i64(&[], &[-1]).is_empty());
+    }
+
     #[test]
     fn test_garner_random() {
         let mut rng = StdRng::seed_from_u64(42);
@@ -292,4 +788,120 @@ mod tests {
             assert_eq!(result, Fp::<1000000007>::new(x));
         }
     }
+
+    #[test]
+    fn test_fft_bit_identical() {
+        type F = Fp<998244353>;
+        let mut rng = StdRng::seed_from_u64(42);
+        let mut plan = NttPlan::new();
+        for k in 0..=12 {
+            let n = 1 << k;
+            let f: Vec<F> = (0..n).map(|_| F::new(rng.gen())).collect();
+            let mut expected = f.clone();
+            fft_recomputing(&mut expected);
+            let mut result = f.clone();
+            fft(&mut result);
+            assert_eq!(result, expected);
+            let mut result = f.clone();
+            fft_with_plan(&mut result, &mut plan);
+            assert_eq!(result, expected);
+
+            ifft_recomputing(&mut expected);
+            assert_eq!(expected, f);
+            ifft(&mut result);
+            assert_eq!(result, f);
+            fft(&mut result);
+            ifft_with_plan(&mut result, &mut plan);
+            assert_eq!(result, f);
+        }
+    }
+
+    #[test]
+    fn test_fps_mul_many_lengths() {
+        type F = Fp<998244353>;
+        let mut rng = StdRng::seed_from_u64(42);
+        for n in 1..=70 {
+            for m in [1, n / 2 + 1, n] {
+                let a: Vec<F> = (0..n).map(|_| F::new(rng.gen())).collect();
+                let b: Vec<F> = (0..m).map(|_| F::new(rng.gen())).collect();
+                assert_eq!(fps_mul(&a, &b), naive_mul(&a, &b));
+            }
+        }
+    }
+
+    #[test]
+    fn test_plan_methods() {
+        type F = Fp<998244353>;
+        let mut rng = StdRng::seed_from_u64(42);
+        let mut plan = NttPlan::new();
+        for k in 0..=10 {
+            let f = (0..1 << k).map(|_| F::new(rng.gen())).collect::<Vec<_>>();
+            let mut expected = f.clone();
+            fft(&mut expected);
+            let mut result = f.clone();
+            plan.fft(&mut result);
+            assert_eq!(result, expected);
+            plan.ifft(&mut result);
+            assert_eq!(result, f);
+        }
+    }
+
+    #[test]
+    #[should_panic(expected = "The length must be a power of two, but it is 6. Pad it with zeros.")]
+    fn test_fft_not_power_of_two() {
+        let mut f = vec![Fp::<998244353>::new(1); 6];
+        fft(&mut f);
+    }
+
+    #[test]
+    #[should_panic(expected = "The length must be a power of two, but it is 3. Pad it with zeros.")]
+    fn test_ifft_not_power_of_two() {
+        let mut f = vec![Fp::<998244353>::new(1); 3];
+        NttPlan::new().ifft(&mut f);
+    }
+
+    #[test]
+    #[should_panic(expected = "The length 16777216 must divide P - 1.")]
+    fn test_fft_too_long() {
+        // $P - 1 = 2^{23} \cdot 7 \cdot 17$
+        NttPlan::<998244353>::new().reserve(1 << 24);
+    }
+
+    #[test]
+    fn test_table_builds_once_per_length() {
+        type F = Fp<998244353>;
+        // The Newton iteration of `fps_inv` in the crate `fps`.
+        fn newton_inv(f: &[F], precision: usize) -> Vec<F> {
+            let mut g = vec![f[0].inv()];
+            while g.len() < precision {
+                let fft_size = g.len() * 4;
+                let mut f = f[..g.len() * 2].to_vec();
+                f.resize(fft_size, F::new(0));
+                g.resize(fft_size, F::new(0));
+                fft(&mut f);
+                fft(&mut g);
+                let mut result = f
+                    .iter()
+                    .zip(&g)
+                    .map(|(&f, &g)| g * (-f * g + F::new(2)))
+                    .collect::<Vec<_>>();
+                ifft(&mut result);
+                result.truncate(fft_size / 2);
+                g = result;
+            }
+            g
+        }
+        let f = (1..=1 << 10).map(F::new).collect::<Vec<_>>();
+        let before = TABLE_BUILDS.with(|c| c.get());
+        let expected = newton_inv(&f, 1 << 10);
+        let after = TABLE_BUILDS.with(|c| c.get());
+        assert!(after - before <= 13);
+        for _ in 0..10 {
+            assert_eq!(newton_inv(&f, 1 << 10), expected);
+        }
+        assert_eq!(TABLE_BUILDS.with(|c| c.get()), after);
+        let product = fps_mul(&f, &expected);
+        assert_eq!(product[0], F::new(1));
+        assert!(product[1..1 << 10].iter().all(|&x| x == F::new(0)));
+    }
 }
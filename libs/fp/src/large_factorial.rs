@@ -0,0 +1,203 @@
+use super::any_mod_fps_mul;
+use super::Factorial;
+use super::Fp;
+
+/// Computes $n! \bmod p$ for a single $n$.
+/// Use [`LargeFactorial`] for multiple queries.
+/// # Examples
+/// ```
+/// use fp::factorial_mod_p_large;
+/// use fp::Fp;
+/// const P: u64 = 1000000007;
+/// assert_eq!(factorial_mod_p_large::<P>(10), Fp::new(3628800));
+/// assert_eq!(factorial_mod_p_large::<P>(P - 1), -Fp::new(1));
+/// ```
+pub fn factorial_mod_p_large<const P: u64>(n: u64) -> Fp<P> {
+    LargeFactorial::<P>::new().fact(n)
+}
+
+/// Precomputes $(kv)!$ for a power of two $v \approx \sqrt p$ to answer $n!$ for huge $n$.
+///
+/// Let $g_d(x) = (xv + 1)(xv + 2) \cdots (xv + d)$. Since $g _ {2d}(x) = g_d(x) g_d(x + d/v)$, the
+/// values $g_d(0), \dots, g_d(d)$ double by shifting the sampling points, and finally $g_v(i) =
+/// ((i+1)v)! / (iv)!$.
+/// # Complexity
+/// - Construction: $O(\sqrt p \log p)$
+/// - Query: $O(\sqrt p)$
+/// # Examples
+/// ```
+/// use fp::Fp;
+/// use fp::LargeFactorial;
+/// const P: u64 = 1000000007;
+/// let fact = LargeFactorial::<P>::new();
+/// assert_eq!(fact.fact(10), Fp::new(3628800));
+/// assert_eq!(fact.fact(100000000), Fp::new(927880474));
+/// ```
+pub struct LargeFactorial<const P: u64> {
+    block: u64,
+    block_fact: Vec<Fp<P>>,
+}
+impl<const P: u64> LargeFactorial<P> {
+    /// Constructs a new instance.
+    pub fn new() -> Self {
+        let mut v = 1;
+        while v * v < P - 1 {
+            v *= 2;
+        }
+        let mut g = vec![Fp::new(1), Fp::new(v + 1)];
+        let mut d = 1;
+        while d != v {
+            let upper = shift_sampling_points(&g, Fp::new(d + 1), d as usize);
+            let shifted = shift_sampling_points(&g, Fp::new(d) / Fp::new(v), 2 * d as usize + 1);
+            g.extend(upper);
+            for (x, y) in g.iter_mut().zip(shifted) {
+                *x *= y;
+            }
+            d *= 2;
+        }
+        let mut block_fact = vec![Fp::new(1)];
+        for &x in &g[..v as usize] {
+            block_fact.push(*block_fact.last().unwrap() * x);
+        }
+        Self {
+            block: v,
+            block_fact,
+        }
+    }
+
+    /// The factorial $n!$, which is zero if $n \ge p$.
+    /// # Examples
+    /// ```
+    /// use fp::Fp;
+    /// use fp::LargeFactorial;
+    /// const P: u64 = 998244353;
+    /// let fact = LargeFactorial::<P>::new();
+    /// assert_eq!(fact.fact(0), Fp::new(1));
+    /// assert_eq!(fact.fact(P - 2), Fp::new(1));
+    /// assert_eq!(fact.fact(P), Fp::new(0));
+    /// ```
+    pub fn fact(&self, n: u64) -> Fp<P> {
+        if P <= n {
+            return Fp::new(0);
+        }
+        let k = n / self.block;
+        let mut result = self.block_fact[k as usize];
+        for i in k * self.block + 1..=n {
+            result *= Fp::new(i);
+        }
+        result
+    }
+}
+impl<const P: u64> Default for LargeFactorial<P> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+// Given $f(0), \dots, f(d)$ of a polynomial $f$ of degree at most $d$, returns $f(a), \dots, f(a
+// + m - 1)$.
+fn shift_sampling_points<const P: u64>(f: &[Fp<P>], a: Fp<P>, m: usize) -> Vec<Fp<P>> {
+    let d = f.len() - 1;
+    let fact = Factorial::<P>::new(d);
+    let c = f
+        .iter()
+        .enumerate()
+        .map(|(i, &x)| x * fact.inv_fact(i) * fact.inv_fact(d - i) * Fp::sign(d - i))
+        .collect::<Vec<_>>();
+    // $a + k - i$ vanishes only if $a + k$ is a sampling point, whose value we already know.
+    let xs = (0..m + d)
+        .map(|t| a - Fp::new(d as u64) + Fp::new(t as u64))
+        .collect::<Vec<_>>();
+    let mut prefix = vec![Fp::new(1)];
+    for &x in &xs {
+        let last = *prefix.last().unwrap();
+        prefix.push(if x.value() == 0 { last } else { last * x });
+    }
+    let mut h = vec![Fp::new(0); m + d];
+    let mut inv = prefix[m + d].inv();
+    for t in (0..m + d).rev() {
+        if xs[t].value() != 0 {
+            h[t] = inv * prefix[t];
+            inv *= xs[t];
+        }
+    }
+    let conv = any_mod_fps_mul(&c, &h);
+    let mut inv_prefix = Fp::new(1);
+    (0..m)
+        .map(|k| {
+            let x = a + Fp::new(k as u64);
+            let result = if x.value() <= d as u64 {
+                f[x.value() as usize]
+            } else {
+                conv[k + d] * prefix[k + d + 1] * inv_prefix
+            };
+            if xs[k].value() != 0 {
+                inv_prefix *= h[k];
+            }
+            result
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rand::rngs::StdRng;
+    use rand::Rng;
+    use rand::SeedableRng;
+
+    fn test_against_iteration<const P: u64>(rng: &mut StdRng, ratio: u32) {
+        let fact = LargeFactorial::<P>::new();
+        let mut expected = Fp::<P>::new(1);
+        for n in 0..P {
+            if n != 0 {
+                expected *= Fp::new(n);
+            }
+            if n == 0 || n == P - 1 || rng.gen_ratio(1, ratio) {
+                assert_eq!(fact.fact(n), expected, "P = {}, n = {}", P, n);
+            }
+        }
+        assert_eq!(fact.fact(P - 1), -Fp::new(1));
+        assert_eq!(fact.fact(P), Fp::new(0));
+    }
+
+    #[test]
+    fn test_small_primes() {
+        let mut rng = StdRng::seed_from_u64(42);
+        test_against_iteration::<2>(&mut rng, 1);
+        test_against_iteration::<3>(&mut rng, 1);
+        test_against_iteration::<5>(&mut rng, 1);
+        test_against_iteration::<7>(&mut rng, 1);
+        test_against_iteration::<17>(&mut rng, 1);
+        test_against_iteration::<97>(&mut rng, 1);
+        test_against_iteration::<1009>(&mut rng, 1);
+        test_against_iteration::<65537>(&mut rng, 64);
+        test_against_iteration::<999983>(&mut rng, 1000);
+    }
+
+    #[test]
+    fn test_large_primes() {
+        const P1: u64 = 1000000007;
+        let fact = LargeFactorial::<P1>::new();
+        assert_eq!(fact.fact(0), Fp::new(1));
+        assert_eq!(fact.fact(100000000), Fp::new(927880474));
+        assert_eq!(fact.fact(123456789), Fp::new(126209852));
+        assert_eq!(fact.fact(500000000), Fp::new(733333339));
+        assert_eq!(fact.fact(P1 - 1), -Fp::new(1));
+        let mut rng = StdRng::seed_from_u64(42);
+        for _ in 0..100 {
+            // Wilson's theorem implies $n! (p - 1 - n)! = (-1)^{n+1}$.
+            let n = rng.gen_range(0..P1);
+            let result = fact.fact(n) * fact.fact(P1 - 1 - n);
+            assert_eq!(result, Fp::sign(n as usize + 1));
+        }
+
+        const P2: u64 = 998244353;
+        let fact = LargeFactorial::<P2>::new();
+        assert_eq!(fact.fact(100000000), Fp::new(808258749));
+        assert_eq!(fact.fact(500000000), Fp::new(62402409));
+        assert_eq!(fact.fact(P2 - 2), Fp::new(1));
+        assert_eq!(fact.fact(P2 - 1), -Fp::new(1));
+        assert_eq!(factorial_mod_p_large::<P2>(500000000), Fp::new(62402409));
+    }
+}
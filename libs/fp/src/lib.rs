@@ -24,6 +24,15 @@
 //! assert_eq!(f.comb_with_reputation(5, 3), fp!(35));
 //! ```
 //!
+//! ## Factorials of huge numbers
+//! ```
+//! use fp::fp;
+//! use fp::LargeFactorial;
+//! let f = LargeFactorial::<1000000007>::new();
+//! assert_eq!(f.fact(5), fp!(120));
+//! assert_eq!(f.fact(1000000006), fp!(-1));
+//! ```
+//!
 //! ## Convolution by Fast Fourier transform (FFT)
 //! ```
 //! use fp::Fp;
@@ -37,13 +46,25 @@
 mod ext_gcd;
 mod factorial;
 mod fourier;
+mod large_factorial;
+mod primitive_root;
 
 use ext_gcd::mod_inv;
 pub use factorial::Factorial;
 pub use fourier::any_mod_fps_mul;
+pub use fourier::convolution_i64;
+pub use fourier::convolution_u64;
 pub use fourier::fft;
+pub use fourier::fft_with_plan;
 pub use fourier::fps_mul;
 pub use fourier::ifft;
+pub use fourier::ifft_with_plan;
+pub use fourier::NttPlan;
+pub use large_factorial::factorial_mod_p_large;
+pub use large_factorial::LargeFactorial;
+pub use primitive_root::find_primitive_root;
+pub use primitive_root::is_primitive_root;
+pub use primitive_root::PrimitiveRoot;
 use std::iter::Product;
 use std::iter::Sum;
 use std::mem::swap;
@@ -75,21 +96,6 @@ macro_rules! fp {
     };
 }
 
-/// A primitive root of unity.
-pub trait PrimitiveRoot<const P: u64> {
-    /// A primitive root of unity.
-    const VALUE: Fp<P>;
-}
-impl PrimitiveRoot<998244353> for () {
-    const VALUE: Fp<998244353> = Fp::new(3);
-}
-impl PrimitiveRoot<1012924417> for () {
-    const VALUE: Fp<1012924417> = Fp::new(5);
-}
-impl PrimitiveRoot<924844033> for () {
-    const VALUE: Fp<924844033> = Fp::new(5);
-}
-
 /// A value in $\mathbb{Fp}_p$.
 /// # Requirements
 /// - $P$ is odd and prime ($P \gt 2^{31}$)
@@ -180,6 +186,76 @@ impl<const P: u64> Fp<P> {
     pub fn sign(pow: usize) -> Self {
         Self::new(if pow % 2 == 0 { 1 } else { P - 1 })
     }
+
+    /// Reduces the canonical representative modulo $q$.
+    /// # Examples
+    /// ```
+    /// use fp::Fp;
+    /// let a = Fp::<998244353>::new(100);
+    /// assert_eq!(a.recast::<7>(), Fp::new(2));
+    /// ```
+    pub fn recast<const Q: u64>(self) -> Fp<Q> {
+        Fp::new(self.value)
+    }
+
+    /// Reinterprets the canonical representative modulo $q$, or returns `None` if it is not less
+    /// than $q$.
+    /// # Examples
+    /// ```
+    /// use fp::Fp;
+    /// let a = Fp::<998244353>::new(100);
+    /// assert_eq!(a.checked_recast::<1000000007>(), Some(Fp::new(100)));
+    /// assert_eq!(a.checked_recast::<7>(), None);
+    /// ```
+    pub fn checked_recast<const Q: u64>(self) -> Option<Fp<Q>> {
+        (self.value < Q).then_some(Fp { value: self.value })
+    }
+
+    /// Returns the representative in $(-p/2, p/2]$.
+    /// # Examples
+    /// ```
+    /// use fp::Fp;
+    /// assert_eq!(Fp::<998244353>::new(3).to_signed(), 3);
+    /// assert_eq!(Fp::<998244353>::new(998244350).to_signed(), -3);
+    /// ```
+    pub fn to_signed(self) -> i64 {
+        if self.value <= P / 2 {
+            self.value as i64
+        } else {
+            self.value as i64 - P as i64
+        }
+    }
+}
+
+/// Applies [`Fp::recast`] to each element.
+/// # Examples
+/// ```
+/// use fp::recast_slice;
+/// use fp::Fp;
+/// let a = [Fp::<998244353>::new(3), Fp::new(10)];
+/// assert_eq!(recast_slice::<998244353, 7>(&a), vec![
+///     Fp::new(3),
+///     Fp::new(3)
+/// ]);
+/// ```
+pub fn recast_slice<const P: u64, const Q: u64>(a: &[Fp<P>]) -> Vec<Fp<Q>> {
+    a.iter().map(|&x| x.recast()).collect()
+}
+
+/// Applies [`Fp::checked_recast`] to each element, or returns `None` if any of them fails.
+/// # Examples
+/// ```
+/// use fp::checked_recast_slice;
+/// use fp::Fp;
+/// let a = [Fp::<998244353>::new(3), Fp::new(10)];
+/// assert_eq!(
+///     checked_recast_slice::<998244353, 11>(&a),
+///     Some(vec![Fp::new(3), Fp::new(10)])
+/// );
+/// assert_eq!(checked_recast_slice::<998244353, 7>(&a), None);
+/// ```
+pub fn checked_recast_slice<const P: u64, const Q: u64>(a: &[Fp<P>]) -> Option<Vec<Fp<Q>>> {
+    a.iter().map(|&x| x.checked_recast()).collect()
 }
 impl<const P: u64> std::fmt::Debug for Fp<P> {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
@@ -462,4 +538,63 @@ mod tests {
             );
         }
     }
+
+    #[test]
+    fn test_recast() {
+        assert_eq!(Fp::<P>::new(0).recast::<7>(), Fp::new(0));
+        assert_eq!(Fp::<P>::new(6).recast::<7>(), Fp::new(6));
+        assert_eq!(Fp::<P>::new(7).recast::<7>(), Fp::new(0));
+        assert_eq!(Fp::<P>::new(P - 1).recast::<7>(), Fp::new((P - 1) % 7));
+        assert_eq!(Fp::<P>::new(P - 1).recast::<1000000007>(), Fp::new(P - 1));
+        let mut rng = StdRng::seed_from_u64(42);
+        for _ in 0..256 {
+            let a = rng.gen_range(0..P);
+            assert_eq!(Fp::<P>::new(a).recast::<65537>().value(), a % 65537);
+        }
+    }
+    #[test]
+    fn test_checked_recast() {
+        assert_eq!(Fp::<P>::new(6).checked_recast::<7>(), Some(Fp::new(6)));
+        assert_eq!(Fp::<P>::new(7).checked_recast::<7>(), None);
+        assert_eq!(
+            Fp::<P>::new(P - 1).checked_recast::<1000000007>(),
+            Some(Fp::new(P - 1))
+        );
+        assert_eq!(Fp::<1000000007>::new(P).checked_recast::<P>(), None);
+        let a = [Fp::<P>::new(1), Fp::new(5), Fp::new(6)];
+        assert_eq!(
+            checked_recast_slice::<P, 7>(&a),
+            Some(vec![Fp::new(1), Fp::new(5), Fp::new(6)])
+        );
+        assert_eq!(checked_recast_slice::<P, 6>(&a), None);
+        assert_eq!(recast_slice::<P, 6>(&a), vec![
+            Fp::new(1),
+            Fp::new(5),
+            Fp::new(0)
+        ]);
+    }
+    #[test]
+    fn test_to_signed() {
+        assert_eq!(Fp::<P>::new(0).to_signed(), 0);
+        assert_eq!(Fp::<P>::new(1).to_signed(), 1);
+        assert_eq!(Fp::<P>::new(P / 2).to_signed(), (P / 2) as i64);
+        assert_eq!(Fp::<P>::new(P / 2 + 1).to_signed(), -((P / 2) as i64));
+        assert_eq!(Fp::<P>::new(P - 1).to_signed(), -1);
+        assert_eq!(Fp::<7>::new(3).to_signed(), 3);
+        assert_eq!(Fp::<7>::new(4).to_signed(), -3);
+        assert_eq!(Fp::<2>::new(1).to_signed(), 1);
+    }
+    #[test]
+    fn test_crt_by_recast() {
+        const Q: u64 = 1012924417;
+        let mut rng = StdRng::seed_from_u64(42);
+        for _ in 0..256 {
+            let x = rng.gen_range(-100_000_000_000_000_000..=100_000_000_000_000_000_i64);
+            let a = Fp::<P>::from(x);
+            let b = Fp::<Q>::from(x);
+            let t = (b - a.recast::<Q>()) / Fp::<Q>::new(P);
+            let result = a.value() as i64 + t.to_signed() * P as i64;
+            assert_eq!(result, x);
+        }
+    }
 }
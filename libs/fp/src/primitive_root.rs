@@ -0,0 +1,198 @@
+use super::Fp;
+
+/// A primitive root of unity.
+///
+/// Every implementation is checked at compile time by [`is_primitive_root`].
+pub trait PrimitiveRoot<const P: u64> {
+    /// A primitive root of unity.
+    const VALUE: Fp<P>;
+}
+
+macro_rules! impl_primitive_root {
+    ($($p:literal => $g:literal,)*) => {$(
+        impl PrimitiveRoot<$p> for () {
+            const VALUE: Fp<$p> = Fp::new($g);
+        }
+        const _: () = assert!(is_primitive_root($p, $g));
+    )*};
+}
+// $P = c 2^k + 1$
+impl_primitive_root! {
+    167772161 => 3, // 5 * 2^25 + 1
+    469762049 => 3, // 7 * 2^26 + 1
+    754974721 => 11, // 45 * 2^24 + 1
+    924844033 => 5, // 441 * 2^21 + 1
+    998244353 => 3, // 119 * 2^23 + 1
+    1004535809 => 3, // 479 * 2^21 + 1
+    1012924417 => 5, // 483 * 2^21 + 1
+    1224736769 => 3, // 73 * 2^24 + 1
+    2013265921 => 31, // 15 * 2^27 + 1
+}
+
+/// Returns `true` if $g$ generates the multiplicative group of $\mathbb{F}_p$.
+///
+/// It checks $g^{(p - 1) / q} \ne 1$ for every prime factor $q$ of $p - 1$, which is found by trial
+/// division, so it is fast when $p - 1$ is a power of two times a small number.
+///
+/// # Requirements
+/// $p$ is prime.
+///
+/// # Complexity
+/// $O(\sqrt{p})$ in the worst case
+///
+/// # Examples
+/// ```
+/// use fp::is_primitive_root;
+/// assert!(is_primitive_root(7, 3));
+/// assert!(!is_primitive_root(7, 2));
+/// ```
+pub const fn is_primitive_root(p: u64, g: u64) -> bool {
+    if g % p == 0 {
+        return false;
+    }
+    let mut m = p - 1;
+    let mut q = 2;
+    while q * q <= m {
+        if m % q == 0 {
+            if pow_mod(g, (p - 1) / q, p) == 1 {
+                return false;
+            }
+            while m % q == 0 {
+                m /= q;
+            }
+        }
+        q += 1;
+    }
+    m == 1 || pow_mod(g, (p - 1) / m, p) != 1
+}
+
+/// Returns the smallest primitive root modulo $p$, for a modulus known only at runtime.
+///
+/// # Requirements
+/// $p$ is prime.
+///
+/// # Complexity
+/// $O(\sqrt{p})$ to factor $p - 1$, and $O(\omega(p - 1) \log p)$ for each candidate.
+///
+/// # Examples
+/// ```
+/// use fp::find_primitive_root;
+/// assert_eq!(find_primitive_root(7), 3);
+/// assert_eq!(find_primitive_root(998244353), 3);
+/// assert_eq!(find_primitive_root(1000000007), 5);
+/// ```
+pub fn find_primitive_root(p: u64) -> u64 {
+    if p == 2 {
+        return 1;
+    }
+    let mut factors = Vec::new();
+    let mut m = p - 1;
+    let mut q = 2;
+    while q * q <= m {
+        if m % q == 0 {
+            factors.push(q);
+            while m % q == 0 {
+                m /= q;
+            }
+        }
+        q += 1;
+    }
+    if m != 1 {
+        factors.push(m);
+    }
+    (2..p)
+        .find(|&g| factors.iter().all(|&q| pow_mod(g, (p - 1) / q, p) != 1))
+        .unwrap()
+}
+
+const fn pow_mod(base: u64, mut exp: u64, p: u64) -> u64 {
+    let p = p as u128;
+    let mut base = base as u128 % p;
+    let mut result = 1 % p;
+    while exp > 0 {
+        if exp & 1 == 1 {
+            result = result * base % p;
+        }
+        base = base * base % p;
+        exp >>= 1;
+    }
+    result as u64
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::fps_mul;
+    use rand::rngs::StdRng;
+    use rand::Rng;
+    use rand::SeedableRng;
+
+    fn brute_is_primitive_root(p: u64, g: u64) -> bool {
+        let mut x = 1;
+        (1..p).all(|i| {
+            x = x * g % p;
+            (x == 1) == (i == p - 1)
+        })
+    }
+
+    #[test]
+    fn test_small_primes() {
+        for p in (2..300u64).filter(|&p| (2..p).all(|d| p % d != 0)) {
+            for g in 0..2 * p {
+                assert_eq!(
+                    is_primitive_root(p, g),
+                    brute_is_primitive_root(p, g % p),
+                    "{:?}",
+                    (p, g)
+                );
+            }
+            let g = find_primitive_root(p);
+            assert!(brute_is_primitive_root(p, g));
+            assert!((1..g).all(|h| !brute_is_primitive_root(p, h)));
+        }
+    }
+
+    fn test_prime<const P: u64>()
+    where
+        (): PrimitiveRoot<P>,
+    {
+        let g = <() as PrimitiveRoot<P>>::VALUE.value();
+        assert_eq!(find_primitive_root(P), g);
+        let mut rng = StdRng::seed_from_u64(42);
+        for _ in 0..20 {
+            let n: usize = rng.gen_range(0..=40);
+            let m: usize = rng.gen_range(0..=40);
+            let a = (0..n)
+                .map(|_| Fp::<P>::new(rng.gen_range(0..P)))
+                .collect::<Vec<_>>();
+            let b = (0..m)
+                .map(|_| Fp::<P>::new(rng.gen_range(0..P)))
+                .collect::<Vec<_>>();
+            // The product is empty if either operand is empty.
+            let len = if n == 0 || m == 0 { 0 } else { n + m - 1 };
+            let mut expected = vec![Fp::new(0); len];
+            for (i, &x) in a.iter().enumerate() {
+                for (j, &y) in b.iter().enumerate() {
+                    expected[i + j] += x * y;
+                }
+            }
+            assert_eq!(fps_mul(&a, &b), expected);
+        }
+        let a = [Fp::<P>::new(1); 3];
+        assert!(fps_mul(a, []).is_empty());
+        assert!(fps_mul([], a).is_empty());
+    }
+
+    #[test]
+    fn test_ntt_primes() {
+        test_prime::<167772161>();
+        test_prime::<469762049>();
+        test_prime::<754974721>();
+        test_prime::<924844033>();
+        test_prime::<998244353>();
+        test_prime::<1004535809>();
+        test_prime::<1012924417>();
+        test_prime::<1224736769>();
+        test_prime::<2013265921>();
+    }
+}
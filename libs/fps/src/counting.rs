@@ -0,0 +1,244 @@
+use crate::fps_exp;
+use crate::fps_inv;
+use crate::polynomial_taylor_shift;
+use fp::fps_mul;
+use fp::Factorial;
+use fp::Fp;
+use fp::PrimitiveRoot;
+
+/// Returns the Stirling numbers of the second kind $S(n, 0), S(n, 1), \dots, S(n, n)$.
+///
+/// By the inclusion–exclusion principle, $S(n, k) = \sum _ {i + j = k} \frac{(-1)^i}{i!}
+/// \frac{j^n}{j!}$, which is one convolution.
+///
+/// # Requirements
+/// $n < P$
+///
+/// # Complexity
+/// $\mathcal{M}(n) + O(n \log n)$
+///
+/// # Examples
+/// ```
+/// use fp::fp;
+/// use fps::fps;
+/// use fps::stirling_second_row;
+/// assert_eq!(stirling_second_row::<998244353>(4), fps![0, 1, 7, 6, 1]);
+/// ```
+pub fn stirling_second_row<const P: u64>(n: usize) -> Vec<Fp<P>>
+where
+    (): PrimitiveRoot<P>,
+{
+    assert!((n as u64) < P, "n must be less than P.");
+    let fact = Factorial::<P>::new(n);
+    let a = (0..=n)
+        .map(|i| Fp::sign(i) * fact.inv_fact(i))
+        .collect::<Vec<_>>();
+    let b = (0..=n)
+        .map(|j| Fp::from(j).pow(n as u64) * fact.inv_fact(j))
+        .collect::<Vec<_>>();
+    let mut s = fps_mul(a, b);
+    s.truncate(n + 1);
+    s
+}
+
+/// Returns the unsigned Stirling numbers of the first kind $\left[ {n \atop 0} \right], \left[
+/// {n \atop 1} \right], \dots, \left[ {n \atop n} \right]$, the coefficients of the rising factorial
+/// $x (x + 1) \cdots (x + n - 1)$.
+///
+/// The signed ones are obtained by multiplying $(-1)^{n - k}$.
+///
+/// Let $f _ m(x)$ be the product of the first $m$ factors. Then $f _ {2m}(x) = f _ m(x) f _ m(x +
+/// m)$, so it doubles $m$ with a Taylor shift and a multiplication.
+///
+/// # Requirements
+/// $n < P$
+///
+/// # Complexity
+/// $O(\mathcal{M}(n))$
+///
+/// # Examples
+/// ```
+/// use fp::fp;
+/// use fps::fps;
+/// use fps::stirling_first_row;
+/// // x (x + 1) (x + 2) (x + 3) = 6x + 11x^2 + 6x^3 + x^4
+/// assert_eq!(stirling_first_row::<998244353>(4), fps![0, 6, 11, 6, 1]);
+/// ```
+pub fn stirling_first_row<const P: u64>(n: usize) -> Vec<Fp<P>>
+where
+    (): PrimitiveRoot<P>,
+{
+    assert!((n as u64) < P, "n must be less than P.");
+    let mut f = vec![Fp::new(1)];
+    for i in (0..usize::BITS - n.leading_zeros()).rev() {
+        let m = f.len() - 1;
+        let shifted = polynomial_taylor_shift(&f, Fp::from(m));
+        f = fps_mul(&f, shifted);
+        if n >> i & 1 == 1 {
+            // Multiplies $x + 2m$.
+            let c = Fp::from(2 * m);
+            let mut g = vec![Fp::new(0); f.len() + 1];
+            for (k, &x) in f.iter().enumerate() {
+                g[k] += x * c;
+                g[k + 1] += x;
+            }
+            f = g;
+        }
+    }
+    f
+}
+
+/// Returns the numbers of the partitions $p(0), p(1), \dots, p(d - 1)$.
+///
+/// By Euler's pentagonal number theorem, $\sum _ n p(n) x^n$ is the inverse of $\sum _ {k \in
+/// \mathbb{Z}} (-1)^k x^{k (3k - 1) / 2}$.
+///
+/// # Complexity
+/// $2\mathcal{M}(d)$
+///
+/// # Examples
+/// ```
+/// use fp::fp;
+/// use fps::fps;
+/// use fps::partition_table;
+/// assert_eq!(partition_table::<998244353>(7), fps![1, 1, 2, 3, 5, 7, 11]);
+/// ```
+pub fn partition_table<const P: u64>(precision: usize) -> Vec<Fp<P>>
+where
+    (): PrimitiveRoot<P>,
+{
+    let mut f = vec![Fp::new(0); precision.max(1)];
+    f[0] = Fp::new(1);
+    for k in 1.. {
+        let a = k * (3 * k - 1) / 2;
+        if a >= precision {
+            break;
+        }
+        f[a] += Fp::sign(k);
+        if a + k < precision {
+            f[a + k] += Fp::sign(k);
+        }
+    }
+    fps_inv(f, precision)
+}
+
+/// Returns the Bell numbers $B _ 0, B _ 1, \dots, B _ {d - 1}$.
+///
+/// The exponential generating function is $\exp (e^x - 1)$.
+///
+/// # Requirements
+/// $d \le P$
+///
+/// # Complexity
+/// $\left(10+2/3\right)\mathcal{M}(d)$
+///
+/// # Examples
+/// ```
+/// use fp::fp;
+/// use fps::bell_numbers;
+/// use fps::fps;
+/// assert_eq!(bell_numbers::<998244353>(6), fps![1, 1, 2, 5, 15, 52]);
+/// ```
+pub fn bell_numbers<const P: u64>(precision: usize) -> Vec<Fp<P>>
+where
+    (): PrimitiveRoot<P>,
+{
+    assert!(
+        precision <= P as usize,
+        "The precision must be less than P."
+    );
+    if precision == 0 {
+        return vec![];
+    }
+    let fact = Factorial::<P>::new(precision - 1);
+    let f = (0..precision)
+        .map(|i| if i == 0 { Fp::new(0) } else { fact.inv_fact(i) })
+        .collect::<Vec<_>>();
+    let mut g = fps_exp(f, precision);
+    for (i, x) in g.iter_mut().enumerate() {
+        *x *= fact.fact(i);
+    }
+    g
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const P: u64 = 998244353;
+    type Fp = fp::Fp<P>;
+
+    const N: usize = 60;
+
+    // The tables of $S(n, k)$ and $\left[ {n \atop k} \right]$ for $n \le N$ by the recurrences.
+    fn stirling_tables() -> (Vec<Vec<Fp>>, Vec<Vec<Fp>>) {
+        let mut second = vec![vec![Fp::new(0); N + 1]; N + 1];
+        let mut first = vec![vec![Fp::new(0); N + 1]; N + 1];
+        second[0][0] = Fp::new(1);
+        first[0][0] = Fp::new(1);
+        for n in 1..=N {
+            for k in 1..=n {
+                second[n][k] = Fp::from(k) * second[n - 1][k] + second[n - 1][k - 1];
+                first[n][k] = Fp::from(n - 1) * first[n - 1][k] + first[n - 1][k - 1];
+            }
+        }
+        (second, first)
+    }
+
+    #[test]
+    fn test_stirling() {
+        let (second, first) = stirling_tables();
+        for n in 0..=N {
+            assert_eq!(stirling_second_row::<P>(n), second[n][..=n]);
+            assert_eq!(stirling_first_row::<P>(n), first[n][..=n]);
+        }
+    }
+
+    #[test]
+    fn test_stirling_first_large() {
+        // Substituting $x = 1$ gives $n!$, and $x = -1$ gives $0$ for $n \ge 2$.
+        for n in [100, 1000, 1023, 1024, 1025, 5000] {
+            let f = stirling_first_row::<P>(n);
+            assert_eq!(f.len(), n + 1);
+            let fact = (1..=n).map(Fp::from).product::<Fp>();
+            assert_eq!(f.iter().sum::<Fp>(), fact);
+            assert_eq!(
+                f.iter()
+                    .enumerate()
+                    .map(|(k, &x)| Fp::sign(k) * x)
+                    .sum::<Fp>(),
+                Fp::new(0)
+            );
+        }
+    }
+
+    #[test]
+    fn test_partition_table() {
+        // Coin change with the coins $1, 2, \dots$
+        let mut expected = vec![Fp::new(0); N];
+        expected[0] = Fp::new(1);
+        for s in 1..N {
+            for i in s..N {
+                let x = expected[i - s];
+                expected[i] += x;
+            }
+        }
+        for precision in 0..=N {
+            assert_eq!(partition_table::<P>(precision), expected[..precision]);
+        }
+        // p(100) = 190569292
+        assert_eq!(partition_table::<P>(101)[100], Fp::new(190569292));
+    }
+
+    #[test]
+    fn test_bell_numbers() {
+        let (second, _) = stirling_tables();
+        let expected = second
+            .iter()
+            .map(|row| row.iter().sum::<Fp>())
+            .collect::<Vec<_>>();
+        for precision in 0..=N {
+            assert_eq!(bell_numbers::<P>(precision), expected[..precision]);
+        }
+    }
+}
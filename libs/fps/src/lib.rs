@@ -46,15 +46,62 @@
 //! | [`fps_sqrt`] | $6\mathcal{M}(d)$ |
 //! | [`fps_deriv`] | $O(d)$ |
 //! | [`fps_int`] | $O(d)$ |
+//! | [`fps_derivative`] | $O(n)$ |
+//! | [`fps_integral`] | $O(n)$ |
 //! | [`fps_log`] | $3\mathcal{M}(d)$ |
 //! | [`fps_exp`] | $(10+2/3)\mathcal{M}(d)$ |
+//! | [`fps_pow`] | $(13+2/3)\mathcal{M}(d)$ |
+//! | [`fps_mul_sparse`] | $O(dk)$ |
+//! | [`fps_inv_sparse`] | $O(dk)$ |
+//! | [`fps_div_sparse`] | $O(dk)$ |
+//! | [`fps_exp_sparse`] | $O(dk)$ |
+//! | [`fps_pow_sparse`] | $O(dk)$ |
+//!
+//! Here $k$ is the number of terms of a sparse formal power series, which is represented as a
+//! list of pairs $(i, f_i)$ of the nonzero terms in strictly increasing order of exponents.
+//!
+//! [`multipoint_eval`] evaluates a polynomial at many points with a [`SubproductTree`] in
+//! $O(\mathcal{M}(n) + \mathcal{M}(m) \log m)$ time, where [`poly_div_rem`] divides
+//! polynomials in $O(\mathcal{M}(n))$ time.
+//!
+//! [`SeriesInverse`] caches the inverse of a fixed series for repeated inversions and divisions
+//! at growing precisions.
+//!
+//! [`polynomial_taylor_shift`] computes $f(x + c)$ in $\mathcal{M}(n)$ time.
+//!
+//! [`fps_product`] multiplies many polynomials in $O(N \log N \log k)$ time.
+//!
+//! [`berlekamp_massey`] finds the shortest linear recurrence of a sequence, and
+//! [`kth_term_of_linear_recurrence`] computes a far term of it by the Bostan–Mori algorithm.
+//!
+//! [`stirling_first_row`], [`stirling_second_row`], [`partition_table`] and [`bell_numbers`]
+//! generate the classical counting sequences.
+
+mod counting;
+mod linear_recurrence;
+mod multipoint;
+mod product;
+mod series_inverse;
+mod taylor_shift;
 
+pub use counting::bell_numbers;
+pub use counting::partition_table;
+pub use counting::stirling_first_row;
+pub use counting::stirling_second_row;
 use fp::fft;
 use fp::fps_mul;
 use fp::ifft;
 use fp::Fp;
 use fp::PrimitiveRoot;
+pub use linear_recurrence::berlekamp_massey;
+pub use linear_recurrence::kth_term_of_linear_recurrence;
+pub use multipoint::multipoint_eval;
+pub use multipoint::poly_div_rem;
+pub use multipoint::SubproductTree;
+pub use product::fps_product;
+pub use series_inverse::SeriesInverse;
 use std::iter::repeat;
+pub use taylor_shift::polynomial_taylor_shift;
 
 /// Define a formal power series in the same way as `vec!`.
 ///
@@ -227,20 +274,75 @@ where
 {
     let f = f.as_ref();
     let mut g = vec![Fp::new(0); precision];
-    let mut invs = vec![0i64];
-    for ((i, g), f) in g.iter_mut().enumerate().skip(1).zip(f) {
-        let inv_i = if i == 1 {
-            1
-        } else {
-            let q = P as usize / i;
-            let r = P as usize - q * i;
-            -(q as i64) * invs[r] % P as i64
-        };
-        invs.push(inv_i);
-        *g = f * Fp::from(inv_i);
+    let invs = inverses::<P>(f.len().min(precision.saturating_sub(1)));
+    for ((g, f), inv) in g.iter_mut().skip(1).zip(f).zip(&invs[1..]) {
+        *g = f * inv;
     }
     g
 }
+/// Returns the derivative of a polynomial.
+///
+/// Unlike [`fps_deriv`], the length of the result is one less than that of $f$, so the derivative
+/// of a constant, or of the empty polynomial, is empty.
+///
+/// # Complexity
+/// $O(n)$
+///
+/// # Examples
+/// ```
+/// use fp::fp;
+/// use fps::fps;
+/// use fps::fps_derivative;
+/// assert_eq!(fps_derivative::<998244353>(fps![1, 2, 3]), fps![2, 6]);
+/// assert_eq!(fps_derivative::<998244353>(fps![1]), fps![]);
+/// ```
+pub fn fps_derivative<const P: u64>(f: impl AsRef<[Fp<P>]>) -> Vec<Fp<P>> {
+    let f = f.as_ref();
+    f.iter()
+        .enumerate()
+        .skip(1)
+        .map(|(i, &f)| f * Fp::from(i))
+        .collect()
+}
+/// Returns the integral of a polynomial with zero constant term.
+///
+/// Unlike [`fps_int`], the length of the result is one more than that of $f$, so the integral of
+/// the empty polynomial is $\lbrack 0 \rbrack$. The inverses of $1, \dots, n$ are computed at once
+/// in $O(n)$ time.
+///
+/// # Requirements
+/// $n < P$
+///
+/// # Complexity
+/// $O(n)$
+///
+/// # Examples
+/// ```
+/// use fp::fp;
+/// use fps::fps;
+/// use fps::fps_integral;
+/// assert_eq!(fps_integral::<998244353>(fps![2, 6]), fps![0, 2, 3]);
+/// assert_eq!(fps_integral::<998244353>(fps![]), fps![0]);
+/// ```
+pub fn fps_integral<const P: u64>(f: impl AsRef<[Fp<P>]>) -> Vec<Fp<P>> {
+    let f = f.as_ref();
+    assert!((f.len() as u64) < P, "The length must be less than P.");
+    let invs = inverses::<P>(f.len());
+    std::iter::once(Fp::new(0))
+        .chain(f.iter().zip(&invs[1..]).map(|(&f, &inv)| f * inv))
+        .collect()
+}
+// Returns $0, 1 ^ {-1}, \dots, n ^ {-1}$ by $i ^ {-1} = -\lfloor P / i \rfloor (P \bmod i) ^ {-1}$.
+fn inverses<const P: u64>(n: usize) -> Vec<Fp<P>> {
+    let mut invs = vec![Fp::new(0); n + 1];
+    if n >= 1 {
+        invs[1] = Fp::new(1);
+    }
+    for i in 2..=n {
+        invs[i] = -Fp::new(P / i as u64) * invs[(P % i as u64) as usize];
+    }
+    invs
+}
 /// Returns the logarithm of a formal power series.
 ///
 /// Note that
@@ -297,6 +399,15 @@ where
 /// # Complexity
 /// It takes $\left(10+2/3\right)\mathcal{M}(d) + O(d)$ time because it performs
 /// a logarithm $(4\mathcal{M}(d))$ and four double-precision FFTs$(\frac{4}{3}\mathcal{M}(d))$ in each iteration.
+///
+/// # Examples
+/// ```
+/// use fp::fp;
+/// use fps::fps;
+/// use fps::fps_exp;
+/// let g = fps_exp::<998244353>(fps![0, 1], 4);
+/// assert_eq!(g, vec![fp!(1), fp!(1), fp!(2).inv(), fp!(6).inv()]);
+/// ```
 pub fn fps_exp<const P: u64>(f: impl AsRef<[Fp<P>]>, precision: usize) -> Vec<Fp<P>>
 where
     (): PrimitiveRoot<P>,
@@ -307,7 +418,10 @@ where
         Fp::new(0),
         "The constant term must be zero."
     );
-    assert!(precision <= P as usize,);
+    assert!(
+        precision <= P as usize,
+        "The precision must be less than P."
+    );
     let mut g = vec![Fp::new(1)];
     while g.len() < precision {
         g = {
@@ -348,6 +462,11 @@ where
 /// f^a = \exp (a \log f)
 /// $$
 ///
+/// If the lowest nonzero term of $f$ is $c x^v$, this computes $c^a x^{va} (x^{-v} f / c)^a$ in
+/// this way, and returns zero without it if $va \ge d$.
+/// Since $\binom{a}{i} \equiv \binom{a \bmod P}{i} \pmod P$ for $i < P$, $a$ may be as large as
+/// `u64::MAX`.
+///
 /// # Requirements
 /// $d \le P$
 ///
@@ -362,8 +481,10 @@ where
 /// use fps::fps_pow;
 /// let g = fps_pow::<998244353>([fp!(1), fp!(2)], 3, 4);
 /// assert_eq!(g, fps![1, 6, 12, 8]);
+/// let g = fps_pow::<998244353>([fp!(0), fp!(0), fp!(1)], 1 << 60, 4);
+/// assert_eq!(g, fps![0, 0, 0, 0]);
 /// ```
-pub fn fps_pow<const P: u64>(f: impl AsRef<[Fp<P>]>, pow: usize, precision: usize) -> Vec<Fp<P>>
+pub fn fps_pow<const P: u64>(f: impl AsRef<[Fp<P>]>, pow: u64, precision: usize) -> Vec<Fp<P>>
 where
     (): PrimitiveRoot<P>,
 {
@@ -380,14 +501,15 @@ where
         "The precision must be less than P."
     );
     let leading_zeros = f.iter().take_while(|&&f| f == Fp::new(0)).count();
-    let (Some(&head), Some(precision)) = (
-        f.get(leading_zeros),
-        precision.checked_sub(leading_zeros.saturating_mul(pow)),
-    ) else {
+    let shift = (leading_zeros as u64).saturating_mul(pow);
+    let (Some(&head), Some(precision)) =
+        (f.get(leading_zeros), (precision as u64).checked_sub(shift))
+    else {
         return vec![Fp::new(0); precision];
     };
+    let precision = precision as usize;
     let head_inv = head.inv();
-    let head_pow = head.pow(pow as u64);
+    let head_pow = head.pow(pow);
     let log = fps_log(
         f[leading_zeros..]
             .iter()
@@ -398,10 +520,235 @@ where
     let mul_log = log.into_iter().map(|log_f| log_f * pow).collect::<Vec<_>>();
     let exp_mul_log = fps_exp(mul_log, precision);
     repeat(Fp::new(0))
-        .take(leading_zeros * pow)
+        .take(shift as usize)
         .chain(exp_mul_log.into_iter().map(|result| result * head_pow))
         .collect()
 }
+/// Returns the product of a formal power series and a sparse formal power series.
+///
+/// # Requirements
+/// The exponents of `sparse` are strictly increasing.
+///
+/// # Complexity
+/// $O(dk)$
+///
+/// # Examples
+/// ```
+/// use fp::fp;
+/// use fps::fps;
+/// use fps::fps_mul_sparse;
+/// let g = fps_mul_sparse::<998244353>(fps![1, 2, 3], &[(0, fp!(1)), (2, fp!(-1))], 5);
+/// assert_eq!(g, fps![1, 2, 2, -2, -3]);
+/// ```
+pub fn fps_mul_sparse<const P: u64>(
+    dense: impl AsRef<[Fp<P>]>,
+    sparse: &[(usize, Fp<P>)],
+    precision: usize,
+) -> Vec<Fp<P>> {
+    let dense = dense.as_ref();
+    assert_sparse(sparse);
+    let mut result = vec![Fp::new(0); precision];
+    for &(e, c) in sparse.iter().take_while(|&&(e, _)| e < precision) {
+        for (result, &f) in result[e..].iter_mut().zip(dense) {
+            *result += f * c;
+        }
+    }
+    result
+}
+/// Returns the multiplicative inverse of a sparse formal power series.
+///
+/// # Requirements
+/// The exponents of `f` are strictly increasing, and $f_0 \ne 0$.
+///
+/// # Complexity
+/// $O(dk)$
+///
+/// # Examples
+/// ```
+/// use fp::fp;
+/// use fps::fps;
+/// use fps::fps_inv_sparse;
+/// // Fibonacci numbers
+/// let g = fps_inv_sparse::<998244353>(&[(0, fp!(1)), (1, fp!(-1)), (2, fp!(-1))], 6);
+/// assert_eq!(g, fps![1, 1, 2, 3, 5, 8]);
+/// ```
+pub fn fps_inv_sparse<const P: u64>(f: &[(usize, Fp<P>)], precision: usize) -> Vec<Fp<P>> {
+    fps_div_sparse([Fp::new(1)], f, precision)
+}
+/// Returns the quotient $g / f$ of a formal power series by a sparse formal power series.
+///
+/// Since $f_0 h_i = g_i - \sum_{j > 0} f_j h_{i-j}$, the coefficients of $h = g / f$ are computed
+/// one by one.
+///
+/// # Requirements
+/// The exponents of `f` are strictly increasing, and $f_0 \ne 0$.
+///
+/// # Complexity
+/// $O(dk)$
+///
+/// # Examples
+/// ```
+/// use fp::fp;
+/// use fps::fps;
+/// use fps::fps_div_sparse;
+/// let h = fps_div_sparse::<998244353>(fps![1, 1], &[(0, fp!(1)), (1, fp!(-2))], 4);
+/// assert_eq!(h, fps![1, 3, 6, 12]);
+/// ```
+pub fn fps_div_sparse<const P: u64>(
+    g: impl AsRef<[Fp<P>]>,
+    f: &[(usize, Fp<P>)],
+    precision: usize,
+) -> Vec<Fp<P>> {
+    let g = g.as_ref();
+    assert_sparse(f);
+    let head_inv = match f.first() {
+        Some(&(0, head)) if head != Fp::new(0) => head.inv(),
+        _ => panic!("The constant term must be nonzero."),
+    };
+    let mut h = g
+        .iter()
+        .copied()
+        .chain(repeat(Fp::new(0)))
+        .take(precision)
+        .collect::<Vec<_>>();
+    for i in 0..precision {
+        let mut x = h[i];
+        for &(e, c) in f[1..].iter().take_while(|&&(e, _)| e <= i) {
+            x -= c * h[i - e];
+        }
+        h[i] = x * head_inv;
+    }
+    h
+}
+
+/// Returns the exponential of a sparse formal power series.
+///
+/// Since $g = \exp f$ satisfies $g' = f' g$, that is, $i g_i = \sum_j j f_j g_{i-j}$, the
+/// coefficients are computed one by one.
+///
+/// # Requirements
+/// The exponents of `f` are strictly increasing, $f_0 = 0$, and $d \le P$.
+///
+/// # Complexity
+/// $O(dk)$
+///
+/// # Examples
+/// ```
+/// use fp::fp;
+/// use fps::fps;
+/// use fps::fps_exp_sparse;
+/// // $\exp (x^2) = 1 + x^2 + x^4 / 2 + \dots$
+/// let g = fps_exp_sparse::<998244353>(&[(2, fp!(1))], 5);
+/// assert_eq!(g, vec![fp!(1), fp!(0), fp!(1), fp!(0), fp!(2).inv()]);
+/// ```
+pub fn fps_exp_sparse<const P: u64>(f: &[(usize, Fp<P>)], precision: usize) -> Vec<Fp<P>> {
+    assert_sparse(f);
+    assert!(
+        f.first().map_or(true, |&(e, c)| e > 0 || c == Fp::new(0)),
+        "The constant term must be zero."
+    );
+    assert!(
+        precision <= P as usize,
+        "The precision must be less than P."
+    );
+    if precision == 0 {
+        return vec![];
+    }
+    let invs = inverses::<P>(precision - 1);
+    let f = f
+        .iter()
+        .filter(|&&(e, _)| e > 0)
+        .map(|&(e, c)| (e, c * Fp::from(e)))
+        .collect::<Vec<_>>();
+    let mut g = vec![Fp::new(0); precision];
+    g[0] = Fp::new(1);
+    for i in 1..precision {
+        let x = f
+            .iter()
+            .take_while(|&&(e, _)| e <= i)
+            .map(|&(e, c)| c * g[i - e])
+            .sum::<Fp<P>>();
+        g[i] = x * invs[i];
+    }
+    g
+}
+/// Returns the $n$-th power of a sparse formal power series.
+///
+/// If the lowest nonzero term of $f$ is $c x^v$, then $g = (x^{-v} f)^a$ satisfies
+/// $f g' = a f' g$ after the shift, that is,
+/// $c i g_i = \sum_{j > 0} f_j (a j - (i - j)) g_{i-j}$, so the coefficients are computed one by one.
+/// As in [`fps_pow`], $a$ may be as large as `u64::MAX`.
+///
+/// # Requirements
+/// The exponents of `f` are strictly increasing, and $d \le P$.
+///
+/// # Complexity
+/// $O(dk + \log a)$
+///
+/// # Examples
+/// ```
+/// use fp::fp;
+/// use fps::fps;
+/// use fps::fps_pow_sparse;
+/// // $(x + x^3)^2 = x^2 + 2 x^4 + x^6$
+/// let g = fps_pow_sparse::<998244353>(&[(1, fp!(1)), (3, fp!(1))], 2, 7);
+/// assert_eq!(g, fps![0, 0, 1, 0, 2, 0, 1]);
+/// ```
+pub fn fps_pow_sparse<const P: u64>(
+    f: &[(usize, Fp<P>)],
+    pow: u64,
+    precision: usize,
+) -> Vec<Fp<P>> {
+    assert_sparse(f);
+    let mut g = vec![Fp::new(0); precision];
+    if pow == 0 {
+        if precision > 0 {
+            g[0] = Fp::new(1);
+        }
+        return g;
+    }
+    assert!(
+        precision <= P as usize,
+        "The precision must be less than P."
+    );
+    let Some(start) = f.iter().position(|&(_, c)| c != Fp::new(0)) else {
+        return g;
+    };
+    let (v, head) = f[start];
+    let shift = (v as u64).saturating_mul(pow);
+    let Some(len) = (precision as u64).checked_sub(shift) else {
+        return g;
+    };
+    let (shift, len) = (shift as usize, len as usize);
+    if len == 0 {
+        return g;
+    }
+    let f = f[start + 1..]
+        .iter()
+        .map(|&(e, c)| (e - v, c))
+        .collect::<Vec<_>>();
+    let invs = inverses::<P>(len - 1);
+    let head_inv = head.inv();
+    let a = Fp::new(pow);
+    let h = &mut g[shift..];
+    h[0] = head.pow(pow);
+    for i in 1..len {
+        let x = f
+            .iter()
+            .take_while(|&&(e, _)| e <= i)
+            .map(|&(e, c)| c * (a * Fp::from(e) - Fp::from(i - e)) * h[i - e])
+            .sum::<Fp<P>>();
+        h[i] = x * head_inv * invs[i];
+    }
+    g
+}
+
+fn assert_sparse<const P: u64>(sparse: &[(usize, Fp<P>)]) {
+    assert!(
+        sparse.windows(2).all(|w| w[0].0 < w[1].0),
+        "The exponents must be strictly increasing."
+    );
+}
 
 #[cfg(test)]
 mod tests {
@@ -540,6 +887,56 @@ mod tests {
         }
     }
 
+    #[test]
+    fn test_fps_derivative_integral_hand() {
+        let fps_derivative = fps_derivative::<P>;
+        let fps_integral = fps_integral::<P>;
+        assert_eq!(fps_derivative(fps![]), fps![]);
+        assert_eq!(fps_derivative(fps![5]), fps![]);
+        assert_eq!(fps_derivative(fps![5, 3]), fps![3]);
+        assert_eq!(fps_derivative(fps![1, 2, 3, 4]), fps![2, 6, 12]);
+        assert_eq!(fps_integral(fps![]), fps![0]);
+        assert_eq!(fps_integral(fps![5]), fps![0, 5]);
+        assert_eq!(fps_integral(fps![2, 6, 12]), fps![0, 2, 3, 4]);
+    }
+
+    #[test]
+    fn test_fps_derivative_integral_random() {
+        let mut rng = StdRng::seed_from_u64(42);
+        for _ in 0..100 {
+            let n = rng.gen_range(0..=200);
+            let f = (0..n)
+                .map(|_| Fp::new(rng.gen_range(0..P)))
+                .collect::<Vec<_>>();
+            let g = fps_integral(&f);
+            assert_eq!(g.len(), n + 1);
+            assert_eq!(g[0], Fp::new(0));
+            for i in 0..n {
+                assert_eq!(g[i + 1] * Fp::from(i + 1), f[i]);
+            }
+            assert_eq!(fps_derivative(&g), f);
+            // Agrees with the truncated versions.
+            assert_eq!(g, fps_int(&f, n + 1));
+            assert_eq!(fps_derivative(&f), fps_deriv(&f, n.saturating_sub(1)));
+        }
+    }
+
+    #[test]
+    fn test_fps_integral_small_modulus() {
+        // The inverses of all the nonzero elements of $\mathbb{F} _ {13}$
+        let f = vec![fp::Fp::<13>::new(1); 12];
+        let g = fps_integral(&f);
+        for (i, &x) in g.iter().enumerate().skip(1) {
+            assert_eq!(x * fp::Fp::<13>::new(i as u64), fp::Fp::new(1));
+        }
+    }
+
+    #[test]
+    #[should_panic(expected = "The length must be less than P.")]
+    fn test_fps_integral_too_long() {
+        fps_integral(vec![fp::Fp::<13>::new(1); 13]);
+    }
+
     #[test]
     fn test_fps_log_hand() {
         let fps_log = fps_log::<P>;
@@ -644,6 +1041,29 @@ mod tests {
         }
     }
 
+    #[test]
+    fn test_fps_exp_log_roundtrip() {
+        let mut rng = StdRng::seed_from_u64(42);
+        for _ in 0..100 {
+            // Not always a power of two, and the input may be shorter than the precision.
+            let precision = rng.gen_range(1..=70);
+            let len = rng.gen_range(1..=precision);
+            let f = random_fps(&mut rng, fp!(1), len);
+            let mut padded = f.clone();
+            padded.resize(precision, Fp::new(0));
+            let log_f = fps_log(&f, precision);
+            assert_eq!(log_f, fps_log(&padded, precision));
+            assert_eq!(fps_exp(&log_f, precision), padded);
+
+            let g = random_fps(&mut rng, fp!(0), len);
+            let mut padded = g.clone();
+            padded.resize(precision, Fp::new(0));
+            let exp_g = fps_exp(&g, precision);
+            assert_eq!(exp_g, fps_exp(&padded, precision));
+            assert_eq!(fps_log(&exp_g, precision), padded);
+        }
+    }
+
     #[test]
     fn test_fps_pow_hand() {
         let fps_pow = fps_pow::<P>;
@@ -662,7 +1082,7 @@ mod tests {
 
     #[test]
     fn test_fps_pow_random() {
-        fn pow_naive(f: &[Fp], pow: usize) -> Vec<Fp> {
+        fn pow_naive(f: &[Fp], pow: u64) -> Vec<Fp> {
             let precision = f.len();
             let mut result = vec![Fp::new(0); precision];
             result[0] = fp!(1);
@@ -688,4 +1108,250 @@ mod tests {
             assert_eq!(g, expected);
         }
     }
+
+    #[test]
+    fn test_fps_pow_huge() {
+        // By repeated squaring
+        fn pow_naive(f: &[Fp], mut pow: u64, precision: usize) -> Vec<Fp> {
+            let mut result = vec![Fp::new(0); precision];
+            if precision == 0 {
+                return result;
+            }
+            result[0] = fp!(1);
+            let mut f = f.to_vec();
+            f.resize(precision, Fp::new(0));
+            while pow != 0 {
+                if pow & 1 == 1 {
+                    result = fps_mul(&result, &f);
+                    result.truncate(precision);
+                }
+                f = fps_mul(&f, &f);
+                f.truncate(precision);
+                pow >>= 1;
+            }
+            result
+        }
+        let mut rng = StdRng::seed_from_u64(42);
+        for _ in 0..100 {
+            let precision: usize = rng.gen_range(0..=30);
+            let leading_zeros = rng.gen_range(0..=precision.min(3));
+            let len = rng.gen_range(0..=precision + 2);
+            let f = repeat(fp!(0))
+                .take(leading_zeros)
+                .chain(repeat_with(|| Fp::new(rng.gen_range(0..100))))
+                .take(len)
+                .collect::<Vec<_>>();
+            // Around multiples of $P$ and the threshold of $va \ge d$
+            let pow = match rng.gen_range(0..4) {
+                0 => rng.gen_range(0..=u64::MAX),
+                1 => P * rng.gen_range(1..=4_u64) + rng.gen_range(0..=2_u64),
+                2 => (precision / leading_zeros.max(1)) as u64 + rng.gen_range(0..=1_u64),
+                _ => rng.gen_range(0..=40),
+            };
+            let g = fps_pow(&f, pow, precision);
+            assert_eq!(g.len(), precision);
+            assert_eq!(g, pow_naive(&f, pow, precision));
+        }
+        assert_eq!(fps_pow::<P>(fps![0, 1], u64::MAX, 4), fps![0, 0, 0, 0]);
+        assert_eq!(fps_pow::<P>(fps![0, 0], 0, 3), fps![1, 0, 0]);
+        assert_eq!(fps_pow::<P>(fps![], 0, 3), fps![1, 0, 0]);
+        assert_eq!(fps_pow::<P>(fps![], 5, 3), fps![0, 0, 0]);
+    }
+
+    #[test]
+    #[ignore]
+    fn test_fps_pow_capacity() {
+        let mut rng = StdRng::seed_from_u64(42);
+        let precision = 500_000;
+        let f = repeat(fp!(0))
+            .take(3)
+            .chain(repeat_with(|| Fp::new(rng.gen_range(0..P))))
+            .take(precision)
+            .collect::<Vec<_>>();
+        let start = std::time::Instant::now();
+        let g = fps_pow(&f, 100_000, precision);
+        let elapsed = start.elapsed();
+        assert_eq!(g[300_000], f[3].pow(100_000));
+        assert!(elapsed.as_secs() < 10, "took {:?}", elapsed);
+    }
+
+    fn random_sparse(rng: &mut StdRng, head: Option<Fp>, max_exponent: usize) -> Vec<(usize, Fp)> {
+        let density = rng.gen_range(0.0..=1.0);
+        let mut sparse = head.map(|head| (0, head)).into_iter().collect::<Vec<_>>();
+        for e in head.is_some() as usize..=max_exponent {
+            if rng.gen_bool(density) {
+                sparse.push((e, Fp::new(rng.gen_range(0..100))));
+            }
+        }
+        sparse
+    }
+
+    fn densify(sparse: &[(usize, Fp)]) -> Vec<Fp> {
+        let mut f = vec![Fp::new(0); sparse.last().map_or(0, |&(e, _)| e + 1)];
+        for &(e, c) in sparse {
+            f[e] = c;
+        }
+        f
+    }
+
+    fn resized(mut f: Vec<Fp>, precision: usize) -> Vec<Fp> {
+        f.resize(precision, Fp::new(0));
+        f
+    }
+
+    #[test]
+    fn test_fps_mul_sparse_random() {
+        let mut rng = StdRng::seed_from_u64(42);
+        for _ in 0..200 {
+            let precision = rng.gen_range(0..40);
+            let (head, len) = (fp!(rng.gen_range(0..100)), rng.gen_range(0..40));
+            let dense = random_fps(&mut rng, head, len);
+            let head = rng.gen_bool(0.5).then(|| fp!(rng.gen_range(0..100)));
+            let max_exponent = rng.gen_range(0..60);
+            let sparse = random_sparse(&mut rng, head, max_exponent);
+            let result = fps_mul_sparse(&dense, &sparse, precision);
+            let expected = resized(fps_mul(&dense, densify(&sparse)), precision);
+            assert_eq!(result, expected);
+        }
+        assert_eq!(fps_mul_sparse::<P>(fps![], &[(3, fp!(1))], 2), fps![0, 0]);
+        assert_eq!(fps_mul_sparse::<P>(fps![1, 2], &[], 3), fps![0, 0, 0]);
+        assert_eq!(fps_mul_sparse::<P>(fps![1, 2], &[(0, fp!(3))], 0), fps![]);
+    }
+
+    #[test]
+    fn test_fps_inv_sparse_random() {
+        let mut rng = StdRng::seed_from_u64(42);
+        for _ in 0..200 {
+            let precision = rng.gen_range(0..40);
+            let (head, max_exponent) = (fp!(rng.gen_range(1..100)), rng.gen_range(0..60));
+            let f = random_sparse(&mut rng, Some(head), max_exponent);
+            let expected = fps_inv(densify(&f), precision);
+            assert_eq!(fps_inv_sparse(&f, precision), expected);
+        }
+    }
+
+    #[test]
+    fn test_fps_exp_sparse_random() {
+        let mut rng = StdRng::seed_from_u64(42);
+        for _ in 0..200 {
+            let precision = rng.gen_range(0..40);
+            let head = rng.gen_bool(0.5).then(|| fp!(0));
+            let max_exponent = rng.gen_range(0..60);
+            let mut f = random_sparse(&mut rng, head, max_exponent);
+            f.retain(|&(e, c)| e > 0 || c == fp!(0));
+            let expected = fps_exp(densify(&f), precision);
+            assert_eq!(fps_exp_sparse(&f, precision), expected);
+        }
+    }
+
+    #[test]
+    fn test_fps_exp_sparse_partitions() {
+        // The number of the partitions into parts 1, 2 and 5, by
+        // $\prod 1 / (1 - x^s) = \exp (\sum_s \sum_k x^{sk} / k)$ and directly.
+        const N: usize = 30;
+        let mut log = vec![fp!(0); N];
+        for s in [1, 2, 5] {
+            for k in 1..=(N - 1) / s {
+                log[s * k] += Fp::from(k).inv();
+            }
+        }
+        let sparse = log
+            .iter()
+            .enumerate()
+            .filter(|&(_, &c)| c != fp!(0))
+            .map(|(e, &c)| (e, c))
+            .collect::<Vec<_>>();
+        let mut expected = vec![fp!(0); N];
+        expected[0] = fp!(1);
+        for s in [1, 2, 5] {
+            for i in s..N {
+                let x = expected[i - s];
+                expected[i] += x;
+            }
+        }
+        assert_eq!(fps_exp_sparse(&sparse, N), expected);
+    }
+
+    #[test]
+    fn test_fps_pow_sparse_random() {
+        let mut rng = StdRng::seed_from_u64(42);
+        for _ in 0..300 {
+            let precision: usize = rng.gen_range(0..40);
+            let head = rng.gen_bool(0.5).then(|| fp!(rng.gen_range(0..100)));
+            let max_exponent = rng.gen_range(0..60);
+            let mut f = random_sparse(&mut rng, head, max_exponent);
+            // Shifts the lowest term.
+            let leading_zeros: usize = rng.gen_range(0..=3);
+            f.iter_mut().for_each(|(e, _)| *e += leading_zeros);
+            let pow = match rng.gen_range(0..4) {
+                0 => rng.gen_range(0..=u64::MAX),
+                1 => P * rng.gen_range(1..=4_u64) + rng.gen_range(0..=2_u64),
+                2 => (precision / leading_zeros.max(1)) as u64 + rng.gen_range(0..=1_u64),
+                _ => rng.gen_range(0..=40),
+            };
+            let expected = fps_pow(densify(&f), pow, precision);
+            assert_eq!(fps_pow_sparse(&f, pow, precision), expected);
+        }
+        assert_eq!(fps_pow_sparse::<P>(&[], 0, 2), fps![1, 0]);
+        assert_eq!(fps_pow_sparse::<P>(&[], 3, 2), fps![0, 0]);
+        assert_eq!(fps_pow_sparse::<P>(&[(0, fp!(0))], 3, 2), fps![0, 0]);
+        assert_eq!(fps_pow_sparse::<P>(&[(1, fp!(1))], u64::MAX, 3), fps![
+            0, 0, 0
+        ]);
+    }
+
+    #[test]
+    fn test_fps_div_sparse_random() {
+        let mut rng = StdRng::seed_from_u64(42);
+        for _ in 0..200 {
+            let precision = rng.gen_range(0..40);
+            let (head, len) = (fp!(rng.gen_range(0..100)), rng.gen_range(0..40));
+            let g = random_fps(&mut rng, head, len);
+            let (head, max_exponent) = (fp!(rng.gen_range(1..100)), rng.gen_range(0..60));
+            let f = random_sparse(&mut rng, Some(head), max_exponent);
+            let expected = resized(fps_mul(&g, fps_inv(densify(&f), precision)), precision);
+            assert_eq!(fps_div_sparse(&g, &f, precision), expected);
+        }
+    }
+
+    #[test]
+    fn test_fps_inv_sparse_dense_support() {
+        const PRECISION: usize = 40;
+        let mut rng = StdRng::seed_from_u64(42);
+        for _ in 0..20 {
+            let head = fp!(rng.gen_range(1..100));
+            let f = random_fps(&mut rng, head, PRECISION);
+            let sparse = f.iter().copied().enumerate().collect::<Vec<_>>();
+            assert_eq!(fps_inv_sparse(&sparse, PRECISION), fps_inv(&f, PRECISION));
+        }
+    }
+
+    #[test]
+    fn test_fps_inv_sparse_hand() {
+        let fps_inv_sparse = fps_inv_sparse::<P>;
+        assert_eq!(fps_inv_sparse(&[(0, fp!(1))], 0), fps![]);
+        assert_eq!(fps_inv_sparse(&[(0, fp!(1))], 3), fps![1, 0, 0]);
+        // $1 / (1 - x ^ 2 - x ^ 3)$ with precision smaller than the largest exponent
+        let f = [(0, fp!(1)), (2, fp!(-1)), (3, fp!(-1))];
+        assert_eq!(fps_inv_sparse(&f, 2), fps![1, 0]);
+        assert_eq!(fps_inv_sparse(&f, 8), fps![1, 0, 1, 1, 1, 2, 2, 3]);
+    }
+
+    #[test]
+    #[should_panic(expected = "The exponents must be strictly increasing.")]
+    fn test_fps_mul_sparse_unsorted() {
+        fps_mul_sparse::<P>(fps![1], &[(1, fp!(1)), (1, fp!(2))], 3);
+    }
+
+    #[test]
+    #[should_panic(expected = "The constant term must be nonzero.")]
+    fn test_fps_inv_sparse_zero_head() {
+        fps_inv_sparse::<P>(&[(1, fp!(1))], 3);
+    }
+
+    #[test]
+    #[should_panic(expected = "The constant term must be nonzero.")]
+    fn test_fps_inv_sparse_explicit_zero_head() {
+        fps_inv_sparse::<P>(&[(0, fp!(0)), (1, fp!(1))], 3);
+    }
 }
@@ -0,0 +1,259 @@
+use fp::fps_mul;
+use fp::Fp;
+use fp::PrimitiveRoot;
+
+/// Returns the shortest linear recurrence satisfied by $a$.
+///
+/// The result $c _ 1, \dots, c _ d$ satisfies $a _ i = \sum _ {j = 1} ^ d c _ j a _ {i - j}$ for
+/// every $d \le i < n$, which is the convention of [`kth_term_of_linear_recurrence`]. The
+/// recurrence is empty if $a$ is all zero, and it is unique if $2d \le n$.
+///
+/// # Complexity
+/// $O(n^2)$
+///
+/// # Examples
+/// ```
+/// use fp::fp;
+/// use fps::berlekamp_massey;
+/// use fps::fps;
+/// // Fibonacci numbers
+/// assert_eq!(
+///     berlekamp_massey::<998244353>(&fps![0, 1, 1, 2, 3, 5]),
+///     fps![1, 1]
+/// );
+/// assert_eq!(berlekamp_massey::<998244353>(&fps![0, 0, 0]), fps![]);
+/// ```
+pub fn berlekamp_massey<const P: u64>(a: &[Fp<P>]) -> Vec<Fp<P>> {
+    // $a _ i = \sum _ j c _ j a _ {i - j}$ holds for the current $c$ up to $i$, and $b$ is the last
+    // recurrence before the length changes, which fails at $i - m$ by the discrepancy $b _ d$.
+    let mut c = Vec::new();
+    let mut b = Vec::new();
+    let mut b_discrepancy = Fp::new(1);
+    let mut m = 1;
+    for i in 0..a.len() {
+        let discrepancy = a[i]
+            - c.iter()
+                .enumerate()
+                .map(|(j, &c)| c * a[i - 1 - j])
+                .sum::<Fp<P>>();
+        if discrepancy == Fp::new(0) {
+            m += 1;
+            continue;
+        }
+        // $c \leftarrow c + (d / b _ d) x^m (1 - b)$ cancels the discrepancy.
+        let coeff = discrepancy / b_discrepancy;
+        let old = c.clone();
+        if c.len() < b.len() + m {
+            c.resize(b.len() + m, Fp::new(0));
+        }
+        c[m - 1] += coeff;
+        for (j, &b) in b.iter().enumerate() {
+            c[m + j] -= coeff * b;
+        }
+        if 2 * old.len() <= i {
+            b = old;
+            b_discrepancy = discrepancy;
+            m = 1;
+        } else {
+            m += 1;
+        }
+    }
+    c
+}
+
+/// Returns $a _ k$ of the sequence with the initial terms $a _ 0, \dots, a _ {d - 1}$ and the
+/// recurrence $a _ i = \sum _ {j = 1} ^ d c _ j a _ {i - j}$, by the Bostan–Mori algorithm.
+///
+/// # Requirements
+/// The lengths of `init` and `c` are the same.
+///
+/// # Complexity
+/// $O(\mathcal{M}(d) \log k)$
+///
+/// # Examples
+/// ```
+/// use fp::fp;
+/// use fps::fps;
+/// use fps::kth_term_of_linear_recurrence;
+/// // Fibonacci numbers
+/// let fib = |k| kth_term_of_linear_recurrence::<998244353>(&fps![0, 1], &fps![1, 1], k);
+/// assert_eq!(fib(10), fp!(55));
+/// assert_eq!(fib(1 << 60), fp!(640076220));
+/// ```
+pub fn kth_term_of_linear_recurrence<const P: u64>(init: &[Fp<P>], c: &[Fp<P>], k: u64) -> Fp<P>
+where
+    (): PrimitiveRoot<P>,
+{
+    assert_eq!(
+        init.len(),
+        c.len(),
+        "The lengths of the initial terms and the recurrence must be the same."
+    );
+    let d = c.len();
+    if d == 0 {
+        return Fp::new(0);
+    }
+    // $a = p / q$ with $q = 1 - \sum _ j c _ j x^j$ and $\deg p < d$.
+    let mut q = vec![Fp::new(1)];
+    q.extend(c.iter().map(|&c| -c));
+    let mut p = fps_mul(init, &q);
+    p.truncate(d);
+    let mut k = k;
+    while k > 0 {
+        let q_neg = q
+            .iter()
+            .enumerate()
+            .map(|(i, &x)| if i % 2 == 0 { x } else { -x })
+            .collect::<Vec<_>>();
+        let u = fps_mul(&p, &q_neg);
+        let v = fps_mul(&q, &q_neg);
+        p = u.into_iter().skip((k % 2) as usize).step_by(2).collect();
+        q = v.into_iter().step_by(2).collect();
+        k /= 2;
+    }
+    p.first().copied().unwrap_or(Fp::new(0)) / q[0]
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rand::rngs::StdRng;
+    use rand::Rng;
+    use rand::SeedableRng;
+
+    const P: u64 = 998244353;
+
+    fn generate<const P: u64>(init: &[Fp<P>], c: &[Fp<P>], len: usize) -> Vec<Fp<P>> {
+        let mut a = init.to_vec();
+        while a.len() < len {
+            let i = a.len();
+            a.push(c.iter().enumerate().map(|(j, &c)| c * a[i - 1 - j]).sum());
+        }
+        a.truncate(len);
+        a
+    }
+
+    fn satisfies<const P: u64>(a: &[Fp<P>], c: &[Fp<P>]) -> bool {
+        (c.len()..a.len()).all(|i| {
+            a[i] == c
+                .iter()
+                .enumerate()
+                .map(|(j, &c)| c * a[i - 1 - j])
+                .sum::<Fp<P>>()
+        })
+    }
+
+    #[test]
+    fn test_recover_random_recurrence() {
+        let mut rng = StdRng::seed_from_u64(42);
+        for _ in 0..200 {
+            let d = rng.gen_range(1..=20);
+            let mut c = (0..d)
+                .map(|_| Fp::<P>::new(rng.gen_range(0..P)))
+                .collect::<Vec<_>>();
+            c[d - 1] = Fp::new(rng.gen_range(1..P));
+            let init = (0..d)
+                .map(|_| Fp::new(rng.gen_range(0..P)))
+                .collect::<Vec<_>>();
+            // Exactly $2d$ terms determine the recurrence.
+            let len = 2 * d + rng.gen_range(0..=5_usize);
+            let a = generate(&init, &c, len);
+            let result = berlekamp_massey(&a);
+            // A random sequence almost surely needs the whole recurrence.
+            assert_eq!(result, c);
+        }
+    }
+
+    #[test]
+    fn test_all_zero() {
+        for n in 0..10 {
+            assert!(berlekamp_massey(&vec![Fp::<P>::new(0); n]).is_empty());
+        }
+    }
+
+    #[test]
+    fn test_impulse() {
+        // $0, \dots, 0, 1$ needs the length $n$, more than half.
+        for n in 1..10 {
+            let mut a = vec![Fp::<P>::new(0); n];
+            a[n - 1] = Fp::new(1);
+            let c = berlekamp_massey(&a);
+            assert_eq!(c.len(), n);
+            assert!(satisfies(&a, &c));
+        }
+    }
+
+    #[test]
+    fn test_minimal_brute() {
+        // Over $\mathbb{F} _ 5$, every recurrence of each length is tried.
+        const Q: u64 = 5;
+        fn recurrences(d: usize) -> Vec<Vec<Fp<Q>>> {
+            (0..Q.pow(d as u32))
+                .map(|mut x| {
+                    (0..d)
+                        .map(|_| {
+                            let c = Fp::new(x % Q);
+                            x /= Q;
+                            c
+                        })
+                        .collect()
+                })
+                .collect()
+        }
+        let mut rng = StdRng::seed_from_u64(42);
+        for _ in 0..500 {
+            let n = rng.gen_range(0..=7);
+            let a = (0..n)
+                .map(|_| Fp::<Q>::new(rng.gen_range(0..Q)))
+                .collect::<Vec<_>>();
+            let c = berlekamp_massey(&a);
+            assert!(satisfies(&a, &c));
+            let min = (0..=n)
+                .find(|&d| recurrences(d).iter().any(|c| satisfies(&a, c)))
+                .unwrap();
+            assert_eq!(c.len(), min);
+        }
+    }
+
+    #[test]
+    fn test_kth_term() {
+        let mut rng = StdRng::seed_from_u64(42);
+        for _ in 0..100 {
+            let d = rng.gen_range(0..=10);
+            let c = (0..d)
+                .map(|_| Fp::<P>::new(rng.gen_range(0..P)))
+                .collect::<Vec<_>>();
+            let init = (0..d)
+                .map(|_| Fp::new(rng.gen_range(0..P)))
+                .collect::<Vec<_>>();
+            let a = generate(&init, &c, 200);
+            for (k, &x) in a.iter().enumerate().step_by(7) {
+                assert_eq!(kth_term_of_linear_recurrence(&init, &c, k as u64), x);
+            }
+        }
+    }
+
+    #[test]
+    fn test_black_box() {
+        // The number of the walks of length $k$ in a graph, recovered from the first terms.
+        let mut rng = StdRng::seed_from_u64(42);
+        let n = 6;
+        let adj = (0..n)
+            .map(|_| (0..n).map(|_| rng.gen_range(0..2)).collect::<Vec<u64>>())
+            .collect::<Vec<_>>();
+        let mut counts = vec![1; n];
+        let mut a = Vec::new();
+        for _ in 0..2 * n {
+            a.push(Fp::<P>::new(counts.iter().sum::<u64>()));
+            counts = (0..n)
+                .map(|i| (0..n).map(|j| adj[i][j] * counts[j]).sum())
+                .collect();
+        }
+        let c = berlekamp_massey(&a);
+        assert!(c.len() <= n);
+        let init = &a[..c.len()];
+        for (k, &x) in a.iter().enumerate() {
+            assert_eq!(kth_term_of_linear_recurrence(init, &c, k as u64), x);
+        }
+    }
+}
@@ -0,0 +1,252 @@
+use super::fps_inv;
+use fp::fps_mul;
+use fp::Fp;
+use fp::PrimitiveRoot;
+
+// Subtrees with at most this many points are evaluated by Horner's method.
+const NAIVE_THRESHOLD: usize = 32;
+
+/// Returns the quotient and the remainder of the polynomial division $f / g$.
+///
+/// The remainder has the length $\deg g$, which may have trailing zeros, unless $f$ is shorter.
+///
+/// # Requirements
+/// The last element of $g$ is nonzero.
+///
+/// # Complexity
+/// $O(\mathcal{M}(n))$
+///
+/// # Examples
+/// ```
+/// use fp::fp;
+/// use fps::fps;
+/// use fps::poly_div_rem;
+/// // x^3 + 2x + 3 = (x^2 - x + 3)(x + 1) + 0
+/// let (q, r) = poly_div_rem::<998244353>(fps![3, 2, 0, 1], fps![1, 1]);
+/// assert_eq!(q, fps![3, -1, 1]);
+/// assert_eq!(r, fps![0]);
+/// ```
+pub fn poly_div_rem<const P: u64>(
+    f: impl AsRef<[Fp<P>]>,
+    g: impl AsRef<[Fp<P>]>,
+) -> (Vec<Fp<P>>, Vec<Fp<P>>)
+where
+    (): PrimitiveRoot<P>,
+{
+    let f = f.as_ref();
+    let g = g.as_ref();
+    assert!(
+        g.last().map_or(false, |&x| x != Fp::new(0)),
+        "The leading coefficient must be nonzero."
+    );
+    if f.len() < g.len() {
+        return (Vec::new(), f.to_vec());
+    }
+    // The reversals satisfy $f^R = q^R g^R \bmod x^{n - k + 1}$.
+    let len = f.len() - g.len() + 1;
+    let f_rev = f.iter().rev().take(len).copied().collect::<Vec<_>>();
+    let g_rev = g.iter().rev().take(len).copied().collect::<Vec<_>>();
+    let mut q = fps_mul(f_rev, fps_inv(g_rev, len));
+    q.truncate(len);
+    q.reverse();
+    let qg = fps_mul(&q, g);
+    let r = f[..g.len() - 1]
+        .iter()
+        .zip(&qg)
+        .map(|(&f, &qg)| f - qg)
+        .collect();
+    (q, r)
+}
+
+/// The subproduct tree of points $x _ 0, \dots, x _ {m - 1}$, whose nodes are the products of
+/// $x - x _ i$ over segments.
+///
+/// # Examples
+/// ```
+/// use fp::fp;
+/// use fps::fps;
+/// use fps::SubproductTree;
+/// let tree = SubproductTree::<998244353>::new(&fps![1, 2, 3]);
+/// // (x - 1)(x - 2)(x - 3)
+/// assert_eq!(tree.product(), fps![-6, 11, -6, 1]);
+/// // 1 + x^2
+/// assert_eq!(tree.evaluate(fps![1, 0, 1]), fps![2, 5, 10]);
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SubproductTree<const P: u64> {
+    points: Vec<Fp<P>>,
+    // The node `i` has the children `2i` and `2i + 1`, and the leaf `size + i` is $x - x _ i$, or
+    // $1$ beyond the points.
+    nodes: Vec<Vec<Fp<P>>>,
+}
+impl<const P: u64> SubproductTree<P>
+where
+    (): PrimitiveRoot<P>,
+{
+    /// Builds the tree.
+    ///
+    /// # Complexity
+    /// $O(\mathcal{M}(m) \log m)$
+    pub fn new(points: &[Fp<P>]) -> Self {
+        let size = points.len().next_power_of_two();
+        let mut nodes = vec![vec![Fp::new(1)]; 2 * size];
+        for (node, &x) in nodes[size..].iter_mut().zip(points) {
+            *node = vec![-x, Fp::new(1)];
+        }
+        for i in (1..size).rev() {
+            nodes[i] = fps_mul(&nodes[2 * i], &nodes[2 * i + 1]);
+        }
+        Self {
+            points: points.to_vec(),
+            nodes,
+        }
+    }
+
+    /// Returns the points.
+    pub fn points(&self) -> &[Fp<P>] {
+        &self.points
+    }
+
+    /// Returns $\prod _ i (x - x _ i)$.
+    pub fn product(&self) -> &[Fp<P>] {
+        &self.nodes[1]
+    }
+
+    /// Returns $f(x _ 0), \dots, f(x _ {m - 1})$.
+    ///
+    /// # Complexity
+    /// $O(\mathcal{M}(n) + \mathcal{M}(m) \log m)$
+    pub fn evaluate(&self, f: impl AsRef<[Fp<P>]>) -> Vec<Fp<P>> {
+        let mut result = vec![Fp::new(0); self.points.len()];
+        if self.points.is_empty() {
+            return result;
+        }
+        let (_, r) = poly_div_rem(f, &self.nodes[1]);
+        self.evaluate_subtree(1, 0, self.nodes.len() / 2, r, &mut result);
+        result
+    }
+
+    // `r` is the remainder modulo the node `i`, which covers the points `start..end`.
+    fn evaluate_subtree(
+        &self,
+        i: usize,
+        start: usize,
+        end: usize,
+        r: Vec<Fp<P>>,
+        result: &mut [Fp<P>],
+    ) {
+        let n = self.points.len();
+        if n <= start {
+            return;
+        }
+        if end - start <= NAIVE_THRESHOLD {
+            for (y, &x) in result[start..end.min(n)]
+                .iter_mut()
+                .zip(&self.points[start..])
+            {
+                *y = r.iter().rev().fold(Fp::new(0), |acc, &c| acc * x + c);
+            }
+            return;
+        }
+        let mid = (start + end) / 2;
+        let (_, left) = poly_div_rem(&r, &self.nodes[2 * i]);
+        self.evaluate_subtree(2 * i, start, mid, left, result);
+        let (_, right) = poly_div_rem(&r, &self.nodes[2 * i + 1]);
+        self.evaluate_subtree(2 * i + 1, mid, end, right, result);
+    }
+}
+
+/// Returns $f(x _ 0), \dots, f(x _ {m - 1})$ using [`SubproductTree`].
+///
+/// # Complexity
+/// $O(\mathcal{M}(n) + \mathcal{M}(m) \log m)$
+///
+/// # Examples
+/// ```
+/// use fp::fp;
+/// use fps::fps;
+/// use fps::multipoint_eval;
+/// let y = multipoint_eval::<998244353>(fps![1, 2, 3], &fps![0, 1, 2, 1]);
+/// assert_eq!(y, fps![1, 6, 17, 6]);
+/// ```
+pub fn multipoint_eval<const P: u64>(f: impl AsRef<[Fp<P>]>, points: &[Fp<P>]) -> Vec<Fp<P>>
+where
+    (): PrimitiveRoot<P>,
+{
+    SubproductTree::new(points).evaluate(f)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rand::rngs::StdRng;
+    use rand::Rng;
+    use rand::SeedableRng;
+
+    const P: u64 = 998244353;
+    type Fp = fp::Fp<P>;
+
+    fn random_poly(rng: &mut StdRng, len: usize, max: u64) -> Vec<Fp> {
+        (0..len).map(|_| Fp::new(rng.gen_range(0..max))).collect()
+    }
+
+    fn eval_naive(f: &[Fp], x: Fp) -> Fp {
+        f.iter().rev().fold(Fp::new(0), |acc, &c| acc * x + c)
+    }
+
+    #[test]
+    fn test_poly_div_rem() {
+        let mut rng = StdRng::seed_from_u64(42);
+        for _ in 0..200 {
+            let n = rng.gen_range(0..=60);
+            let k = rng.gen_range(1..=60);
+            let f = random_poly(&mut rng, n, P);
+            let mut g = random_poly(&mut rng, k, P);
+            *g.last_mut().unwrap() = Fp::new(rng.gen_range(1..P));
+            let (q, r) = poly_div_rem(&f, &g);
+            assert!(r.len() < g.len());
+            let mut qg = fps_mul(&q, &g);
+            qg.resize(f.len(), Fp::new(0));
+            for (i, (&qg, &f)) in qg.iter().zip(&f).enumerate() {
+                assert_eq!(qg + r.get(i).copied().unwrap_or(Fp::new(0)), f);
+            }
+        }
+    }
+
+    #[test]
+    fn test_multipoint_eval() {
+        let mut rng = StdRng::seed_from_u64(42);
+        for _ in 0..50 {
+            let n = rng.gen_range(0..=300);
+            let m = rng.gen_range(0..=300);
+            let f = if rng.gen_bool(0.1) {
+                vec![Fp::new(0); n]
+            } else {
+                random_poly(&mut rng, n, P)
+            };
+            // Small points to make duplicates
+            let max = if rng.gen_bool(0.5) { 5 } else { P };
+            let points = random_poly(&mut rng, m, max);
+            let expected = points
+                .iter()
+                .map(|&x| eval_naive(&f, x))
+                .collect::<Vec<_>>();
+            assert_eq!(multipoint_eval(&f, &points), expected);
+        }
+    }
+
+    #[test]
+    fn test_edge_cases() {
+        assert_eq!(multipoint_eval::<P>(Vec::new(), &[]), Vec::new());
+        assert_eq!(
+            multipoint_eval::<P>(vec![Fp::new(3)], &[]),
+            Vec::<Fp>::new()
+        );
+        assert_eq!(
+            multipoint_eval::<P>(Vec::new(), &[Fp::new(1), Fp::new(1)]),
+            vec![Fp::new(0); 2]
+        );
+        let tree = SubproductTree::<P>::new(&[]);
+        assert_eq!(tree.product(), [Fp::new(1)]);
+    }
+}
@@ -0,0 +1,122 @@
+use fp::fps_mul;
+use fp::Fp;
+use fp::PrimitiveRoot;
+use std::cmp::Reverse;
+use std::collections::BinaryHeap;
+use std::mem::take;
+
+/// Returns the product of polynomials.
+///
+/// It repeatedly multiplies the two shortest polynomials, as in Huffman coding, so that every
+/// coefficient takes part in $O(\log k)$ multiplications.
+///
+/// The product of no polynomials is $1$. If some polynomial is zero, that is, empty or all zeros,
+/// it returns an empty vector without any multiplication.
+///
+/// # Complexity
+/// $O(N \log N \log k)$, where $N$ is the total length and $k$ is the number of the polynomials.
+///
+/// # Examples
+/// ```
+/// use fp::fp;
+/// use fps::fps;
+/// use fps::fps_product;
+/// // (1 + x) (1 + 2x) (1 + 3x) = 1 + 6x + 11x^2 + 6x^3
+/// let polys = vec![fps![1, 1], fps![1, 2], fps![1, 3]];
+/// assert_eq!(fps_product::<998244353>(&polys), fps![1, 6, 11, 6]);
+/// ```
+pub fn fps_product<const P: u64>(polys: &[Vec<Fp<P>>]) -> Vec<Fp<P>>
+where
+    (): PrimitiveRoot<P>,
+{
+    if polys.iter().any(|f| f.iter().all(|&x| x == Fp::new(0))) {
+        return vec![];
+    }
+    let mut polys = polys.to_vec();
+    let mut heap = polys
+        .iter()
+        .enumerate()
+        .map(|(i, f)| Reverse((f.len(), i)))
+        .collect::<BinaryHeap<_>>();
+    while heap.len() >= 2 {
+        let Reverse((_, i)) = heap.pop().unwrap();
+        let Reverse((_, j)) = heap.pop().unwrap();
+        let f = fps_mul(take(&mut polys[i]), take(&mut polys[j]));
+        heap.push(Reverse((f.len(), i)));
+        polys[i] = f;
+    }
+    heap.pop()
+        .map_or_else(|| vec![Fp::new(1)], |Reverse((_, i))| take(&mut polys[i]))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rand::rngs::StdRng;
+    use rand::Rng;
+    use rand::SeedableRng;
+
+    const P: u64 = 998244353;
+    type Fp = fp::Fp<P>;
+
+    fn random_poly(rng: &mut StdRng, len: usize) -> Vec<Fp> {
+        (0..len).map(|_| Fp::new(rng.gen_range(1..P))).collect()
+    }
+
+    fn brute(polys: &[Vec<Fp>]) -> Vec<Fp> {
+        let mut result = vec![Fp::new(1)];
+        for f in polys {
+            let mut next = vec![Fp::new(0); result.len() + f.len() - 1];
+            for (i, &x) in result.iter().enumerate() {
+                for (j, &y) in f.iter().enumerate() {
+                    next[i + j] += x * y;
+                }
+            }
+            result = next;
+        }
+        result
+    }
+
+    #[test]
+    fn test_random() {
+        let mut rng = StdRng::seed_from_u64(42);
+        for _ in 0..200 {
+            let k = rng.gen_range(0..=20);
+            let polys = (0..k)
+                .map(|_| {
+                    let len = rng.gen_range(1..=8);
+                    random_poly(&mut rng, len)
+                })
+                .collect::<Vec<_>>();
+            assert_eq!(fps_product(&polys), brute(&polys));
+        }
+    }
+
+    #[test]
+    fn test_linear_factors() {
+        // $\prod _ i (1 + a _ i x)$ gives the elementary symmetric polynomials of $a _ i$.
+        let mut rng = StdRng::seed_from_u64(42);
+        let n = 3000;
+        let a = random_poly(&mut rng, n);
+        let polys = a.iter().map(|&a| vec![Fp::new(1), a]).collect::<Vec<_>>();
+        let f = fps_product(&polys);
+        assert_eq!(f.len(), n + 1);
+        assert_eq!(f[1], a.iter().sum::<Fp>());
+        assert_eq!(f[n], a.iter().product::<Fp>());
+        // Substituting $x = 1$.
+        let expected = a.iter().map(|&a| a + 1).product::<Fp>();
+        assert_eq!(f.iter().sum::<Fp>(), expected);
+    }
+
+    #[test]
+    fn test_edge_cases() {
+        assert_eq!(fps_product::<P>(&[]), vec![Fp::new(1)]);
+        let f = vec![Fp::new(3), Fp::new(0), Fp::new(0)];
+        assert_eq!(fps_product(&[f.clone()]), f);
+        assert_eq!(fps_product(&[f.clone(), vec![]]), vec![]);
+        assert_eq!(fps_product(&[f.clone(), vec![Fp::new(0); 4], f]), vec![]);
+        // Trailing zeros are kept as in `fps_mul`.
+        let g = vec![Fp::new(1), Fp::new(1), Fp::new(0)];
+        assert_eq!(fps_product(&[g.clone(), g]).len(), 5);
+    }
+}
@@ -0,0 +1,182 @@
+use fp::fft;
+use fp::fps_mul;
+use fp::ifft;
+use fp::Fp;
+use fp::PrimitiveRoot;
+
+#[cfg(test)]
+thread_local! {
+    static FFT_WORK: std::cell::Cell<usize> = std::cell::Cell::new(0);
+}
+
+/// The multiplicative inverse of a fixed formal power series, extended on demand.
+///
+/// The coefficients computed so far are kept, and a request of a higher precision continues
+/// Newton's method from them, so requesting the precisions $d _ 1 < d _ 2 < \dots < d _ k$ costs
+/// about the same as requesting $d _ k$ alone.
+///
+/// # Examples
+/// ```
+/// use fp::fp;
+/// use fps::fps;
+/// use fps::SeriesInverse;
+/// let mut inv = SeriesInverse::<998244353>::new(fps![1, -1, -1]);
+/// // Fibonacci numbers
+/// assert_eq!(inv.inv_prefix(5), fps![1, 1, 2, 3, 5]);
+/// assert_eq!(inv.inv_prefix(8), fps![1, 1, 2, 3, 5, 8, 13, 21]);
+/// assert_eq!(inv.div_prefix(fps![0, 1], 5), fps![0, 1, 1, 2, 3]);
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SeriesInverse<const P: u64> {
+    q: Vec<Fp<P>>,
+    inv: Vec<Fp<P>>,
+}
+impl<const P: u64> SeriesInverse<P>
+where
+    (): PrimitiveRoot<P>,
+{
+    /// Constructs the inverse of $q$ of precision $1$.
+    ///
+    /// # Requirements
+    /// $q_0 \ne 0$
+    pub fn new(q: impl AsRef<[Fp<P>]>) -> Self {
+        let q = q.as_ref().to_vec();
+        assert!(
+            q.first().map_or(false, |&x| x != Fp::new(0)),
+            "The constant term must be nonzero."
+        );
+        let inv = vec![q[0].inv()];
+        Self { q, inv }
+    }
+
+    /// Returns the number of the coefficients computed so far.
+    pub fn computed(&self) -> usize {
+        self.inv.len()
+    }
+
+    /// Returns the first $d$ coefficients of $q^{-1}$.
+    ///
+    /// # Complexity
+    /// If $m$ coefficients have been computed, it takes $O(d \log d)$ time for $m < d$, and
+    /// $O(1)$ time otherwise.
+    pub fn inv_prefix(&mut self, precision: usize) -> &[Fp<P>] {
+        while self.inv.len() < precision {
+            self.double();
+        }
+        &self.inv[..precision]
+    }
+
+    /// Returns the first $d$ coefficients of $p / q$.
+    ///
+    /// # Complexity
+    /// $\mathcal{M}(d)$ besides [`SeriesInverse::inv_prefix`].
+    pub fn div_prefix(&mut self, p: impl AsRef<[Fp<P>]>, precision: usize) -> Vec<Fp<P>> {
+        let p = p.as_ref();
+        let p = &p[..p.len().min(precision)];
+        let mut result = fps_mul(p, self.inv_prefix(precision));
+        result.resize(precision, Fp::new(0));
+        result
+    }
+
+    // $g \leftarrow g(2 - qg) \bmod x^{2m}$, which only needs $g$ to be correct modulo $x^m$, so
+    // $m$ need not be a power of two.
+    fn double(&mut self) {
+        let precision = self.inv.len() * 2;
+        // $\deg g^2 q < 4m - 2$
+        let fft_size = (precision * 2).next_power_of_two();
+        #[cfg(test)]
+        FFT_WORK.with(|work| work.set(work.get() + fft_size));
+        let mut f = self.q[..self.q.len().min(precision)].to_vec();
+        f.resize(fft_size, Fp::new(0));
+        let mut g = std::mem::take(&mut self.inv);
+        g.resize(fft_size, Fp::new(0));
+        fft(&mut f);
+        fft(&mut g);
+        let mut result = f
+            .iter()
+            .zip(&g)
+            .map(|(&f, &g)| g * (-f * g + 2))
+            .collect::<Vec<_>>();
+        ifft(&mut result);
+        result.truncate(precision);
+        self.inv = result;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::fps_inv;
+    use rand::rngs::StdRng;
+    use rand::Rng;
+    use rand::SeedableRng;
+
+    const P: u64 = 998244353;
+
+    fn random_fps(rng: &mut StdRng, len: usize) -> Vec<Fp<P>> {
+        let mut f = (0..len)
+            .map(|_| Fp::new(rng.gen_range(0..P)))
+            .collect::<Vec<_>>();
+        f[0] = Fp::new(rng.gen_range(1..P));
+        f
+    }
+
+    #[test]
+    fn test_interleaved() {
+        let mut rng = StdRng::seed_from_u64(42);
+        for _ in 0..20 {
+            let len = rng.gen_range(1..=300);
+            let q = random_fps(&mut rng, len);
+            let mut inv = SeriesInverse::new(&q);
+            for _ in 0..30 {
+                // Increasing, repeated and decreasing precisions, sometimes beyond `q`
+                let precision = match rng.gen_range(0..3) {
+                    0 => (inv.computed() + rng.gen_range(0..=3_usize)).min(2 * len),
+                    1 => rng.gen_range(0..=inv.computed()),
+                    _ => rng.gen_range(0..=2 * len),
+                };
+                assert_eq!(inv.inv_prefix(precision), fps_inv(&q, precision));
+                let p = (0..rng.gen_range(0..=2 * precision))
+                    .map(|_| Fp::new(rng.gen_range(0..P)))
+                    .collect::<Vec<_>>();
+                let mut expected = fps_mul(&p, fps_inv(&q, precision));
+                expected.resize(precision, Fp::new(0));
+                assert_eq!(inv.div_prefix(&p, precision), expected);
+            }
+        }
+    }
+
+    #[test]
+    #[should_panic(expected = "The constant term must be nonzero.")]
+    fn test_empty() {
+        SeriesInverse::<P>::new([]);
+    }
+
+    fn work(f: impl FnOnce()) -> usize {
+        FFT_WORK.with(|work| work.set(0));
+        f();
+        FFT_WORK.with(|work| work.get())
+    }
+
+    #[test]
+    fn test_fft_work() {
+        let mut rng = StdRng::seed_from_u64(42);
+        let n = 1 << 10;
+        let q = random_fps(&mut rng, n);
+        let precisions = (1..=n / 8).map(|k| 8 * k).collect::<Vec<_>>();
+        let incremental = work(|| {
+            let mut inv = SeriesInverse::new(&q);
+            for &d in &precisions {
+                inv.inv_prefix(d);
+            }
+        });
+        let restarted = work(|| {
+            for &d in &precisions {
+                SeriesInverse::new(&q).inv_prefix(d);
+            }
+        });
+        // The same as the last precision alone
+        assert!(incremental <= 4 * n * 2);
+        assert!(incremental * 20 < restarted);
+    }
+}
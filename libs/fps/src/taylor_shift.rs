@@ -0,0 +1,128 @@
+use fp::fps_mul;
+use fp::Factorial;
+use fp::Fp;
+use fp::PrimitiveRoot;
+
+/// Returns the coefficients of $f(x + c)$.
+///
+/// Since $[x^k] f(x + c) = \frac{1}{k!} \sum _ {i \ge k} f _ i i! \frac{c^{i - k}}{(i - k)!}$, this
+/// is one convolution of the reversed $f _ i i!$ and $c^j / j!$.
+///
+/// # Requirements
+/// The length of $f$ is at most $P$.
+///
+/// # Complexity
+/// $\mathcal{M}(n)$
+///
+/// # Examples
+/// ```
+/// use fp::fp;
+/// use fps::fps;
+/// use fps::polynomial_taylor_shift;
+/// // (x + 1)^2 = x^2 + 2x + 1
+/// assert_eq!(
+///     polynomial_taylor_shift::<998244353>(fps![0, 0, 1], fp!(1)),
+///     fps![1, 2, 1]
+/// );
+/// ```
+pub fn polynomial_taylor_shift<const P: u64>(f: impl AsRef<[Fp<P>]>, c: Fp<P>) -> Vec<Fp<P>>
+where
+    (): PrimitiveRoot<P>,
+{
+    let f = f.as_ref();
+    let n = f.len();
+    if n == 0 || c == Fp::new(0) {
+        return f.to_vec();
+    }
+    assert!(n as u64 <= P, "The length must be at most P.");
+    let fact = Factorial::<P>::new(n - 1);
+    let g = (0..n)
+        .rev()
+        .map(|i| f[i] * fact.fact(i))
+        .collect::<Vec<_>>();
+    let mut power = Fp::new(1);
+    let h = (0..n)
+        .map(|j| {
+            let x = power * fact.inv_fact(j);
+            power *= c;
+            x
+        })
+        .collect::<Vec<_>>();
+    let gh = fps_mul(g, h);
+    (0..n).map(|k| gh[n - 1 - k] * fact.inv_fact(k)).collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rand::rngs::StdRng;
+    use rand::Rng;
+    use rand::SeedableRng;
+
+    const P: u64 = 998244353;
+    type Fp = fp::Fp<P>;
+
+    fn random_poly(rng: &mut StdRng, len: usize) -> Vec<Fp> {
+        (0..len).map(|_| Fp::new(rng.gen_range(0..P))).collect()
+    }
+
+    // Expands $f(x + c)$ by Horner's method.
+    fn brute(f: &[Fp], c: Fp) -> Vec<Fp> {
+        let mut result = vec![Fp::new(0); f.len()];
+        for &a in f.iter().rev() {
+            // result <- result * (x + c) + a
+            for k in (0..f.len()).rev() {
+                let lower = if k == 0 { Fp::new(0) } else { result[k - 1] };
+                result[k] = result[k] * c + lower;
+            }
+            result[0] += a;
+        }
+        result
+    }
+
+    #[test]
+    fn test_taylor_shift() {
+        let mut rng = StdRng::seed_from_u64(42);
+        for _ in 0..200 {
+            let n = rng.gen_range(0..=60);
+            let f = random_poly(&mut rng, n);
+            let c = match rng.gen_range(0..4) {
+                0 => Fp::new(0),
+                1 => Fp::new(1),
+                2 => -Fp::new(1),
+                _ => Fp::new(rng.gen_range(0..P)),
+            };
+            assert_eq!(polynomial_taylor_shift(&f, c), brute(&f, c));
+        }
+    }
+
+    #[test]
+    fn test_composition() {
+        let mut rng = StdRng::seed_from_u64(42);
+        for _ in 0..20 {
+            let n = rng.gen_range(0..=1000);
+            let f = random_poly(&mut rng, n);
+            let a = Fp::new(rng.gen_range(0..P));
+            let b = Fp::new(rng.gen_range(0..P));
+            let g = polynomial_taylor_shift(&f, a);
+            assert_eq!(
+                polynomial_taylor_shift(&g, b),
+                polynomial_taylor_shift(&f, a + b)
+            );
+            assert_eq!(polynomial_taylor_shift(&g, -a), f);
+        }
+    }
+
+    #[test]
+    fn test_edge_cases() {
+        assert_eq!(
+            polynomial_taylor_shift::<P>(Vec::new(), Fp::new(3)),
+            Vec::new()
+        );
+        let f = vec![Fp::new(1), Fp::new(2), Fp::new(3)];
+        assert_eq!(polynomial_taylor_shift(&f, Fp::new(0)), f);
+        // Trailing zeros are kept.
+        let f = vec![Fp::new(5), Fp::new(0), Fp::new(0)];
+        assert_eq!(polynomial_taylor_shift(&f, Fp::new(7)), f);
+    }
+}
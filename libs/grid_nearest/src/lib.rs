@@ -0,0 +1,375 @@
+//! Finds the nearest source of every cell of a grid.
+//!
+//! Every cell gets the pair $(d, s)$ of the distance $d$ to its nearest source and the smallest
+//! index $s$ among the sources at that distance, so the regions are deterministic even if the
+//! sources tie or coincide. Unreachable cells and walls get [`UNREACHABLE`].
+//!
+//! The smallest index is propagated along the shortest paths: the nearest sources of a cell are
+//! exactly the nearest sources of its neighbors on the shortest paths, so it is enough to
+//! overwrite the pair of a cell only if it strictly decreases lexicographically.
+//!
+//! # Examples
+//!
+//! ```
+//! use grid_nearest::nearest_source;
+//! use grid_nearest::regions;
+//! use grid_nearest::UNREACHABLE;
+//! // .....
+//! // .###.
+//! // .....
+//! let grid = [".....", ".###.", "....."]
+//!     .iter()
+//!     .map(|row| row.chars().map(|c| c == '.').collect::<Vec<_>>())
+//!     .collect::<Vec<_>>();
+//! let nearest = nearest_source(&grid, &[(0, 0), (2, 4)]);
+//! assert_eq!(nearest[0], vec![(0, 0), (1, 0), (2, 0), (3, 0), (2, 1)]);
+//! assert_eq!(nearest[1][0], (1, 0));
+//! assert_eq!(nearest[1][1], UNREACHABLE);
+//! // The cell (0, 3) is at distance 3 from both sources.
+//! assert_eq!(nearest[0][3], (3, 0));
+//! assert_eq!(regions(&nearest, &[(2, 0), (1, 4), (1, 2)]), vec![
+//!     Some(0),
+//!     Some(1),
+//!     None
+//! ]);
+//! ```
+use gridnei::grid4;
+use std::cmp::Reverse;
+use std::collections::BinaryHeap;
+use std::collections::VecDeque;
+
+/// The pair of a wall or a cell unreachable from every source.
+pub const UNREACHABLE: (u32, u32) = (u32::MAX, u32::MAX);
+
+/// The pair of a wall or a cell unreachable from every source in [`nearest_source_weighted`].
+pub const UNREACHABLE_WEIGHTED: (u64, u32) = (u64::MAX, u32::MAX);
+
+/// Returns the distance to the nearest source and its smallest index for every cell, moving to
+/// the adjacent four cells.
+///
+/// # Requirements
+///
+/// - `grid[i][j]` is `true` if the cell $(i, j)$ is passable, and all the rows have the same
+///   length.
+/// - Every source is a passable cell.
+///
+/// # Complexity
+///
+/// $O(HW + K)$, where $K$ is the number of the sources.
+pub fn nearest_source(grid: &[Vec<bool>], sources: &[(usize, usize)]) -> Vec<Vec<(u32, u32)>> {
+    let (h, w) = shape(grid);
+    check_sources(sources, h, w, |i, j| grid[i][j]);
+    let mut result = vec![vec![UNREACHABLE; w]; h];
+    let mut queue = VecDeque::new();
+    // Coincident sources keep the first, i.e. the smallest, index.
+    for (s, &(i, j)) in sources.iter().enumerate() {
+        if result[i][j] == UNREACHABLE {
+            result[i][j] = (0, s as u32);
+            queue.push_back((i, j));
+        }
+    }
+    while let Some((i, j)) = queue.pop_front() {
+        let (d, s) = result[i][j];
+        for (ni, nj) in grid4(i, j, h, w) {
+            if !grid[ni][nj] {
+                continue;
+            }
+            let x = &mut result[ni][nj];
+            if *x == UNREACHABLE {
+                *x = (d + 1, s);
+                queue.push_back((ni, nj));
+            } else if (d + 1, s) < *x {
+                // Still in the queue, since its distance is $d + 1$.
+                *x = (d + 1, s);
+            }
+        }
+    }
+    result
+}
+
+/// Returns the distance to the nearest source and its smallest index for every cell, where
+/// entering the cell $(i, j)$ costs `cost[i][j]`.
+///
+/// # Requirements
+///
+/// - `cost[i][j]` is `None` if the cell $(i, j)$ is a wall, and all the rows have the same
+///   length.
+/// - Every source is a passable cell.
+/// - The distances do not overflow.
+///
+/// # Complexity
+///
+/// $O((HW + K) \log (HW + K))$, where $K$ is the number of the sources.
+pub fn nearest_source_weighted(
+    cost: &[Vec<Option<u64>>],
+    sources: &[(usize, usize)],
+) -> Vec<Vec<(u64, u32)>> {
+    let (h, w) = shape(cost);
+    check_sources(sources, h, w, |i, j| cost[i][j].is_some());
+    let mut result = vec![vec![UNREACHABLE_WEIGHTED; w]; h];
+    let mut heap = BinaryHeap::new();
+    for (s, &(i, j)) in sources.iter().enumerate() {
+        if result[i][j] == UNREACHABLE_WEIGHTED {
+            result[i][j] = (0, s as u32);
+            heap.push(Reverse((0, s as u32, i, j)));
+        }
+    }
+    while let Some(Reverse((d, s, i, j))) = heap.pop() {
+        if result[i][j] != (d, s) {
+            continue;
+        }
+        for (ni, nj) in grid4(i, j, h, w) {
+            let Some(c) = cost[ni][nj] else {
+                continue;
+            };
+            let x = (d + c, s);
+            if x < result[ni][nj] {
+                result[ni][nj] = x;
+                heap.push(Reverse((x.0, x.1, ni, nj)));
+            }
+        }
+    }
+    result
+}
+
+/// Returns the region, i.e. the index of the nearest source, of each query cell, or `None` if the
+/// cell is a wall or unreachable.
+///
+/// # Examples
+///
+/// ```
+/// use grid_nearest::nearest_source;
+/// use grid_nearest::regions;
+/// let grid = vec![vec![true; 4]];
+/// let nearest = nearest_source(&grid, &[(0, 3), (0, 0)]);
+/// assert_eq!(regions(&nearest, &[(0, 0), (0, 1), (0, 2)]), vec![
+///     Some(1),
+///     Some(1),
+///     Some(0)
+/// ]);
+/// ```
+pub fn regions<D>(nearest: &[Vec<(D, u32)>], queries: &[(usize, usize)]) -> Vec<Option<usize>> {
+    queries
+        .iter()
+        .map(|&(i, j)| {
+            let s = nearest[i][j].1;
+            (s != u32::MAX).then_some(s as usize)
+        })
+        .collect()
+}
+
+fn shape<T>(grid: &[Vec<T>]) -> (usize, usize) {
+    let h = grid.len();
+    let w = grid.first().map_or(0, Vec::len);
+    assert!(
+        grid.iter().all(|row| row.len() == w),
+        "the rows of the grid have different lengths"
+    );
+    (h, w)
+}
+
+fn check_sources(
+    sources: &[(usize, usize)],
+    h: usize,
+    w: usize,
+    passable: impl Fn(usize, usize) -> bool,
+) {
+    assert!(sources.len() < u32::MAX as usize, "too many sources");
+    for (s, &(i, j)) in sources.iter().enumerate() {
+        assert!(
+            i < h && j < w,
+            "source {} at ({}, {}) is out of the grid of size {}x{}",
+            s,
+            i,
+            j,
+            h,
+            w
+        );
+        assert!(passable(i, j), "source {} at ({}, {}) is a wall", s, i, j);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rand::rngs::StdRng;
+    use rand::Rng;
+    use rand::SeedableRng;
+
+    fn random_sources(
+        rng: &mut StdRng,
+        passable: &[(usize, usize)],
+        h: usize,
+        w: usize,
+    ) -> Vec<(usize, usize)> {
+        let k = rng.gen_range(0..=6);
+        let mut sources = Vec::new();
+        // Sources clustered around a center, sometimes on the same cell.
+        let (ci, cj) = passable[rng.gen_range(0..passable.len())];
+        for _ in 0..k {
+            let source = match rng.gen_range(0..3) {
+                0 => passable[rng.gen_range(0..passable.len())],
+                1 => (ci, cj),
+                _ => {
+                    let i = (ci + rng.gen_range(0..3_usize))
+                        .saturating_sub(1)
+                        .min(h - 1);
+                    let j = (cj + rng.gen_range(0..3_usize))
+                        .saturating_sub(1)
+                        .min(w - 1);
+                    if passable.contains(&(i, j)) {
+                        (i, j)
+                    } else {
+                        (ci, cj)
+                    }
+                }
+            };
+            sources.push(source);
+        }
+        sources
+    }
+
+    // Single-source Dijkstra with a quadratic scan.
+    fn distances_from(cost: &[Vec<Option<u64>>], (si, sj): (usize, usize)) -> Vec<Vec<u64>> {
+        let (h, w) = (cost.len(), cost[0].len());
+        let mut dist = vec![vec![u64::MAX; w]; h];
+        let mut done = vec![vec![false; w]; h];
+        dist[si][sj] = 0;
+        while let Some((i, j)) = (0..h)
+            .flat_map(|i| (0..w).map(move |j| (i, j)))
+            .filter(|&(i, j)| !done[i][j] && dist[i][j] != u64::MAX)
+            .min_by_key(|&(i, j)| dist[i][j])
+        {
+            done[i][j] = true;
+            for (ni, nj) in grid4(i, j, h, w) {
+                if let Some(c) = cost[ni][nj] {
+                    dist[ni][nj] = dist[ni][nj].min(dist[i][j] + c);
+                }
+            }
+        }
+        dist
+    }
+
+    fn brute(cost: &[Vec<Option<u64>>], sources: &[(usize, usize)]) -> Vec<Vec<(u64, u32)>> {
+        let (h, w) = (cost.len(), cost[0].len());
+        let mut result = vec![vec![UNREACHABLE_WEIGHTED; w]; h];
+        for (s, &source) in sources.iter().enumerate() {
+            let dist = distances_from(cost, source);
+            for i in 0..h {
+                for j in 0..w {
+                    // The argmin with the smallest index on ties.
+                    if dist[i][j] != u64::MAX && (dist[i][j], s as u32) < result[i][j] {
+                        result[i][j] = (dist[i][j], s as u32);
+                    }
+                }
+            }
+        }
+        result
+    }
+
+    #[test]
+    fn test_nearest_source_random() {
+        let mut rng = StdRng::seed_from_u64(42);
+        for _ in 0..300 {
+            let h: usize = rng.gen_range(1..=8);
+            let w: usize = rng.gen_range(1..=8);
+            let density = rng.gen_range(0.0..0.5);
+            let mut grid = (0..h)
+                .map(|_| (0..w).map(|_| !rng.gen_bool(density)).collect::<Vec<_>>())
+                .collect::<Vec<_>>();
+            grid[rng.gen_range(0..h)][rng.gen_range(0..w)] = true;
+            let passable = (0..h)
+                .flat_map(|i| (0..w).map(move |j| (i, j)))
+                .filter(|&(i, j)| grid[i][j])
+                .collect::<Vec<_>>();
+            let sources = random_sources(&mut rng, &passable, h, w);
+            let result = nearest_source(&grid, &sources);
+            let cost = grid
+                .iter()
+                .map(|row| row.iter().map(|&b| b.then_some(1)).collect::<Vec<_>>())
+                .collect::<Vec<_>>();
+            let expected = brute(&cost, &sources);
+            for i in 0..h {
+                for j in 0..w {
+                    let (d, s) = expected[i][j];
+                    let expected = if s == u32::MAX { UNREACHABLE } else { (d as u32, s) };
+                    assert_eq!(result[i][j], expected);
+                }
+            }
+            let queries = (0..10)
+                .map(|_| (rng.gen_range(0..h), rng.gen_range(0..w)))
+                .collect::<Vec<_>>();
+            let dist = sources
+                .iter()
+                .map(|&source| distances_from(&cost, source))
+                .collect::<Vec<_>>();
+            let expected = queries
+                .iter()
+                .map(|&(i, j)| {
+                    (0..sources.len())
+                        .filter(|&s| dist[s][i][j] != u64::MAX)
+                        .min_by_key(|&s| (dist[s][i][j], s))
+                })
+                .collect::<Vec<_>>();
+            assert_eq!(regions(&result, &queries), expected);
+        }
+    }
+
+    #[test]
+    fn test_nearest_source_weighted_random() {
+        let mut rng = StdRng::seed_from_u64(42);
+        for _ in 0..300 {
+            let h: usize = rng.gen_range(1..=8);
+            let w: usize = rng.gen_range(1..=8);
+            let density = rng.gen_range(0.0..0.5);
+            let max_cost = rng.gen_range(0..=5);
+            let mut cost = (0..h)
+                .map(|_| {
+                    (0..w)
+                        .map(|_| (!rng.gen_bool(density)).then(|| rng.gen_range(0..=max_cost)))
+                        .collect::<Vec<_>>()
+                })
+                .collect::<Vec<_>>();
+            cost[rng.gen_range(0..h)][rng.gen_range(0..w)] = Some(1);
+            let passable = (0..h)
+                .flat_map(|i| (0..w).map(move |j| (i, j)))
+                .filter(|&(i, j)| cost[i][j].is_some())
+                .collect::<Vec<_>>();
+            let sources = random_sources(&mut rng, &passable, h, w);
+            let result = nearest_source_weighted(&cost, &sources);
+            assert_eq!(result, brute(&cost, &sources));
+        }
+    }
+
+    #[test]
+    fn test_coincident_sources() {
+        let grid = vec![vec![true; 5]; 3];
+        let result = nearest_source(&grid, &[(1, 2), (1, 2), (0, 1)]);
+        assert_eq!(result[1][2], (0, 0));
+        assert_eq!(result[0][1], (0, 2));
+        assert_eq!(result[0][0], (1, 2));
+        // At distance 1 from both (1, 2) and (0, 1).
+        assert_eq!(result[0][2], (1, 0));
+        assert_eq!(result[1][1], (1, 0));
+        assert_eq!(result[2][4], (3, 0));
+    }
+
+    #[test]
+    fn test_no_sources() {
+        let grid = vec![vec![true; 3]; 2];
+        assert_eq!(nearest_source(&grid, &[]), vec![vec![UNREACHABLE; 3]; 2]);
+        assert_eq!(nearest_source(&[], &[]), Vec::<Vec<_>>::new());
+    }
+
+    #[test]
+    #[should_panic(expected = "source 1 at (0, 1) is a wall")]
+    fn test_source_on_wall() {
+        nearest_source(&[vec![true, false]], &[(0, 0), (0, 1)]);
+    }
+
+    #[test]
+    #[should_panic(expected = "source 0 at (2, 0) is out of the grid of size 1x2")]
+    fn test_source_out_of_grid() {
+        nearest_source_weighted(&[vec![Some(1), None]], &[(2, 0)]);
+    }
+}
@@ -465,7 +465,8 @@ mod tests {
     use rand::prelude::StdRng;
     use rand::Rng;
     use rand::SeedableRng;
-    use randtools::Tree;
+    use randtools::Shape;
+    use randtools::ShapedTree;
     use std::mem::swap;
     use std::usize::MAX;
 
@@ -481,7 +482,8 @@ mod tests {
         for _ in 0..20 {
             let n = rng.gen_range(1..12);
             let root = rng.gen_range(0..n);
-            let g = rng.sample(Tree(n));
+            let shape = rng.gen();
+            let g = rng.sample(ShapedTree(n, shape));
             let mut parent = vec![MAX; n];
             parent[root] = root;
             dfs(root, root, &g, &mut parent);
@@ -496,7 +498,8 @@ mod tests {
         for _ in 0..20 {
             let n = rng.gen_range(1..12);
             let root = rng.gen_range(0..n);
-            let g = rng.sample(Tree(n));
+            let shape = rng.gen();
+            let g = rng.sample(ShapedTree(n, shape));
             let dist = (0..n).map(|i| calc_dist(i, &g)).collect_vec();
             let hld = Hld::new(root, &g);
             for (i, dist_i) in dist.iter().enumerate() {
@@ -522,7 +525,8 @@ mod tests {
         for _ in 0..20 {
             let n = rng.gen_range(1..12);
             let root = rng.gen_range(0..n);
-            let g = rng.sample(Tree(n));
+            let shape = rng.gen();
+            let g = rng.sample(ShapedTree(n, shape));
             let mut height = vec![MAX; n];
             let mut parent = vec![MAX; n];
             height[root] = 1;
@@ -587,7 +591,8 @@ mod tests {
         for _ in 0..20 {
             let n = rng.gen_range(1..12);
             let root = rng.gen_range(0..n);
-            let g = rng.sample(Tree(n));
+            let shape = rng.gen();
+            let g = rng.sample(ShapedTree(n, shape));
             let dist = (0..n).map(|i| calc_dist(i, &g)).collect_vec();
             let hld = Hld::new(root, &g);
             for i in 0..n {
@@ -608,7 +613,8 @@ mod tests {
         for _ in 0..20 {
             let n = rng.gen_range(1..12);
             let root = rng.gen_range(0..n);
-            let orig_g = rng.sample(Tree(n));
+            let shape = rng.gen();
+            let orig_g = rng.sample(ShapedTree(n, shape));
             let g = orig_g.clone();
             let hld = Hld::new(root, &g);
             let a = (0..n).map(|i| 1u64 << i).collect::<Vec<_>>();
@@ -627,6 +633,33 @@ mod tests {
         }
     }
 
+    #[test]
+    fn test_deep_path() {
+        // 一様ランダムな木の直径は O(√n) 程度なので、長いパスはほぼ出ません。
+        let mut rng = StdRng::seed_from_u64(42);
+        let n = 3000;
+        let g = rng.sample(ShapedTree(n, Shape::Path));
+        let ends = (0..n).filter(|&x| g[x].len() == 1).collect_vec();
+        assert_eq!(ends.len(), 2);
+        let (s, t) = (ends[0], ends[1]);
+        let dist = calc_dist(s, &g);
+        for root in [s, t, rng.gen_range(0..n)] {
+            let hld = Hld::new(root, &g);
+            assert_eq!(hld.dist(s, t), n - 1);
+            // 根から伸びるパスは高々 2 本の heavy path です。
+            assert!(hld.iter_v(s, t).count() <= 2);
+            for _ in 0..100 {
+                let u = rng.gen_range(0..n);
+                let v = rng.gen_range(0..n);
+                assert_eq!(hld.dist(u, v), dist[u].abs_diff(dist[v]) as usize);
+                if root == s {
+                    let expected = if dist[u] <= dist[v] { u } else { v };
+                    assert_eq!(hld.lca(u, v), expected);
+                }
+            }
+        }
+    }
+
     #[test]
     fn test_hand_4vtx() {
         let n = 4;
@@ -0,0 +1,274 @@
+//! Minimizes over the ways to split intervals recursively.
+//!
+//! For $0 \le i < j \le n$, the value of the interval $\[i, j)$ is
+//!
+//! $$
+//! \mathrm{dp}(i, j) =
+//! \begin{cases}
+//! \mathrm{base}(i) & \text{if $j = i + 1$} \\\\
+//! \min _ { i < k < j } \mathrm{combine}(i, k, j, \mathrm{dp}(i, k), \mathrm{dp}(k, j))
+//! & \text{otherwise}
+//! \end{cases}
+//! $$
+//!
+//! Matrix chain multiplication, polygon triangulation and merging stones are instances of this.
+//!
+//! # Examples
+//!
+//! ```
+//! use interval_dp::interval_dp;
+//! // Matrix chain multiplication of $10 \times 30$, $30 \times 5$ and $5 \times 60$ matrices.
+//! let d = [10, 30, 5, 60];
+//! let result = interval_dp(3, |_| 0, |i, k, j, l, r| l + r + d[i] * d[k] * d[j]);
+//! assert_eq!(result.value(0, 3), 4500);
+//! assert_eq!(result.split(0, 3), Some(2));
+//! assert_eq!(result.splits(0, 3), vec![(0, 2, 3), (0, 1, 2)]);
+//! ```
+
+/// The result of [`interval_dp`] and [`interval_dp_knuth`].
+#[derive(Debug, Clone, PartialEq)]
+pub struct IntervalDpResult<T> {
+    n: usize,
+    value: Vec<Option<T>>,
+    split: Vec<usize>,
+}
+impl<T: Copy> IntervalDpResult<T> {
+    /// Returns the number of the unit intervals.
+    pub fn len(&self) -> usize {
+        self.n
+    }
+
+    /// Returns `true` if there are no intervals.
+    pub fn is_empty(&self) -> bool {
+        self.n == 0
+    }
+
+    /// Returns $\mathrm{dp}(i, j)$.
+    pub fn value(&self, i: usize, j: usize) -> T {
+        self.value[self.index(i, j)].unwrap()
+    }
+
+    /// Returns the optimal split point of $\[i, j)$, or `None` if it is a unit interval.
+    pub fn split(&self, i: usize, j: usize) -> Option<usize> {
+        let k = self.split[self.index(i, j)];
+        (k != usize::MAX).then_some(k)
+    }
+
+    /// Returns the optimal split tree of $\[i, j)$ as the list of $(i, k, j)$ in pre-order.
+    pub fn splits(&self, i: usize, j: usize) -> Vec<(usize, usize, usize)> {
+        let mut splits = Vec::new();
+        let mut stack = vec![(i, j)];
+        while let Some((i, j)) = stack.pop() {
+            if let Some(k) = self.split(i, j) {
+                splits.push((i, k, j));
+                stack.push((k, j));
+                stack.push((i, k));
+            }
+        }
+        splits
+    }
+
+    fn index(&self, i: usize, j: usize) -> usize {
+        assert!(
+            i < j && j <= self.n,
+            "interval {}..{} out of range for interval dp of length {}",
+            i,
+            j,
+            self.n
+        );
+        i * (self.n + 1) + j
+    }
+}
+
+/// Computes the interval DP.
+///
+/// Ties are broken by the smallest split point.
+///
+/// # Complexity
+///
+/// $O(n ^ 3)$ calls of `combine`
+pub fn interval_dp<T: Copy + Ord>(
+    n: usize,
+    base: impl FnMut(usize) -> T,
+    combine: impl FnMut(usize, usize, usize, T, T) -> T,
+) -> IntervalDpResult<T> {
+    run(n, base, combine, |_, i, j| i + 1..j)
+}
+
+/// Computes the interval DP, assuming that the optimal split points are monotone.
+///
+/// # Requirements
+///
+/// $\mathrm{opt}(i, j - 1) \le \mathrm{opt}(i, j) \le \mathrm{opt}(i + 1, j)$ for the smallest
+/// optimal split points. This holds if `combine(i, k, j, l, r) = l + r + w(i, j)`, and $w$
+/// satisfies the quadrangle inequality $w(a, c) + w(b, d) \le w(a, d) + w(b, c)$ and the
+/// monotonicity $w(b, c) \le w(a, d)$ for $a \le b \le c \le d$.
+///
+/// # Complexity
+///
+/// $O(n ^ 2)$ calls of `combine`
+pub fn interval_dp_knuth<T: Copy + Ord>(
+    n: usize,
+    base: impl FnMut(usize) -> T,
+    combine: impl FnMut(usize, usize, usize, T, T) -> T,
+) -> IntervalDpResult<T> {
+    run(n, base, combine, |result, i, j| {
+        if j - i == 2 {
+            i + 1..j
+        } else {
+            result.split[result.index(i, j - 1)]..result.split[result.index(i + 1, j)] + 1
+        }
+    })
+}
+
+fn run<T: Copy + Ord>(
+    n: usize,
+    mut base: impl FnMut(usize) -> T,
+    mut combine: impl FnMut(usize, usize, usize, T, T) -> T,
+    candidates: impl Fn(&IntervalDpResult<T>, usize, usize) -> std::ops::Range<usize>,
+) -> IntervalDpResult<T> {
+    let mut result = IntervalDpResult {
+        n,
+        value: vec![None; (n + 1) * (n + 1)],
+        split: vec![usize::MAX; (n + 1) * (n + 1)],
+    };
+    for i in 0..n {
+        let index = result.index(i, i + 1);
+        result.value[index] = Some(base(i));
+    }
+    for len in 2..=n {
+        for i in 0..=n - len {
+            let j = i + len;
+            let mut best: Option<(T, usize)> = None;
+            for k in candidates(&result, i, j) {
+                let x = combine(i, k, j, result.value(i, k), result.value(k, j));
+                if best.map_or(true, |(y, _)| x < y) {
+                    best = Some((x, k));
+                }
+            }
+            let (x, k) = best.unwrap();
+            let index = result.index(i, j);
+            result.value[index] = Some(x);
+            result.split[index] = k;
+        }
+    }
+    result
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rand::rngs::StdRng;
+    use rand::Rng;
+    use rand::SeedableRng;
+    use std::collections::HashMap;
+
+    fn brute(
+        i: usize,
+        j: usize,
+        base: &impl Fn(usize) -> u64,
+        combine: &impl Fn(usize, usize, usize, u64, u64) -> u64,
+        memo: &mut HashMap<(usize, usize), u64>,
+    ) -> u64 {
+        if j == i + 1 {
+            return base(i);
+        }
+        if let Some(&x) = memo.get(&(i, j)) {
+            return x;
+        }
+        let x = (i + 1..j)
+            .map(|k| {
+                let l = brute(i, k, base, combine, memo);
+                let r = brute(k, j, base, combine, memo);
+                combine(i, k, j, l, r)
+            })
+            .min()
+            .unwrap();
+        memo.insert((i, j), x);
+        x
+    }
+
+    fn check(
+        n: usize,
+        result: &IntervalDpResult<u64>,
+        base: impl Fn(usize) -> u64,
+        combine: impl Fn(usize, usize, usize, u64, u64) -> u64,
+    ) {
+        let mut memo = HashMap::new();
+        for i in 0..n {
+            for j in i + 1..=n {
+                assert_eq!(result.value(i, j), brute(i, j, &base, &combine, &mut memo));
+                // Evaluate the split tree bottom-up.
+                let mut values = HashMap::new();
+                for (i, k, j) in result.splits(i, j).into_iter().rev() {
+                    let l = values.get(&(i, k)).copied().unwrap_or_else(|| base(i));
+                    let r = values.get(&(k, j)).copied().unwrap_or_else(|| base(k));
+                    values.insert((i, j), combine(i, k, j, l, r));
+                }
+                let root = values.get(&(i, j)).copied().unwrap_or_else(|| base(i));
+                assert_eq!(root, result.value(i, j));
+            }
+        }
+    }
+
+    #[test]
+    fn test_matrix_chain() {
+        let mut rng = StdRng::seed_from_u64(42);
+        for _ in 0..100 {
+            let n = rng.gen_range(1..=10);
+            let d = (0..=n).map(|_| rng.gen_range(1..=20)).collect::<Vec<u64>>();
+            let combine = |i: usize, k: usize, j: usize, l: u64, r: u64| l + r + d[i] * d[k] * d[j];
+            let result = interval_dp(n, |_| 0, combine);
+            check(n, &result, |_| 0, combine);
+        }
+    }
+
+    #[test]
+    fn test_merge_stones() {
+        let mut rng = StdRng::seed_from_u64(42);
+        for _ in 0..100 {
+            let n = rng.gen_range(1..=12);
+            let a = (0..n).map(|_| rng.gen_range(0..=20)).collect::<Vec<u64>>();
+            let mut prefix = vec![0];
+            for &x in &a {
+                prefix.push(prefix.last().unwrap() + x);
+            }
+            let combine =
+                |i: usize, _: usize, j: usize, l: u64, r: u64| l + r + prefix[j] - prefix[i];
+            let result = interval_dp(n, |_| 0, combine);
+            check(n, &result, |_| 0, combine);
+            let knuth = interval_dp_knuth(n, |_| 0, combine);
+            check(n, &knuth, |_| 0, combine);
+            assert_eq!(knuth, result);
+        }
+    }
+
+    #[test]
+    fn test_random_combine() {
+        let mut rng = StdRng::seed_from_u64(42);
+        for _ in 0..100 {
+            let n: usize = rng.gen_range(1..=8);
+            let a = (0..n).map(|_| rng.gen_range(0..=20)).collect::<Vec<u64>>();
+            let w = (0..(n + 1).pow(3))
+                .map(|_| rng.gen_range(0..=20))
+                .collect::<Vec<u64>>();
+            let combine = |i: usize, k: usize, j: usize, l: u64, r: u64| {
+                l.max(r) + w[(i * (n + 1) + k) * (n + 1) + j]
+            };
+            let result = interval_dp(n, |i| a[i], combine);
+            check(n, &result, |i| a[i], combine);
+        }
+    }
+
+    #[test]
+    fn test_empty() {
+        let result = interval_dp(0, |_| 0, |_, _, _, l, r| l + r);
+        assert!(result.is_empty());
+    }
+
+    #[test]
+    #[should_panic(expected = "interval 1..1 out of range for interval dp of length 3")]
+    fn test_empty_interval() {
+        interval_dp(3, |_| 0, |_, _, _, l, r| l + r).value(1, 1);
+    }
+}
@@ -0,0 +1,190 @@
+//! Assigns jobs to slots where each job can go to any slot in an interval.
+//!
+//! Job $i$ can be assigned to any slot in $\[l_i, r_i)$, and slot $s$ can hold at most $c_s$ jobs.
+//! This is a bipartite matching problem, but the interval structure lets the earliest deadline
+//! first greedy solve it without building the graph.
+//!
+//! By Hall's theorem, the jobs cannot be assigned if and only if there exists a set of jobs that
+//! outnumbers the total capacity of its neighbourhood. For intervals, we can always take a set of
+//! the form "some jobs whose intervals are contained in $\[p, t)$", which is what
+//! [`HallViolation`] describes.
+//!
+//! # Examples
+//!
+//! ```
+//! use interval_matching::interval_bipartite_assign;
+//! use interval_matching::interval_bipartite_feasible;
+//!
+//! assert!(interval_bipartite_feasible(&[(0, 2), (0, 1)], &[1, 1]));
+//! assert_eq!(
+//!     interval_bipartite_assign(&[(0, 2), (0, 1)], &[1, 1]),
+//!     Ok(vec![1, 0])
+//! );
+//!
+//! let violation = interval_bipartite_assign(&[(0, 2), (0, 1), (1, 2)], &[1, 1]).unwrap_err();
+//! assert_eq!(violation.jobs, vec![0, 1, 2]);
+//! assert_eq!(violation.slots, 0..2);
+//! ```
+use std::cmp::Reverse;
+use std::collections::BinaryHeap;
+use std::ops::Range;
+
+/// A witness of infeasibility.
+///
+/// Every job in `jobs` has its interval contained in `slots`, and `jobs.len()` exceeds the total
+/// capacity of `slots`.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct HallViolation {
+    /// The indices of the jobs in ascending order.
+    pub jobs: Vec<usize>,
+    /// The range of the slots that contains the intervals of the jobs.
+    pub slots: Range<usize>,
+}
+
+/// Returns `true` if every job can be assigned to a slot in its interval.
+///
+/// See [`interval_bipartite_assign`] for details.
+pub fn interval_bipartite_feasible(jobs: &[(usize, usize)], slot_capacities: &[usize]) -> bool {
+    interval_bipartite_assign(jobs, slot_capacities).is_ok()
+}
+
+/// Returns the slot of each job, or a violation of Hall's condition if impossible.
+///
+/// # Requirements
+///
+/// Each job `(l, r)` satisfies $l \le r \le m$, where $m$ is the number of slots.
+///
+/// # Complexity
+///
+/// $O(m + n \log n)$
+pub fn interval_bipartite_assign(
+    jobs: &[(usize, usize)],
+    slot_capacities: &[usize],
+) -> Result<Vec<usize>, HallViolation> {
+    let m = slot_capacities.len();
+    let mut released = vec![Vec::new(); m];
+    for (i, &(l, r)) in jobs.iter().enumerate() {
+        assert!(
+            l <= r && r <= m,
+            "job {} has the interval {}..{} out of range for {} slots",
+            i,
+            l,
+            r,
+            m
+        );
+        if l == r {
+            return Err(HallViolation {
+                jobs: vec![i],
+                slots: l..r,
+            });
+        }
+        released[l].push(i);
+    }
+    let mut assignment = vec![usize::MAX; jobs.len()];
+    let mut count = vec![0; m];
+    let mut max_deadline = vec![0; m];
+    let mut heap = BinaryHeap::new();
+    for s in 0..=m {
+        // Every job in the heap has a deadline at least `s`, so an expired job has exactly `s`.
+        if let Some(&Reverse((t, i))) = heap.peek() {
+            if t == s {
+                // Slots filled up with jobs due by `s` cannot have helped `i`.
+                let mut p = s;
+                while p > 0 && count[p - 1] == slot_capacities[p - 1] && max_deadline[p - 1] <= s {
+                    p -= 1;
+                }
+                let jobs = (0..jobs.len())
+                    .filter(|&j| j == i || (p..s).contains(&assignment[j]))
+                    .collect();
+                return Err(HallViolation { jobs, slots: p..s });
+            }
+        }
+        if s == m {
+            break;
+        }
+        heap.extend(released[s].iter().map(|&i| Reverse((jobs[i].1, i))));
+        while count[s] < slot_capacities[s] {
+            let Some(Reverse((t, i))) = heap.pop() else {
+                break;
+            };
+            assignment[i] = s;
+            count[s] += 1;
+            max_deadline[s] = max_deadline[s].max(t);
+        }
+    }
+    Ok(assignment)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use hopkarp::hopkarp;
+    use rand::rngs::StdRng;
+    use rand::Rng;
+    use rand::SeedableRng;
+
+    fn brute(jobs: &[(usize, usize)], slot_capacities: &[usize]) -> bool {
+        let mut offset = vec![0];
+        for &c in slot_capacities {
+            offset.push(offset.last().unwrap() + c);
+        }
+        let graph = jobs
+            .iter()
+            .map(|&(l, r)| (offset[l]..offset[r]).collect::<Vec<_>>())
+            .collect::<Vec<_>>();
+        hopkarp(offset[slot_capacities.len()], &graph).count == jobs.len()
+    }
+
+    #[test]
+    fn test_interval_bipartite_assign() {
+        let mut rng = StdRng::seed_from_u64(42);
+        for _ in 0..2000 {
+            let m = rng.gen_range(1..=8);
+            let n = rng.gen_range(0..=10);
+            let slot_capacities = (0..m).map(|_| rng.gen_range(0..=2)).collect::<Vec<_>>();
+            let jobs = (0..n)
+                .map(|_| {
+                    let l = rng.gen_range(0..m);
+                    let r = if rng.gen_ratio(1, 20) { l } else { rng.gen_range(l + 1..=m) };
+                    (l, r)
+                })
+                .collect::<Vec<_>>();
+            let expected = brute(&jobs, &slot_capacities);
+            assert_eq!(
+                interval_bipartite_feasible(&jobs, &slot_capacities),
+                expected
+            );
+            match interval_bipartite_assign(&jobs, &slot_capacities) {
+                Ok(assignment) => {
+                    assert!(expected);
+                    let mut count = vec![0; m];
+                    for (&(l, r), &s) in jobs.iter().zip(&assignment) {
+                        assert!((l..r).contains(&s));
+                        count[s] += 1;
+                    }
+                    for (&c, &cap) in count.iter().zip(&slot_capacities) {
+                        assert!(c <= cap);
+                    }
+                }
+                Err(HallViolation {
+                    jobs: witness,
+                    slots,
+                }) => {
+                    assert!(!expected);
+                    assert!(witness.windows(2).all(|w| w[0] < w[1]));
+                    for &i in &witness {
+                        let (l, r) = jobs[i];
+                        assert!(slots.start <= l && r <= slots.end);
+                    }
+                    assert!(witness.len() > slot_capacities[slots].iter().sum::<usize>());
+                }
+            }
+        }
+    }
+
+    #[test]
+    #[should_panic(expected = "job 0 has the interval 1..3 out of range for 2 slots")]
+    fn test_out_of_range() {
+        interval_bipartite_feasible(&[(1, 3)], &[1, 1]);
+    }
+}
@@ -1,6 +1,13 @@
 //! Calculates the number of inversions.
 //!
 //! [See the document of `inversion_number](inversion_number)
+//!
+//! [`min_adjacent_swaps`] is the number of inversions of the permutation given by
+//! [`map_positions_stable`].
+
+use std::collections::HashMap;
+use std::collections::VecDeque;
+use std::hash::Hash;
 
 /// Takes a sequence of numbers in `0..value_limit` and returns the number of (strong) inversions.
 ///
@@ -38,9 +45,61 @@ pub fn inversion_number(value_limit: usize, a: &[usize]) -> u64 {
     inv
 }
 
+/// Returns `p` such that `b[p[i]] == a[i]`, matching the equal elements in the same order, or
+/// `None` if `a` and `b` are not equal as multisets.
+///
+/// # Examples
+///
+/// ```
+/// use inversion_number::map_positions_stable;
+///
+/// assert_eq!(
+///     map_positions_stable(&[1, 2, 1], &[2, 1, 1]),
+///     Some(vec![1, 0, 2])
+/// );
+/// assert_eq!(map_positions_stable(&[1, 2, 1], &[2, 2, 1]), None);
+/// ```
+pub fn map_positions_stable<T: Hash + Eq>(a: &[T], b: &[T]) -> Option<Vec<usize>> {
+    if a.len() != b.len() {
+        return None;
+    }
+    let mut positions = HashMap::<_, VecDeque<_>>::new();
+    for (j, x) in b.iter().enumerate() {
+        positions.entry(x).or_default().push_back(j);
+    }
+    a.iter()
+        .map(|x| positions.get_mut(x)?.pop_front())
+        .collect()
+}
+
+/// Returns the minimum number of swaps of adjacent elements to turn `a` into `b`, or `None` if
+/// `a` and `b` are not equal as multisets.
+///
+/// Swapping two equal elements is useless, so an optimal way never changes the order of the
+/// equal elements, which [`map_positions_stable`] keeps.
+///
+/// # Examples
+///
+/// ```
+/// use inversion_number::min_adjacent_swaps;
+///
+/// assert_eq!(min_adjacent_swaps(&[3, 1, 2], &[1, 2, 3]), Some(2));
+/// assert_eq!(min_adjacent_swaps(&[1, 2, 1], &[1, 1, 2]), Some(1));
+/// assert_eq!(min_adjacent_swaps(&[1, 2], &[1, 3]), None);
+/// ```
+pub fn min_adjacent_swaps<T: Hash + Eq>(a: &[T], b: &[T]) -> Option<u64> {
+    let p = map_positions_stable(a, b)?;
+    Some(inversion_number(p.len(), &p))
+}
+
 #[cfg(test)]
 mod tests {
     use super::inversion_number;
+    use super::map_positions_stable;
+    use super::min_adjacent_swaps;
+    use rand::prelude::*;
+    use std::collections::HashMap;
+    use std::collections::VecDeque;
     use test_case::test_case;
 
     #[test_case(3, &[0, 1, 2] => 0)]
@@ -52,4 +111,81 @@ mod tests {
     fn test_hand(n: usize, a: &[usize]) -> u64 {
         inversion_number(n, a)
     }
+
+    fn brute_swaps(a: &[u8], b: &[u8]) -> Option<u64> {
+        let mut dist = HashMap::new();
+        dist.insert(a.to_vec(), 0);
+        let mut queue = VecDeque::from(vec![a.to_vec()]);
+        while let Some(x) = queue.pop_front() {
+            let d = dist[&x];
+            if x == b {
+                return Some(d);
+            }
+            for i in 1..x.len() {
+                let mut y = x.clone();
+                y.swap(i - 1, i);
+                dist.entry(y.clone()).or_insert_with(|| {
+                    queue.push_back(y);
+                    d + 1
+                });
+            }
+        }
+        None
+    }
+
+    #[test]
+    fn test_min_adjacent_swaps_brute() {
+        let mut rng = StdRng::seed_from_u64(42);
+        for _ in 0..300 {
+            let n: usize = rng.gen_range(0..=8);
+            let sigma = rng.gen_range(1..=4);
+            let a = (0..n).map(|_| rng.gen_range(0..sigma)).collect::<Vec<u8>>();
+            let mut b = a.clone();
+            b.shuffle(&mut rng);
+            if rng.gen_bool(0.2) && n > 0 {
+                // Probably a different multiset
+                b[rng.gen_range(0..n)] = rng.gen_range(0..sigma);
+            }
+            assert_eq!(
+                min_adjacent_swaps(&a, &b),
+                brute_swaps(&a, &b),
+                "{:?}",
+                (&a, &b)
+            );
+        }
+    }
+
+    #[test]
+    fn test_multiset_mismatch() {
+        assert_eq!(min_adjacent_swaps(&[1, 1, 2], &[1, 2, 2]), None);
+        assert_eq!(min_adjacent_swaps(&[1, 2], &[1, 2, 2]), None);
+        assert_eq!(map_positions_stable(&[0], &[]), None);
+        assert_eq!(min_adjacent_swaps::<u8>(&[], &[]), Some(0));
+    }
+
+    #[test]
+    fn test_large_consistency() {
+        let mut rng = StdRng::seed_from_u64(42);
+        for _ in 0..20 {
+            let n = rng.gen_range(1..=100_000);
+            let sigma = rng.gen_range(1..=n as u32);
+            let a = (0..n).map(|_| rng.gen_range(0..sigma)).collect::<Vec<_>>();
+            let mut b = a.clone();
+            b.shuffle(&mut rng);
+            let p = map_positions_stable(&a, &b).unwrap();
+            // A permutation with `b[p[i]] == a[i]`, keeping the order of the equal elements
+            let mut seen = vec![false; n];
+            for i in 0..n {
+                assert_eq!(b[p[i]], a[i]);
+                assert!(!std::mem::replace(&mut seen[p[i]], true));
+            }
+            let mut last = HashMap::new();
+            for i in 0..n {
+                if let Some(j) = last.insert(a[i], p[i]) {
+                    assert!(j < p[i]);
+                }
+            }
+            assert_eq!(min_adjacent_swaps(&a, &b), Some(inversion_number(n, &p)));
+        }
+    }
 }
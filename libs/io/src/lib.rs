@@ -11,9 +11,17 @@
 //! # Types that can be parsed
 //! - primitive integer types ([`u8`], [`u16`], [`u32`], [`u64`], [`u128`], [`usize`], [`i8`], [`i16`], [`i32`], [`i64`], [`i128`], [`isize`])
 //! - [`String`], [`char`]
+//! - [`Usize1`], a 1-based index converted to the 0-based one
 //! - tuples (up to 10 elements)
 //! - vectors
+//!
+//! # Output
+//!
+//! [`format_fixed`] formats a floating-point answer with a fixed number of digits.
 
+mod output;
+
+pub use output::format_fixed;
 use std::cell::Cell;
 use std::io::stdin;
 use std::io::BufRead;
@@ -43,6 +51,29 @@ macro_rules! impl_parse_line {
     };
 }
 impl_parse_line!(u8, u16, u32, u64, u128, usize, i8, i16, i32, i64, i128, isize, String, char);
+
+/// A 1-based index in the input, parsed into the 0-based one.
+///
+/// # Panics
+///
+/// If the input is `0`.
+///
+/// # Example
+///
+/// ```
+/// # use io::{ParseLine, Usize1};
+/// let (Usize1(u), Usize1(v)) = <(Usize1, Usize1)>::parse_line("1 3");
+/// assert_eq!((u, v), (0, 2));
+/// ```
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct Usize1(pub usize);
+impl ParseLine for Usize1 {
+    fn parse_line(s: &str) -> Self {
+        let x: usize = s.parse().unwrap();
+        assert_ne!(x, 0, "a 1-based index must be positive");
+        Usize1(x - 1)
+    }
+}
 macro_rules! impl_parse_line_tuple {
     ($($t:ident),*) => {
         impl<$($t: ParseLine),*> ParseLine for ($($t,)*) {
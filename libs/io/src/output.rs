@@ -0,0 +1,185 @@
+/// Formats `x` with exactly `digits` digits after the decimal point.
+///
+/// Unlike `format!("{:.*}", digits, x)`, which rounds a tie to even, it rounds half away from
+/// zero. The tie is decided on the exact binary value of `x`, so `2.675`, which is slightly less
+/// than $2.675$, becomes `"2.67"`. A result that rounds to zero never has a minus sign.
+///
+/// # Panics
+///
+/// If `x` is not finite.
+///
+/// # Examples
+///
+/// ```
+/// use io::format_fixed;
+/// assert_eq!(format_fixed(0.125, 2), "0.13");
+/// assert_eq!(format_fixed(-2.5, 0), "-3");
+/// assert_eq!(format_fixed(-1e-12, 6), "0.000000");
+/// assert_eq!(format_fixed(9.9996, 3), "10.000");
+/// ```
+pub fn format_fixed(x: f64, digits: usize) -> String {
+    assert!(x.is_finite(), "cannot format a non-finite value {}", x);
+    // Every finite `f64` is a multiple of $2 ^ {-1074}$, so it has a finite decimal expansion,
+    // which `format!` prints exactly if the precision is long enough.
+    let exact = format!("{:.*}", exact_precision(x).max(digits + 1), x.abs());
+    let point = exact.find('.').unwrap();
+    let mut number = exact[..point].bytes().collect::<Vec<_>>();
+    number.extend(exact[point + 1..point + 1 + digits].bytes());
+    if exact.as_bytes()[point + 1 + digits] >= b'5' {
+        let carry = number.iter_mut().rev().all(|d| {
+            if *d == b'9' {
+                *d = b'0';
+                true
+            } else {
+                *d += 1;
+                false
+            }
+        });
+        if carry {
+            number.insert(0, b'1');
+        }
+    }
+    let negative = x.is_sign_negative() && number.iter().any(|&d| d != b'0');
+    let int_len = number.len() - digits;
+    let mut result = String::with_capacity(number.len() + 2);
+    if negative {
+        result.push('-');
+    }
+    result.push_str(std::str::from_utf8(&number[..int_len]).unwrap());
+    if digits != 0 {
+        result.push('.');
+        result.push_str(std::str::from_utf8(&number[int_len..]).unwrap());
+    }
+    result
+}
+
+// The number of the fractional digits of the exact decimal expansion of `x`.
+fn exact_precision(x: f64) -> usize {
+    let bits = x.to_bits();
+    let exponent = (bits >> 52 & 0x7ff) as i64;
+    let mantissa = bits & ((1 << 52) - 1);
+    if exponent == 0 && mantissa == 0 {
+        return 0;
+    }
+    // $x = m 2 ^ e$ with an integer $m$
+    let (m, e) = if exponent == 0 {
+        (mantissa, -1074)
+    } else {
+        (mantissa | 1 << 52, exponent - 1075)
+    };
+    (-(e + i64::from(m.trailing_zeros()))).max(0) as usize
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rand::rngs::StdRng;
+    use rand::Rng;
+    use rand::SeedableRng;
+
+    #[test]
+    fn test_ties() {
+        // Exact ties in binary.
+        assert_eq!(format_fixed(0.5, 0), "1");
+        assert_eq!(format_fixed(1.5, 0), "2");
+        assert_eq!(format_fixed(2.5, 0), "3");
+        assert_eq!(format_fixed(-0.5, 0), "-1");
+        assert_eq!(format_fixed(-2.5, 0), "-3");
+        assert_eq!(format_fixed(0.125, 2), "0.13");
+        assert_eq!(format_fixed(-0.125, 2), "-0.13");
+        assert_eq!(format_fixed(0.375, 2), "0.38");
+        assert_eq!(format_fixed(1.0625, 3), "1.063");
+        assert_eq!(format_fixed(-1.0625, 3), "-1.063");
+    }
+
+    #[test]
+    fn test_near_ties() {
+        // These are not ties in binary.
+        assert_eq!(format_fixed(2.675, 2), "2.67");
+        assert_eq!(format_fixed(-2.675, 2), "-2.67");
+        assert_eq!(format_fixed(1.005, 2), "1.00");
+        assert_eq!(format_fixed(0.15, 1), "0.1");
+        assert_eq!(format_fixed(0.25, 1), "0.3");
+        assert_eq!(format_fixed(0.35, 1), "0.3");
+        assert_eq!(format_fixed(0.45, 1), "0.5");
+        assert_eq!(format_fixed(0.95, 1), "0.9");
+        assert_eq!(format_fixed(9.995, 2), "9.99");
+    }
+
+    #[test]
+    fn test_carry() {
+        assert_eq!(format_fixed(9.5, 0), "10");
+        assert_eq!(format_fixed(0.9996, 3), "1.000");
+        assert_eq!(format_fixed(-999.9996, 3), "-1000.000");
+        assert_eq!(format_fixed(99.96, 1), "100.0");
+    }
+
+    #[test]
+    fn test_zero() {
+        assert_eq!(format_fixed(0.0, 6), "0.000000");
+        assert_eq!(format_fixed(-0.0, 6), "0.000000");
+        assert_eq!(format_fixed(-0.0, 0), "0");
+        assert_eq!(format_fixed(1e-12, 6), "0.000000");
+        assert_eq!(format_fixed(-1e-12, 6), "0.000000");
+        assert_eq!(format_fixed(-4e-7, 6), "0.000000");
+        // `5e-7` is slightly less than $5 \times 10 ^ {-7}$.
+        assert_eq!(format_fixed(-5e-7, 6), "0.000000");
+        assert_eq!(format_fixed(-5.000001e-7, 6), "-0.000001");
+        assert_eq!(format_fixed(-0.4, 0), "0");
+        assert_eq!(format_fixed(f64::MIN_POSITIVE, 3), "0.000");
+        assert_eq!(format_fixed(-5e-324, 3), "0.000");
+    }
+
+    #[test]
+    fn test_large() {
+        assert_eq!(format_fixed(1e20, 2), "100000000000000000000.00");
+        assert_eq!(format_fixed(-1e20, 0), "-100000000000000000000");
+        assert_eq!(format_fixed(123456789.125, 2), "123456789.13");
+        assert_eq!(format_fixed(f64::MAX, 0), format!("{:.0}", f64::MAX));
+        assert_eq!(format_fixed(f64::MIN, 1), format!("{:.1}", f64::MIN));
+    }
+
+    #[test]
+    fn test_random() {
+        let mut rng = StdRng::seed_from_u64(42);
+        for _ in 0..10_000 {
+            let x = rng.gen_range(-1e6..1e6) * 10f64.powi(rng.gen_range(-8..8));
+            let digits = rng.gen_range(0..=12);
+            let s = format_fixed(x, digits);
+            // The number of the digits
+            match s.find('.') {
+                Some(point) => assert_eq!(s.len() - point - 1, digits),
+                None => assert_eq!(digits, 0),
+            }
+            // Round trip: within half a unit, up to the error of parsing.
+            let unit = 10f64.powi(-(digits as i32));
+            let y = s.parse::<f64>().unwrap();
+            assert!(
+                (x - y).abs() <= unit / 2.0 * (1.0 + 1e-9) + x.abs() * 1e-15,
+                "{} {}",
+                x,
+                s
+            );
+            // It agrees with `format!` except for ties and negative zeros.
+            let std = format!("{:.*}", digits, x);
+            let is_tie =
+                exact_precision(x) == digits + 1 && format!("{:.*}", digits + 1, x).ends_with('5');
+            let is_zero = std.bytes().all(|c| matches!(c, b'-' | b'0' | b'.'));
+            if !is_tie && !is_zero {
+                assert_eq!(s, std, "{}", x);
+            }
+        }
+    }
+
+    #[test]
+    #[should_panic(expected = "cannot format a non-finite value NaN")]
+    fn test_nan() {
+        format_fixed(f64::NAN, 3);
+    }
+
+    #[test]
+    #[should_panic(expected = "cannot format a non-finite value -inf")]
+    fn test_infinity() {
+        format_fixed(f64::NEG_INFINITY, 3);
+    }
+}
@@ -0,0 +1,281 @@
+//! The KMP failure function, and the borders and periods of strings.
+//!
+//! A border of $s$ is a proper prefix that is also a suffix, and $p$ is a period of $s$ if
+//! $s _ i = s _ {i + p}$ for every $i$. A border of length $b$ corresponds to the period $n - b$,
+//! and the borders of $s$ are the longest border and its borders, which the failure function
+//! chains.
+//!
+//! # Periodicity lemma
+//!
+//! If $p$ and $q$ are periods and $p + q - \gcd(p, q) \le n$, then $\gcd(p, q)$ is also a period.
+//! In particular, every period at most $n / 2$ is a multiple of the smallest period, so $s$ is a
+//! power of a shorter string if and only if the smallest period divides $n$ and is less than $n$.
+//!
+//! # Examples
+//!
+//! ```
+//! use kmp::all_periods;
+//! use kmp::is_power;
+//! use kmp::smallest_period;
+//! let s = b"abaababaab";
+//! assert_eq!(all_periods(s), [5, 8, 10]);
+//! assert_eq!(smallest_period(s), 5);
+//! assert_eq!(is_power(s), Some((5, 2)));
+//! ```
+
+/// Returns the failure function `f`, where `f[i]` is the length of the longest border of `s[..i]`.
+///
+/// The length of `f` is $n + 1$, and `f[0]` is zero.
+///
+/// # Complexity
+///
+/// $O(n)$
+///
+/// # Examples
+///
+/// ```
+/// use kmp::failure_function;
+/// assert_eq!(failure_function(b"aabaa"), [0, 0, 1, 0, 1, 2]);
+/// ```
+pub fn failure_function<T: Eq>(s: &[T]) -> Vec<usize> {
+    let mut f = vec![0; s.len() + 1];
+    for i in 1..s.len() {
+        let mut j = f[i];
+        while j > 0 && s[i] != s[j] {
+            j = f[j];
+        }
+        if s[i] == s[j] {
+            j += 1;
+        }
+        f[i + 1] = j;
+    }
+    f
+}
+
+/// Returns the lengths of the nonempty borders of `s` in decreasing order.
+///
+/// # Complexity
+///
+/// $O(n)$
+///
+/// # Examples
+///
+/// ```
+/// use kmp::all_borders;
+/// assert_eq!(all_borders(b"abaababaab"), [5, 2]);
+/// assert_eq!(all_borders(b"aaa"), [2, 1]);
+/// assert!(all_borders(b"ab").is_empty());
+/// ```
+pub fn all_borders<T: Eq>(s: &[T]) -> Vec<usize> {
+    let f = failure_function(s);
+    let mut borders = Vec::new();
+    let mut b = f[s.len()];
+    while b > 0 {
+        borders.push(b);
+        b = f[b];
+    }
+    borders
+}
+
+/// Returns the periods of `s` in increasing order, which always contain $n$ unless `s` is
+/// empty.
+///
+/// # Complexity
+///
+/// $O(n)$
+///
+/// # Examples
+///
+/// ```
+/// use kmp::all_periods;
+/// assert_eq!(all_periods(b"aaa"), [1, 2, 3]);
+/// assert_eq!(all_periods(b"abcab"), [3, 5]);
+/// ```
+pub fn all_periods<T: Eq>(s: &[T]) -> Vec<usize> {
+    let n = s.len();
+    let mut periods = all_borders(s)
+        .into_iter()
+        .map(|b| n - b)
+        .collect::<Vec<_>>();
+    if n > 0 {
+        periods.push(n);
+    }
+    periods
+}
+
+/// Returns the smallest period of `s`, which is zero for the empty string.
+///
+/// # Complexity
+///
+/// $O(n)$
+///
+/// # Examples
+///
+/// ```
+/// use kmp::smallest_period;
+/// assert_eq!(smallest_period(b"abcab"), 3);
+/// assert_eq!(smallest_period(b"abc"), 3);
+/// ```
+pub fn smallest_period<T: Eq>(s: &[T]) -> usize {
+    s.len() - failure_function(s)[s.len()]
+}
+
+/// Returns `(p, k)` if `s` is the `k`-th power of its primitive root `s[..p]` with $k \ge 2$.
+///
+/// # Complexity
+///
+/// $O(n)$
+///
+/// # Examples
+///
+/// ```
+/// use kmp::is_power;
+/// assert_eq!(is_power(b"abab"), Some((2, 2)));
+/// assert_eq!(is_power(b"aaa"), Some((1, 3)));
+/// assert_eq!(is_power(b"aba"), None);
+/// assert_eq!(is_power(b"a"), None);
+/// assert_eq!(is_power(b""), None);
+/// ```
+pub fn is_power<T: Eq>(s: &[T]) -> Option<(usize, usize)> {
+    let n = s.len();
+    let p = smallest_period(s);
+    if p < n && n % p == 0 {
+        Some((p, n / p))
+    } else {
+        None
+    }
+}
+
+/// Returns the smallest period of each prefix, where the $i$-th element is for `s[..i]`.
+///
+/// The prefix `s[..i]` is a power of a shorter string if and only if the $i$-th element `p`
+/// satisfies `p < i && i % p == 0`.
+///
+/// # Complexity
+///
+/// $O(n)$
+///
+/// # Examples
+///
+/// ```
+/// use kmp::smallest_period_of_each_prefix;
+/// assert_eq!(smallest_period_of_each_prefix(b"abaab"), [0, 1, 2, 2, 3, 3]);
+/// ```
+pub fn smallest_period_of_each_prefix<T: Eq>(s: &[T]) -> Vec<usize> {
+    failure_function(s)
+        .into_iter()
+        .enumerate()
+        .map(|(i, b)| i - b)
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rand::rngs::StdRng;
+    use rand::Rng;
+    use rand::SeedableRng;
+
+    fn brute_periods(s: &[u8]) -> Vec<usize> {
+        (1..=s.len())
+            .filter(|&p| (p..s.len()).all(|i| s[i] == s[i - p]))
+            .collect()
+    }
+
+    fn brute_is_power(s: &[u8]) -> Option<(usize, usize)> {
+        let n = s.len();
+        (1..n)
+            .find(|&p| n % p == 0 && s.chunks(p).all(|c| c == &s[..p]))
+            .map(|p| (p, n / p))
+    }
+
+    fn check(s: &[u8]) {
+        let n = s.len();
+        let periods = brute_periods(s);
+        assert_eq!(all_periods(s), periods);
+        assert_eq!(
+            all_borders(s),
+            periods
+                .iter()
+                .filter(|&&p| p < n)
+                .map(|&p| n - p)
+                .collect::<Vec<_>>()
+        );
+        assert_eq!(smallest_period(s), periods.first().copied().unwrap_or(0));
+        assert_eq!(is_power(s), brute_is_power(s));
+    }
+
+    #[test]
+    fn test_binary_exhaustive() {
+        for n in 0..=16 {
+            for bs in 0..1_u32 << n {
+                let s = (0..n).map(|i| (bs >> i & 1) as u8).collect::<Vec<_>>();
+                check(&s);
+                // Every prefix is checked by itself, so it suffices to compare with the whole.
+                let prefix_periods = smallest_period_of_each_prefix(&s);
+                assert_eq!(prefix_periods.len(), n + 1);
+                assert_eq!(prefix_periods[n], smallest_period(&s));
+            }
+        }
+    }
+
+    fn random_strings(rng: &mut StdRng) -> Vec<Vec<u8>> {
+        let mut strings = Vec::new();
+        // Fibonacci strings
+        let (mut x, mut y) = (b"a".to_vec(), b"ab".to_vec());
+        for _ in 0..14 {
+            strings.push(y.clone());
+            let z = [y.as_slice(), x.as_slice()].concat();
+            x = std::mem::replace(&mut y, z);
+        }
+        for _ in 0..50 {
+            let n: usize = rng.gen_range(1..=1000);
+            let sigma: u8 = rng.gen_range(1..=3);
+            let s = (0..n).map(|_| rng.gen_range(0..sigma)).collect::<Vec<_>>();
+            // A long repetition of a short string, possibly with a mutation
+            let p = rng.gen_range(1..=n.min(10));
+            let mut t = (0..n).map(|i| s[i % p]).collect::<Vec<_>>();
+            if rng.gen_bool(0.5) {
+                let i = rng.gen_range(0..n);
+                t[i] = rng.gen_range(0..sigma);
+            }
+            strings.push(s);
+            strings.push(t);
+        }
+        strings
+    }
+
+    #[test]
+    fn test_random() {
+        let mut rng = StdRng::seed_from_u64(42);
+        for s in random_strings(&mut rng) {
+            check(&s);
+            let prefix_periods = smallest_period_of_each_prefix(&s);
+            for i in (0..=s.len()).step_by(37) {
+                assert_eq!(
+                    prefix_periods[i],
+                    brute_periods(&s[..i]).first().copied().unwrap_or(0)
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn test_fibonacci_borders() {
+        // The borders of the Fibonacci string $f _ n$ are $f _ {n - 2}, f _ {n - 4}, \dots$.
+        let mut fib = vec![b"a".to_vec(), b"ab".to_vec()];
+        for k in 2..14 {
+            fib.push([fib[k - 1].as_slice(), fib[k - 2].as_slice()].concat());
+        }
+        for n in 2..fib.len() {
+            let expected = (0..n - 1)
+                .rev()
+                .step_by(2)
+                .map(|k| fib[k].len())
+                .collect::<Vec<_>>();
+            assert_eq!(all_borders(&fib[n]), expected);
+            assert_eq!(smallest_period(&fib[n]), fib[n - 1].len());
+            assert_eq!(is_power(&fib[n]), None);
+        }
+    }
+}
@@ -0,0 +1,338 @@
+//! Selects the $k$-th smallest value of a large multiset without enumerating it.
+//!
+//! If the number of the elements at most $v$ can be counted quickly, the $k$-th smallest element is
+//! the smallest $v$ such that the count exceeds $k$, which [`kth_by_counting`] finds by a binary
+//! search on $v$. The sums and the products of the pairs from two sorted arrays are built on it.
+//!
+//! Every $k$ is 0-indexed, as in [`slice::select_nth_unstable`].
+//!
+//! # Examples
+//!
+//! ```
+//! use kth_selection::kth_by_counting;
+//! // The 3rd smallest distance of the pairs of [1, 3, 6]: 2, 3, 5
+//! let a = [1, 3, 6];
+//! let count = |v: i64| {
+//!     let mut count = 0;
+//!     for i in 0..a.len() {
+//!         count += a[i + 1..].iter().filter(|&&x| x - a[i] <= v).count() as u64;
+//!     }
+//!     count
+//! };
+//! assert_eq!(kth_by_counting(0, 5, count, 2), 5);
+//! ```
+
+use numeric_search::binary_search_signed;
+use numeric_search::Signed;
+
+/// Returns the smallest $v$ in $[\mathrm{lo}, \mathrm{hi}]$ such that `count_leq(v)` is greater
+/// than $k$, that is, the $k$-th smallest element of the multiset whose numbers of the elements at
+/// most $v$ are `count_leq(v)`.
+///
+/// # Requirements
+///
+/// - `count_leq` is monotone.
+/// - No element is less than `lo`.
+///
+/// # Panics
+///
+/// Panics if `count_leq(hi)` is not greater than $k$.
+///
+/// # Complexity
+///
+/// $O(\log (\mathrm{hi} - \mathrm{lo}))$ calls of `count_leq`.
+///
+/// # Examples
+///
+/// ```
+/// use kth_selection::kth_by_counting;
+/// // The multiset {1, 1, 4}
+/// let count = |v| [1, 1, 4].iter().filter(|&&x| x <= v).count() as u64;
+/// assert_eq!(kth_by_counting(0, 10, count, 0), 1);
+/// assert_eq!(kth_by_counting(0, 10, count, 1), 1);
+/// assert_eq!(kth_by_counting(0, 10, count, 2), 4);
+/// ```
+pub fn kth_by_counting<T: Signed>(lo: T, hi: T, mut count_leq: impl FnMut(T) -> u64, k: u64) -> T {
+    assert!(lo <= hi, "the range is empty: {:?}..={:?}", lo, hi);
+    assert!(
+        k < count_leq(hi),
+        "k = {} is out of range: there are only {} elements",
+        k,
+        count_leq(hi)
+    );
+    if k < count_leq(lo) {
+        return lo;
+    }
+    binary_search_signed(lo, hi, |v| k < count_leq(v))
+}
+
+/// Returns the $k$-th smallest of $a _ i + b _ j$ over all the pairs $(i, j)$.
+///
+/// # Requirements
+///
+/// `a` and `b` are sorted in increasing order.
+///
+/// # Panics
+///
+/// Panics if $k \ge nm$.
+///
+/// # Complexity
+///
+/// $O((n + m) \log V)$, where $V$ is the range of the sums.
+///
+/// # Examples
+///
+/// ```
+/// use kth_selection::kth_smallest_sum;
+/// // 11, 12, 21, 22
+/// assert_eq!(kth_smallest_sum(&[1, 2], &[10, 20], 1), 12);
+/// assert_eq!(kth_smallest_sum(&[1, 2], &[10, 20], 2), 21);
+/// ```
+pub fn kth_smallest_sum(a: &[i64], b: &[i64], k: u64) -> i128 {
+    debug_assert!(a.windows(2).all(|w| w[0] <= w[1]), "a is not sorted");
+    debug_assert!(b.windows(2).all(|w| w[0] <= w[1]), "b is not sorted");
+    assert!(
+        k < a.len() as u64 * b.len() as u64,
+        "k = {} is out of range for {} pairs",
+        k,
+        a.len() as u64 * b.len() as u64
+    );
+    let lo = a[0] as i128 + b[0] as i128;
+    let hi = a[a.len() - 1] as i128 + b[b.len() - 1] as i128;
+    kth_by_counting(lo, hi, |v| count_sums_leq(a, b, v), k)
+}
+
+/// Returns the $k$-th smallest of $a _ i b _ j$ over all the pairs $(i, j)$.
+///
+/// The products are computed in `i128`, so they never overflow.
+///
+/// # Requirements
+///
+/// `a` and `b` are sorted in increasing order.
+///
+/// # Panics
+///
+/// Panics if $k \ge nm$.
+///
+/// # Complexity
+///
+/// $O(n \log m \log V)$, where $V$ is the range of the products.
+///
+/// # Examples
+///
+/// ```
+/// use kth_selection::kth_smallest_product;
+/// // -6, -3, 0, 0, 2, 4
+/// let a = [-3, 0, 2];
+/// let b = [-1, 2];
+/// assert_eq!(kth_smallest_product(&a, &b, 0), -6);
+/// assert_eq!(kth_smallest_product(&a, &b, 3), 0);
+/// assert_eq!(kth_smallest_product(&a, &b, 5), 4);
+/// ```
+pub fn kth_smallest_product(a: &[i64], b: &[i64], k: u64) -> i128 {
+    debug_assert!(a.windows(2).all(|w| w[0] <= w[1]), "a is not sorted");
+    debug_assert!(b.windows(2).all(|w| w[0] <= w[1]), "b is not sorted");
+    assert!(
+        k < a.len() as u64 * b.len() as u64,
+        "k = {} is out of range for {} pairs",
+        k,
+        a.len() as u64 * b.len() as u64
+    );
+    // The extreme products are among those of the extreme elements.
+    let corners = [
+        a[0] as i128 * b[0] as i128,
+        a[0] as i128 * b[b.len() - 1] as i128,
+        a[a.len() - 1] as i128 * b[0] as i128,
+        a[a.len() - 1] as i128 * b[b.len() - 1] as i128,
+    ];
+    let lo = *corners.iter().min().unwrap();
+    let hi = *corners.iter().max().unwrap();
+    kth_by_counting(lo, hi, |v| count_products_leq(a, b, v), k)
+}
+
+// The number of the pairs with $a _ i + b _ j \le v$, by two pointers.
+fn count_sums_leq(a: &[i64], b: &[i64], v: i128) -> u64 {
+    let mut count = 0;
+    let mut j = b.len();
+    for &x in a {
+        while j > 0 && x as i128 + b[j - 1] as i128 > v {
+            j -= 1;
+        }
+        count += j as u64;
+    }
+    count
+}
+
+// The number of the pairs with $a _ i b _ j \le v$.
+fn count_products_leq(a: &[i64], b: &[i64], v: i128) -> u64 {
+    let m = b.len() as u64;
+    a.iter()
+        .map(|&x| {
+            let x = x as i128;
+            match x.signum() {
+                // $y \le \lfloor v / x \rfloor$
+                1 => {
+                    let bound = v.div_euclid(x);
+                    b.partition_point(|&y| y as i128 <= bound) as u64
+                }
+                // $0 \le v$ or nothing
+                0 => {
+                    if v >= 0 {
+                        m
+                    } else {
+                        0
+                    }
+                }
+                // $y \ge \lceil v / x \rceil = - \lfloor v / (-x) \rfloor$
+                _ => {
+                    let bound = -v.div_euclid(-x);
+                    m - b.partition_point(|&y| (y as i128) < bound) as u64
+                }
+            }
+        })
+        .sum()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rand::rngs::StdRng;
+    use rand::Rng;
+    use rand::SeedableRng;
+
+    fn random_sorted(rng: &mut StdRng, n: usize, max: i64) -> Vec<i64> {
+        let mut a = (0..n)
+            .map(|_| match rng.gen_range(0..8) {
+                0 => 0,
+                1 => max,
+                2 => -max,
+                _ => rng.gen_range(-max..=max),
+            })
+            .collect::<Vec<_>>();
+        a.sort_unstable();
+        a
+    }
+
+    fn check(a: &[i64], b: &[i64], ks: impl Iterator<Item = u64>) {
+        let mut sums = Vec::new();
+        let mut products = Vec::new();
+        for &x in a {
+            for &y in b {
+                sums.push(x as i128 + y as i128);
+                products.push(x as i128 * y as i128);
+            }
+        }
+        sums.sort_unstable();
+        products.sort_unstable();
+        for k in ks {
+            assert_eq!(kth_smallest_sum(a, b, k), sums[k as usize]);
+            assert_eq!(kth_smallest_product(a, b, k), products[k as usize]);
+        }
+    }
+
+    #[test]
+    fn test_small_exhaustive_k() {
+        let mut rng = StdRng::seed_from_u64(42);
+        for _ in 0..500 {
+            let n = rng.gen_range(1..=8);
+            let m = rng.gen_range(1..=8);
+            let max = rng.gen_range(0..=5);
+            let a = random_sorted(&mut rng, n, max);
+            let b = random_sorted(&mut rng, m, max);
+            check(&a, &b, 0..(n * m) as u64);
+        }
+    }
+
+    #[test]
+    fn test_large() {
+        let mut rng = StdRng::seed_from_u64(42);
+        for _ in 0..30 {
+            let n = rng.gen_range(1..=300);
+            let m = rng.gen_range(1..=300);
+            let max = match rng.gen_range(0..3) {
+                0 => 10,
+                1 => 1_000_000_000,
+                _ => i64::MAX,
+            };
+            let a = random_sorted(&mut rng, n, max);
+            let b = random_sorted(&mut rng, m, max);
+            let len = (n * m) as u64;
+            let ks = [0, 1, len / 2, len - 2, len - 1]
+                .into_iter()
+                .filter(|&k| k < len)
+                .chain((0..10).map(|_| rng.gen_range(0..len)))
+                .collect::<Vec<_>>();
+            check(&a, &b, ks.into_iter());
+        }
+    }
+
+    #[test]
+    fn test_extreme_products() {
+        let a = [i64::MIN, -1, 0, i64::MAX];
+        let b = [i64::MIN, i64::MAX];
+        check(&a, &b, 0..8);
+        let a = [i64::MIN; 3];
+        check(&a, &a, 0..9);
+        assert_eq!(
+            kth_smallest_product(&a, &a, 0),
+            i64::MIN as i128 * i64::MIN as i128
+        );
+    }
+
+    #[test]
+    fn test_kth_subarray_sum() {
+        // The sums of the nonempty subarrays of a positive array, counted by two pointers
+        let mut rng = StdRng::seed_from_u64(42);
+        for _ in 0..100 {
+            let n = rng.gen_range(1..=50);
+            let a = (0..n).map(|_| rng.gen_range(1..=20)).collect::<Vec<i64>>();
+            let count = |v: i64| {
+                let mut count = 0;
+                let mut sum = 0;
+                let mut l = 0;
+                for r in 0..n {
+                    sum += a[r];
+                    while sum > v {
+                        sum -= a[l];
+                        l += 1;
+                    }
+                    count += (r + 1 - l) as u64;
+                }
+                count
+            };
+            let mut expected = Vec::new();
+            for l in 0..n {
+                for r in l + 1..=n {
+                    expected.push(a[l..r].iter().sum::<i64>());
+                }
+            }
+            expected.sort_unstable();
+            let total = a.iter().sum();
+            for (k, &x) in expected.iter().enumerate() {
+                assert_eq!(kth_by_counting(1, total, count, k as u64), x);
+            }
+        }
+    }
+
+    #[test]
+    fn test_full_range() {
+        let a = [i64::MIN, i64::MIN, -1, 0, i64::MAX];
+        let count = |v: i64| a.iter().filter(|&&x| x <= v).count() as u64;
+        for (k, &x) in a.iter().enumerate() {
+            assert_eq!(kth_by_counting(i64::MIN, i64::MAX, count, k as u64), x);
+        }
+        assert_eq!(kth_by_counting(i64::MIN, i64::MIN, count, 1), i64::MIN);
+    }
+
+    #[test]
+    #[should_panic(expected = "k = 4 is out of range for 4 pairs")]
+    fn test_out_of_range() {
+        kth_smallest_sum(&[1, 2], &[3, 4], 4);
+    }
+
+    #[test]
+    #[should_panic(expected = "k = 0 is out of range for 0 pairs")]
+    fn test_empty() {
+        kth_smallest_product(&[], &[3, 4], 0);
+    }
+}
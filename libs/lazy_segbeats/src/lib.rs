@@ -1,4 +1,4 @@
-use open::open;
+use open::resolve_range;
 use std::cell::RefCell;
 use std::fmt::Debug;
 use std::ops::Add;
@@ -8,58 +8,66 @@ use std::ops::RangeBounds;
 use std::ops::Sub;
 use std::ops::SubAssign;
 
+/// 区間 chmin, chmax, 加算と、区間 min, max, 和ができる Segment Tree Beats です。
+///
+/// 和も要素と同じ型 `T` で計算するので、オーバーフローしない型を選んでください。たとえば
+/// $n \le 2 \times 10 ^ 5$, $|a_i| \le 10 ^ 9$ なら和は $2 \times 10 ^ {14}$ 程度なので、`i64` で足ります。
+/// ただし区間加算を繰り返すと要素そのものが大きくなることに注意です。
 #[derive(Debug, Clone, PartialEq, Eq)]
 pub struct Segbeats<T> {
     len: usize,
+    size: usize,
     lg: u32,
     table: RefCell<Vec<Node<T>>>,
 }
 
 impl<T: Elm> Segbeats<T> {
     pub fn new(src: &[T]) -> Self {
-        let len = src.len().next_power_of_two();
-        let lg = len.trailing_zeros();
-        let mut table = vec![Node::new(); 2 * len];
+        let len = src.len();
+        let size = len.next_power_of_two();
+        let lg = size.trailing_zeros();
+        let mut table = vec![Node::new(); 2 * size];
         for (i, &x) in src.iter().enumerate() {
-            table[len + i] = Node::single(x);
+            table[size + i] = Node::single(x);
         }
-        (1..len)
+        (1..size)
             .rev()
             .for_each(|i| table[i] = Node::merge(table[2 * i], table[2 * i + 1]));
         Self {
             len,
+            size,
             lg,
             table: RefCell::new(table),
         }
     }
 
     pub fn change_min(&mut self, range: impl Clone + RangeBounds<usize>, x: T) {
-        let range = open(self.len, range);
+        let range = resolve_range(self.len, range);
         self.dfs::<ChangeMin<T>>(range, x)
     }
 
     pub fn change_max(&mut self, range: impl Clone + RangeBounds<usize>, x: T) {
-        let range = open(self.len, range);
+        let range = resolve_range(self.len, range);
         self.dfs::<ChangeMax<T>>(range, x)
     }
 
     pub fn range_add(&mut self, range: impl Clone + RangeBounds<usize>, x: T) {
-        let range = open(self.len, range);
+        let range = resolve_range(self.len, range);
         self.dfs::<RangeAdd<T>>(range, x)
     }
 
     pub fn query_min(&self, range: impl RangeBounds<usize>) -> T {
-        let range = open(self.len, range);
+        let range = resolve_range(self.len, range);
         self.dfs::<QueryMin<T>>(range, ())
     }
 
     pub fn query_max(&self, range: impl RangeBounds<usize>) -> T {
-        let range = open(self.len, range);
+        let range = resolve_range(self.len, range);
         self.dfs::<QueryMax<T>>(range, ())
     }
 
     pub fn query_sum(&self, range: impl RangeBounds<usize>) -> T {
-        let range = open(self.len, range);
+        let range = resolve_range(self.len, range);
         self.dfs::<QuerySum<T>>(range, ())
     }
 
@@ -86,7 +94,7 @@ impl<T: Elm> Segbeats<T> {
     }
 
     fn dfs<D: Dfs<Value = T>>(&self, range: Range<usize>, x: D::Param) -> D::Output {
-        self.dfs_impl::<D>(1, 0..self.len, range, x)
+        self.dfs_impl::<D>(1, 0..self.size, range, x)
     }
 
     fn dfs_impl<D: Dfs<Value = T>>(
@@ -328,7 +336,7 @@ impl<T: Elm> Node<T> {
             let [a, b] = left.max;
             let [c, d] = right.max;
             match a.cmp(&c) {
-                Ordering::Equal => ([a, c.max(d)], left.c_max + right.c_max),
+                Ordering::Equal => ([a, b.max(d)], left.c_max + right.c_max),
                 Ordering::Greater => ([a, b.max(c)], left.c_max),
                 Ordering::Less => ([c, a.max(d)], right.c_max),
             }
@@ -337,7 +345,7 @@ impl<T: Elm> Node<T> {
             let [a, b] = left.min;
             let [c, d] = right.min;
             match a.cmp(&c) {
-                Ordering::Equal => ([a, c.min(d)], left.c_min + right.c_min),
+                Ordering::Equal => ([a, b.min(d)], left.c_min + right.c_min),
                 Ordering::Less => ([a, b.min(c)], left.c_min),
                 Ordering::Greater => ([c, a.min(d)], right.c_min),
             }
@@ -401,42 +409,129 @@ impl_elm! {
     i8; i16; i32; i64; i128; isize;
 }
 
-// #[cfg(test)]
-// mod tests {
-//     mod impl_query;
-//     mod queries;
-//     mod vector;
-//
-//     use super::Segbeats;
-//     use queries::{ChangeMax, ChangeMin, QueryMax, QueryMin, QuerySum, RangeAdd};
-//     use query_test::{impl_help, Config};
-//     use rand::prelude::*;
-//     use vector::{Len, Value, Vector};
-//
-//     type Tester<T, G> = query_test::Tester<StdRng, Vector<T>, Segbeats<T>, G>;
-//
-//     #[test]
-//     fn test_i64() {
-//         #[derive(Debug, Clone, PartialEq, Copy, Eq)]
-//         struct G {}
-//         impl_help! {Len, |rng| rng.gen_range(1..100); }
-//         impl_help! {Value<i64>, |rng| rng.gen_range(-1_000_000_000, 1_000_000_000); }
-//
-//         let mut tester = Tester::<i64, G>::new(StdRng::seed_from_u64(42), Config::Short);
-//         for _ in 0..10 {
-//             tester.initialize();
-//             for _ in 0..100 {
-//                 let command = tester.rng_mut().gen_range(0..6);
-//                 match command {
-//                     0 => tester.mutate::<ChangeMin<_>>(),
-//                     1 => tester.mutate::<ChangeMax<_>>(),
-//                     2 => tester.mutate::<RangeAdd<_>>(),
-//                     3 => tester.compare::<QueryMin<_>>(),
-//                     4 => tester.compare::<QueryMax<_>>(),
-//                     5 => tester.compare::<QuerySum<_>>(),
-//                     _ => unreachable!(),
-//                 }
-//             }
-//         }
-//     }
-// }
+#[cfg(test)]
+mod tests {
+    use super::Segbeats;
+    use rand::prelude::*;
+    use randtools::SubRange;
+
+    #[test]
+    fn test_i64() {
+        let mut rng = StdRng::seed_from_u64(42);
+        for _ in 0..100 {
+            let n = rng.gen_range(1..100);
+            let value_max = if rng.gen() { 10 } else { 1_000_000_000 };
+            let mut vec = (0..n)
+                .map(|_| rng.gen_range(-value_max..value_max))
+                .collect::<Vec<i64>>();
+            let mut segbeats = Segbeats::new(&vec);
+            for _ in 0..100 {
+                let range = rng.sample(SubRange(0..n));
+                let x = rng.gen_range(-value_max..value_max);
+                let a = &mut vec[range.clone()];
+                match rng.gen_range(0..6) {
+                    0 => {
+                        segbeats.change_min(range, x);
+                        a.iter_mut().for_each(|y| *y = (*y).min(x));
+                    }
+                    1 => {
+                        segbeats.change_max(range, x);
+                        a.iter_mut().for_each(|y| *y = (*y).max(x));
+                    }
+                    2 => {
+                        segbeats.range_add(range, x);
+                        a.iter_mut().for_each(|y| *y += x);
+                    }
+                    3 => {
+                        let expected = a.iter().copied().min().unwrap_or(i64::MAX);
+                        assert_eq!(segbeats.query_min(range), expected);
+                    }
+                    4 => {
+                        let expected = a.iter().copied().max().unwrap_or(i64::MIN);
+                        assert_eq!(segbeats.query_max(range), expected);
+                    }
+                    5 => {
+                        let expected = a.iter().sum::<i64>();
+                        assert_eq!(segbeats.query_sum(range), expected);
+                    }
+                    _ => unreachable!(),
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn test_second_max_of_equal_maxima() {
+        // 最大値が複数あっても 2 番目の最大値は真に小さい値です。そうでないと chmin のタグが
+        // 付かず、葉まで潜ってしまいます。
+        let segbeats = Segbeats::new(&[9_i64, 9, 5, 5]);
+        let root = segbeats.table.borrow()[1];
+        assert_eq!((root.max, root.c_max), ([9, 5], 2));
+        assert_eq!((root.min, root.c_min), ([5, 9], 2));
+    }
+
+    #[test]
+    fn test_equal_max_blocks() {
+        let n = 48;
+        let mut vec = (0..n)
+            .map(|i| [9, 9, 9, 5, 5, 9][i % 6])
+            .collect::<Vec<i64>>();
+        let mut segbeats = Segbeats::new(&vec);
+        for &x in &[9, 7, 5, 5, 4, 2] {
+            for range in [0..n, 1..n - 1, 3..5, 6..30, 0..1, 17..18, 30..48] {
+                segbeats.change_min(range.clone(), x);
+                vec[range.clone()].iter_mut().for_each(|y| *y = (*y).min(x));
+                segbeats.range_add(range.clone(), 1);
+                vec[range.clone()].iter_mut().for_each(|y| *y += 1);
+                segbeats.change_max(range.clone(), x - 3);
+                vec[range].iter_mut().for_each(|y| *y = (*y).max(x - 3));
+                for l in 0..=n {
+                    for r in l..=n {
+                        let a = &vec[l..r];
+                        let max = a.iter().copied().max().unwrap_or(i64::MIN);
+                        let min = a.iter().copied().min().unwrap_or(i64::MAX);
+                        assert_eq!(segbeats.query_max(l..r), max);
+                        assert_eq!(segbeats.query_min(l..r), min);
+                        assert_eq!(segbeats.query_sum(l..r), a.iter().sum::<i64>());
+                    }
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn test_large_sum() {
+        // n = 2 × 10^5, |x| ≤ 10^9 の総和は 2 × 10^14 程度なので、i64 で足ります。
+        let mut rng = StdRng::seed_from_u64(42);
+        let n = 200_000;
+        let mut vec = vec![1_000_000_000_i64; n];
+        let mut segbeats = Segbeats::new(&vec);
+        for _ in 0..30 {
+            let range = rng.sample(SubRange(0..n));
+            let x = rng.gen_range(-1_000_000_000..=1_000_000_000);
+            match rng.gen_range(0..3) {
+                0 => {
+                    segbeats.change_min(range.clone(), x);
+                    vec[range].iter_mut().for_each(|y| *y = (*y).min(x));
+                }
+                1 => {
+                    segbeats.change_max(range.clone(), x);
+                    vec[range].iter_mut().for_each(|y| *y = (*y).max(x));
+                }
+                2 => {
+                    segbeats.range_add(range.clone(), x / 4);
+                    vec[range].iter_mut().for_each(|y| *y += x / 4);
+                }
+                _ => unreachable!(),
+            }
+            assert_eq!(segbeats.query_sum(..n), vec.iter().sum::<i64>());
+        }
+    }
+
+    #[test]
+    #[should_panic(expected = "range end index 7 out of range for length 5")]
+    fn test_range_beyond_len() {
+        // 内部の表の大きさは 8 ですが、長さ 5 で検査します。
+        Segbeats::new(&[0_i64; 5]).change_min(6..7, 0);
+    }
+}
@@ -0,0 +1,311 @@
+use crate::Op;
+
+/// A composable action $x \mapsto \mathrm{clamp}(x, l, h) + a$ on `i64` values.
+///
+/// Every action made of assignments, additions, chmins and chmaxes is of this form, and so is the
+/// composition of two of them, which is the error-prone part this type takes care of.
+/// Additions saturate at the bounds of `i64`, and so do their compositions: adding `i64::MAX`
+/// twice and then `i64::MIN` to `0` gives `-1`, not `i64::MAX`.
+///
+/// # Examples
+///
+/// ```
+/// use lazy_segtree::ClampAdd;
+///
+/// // Applies `add(3)` and then `chmin(5)`.
+/// let f = ClampAdd::chmin(5).compose(&ClampAdd::add(3));
+/// assert_eq!(f.apply(1), 4);
+/// assert_eq!(f.apply(10), 5);
+/// assert_eq!(ClampAdd::add(1).apply(i64::MAX), i64::MAX);
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct ClampAdd {
+    lo: i64,
+    hi: i64,
+    // Both `lo + add` and `hi + add` fit in `i64`, so `add` fits in `i128`.
+    add: i128,
+}
+impl ClampAdd {
+    /// Returns the identity action.
+    pub fn identity() -> Self {
+        Self {
+            lo: i64::MIN,
+            hi: i64::MAX,
+            add: 0,
+        }
+    }
+
+    /// Returns the action $x \mapsto c$.
+    pub fn assign(c: i64) -> Self {
+        Self {
+            lo: c,
+            hi: c,
+            add: 0,
+        }
+    }
+
+    /// Returns the action $x \mapsto x + a$, saturating at the bounds of `i64`.
+    pub fn add(a: i64) -> Self {
+        Self {
+            lo: i64::MIN.saturating_sub(a),
+            hi: i64::MAX.saturating_sub(a),
+            add: a.into(),
+        }
+    }
+
+    /// Returns the action $x \mapsto \min(x, c)$.
+    pub fn chmin(c: i64) -> Self {
+        Self {
+            lo: i64::MIN,
+            hi: c,
+            add: 0,
+        }
+    }
+
+    /// Returns the action $x \mapsto \max(x, c)$.
+    pub fn chmax(c: i64) -> Self {
+        Self {
+            lo: c,
+            hi: i64::MAX,
+            add: 0,
+        }
+    }
+
+    /// Returns the action $x \mapsto \mathrm{clamp}(x, lo, hi)$.
+    ///
+    /// # Panics
+    ///
+    /// If `lo > hi`.
+    pub fn clamp(lo: i64, hi: i64) -> Self {
+        assert!(lo <= hi, "clamp bounds are reversed: {} > {}", lo, hi);
+        Self { lo, hi, add: 0 }
+    }
+
+    /// Applies the action to a value.
+    pub fn apply(&self, x: i64) -> i64 {
+        (i128::from(x.clamp(self.lo, self.hi)) + self.add) as i64
+    }
+
+    /// Returns the action that applies `other` and then `self`.
+    pub fn compose(&self, other: &Self) -> Self {
+        // $\mathrm{clamp}(\mathrm{clamp}(x, l_1, h_1) + a_1, l_2, h_2) + a_2
+        // = \mathrm{clamp}(x, \mathrm{clamp}(l_1, l, h), \mathrm{clamp}(h_1, l, h)) + a_1 + a_2$
+        // where $l = l_2 - a_1$ and $h = h_2 - a_1$ may be out of `i64`.
+        let l = i128::from(self.lo) - other.add;
+        let h = i128::from(self.hi) - other.add;
+        let lo = i128::from(other.lo).clamp(l, h);
+        let hi = i128::from(other.hi).clamp(l, h);
+        let add = other.add + self.add;
+        if lo == hi {
+            // The bounds may be out of `i64`, but the constant is not.
+            Self::assign((lo + add) as i64)
+        } else {
+            // Since $l_1 \le lo < hi \le h_1$, they are in `i64`.
+            Self {
+                lo: lo as i64,
+                hi: hi as i64,
+                add,
+            }
+        }
+    }
+}
+impl Default for ClampAdd {
+    fn default() -> Self {
+        Self::identity()
+    }
+}
+
+/// The minimum and the maximum of a range.
+///
+/// The empty range has `min = i64::MAX` and `max = i64::MIN`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct MinMax {
+    /// The minimum.
+    pub min: i64,
+    /// The maximum.
+    pub max: i64,
+}
+impl MinMax {
+    /// Constructs a value of a single element.
+    pub fn single(x: i64) -> Self {
+        Self { min: x, max: x }
+    }
+}
+
+/// Range [`ClampAdd`] actions and range minimums and maximums of `i64` values.
+///
+/// Range sums are not supported since a chmin changes the sum by an amount that depends on every
+/// element. Use `lazy_segbeats` for range chmin, chmax, add and sum.
+///
+/// # Examples
+///
+/// ```
+/// use lazy_segtree::ClampAdd;
+/// use lazy_segtree::ClampAddMinMax;
+/// use lazy_segtree::LazySegtree;
+/// use lazy_segtree::MinMax;
+///
+/// let mut seg = [3, 1, 4, 1, 5]
+///     .iter()
+///     .map(|&x| MinMax::single(x))
+///     .collect::<LazySegtree<ClampAddMinMax>>();
+/// seg.range_apply(1..4, &ClampAdd::chmax(2));
+/// seg.range_apply(.., &ClampAdd::add(10));
+/// assert_eq!(seg.fold(..), MinMax { min: 12, max: 15 });
+/// seg.range_apply(3.., &ClampAdd::assign(0));
+/// assert_eq!(seg.fold(1..4), MinMax { min: 0, max: 14 });
+/// ```
+pub enum ClampAddMinMax {}
+impl Op for ClampAddMinMax {
+    type Operator = ClampAdd;
+    type Value = MinMax;
+
+    fn identity() -> Self::Value {
+        MinMax {
+            min: i64::MAX,
+            max: i64::MIN,
+        }
+    }
+
+    fn op(lhs: &Self::Value, rhs: &Self::Value) -> Self::Value {
+        MinMax {
+            min: lhs.min.min(rhs.min),
+            max: lhs.max.max(rhs.max),
+        }
+    }
+
+    fn apply(op: &Self::Operator, value: &Self::Value) -> Self::Value {
+        if value.min > value.max {
+            return *value;
+        }
+        // The action is monotone.
+        MinMax {
+            min: op.apply(value.min),
+            max: op.apply(value.max),
+        }
+    }
+
+    fn identity_op() -> Self::Operator {
+        ClampAdd::identity()
+    }
+
+    fn compose(op: &Self::Operator, other: &Self::Operator) -> Self::Operator {
+        op.compose(other)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::LazySegtree;
+    use rand::rngs::StdRng;
+    use rand::Rng;
+    use rand::SeedableRng;
+
+    // An action with the operation it stands for, applied step by step.
+    fn random_action(rng: &mut StdRng, extreme: bool) -> (ClampAdd, impl Fn(i64) -> i64) {
+        let c: i64 = if extreme && rng.gen_bool(0.5) {
+            if rng.gen() {
+                i64::MAX - rng.gen_range(0..3_i64)
+            } else {
+                i64::MIN + rng.gen_range(0..3_i64)
+            }
+        } else {
+            rng.gen_range(-20..=20_i64)
+        };
+        let kind: u32 = rng.gen_range(0..4);
+        let action = match kind {
+            0 => ClampAdd::assign(c),
+            1 => ClampAdd::add(c),
+            2 => ClampAdd::chmin(c),
+            _ => ClampAdd::chmax(c),
+        };
+        let naive = move |x: i64| match kind {
+            0 => c,
+            1 => x.saturating_add(c),
+            2 => x.min(c),
+            _ => x.max(c),
+        };
+        (action, naive)
+    }
+
+    #[test]
+    fn test_compose() {
+        let mut rng = StdRng::seed_from_u64(42);
+        for _ in 0..2000 {
+            let extreme = rng.gen();
+            let mut f = ClampAdd::identity();
+            let mut naives = Vec::new();
+            for _ in 0..rng.gen_range(0..8_usize) {
+                let (action, naive) = random_action(&mut rng, extreme);
+                f = action.compose(&f);
+                naives.push(naive);
+            }
+            for _ in 0..10 {
+                let x = if rng.gen() {
+                    rng.gen_range(-30..=30_i64)
+                } else {
+                    [i64::MIN, i64::MIN + 1, i64::MAX - 1, i64::MAX][rng.gen_range(0..4_usize)]
+                };
+                let expected = naives.iter().fold(x, |x, naive| naive(x));
+                assert_eq!(f.apply(x), expected);
+            }
+        }
+    }
+
+    #[test]
+    fn test_saturation() {
+        let twice = ClampAdd::add(i64::MAX).compose(&ClampAdd::add(i64::MAX));
+        let f = ClampAdd::add(i64::MIN).compose(&twice);
+        assert_eq!(f.apply(0), -1);
+        assert_eq!(f.apply(i64::MIN), -2);
+        let f = ClampAdd::add(5)
+            .compose(&ClampAdd::add(i64::MAX).compose(&ClampAdd::chmin(i64::MIN + 1)));
+        assert_eq!(f.apply(i64::MIN), 4);
+        assert_eq!(f.apply(0), 5);
+        assert_eq!(ClampAdd::chmin(i64::MAX), ClampAdd::identity());
+        assert_eq!(ClampAdd::add(0), ClampAdd::identity());
+    }
+
+    #[test]
+    #[should_panic(expected = "clamp bounds are reversed: 3 > 2")]
+    fn test_clamp_reversed() {
+        ClampAdd::clamp(3, 2);
+    }
+
+    #[test]
+    fn test_clamp_add_min_max() {
+        let mut rng = StdRng::seed_from_u64(42);
+        for _ in 0..200 {
+            let n = rng.gen_range(1..=40_usize);
+            let extreme = rng.gen();
+            let mut vec = (0..n)
+                .map(|_| rng.gen_range(-20..=20_i64))
+                .collect::<Vec<_>>();
+            let mut seg = vec
+                .iter()
+                .map(|&x| MinMax::single(x))
+                .collect::<LazySegtree<ClampAddMinMax>>();
+            for _ in 0..200 {
+                let mut l = rng.gen_range(0..=n);
+                let mut r = rng.gen_range(0..=n);
+                if l > r {
+                    std::mem::swap(&mut l, &mut r);
+                }
+                if rng.gen() {
+                    let (action, naive) = random_action(&mut rng, extreme);
+                    seg.range_apply(l..r, &action);
+                    vec[l..r].iter_mut().for_each(|x| *x = naive(*x));
+                } else {
+                    let expected = MinMax {
+                        min: vec[l..r].iter().copied().min().unwrap_or(i64::MAX),
+                        max: vec[l..r].iter().copied().max().unwrap_or(i64::MIN),
+                    };
+                    assert_eq!(seg.fold(l..r), expected);
+                }
+            }
+            let result = (0..n).map(|i| seg.get(i).min).collect::<Vec<_>>();
+            assert_eq!(result, vec);
+        }
+    }
+}
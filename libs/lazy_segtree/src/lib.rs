@@ -6,6 +6,17 @@
 //!
 //! It does not support binary searches.
 //!
+//! # Specializations
+//!
+//! - [`RangeAssignRangeSum`]: range assignments and range sums of `u32` values
+//!
+//! # Operations
+//!
+//! - [`AddSum`]: range additions and range sums of `i64` values
+//! - [`AffineSum`]: range affine maps and range sums in $\mathbb{F}_P$
+//! - [`ClampAddMinMax`]: range assignments, additions, chmins and chmaxes ([`ClampAdd`]) and range
+//!   minimums and maximums of `i64` values
+//!
 //! # Example
 //!
 //! ```
@@ -43,8 +54,21 @@
 //! seg.range_apply(3..6, &2);
 //! assert_eq!(seg.fold(0..8), 11);
 //! ```
+mod clamp_add;
+mod ops;
+mod range_assign_range_sum;
+
+pub use clamp_add::ClampAdd;
+pub use clamp_add::ClampAddMinMax;
+pub use clamp_add::MinMax;
+use open::resolve_range;
+pub use ops::AddSum;
+pub use ops::AffineSum;
+pub use ops::WithLen;
+pub use range_assign_range_sum::RangeAssignRangeSum;
 use std::iter::FromIterator;
 use std::mem::replace;
+use std::ops::Range;
 use std::ops::RangeBounds;
 
 /// Opertions for a lazy segment tree.
@@ -97,7 +121,7 @@ impl<O: Op> LazySegtree<O> {
     /// Applies an operator to a range.
     pub fn range_apply<R: RangeBounds<usize>>(&mut self, range: R, f: &O::Operator) {
         let n = self.operators.len() / 2;
-        let (l, r) = open(range, n);
+        let Range { start: l, end: r } = resolve_range(n, range);
         if l == r {
             return;
         }
@@ -132,7 +156,10 @@ impl<O: Op> LazySegtree<O> {
     /// Folds a range.
     pub fn fold<R: RangeBounds<usize>>(&mut self, range: R) -> O::Value {
         let n = self.operators.len() / 2;
-        let (mut l, mut r) = open(range, n);
+        let Range {
+            start: mut l,
+            end: mut r,
+        } = resolve_range(n, range);
         if l == r {
             return O::identity();
         }
@@ -213,21 +240,6 @@ where
     }
 }
 
-fn open<B: RangeBounds<usize>>(bounds: B, n: usize) -> (usize, usize) {
-    use std::ops::Bound;
-    let start = match bounds.start_bound() {
-        Bound::Unbounded => 0,
-        Bound::Included(&x) => x,
-        Bound::Excluded(&x) => x + 1,
-    };
-    let end = match bounds.end_bound() {
-        Bound::Unbounded => n,
-        Bound::Included(&x) => x + 1,
-        Bound::Excluded(&x) => x,
-    };
-    (start, end)
-}
-
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -331,4 +343,27 @@ mod tests {
             }
         }
     }
+
+    fn unit_segtree(n: usize) -> LazySegtree<O> {
+        (0..n).map(|_| Value { value: 1, len: 1 }).collect()
+    }
+
+    #[test]
+    #[should_panic(expected = "range starts at 3 but ends at 2")]
+    fn test_fold_reversed_range() {
+        let (start, end) = (3, 2);
+        unit_segtree(5).fold(start..end);
+    }
+
+    #[test]
+    #[should_panic(expected = "range end index 6 out of range for length 5")]
+    fn test_range_apply_out_of_range() {
+        unit_segtree(5).range_apply(4..6, &Affine { a: 1, b: 1 });
+    }
+
+    #[test]
+    #[should_panic(expected = "attempted to index up to maximum usize")]
+    fn test_fold_included_usize_max() {
+        unit_segtree(5).fold(..=usize::MAX);
+    }
 }
@@ -0,0 +1,196 @@
+use crate::Op;
+use fp::Fp;
+
+/// A value with the number of the elements it covers.
+///
+/// Actions such as range additions need the length of the range to update its sum.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub struct WithLen<T> {
+    /// The sum of the elements.
+    pub value: T,
+    /// The number of the elements.
+    pub len: usize,
+}
+impl<T> WithLen<T> {
+    /// Constructs a value of a single element.
+    pub fn single(value: T) -> Self {
+        Self { value, len: 1 }
+    }
+}
+
+/// Range additions and range sums of `i64` values.
+///
+/// # Examples
+///
+/// ```
+/// use lazy_segtree::AddSum;
+/// use lazy_segtree::LazySegtree;
+/// use lazy_segtree::WithLen;
+///
+/// let mut seg = [3, 1, 4, 1, 5]
+///     .iter()
+///     .map(|&x| WithLen::single(x))
+///     .collect::<LazySegtree<AddSum>>();
+/// seg.range_apply(1..4, &10);
+/// assert_eq!(seg.fold(..).value, 44);
+/// assert_eq!(seg.fold(2..).value, 30);
+/// ```
+pub enum AddSum {}
+impl Op for AddSum {
+    type Operator = i64;
+    type Value = WithLen<i64>;
+
+    fn identity() -> Self::Value {
+        WithLen { value: 0, len: 0 }
+    }
+
+    fn op(lhs: &Self::Value, rhs: &Self::Value) -> Self::Value {
+        WithLen {
+            value: lhs.value + rhs.value,
+            len: lhs.len + rhs.len,
+        }
+    }
+
+    fn apply(op: &Self::Operator, value: &Self::Value) -> Self::Value {
+        WithLen {
+            value: value.value + op * value.len as i64,
+            len: value.len,
+        }
+    }
+
+    fn identity_op() -> Self::Operator {
+        0
+    }
+
+    fn compose(op: &Self::Operator, other: &Self::Operator) -> Self::Operator {
+        op + other
+    }
+}
+
+/// Range affine maps $x \mapsto ax + b$ and range sums in $\mathbb{F}_P$.
+///
+/// The operator `(a, b)` stands for $x \mapsto ax + b$.
+///
+/// # Examples
+///
+/// ```
+/// use fp::Fp;
+/// use lazy_segtree::AffineSum;
+/// use lazy_segtree::LazySegtree;
+/// use lazy_segtree::WithLen;
+///
+/// type F = Fp<998244353>;
+/// let mut seg = (1..=4)
+///     .map(|x| WithLen::single(F::new(x)))
+///     .collect::<LazySegtree<AffineSum<998244353>>>();
+/// // [1, 2, 3, 4] -> [1, 5, 7, 4]
+/// seg.range_apply(1..3, &(F::new(2), F::new(1)));
+/// assert_eq!(seg.fold(..).value, F::new(17));
+/// ```
+pub enum AffineSum<const P: u64> {}
+impl<const P: u64> Op for AffineSum<P> {
+    type Operator = (Fp<P>, Fp<P>);
+    type Value = WithLen<Fp<P>>;
+
+    fn identity() -> Self::Value {
+        WithLen {
+            value: Fp::new(0),
+            len: 0,
+        }
+    }
+
+    fn op(lhs: &Self::Value, rhs: &Self::Value) -> Self::Value {
+        WithLen {
+            value: lhs.value + rhs.value,
+            len: lhs.len + rhs.len,
+        }
+    }
+
+    fn apply(&(a, b): &Self::Operator, value: &Self::Value) -> Self::Value {
+        WithLen {
+            value: a * value.value + b * Fp::from(value.len),
+            len: value.len,
+        }
+    }
+
+    fn identity_op() -> Self::Operator {
+        (Fp::new(1), Fp::new(0))
+    }
+
+    fn compose(&(a, b): &Self::Operator, &(c, d): &Self::Operator) -> Self::Operator {
+        (a * c, a * d + b)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::LazySegtree;
+    use rand::rngs::StdRng;
+    use rand::Rng;
+    use rand::SeedableRng;
+    use randtools::SubRange;
+    use std::ops::Range;
+
+    const P: u64 = 998244353;
+    type F = Fp<P>;
+
+    #[test]
+    fn test_add_sum() {
+        let mut rng = StdRng::seed_from_u64(42);
+        for _ in 0..200 {
+            let n = rng.gen_range(0..=40);
+            let mut vec = (0..n)
+                .map(|_| rng.gen_range(-100..=100))
+                .collect::<Vec<i64>>();
+            let mut seg = vec
+                .iter()
+                .map(|&x| WithLen::single(x))
+                .collect::<LazySegtree<AddSum>>();
+            for _ in 0..40 {
+                let Range { start: l, end: r } = rng.sample(SubRange(0..n));
+                if rng.gen() {
+                    let x = rng.gen_range(-100..=100);
+                    seg.range_apply(l..r, &x);
+                    vec[l..r].iter_mut().for_each(|y| *y += x);
+                } else {
+                    let result = seg.fold(l..r);
+                    assert_eq!(result.value, vec[l..r].iter().sum::<i64>());
+                    assert_eq!(result.len, r - l);
+                }
+            }
+            let result = (0..n).map(|i| seg.get(i).value).collect::<Vec<_>>();
+            assert_eq!(result, vec);
+        }
+    }
+
+    #[test]
+    fn test_affine_sum() {
+        let mut rng = StdRng::seed_from_u64(42);
+        for _ in 0..200 {
+            let n = rng.gen_range(0..=40);
+            let mut vec = (0..n)
+                .map(|_| F::new(rng.gen_range(0..P)))
+                .collect::<Vec<_>>();
+            let mut seg = vec
+                .iter()
+                .map(|&x| WithLen::single(x))
+                .collect::<LazySegtree<AffineSum<P>>>();
+            for _ in 0..40 {
+                let Range { start: l, end: r } = rng.sample(SubRange(0..n));
+                if rng.gen() {
+                    let a = F::new(rng.gen_range(0..P));
+                    let b = F::new(rng.gen_range(0..P));
+                    seg.range_apply(l..r, &(a, b));
+                    vec[l..r].iter_mut().for_each(|x| *x = a * *x + b);
+                } else {
+                    let result = seg.fold(l..r);
+                    assert_eq!(result.value, vec[l..r].iter().sum::<F>());
+                    assert_eq!(result.len, r - l);
+                }
+            }
+            let result = (0..n).map(|i| seg.get(i).value).collect::<Vec<_>>();
+            assert_eq!(result, vec);
+        }
+    }
+}
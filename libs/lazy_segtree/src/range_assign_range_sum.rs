@@ -0,0 +1,364 @@
+use open::resolve_range;
+use std::ops::RangeBounds;
+
+// The number of the elements under a leaf of the tree.
+const BLOCK: usize = 16;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+struct Node {
+    // The sum of the subtree, with `tag` already applied.
+    sum: u64,
+    // Every element of the subtree is `tag` if it is `Some`.
+    tag: Option<u32>,
+}
+
+/// A lazy segment tree specialized for range assignments and range sums of `u32` values.
+///
+/// The leaves of the tree are blocks of 16 elements stored in a plain `Vec<u32>`, and each node
+/// keeps its sum next to its assignment tag, so $n = 10 ^ 7$ takes about 75 MB.
+///
+/// # Examples
+///
+/// ```
+/// use lazy_segtree::RangeAssignRangeSum;
+/// let mut seg = RangeAssignRangeSum::from_slice(&[3, 1, 4, 1, 5, 9, 2, 6]);
+/// assert_eq!(seg.sum(..), 31);
+/// seg.assign(2..6, 10);
+/// assert_eq!(seg.sum(1..7), 43);
+/// assert_eq!(seg.get(5), 10);
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RangeAssignRangeSum {
+    values: Vec<u32>,
+    nodes: Vec<Node>,
+    lg: u32,
+}
+impl RangeAssignRangeSum {
+    /// Constructs a tree from a slice.
+    ///
+    /// # Complexity
+    ///
+    /// $O(n)$
+    pub fn from_slice(values: &[u32]) -> Self {
+        let size = ((values.len() + BLOCK - 1) / BLOCK).next_power_of_two();
+        let mut nodes = vec![Node { sum: 0, tag: None }; 2 * size];
+        for (b, chunk) in values.chunks(BLOCK).enumerate() {
+            nodes[size + b].sum = chunk.iter().map(|&x| u64::from(x)).sum();
+        }
+        let mut this = Self {
+            values: values.to_vec(),
+            nodes,
+            lg: size.trailing_zeros(),
+        };
+        (1..size).rev().for_each(|i| this.update(i));
+        this
+    }
+
+    /// Returns the number of the elements.
+    pub fn len(&self) -> usize {
+        self.values.len()
+    }
+
+    /// Returns `true` if there are no elements.
+    pub fn is_empty(&self) -> bool {
+        self.values.is_empty()
+    }
+
+    /// Assigns `value` to every element in `range`.
+    ///
+    /// # Complexity
+    ///
+    /// $O(\log n)$
+    pub fn assign(&mut self, range: impl RangeBounds<usize>, value: u32) {
+        let (l, r) = self.resolve(range);
+        if l == r {
+            return;
+        }
+        let (lb, rb) = self.full_blocks(l, r);
+        if l / BLOCK == (r - 1) / BLOCK {
+            self.assign_in_block(l / BLOCK, l, r, value);
+            return;
+        }
+        if l < lb * BLOCK {
+            self.assign_in_block(l / BLOCK, l, lb * BLOCK, value);
+        }
+        if rb * BLOCK < r {
+            self.assign_in_block(rb, rb * BLOCK, r, value);
+        }
+        let size = 1 << self.lg;
+        let (l, r) = (lb + size, rb + size);
+        self.push_boundaries(l, r);
+        {
+            let (mut l, mut r) = (l, r);
+            while l < r {
+                if l & 1 != 0 {
+                    self.fill(l, value);
+                    l += 1;
+                }
+                if r & 1 != 0 {
+                    r -= 1;
+                    self.fill(r, value);
+                }
+                l >>= 1;
+                r >>= 1;
+            }
+        }
+        for p in 1..=self.lg {
+            if (l >> p) << p != l {
+                self.update(l >> p);
+            }
+            if (r >> p) << p != r {
+                self.update((r - 1) >> p);
+            }
+        }
+    }
+
+    /// Returns the sum of the elements in `range`.
+    ///
+    /// # Complexity
+    ///
+    /// $O(\log n)$
+    pub fn sum(&self, range: impl RangeBounds<usize>) -> u64 {
+        let (l, r) = self.resolve(range);
+        self.sum_from(1, l, r)
+    }
+
+    /// Returns the `i`-th element.
+    ///
+    /// # Panics
+    ///
+    /// If `i` is out of bounds.
+    ///
+    /// # Complexity
+    ///
+    /// $O(\log n)$
+    pub fn get(&self, i: usize) -> u32 {
+        assert!(
+            i < self.len(),
+            "index out of bounds: the len is {} but the index is {}",
+            self.len(),
+            i
+        );
+        // The tag of the highest node is the latest one.
+        let leaf = (1 << self.lg) + i / BLOCK;
+        (0..=self.lg)
+            .rev()
+            .find_map(|p| self.nodes[leaf >> p].tag)
+            .unwrap_or(self.values[i])
+    }
+
+    fn resolve(&self, range: impl RangeBounds<usize>) -> (usize, usize) {
+        let range = resolve_range(self.len(), range);
+        (range.start, range.end)
+    }
+
+    // The blocks entirely contained in `l..r`.
+    fn full_blocks(&self, l: usize, r: usize) -> (usize, usize) {
+        let lb = (l + BLOCK - 1) / BLOCK;
+        let rb = if r == self.len() { (r + BLOCK - 1) / BLOCK } else { r / BLOCK };
+        (lb, rb)
+    }
+
+    // The elements in the subtree of `i`.
+    fn span(&self, i: usize) -> (usize, usize) {
+        let h = self.lg + 1 - (usize::BITS - i.leading_zeros());
+        let start = ((i << h) - (1 << self.lg)) * BLOCK;
+        let end = start + (BLOCK << h);
+        (start.min(self.len()), end.min(self.len()))
+    }
+
+    // The number of the elements in the subtree of `i`.
+    fn width(&self, i: usize) -> u64 {
+        let (start, end) = self.span(i);
+        (end - start) as u64
+    }
+
+    fn fill(&mut self, i: usize, value: u32) {
+        self.nodes[i] = Node {
+            sum: u64::from(value) * self.width(i),
+            tag: Some(value),
+        };
+    }
+
+    fn push(&mut self, i: usize) {
+        if let Some(value) = self.nodes[i].tag.take() {
+            self.fill(2 * i, value);
+            self.fill(2 * i + 1, value);
+        }
+    }
+
+    fn update(&mut self, i: usize) {
+        self.nodes[i].sum = self.nodes[2 * i].sum + self.nodes[2 * i + 1].sum;
+    }
+
+    fn push_boundaries(&mut self, l: usize, r: usize) {
+        for p in (1..=self.lg).rev() {
+            if (l >> p) << p != l {
+                self.push(l >> p);
+            }
+            if (r >> p) << p != r {
+                self.push((r - 1) >> p);
+            }
+        }
+    }
+
+    // Pushes every tag down to the elements of the block `b`.
+    fn descend(&mut self, b: usize) -> usize {
+        let leaf = (1 << self.lg) + b;
+        for p in (1..=self.lg).rev() {
+            self.push(leaf >> p);
+        }
+        if let Some(value) = self.nodes[leaf].tag.take() {
+            let end = (b * BLOCK + BLOCK).min(self.len());
+            self.values[b * BLOCK..end].fill(value);
+        }
+        leaf
+    }
+
+    fn assign_in_block(&mut self, b: usize, l: usize, r: usize, value: u32) {
+        let leaf = self.descend(b);
+        let start = b * BLOCK;
+        let end = (start + BLOCK).min(self.len());
+        self.values[l..r].fill(value);
+        self.nodes[leaf].sum = self.values[start..end].iter().map(|&x| u64::from(x)).sum();
+        for p in 1..=self.lg {
+            self.update(leaf >> p);
+        }
+    }
+
+    // The sum of `l..r` in the subtree of `i`, whose proper ancestors have no tags.
+    fn sum_from(&self, i: usize, l: usize, r: usize) -> u64 {
+        let (start, end) = self.span(i);
+        let (l, r) = (l.max(start), r.min(end));
+        if l >= r {
+            return 0;
+        }
+        if let Some(value) = self.nodes[i].tag {
+            return u64::from(value) * (r - l) as u64;
+        }
+        if (l, r) == (start, end) {
+            return self.nodes[i].sum;
+        }
+        if i >= 1 << self.lg {
+            return self.values[l..r].iter().map(|&x| u64::from(x)).sum();
+        }
+        self.sum_from(2 * i, l, r) + self.sum_from(2 * i + 1, l, r)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::LazySegtree;
+    use crate::Op;
+    use rand::rngs::StdRng;
+    use rand::Rng;
+    use rand::SeedableRng;
+    use randtools::SubRange;
+    use std::ops::Range;
+    use std::time::Instant;
+
+    enum O {}
+    impl Op for O {
+        type Operator = Option<u32>;
+        type Value = (u64, u64);
+
+        fn identity() -> Self::Value {
+            (0, 0)
+        }
+
+        fn op(lhs: &Self::Value, rhs: &Self::Value) -> Self::Value {
+            (lhs.0 + rhs.0, lhs.1 + rhs.1)
+        }
+
+        fn apply(op: &Self::Operator, value: &Self::Value) -> Self::Value {
+            match op {
+                Some(x) => (u64::from(*x) * value.1, value.1),
+                None => *value,
+            }
+        }
+
+        fn identity_op() -> Self::Operator {
+            None
+        }
+
+        fn compose(op: &Self::Operator, other: &Self::Operator) -> Self::Operator {
+            op.or(*other)
+        }
+    }
+
+    #[test]
+    fn test_random() {
+        let mut rng = StdRng::seed_from_u64(42);
+        for _ in 0..200 {
+            let n = rng.gen_range(0..=100);
+            let max = if rng.gen_bool(0.5) { u32::MAX } else { 10 };
+            let mut vec = (0..n).map(|_| rng.gen_range(0..=max)).collect::<Vec<_>>();
+            let mut seg = RangeAssignRangeSum::from_slice(&vec);
+            let mut generic = vec
+                .iter()
+                .map(|&x| (u64::from(x), 1))
+                .collect::<LazySegtree<O>>();
+            assert_eq!(seg.len(), n);
+            for _ in 0..100 {
+                match rng.gen_range(0..3) {
+                    0 => {
+                        let Range { start: l, end: r } = rng.sample(SubRange(0..n));
+                        let value = rng.gen_range(0..=max);
+                        seg.assign(l..r, value);
+                        generic.range_apply(l..r, &Some(value));
+                        vec[l..r].fill(value);
+                    }
+                    1 => {
+                        let Range { start: l, end: r } = rng.sample(SubRange(0..n));
+                        let expected = vec[l..r].iter().map(|&x| u64::from(x)).sum::<u64>();
+                        assert_eq!(generic.fold(l..r).0, expected);
+                        assert_eq!(seg.sum(l..r), expected);
+                    }
+                    2 => {
+                        if n != 0 {
+                            let i = rng.gen_range(0..n);
+                            assert_eq!(seg.get(i), vec[i]);
+                        }
+                    }
+                    _ => unreachable!(),
+                }
+            }
+            assert_eq!(seg.sum(..), vec.iter().map(|&x| u64::from(x)).sum::<u64>());
+            for (i, &x) in vec.iter().enumerate() {
+                assert_eq!(seg.get(i), x);
+            }
+        }
+    }
+
+    #[test]
+    #[should_panic(expected = "range end index 4 out of range for length 3")]
+    fn test_range_out_of_bounds() {
+        RangeAssignRangeSum::from_slice(&[1, 2, 3]).assign(1..4, 0);
+    }
+
+    #[test]
+    #[ignore]
+    fn test_capacity() {
+        let mut rng = StdRng::seed_from_u64(42);
+        let n = 10_000_000;
+        let q = 1_000_000;
+        let values = (0..n).map(|_| rng.gen()).collect::<Vec<u32>>();
+        let ops = (0..q)
+            .map(|_| (rng.sample(SubRange(0..n)), rng.gen::<u32>()))
+            .collect::<Vec<_>>();
+        let start = Instant::now();
+        let mut seg = RangeAssignRangeSum::from_slice(&values);
+        let mut checksum = 0_u64;
+        for (i, (range, value)) in ops.into_iter().enumerate() {
+            if i % 2 == 0 {
+                seg.assign(range, value);
+            } else {
+                checksum ^= seg.sum(range);
+            }
+        }
+        let elapsed = start.elapsed();
+        assert_ne!(checksum, 0);
+        assert!(elapsed.as_secs() < 5, "took {:?}", elapsed);
+    }
+}
@@ -0,0 +1,132 @@
+use io::ParseLine;
+use io::Usize1;
+
+/// 0-based の頂点番号です。
+///
+/// 問題文の 1-based の番号とは [`from_one_based`](VertexId::from_one_based),
+/// [`to_one_based`](VertexId::to_one_based) で明示的に変換します。ずれを持ち込まないように、
+/// 番号どうしの演算はわざと実装していません。
+///
+/// [`ParseLine`] を実装しているので、1-based の入力から直接読めます。
+///
+/// # Examples
+///
+/// ```
+/// use io::ParseLine;
+/// use make_graph::VertexId;
+/// let (u, v) = <(VertexId, VertexId)>::parse_line("1 3");
+/// assert_eq!((u.index(), v.index()), (0, 2));
+/// assert_eq!(v.to_one_based(), 3);
+/// assert_eq!(VertexId::from_one_based(3), v);
+/// ```
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct VertexId(usize);
+impl VertexId {
+    /// 0-based の番号から構築します。
+    pub fn new(index: usize) -> Self {
+        Self(index)
+    }
+
+    /// 1-based の番号から構築します。
+    ///
+    /// # Panics
+    ///
+    /// デバッグビルドで `x` が `0` のとき
+    pub fn from_one_based(x: usize) -> Self {
+        debug_assert_ne!(x, 0, "a 1-based index must be positive");
+        Self(x.wrapping_sub(1))
+    }
+
+    /// 0-based の番号を返します。
+    pub fn index(self) -> usize {
+        self.0
+    }
+
+    /// 1-based の番号を返します。
+    pub fn to_one_based(self) -> usize {
+        self.0 + 1
+    }
+}
+impl From<Usize1> for VertexId {
+    fn from(Usize1(index): Usize1) -> Self {
+        Self(index)
+    }
+}
+impl From<VertexId> for usize {
+    fn from(VertexId(index): VertexId) -> Self {
+        index
+    }
+}
+impl ParseLine for VertexId {
+    fn parse_line(s: &str) -> Self {
+        Usize1::parse_line(s).into()
+    }
+}
+
+/// 0-based の番号に変換できる型です。グラフを構築する関数は、生の [`usize`] も [`VertexId`]
+/// も受け付けます。
+pub trait IntoIndex {
+    /// 0-based の番号を返します。
+    fn into_index(self) -> usize;
+}
+impl IntoIndex for usize {
+    fn into_index(self) -> usize {
+        self
+    }
+}
+impl IntoIndex for VertexId {
+    fn into_index(self) -> usize {
+        self.0
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::make_undirected_by;
+    use bfs::calc_dist;
+
+    #[test]
+    fn test_one_based_flow() {
+        let input = "5 4\n1 2\n2 3\n1 4\n5 4";
+        let mut lines = input.lines();
+        let (n, m) = <(usize, usize)>::parse_line(lines.next().unwrap());
+        let edges = (0..m)
+            .map(|_| <(VertexId, VertexId)>::parse_line(lines.next().unwrap()))
+            .collect::<Vec<_>>();
+        let g = make_undirected_by(n, &edges, |&(u, v)| [u, v]);
+        assert_eq!(g, vec![vec![1, 3], vec![0, 2], vec![1], vec![0, 4], vec![
+            3
+        ]]);
+
+        let start = VertexId::from_one_based(3);
+        let dist = calc_dist(start.index(), &g);
+        let farthest = (0..n)
+            .map(VertexId::new)
+            .max_by_key(|v| dist[v.index()])
+            .unwrap();
+        assert_eq!(farthest.to_one_based(), 5);
+        assert_eq!(dist, vec![2, 1, 0, 3, 4]);
+    }
+
+    #[test]
+    fn test_conversions() {
+        assert_eq!(VertexId::from(Usize1(4)), VertexId::new(4));
+        assert_eq!(usize::from(VertexId::new(4)), 4);
+        assert_eq!(VertexId::new(4).into_index(), 4);
+        assert_eq!(4_usize.into_index(), 4);
+    }
+
+    #[test]
+    #[cfg(debug_assertions)]
+    #[should_panic(expected = "a 1-based index must be positive")]
+    fn test_from_zero() {
+        VertexId::from_one_based(0);
+    }
+
+    #[test]
+    #[should_panic(expected = "a 1-based index must be positive")]
+    fn test_parse_zero() {
+        VertexId::parse_line("0");
+    }
+}
@@ -1,4 +1,11 @@
 //! 辺全体のスライスから、グラフの隣接リストを作ります。
+//!
+//! `_by` で終わる関数は、頂点番号として [`usize`] のほかに [`VertexId`] も受け付けます。
+
+mod id;
+
+pub use id::IntoIndex;
+pub use id::VertexId;
 
 /// `(u, v)` の形の辺から無向グラフを構築します。
 pub fn tuple_make_undirected(n: usize, edges: &[(usize, usize)]) -> Vec<Vec<usize>> {
@@ -9,13 +16,14 @@ pub fn array_make_undirected(n: usize, edges: &[[usize; 2]]) -> Vec<Vec<usize>>
     make_undirected_by(n, edges, |&[u, v]| [u, v])
 }
 /// 一般の形の辺から（重みなし）無向グラフを構築します。
-pub fn make_undirected_by<E>(
+pub fn make_undirected_by<E, I: IntoIndex>(
     n: usize,
     edges: &[E],
-    f: impl Fn(&E) -> [usize; 2],
+    f: impl Fn(&E) -> [I; 2],
 ) -> Vec<Vec<usize>> {
     let mut g = vec![Vec::new(); n];
     for [u, v] in edges.iter().map(f) {
+        let (u, v) = (u.into_index(), v.into_index());
         g[u].push(v);
         g[v].push(u);
     }
@@ -31,9 +39,16 @@ pub fn array_make_directed(n: usize, edges: &[[usize; 2]]) -> Vec<Vec<usize>> {
     make_directed_by(n, edges, |&[u, v]| [u, v])
 }
 /// 一般の形の辺から（重みなし）有向グラフを構築します。
-pub fn make_directed_by<E>(n: usize, edges: &[E], f: impl Fn(&E) -> [usize; 2]) -> Vec<Vec<usize>> {
+pub fn make_directed_by<E, I: IntoIndex>(
+    n: usize,
+    edges: &[E],
+    f: impl Fn(&E) -> [I; 2],
+) -> Vec<Vec<usize>> {
     let mut g = vec![Vec::new(); n];
-    edges.iter().map(f).for_each(|[u, v]| g[u].push(v));
+    edges
+        .iter()
+        .map(f)
+        .for_each(|[u, v]| g[u.into_index()].push(v.into_index()));
     g
 }
 
@@ -52,13 +67,14 @@ pub fn array_make_undirected_weighted<T: Copy>(
     make_undirected_weighted_by(n, edges, |&([u, v], x)| ([u, v], x))
 }
 /// 一般の形の辺から重みつき無向グラフを構築します。
-pub fn make_undirected_weighted_by<E, T: Copy>(
+pub fn make_undirected_weighted_by<E, I: IntoIndex, T: Copy>(
     n: usize,
     edges: &[E],
-    f: impl Fn(&E) -> ([usize; 2], T),
+    f: impl Fn(&E) -> ([I; 2], T),
 ) -> Vec<Vec<(usize, T)>> {
     let mut g = vec![Vec::new(); n];
     for ([u, v], x) in edges.iter().map(f) {
+        let (u, v) = (u.into_index(), v.into_index());
         g[u].push((v, x));
         g[v].push((u, x));
     }
@@ -80,16 +96,16 @@ pub fn array_make_directed_weighted<T: Copy>(
     make_directed_weighted_by(n, edges, |&([u, v], x)| ([u, v], x))
 }
 /// 一般の形の辺から重み付き有向グラフを構築します。
-pub fn make_directed_weighted_by<E, T: Copy>(
+pub fn make_directed_weighted_by<E, I: IntoIndex, T: Copy>(
     n: usize,
     edges: &[E],
-    f: impl Fn(&E) -> ([usize; 2], T),
+    f: impl Fn(&E) -> ([I; 2], T),
 ) -> Vec<Vec<(usize, T)>> {
     let mut g = vec![Vec::new(); n];
     edges
         .iter()
         .map(f)
-        .for_each(|([u, v], w)| g[u].push((v, w)));
+        .for_each(|([u, v], w)| g[u.into_index()].push((v.into_index(), w)));
     g
 }
 
@@ -0,0 +1,432 @@
+//! Maximum common independent sets of two matroids.
+//!
+//! [`matroid_intersection`] finds a maximum set independent in both matroids by augmenting along
+//! the shortest paths of the exchange graph. A matroid is given as an implementation of
+//! [`Matroid`], which answers whether an element can be added to, or exchanged with an element
+//! of, the current independent set.
+//!
+//! * [`GraphicMatroid`]: the forests of a graph.
+//! * [`PartitionMatroid`]: the sets with at most `capacities[c]` elements of each class `c`.
+//! * [`OracleMatroid`]: any matroid given by an independence oracle, which is slower.
+//!
+//! # Complexity
+//!
+//! Each augmentation runs a BFS over the exchange graph with $O(rm)$ queries, where $r$ is the
+//! size of the result and $m$ is the size of the ground set, so the total is $O(r^2 m)$ queries
+//! besides $O(r)$ calls of [`Matroid::build`].
+//!
+//! # Examples
+//!
+//! A spanning tree with at most one edge of each color:
+//!
+//! ```
+//! use matroid_intersection::matroid_intersection;
+//! use matroid_intersection::GraphicMatroid;
+//! use matroid_intersection::PartitionMatroid;
+//! let edges = [(0, 1), (1, 2), (2, 0), (2, 3)];
+//! let colors = [0, 0, 1, 2];
+//! let mut graphic = GraphicMatroid::new(4, &edges);
+//! let mut partition = PartitionMatroid::new(&colors, &[1, 1, 1]);
+//! let tree = matroid_intersection(&mut graphic, &mut partition);
+//! assert_eq!(tree.len(), 3);
+//! assert!(tree.contains(&2) && tree.contains(&3));
+//! ```
+
+use std::collections::VecDeque;
+
+/// A matroid over the ground set $\\{0, \dots, m - 1\\}$, queried against an independent set.
+pub trait Matroid {
+    /// Returns the size of the ground set.
+    fn len(&self) -> usize;
+
+    /// Returns `true` if the ground set is empty.
+    fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Prepares for the queries against the independent set $I$ given as its indicator.
+    fn build(&mut self, set: &[bool]);
+
+    /// Returns whether $I + y$ is independent for $y \notin I$.
+    fn can_add(&self, y: usize) -> bool;
+
+    /// Returns whether $I - x + y$ is independent for $x \in I$ and $y \notin I$.
+    fn can_exchange(&self, x: usize, y: usize) -> bool;
+}
+
+/// The graphic matroid, whose independent sets are the forests.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct GraphicMatroid {
+    edges: Vec<(usize, usize)>,
+    n: usize,
+    // The forest of $I$ rooted in each component: the component, the preorder interval of the
+    // subtree of each vertex, and the lower endpoint of each edge in $I$.
+    comp: Vec<usize>,
+    enter: Vec<usize>,
+    exit: Vec<usize>,
+    lower: Vec<usize>,
+}
+impl GraphicMatroid {
+    /// Constructs the graphic matroid of a graph with $n$ vertices, whose ground set is `edges`.
+    pub fn new(n: usize, edges: &[(usize, usize)]) -> Self {
+        Self {
+            edges: edges.to_vec(),
+            n,
+            comp: vec![0; n],
+            enter: vec![0; n],
+            exit: vec![0; n],
+            lower: vec![0; edges.len()],
+        }
+    }
+
+    fn is_ancestor(&self, u: usize, v: usize) -> bool {
+        self.enter[u] <= self.enter[v] && self.exit[v] <= self.exit[u]
+    }
+}
+impl Matroid for GraphicMatroid {
+    fn len(&self) -> usize {
+        self.edges.len()
+    }
+
+    fn build(&mut self, set: &[bool]) {
+        let mut g = vec![Vec::new(); self.n];
+        for (i, &(u, v)) in self.edges.iter().enumerate() {
+            if set[i] {
+                g[u].push((v, i));
+                g[v].push((u, i));
+            }
+        }
+        let mut visited = vec![false; self.n];
+        let mut time = 0;
+        for root in 0..self.n {
+            if visited[root] {
+                continue;
+            }
+            visited[root] = true;
+            self.comp[root] = root;
+            self.enter[root] = time;
+            time += 1;
+            let mut stack = vec![(root, 0)];
+            while let Some((u, k)) = stack.pop() {
+                if let Some(&(v, i)) = g[u].get(k) {
+                    stack.push((u, k + 1));
+                    if !visited[v] {
+                        visited[v] = true;
+                        self.comp[v] = root;
+                        self.enter[v] = time;
+                        time += 1;
+                        self.lower[i] = v;
+                        stack.push((v, 0));
+                    }
+                } else {
+                    self.exit[u] = time;
+                }
+            }
+        }
+    }
+
+    fn can_add(&self, y: usize) -> bool {
+        let (u, v) = self.edges[y];
+        self.comp[u] != self.comp[v]
+    }
+
+    fn can_exchange(&self, x: usize, y: usize) -> bool {
+        let (u, v) = self.edges[y];
+        if u == v {
+            return false;
+        }
+        // Removing `x` separates `u` and `v` if `x` is on the path between them.
+        let c = self.lower[x];
+        self.can_add(y) || self.is_ancestor(c, u) != self.is_ancestor(c, v)
+    }
+}
+
+/// The partition matroid, whose independent sets have at most `capacities[c]` elements of each
+/// class `c`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PartitionMatroid {
+    classes: Vec<usize>,
+    capacities: Vec<usize>,
+    counts: Vec<usize>,
+}
+impl PartitionMatroid {
+    /// Constructs the partition matroid where the element `i` is in the class `classes[i]`.
+    pub fn new(classes: &[usize], capacities: &[usize]) -> Self {
+        assert!(
+            classes.iter().all(|&c| c < capacities.len()),
+            "a class has no capacity"
+        );
+        Self {
+            classes: classes.to_vec(),
+            capacities: capacities.to_vec(),
+            counts: vec![0; capacities.len()],
+        }
+    }
+}
+impl Matroid for PartitionMatroid {
+    fn len(&self) -> usize {
+        self.classes.len()
+    }
+
+    fn build(&mut self, set: &[bool]) {
+        self.counts.iter_mut().for_each(|c| *c = 0);
+        for (&c, &b) in self.classes.iter().zip(set) {
+            if b {
+                self.counts[c] += 1;
+            }
+        }
+    }
+
+    fn can_add(&self, y: usize) -> bool {
+        let c = self.classes[y];
+        self.counts[c] < self.capacities[c]
+    }
+
+    fn can_exchange(&self, x: usize, y: usize) -> bool {
+        self.can_add(y) || self.classes[x] == self.classes[y]
+    }
+}
+
+/// A matroid given by an independence oracle, which takes the indicator of a set.
+///
+/// Each query calls the oracle once with a copy of the set.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct OracleMatroid<F> {
+    is_independent: F,
+    set: Vec<bool>,
+}
+impl<F: Fn(&[bool]) -> bool> OracleMatroid<F> {
+    /// Constructs the matroid over the ground set of size $m$.
+    pub fn new(m: usize, is_independent: F) -> Self {
+        Self {
+            is_independent,
+            set: vec![false; m],
+        }
+    }
+}
+impl<F: Fn(&[bool]) -> bool> Matroid for OracleMatroid<F> {
+    fn len(&self) -> usize {
+        self.set.len()
+    }
+
+    fn build(&mut self, set: &[bool]) {
+        self.set.copy_from_slice(set);
+    }
+
+    fn can_add(&self, y: usize) -> bool {
+        let mut set = self.set.clone();
+        set[y] = true;
+        (self.is_independent)(&set)
+    }
+
+    fn can_exchange(&self, x: usize, y: usize) -> bool {
+        let mut set = self.set.clone();
+        set[x] = false;
+        set[y] = true;
+        (self.is_independent)(&set)
+    }
+}
+
+/// Returns a maximum set independent in both `m1` and `m2` in increasing order.
+///
+/// # Panics
+///
+/// If the ground sets have different sizes.
+pub fn matroid_intersection(m1: &mut impl Matroid, m2: &mut impl Matroid) -> Vec<usize> {
+    let m = m1.len();
+    assert_eq!(m, m2.len(), "the ground sets have different sizes");
+    let mut set = vec![false; m];
+    loop {
+        m1.build(&set);
+        m2.build(&set);
+        // BFS from the elements addable in `m1` to those addable in `m2`, where `y` is followed
+        // by `x` if $I - x + y$ is independent in `m2`, and `x` by `y` if so in `m1`.
+        let mut prev = vec![None; m];
+        let mut visited = vec![false; m];
+        let mut queue = VecDeque::new();
+        for y in 0..m {
+            if !set[y] && m1.can_add(y) {
+                visited[y] = true;
+                queue.push_back(y);
+            }
+        }
+        let mut end = None;
+        while let Some(u) = queue.pop_front() {
+            if !set[u] && m2.can_add(u) {
+                end = Some(u);
+                break;
+            }
+            for v in 0..m {
+                if visited[v] || set[v] == set[u] {
+                    continue;
+                }
+                let adjacent = if set[u] { m1.can_exchange(u, v) } else { m2.can_exchange(v, u) };
+                if adjacent {
+                    visited[v] = true;
+                    prev[v] = Some(u);
+                    queue.push_back(v);
+                }
+            }
+        }
+        let Some(mut u) = end else { break };
+        loop {
+            set[u] = !set[u];
+            match prev[u] {
+                Some(v) => u = v,
+                None => break,
+            }
+        }
+    }
+    (0..m).filter(|&i| set[i]).collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rand::rngs::StdRng;
+    use rand::Rng;
+    use rand::SeedableRng;
+
+    fn is_forest(n: usize, edges: &[(usize, usize)], set: &[bool]) -> bool {
+        let mut parent = (0..n).collect::<Vec<_>>();
+        fn find(parent: &mut [usize], mut x: usize) -> usize {
+            while parent[x] != x {
+                x = parent[x];
+            }
+            x
+        }
+        for (&(u, v), &b) in edges.iter().zip(set) {
+            if b {
+                let (u, v) = (find(&mut parent, u), find(&mut parent, v));
+                if u == v {
+                    return false;
+                }
+                parent[u] = v;
+            }
+        }
+        true
+    }
+
+    fn is_within(classes: &[usize], capacities: &[usize], set: &[bool]) -> bool {
+        let mut counts = vec![0; capacities.len()];
+        for (&c, &b) in classes.iter().zip(set) {
+            if b {
+                counts[c] += 1;
+            }
+        }
+        counts.iter().zip(capacities).all(|(x, y)| x <= y)
+    }
+
+    fn brute(m: usize, is_independent: impl Fn(&[bool]) -> bool) -> usize {
+        (0..1_u32 << m)
+            .map(|bs| (0..m).map(|i| bs >> i & 1 == 1).collect::<Vec<_>>())
+            .filter(|set| is_independent(set))
+            .map(|set| set.iter().filter(|&&b| b).count())
+            .max()
+            .unwrap()
+    }
+
+    fn indicator(m: usize, result: &[usize]) -> Vec<bool> {
+        let mut set = vec![false; m];
+        for &i in result {
+            set[i] = true;
+        }
+        set
+    }
+
+    fn random_partition(rng: &mut StdRng, m: usize) -> (Vec<usize>, Vec<usize>) {
+        let k = rng.gen_range(1..=4);
+        let classes = (0..m).map(|_| rng.gen_range(0..k)).collect();
+        let capacities = (0..k).map(|_| rng.gen_range(0..=2)).collect();
+        (classes, capacities)
+    }
+
+    #[test]
+    fn test_graphic_partition() {
+        let mut rng = StdRng::seed_from_u64(42);
+        for _ in 0..300 {
+            let n = rng.gen_range(1..=6);
+            let m = rng.gen_range(0..=10);
+            let edges = (0..m)
+                .map(|_| (rng.gen_range(0..n), rng.gen_range(0..n)))
+                .collect::<Vec<_>>();
+            let (classes, capacities) = random_partition(&mut rng, m);
+            let result = matroid_intersection(
+                &mut GraphicMatroid::new(n, &edges),
+                &mut PartitionMatroid::new(&classes, &capacities),
+            );
+            assert!(result.windows(2).all(|w| w[0] < w[1]));
+            let set = indicator(m, &result);
+            assert!(is_forest(n, &edges, &set));
+            assert!(is_within(&classes, &capacities, &set));
+            let expected = brute(m, |set| {
+                is_forest(n, &edges, set) && is_within(&classes, &capacities, set)
+            });
+            assert_eq!(result.len(), expected);
+
+            // The same with the oracles
+            let oracle = matroid_intersection(
+                &mut OracleMatroid::new(m, |set: &[bool]| is_forest(n, &edges, set)),
+                &mut OracleMatroid::new(m, |set: &[bool]| is_within(&classes, &capacities, set)),
+            );
+            assert_eq!(oracle.len(), expected);
+        }
+    }
+
+    #[test]
+    fn test_partition_partition() {
+        let mut rng = StdRng::seed_from_u64(42);
+        for _ in 0..300 {
+            let m = rng.gen_range(0..=10);
+            let (classes1, capacities1) = random_partition(&mut rng, m);
+            let (classes2, capacities2) = random_partition(&mut rng, m);
+            let result = matroid_intersection(
+                &mut PartitionMatroid::new(&classes1, &capacities1),
+                &mut PartitionMatroid::new(&classes2, &capacities2),
+            );
+            let set = indicator(m, &result);
+            assert!(is_within(&classes1, &capacities1, &set));
+            assert!(is_within(&classes2, &capacities2, &set));
+            let expected = brute(m, |set| {
+                is_within(&classes1, &capacities1, set) && is_within(&classes2, &capacities2, set)
+            });
+            assert_eq!(result.len(), expected);
+        }
+    }
+
+    #[test]
+    fn test_no_colorful_spanning_tree() {
+        // A path of four vertices needs all the three edges, but only two colors are allowed
+        // once each, and the triangle edge of the third color is useless.
+        let edges = [(0, 1), (1, 2), (2, 3), (0, 2)];
+        let colors = [0, 0, 1, 2];
+        let result = matroid_intersection(
+            &mut GraphicMatroid::new(4, &edges),
+            &mut PartitionMatroid::new(&colors, &[1, 1, 0]),
+        );
+        assert_eq!(result.len(), 2);
+        // A zero capacity for every color
+        let result = matroid_intersection(
+            &mut GraphicMatroid::new(4, &edges),
+            &mut PartitionMatroid::new(&colors, &[0, 0, 0]),
+        );
+        assert!(result.is_empty());
+    }
+
+    #[test]
+    fn test_large() {
+        // A colorful spanning tree of a random connected graph with many colors
+        let mut rng = StdRng::seed_from_u64(42);
+        let n = 100;
+        let mut edges = (1..n).map(|i| (rng.gen_range(0..i), i)).collect::<Vec<_>>();
+        edges.extend((0..300).map(|_| (rng.gen_range(0..n), rng.gen_range(0..n))));
+        let colors = (0..edges.len()).map(|i| i % (n - 1)).collect::<Vec<_>>();
+        let result = matroid_intersection(
+            &mut GraphicMatroid::new(n, &edges),
+            &mut PartitionMatroid::new(&colors, &vec![1; n - 1]),
+        );
+        let set = indicator(edges.len(), &result);
+        assert!(is_forest(n, &edges, &set));
+        assert!(is_within(&colors, &vec![1; n - 1], &set));
+    }
+}
@@ -0,0 +1,306 @@
+//! Enumerates the maximal cliques of a small graph by the Bron–Kerbosch algorithm.
+//!
+//! Sets of vertices are bitmasks in `u64`. It keeps the clique $R$ being built, the candidates
+//! $P$ that extend it, and the excluded vertices $X$ whose cliques were already reported. It
+//! branches only on the vertices of $P$ not adjacent to a pivot $u \in P \cup X$ with the most
+//! neighbors in $P$, because every maximal clique contains $u$ or one of its non-neighbors.
+//! This runs in $O(3 ^ {n / 3})$ time, which is the maximum number of maximal cliques.
+//!
+//! # Examples
+//!
+//! ```
+//! use maximal_cliques::bron_kerbosch;
+//! // A triangle 0-1-2 and an edge 2-3.
+//! let adj = [0b0110, 0b0101, 0b1011, 0b0100];
+//! let mut cliques = Vec::new();
+//! bron_kerbosch(&adj, 4, |r| cliques.push(r));
+//! cliques.sort_unstable();
+//! assert_eq!(cliques, vec![0b0111, 0b1100]);
+//! ```
+
+/// Calls `visit` for each maximal clique, once for each.
+///
+/// # Requirements
+///
+/// - `adj[i]` is the bit set of the neighbors of the vertex $i$, which is symmetric and does not
+///   contain $i$ itself.
+/// - $n \le 64$, and the number of the maximal cliques is small enough.
+///
+/// # Complexity
+///
+/// $O(3 ^ {n / 3} n)$
+pub fn bron_kerbosch(adj: &[u64], n: usize, visit: impl FnMut(u64)) {
+    bron_kerbosch_with_limit(adj, n, usize::MAX, visit);
+}
+
+/// Calls `visit` for each maximal clique, but aborts if there are more than `limit` of them.
+///
+/// It returns `true` if it has visited all the maximal cliques, and `false` if it has aborted
+/// after visiting `limit` of them.
+///
+/// # Examples
+///
+/// ```
+/// use maximal_cliques::bron_kerbosch_with_limit;
+/// // Four isolated vertices have four maximal cliques.
+/// let adj = [0; 4];
+/// let mut count = 0;
+/// assert!(!bron_kerbosch_with_limit(&adj, 4, 3, |_| count += 1));
+/// assert_eq!(count, 3);
+/// assert!(bron_kerbosch_with_limit(&adj, 4, 4, |_| {}));
+/// ```
+pub fn bron_kerbosch_with_limit(
+    adj: &[u64],
+    n: usize,
+    limit: usize,
+    mut visit: impl FnMut(u64),
+) -> bool {
+    validate(adj, n);
+    let all = if n == 0 { 0 } else { u64::MAX >> (64 - n) };
+    let mut remaining = limit;
+    recurse(adj, 0, all, 0, &mut remaining, &mut visit)
+}
+
+/// Returns the number of the maximal cliques, or `None` if it is more than `limit`.
+///
+/// # Examples
+///
+/// ```
+/// use maximal_cliques::count_maximal_cliques;
+/// // A cycle of length 5 has 5 maximal cliques, the edges.
+/// let adj = [0b10010, 0b00101, 0b01010, 0b10100, 0b01001];
+/// assert_eq!(count_maximal_cliques(&adj, 5, None), Some(5));
+/// assert_eq!(count_maximal_cliques(&adj, 5, Some(4)), None);
+/// ```
+pub fn count_maximal_cliques(adj: &[u64], n: usize, limit: Option<usize>) -> Option<usize> {
+    let mut count = 0;
+    bron_kerbosch_with_limit(adj, n, limit.unwrap_or(usize::MAX), |_| count += 1).then_some(count)
+}
+
+/// Returns the maximum weight of a clique and the clique.
+///
+/// Since the weights are nonnegative, some maximal clique has the maximum weight. The empty graph
+/// has the empty clique of weight $0$.
+///
+/// # Examples
+///
+/// ```
+/// use maximal_cliques::max_weight_clique;
+/// // A triangle 0-1-2 and an edge 2-3.
+/// let adj = [0b0110, 0b0101, 0b1011, 0b0100];
+/// assert_eq!(max_weight_clique(&adj, 4, &[1, 1, 1, 5]), (6, 0b1100));
+/// ```
+pub fn max_weight_clique(adj: &[u64], n: usize, weights: &[u64]) -> (u64, u64) {
+    assert_eq!(
+        weights.len(),
+        n,
+        "`weights` has {} elements for {} vertices",
+        weights.len(),
+        n
+    );
+    let mut best = (0, 0);
+    bron_kerbosch(adj, n, |r| {
+        let weight = ones(r).map(|i| weights[i]).sum::<u64>();
+        if best.0 < weight {
+            best = (weight, r);
+        }
+    });
+    best
+}
+
+// Returns `false` if it has aborted.
+fn recurse(
+    adj: &[u64],
+    r: u64,
+    mut p: u64,
+    mut x: u64,
+    remaining: &mut usize,
+    visit: &mut impl FnMut(u64),
+) -> bool {
+    if p == 0 && x == 0 {
+        if *remaining == 0 {
+            return false;
+        }
+        *remaining -= 1;
+        visit(r);
+        return true;
+    }
+    let u = ones(p | x)
+        .max_by_key(|&u| (p & adj[u]).count_ones())
+        .unwrap();
+    for v in ones(p & !adj[u]) {
+        let bit = 1 << v;
+        if !recurse(adj, r | bit, p & adj[v], x & adj[v], remaining, visit) {
+            return false;
+        }
+        p &= !bit;
+        x |= bit;
+    }
+    true
+}
+
+fn ones(mut s: u64) -> impl Iterator<Item = usize> {
+    std::iter::from_fn(move || {
+        (s != 0).then(|| {
+            let i = s.trailing_zeros() as usize;
+            s &= s - 1;
+            i
+        })
+    })
+}
+
+fn validate(adj: &[u64], n: usize) {
+    assert_eq!(
+        adj.len(),
+        n,
+        "`adj` has {} rows for {} vertices",
+        adj.len(),
+        n
+    );
+    assert!(n <= 64, "too many vertices: {}", n);
+    assert!(
+        n == 64 || adj.iter().all(|&a| a >> n == 0),
+        "`adj` has a vertex out of range"
+    );
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rand::rngs::StdRng;
+    use rand::Rng;
+    use rand::SeedableRng;
+
+    fn random_graph(rng: &mut StdRng, n: usize, p: f64) -> Vec<u64> {
+        let mut adj = vec![0; n];
+        for i in 0..n {
+            for j in 0..i {
+                if rng.gen_bool(p) {
+                    adj[i] |= 1 << j;
+                    adj[j] |= 1 << i;
+                }
+            }
+        }
+        adj
+    }
+
+    // Every subset that is a clique and cannot be extended.
+    fn brute(adj: &[u64], n: usize) -> Vec<u64> {
+        let is_clique = |s: u64| ones(s).all(|i| s & !(1 << i) & !adj[i] == 0);
+        (0..1_u64 << n)
+            .filter(|&s| is_clique(s) && (0..n).all(|i| s >> i & 1 == 1 || !is_clique(s | 1 << i)))
+            .collect()
+    }
+
+    fn collect(adj: &[u64], n: usize) -> Vec<u64> {
+        let mut cliques = Vec::new();
+        bron_kerbosch(adj, n, |r| cliques.push(r));
+        cliques.sort_unstable();
+        cliques
+    }
+
+    // The complete multipartite graph with the parts of the given sizes.
+    fn complete_multipartite(sizes: &[usize]) -> (Vec<u64>, usize) {
+        let part = sizes
+            .iter()
+            .enumerate()
+            .flat_map(|(k, &s)| std::iter::repeat(k).take(s))
+            .collect::<Vec<_>>();
+        let n = part.len();
+        let adj = (0..n)
+            .map(|i| {
+                (0..n)
+                    .filter(|&j| part[i] != part[j])
+                    .fold(0, |acc, j| acc | 1 << j)
+            })
+            .collect();
+        (adj, n)
+    }
+
+    #[test]
+    fn test_random() {
+        let mut rng = StdRng::seed_from_u64(42);
+        for _ in 0..300 {
+            let n = rng.gen_range(0..=16);
+            let p = rng.gen_range(0.0..1.0);
+            let adj = random_graph(&mut rng, n, p);
+            let result = collect(&adj, n);
+            let expected = brute(&adj, n);
+            // Sorted and deduplicated, so none is visited twice.
+            let mut dedup = result.clone();
+            dedup.dedup();
+            assert_eq!(dedup, result);
+            assert_eq!(result, expected);
+            assert_eq!(count_maximal_cliques(&adj, n, None), Some(expected.len()));
+        }
+    }
+
+    #[test]
+    fn test_max_weight_clique() {
+        let mut rng = StdRng::seed_from_u64(42);
+        for _ in 0..300 {
+            let n = rng.gen_range(0..=12);
+            let p = rng.gen_range(0.0..1.0);
+            let adj = random_graph(&mut rng, n, p);
+            let weights = (0..n).map(|_| rng.gen_range(0..100)).collect::<Vec<u64>>();
+            let weight = |s: u64| ones(s).map(|i| weights[i]).sum::<u64>();
+            let expected = brute(&adj, n).into_iter().map(weight).max().unwrap();
+            let (result, clique) = max_weight_clique(&adj, n, &weights);
+            assert_eq!(result, expected);
+            assert_eq!(weight(clique), result);
+            assert!(ones(clique).all(|i| clique & !(1 << i) & !adj[i] == 0));
+        }
+    }
+
+    #[test]
+    fn test_moon_moser() {
+        // The complement of k disjoint triangles has 3^k maximal cliques, the most for n = 3k.
+        for k in 0..=10 {
+            let (adj, n) = complete_multipartite(&vec![3; k]);
+            assert_eq!(
+                count_maximal_cliques(&adj, n, None),
+                Some(3_usize.pow(k as u32))
+            );
+        }
+    }
+
+    #[test]
+    fn test_complete_multipartite() {
+        let mut rng = StdRng::seed_from_u64(42);
+        for _ in 0..100 {
+            let m = rng.gen_range(1..=6);
+            let sizes = (0..m).map(|_| rng.gen_range(1..=4)).collect::<Vec<usize>>();
+            let (adj, n) = complete_multipartite(&sizes);
+            let expected = sizes.iter().product::<usize>();
+            assert_eq!(count_maximal_cliques(&adj, n, None), Some(expected));
+        }
+    }
+
+    #[test]
+    fn test_limit() {
+        let (adj, n) = complete_multipartite(&[3; 6]);
+        for limit in [0, 1, 100, 728, 729, 730] {
+            let mut count = 0;
+            let finished = bron_kerbosch_with_limit(&adj, n, limit, |_| count += 1);
+            assert_eq!(finished, limit >= 729);
+            assert_eq!(count, limit.min(729));
+            let expected = if limit >= 729 { Some(729) } else { None };
+            assert_eq!(count_maximal_cliques(&adj, n, Some(limit)), expected);
+        }
+    }
+
+    #[test]
+    fn test_edge_cases() {
+        assert_eq!(collect(&[], 0), vec![0]);
+        assert_eq!(collect(&[0], 1), vec![1]);
+        // The complete graph on 64 vertices.
+        let adj = (0..64).map(|i| !(1 << i)).collect::<Vec<u64>>();
+        assert_eq!(collect(&adj, 64), vec![u64::MAX]);
+    }
+
+    #[test]
+    #[should_panic(expected = "`adj` has a vertex out of range")]
+    fn test_out_of_range() {
+        bron_kerbosch(&[0b100, 0], 2, |_| {});
+    }
+}
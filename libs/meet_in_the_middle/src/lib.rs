@@ -0,0 +1,304 @@
+//! Meet-in-the-middle over the subsets of up to about $40$ items.
+//!
+//! [`meet_in_the_middle`] splits the items into halves and enumerates the aggregates of all the
+//! subsets of each half, by a closure that adds an item to an aggregate. The combinators answer
+//! the classic queries on top of it:
+//!
+//! - [`count_subsets_at_most`]: the number of the subsets with the sum at most $C$
+//! - [`knapsack`]: the maximum value of the subsets with the weight at most $C$
+//! - [`xor_subset_exists`]: whether some subset has the xor `target`
+//!
+//! # Examples
+//!
+//! ```
+//! use meet_in_the_middle::meet_in_the_middle;
+//!
+//! // The sums of the subsets of each half.
+//! let (left, right) = meet_in_the_middle(&[1, 2, 4, 8], 0, |&acc, &x| acc + x);
+//! assert_eq!(left, vec![0, 1, 2, 3]);
+//! assert_eq!(right, vec![0, 4, 8, 12]);
+//! ```
+
+/// Returns the aggregates of all the subsets of `items`.
+///
+/// The $S$-th element, where $S$ is the bitset of the chosen items, is `identity` with the items
+/// of $S$ added by `f` in the ascending order of the indices.
+///
+/// # Complexity
+///
+/// $O(2 ^ n)$ calls of `f`.
+///
+/// # Examples
+///
+/// ```
+/// use meet_in_the_middle::subset_aggregates;
+/// let xors = subset_aggregates(&[0b01, 0b11], 0, |&acc, &x| acc ^ x);
+/// assert_eq!(xors, vec![0b00, 0b01, 0b11, 0b10]);
+/// ```
+pub fn subset_aggregates<A, T>(items: &[A], identity: T, mut f: impl FnMut(&T, &A) -> T) -> Vec<T> {
+    let mut result = Vec::with_capacity(1 << items.len());
+    result.push(identity);
+    for item in items {
+        for s in 0..result.len() {
+            let next = f(&result[s], item);
+            result.push(next);
+        }
+    }
+    result
+}
+
+/// Returns the [`subset_aggregates`] of the first $\lfloor n / 2 \rfloor$ items and the rest.
+///
+/// # Complexity
+///
+/// $O(2 ^ {n / 2})$ calls of `f`.
+pub fn meet_in_the_middle<A, T: Clone>(
+    items: &[A],
+    identity: T,
+    mut f: impl FnMut(&T, &A) -> T,
+) -> (Vec<T>, Vec<T>) {
+    let (left, right) = items.split_at(items.len() / 2);
+    (
+        subset_aggregates(left, identity.clone(), &mut f),
+        subset_aggregates(right, identity, &mut f),
+    )
+}
+
+/// Returns the number of the subsets of `items` with the sum at most `capacity`.
+///
+/// # Requirements
+///
+/// The sum of all the items fits in `u64`.
+///
+/// # Complexity
+///
+/// $O(n 2 ^ {n / 2})$
+///
+/// # Examples
+///
+/// ```
+/// use meet_in_the_middle::count_subsets_at_most;
+/// // {}, {1}, {2}, {1, 2}, {3}
+/// assert_eq!(count_subsets_at_most(&[1, 2, 3], 3), 5);
+/// ```
+pub fn count_subsets_at_most(items: &[u64], capacity: u64) -> u64 {
+    let (left, mut right) = meet_in_the_middle(items, 0, |&acc, &x| acc + x);
+    right.sort_unstable();
+    left.iter()
+        .filter(|&&x| x <= capacity)
+        .map(|&x| right.partition_point(|&y| y <= capacity - x) as u64)
+        .sum()
+}
+
+/// Returns the maximum total value of the subsets of `items` with the total weight at most
+/// `capacity`, where `items` are pairs of the weights and the values.
+///
+/// The right half is sorted by the weights and reduced to its Pareto front, the subsets that
+/// have more value than every lighter one, so that the best partner of a left subset is the last
+/// one that fits.
+///
+/// # Requirements
+///
+/// The sums of all the weights and all the values fit in `u64`.
+///
+/// # Complexity
+///
+/// $O(n 2 ^ {n / 2})$
+///
+/// # Examples
+///
+/// ```
+/// use meet_in_the_middle::knapsack;
+/// let items = [(3, 4), (4, 5), (2, 3)];
+/// assert_eq!(knapsack(&items, 5), 7);
+/// assert_eq!(knapsack(&items, 1), 0);
+/// ```
+pub fn knapsack(items: &[(u64, u64)], capacity: u64) -> u64 {
+    let (left, mut right) =
+        meet_in_the_middle(items, (0, 0), |&(w, v), &(dw, dv)| (w + dw, v + dv));
+    right.sort_unstable();
+    let mut front = Vec::<(u64, u64)>::new();
+    for (w, v) in right {
+        if front.last().map_or(true, |&(_, u)| u < v) {
+            front.push((w, v));
+        }
+    }
+    left.iter()
+        .filter(|&&(w, _)| w <= capacity)
+        .map(|&(w, v)| {
+            // Never zero, since `front[0]` is the empty subset.
+            let i = front.partition_point(|&(u, _)| u <= capacity - w);
+            v + front[i - 1].1
+        })
+        .max()
+        .unwrap()
+}
+
+/// Returns `true` if some subset of `items`, possibly empty, has the xor `target`.
+///
+/// # Complexity
+///
+/// $O(n 2 ^ {n / 2})$
+///
+/// # Examples
+///
+/// ```
+/// use meet_in_the_middle::xor_subset_exists;
+/// assert!(xor_subset_exists(&[0b011, 0b110], 0b101));
+/// assert!(!xor_subset_exists(&[0b011, 0b110], 0b100));
+/// assert!(xor_subset_exists(&[], 0));
+/// ```
+pub fn xor_subset_exists(items: &[u64], target: u64) -> bool {
+    let (left, mut right) = meet_in_the_middle(items, 0, |&acc, &x| acc ^ x);
+    right.sort_unstable();
+    left.iter()
+        .any(|&x| right.binary_search(&(target ^ x)).is_ok())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rand::rngs::StdRng;
+    use rand::Rng;
+    use rand::SeedableRng;
+
+    // All the subsets, as the lists of the chosen items.
+    fn brute_subsets<A: Copy>(items: &[A]) -> impl Iterator<Item = Vec<A>> + '_ {
+        (0..1_u32 << items.len()).map(move |s| {
+            (0..items.len())
+                .filter(|&i| s >> i & 1 == 1)
+                .map(|i| items[i])
+                .collect()
+        })
+    }
+
+    // Small values so that the weights, the values and the sums collide.
+    fn random_items(rng: &mut StdRng, n: usize) -> Vec<u64> {
+        let max: u64 = rng.gen_range(1..=20);
+        (0..n).map(|_| rng.gen_range(0..=max)).collect()
+    }
+
+    // A subset sum or one off it, to hit the boundaries.
+    fn random_capacity(rng: &mut StdRng, items: &[u64]) -> u64 {
+        let sum = items.iter().filter(|_| rng.gen()).sum::<u64>();
+        match rng.gen_range(0..4) {
+            0 => sum.saturating_sub(1),
+            1 => sum + 1,
+            _ => sum,
+        }
+    }
+
+    #[test]
+    fn test_subset_aggregates() {
+        let mut rng = StdRng::seed_from_u64(42);
+        for n in 0..=10 {
+            let items = random_items(&mut rng, n);
+            let sums = subset_aggregates(&items, 0, |&acc, &x| acc + x);
+            let expected = brute_subsets(&items)
+                .map(|s| s.iter().sum::<u64>())
+                .collect::<Vec<_>>();
+            assert_eq!(sums, expected);
+            // The order of the additions.
+            let lists = subset_aggregates(&items, vec![], |acc, &x| {
+                let mut acc = acc.clone();
+                acc.push(x);
+                acc
+            });
+            assert_eq!(lists, brute_subsets(&items).collect::<Vec<_>>());
+            let (left, right) = meet_in_the_middle(&items, 0, |&acc, &x| acc + x);
+            assert_eq!(left.len(), 1 << (n / 2));
+            assert_eq!(right.len(), 1 << (n - n / 2));
+        }
+    }
+
+    #[test]
+    fn test_count_subsets_at_most() {
+        let mut rng = StdRng::seed_from_u64(42);
+        for _ in 0..300 {
+            let n = rng.gen_range(0..=14);
+            let items = random_items(&mut rng, n);
+            let capacity = random_capacity(&mut rng, &items);
+            let expected = brute_subsets(&items)
+                .filter(|s| s.iter().sum::<u64>() <= capacity)
+                .count() as u64;
+            assert_eq!(count_subsets_at_most(&items, capacity), expected);
+        }
+    }
+
+    #[test]
+    fn test_knapsack() {
+        let mut rng = StdRng::seed_from_u64(42);
+        for _ in 0..300 {
+            let n = rng.gen_range(0..=14);
+            let weights = random_items(&mut rng, n);
+            let values = random_items(&mut rng, n);
+            let items = weights
+                .iter()
+                .copied()
+                .zip(values.iter().copied())
+                .collect::<Vec<_>>();
+            let capacity = random_capacity(&mut rng, &weights);
+            let expected = brute_subsets(&items)
+                .filter(|s| s.iter().map(|&(w, _)| w).sum::<u64>() <= capacity)
+                .map(|s| s.iter().map(|&(_, v)| v).sum::<u64>())
+                .max()
+                .unwrap();
+            assert_eq!(knapsack(&items, capacity), expected);
+        }
+    }
+
+    #[test]
+    fn test_xor_subset_exists() {
+        let mut rng = StdRng::seed_from_u64(42);
+        for _ in 0..300 {
+            let n = rng.gen_range(0..=14);
+            let bits = rng.gen_range(1..=8);
+            let items = (0..n)
+                .map(|_| rng.gen_range(0..1_u64 << bits))
+                .collect::<Vec<_>>();
+            let target = rng.gen_range(0..1_u64 << bits);
+            let expected =
+                brute_subsets(&items).any(|s| s.iter().fold(0, |acc, &x| acc ^ x) == target);
+            assert_eq!(xor_subset_exists(&items, target), expected);
+        }
+    }
+
+    #[test]
+    fn test_large() {
+        // n = 20 against the brute force, once for each query.
+        let mut rng = StdRng::seed_from_u64(42);
+        let n = 20;
+        let weights = (0..n)
+            .map(|_| rng.gen_range(1..=1_000_000_u64))
+            .collect::<Vec<_>>();
+        let values = (0..n)
+            .map(|_| rng.gen_range(1..=1_000_000_u64))
+            .collect::<Vec<_>>();
+        let items = weights
+            .iter()
+            .copied()
+            .zip(values.iter().copied())
+            .collect::<Vec<_>>();
+        let capacity = random_capacity(&mut rng, &weights);
+        let target = rng.gen_range(0..1_u64 << 20);
+        let mut count = 0;
+        let mut best = 0;
+        let mut found = false;
+        for s in 0..1_u32 << n {
+            let (mut w, mut v, mut x) = (0, 0, 0);
+            for i in (0..n).filter(|&i| s >> i & 1 == 1) {
+                w += weights[i];
+                v += values[i];
+                x ^= weights[i];
+            }
+            if w <= capacity {
+                count += 1;
+                best = best.max(v);
+            }
+            found |= x == target;
+        }
+        assert_eq!(count_subsets_at_most(&weights, capacity), count);
+        assert_eq!(knapsack(&items, capacity), best);
+        assert_eq!(xor_subset_exists(&weights, target), found);
+    }
+}
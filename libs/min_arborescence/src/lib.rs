@@ -0,0 +1,291 @@
+//! The minimum spanning arborescence by the Chu–Liu/Edmonds algorithm.
+//!
+//! # Examples
+//!
+//! ```
+//! use min_arborescence::min_arborescence;
+//!
+//! let edges = [(0, 1, 10), (0, 2, 1), (2, 1, 2), (1, 2, 3), (1, 1, -5)];
+//! let (weight, parent_edge) = min_arborescence(3, 0, &edges).unwrap();
+//! assert_eq!(weight, 3);
+//! assert_eq!(parent_edge, vec![usize::MAX, 2, 1]);
+//!
+//! // 0 is unreachable from 1.
+//! assert_eq!(min_arborescence(3, 1, &edges), None);
+//! ```
+
+/// Returns the minimum weight of an arborescence rooted at `root` and its edges, or `None` if
+/// some vertex is unreachable from `root`.
+///
+/// `edges` are $(u, v, w)$ for the edges $u \to v$ of the weight $w$, which may contain parallel
+/// edges and self-loops. The $v$-th element of the returned vector is the index in `edges` of the
+/// edge entering $v$, and `usize::MAX` for the root.
+///
+/// It takes the cheapest edge entering each vertex, and if they form a cycle, contracts it,
+/// subtracting the weight of the chosen edge entering the head from each edge entering the cycle.
+/// After the contractions stop, it expands the cycles in the reverse order: the edge entering a
+/// contracted cycle replaces the chosen edge of its head, and the others are kept.
+///
+/// # Complexity
+///
+/// $O(nm)$
+pub fn min_arborescence(
+    n: usize,
+    root: usize,
+    edges: &[(usize, usize, i64)],
+) -> Option<(i64, Vec<usize>)> {
+    assert!(root < n, "root {} out of range for length {}", root, n);
+    // Each level is a graph with its edges and their indices in the previous level.
+    struct Level {
+        n: usize,
+        root: usize,
+        edges: Vec<(usize, usize, i64)>,
+        origin: Vec<usize>,
+        in_edge: Vec<usize>,
+    }
+    let mut levels = Vec::new();
+    let mut current = Level {
+        n,
+        root,
+        edges: edges.to_vec(),
+        origin: (0..edges.len()).collect(),
+        in_edge: Vec::new(),
+    };
+    loop {
+        let Level {
+            n, root, ref edges, ..
+        } = current;
+        let mut in_edge = vec![usize::MAX; n];
+        for (i, &(u, v, w)) in edges.iter().enumerate() {
+            if u != v && v != root && (in_edge[v] == usize::MAX || w < edges[in_edge[v]].2) {
+                in_edge[v] = i;
+            }
+        }
+        if (0..n).any(|v| v != root && in_edge[v] == usize::MAX) {
+            return None;
+        }
+        // Walks up the chosen edges from each vertex, and finds a cycle when it hits its own path.
+        let mut comp = vec![usize::MAX; n];
+        let mut visited = vec![usize::MAX; n];
+        let mut comp_len = 0;
+        for s in 0..n {
+            let mut v = s;
+            while v != root && visited[v] == usize::MAX {
+                visited[v] = s;
+                v = edges[in_edge[v]].0;
+            }
+            if v != root && visited[v] == s && comp[v] == usize::MAX {
+                let head = v;
+                loop {
+                    comp[v] = comp_len;
+                    v = edges[in_edge[v]].0;
+                    if v == head {
+                        break;
+                    }
+                }
+                comp_len += 1;
+            }
+        }
+        current.in_edge = in_edge;
+        if comp_len == 0 {
+            break;
+        }
+        for c in &mut comp {
+            if *c == usize::MAX {
+                *c = comp_len;
+                comp_len += 1;
+            }
+        }
+        let mut next_edges = Vec::new();
+        let mut next_origin = Vec::new();
+        for (i, &(u, v, w)) in current.edges.iter().enumerate() {
+            if comp[u] != comp[v] {
+                let reduced =
+                    if v == current.root { w } else { w - current.edges[current.in_edge[v]].2 };
+                next_edges.push((comp[u], comp[v], reduced));
+                next_origin.push(i);
+            }
+        }
+        let next = Level {
+            n: comp_len,
+            root: comp[current.root],
+            edges: next_edges,
+            origin: next_origin,
+            in_edge: Vec::new(),
+        };
+        levels.push(std::mem::replace(&mut current, next));
+    }
+    // Expands the cycles.
+    let mut chosen = current.in_edge;
+    let mut origin = current.origin;
+    while let Some(level) = levels.pop() {
+        let mut next = level.in_edge;
+        for &e in chosen.iter().filter(|&&e| e != usize::MAX) {
+            let e = origin[e];
+            next[level.edges[e].1] = e;
+        }
+        chosen = next;
+        origin = level.origin;
+    }
+    let weight = chosen
+        .iter()
+        .filter(|&&e| e != usize::MAX)
+        .map(|&e| edges[e].2)
+        .sum();
+    Some((weight, chosen))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rand::rngs::StdRng;
+    use rand::Rng;
+    use rand::SeedableRng;
+
+    // The minimum over all the choices of an edge entering each vertex that form an arborescence.
+    fn brute(n: usize, root: usize, edges: &[(usize, usize, i64)]) -> Option<i64> {
+        let candidates = (0..n)
+            .map(|v| {
+                if v == root {
+                    vec![usize::MAX]
+                } else {
+                    (0..edges.len()).filter(|&i| edges[i].1 == v).collect()
+                }
+            })
+            .collect::<Vec<Vec<_>>>();
+        let mut best = None;
+        let mut choice = vec![0; n];
+        'outer: loop {
+            let parent_edge = (0..n)
+                .map(|v| candidates[v].get(choice[v]).copied())
+                .collect::<Option<Vec<_>>>();
+            if let Some(parent_edge) = parent_edge {
+                if is_arborescence(n, root, edges, &parent_edge) {
+                    let weight = weight_of(edges, &parent_edge);
+                    best = Some(best.map_or(weight, |b: i64| b.min(weight)));
+                }
+            }
+            for v in 0..n {
+                choice[v] += 1;
+                if choice[v] < candidates[v].len() {
+                    continue 'outer;
+                }
+                choice[v] = 0;
+            }
+            break best;
+        }
+    }
+
+    fn weight_of(edges: &[(usize, usize, i64)], parent_edge: &[usize]) -> i64 {
+        parent_edge
+            .iter()
+            .filter(|&&e| e != usize::MAX)
+            .map(|&e| edges[e].2)
+            .sum()
+    }
+
+    fn is_arborescence(
+        n: usize,
+        root: usize,
+        edges: &[(usize, usize, i64)],
+        parent_edge: &[usize],
+    ) -> bool {
+        if parent_edge.len() != n || parent_edge[root] != usize::MAX {
+            return false;
+        }
+        if (0..n).any(|v| v != root && edges[parent_edge[v]].1 != v) {
+            return false;
+        }
+        // Every vertex reaches the root in at most n steps.
+        (0..n).all(|s| {
+            let mut v = s;
+            for _ in 0..n {
+                if v == root {
+                    return true;
+                }
+                v = edges[parent_edge[v]].0;
+            }
+            v == root
+        })
+    }
+
+    #[test]
+    fn test_random() {
+        let mut rng = StdRng::seed_from_u64(42);
+        let mut found = 0;
+        for _ in 0..2000 {
+            let n = rng.gen_range(1..=5);
+            let m = rng.gen_range(0..=9);
+            let max_weight: i64 = rng.gen_range(1..=10);
+            let edges = (0..m)
+                .map(|_| {
+                    let u = rng.gen_range(0..n);
+                    let v = rng.gen_range(0..n);
+                    let w = rng.gen_range(-max_weight..=max_weight);
+                    (u, v, w)
+                })
+                .collect::<Vec<_>>();
+            let root = rng.gen_range(0..n);
+            let result = min_arborescence(n, root, &edges);
+            let expected = brute(n, root, &edges);
+            assert_eq!(result.as_ref().map(|&(w, _)| w), expected);
+            if let Some((weight, parent_edge)) = result {
+                assert!(is_arborescence(n, root, &edges, &parent_edge));
+                assert_eq!(weight_of(&edges, &parent_edge), weight);
+                found += 1;
+            }
+        }
+        assert!(found > 500);
+    }
+
+    #[test]
+    fn test_dense_cycles() {
+        // Complete graphs with parallel edges and self-loops, where cycles are contracted often.
+        let mut rng = StdRng::seed_from_u64(42);
+        for _ in 0..200 {
+            let n = rng.gen_range(1..=6);
+            let mut edges = Vec::new();
+            for u in 0..n {
+                for v in 0..n {
+                    for _ in 0..rng.gen_range(0..=2) {
+                        edges.push((u, v, rng.gen_range(0..20)));
+                    }
+                }
+            }
+            let root = rng.gen_range(0..n);
+            let result = min_arborescence(n, root, &edges);
+            assert_eq!(result.as_ref().map(|&(w, _)| w), brute(n, root, &edges));
+            if let Some((weight, parent_edge)) = result {
+                assert!(is_arborescence(n, root, &edges, &parent_edge));
+                assert_eq!(weight_of(&edges, &parent_edge), weight);
+            }
+        }
+    }
+
+    #[test]
+    fn test_nested_cycles() {
+        // The cheap edges form 1 -> 2 -> 3 -> 1, and after the contraction, a cycle with 4.
+        let edges = vec![
+            (1, 2, 1),
+            (2, 3, 1),
+            (3, 1, 1),
+            (3, 4, 1),
+            (4, 1, 2),
+            (0, 4, 100),
+            (0, 2, 50),
+            (2, 2, -100),
+        ];
+        let (weight, parent_edge) = min_arborescence(5, 0, &edges).unwrap();
+        assert_eq!(Some(weight), brute(5, 0, &edges));
+        assert!(is_arborescence(5, 0, &edges, &parent_edge));
+        assert_eq!(parent_edge, vec![usize::MAX, 2, 6, 1, 3]);
+    }
+
+    #[test]
+    fn test_unreachable() {
+        assert_eq!(min_arborescence(1, 0, &[]), Some((0, vec![usize::MAX])));
+        assert_eq!(min_arborescence(2, 0, &[(1, 1, 0)]), None);
+        // Reachable only inside a cycle.
+        assert_eq!(min_arborescence(3, 0, &[(1, 2, 0), (2, 1, 0)]), None);
+    }
+}
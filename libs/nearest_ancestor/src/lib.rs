@@ -0,0 +1,262 @@
+//! Nearest ancestors with smaller values, the all nearest smaller values problem on a tree.
+//!
+//! Trees are given by undirected adjacency lists and a root, and an ancestor here is always a
+//! proper one, so the answer for the root is `None`.
+//!
+//! # Examples
+//!
+//! ```
+//! use nearest_ancestor::nearest_ancestor_at_most;
+//! use nearest_ancestor::nearest_ancestor_by;
+//! // 0 - 1 - 2
+//! //      \
+//! //       3
+//! let g = vec![vec![1], vec![0, 2, 3], vec![1], vec![1]];
+//! let values = [2, 5, 3, 1];
+//! let smaller = nearest_ancestor_by(&g, 0, &values, |a, b| a < b);
+//! assert_eq!(smaller, vec![None, Some(0), Some(0), None]);
+//! let larger = nearest_ancestor_by(&g, 0, &values, |a, b| a > b);
+//! assert_eq!(larger, vec![None, None, Some(1), Some(1)]);
+//! let at_most = nearest_ancestor_at_most(&g, 0, &values, &[0, 0, 9, 2]);
+//! assert_eq!(at_most, vec![None, None, Some(1), Some(0)]);
+//! ```
+
+/// Returns the nearest ancestor $a$ of each vertex $x$ such that `less(&values[a], &values[x])`.
+///
+/// It keeps the monotone stack of the ancestors as an array. Entering a vertex binary-searches
+/// its position, overwrites a single element and shortens the stack, and leaving it restores
+/// them, so each vertex costs $O(\log n)$ even if a long stack is shared by many branches.
+///
+/// # Requirements
+///
+/// `less` is `<` or `<=` of a total order, or the reverse of either.
+///
+/// # Complexity
+///
+/// $O(n \log n)$
+pub fn nearest_ancestor_by<T>(
+    g: &[Vec<usize>],
+    root: usize,
+    values: &[T],
+    mut less: impl FnMut(&T, &T) -> bool,
+) -> Vec<Option<usize>> {
+    let n = g.len();
+    let mut result = vec![None; n];
+    // The monotone stack is `stack[..len]`, and the elements after it are kept to restore.
+    let mut stack = vec![0; n];
+    let mut len = 0;
+    // The length and the overwritten element to restore at leaving
+    let mut saved = vec![(0, 0); n];
+    let mut dfs = vec![(root, !0, false)];
+    while let Some((x, p, leaving)) = dfs.pop() {
+        if leaving {
+            let (old_len, overwritten) = saved[x];
+            stack[len - 1] = overwritten;
+            len = old_len;
+            continue;
+        }
+        let k = stack[..len].partition_point(|&a| less(&values[a], &values[x]));
+        result[x] = k.checked_sub(1).map(|k| stack[k]);
+        saved[x] = (len, stack[k]);
+        stack[k] = x;
+        len = k + 1;
+        dfs.push((x, p, true));
+        for &y in &g[x] {
+            if y != p {
+                dfs.push((y, x, false));
+            }
+        }
+    }
+    result
+}
+
+/// Returns the nearest ancestor $a$ of each vertex $x$ such that `values[a] <= thresholds[x]`.
+///
+/// # Complexity
+///
+/// $O(n \log n)$
+pub fn nearest_ancestor_at_most<T: Ord + Copy>(
+    g: &[Vec<usize>],
+    root: usize,
+    values: &[T],
+    thresholds: &[T],
+) -> Vec<Option<usize>> {
+    let n = g.len();
+    let mut parent = vec![None; n];
+    let mut order = Vec::with_capacity(n);
+    let mut stack = vec![root];
+    while let Some(x) = stack.pop() {
+        order.push(x);
+        for &y in &g[x] {
+            if Some(y) != parent[x] && y != root {
+                parent[y] = Some(x);
+                stack.push(y);
+            }
+        }
+    }
+    // `up[k][x]` is the minimum of the values of the $2 ^ k$ nearest ancestors of $x$ and the
+    // farthest of them, or `None` if $x$ has fewer ancestors.
+    let mut up = vec![parent
+        .iter()
+        .map(|&p| p.map(|p| (values[p], p)))
+        .collect::<Vec<_>>()];
+    while 1 << up.len() < n {
+        let last = up.last().unwrap();
+        let next = (0..n)
+            .map(|x| {
+                let (m1, a) = last[x]?;
+                let (m2, b) = last[a]?;
+                Some((m1.min(m2), b))
+            })
+            .collect();
+        up.push(next);
+    }
+    (0..n)
+        .map(|x| {
+            let threshold = thresholds[x];
+            let mut x = x;
+            for table in up.iter().rev() {
+                if let Some((min, a)) = table[x] {
+                    if threshold < min {
+                        x = a;
+                    }
+                }
+            }
+            parent[x].filter(|&p| values[p] <= threshold)
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rand::rngs::StdRng;
+    use rand::Rng;
+    use rand::SeedableRng;
+
+    fn random_tree(rng: &mut StdRng, n: usize) -> Vec<Vec<usize>> {
+        let mut g = vec![Vec::new(); n];
+        for i in 1..n {
+            let p = match rng.gen_range(0..4) {
+                0 => i - 1,
+                1 => rng.gen_range(i.saturating_sub(3)..i),
+                _ => rng.gen_range(0..i),
+            };
+            g[i].push(p);
+            g[p].push(i);
+        }
+        g
+    }
+
+    fn parents(g: &[Vec<usize>], root: usize) -> Vec<Option<usize>> {
+        let mut parent = vec![None; g.len()];
+        let mut stack = vec![root];
+        while let Some(x) = stack.pop() {
+            for &y in &g[x] {
+                if Some(y) != parent[x] && y != root {
+                    parent[y] = Some(x);
+                    stack.push(y);
+                }
+            }
+        }
+        parent
+    }
+
+    fn brute(
+        parent: &[Option<usize>],
+        mut f: impl FnMut(usize, usize) -> bool,
+    ) -> Vec<Option<usize>> {
+        (0..parent.len())
+            .map(|x| {
+                let mut a = parent[x];
+                while let Some(b) = a {
+                    if f(b, x) {
+                        break;
+                    }
+                    a = parent[b];
+                }
+                a
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_random() {
+        let mut rng = StdRng::seed_from_u64(42);
+        for _ in 0..300 {
+            let n = rng.gen_range(1..=60);
+            let g = random_tree(&mut rng, n);
+            let root = rng.gen_range(0..n);
+            let max = rng.gen_range(0..=5);
+            let values = (0..n).map(|_| rng.gen_range(0..=max)).collect::<Vec<u32>>();
+            let parent = parents(&g, root);
+            assert_eq!(
+                nearest_ancestor_by(&g, root, &values, |a, b| a < b),
+                brute(&parent, |a, x| values[a] < values[x])
+            );
+            assert_eq!(
+                nearest_ancestor_by(&g, root, &values, |a, b| a <= b),
+                brute(&parent, |a, x| values[a] <= values[x])
+            );
+            assert_eq!(
+                nearest_ancestor_by(&g, root, &values, |a, b| a > b),
+                brute(&parent, |a, x| values[a] > values[x])
+            );
+            assert_eq!(
+                nearest_ancestor_by(&g, root, &values, |a, b| a >= b),
+                brute(&parent, |a, x| values[a] >= values[x])
+            );
+            let thresholds = (0..n)
+                .map(|_| rng.gen_range(0..=max + 1))
+                .collect::<Vec<u32>>();
+            let result = nearest_ancestor_at_most(&g, root, &values, &thresholds);
+            assert_eq!(result, brute(&parent, |a, x| values[a] <= thresholds[x]));
+            assert_eq!(result[root], None);
+        }
+    }
+
+    #[test]
+    fn test_deep_path() {
+        let n = 200_000;
+        let g = (0..n)
+            .map(|i| {
+                let mut adj = Vec::new();
+                if i > 0 {
+                    adj.push(i - 1);
+                }
+                if i + 1 < n {
+                    adj.push(i + 1);
+                }
+                adj
+            })
+            .collect::<Vec<_>>();
+        // A zigzag keeps the stack long.
+        let values = (0..n).map(|i| i / 2 + i % 2 * n).collect::<Vec<_>>();
+        let result = nearest_ancestor_by(&g, 0, &values, |a, b| a < b);
+        for (i, &a) in result.iter().enumerate().skip(1) {
+            assert_eq!(a, Some(if i % 2 == 1 { i - 1 } else { i - 2 }));
+        }
+        let result = nearest_ancestor_at_most(&g, 0, &values, &values);
+        assert_eq!(result[n - 1], Some(n - 2));
+        assert_eq!(result[n - 2], Some(n - 4));
+    }
+
+    #[test]
+    fn test_broom() {
+        // A long decreasing path and then many leaves, which makes popping quadratic.
+        let m = 2000;
+        let n = 2 * m;
+        let mut g = vec![Vec::new(); n];
+        for i in 1..n {
+            let p = if i < m { i - 1 } else { m - 1 };
+            g[i].push(p);
+            g[p].push(i);
+        }
+        let values = (0..n)
+            .map(|i| if i < m { i } else { n - i })
+            .collect::<Vec<_>>();
+        let result = nearest_ancestor_by(&g, 0, &values, |a, b| a < b);
+        let parent = parents(&g, 0);
+        assert_eq!(result, brute(&parent, |a, x| values[a] < values[x]));
+    }
+}
@@ -0,0 +1,367 @@
+//! Integration, root finding and minimization of real functions.
+//!
+//! Every tolerance here is absolute:
+//!
+//! - [`integrate`]: the error of the returned integral is about `eps` or less.
+//! - [`find_root`]: $f$ has a root within `eps` of the returned point.
+//! - [`golden_section_min`]: $f$ attains its minimum within `eps` of the returned point.
+//!
+//! A tolerance smaller than the precision of [`f64`] at the answer cannot be met, and then the
+//! best representable answer is returned.
+//!
+//! # Examples
+//!
+//! ```
+//! use numerical::find_root;
+//! use numerical::golden_section_min;
+//! use numerical::integrate;
+//! let pi = std::f64::consts::PI;
+//! assert!((integrate(f64::sin, 0.0, pi, 1e-12) - 2.0).abs() < 1e-10);
+//! let x = find_root(|x| x * x - 2.0, |x| 2.0 * x, 0.0, 2.0, 1e-12);
+//! assert!((x - 2.0_f64.sqrt()).abs() <= 1e-12);
+//! let x = golden_section_min(|x| (x - 1.0) * (x - 1.0), -5.0, 5.0, 1e-9);
+//! assert!((x - 1.0).abs() <= 1e-9);
+//! ```
+
+// Every part is subdivided at least this many times, so that a narrow peak is not missed.
+const MIN_DEPTH: u32 = 4;
+// No part is subdivided more than this many times.
+const MAX_DEPTH: u32 = 50;
+
+/// Returns $\int _ a ^ b f(x) \mathrm dx$ by the adaptive Simpson's rule.
+///
+/// An interval is accepted when the Simpson's estimates of it and of its two halves differ by at
+/// most $15 \varepsilon$, where the tolerance $\varepsilon$ is halved at each subdivision. The
+/// recursion stops at the depth $50$ regardless of the error, so a singularity does not hang it.
+///
+/// If $a > b$, the result is negated as usual.
+///
+/// # Complexity
+///
+/// $O(\text{number of the parts})$ evaluations of $f$
+pub fn integrate(mut f: impl FnMut(f64) -> f64, a: f64, b: f64, eps: f64) -> f64 {
+    let fa = f(a);
+    let fb = f(b);
+    let fm = f((a + b) / 2.0);
+    let whole = simpson(a, b, fa, fm, fb);
+    adaptive_simpson(&mut f, [a, b], [fa, fm, fb], whole, eps, 0)
+}
+
+fn simpson(a: f64, b: f64, fa: f64, fm: f64, fb: f64) -> f64 {
+    (b - a) / 6.0 * (fa + 4.0 * fm + fb)
+}
+
+fn adaptive_simpson(
+    f: &mut impl FnMut(f64) -> f64,
+    [a, b]: [f64; 2],
+    [fa, fm, fb]: [f64; 3],
+    whole: f64,
+    eps: f64,
+    depth: u32,
+) -> f64 {
+    let m = (a + b) / 2.0;
+    let flm = f((a + m) / 2.0);
+    let frm = f((m + b) / 2.0);
+    let left = simpson(a, m, fa, flm, fm);
+    let right = simpson(m, b, fm, frm, fb);
+    let delta = left + right - whole;
+    if depth >= MAX_DEPTH || depth >= MIN_DEPTH && delta.abs() <= 15.0 * eps {
+        // Richardson extrapolation
+        left + right + delta / 15.0
+    } else {
+        adaptive_simpson(f, [a, m], [fa, flm, fm], left, eps / 2.0, depth + 1)
+            + adaptive_simpson(f, [m, b], [fm, frm, fb], right, eps / 2.0, depth + 1)
+    }
+}
+
+/// Returns a point within `eps` of a root of $f$ in $\[lo, hi\]$, by Newton's method safeguarded
+/// by bisection.
+///
+/// The method keeps a bracket of a sign change and falls back to bisection whenever a Newton
+/// step leaves the bracket or does not halve the step, so it converges even for multiple roots
+/// or a derivative that vanishes. When a Newton step gets shorter than `eps`, the sign change is
+/// confirmed at distance `eps` before returning.
+///
+/// # Requirements
+///
+/// - `df` is the derivative of `f`.
+/// - $f(lo)$ and $f(hi)$ do not have the same strict sign.
+///
+/// # Panics
+///
+/// If $f(lo)$ and $f(hi)$ have the same strict sign.
+pub fn find_root(
+    mut f: impl FnMut(f64) -> f64,
+    mut df: impl FnMut(f64) -> f64,
+    lo: f64,
+    hi: f64,
+    eps: f64,
+) -> f64 {
+    let flo = f(lo);
+    let fhi = f(hi);
+    if flo == 0.0 {
+        return lo;
+    }
+    if fhi == 0.0 {
+        return hi;
+    }
+    assert!(
+        (flo < 0.0) != (fhi < 0.0),
+        "f({}) = {} and f({}) = {} have the same sign",
+        lo,
+        flo,
+        hi,
+        fhi
+    );
+    // $f(neg) < 0 < f(pos)$
+    let (mut neg, mut pos) = if flo < 0.0 { (lo, hi) } else { (hi, lo) };
+    let mut x = (lo + hi) / 2.0;
+    let mut last_step = (hi - lo).abs();
+    loop {
+        let fx = f(x);
+        if fx == 0.0 {
+            return x;
+        }
+        if fx < 0.0 {
+            neg = x;
+        } else {
+            pos = x;
+        }
+        if (pos - neg).abs() <= 2.0 * eps {
+            return (pos + neg) / 2.0;
+        }
+        let dfx = df(x);
+        let newton = x - fx / dfx;
+        let inside = (neg.min(pos)..=neg.max(pos)).contains(&newton);
+        let step = (newton - x).abs();
+        // A zero step makes no progress, which happens when `eps` is too small.
+        if inside && 0.0 < step && 2.0 * step <= last_step {
+            last_step = step;
+            if step < eps {
+                // Confirms that the root is within `eps`.
+                let toward_neg = x + (neg - x).signum() * eps;
+                let toward_pos = x + (pos - x).signum() * eps;
+                if f(toward_neg) <= 0.0 && f(toward_pos) >= 0.0 {
+                    return x;
+                }
+            }
+            x = newton;
+        } else {
+            let mid = (neg + pos) / 2.0;
+            if mid == neg || mid == pos {
+                return mid;
+            }
+            last_step = (pos - neg).abs() / 2.0;
+            x = mid;
+        }
+    }
+}
+
+/// Returns a point within `eps` of a minimizer of $f$ in $\[lo, hi\]$, by the golden-section
+/// search.
+///
+/// # Requirements
+///
+/// $f$ is strictly decreasing and then strictly increasing, except that it may be constant
+/// around the minimum. Otherwise, equal values at the two probes may discard the minimum.
+///
+/// # Complexity
+///
+/// $O(\log ((hi - lo) / eps))$ evaluations of $f$
+pub fn golden_section_min(mut f: impl FnMut(f64) -> f64, lo: f64, hi: f64, eps: f64) -> f64 {
+    // $1 / \phi$
+    let r = (5.0_f64.sqrt() - 1.0) / 2.0;
+    let (mut lo, mut hi) = (lo.min(hi), lo.max(hi));
+    let mut c = hi - r * (hi - lo);
+    let mut d = lo + r * (hi - lo);
+    let mut fc = f(c);
+    let mut fd = f(d);
+    while hi - lo > 2.0 * eps {
+        if fc <= fd {
+            hi = d;
+            d = c;
+            fd = fc;
+            c = hi - r * (hi - lo);
+            fc = f(c);
+        } else {
+            lo = c;
+            c = d;
+            fc = fd;
+            d = lo + r * (hi - lo);
+            fd = f(d);
+        }
+        // The interval no longer shrinks in `f64`.
+        if !(lo < c && d < hi) {
+            break;
+        }
+    }
+    (lo + hi) / 2.0
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rand::rngs::StdRng;
+    use rand::Rng;
+    use rand::SeedableRng;
+    use std::f64::consts::PI;
+
+    #[test]
+    fn test_integrate_known() {
+        type F = fn(f64) -> f64;
+        let cases: [[F; 2]; 5] = [
+            [|x| x * x * x - 2.0 * x, |x| x.powi(4) / 4.0 - x * x],
+            [f64::exp, f64::exp],
+            [f64::cos, f64::sin],
+            [|x| 1.0 / (1.0 + x * x), f64::atan],
+            [
+                |x| (x * x + 1.0).sqrt(),
+                |x| (x * (x * x + 1.0).sqrt() + (x + (x * x + 1.0).sqrt()).ln()) / 2.0,
+            ],
+        ];
+        let mut rng = StdRng::seed_from_u64(42);
+        for [f, antiderivative] in cases {
+            for _ in 0..20 {
+                let a = rng.gen_range(-3.0..3.0);
+                let b = rng.gen_range(-3.0..3.0);
+                let expected = antiderivative(b) - antiderivative(a);
+                let result = integrate(f, a, b, 1e-10);
+                assert!((result - expected).abs() < 1e-9, "{} {}", result, expected);
+            }
+        }
+    }
+
+    #[test]
+    fn test_integrate_sharp_peak() {
+        // A peak of width $10 ^ {-4}$ that the first five points miss.
+        let k = 1e4;
+        let c = 0.3;
+        let mut count = 0;
+        let result = integrate(
+            |x| {
+                count += 1;
+                k / (1.0 + (k * (x - c)).powi(2))
+            },
+            0.0,
+            1.0,
+            1e-9,
+        );
+        let expected = (k * (1.0 - c)).atan() - (k * -c).atan();
+        assert!((result - expected).abs() < 1e-7, "{} {}", result, expected);
+        assert!(count < 1_000_000);
+
+        // A kink and a square-root singularity of the derivative
+        let result = integrate(|x: f64| x.abs().sqrt(), -1.0, 4.0, 1e-10);
+        assert!((result - 2.0 / 3.0 * 9.0).abs() < 1e-8, "{}", result);
+        assert_eq!(integrate(|x| x, 2.0, 2.0, 1e-9), 0.0);
+        assert!((integrate(|x| x, 2.0, 0.0, 1e-9) + 2.0).abs() < 1e-12);
+    }
+
+    #[test]
+    fn test_find_root_clustered() {
+        let mut rng = StdRng::seed_from_u64(42);
+        for _ in 0..200 {
+            let k: u32 = rng.gen_range(1..=6);
+            let center: f64 = rng.gen_range(-2.0..2.0);
+            let gap: f64 = [1e-1, 1e-3, 1e-5, 0.0][rng.gen_range(0..4_usize)];
+            let roots = (0..k)
+                .map(|i| center + gap * f64::from(i))
+                .collect::<Vec<_>>();
+            let f = |x: f64| roots.iter().map(|&r| x - r).product::<f64>();
+            let df = |x: f64| {
+                (0..k)
+                    .map(|i| {
+                        (0..k)
+                            .filter(|&j| j != i)
+                            .map(|j| x - roots[j as usize])
+                            .product::<f64>()
+                    })
+                    .sum::<f64>()
+            };
+            let lo = center - rng.gen_range(0.1..3.0);
+            let hi = center + gap * f64::from(k) + rng.gen_range(0.1..3.0);
+            if (f(lo) < 0.0) == (f(hi) < 0.0) {
+                continue;
+            }
+            let eps = 1e-9;
+            let x = find_root(f, df, lo, hi, eps);
+            // Some root within `eps`, allowing for the rounding of `f` near clustered roots
+            let fuzz = if gap == 0.0 { 1e-3_f64.powf(1.0 / f64::from(k)).max(eps) } else { eps };
+            assert!(
+                roots.iter().any(|&r| (x - r).abs() <= eps + fuzz),
+                "x = {}, roots = {:?}",
+                x,
+                roots
+            );
+        }
+    }
+
+    #[test]
+    fn test_find_root_nasty() {
+        // Newton's method alone cycles between $\pm 1$.
+        let x = find_root(
+            |x: f64| x.powi(3) - 2.0 * x + 2.0,
+            |x| 3.0 * x * x - 2.0,
+            -3.0,
+            3.0,
+            1e-12,
+        );
+        assert!((x.powi(3) - 2.0 * x + 2.0).abs() < 1e-10);
+        // Newton's method alone diverges.
+        let x = find_root(f64::atan, |x| 1.0 / (1.0 + x * x), -20.0, 100.0, 1e-12);
+        assert!(x.abs() <= 1e-12);
+        // A derivative that vanishes at the root
+        let x = find_root(
+            |x: f64| x.cbrt(),
+            |x: f64| x.cbrt() / x / 3.0,
+            -1.0,
+            2.0,
+            1e-12,
+        );
+        assert!(x.abs() <= 1e-12);
+        // The endpoints
+        assert_eq!(find_root(|x| x - 1.0, |_| 1.0, 1.0, 2.0, 1e-9), 1.0);
+        assert_eq!(find_root(|x| x - 2.0, |_| 1.0, 1.0, 2.0, 1e-9), 2.0);
+        // A root at $\pi$ that is not representable
+        let x = find_root(f64::sin, f64::cos, 3.0, 3.5, 0.0);
+        assert!((x - PI).abs() <= 1e-15);
+    }
+
+    #[test]
+    #[should_panic(expected = "have the same sign")]
+    fn test_find_root_no_sign_change() {
+        find_root(|x| x * x + 1.0, |x| 2.0 * x, -1.0, 1.0, 1e-9);
+    }
+
+    #[test]
+    fn test_golden_section_min() {
+        let mut rng = StdRng::seed_from_u64(42);
+        for _ in 0..200 {
+            let m: f64 = rng.gen_range(-5.0..5.0);
+            let flat: f64 = [0.0, 1e-6, 0.5][rng.gen_range(0..3_usize)];
+            let lo = rng.gen_range(-10.0..m);
+            let hi = rng.gen_range(m + flat..10.0);
+            let eps = 1e-8;
+            // Flat on $\[m, m + flat\]$
+            let f = |x: f64| {
+                if x < m {
+                    (m - x).powi(2)
+                } else if x <= m + flat {
+                    0.0
+                } else {
+                    (x - m - flat).abs().sqrt()
+                }
+            };
+            let x = golden_section_min(f, lo, hi, eps);
+            assert!(m - eps <= x && x <= m + flat + eps, "x = {}, m = {}", x, m);
+        }
+        // The minimum at an endpoint
+        let x = golden_section_min(|x| x, 0.0, 1.0, 1e-9);
+        assert!(x <= 1e-9);
+        let x = golden_section_min(|x| -x, 0.0, 1.0, 1e-9);
+        assert!(x >= 1.0 - 1e-9);
+        // A tolerance that `f64` cannot meet
+        let x = golden_section_min(|x| (x - 1.0).abs(), 0.0, 3.0, 0.0);
+        assert!((x - 1.0).abs() <= 1e-15);
+    }
+}
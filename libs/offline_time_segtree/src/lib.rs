@@ -0,0 +1,432 @@
+//! Offline processing of operations with lifetimes by a segment tree on time.
+//!
+//! An operation alive on $\[l, r)$ is registered to $O(\log n)$ nodes of a segment tree over the
+//! time points. A DFS over the tree applies the operations of a node on entering it and undoes
+//! them on leaving it, so a structure that supports only rollback (e.g. a union-find without path
+//! compression) answers the queries at every leaf.
+//!
+//! # Examples
+//!
+//! ```
+//! use offline_time_segtree::OfflineTimeSegtree;
+//! use offline_time_segtree::Rollback;
+//!
+//! struct Sum(i64);
+//! impl Rollback for Sum {
+//!     type Answer = i64;
+//!     type Op = i64;
+//!     type Query = ();
+//!     type Token = i64;
+//!
+//!     fn apply(&mut self, &op: &i64) -> i64 {
+//!         self.0 += op;
+//!         op
+//!     }
+//!
+//!     fn undo(&mut self, token: i64) {
+//!         self.0 -= token;
+//!     }
+//!
+//!     fn answer(&mut self, _: &()) -> i64 {
+//!         self.0
+//!     }
+//! }
+//!
+//! let mut driver = OfflineTimeSegtree::new(4);
+//! driver.add_operation(0..3, 1);
+//! driver.add_operation(2.., 10);
+//! for t in 0..4 {
+//!     driver.add_query(t, ());
+//! }
+//! assert_eq!(driver.run(&mut Sum(0)), vec![1, 1, 11, 10]);
+//! ```
+use std::ops::Range;
+use std::ops::RangeBounds;
+
+/// A structure that supports undoing the last operation.
+pub trait Rollback {
+    /// The type of operations.
+    type Op;
+    /// The information to undo an operation.
+    type Token;
+    /// The type of queries.
+    type Query;
+    /// The type of answers.
+    type Answer;
+    /// Applies an operation.
+    fn apply(&mut self, op: &Self::Op) -> Self::Token;
+    /// Undoes the last operation that is not undone yet.
+    fn undo(&mut self, token: Self::Token);
+    /// Answers a query.
+    fn answer(&mut self, query: &Self::Query) -> Self::Answer;
+}
+
+/// The driver of the offline processing.
+#[derive(Debug, Clone, PartialEq)]
+pub struct OfflineTimeSegtree<O, Q> {
+    len: usize,
+    ops: Vec<O>,
+    node_ops: Vec<Vec<usize>>,
+    queries: Vec<Q>,
+    leaf_queries: Vec<Vec<usize>>,
+}
+impl<O, Q> OfflineTimeSegtree<O, Q> {
+    /// Constructs a driver with time points $0, 1, \dots, n - 1$.
+    pub fn new(len: usize) -> Self {
+        Self {
+            len,
+            ops: Vec::new(),
+            node_ops: vec![Vec::new(); 2 * len],
+            queries: Vec::new(),
+            leaf_queries: vec![Vec::new(); len],
+        }
+    }
+
+    /// Registers an operation alive on the time points in `range`.
+    pub fn add_operation(&mut self, range: impl RangeBounds<usize>, op: O) {
+        let Range { mut start, mut end } = open::resolve_range(self.len, range);
+        let id = self.ops.len();
+        self.ops.push(op);
+        start += self.len;
+        end += self.len;
+        while start < end {
+            if start % 2 == 1 {
+                self.node_ops[start].push(id);
+                start += 1;
+            }
+            if end % 2 == 1 {
+                end -= 1;
+                self.node_ops[end].push(id);
+            }
+            start /= 2;
+            end /= 2;
+        }
+    }
+
+    /// Registers a query at time $t$, and returns its index in the result of [`Self::run`].
+    pub fn add_query(&mut self, t: usize, query: Q) -> usize {
+        assert!(
+            t < self.len,
+            "time {} out of range for time of length {}",
+            t,
+            self.len
+        );
+        let id = self.queries.len();
+        self.queries.push(query);
+        self.leaf_queries[t].push(id);
+        id
+    }
+
+    /// Runs the DFS, and returns the answers in the order of registration.
+    ///
+    /// # Complexity
+    ///
+    /// $O(q + m \log n)$ calls of the methods of [`Rollback`], where $m$ is the number of the
+    /// operations and $q$ is that of the queries.
+    pub fn run<S>(&self, structure: &mut S) -> Vec<S::Answer>
+    where
+        S: Rollback<Op = O, Query = Q>,
+    {
+        let n = self.len;
+        let mut has_query = vec![false; 2 * n];
+        for (t, queries) in self.leaf_queries.iter().enumerate() {
+            has_query[n + t] = !queries.is_empty();
+        }
+        for k in (1..n).rev() {
+            has_query[k] = has_query[2 * k] || has_query[2 * k + 1];
+        }
+        let mut answers = (0..self.queries.len()).map(|_| None).collect::<Vec<_>>();
+        if n != 0 {
+            self.dfs(1, &has_query, structure, &mut answers);
+        }
+        answers.into_iter().map(Option::unwrap).collect()
+    }
+
+    fn dfs<S>(
+        &self,
+        k: usize,
+        has_query: &[bool],
+        structure: &mut S,
+        answers: &mut [Option<S::Answer>],
+    ) where
+        S: Rollback<Op = O, Query = Q>,
+    {
+        if !has_query[k] {
+            return;
+        }
+        let tokens = self.node_ops[k]
+            .iter()
+            .map(|&id| structure.apply(&self.ops[id]))
+            .collect::<Vec<_>>();
+        if self.len <= k {
+            for &id in &self.leaf_queries[k - self.len] {
+                answers[id] = Some(structure.answer(&self.queries[id]));
+            }
+        } else {
+            self.dfs(2 * k, has_query, structure, answers);
+            self.dfs(2 * k + 1, has_query, structure, answers);
+        }
+        for token in tokens.into_iter().rev() {
+            structure.undo(token);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rand::rngs::StdRng;
+    use rand::Rng;
+    use rand::SeedableRng;
+
+    // Union by size without path compression.
+    struct RollbackDsu {
+        parent: Vec<usize>,
+        size: Vec<usize>,
+    }
+    impl RollbackDsu {
+        fn new(n: usize) -> Self {
+            Self {
+                parent: (0..n).collect(),
+                size: vec![1; n],
+            }
+        }
+
+        fn find(&self, mut x: usize) -> usize {
+            while self.parent[x] != x {
+                x = self.parent[x];
+            }
+            x
+        }
+    }
+    impl Rollback for RollbackDsu {
+        type Answer = bool;
+        type Op = (usize, usize);
+        type Query = (usize, usize);
+        type Token = Option<usize>;
+
+        fn apply(&mut self, &(u, v): &(usize, usize)) -> Option<usize> {
+            let mut u = self.find(u);
+            let mut v = self.find(v);
+            if u == v {
+                return None;
+            }
+            if self.size[u] < self.size[v] {
+                std::mem::swap(&mut u, &mut v);
+            }
+            self.parent[v] = u;
+            self.size[u] += self.size[v];
+            Some(v)
+        }
+
+        fn undo(&mut self, token: Option<usize>) {
+            if let Some(v) = token {
+                let u = self.parent[v];
+                self.parent[v] = v;
+                self.size[u] -= self.size[v];
+            }
+        }
+
+        fn answer(&mut self, &(u, v): &(usize, usize)) -> bool {
+            self.find(u) == self.find(v)
+        }
+    }
+
+    // Union by size with the parity to the parent, counting the odd cycles.
+    struct ParityDsu {
+        parent: Vec<usize>,
+        size: Vec<usize>,
+        parity: Vec<bool>,
+        odd_cycles: usize,
+    }
+    impl ParityDsu {
+        fn new(n: usize) -> Self {
+            Self {
+                parent: (0..n).collect(),
+                size: vec![1; n],
+                parity: vec![false; n],
+                odd_cycles: 0,
+            }
+        }
+
+        fn find(&self, mut x: usize) -> (usize, bool) {
+            let mut parity = false;
+            while self.parent[x] != x {
+                parity ^= self.parity[x];
+                x = self.parent[x];
+            }
+            (x, parity)
+        }
+    }
+    enum ParityToken {
+        Union(usize),
+        OddCycle,
+        EvenCycle,
+    }
+    impl Rollback for ParityDsu {
+        type Answer = bool;
+        type Op = (usize, usize);
+        type Query = ();
+        type Token = ParityToken;
+
+        fn apply(&mut self, &(u, v): &(usize, usize)) -> ParityToken {
+            let (mut u, pu) = self.find(u);
+            let (mut v, pv) = self.find(v);
+            if u == v {
+                if pu == pv {
+                    self.odd_cycles += 1;
+                    return ParityToken::OddCycle;
+                }
+                return ParityToken::EvenCycle;
+            }
+            if self.size[u] < self.size[v] {
+                std::mem::swap(&mut u, &mut v);
+            }
+            self.parent[v] = u;
+            self.size[u] += self.size[v];
+            self.parity[v] = !(pu ^ pv);
+            ParityToken::Union(v)
+        }
+
+        fn undo(&mut self, token: ParityToken) {
+            match token {
+                ParityToken::Union(v) => {
+                    let u = self.parent[v];
+                    self.parent[v] = v;
+                    self.parity[v] = false;
+                    self.size[u] -= self.size[v];
+                }
+                ParityToken::OddCycle => self.odd_cycles -= 1,
+                ParityToken::EvenCycle => {}
+            }
+        }
+
+        fn answer(&mut self, _: &()) -> bool {
+            self.odd_cycles == 0
+        }
+    }
+
+    fn adjacency(n: usize, ops: &[(Range<usize>, (usize, usize))], t: usize) -> Vec<Vec<usize>> {
+        let mut g = vec![Vec::new(); n];
+        for &(ref range, (u, v)) in ops {
+            if range.contains(&t) {
+                g[u].push(v);
+                g[v].push(u);
+            }
+        }
+        g
+    }
+
+    fn brute_connected(g: &[Vec<usize>], u: usize, v: usize) -> bool {
+        let mut visited = vec![false; g.len()];
+        visited[u] = true;
+        let mut stack = vec![u];
+        while let Some(x) = stack.pop() {
+            for &y in &g[x] {
+                if !visited[y] {
+                    visited[y] = true;
+                    stack.push(y);
+                }
+            }
+        }
+        visited[v]
+    }
+
+    fn brute_bipartite(g: &[Vec<usize>]) -> bool {
+        let mut color = vec![None; g.len()];
+        for s in 0..g.len() {
+            if color[s].is_some() {
+                continue;
+            }
+            color[s] = Some(false);
+            let mut stack = vec![s];
+            while let Some(x) = stack.pop() {
+                let c = color[x].unwrap();
+                for &y in &g[x] {
+                    match color[y] {
+                        None => {
+                            color[y] = Some(!c);
+                            stack.push(y);
+                        }
+                        Some(d) if c == d => return false,
+                        Some(_) => {}
+                    }
+                }
+            }
+        }
+        true
+    }
+
+    #[allow(clippy::type_complexity)]
+    fn random_instance<Q>(
+        rng: &mut StdRng,
+        n: usize,
+        time: usize,
+    ) -> (
+        OfflineTimeSegtree<(usize, usize), Q>,
+        Vec<(Range<usize>, (usize, usize))>,
+    ) {
+        let mut driver = OfflineTimeSegtree::new(time);
+        let mut ops = Vec::new();
+        for _ in 0..rng.gen_range(0..=2 * n) {
+            let l = rng.gen_range(0..=time);
+            let r = rng.gen_range(l..=time);
+            let edge = (rng.gen_range(0..n), rng.gen_range(0..n));
+            driver.add_operation(l..r, edge);
+            ops.push((l..r, edge));
+        }
+        (driver, ops)
+    }
+
+    #[test]
+    fn test_connectivity() {
+        let mut rng = StdRng::seed_from_u64(42);
+        for _ in 0..200 {
+            let n = rng.gen_range(1..=8);
+            let time = rng.gen_range(1..=12);
+            let (mut driver, ops) = random_instance(&mut rng, n, time);
+            let mut expected = Vec::new();
+            for _ in 0..rng.gen_range(0..=20) {
+                let t = rng.gen_range(0..time);
+                let (u, v) = (rng.gen_range(0..n), rng.gen_range(0..n));
+                assert_eq!(driver.add_query(t, (u, v)), expected.len());
+                expected.push(brute_connected(&adjacency(n, &ops, t), u, v));
+            }
+            let mut dsu = RollbackDsu::new(n);
+            assert_eq!(driver.run(&mut dsu), expected);
+            assert_eq!(dsu.parent, (0..n).collect::<Vec<_>>());
+        }
+    }
+
+    #[test]
+    fn test_bipartiteness() {
+        let mut rng = StdRng::seed_from_u64(42);
+        for _ in 0..200 {
+            let n = rng.gen_range(1..=8);
+            let time = rng.gen_range(1..=12);
+            let (mut driver, ops) = random_instance(&mut rng, n, time);
+            let mut expected = Vec::new();
+            for t in 0..time {
+                driver.add_query(t, ());
+                expected.push(brute_bipartite(&adjacency(n, &ops, t)));
+            }
+            let mut dsu = ParityDsu::new(n);
+            assert_eq!(driver.run(&mut dsu), expected);
+            assert_eq!(dsu.odd_cycles, 0);
+        }
+    }
+
+    #[test]
+    fn test_empty() {
+        let driver = OfflineTimeSegtree::<(usize, usize), ()>::new(0);
+        assert!(driver.run(&mut ParityDsu::new(1)).is_empty());
+    }
+
+    #[test]
+    #[should_panic(expected = "range starts at 3 but ends at 2")]
+    fn test_reversed_range() {
+        let mut driver = OfflineTimeSegtree::<(usize, usize), ()>::new(5);
+        let (start, end) = (3, 2);
+        driver.add_operation(start..end, (0, 1));
+    }
+}
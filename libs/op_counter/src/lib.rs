@@ -0,0 +1,270 @@
+//! Operation counters for testing the complexity of data structures.
+//!
+//! A data structure calls [`tick`] in its hot loops, only under
+//! `#[cfg(any(test, feature = "count-ops"))]`, so the counter compiles away to nothing in the
+//! default build. A test measures the number of the operations for several sizes with
+//! [`OpCounter`], and checks the growth rate with [`assert_complexity`].
+//!
+//! # Wiring into a crate
+//!
+//! ```toml
+//! [dependencies]
+//! op_counter = { path = "../op_counter", optional = true }
+//!
+//! [dev-dependencies]
+//! op_counter = { path = "../op_counter" }
+//!
+//! [features]
+//! count-ops = ["op_counter"]
+//! ```
+//!
+//! # Examples
+//!
+//! ```
+//! use op_counter::assert_complexity;
+//! use op_counter::tick;
+//! use op_counter::OpCounter;
+//!
+//! fn sum(a: &[u64]) -> u64 {
+//!     a.iter()
+//!         .map(|&x| {
+//!             tick();
+//!             x
+//!         })
+//!         .sum()
+//! }
+//!
+//! let ns = [1000, 2000, 4000, 8000];
+//! let counts = ns
+//!     .iter()
+//!     .map(|&n| {
+//!         let counter = OpCounter::start();
+//!         sum(&vec![1; n]);
+//!         counter.get()
+//!     })
+//!     .collect::<Vec<_>>();
+//! assert_complexity(&ns, &counts, 1.1);
+//! ```
+
+use std::cell::Cell;
+
+thread_local! {
+    static COUNT: Cell<u64> = Cell::new(0);
+}
+
+/// Counts one operation in the current thread.
+pub fn tick() {
+    add(1);
+}
+
+/// Counts `n` operations in the current thread.
+pub fn add(n: u64) {
+    COUNT.with(|c| c.set(c.get() + n));
+}
+
+/// A stopwatch of the operations counted in the current thread.
+///
+/// The counter is never reset, so nested or overlapping measurements do not interfere.
+///
+/// # Examples
+///
+/// ```
+/// use op_counter::tick;
+/// use op_counter::OpCounter;
+/// let outer = OpCounter::start();
+/// tick();
+/// let inner = OpCounter::start();
+/// tick();
+/// tick();
+/// assert_eq!(inner.get(), 2);
+/// assert_eq!(outer.get(), 3);
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct OpCounter {
+    start: u64,
+}
+impl OpCounter {
+    /// Starts measuring.
+    pub fn start() -> Self {
+        Self {
+            start: COUNT.with(Cell::get),
+        }
+    }
+
+    /// Returns the number of the operations since [`start`](Self::start).
+    pub fn get(&self) -> u64 {
+        COUNT.with(Cell::get) - self.start
+    }
+}
+
+/// Returns the number of the operations counted in `f`, together with its result.
+///
+/// # Examples
+///
+/// ```
+/// use op_counter::count_ops;
+/// use op_counter::tick;
+/// assert_eq!(count_ops(|| (0..10).for_each(|_| tick())), ((), 10));
+/// ```
+pub fn count_ops<R>(f: impl FnOnce() -> R) -> (R, u64) {
+    let counter = OpCounter::start();
+    let result = f();
+    (result, counter.get())
+}
+
+/// Returns the exponent $e$ of the best fit $\mathrm{count} \approx c n^e$, by least squares on the
+/// log-log scale.
+///
+/// For doubling sizes, this is the average of $\log _ 2$ of the doubling ratios, weighted to the
+/// both ends. Note that $n \log n$ has a slightly larger exponent than $1$, such as $1.1$ for $n$
+/// around $2^{10}$.
+///
+/// # Panics
+///
+/// - The lengths of `ns` and `counts` are different.
+/// - There are fewer than two distinct sizes.
+/// - A size or a count is zero.
+///
+/// # Examples
+///
+/// ```
+/// use op_counter::fitted_exponent;
+/// let e = fitted_exponent(&[10, 20, 40], &[100, 400, 1600]);
+/// assert!((e - 2.0).abs() < 1e-9);
+/// ```
+pub fn fitted_exponent(ns: &[usize], counts: &[u64]) -> f64 {
+    assert_eq!(
+        ns.len(),
+        counts.len(),
+        "the lengths of the sizes and the counts must be the same"
+    );
+    assert!(
+        ns.iter().all(|&n| n > 0) && counts.iter().all(|&c| c > 0),
+        "the sizes and the counts must be positive"
+    );
+    let xs = ns.iter().map(|&n| (n as f64).ln()).collect::<Vec<_>>();
+    let ys = counts.iter().map(|&c| (c as f64).ln()).collect::<Vec<_>>();
+    let len = xs.len() as f64;
+    let x_mean = xs.iter().sum::<f64>() / len;
+    let y_mean = ys.iter().sum::<f64>() / len;
+    let sxx = xs.iter().map(|&x| (x - x_mean).powi(2)).sum::<f64>();
+    let sxy = xs
+        .iter()
+        .zip(&ys)
+        .map(|(&x, &y)| (x - x_mean) * (y - y_mean))
+        .sum::<f64>();
+    assert!(sxx > 0.0, "at least two distinct sizes are needed");
+    sxy / sxx
+}
+
+/// Asserts that the counts grow at most as fast as $n ^ {\mathrm{exponent\_bound}}$, by
+/// [`fitted_exponent`].
+///
+/// # Panics
+///
+/// Panics if the fitted exponent exceeds `exponent_bound`, or in the cases of
+/// [`fitted_exponent`].
+///
+/// # Examples
+///
+/// ```should_panic
+/// use op_counter::assert_complexity;
+/// // Quadratic
+/// assert_complexity(&[10, 20, 40], &[100, 400, 1600], 1.5);
+/// ```
+pub fn assert_complexity(ns: &[usize], counts: &[u64], exponent_bound: f64) {
+    let exponent = fitted_exponent(ns, counts);
+    assert!(
+        exponent <= exponent_bound,
+        "the fitted exponent {:.3} exceeds the bound {:.3}: ns = {:?}, counts = {:?}",
+        exponent,
+        exponent_bound,
+        ns,
+        counts
+    );
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_counter_is_thread_local() {
+        let counter = OpCounter::start();
+        tick();
+        std::thread::spawn(|| (0..100).for_each(|_| tick()))
+            .join()
+            .unwrap();
+        assert_eq!(counter.get(), 1);
+    }
+
+    #[test]
+    fn test_fitted_exponent() {
+        let ns = [1 << 8, 1 << 9, 1 << 10, 1 << 11, 1 << 12];
+        for e in [0.0, 0.5, 1.0, 1.5, 2.0, 3.0] {
+            let counts = ns
+                .iter()
+                .map(|&n| (3.0 * (n as f64).powf(e)).round() as u64)
+                .collect::<Vec<_>>();
+            assert!((fitted_exponent(&ns, &counts) - e).abs() < 1e-3);
+        }
+        // $n \log n$
+        let counts = ns
+            .iter()
+            .map(|&n| (n * n.trailing_zeros() as usize) as u64)
+            .collect::<Vec<_>>();
+        let e = fitted_exponent(&ns, &counts);
+        assert!(1.0 < e && e < 1.2);
+    }
+
+    // Insertion sort, counting the comparisons
+    fn insertion_sort(a: &mut [u64]) {
+        for i in 1..a.len() {
+            let mut j = i;
+            while j > 0 && {
+                tick();
+                a[j - 1] > a[j]
+            } {
+                a.swap(j - 1, j);
+                j -= 1;
+            }
+        }
+    }
+
+    fn measure_insertion_sort() -> (Vec<usize>, Vec<u64>) {
+        let ns = vec![100, 200, 400, 800];
+        let counts = ns
+            .iter()
+            .map(|&n| {
+                let mut a = (0..n as u64).rev().collect::<Vec<_>>();
+                count_ops(|| insertion_sort(&mut a)).1
+            })
+            .collect();
+        (ns, counts)
+    }
+
+    #[test]
+    fn test_quadratic_within_bound() {
+        let (ns, counts) = measure_insertion_sort();
+        assert_complexity(&ns, &counts, 2.1);
+    }
+
+    #[test]
+    #[should_panic(expected = "exceeds the bound")]
+    fn test_quadratic_canary() {
+        let (ns, counts) = measure_insertion_sort();
+        assert_complexity(&ns, &counts, 1.5);
+    }
+
+    #[test]
+    #[should_panic(expected = "at least two distinct sizes are needed")]
+    fn test_single_size() {
+        fitted_exponent(&[10, 10], &[1, 2]);
+    }
+
+    #[test]
+    #[should_panic(expected = "the sizes and the counts must be positive")]
+    fn test_zero_count() {
+        fitted_exponent(&[10, 20], &[0, 2]);
+    }
+}
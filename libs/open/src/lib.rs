@@ -1,7 +1,37 @@
+//! Conversions from [`RangeBounds<usize>`] to [`Range<usize>`].
+//!
+//! The range-query structures convert their range arguments with [`resolve_range`], so that they
+//! validate them in the same way and panic with the same messages as slices do, except that the
+//! messages do not say "slice".
+//!
+//! # Examples
+//!
+//! ```
+//! use open::resolve_range;
+//! assert_eq!(resolve_range(5, ..), 0..5);
+//! assert_eq!(resolve_range(5, 1..=3), 1..4);
+//! ```
+//!
+//! ```should_panic
+//! use open::resolve_range;
+//! // range starts at 3 but ends at 2
+//! resolve_range(5, 3..2);
+//! ```
+
 use std::ops::Bound;
 use std::ops::Range;
 use std::ops::RangeBounds;
 
+/// Converts `range` into a [`Range`] without any validation.
+///
+/// # Examples
+///
+/// ```
+/// use open::open;
+/// assert_eq!(open(5, 2..), 2..5);
+/// // Not checked
+/// assert_eq!(open(5, 6..3), 6..3);
+/// ```
 pub fn open(len: usize, range: impl RangeBounds<usize>) -> Range<usize> {
     use Bound::Excluded;
     use Bound::Included;
@@ -16,3 +46,156 @@ pub fn open(len: usize, range: impl RangeBounds<usize>) -> Range<usize> {
         Unbounded => len,
     })
 }
+
+/// Converts `range` into a [`Range`] within `0..len`.
+///
+/// # Panics
+///
+/// With the same checks and messages as indexing a slice by a range, that is,
+/// `<[T] as Index<Range<usize>>>::index`, in this order:
+///
+/// - `Excluded(usize::MAX)` start: "attempted to index from after maximum usize"
+/// - `Included(usize::MAX)` end: "attempted to index up to maximum usize"
+/// - $\mathrm{start} > \mathrm{end}$: "range starts at {start} but ends at {end}"
+/// - $\mathrm{end} > \mathrm{len}$: "range end index {end} out of range for length {len}"
+///
+/// # Examples
+///
+/// ```
+/// use open::resolve_range;
+/// use std::ops::Bound;
+/// assert_eq!(resolve_range(5, 2..), 2..5);
+/// assert_eq!(resolve_range(5, ..=2), 0..3);
+/// assert_eq!(
+///     resolve_range(5, (Bound::Excluded(1), Bound::Included(3))),
+///     2..4
+/// );
+/// assert_eq!(resolve_range(5, 5..5), 5..5);
+/// ```
+pub fn resolve_range(len: usize, range: impl RangeBounds<usize>) -> Range<usize> {
+    let start = match range.start_bound() {
+        Bound::Unbounded => 0,
+        Bound::Included(&x) => x,
+        Bound::Excluded(&x) => x.checked_add(1).unwrap_or_else(|| start_overflow_fail()),
+    };
+    let end = match range.end_bound() {
+        Bound::Unbounded => len,
+        Bound::Included(&x) => x.checked_add(1).unwrap_or_else(|| end_overflow_fail()),
+        Bound::Excluded(&x) => x,
+    };
+    if start > end {
+        index_order_fail(start, end);
+    }
+    if end > len {
+        end_index_len_fail(end, len);
+    }
+    start..end
+}
+
+#[cold]
+#[inline(never)]
+fn start_overflow_fail() -> ! {
+    panic!("attempted to index from after maximum usize");
+}
+#[cold]
+#[inline(never)]
+fn end_overflow_fail() -> ! {
+    panic!("attempted to index up to maximum usize");
+}
+#[cold]
+#[inline(never)]
+fn index_order_fail(start: usize, end: usize) -> ! {
+    panic!("range starts at {} but ends at {}", start, end);
+}
+#[cold]
+#[inline(never)]
+fn end_index_len_fail(end: usize, len: usize) -> ! {
+    panic!("range end index {} out of range for length {}", end, len);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::panic::catch_unwind;
+
+    // The resolved range, or the panic message.
+    fn message(len: usize, range: (Bound<usize>, Bound<usize>)) -> Result<Range<usize>, String> {
+        catch_unwind(|| resolve_range(len, range)).map_err(|e| match e.downcast::<String>() {
+            Ok(s) => *s,
+            Err(e) => e.downcast::<&str>().unwrap().to_string(),
+        })
+    }
+
+    #[test]
+    fn test_agrees_with_slices() {
+        // Every combination of the bounds, compared with `slice::get`.
+        let values = [0, 1, 2, 3, 4, 5, usize::MAX - 1, usize::MAX];
+        let bounds = |x: usize| [Bound::Included(x), Bound::Excluded(x)];
+        for len in 0..=4 {
+            let a = vec![(); len];
+            let starts = values
+                .iter()
+                .flat_map(|&x| bounds(x))
+                .chain([Bound::Unbounded]);
+            for start in starts {
+                let ends = values
+                    .iter()
+                    .flat_map(|&x| bounds(x))
+                    .chain([Bound::Unbounded]);
+                for end in ends {
+                    let expected = a.get((start, end)).map(|s| s.len());
+                    let result = message(len, (start, end));
+                    assert_eq!(
+                        result.as_ref().ok().map(|r| r.len()),
+                        expected,
+                        "{:?}",
+                        (len, start, end)
+                    );
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn test_messages() {
+        use Bound::Excluded;
+        use Bound::Included;
+        use Bound::Unbounded;
+        let max_start = format!("range starts at {} but ends at 5", usize::MAX);
+        let cases = [
+            (
+                (Excluded(usize::MAX), Unbounded),
+                "attempted to index from after maximum usize",
+            ),
+            (
+                (Included(0), Included(usize::MAX)),
+                "attempted to index up to maximum usize",
+            ),
+            (
+                (Excluded(usize::MAX), Included(usize::MAX)),
+                "attempted to index from after maximum usize",
+            ),
+            (
+                (Included(3), Excluded(2)),
+                "range starts at 3 but ends at 2",
+            ),
+            (
+                (Excluded(2), Included(1)),
+                "range starts at 3 but ends at 2",
+            ),
+            ((Included(6), Unbounded), "range starts at 6 but ends at 5"),
+            ((Included(usize::MAX), Unbounded), max_start.as_str()),
+            (
+                (Unbounded, Included(5)),
+                "range end index 6 out of range for length 5",
+            ),
+            (
+                (Included(6), Excluded(7)),
+                "range end index 7 out of range for length 5",
+            ),
+        ];
+        for (range, expected) in cases {
+            assert_eq!(message(5, range).unwrap_err(), expected);
+        }
+    }
+}
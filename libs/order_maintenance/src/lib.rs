@@ -0,0 +1,416 @@
+//! An order-maintenance list: a linked list that compares the positions of two elements in $O(1)$.
+//!
+//! Every element carries an integer label increasing along the list, so that comparing two
+//! elements is comparing their labels. When an insertion finds no free label between its
+//! neighbours, it relabels the smallest enclosing aligned window of labels that is sparse enough,
+//! which costs $O(\log n)$ amortized (Bender, Cole, Demaine, Farach-Colton and Zito, *Two
+//! Simplified Algorithms for Maintaining Order in a List*, 2002).
+//!
+//! # Examples
+//!
+//! ```
+//! use order_maintenance::OrderMaintenance;
+//! use std::cmp::Ordering;
+//!
+//! let mut list = OrderMaintenance::new();
+//! let a = list.insert_back();
+//! let c = list.insert_after(a);
+//! let b = list.insert_before(c);
+//! assert_eq!(list.iter().collect::<Vec<_>>(), [a, b, c]);
+//! assert_eq!(list.order(a, c), Ordering::Less);
+//! assert!(list.is_before(b, c));
+//!
+//! list.delete(b);
+//! assert_eq!(list.next(a), Some(c));
+//! ```
+
+use std::cmp::Ordering;
+
+// Labels lie in `0..UNIVERSE`, and the sentinel always has the label `0`.
+const LOG_UNIVERSE: u32 = 62;
+const UNIVERSE: u64 = 1 << LOG_UNIVERSE;
+// A window of `2 ^ i` labels may hold at most `(2 / T) ^ i` elements, where `1 < T < 2`.
+const T: f64 = 1.4;
+const SENTINEL: usize = 0;
+
+/// An order-maintenance list.
+///
+/// A handle is the number of the insertions before it, and stays valid until the element is
+/// deleted. Handles of the deleted elements are never reused.
+#[derive(Clone, Debug)]
+pub struct OrderMaintenance {
+    // Node `0` is the sentinel, and the node of the handle `h` is `h + 1`.
+    nodes: Vec<Node>,
+    len: usize,
+}
+#[derive(Clone, Debug)]
+struct Node {
+    label: u64,
+    prev: usize,
+    next: usize,
+    alive: bool,
+}
+impl OrderMaintenance {
+    /// Constructs an empty list.
+    pub fn new() -> Self {
+        Self {
+            nodes: vec![Node {
+                label: 0,
+                prev: SENTINEL,
+                next: SENTINEL,
+                alive: true,
+            }],
+            len: 0,
+        }
+    }
+
+    /// Returns the number of the elements.
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    /// Returns `true` if the list is empty.
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    /// Inserts an element at the front, and returns its handle.
+    ///
+    /// # Complexity
+    ///
+    /// $O(\log n)$ amortized
+    pub fn insert_front(&mut self) -> usize {
+        self.insert_node_after(SENTINEL)
+    }
+
+    /// Inserts an element at the back, and returns its handle.
+    ///
+    /// # Complexity
+    ///
+    /// $O(\log n)$ amortized
+    pub fn insert_back(&mut self) -> usize {
+        let last = self.nodes[SENTINEL].prev;
+        self.insert_node_after(last)
+    }
+
+    /// Inserts an element right after `h`, and returns its handle.
+    ///
+    /// # Panics
+    ///
+    /// If `h` is not an element.
+    ///
+    /// # Complexity
+    ///
+    /// $O(\log n)$ amortized
+    pub fn insert_after(&mut self, h: usize) -> usize {
+        let x = self.node(h);
+        self.insert_node_after(x)
+    }
+
+    /// Inserts an element right before `h`, and returns its handle.
+    ///
+    /// # Panics
+    ///
+    /// If `h` is not an element.
+    ///
+    /// # Complexity
+    ///
+    /// $O(\log n)$ amortized
+    pub fn insert_before(&mut self, h: usize) -> usize {
+        let x = self.node(h);
+        self.insert_node_after(self.nodes[x].prev)
+    }
+
+    /// Deletes `h`.
+    ///
+    /// # Panics
+    ///
+    /// If `h` is not an element.
+    ///
+    /// # Complexity
+    ///
+    /// $O(1)$
+    pub fn delete(&mut self, h: usize) {
+        let x = self.node(h);
+        let Node { prev, next, .. } = self.nodes[x];
+        self.nodes[prev].next = next;
+        self.nodes[next].prev = prev;
+        self.nodes[x].alive = false;
+        self.len -= 1;
+    }
+
+    /// Returns `true` if `h` is an element, that is, it has been inserted and not deleted.
+    pub fn contains(&self, h: usize) -> bool {
+        self.nodes.get(h + 1).map_or(false, |node| node.alive)
+    }
+
+    /// Compares the positions of `a` and `b`.
+    ///
+    /// # Panics
+    ///
+    /// If `a` or `b` is not an element.
+    ///
+    /// # Complexity
+    ///
+    /// $O(1)$
+    pub fn order(&self, a: usize, b: usize) -> Ordering {
+        let a = self.node(a);
+        let b = self.node(b);
+        self.nodes[a].label.cmp(&self.nodes[b].label)
+    }
+
+    /// Returns `true` if `a` is strictly before `b`.
+    ///
+    /// # Panics
+    ///
+    /// If `a` or `b` is not an element.
+    pub fn is_before(&self, a: usize, b: usize) -> bool {
+        self.order(a, b) == Ordering::Less
+    }
+
+    /// Returns the first element.
+    pub fn first(&self) -> Option<usize> {
+        self.handle(self.nodes[SENTINEL].next)
+    }
+
+    /// Returns the last element.
+    pub fn last(&self) -> Option<usize> {
+        self.handle(self.nodes[SENTINEL].prev)
+    }
+
+    /// Returns the element right after `h`.
+    ///
+    /// # Panics
+    ///
+    /// If `h` is not an element.
+    pub fn next(&self, h: usize) -> Option<usize> {
+        self.handle(self.nodes[self.node(h)].next)
+    }
+
+    /// Returns the element right before `h`.
+    ///
+    /// # Panics
+    ///
+    /// If `h` is not an element.
+    pub fn prev(&self, h: usize) -> Option<usize> {
+        self.handle(self.nodes[self.node(h)].prev)
+    }
+
+    /// Returns an iterator over the elements from front to back.
+    pub fn iter(&self) -> impl Iterator<Item = usize> + '_ {
+        let mut x = self.nodes[SENTINEL].next;
+        std::iter::from_fn(move || {
+            let h = self.handle(x)?;
+            x = self.nodes[x].next;
+            Some(h)
+        })
+    }
+
+    fn node(&self, h: usize) -> usize {
+        assert!(self.contains(h), "{} is not an element", h);
+        h + 1
+    }
+
+    fn handle(&self, x: usize) -> Option<usize> {
+        x.checked_sub(1)
+    }
+
+    fn insert_node_after(&mut self, x: usize) -> usize {
+        let y = self.nodes.len();
+        let next = self.nodes[x].next;
+        let lo = self.nodes[x].label;
+        let hi = if next == SENTINEL { UNIVERSE } else { self.nodes[next].label };
+        self.nodes.push(Node {
+            label: lo + (hi - lo) / 2,
+            prev: x,
+            next,
+            alive: true,
+        });
+        self.nodes[x].next = y;
+        self.nodes[next].prev = y;
+        self.len += 1;
+        if hi - lo < 2 {
+            self.relabel(x, y);
+        }
+        y - 1
+    }
+
+    // Relabels the smallest sparse window containing the label of `x`, where `y` is the new node
+    // right after `x` and has no valid label yet.
+    fn relabel(&mut self, x: usize, y: usize) {
+        let label = self.nodes[x].label;
+        // The window is `first..=last` in the list, whose labels are in `lo..lo + 2 ^ i`.
+        let mut first = x;
+        let mut last = y;
+        let mut count = 2;
+        let mut limit = 1.0;
+        for i in 1..=LOG_UNIVERSE {
+            limit *= 2.0 / T;
+            let lo = label >> i << i;
+            let hi = lo + (1 << i);
+            while first != SENTINEL && self.nodes[self.nodes[first].prev].label >= lo {
+                first = self.nodes[first].prev;
+                count += 1;
+            }
+            loop {
+                let next = self.nodes[last].next;
+                if next == SENTINEL || self.nodes[next].label >= hi {
+                    break;
+                }
+                last = next;
+                count += 1;
+            }
+            if count as f64 <= limit {
+                let gap = (1 << i) / count as u64;
+                let mut z = first;
+                for k in 0..count as u64 {
+                    self.nodes[z].label = lo + k * gap;
+                    z = self.nodes[z].next;
+                }
+                return;
+            }
+        }
+        panic!("too many elements");
+    }
+}
+impl Default for OrderMaintenance {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rand::rngs::StdRng;
+    use rand::Rng;
+    use rand::SeedableRng;
+
+    // Checks the labels and the links against `expected`.
+    fn validate(list: &OrderMaintenance, expected: &[usize]) {
+        assert_eq!(list.len(), expected.len());
+        assert_eq!(list.iter().collect::<Vec<_>>(), expected);
+        let mut backward = Vec::new();
+        let mut h = list.last();
+        while let Some(x) = h {
+            backward.push(x);
+            h = list.prev(x);
+        }
+        backward.reverse();
+        assert_eq!(backward, expected);
+        assert_eq!(list.first(), expected.first().copied());
+        assert_eq!(list.last(), expected.last().copied());
+        let labels = expected
+            .iter()
+            .map(|&h| list.nodes[h + 1].label)
+            .collect::<Vec<_>>();
+        assert!(labels.windows(2).all(|w| w[0] < w[1]));
+        assert!(labels.iter().all(|&l| 0 < l && l < UNIVERSE));
+    }
+
+    #[test]
+    fn test_random() {
+        let mut rng = StdRng::seed_from_u64(42);
+        for _ in 0..100 {
+            let mut list = OrderMaintenance::new();
+            let mut expected = Vec::<usize>::new();
+            let mut deleted = Vec::new();
+            for _ in 0..300 {
+                match rng.gen_range(0..6) {
+                    0 => {
+                        let h = list.insert_front();
+                        expected.insert(0, h);
+                    }
+                    1 => {
+                        let h = list.insert_back();
+                        expected.push(h);
+                    }
+                    2 | 3 if !expected.is_empty() => {
+                        let i = rng.gen_range(0..expected.len());
+                        if rng.gen_bool(0.5) {
+                            let h = list.insert_after(expected[i]);
+                            expected.insert(i + 1, h);
+                        } else {
+                            let h = list.insert_before(expected[i]);
+                            expected.insert(i, h);
+                        }
+                    }
+                    4 if !expected.is_empty() => {
+                        let i = rng.gen_range(0..expected.len());
+                        let h = expected.remove(i);
+                        list.delete(h);
+                        deleted.push(h);
+                    }
+                    _ if !expected.is_empty() => {
+                        let i = rng.gen_range(0..expected.len());
+                        let j = rng.gen_range(0..expected.len());
+                        assert_eq!(list.order(expected[i], expected[j]), i.cmp(&j));
+                        assert_eq!(list.is_before(expected[i], expected[j]), i < j);
+                        assert_eq!(list.next(expected[i]), expected.get(i + 1).copied());
+                    }
+                    _ => {}
+                }
+                assert!(deleted.iter().all(|&h| !list.contains(h)));
+            }
+            validate(&list, &expected);
+        }
+    }
+
+    #[test]
+    fn test_dense_insertion() {
+        // Inserting repeatedly at one point runs out of labels there quickly.
+        let mut list = OrderMaintenance::new();
+        let a = list.insert_back();
+        let b = list.insert_back();
+        let mut expected = vec![a, b];
+        for _ in 0..20_000 {
+            let h = list.insert_after(a);
+            expected.insert(1, h);
+        }
+        for _ in 0..20_000 {
+            let h = list.insert_before(b);
+            expected.insert(expected.len() - 1, h);
+        }
+        validate(&list, &expected);
+
+        // Handles are the insertion numbers, and survive every relabeling.
+        let mut list = OrderMaintenance::new();
+        let mut expected = Vec::new();
+        for i in 0..20_000 {
+            assert_eq!(list.insert_front(), i);
+            expected.insert(0, i);
+        }
+        validate(&list, &expected);
+        for i in 0..20_000 {
+            assert_eq!(list.order(i, 0), 0.cmp(&i));
+        }
+    }
+
+    #[test]
+    fn test_random_dense() {
+        let mut rng = StdRng::seed_from_u64(42);
+        let mut list = OrderMaintenance::new();
+        let mut expected = vec![list.insert_back()];
+        for _ in 0..20_000 {
+            // Insert mostly near the front to relabel overlapping windows.
+            let i = rng.gen_range(0..expected.len().min(8));
+            if rng.gen_range(0..10) == 0 && expected.len() > 1 {
+                list.delete(expected.remove(i));
+            } else {
+                let h = list.insert_after(expected[i]);
+                expected.insert(i + 1, h);
+            }
+        }
+        validate(&list, &expected);
+    }
+
+    #[test]
+    #[should_panic(expected = "0 is not an element")]
+    fn test_deleted_handle() {
+        let mut list = OrderMaintenance::new();
+        let a = list.insert_back();
+        let b = list.insert_back();
+        list.delete(a);
+        list.order(a, b);
+    }
+}
@@ -0,0 +1,175 @@
+//! Binary searches many queries over a shared sequence of updates at once.
+//!
+//! The time $t$ means the state after applying the updates $0, 1, \dots, t - 1$. Each pass resets
+//! the state, applies all the updates in order, and checks each query at the midpoint of its
+//! current range, so $O(\log T)$ passes suffice.
+//!
+//! # Examples
+//!
+//! ```
+//! use parallel_binary_search::parallel_binary_search;
+//! // The updates add 3, 1, 4, 1, 5 to a counter. When does it reach 5, 9 and 100?
+//! let add = [3, 1, 4, 1, 5];
+//! let targets = [5, 9, 100];
+//! let result = parallel_binary_search(
+//!     &mut 0,
+//!     add.len(),
+//!     &[0..6, 0..6, 0..6],
+//!     |sum| *sum = 0,
+//!     |sum, t| *sum += add[t],
+//!     |sum, i| targets[i] <= *sum,
+//! );
+//! assert_eq!(result, vec![Some(3), Some(4), None]);
+//! ```
+use std::ops::Range;
+
+/// Returns the first time in `ranges[i]` at which `check(i)` holds for each query $i$.
+///
+/// # Requirements
+///
+/// - `ranges[i]` is contained in $\[0, T\]$.
+/// - `check(i)` is monotone in time: once it holds, it holds until the end of `ranges[i]`.
+/// - `reset` sets the state to the time $0$, and `apply(t)` advances it from $t$ to $t + 1$.
+///
+/// # Complexity
+///
+/// $O(\log T)$ calls of `reset`, $O(T \log T)$ calls of `apply` and $O(Q \log T)$ calls of `check`
+pub fn parallel_binary_search<S>(
+    state: &mut S,
+    updates: usize,
+    ranges: &[Range<usize>],
+    mut reset: impl FnMut(&mut S),
+    mut apply: impl FnMut(&mut S, usize),
+    mut check: impl FnMut(&mut S, usize) -> bool,
+) -> Vec<Option<usize>> {
+    for (i, range) in ranges.iter().enumerate() {
+        assert!(
+            range.start <= range.end && range.end <= updates + 1,
+            "query {} has the range {}..{} out of range for {} updates",
+            i,
+            range.start,
+            range.end,
+            updates
+        );
+    }
+    // The answer of the query $i$ is in $\[lo _ i, hi _ i\]$, where $hi _ i$ means `None`.
+    let mut lo = ranges.iter().map(|range| range.start).collect::<Vec<_>>();
+    let mut hi = ranges.iter().map(|range| range.end).collect::<Vec<_>>();
+    let mut buckets = vec![Vec::new(); updates + 1];
+    loop {
+        let mut active = false;
+        for i in 0..ranges.len() {
+            if lo[i] < hi[i] {
+                buckets[(lo[i] + hi[i]) / 2].push(i);
+                active = true;
+            }
+        }
+        if !active {
+            break;
+        }
+        reset(state);
+        for (t, bucket) in buckets.iter_mut().enumerate() {
+            for i in bucket.drain(..) {
+                if check(state, i) {
+                    hi[i] = t;
+                } else {
+                    lo[i] = t + 1;
+                }
+            }
+            if t < updates {
+                apply(state, t);
+            }
+        }
+    }
+    lo.into_iter()
+        .zip(ranges)
+        .map(|(t, range)| (t < range.end).then_some(t))
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rand::rngs::StdRng;
+    use rand::Rng;
+    use rand::SeedableRng;
+    use std::cell::Cell;
+    use union_find::UnionFind;
+
+    fn connected_after(n: usize, edges: &[(usize, usize)], t: usize, u: usize, v: usize) -> bool {
+        let mut uf = <UnionFind>::new(n);
+        for &(a, b) in &edges[..t] {
+            uf.union(a, b);
+        }
+        uf.same(u, v)
+    }
+
+    #[test]
+    fn test_connectivity() {
+        let mut rng = StdRng::seed_from_u64(42);
+        for _ in 0..200 {
+            let n = rng.gen_range(1..=10);
+            let m: usize = rng.gen_range(0..=20);
+            let q = rng.gen_range(0..=20);
+            let edges = (0..m)
+                .map(|_| (rng.gen_range(0..n), rng.gen_range(0..n)))
+                .collect::<Vec<_>>();
+            let queries = (0..q)
+                .map(|_| (rng.gen_range(0..n), rng.gen_range(0..n)))
+                .collect::<Vec<_>>();
+            let ranges = (0..q)
+                .map(|_| {
+                    let l = rng.gen_range(0..=m + 1);
+                    let r = rng.gen_range(0..=m + 1);
+                    l.min(r)..l.max(r)
+                })
+                .collect::<Vec<_>>();
+            let resets = Cell::new(0);
+            let result = parallel_binary_search(
+                &mut <UnionFind>::new(n),
+                m,
+                &ranges,
+                |uf| {
+                    resets.set(resets.get() + 1);
+                    *uf = UnionFind::new(n);
+                },
+                |uf, t| {
+                    uf.union(edges[t].0, edges[t].1);
+                },
+                |uf, i| uf.same(queries[i].0, queries[i].1),
+            );
+            assert!(resets.get() <= (usize::BITS - (m + 1).leading_zeros()) as usize + 1);
+            for ((&(u, v), range), result) in queries.iter().zip(&ranges).zip(result) {
+                // Binary search with full rebuilds.
+                let mut lo = range.start;
+                let mut hi = range.end;
+                while lo < hi {
+                    let mid = (lo + hi) / 2;
+                    if connected_after(n, &edges, mid, u, v) {
+                        hi = mid;
+                    } else {
+                        lo = mid + 1;
+                    }
+                }
+                let expected = (lo < range.end).then_some(lo);
+                assert_eq!(result, expected);
+                if let Some(t) = result {
+                    assert!(connected_after(n, &edges, t, u, v));
+                    assert!(t == range.start || !connected_after(n, &edges, t - 1, u, v));
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn test_no_queries() {
+        let result = parallel_binary_search(&mut (), 10, &[], |_| panic!(), |_, _| {}, |_, _| true);
+        assert!(result.is_empty());
+    }
+
+    #[test]
+    #[should_panic(expected = "query 1 has the range 2..5 out of range for 3 updates")]
+    fn test_out_of_range() {
+        parallel_binary_search(&mut (), 3, &[0..4, 2..5], |_| {}, |_, _| {}, |_, _| true);
+    }
+}
@@ -0,0 +1,455 @@
+//! A persistent ordered multiset on an arena.
+//!
+//! The nodes are immutable once created and live in a single arena, and a version is identified
+//! by the index of its root, so an update copies only the nodes on the path it touches and
+//! returns the new root. [`EMPTY`] is the empty version.
+//!
+//! Stored priorities would break the balance once a version is merged with another sharing its
+//! nodes, so [`PersistentTreap::merge`] instead chooses the root of the left side with the
+//! probability proportional to its size, which makes every tree a random binary search tree.
+//!
+//! # Examples
+//!
+//! ```
+//! use persistent_treap::PersistentTreap;
+//! use persistent_treap::EMPTY;
+//! let mut treap = PersistentTreap::new();
+//! let v1 = treap.insert(EMPTY, 30);
+//! let v2 = treap.insert(v1, 10);
+//! let v3 = treap.insert(v2, 20);
+//! let v4 = treap.erase(v3, &30);
+//! assert_eq!(treap.to_vec(v3), vec![10, 20, 30]);
+//! assert_eq!(treap.to_vec(v4), vec![10, 20]);
+//! assert_eq!(treap.kth(v3, 1), Some(&20));
+//! assert_eq!(treap.count_less(v1, &30), 0);
+//! assert_eq!(treap.count_less(v3, &30), 2);
+//! ```
+use std::cmp::Ordering;
+
+/// The root of the empty version.
+pub const EMPTY: u32 = u32::MAX;
+
+#[derive(Debug, Clone)]
+struct Node<K> {
+    key: K,
+    left: u32,
+    right: u32,
+    len: u32,
+}
+
+/// An arena of the versions of a persistent ordered multiset.
+#[derive(Debug, Clone)]
+pub struct PersistentTreap<K> {
+    nodes: Vec<Node<K>>,
+    state: u64,
+}
+impl<K: Ord + Clone> Default for PersistentTreap<K> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+impl<K: Ord + Clone> PersistentTreap<K> {
+    /// Returns an empty arena with a seed from [`salt::next_seed`].
+    pub fn new() -> Self {
+        Self::with_seed(salt::next_seed())
+    }
+
+    /// Returns an empty arena with the seed `seed`.
+    pub fn with_seed(seed: u64) -> Self {
+        Self {
+            nodes: Vec::new(),
+            state: seed,
+        }
+    }
+
+    /// Returns the number of the nodes ever created.
+    pub fn node_count(&self) -> usize {
+        self.nodes.len()
+    }
+
+    /// Returns the number of the elements of the version `root`.
+    pub fn len(&self, root: u32) -> usize {
+        self.node_len(root) as usize
+    }
+
+    /// Returns `true` if the version `root` is empty.
+    pub fn is_empty(&self, root: u32) -> bool {
+        root == EMPTY
+    }
+
+    /// Returns the version `root` with `key` added.
+    ///
+    /// # Complexity
+    ///
+    /// Expected $O(\log n)$ time and new nodes
+    pub fn insert(&mut self, root: u32, key: K) -> u32 {
+        let [left, right] = self.split(root, &|x| x < &key);
+        let node = self.push(Node {
+            key,
+            left: EMPTY,
+            right: EMPTY,
+            len: 1,
+        });
+        let left = self.merge_unchecked(left, node);
+        self.merge_unchecked(left, right)
+    }
+
+    /// Returns the version `root` with one occurrence of `key` removed, or `root` itself if it
+    /// does not contain `key`.
+    ///
+    /// # Complexity
+    ///
+    /// Expected $O(\log n)$ time and new nodes
+    pub fn erase(&mut self, root: u32, key: &K) -> u32 {
+        if !self.contains(root, key) {
+            return root;
+        }
+        let [left, right] = self.split(root, &|x| x < key);
+        let right = self.remove_first(right);
+        self.merge_unchecked(left, right)
+    }
+
+    /// Returns `true` if the version `root` contains `key`.
+    pub fn contains(&self, mut root: u32, key: &K) -> bool {
+        while root != EMPTY {
+            let node = &self.nodes[root as usize];
+            root = match key.cmp(&node.key) {
+                Ordering::Less => node.left,
+                Ordering::Equal => return true,
+                Ordering::Greater => node.right,
+            };
+        }
+        false
+    }
+
+    /// Returns the $k$-th smallest element (0-based) of the version `root`.
+    pub fn kth(&self, mut root: u32, mut k: usize) -> Option<&K> {
+        if self.len(root) <= k {
+            return None;
+        }
+        loop {
+            let node = &self.nodes[root as usize];
+            let left = self.len(node.left);
+            match k.cmp(&left) {
+                Ordering::Less => root = node.left,
+                Ordering::Equal => return Some(&node.key),
+                Ordering::Greater => {
+                    k -= left + 1;
+                    root = node.right;
+                }
+            }
+        }
+    }
+
+    /// Returns the number of the elements less than `key` in the version `root`.
+    pub fn count_less(&self, root: u32, key: &K) -> usize {
+        self.count_while(root, |x| x < key)
+    }
+
+    /// Returns the number of the elements in $\[l, r)$ in the version `root`.
+    pub fn count_range(&self, root: u32, l: &K, r: &K) -> usize {
+        self.count_less(root, r)
+            .saturating_sub(self.count_less(root, l))
+    }
+
+    /// Returns the version containing the elements of both `a` and `b`.
+    ///
+    /// # Panics
+    ///
+    /// If the ranges of `a` and `b` overlap, i.e. neither of them is entirely less than or equal
+    /// to the other.
+    ///
+    /// # Complexity
+    ///
+    /// Expected $O(\log n)$ time and new nodes
+    pub fn merge(&mut self, a: u32, b: u32) -> u32 {
+        if self.is_empty(a) || self.is_empty(b) {
+            return self.merge_unchecked(a, b);
+        }
+        let (a_min, a_max) = (self.first(a), self.last(a));
+        let (b_min, b_max) = (self.first(b), self.last(b));
+        if a_max <= b_min {
+            self.merge_unchecked(a, b)
+        } else if b_max <= a_min {
+            self.merge_unchecked(b, a)
+        } else {
+            panic!("the versions to merge overlap");
+        }
+    }
+
+    /// Returns the elements of the version `root` in the ascending order.
+    pub fn to_vec(&self, root: u32) -> Vec<K> {
+        let mut result = Vec::with_capacity(self.len(root));
+        let mut stack = Vec::new();
+        let mut x = root;
+        loop {
+            while x != EMPTY {
+                stack.push(x);
+                x = self.nodes[x as usize].left;
+            }
+            let Some(y) = stack.pop() else {
+                break;
+            };
+            result.push(self.nodes[y as usize].key.clone());
+            x = self.nodes[y as usize].right;
+        }
+        result
+    }
+
+    fn first(&self, root: u32) -> &K {
+        self.kth(root, 0).unwrap()
+    }
+
+    fn last(&self, root: u32) -> &K {
+        self.kth(root, self.len(root) - 1).unwrap()
+    }
+
+    fn count_while(&self, mut root: u32, pred: impl Fn(&K) -> bool) -> usize {
+        let mut count = 0;
+        while root != EMPTY {
+            let node = &self.nodes[root as usize];
+            if pred(&node.key) {
+                count += self.len(node.left) + 1;
+                root = node.right;
+            } else {
+                root = node.left;
+            }
+        }
+        count
+    }
+
+    fn node_len(&self, root: u32) -> u32 {
+        if root == EMPTY {
+            0
+        } else {
+            self.nodes[root as usize].len
+        }
+    }
+
+    fn push(&mut self, node: Node<K>) -> u32 {
+        assert!(self.nodes.len() < EMPTY as usize, "too many nodes");
+        self.nodes.push(node);
+        (self.nodes.len() - 1) as u32
+    }
+
+    // Copies the node `x` with the new children.
+    fn with_children(&mut self, x: u32, left: u32, right: u32) -> u32 {
+        let node = Node {
+            key: self.nodes[x as usize].key.clone(),
+            left,
+            right,
+            len: self.node_len(left) + self.node_len(right) + 1,
+        };
+        self.push(node)
+    }
+
+    // Splits into the elements satisfying `pred` and the others, assuming that `pred` is monotone.
+    fn split(&mut self, root: u32, pred: &impl Fn(&K) -> bool) -> [u32; 2] {
+        if root == EMPTY {
+            return [EMPTY, EMPTY];
+        }
+        let Node { left, right, .. } = self.nodes[root as usize];
+        if pred(&self.nodes[root as usize].key) {
+            let [mid, right] = self.split(right, pred);
+            [self.with_children(root, left, mid), right]
+        } else {
+            let [left, mid] = self.split(left, pred);
+            [left, self.with_children(root, mid, right)]
+        }
+    }
+
+    fn remove_first(&mut self, root: u32) -> u32 {
+        let Node { left, right, .. } = self.nodes[root as usize];
+        if left == EMPTY {
+            right
+        } else {
+            let left = self.remove_first(left);
+            self.with_children(root, left, right)
+        }
+    }
+
+    fn merge_unchecked(&mut self, a: u32, b: u32) -> u32 {
+        if a == EMPTY {
+            return b;
+        }
+        if b == EMPTY {
+            return a;
+        }
+        let (la, lb) = (self.node_len(a) as u64, self.node_len(b) as u64);
+        self.state = splitmix64(self.state);
+        if self.state % (la + lb) < la {
+            let Node { left, right, .. } = self.nodes[a as usize];
+            let right = self.merge_unchecked(right, b);
+            self.with_children(a, left, right)
+        } else {
+            let Node { left, right, .. } = self.nodes[b as usize];
+            let left = self.merge_unchecked(a, left);
+            self.with_children(b, left, right)
+        }
+    }
+}
+
+fn splitmix64(x: u64) -> u64 {
+    let mut x = x.wrapping_add(0x9e37_79b9_7f4a_7c15);
+    x = (x ^ (x >> 30)).wrapping_mul(0xbf58_476d_1ce4_e5b9);
+    x = (x ^ (x >> 27)).wrapping_mul(0x94d0_49bb_1331_11eb);
+    x ^ (x >> 31)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rand::rngs::StdRng;
+    use rand::Rng;
+    use rand::SeedableRng;
+
+    fn depth(treap: &PersistentTreap<u32>, root: u32) -> usize {
+        if root == EMPTY {
+            0
+        } else {
+            let node = &treap.nodes[root as usize];
+            1 + depth(treap, node.left).max(depth(treap, node.right))
+        }
+    }
+
+    #[test]
+    fn test_versions_random() {
+        let mut rng = StdRng::seed_from_u64(42);
+        for _ in 0..20 {
+            let mut treap = PersistentTreap::with_seed(rng.gen());
+            let mut versions = vec![(EMPTY, Vec::<u32>::new())];
+            let max_key = rng.gen_range(1..=100);
+            for _ in 0..500 {
+                let (root, expected) = versions[rng.gen_range(0..versions.len())].clone();
+                let (root, expected) = match rng.gen_range(0..4) {
+                    0 | 1 => {
+                        let key = rng.gen_range(0..max_key);
+                        let mut expected = expected;
+                        let i = expected.partition_point(|&x| x < key);
+                        expected.insert(i, key);
+                        (treap.insert(root, key), expected)
+                    }
+                    2 => {
+                        let key = rng.gen_range(0..max_key);
+                        let mut expected = expected;
+                        if let Ok(i) = expected.binary_search(&key) {
+                            expected.remove(i);
+                        }
+                        (treap.erase(root, &key), expected)
+                    }
+                    3 => {
+                        let (other, other_expected) =
+                            versions[rng.gen_range(0..versions.len())].clone();
+                        let other_max = other_expected.last().copied().unwrap_or(0);
+                        let min = expected.first().copied().unwrap_or(u32::MAX);
+                        // If the other version overlaps, uses its elements not greater than the
+                        // minimum instead.
+                        let (other, other_expected) = if other_max <= min {
+                            (other, other_expected)
+                        } else {
+                            let k = other_expected.partition_point(|&x| x <= min);
+                            let mut prefix = EMPTY;
+                            for &x in &other_expected[..k] {
+                                prefix = treap.insert(prefix, x);
+                            }
+                            (prefix, other_expected[..k].to_vec())
+                        };
+                        let merged = if rng.gen_bool(0.5) {
+                            treap.merge(other, root)
+                        } else {
+                            treap.merge(root, other)
+                        };
+                        let mut expected_merged = other_expected;
+                        expected_merged.extend(expected);
+                        (merged, expected_merged)
+                    }
+                    _ => unreachable!(),
+                };
+                assert_eq!(treap.len(root), expected.len());
+                for _ in 0..4 {
+                    let k = rng.gen_range(0..=expected.len());
+                    assert_eq!(treap.kth(root, k), expected.get(k));
+                    let key = rng.gen_range(0..=max_key);
+                    let less = expected.partition_point(|&x| x < key);
+                    assert_eq!(treap.count_less(root, &key), less);
+                    assert_eq!(
+                        treap.contains(root, &key),
+                        expected.binary_search(&key).is_ok()
+                    );
+                    let r = rng.gen_range(0..=max_key);
+                    let expected_range = expected.iter().filter(|&&x| key <= x && x < r).count();
+                    assert_eq!(treap.count_range(root, &key, &r), expected_range);
+                }
+                versions.push((root, expected));
+            }
+            for (root, expected) in &versions {
+                assert_eq!(&treap.to_vec(*root), expected);
+            }
+        }
+    }
+
+    #[test]
+    fn test_merge_shared_versions() {
+        // Merging a version with its own copies keeps the depth logarithmic.
+        let mut treap = PersistentTreap::with_seed(42);
+        let mut root = treap.insert(EMPTY, 0);
+        for _ in 0..16 {
+            root = treap.merge(root, root);
+        }
+        assert_eq!(treap.len(root), 1 << 16);
+        assert_eq!(treap.count_less(root, &1), 1 << 16);
+        assert!(depth(&treap, root) <= 64, "depth = {}", depth(&treap, root));
+    }
+
+    #[test]
+    fn test_node_count_per_update() {
+        let mut rng = StdRng::seed_from_u64(42);
+        let mut treap = PersistentTreap::with_seed(42);
+        let n = 100_000;
+        let mut root = EMPTY;
+        let mut versions = Vec::new();
+        let mut max_nodes = 0;
+        for i in 0..n {
+            let before = treap.node_count();
+            root = if rng.gen_ratio(1, 4) {
+                let key = rng.gen_range(0..i + 1);
+                treap.erase(root, &key)
+            } else {
+                treap.insert(root, rng.gen_range(0..n))
+            };
+            max_nodes = max_nodes.max(treap.node_count() - before);
+            versions.push(root);
+        }
+        // A copy of the whole tree would be $\Theta(n)$ nodes per update.
+        let average = treap.node_count() as f64 / n as f64;
+        assert!(average < 60.0, "average = {}", average);
+        assert!(max_nodes < 300, "max_nodes = {}", max_nodes);
+        assert!(depth(&treap, root) < 100);
+        assert!(treap.len(versions[n as usize / 2]) <= n as usize / 2 + 1);
+    }
+
+    #[test]
+    fn test_salt() {
+        let build = || {
+            let mut treap = PersistentTreap::new();
+            let root = (0..100).fold(EMPTY, |root, x| treap.insert(root, x));
+            format!("{:?}", (treap, root))
+        };
+        salt::set_seed(42);
+        let a = build();
+        salt::set_seed(42);
+        assert_eq!(build(), a);
+        salt::set_seed(43);
+        assert_ne!(build(), a);
+    }
+
+    #[test]
+    #[should_panic(expected = "the versions to merge overlap")]
+    fn test_merge_overlap() {
+        let mut treap = PersistentTreap::new();
+        let a = treap.insert(EMPTY, 1);
+        let a = treap.insert(a, 3);
+        let b = treap.insert(EMPTY, 2);
+        treap.merge(a, b);
+    }
+}
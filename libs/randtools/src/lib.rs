@@ -147,13 +147,212 @@ impl Distribution<Vec<(usize, usize)>> for SimpleDigraphEdges {
     }
 }
 
+/// A boundary-biased sub-range: half the time uniform as [`SubRange`], and otherwise empty, full,
+/// a prefix, a suffix or a single element.
+#[derive(Debug)]
+pub struct BiasedSubRange(pub Range<usize>);
+impl Distribution<Range<usize>> for BiasedSubRange {
+    fn sample<R: Rng + ?Sized>(&self, rng: &mut R) -> Range<usize> {
+        let Range { start, end } = self.0;
+        assert!(start <= end);
+        if rng.gen() {
+            return rng.sample(SubRange(start..end));
+        }
+        let mid = rng.gen_range(start..=end);
+        match rng.gen_range(0..5) {
+            0 => mid..mid,
+            1 => start..end,
+            2 => start..mid,
+            3 => mid..end,
+            4 if start < end => {
+                let i = rng.gen_range(start..end);
+                i..i + 1
+            }
+            _ => mid..mid,
+        }
+    }
+}
+
+/// The shape of [`ShapedTree`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Shape {
+    /// Uniform over the labeled trees, with the diameter about $\sqrt n$.
+    Uniform,
+    /// A path.
+    Path,
+    /// A star.
+    Star,
+    /// A path with leaves attached.
+    Caterpillar,
+    /// A complete binary tree.
+    Binary,
+}
+impl Shape {
+    pub const ALL: [Shape; 5] = [
+        Shape::Uniform,
+        Shape::Path,
+        Shape::Star,
+        Shape::Caterpillar,
+        Shape::Binary,
+    ];
+}
+impl Distribution<Shape> for rand::distributions::Standard {
+    fn sample<R: Rng + ?Sized>(&self, rng: &mut R) -> Shape {
+        Shape::ALL[rng.gen_range(0..Shape::ALL.len())]
+    }
+}
+
+/// A tree of the specified shape with randomly relabeled vertices, as adjacency lists.
+#[derive(Debug)]
+pub struct ShapedTree(pub usize, pub Shape);
+impl Distribution<Vec<Vec<usize>>> for ShapedTree {
+    fn sample<R: Rng + ?Sized>(&self, rng: &mut R) -> Vec<Vec<usize>> {
+        let &Self(n, shape) = self;
+        assert!(1 <= n);
+        if shape == Shape::Uniform {
+            return rng.sample(Tree(n));
+        }
+        let spine = rng.gen_range(1..=n);
+        let parent = |rng: &mut R, i: usize| match shape {
+            Shape::Uniform => unreachable!(),
+            Shape::Path => i - 1,
+            Shape::Star => 0,
+            Shape::Caterpillar if i < spine => i - 1,
+            Shape::Caterpillar => rng.gen_range(0..spine),
+            Shape::Binary => (i - 1) / 2,
+        };
+        let mut label = (0..n).collect::<Vec<_>>();
+        label.shuffle(rng);
+        let mut g = vec![Vec::new(); n];
+        for i in 1..n {
+            let (u, v) = (label[parent(rng, i)], label[i]);
+            g[u].push(v);
+            g[v].push(u);
+        }
+        for adj in &mut g {
+            adj.shuffle(rng);
+        }
+        g
+    }
+}
+
+/// Undirected edges $(u, v)$ of $n$ vertices in a random order.
+///
+/// If `connected`, they contain a spanning tree, and if `simple`, there are no self-loops or
+/// parallel edges.
+#[derive(Debug)]
+pub struct GraphEdges {
+    pub n: usize,
+    pub m: usize,
+    pub connected: bool,
+    pub simple: bool,
+}
+impl Distribution<Vec<(usize, usize)>> for GraphEdges {
+    fn sample<R: Rng + ?Sized>(&self, rng: &mut R) -> Vec<(usize, usize)> {
+        let &Self {
+            n,
+            m,
+            connected,
+            simple,
+        } = self;
+        assert!(!connected || 1 <= n && n - 1 <= m);
+        assert!(!simple || m <= n * n.saturating_sub(1) / 2);
+        assert!(simple || m == 0 || 1 <= n);
+        let mut edges = Vec::with_capacity(m);
+        let mut set = HashSet::new();
+        if connected {
+            let g = rng.sample(Tree(n));
+            for (u, adj) in g.iter().enumerate() {
+                for &v in adj.iter().filter(|&&v| u < v) {
+                    edges.push((u, v));
+                    set.insert((u, v));
+                }
+            }
+        }
+        while edges.len() < m {
+            let (u, v) = if simple {
+                rng.sample(DistinctTwo(0..n))
+            } else {
+                (rng.gen_range(0..n), rng.gen_range(0..n))
+            };
+            if !simple || set.insert((u.min(v), u.max(v))) {
+                edges.push((u, v));
+            }
+        }
+        edges.shuffle(rng);
+        for (u, v) in &mut edges {
+            if rng.gen() {
+                mem::swap(u, v);
+            }
+        }
+        edges
+    }
+}
+
+/// A string of `b'a'`, `b'b'`, ... with the alphabet size `alphabet`, where each step copies an
+/// earlier substring with the probability `repetitiveness` and appends a random letter otherwise.
+#[derive(Debug)]
+pub struct RepetitiveString {
+    pub len: usize,
+    pub alphabet: u8,
+    pub repetitiveness: f64,
+}
+impl Distribution<Vec<u8>> for RepetitiveString {
+    fn sample<R: Rng + ?Sized>(&self, rng: &mut R) -> Vec<u8> {
+        let &Self {
+            len,
+            alphabet,
+            repetitiveness,
+        } = self;
+        assert!((1..=26).contains(&alphabet));
+        let mut s = Vec::with_capacity(len);
+        while s.len() < len {
+            if !s.is_empty() && rng.gen_bool(repetitiveness) {
+                let start = rng.gen_range(0..s.len());
+                let copy = rng.gen_range(1..=s.len() - start).min(len - s.len());
+                for i in start..start + copy {
+                    s.push(s[i]);
+                }
+            } else {
+                s.push(b'a' + rng.gen_range(0..alphabet));
+            }
+        }
+        s
+    }
+}
+
+/// A permutation of $0, 1, \dots, n - 1$ sorted except for $k$ random swaps of adjacent
+/// elements.
+#[derive(Debug)]
+pub struct NearlySorted(pub usize, pub usize);
+impl Distribution<Vec<usize>> for NearlySorted {
+    fn sample<R: Rng + ?Sized>(&self, rng: &mut R) -> Vec<usize> {
+        let &Self(n, k) = self;
+        let mut a = (0..n).collect::<Vec<_>>();
+        if n >= 2 {
+            for _ in 0..k {
+                let i = rng.gen_range(0..n - 1);
+                a.swap(i, i + 1);
+            }
+        }
+        a
+    }
+}
+
 #[cfg(test)]
 mod tests {
+    use super::BiasedSubRange;
+    use super::GraphEdges;
     use super::LogUniform;
+    use super::NearlySorted;
     use super::NonEmptySubRange;
+    use super::RepetitiveString;
+    use super::Shape;
+    use super::ShapedTree;
     use super::SubRange;
     use super::Tree;
     use rand::prelude::*;
+    use std::collections::HashSet;
     use std::ops::Range;
     mod algo;
 
@@ -171,7 +370,7 @@ mod tests {
     fn test_open() {
         let mut rng = StdRng::seed_from_u64(42);
         for _ in 0..2000 {
-            let d = rng.gen_range(2..8);
+            let d: usize = rng.gen_range(2..8);
             let l = rng.gen_range(0..40);
             let r = l + d;
             let Range { start, end } = rng.sample(SubRange(l..r));
@@ -190,4 +389,123 @@ mod tests {
             assert!(algo::is_tree(&g));
         }
     }
+
+    #[test]
+    fn test_biased_sub_range() {
+        let mut rng = StdRng::seed_from_u64(42);
+        let mut full = 0;
+        let mut empty = 0;
+        for _ in 0..2000 {
+            let l: usize = rng.gen_range(0..10);
+            let r = l + rng.gen_range(0..10_usize);
+            let range = rng.sample(BiasedSubRange(l..r));
+            assert!(l <= range.start && range.start <= range.end && range.end <= r);
+            full += usize::from(range == (l..r));
+            empty += usize::from(range.is_empty());
+        }
+        assert!(full > 200 && empty > 200);
+    }
+
+    #[test]
+    fn test_shaped_tree() {
+        let mut rng = StdRng::seed_from_u64(42);
+        for shape in Shape::ALL {
+            for n in (1..30).chain([100, 1000]) {
+                let g = rng.sample(ShapedTree(n, shape));
+                assert!(algo::is_tree(&g));
+                let max_degree = g.iter().map(Vec::len).max().unwrap();
+                match shape {
+                    Shape::Path => assert!(max_degree <= 2),
+                    Shape::Star => assert_eq!(max_degree, n - 1),
+                    Shape::Binary => assert!(max_degree <= 3),
+                    Shape::Uniform | Shape::Caterpillar => {}
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn test_graph_edges() {
+        let mut rng = StdRng::seed_from_u64(42);
+        for _ in 0..2000 {
+            let n: usize = rng.gen_range(1..8);
+            let connected = rng.gen();
+            let simple = rng.gen();
+            let min = if connected { n - 1 } else { 0 };
+            let max = if simple { n * (n - 1) / 2 } else { 3 * n };
+            let m = rng.gen_range(min..=max.max(min));
+            let edges = rng.sample(GraphEdges {
+                n,
+                m,
+                connected,
+                simple,
+            });
+            assert_eq!(edges.len(), m);
+            assert!(edges.iter().all(|&(u, v)| u < n && v < n));
+            if simple {
+                let set = edges
+                    .iter()
+                    .map(|&(u, v)| (u.min(v), u.max(v)))
+                    .collect::<HashSet<_>>();
+                assert_eq!(set.len(), m);
+                assert!(edges.iter().all(|&(u, v)| u != v));
+            }
+            if connected {
+                let mut g = vec![Vec::new(); n];
+                for &(u, v) in &edges {
+                    g[u].push(v);
+                    g[v].push(u);
+                }
+                let mut seen = vec![false; n];
+                let mut stack = vec![0];
+                seen[0] = true;
+                while let Some(u) = stack.pop() {
+                    for &v in &g[u] {
+                        if !std::mem::replace(&mut seen[v], true) {
+                            stack.push(v);
+                        }
+                    }
+                }
+                assert!(seen.iter().all(|&b| b));
+            }
+        }
+    }
+
+    #[test]
+    fn test_repetitive_string() {
+        let mut rng = StdRng::seed_from_u64(42);
+        for alphabet in 1..=26 {
+            let s = rng.sample(RepetitiveString {
+                len: 1000,
+                alphabet,
+                repetitiveness: 0.5,
+            });
+            assert_eq!(s.len(), 1000);
+            assert!(s.iter().all(|&c| (b'a'..b'a' + alphabet).contains(&c)));
+        }
+        // Few distinct substrings of length 20.
+        let s = rng.sample(RepetitiveString {
+            len: 1000,
+            alphabet: 26,
+            repetitiveness: 0.9,
+        });
+        assert!(s.windows(20).collect::<HashSet<_>>().len() < 900);
+    }
+
+    #[test]
+    fn test_nearly_sorted() {
+        let mut rng = StdRng::seed_from_u64(42);
+        for n in 0..30 {
+            let k: usize = rng.gen_range(0..5);
+            let a = rng.sample(NearlySorted(n, k));
+            let mut sorted = a.clone();
+            sorted.sort_unstable();
+            assert_eq!(sorted, (0..n).collect::<Vec<_>>());
+            let inversions = (0..n)
+                .flat_map(|i| (i + 1..n).map(move |j| (i, j)))
+                .filter(|&(i, j)| a[i] > a[j])
+                .count();
+            assert!(inversions <= k);
+        }
+    }
 }
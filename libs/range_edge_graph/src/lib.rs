@@ -0,0 +1,203 @@
+//! Graphs with edges between a vertex and a range of vertices.
+//!
+//! An edge from a vertex $u$ to every vertex in $\[l, r)$ (or from every vertex in $\[l, r)$ to
+//! $v$) is reduced to $O(\log n)$ ordinary edges through auxiliary vertices of two segment trees:
+//!
+//! - The *down tree* has zero-weight edges from each node to its children. A vertex-to-range edge
+//!   goes from $u$ to the canonical nodes of $\[l, r)$, and then flows down to the leaves.
+//! - The *up tree* has zero-weight edges from each node to its parent. A range-to-vertex edge goes
+//!   from the canonical nodes of $\[l, r)$ to $v$, and the leaves flow up into them.
+//!
+//! The leaves of both trees are the original vertices themselves, so the original vertex $i$ keeps
+//! the id $i$ in the built graph, and the auxiliary vertices follow after them. The graph is built
+//! as an adjacency list, the form that the shortest path crates such as `dijkstra` take.
+//!
+//! # Examples
+//!
+//! ```
+//! use range_edge_graph::RangeEdgeGraphBuilder;
+//! let mut builder = RangeEdgeGraphBuilder::<u32>::new(5);
+//! builder.add_edge_vertex_to_range(0, 2..5, 3);
+//! builder.add_edge_range_to_vertex(3..4, 1, 1);
+//! let g = builder.build();
+//! assert_eq!(g.len(), 3 * 5 - 2);
+//! let dist = dijkstra::dijkstra(&g, 0);
+//! assert_eq!(&dist[..5], &[Some(0), Some(4), Some(3), Some(3), Some(3)]);
+//! ```
+use std::ops::Range;
+use std::ops::RangeBounds;
+
+/// A builder of a graph with range edges.
+#[derive(Debug, Clone, PartialEq)]
+pub struct RangeEdgeGraphBuilder<T> {
+    n: usize,
+    graph: Vec<Vec<(usize, T)>>,
+}
+impl<T: Copy + Default> RangeEdgeGraphBuilder<T> {
+    /// Constructs a builder with $n$ original vertices.
+    ///
+    /// The internal edges of the segment trees have the weight `T::default()`, which must be zero.
+    pub fn new(n: usize) -> Self {
+        let mut graph = vec![Vec::new(); if n == 0 { 0 } else { 3 * n - 2 }];
+        for k in 1..n {
+            for c in [2 * k, 2 * k + 1] {
+                graph[down(n, k)].push((down(n, c), T::default()));
+                graph[up(n, c)].push((up(n, k), T::default()));
+            }
+        }
+        Self { n, graph }
+    }
+
+    /// Returns the number of the original vertices.
+    pub fn original_len(&self) -> usize {
+        self.n
+    }
+
+    /// Returns the number of all the vertices including the auxiliary ones.
+    pub fn len(&self) -> usize {
+        self.graph.len()
+    }
+
+    /// Returns `true` if the graph has no vertices.
+    pub fn is_empty(&self) -> bool {
+        self.graph.is_empty()
+    }
+
+    /// Adds an edge $u \to v$.
+    pub fn add_edge(&mut self, u: usize, v: usize, w: T) {
+        assert!(u < self.n && v < self.n, "vertex out of range");
+        self.graph[u].push((v, w));
+    }
+
+    /// Adds edges $u \to v$ for every $v$ in `range`.
+    pub fn add_edge_vertex_to_range(&mut self, u: usize, range: impl RangeBounds<usize>, w: T) {
+        assert!(u < self.n, "vertex out of range");
+        let n = self.n;
+        for k in self.canonical_nodes(range) {
+            self.graph[u].push((down(n, k), w));
+        }
+    }
+
+    /// Adds edges $u \to v$ for every $u$ in `range`.
+    pub fn add_edge_range_to_vertex(&mut self, range: impl RangeBounds<usize>, v: usize, w: T) {
+        assert!(v < self.n, "vertex out of range");
+        let n = self.n;
+        for k in self.canonical_nodes(range) {
+            self.graph[up(n, k)].push((v, w));
+        }
+    }
+
+    /// Returns the adjacency list of all the vertices.
+    ///
+    /// The original vertex $i$ has the id $i$, so no mapping of the ids is needed: the distances
+    /// to the original vertices are the first $n$ entries of those by `dijkstra::dijkstra` or the
+    /// like.
+    pub fn build(self) -> Vec<Vec<(usize, T)>> {
+        self.graph
+    }
+
+    fn canonical_nodes(&self, range: impl RangeBounds<usize>) -> Vec<usize> {
+        let Range { mut start, mut end } = open::resolve_range(self.n, range);
+        start += self.n;
+        end += self.n;
+        let mut nodes = Vec::new();
+        while start < end {
+            if start % 2 == 1 {
+                nodes.push(start);
+                start += 1;
+            }
+            if end % 2 == 1 {
+                end -= 1;
+                nodes.push(end);
+            }
+            start /= 2;
+            end /= 2;
+        }
+        nodes
+    }
+}
+
+// The id of the node `k` of the down tree.
+fn down(n: usize, k: usize) -> usize {
+    if n <= k {
+        k - n
+    } else {
+        n + k - 1
+    }
+}
+
+// The id of the node `k` of the up tree.
+fn up(n: usize, k: usize) -> usize {
+    if n <= k {
+        k - n
+    } else {
+        2 * n + k - 2
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use dijkstra::dijkstra;
+    use rand::rngs::StdRng;
+    use rand::Rng;
+    use rand::SeedableRng;
+    use randtools::BiasedSubRange;
+
+    #[test]
+    fn test_range_edge_graph() {
+        let mut rng = StdRng::seed_from_u64(42);
+        for _ in 0..300 {
+            let n = rng.gen_range(1..=12);
+            let m = rng.gen_range(0..=2 * n);
+            let mut builder = RangeEdgeGraphBuilder::<u64>::new(n);
+            let mut naive = vec![Vec::new(); n];
+            for _ in 0..m {
+                let w = rng.gen_range(0..10);
+                match rng.gen_range(0..3) {
+                    0 => {
+                        let u = rng.gen_range(0..n);
+                        let v = rng.gen_range(0..n);
+                        builder.add_edge(u, v, w);
+                        naive[u].push((v, w));
+                    }
+                    1 => {
+                        let u = rng.gen_range(0..n);
+                        let range = rng.sample(BiasedSubRange(0..n));
+                        builder.add_edge_vertex_to_range(u, range.clone(), w);
+                        naive[u].extend(range.map(|v| (v, w)));
+                    }
+                    2 => {
+                        let range = rng.sample(BiasedSubRange(0..n));
+                        let v = rng.gen_range(0..n);
+                        builder.add_edge_range_to_vertex(range.clone(), v, w);
+                        range.for_each(|u| naive[u].push((v, w)));
+                    }
+                    _ => unreachable!(),
+                }
+            }
+            assert_eq!(builder.original_len(), n);
+            let g = builder.build();
+            assert_eq!(g.len(), 3 * n - 2);
+            for s in 0..n {
+                let result = dijkstra(&g, s);
+                let expected = dijkstra(&naive, s);
+                assert_eq!(&result[..n], &expected[..]);
+            }
+        }
+    }
+
+    #[test]
+    fn test_empty_graph() {
+        let builder = RangeEdgeGraphBuilder::<u64>::new(0);
+        assert!(builder.is_empty());
+        assert!(builder.build().is_empty());
+    }
+
+    #[test]
+    #[should_panic(expected = "range end index 4 out of range for length 3")]
+    fn test_range_out_of_bounds() {
+        let mut builder = RangeEdgeGraphBuilder::<u64>::new(3);
+        builder.add_edge_vertex_to_range(0, 2..4, 0);
+    }
+}
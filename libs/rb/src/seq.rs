@@ -4,12 +4,12 @@ use crate::balance::BlackViolation;
 use crate::balance::Color;
 use crate::balance::Ptr;
 use crate::balance::Tree;
+use open::resolve_range;
 use std::cmp::Ordering;
 use std::cmp::Reverse;
 use std::fmt;
 use std::marker::PhantomData;
 use std::ops;
-use std::ops::Bound;
 use std::ops::Range;
 use std::ops::RangeBounds;
 
@@ -320,12 +320,7 @@ impl<O: Op> Seg<O> {
     }
 
     pub fn fold(&self, range: impl RangeBounds<usize>) -> O::Value {
-        let (start, end) = into_range(range, self.len());
-        assert!(
-            start <= end && end <= self.len(),
-            "Index out of range:\n range: {start}..{end}\n   len:{}",
-            self.len(),
-        );
+        let Range { start, end } = resolve_range(self.len(), range);
         if start == end {
             return O::identity();
         }
@@ -819,19 +814,6 @@ impl<'a, O: Op> DoubleEndedIterator for SegIter<'a, O> {
 }
 impl<'a, O: Op> ExactSizeIterator for SegIter<'a, O> {}
 
-fn into_range(range: impl RangeBounds<usize>, len: usize) -> (usize, usize) {
-    let start = match range.start_bound() {
-        Bound::Included(&i) => i,
-        Bound::Excluded(&i) => i + 1,
-        Bound::Unbounded => 0,
-    };
-    let end = match range.end_bound() {
-        Bound::Included(&i) => i + 1,
-        Bound::Excluded(&i) => i,
-        Bound::Unbounded => len,
-    };
-    (start, end)
-}
 #[cfg(test)]
 mod test_seg {
     use super::Node;
@@ -999,6 +981,27 @@ mod test_seg {
         }
     }
 
+    #[test]
+    #[should_panic(expected = "range starts at 3 but ends at 2")]
+    fn test_fold_reversed_range() {
+        let seg = ["a", "b", "c", "d"]
+            .iter()
+            .map(|s| s.to_string())
+            .collect::<Seg<O>>();
+        let (start, end) = (3, 2);
+        seg.fold(start..end);
+    }
+
+    #[test]
+    #[should_panic(expected = "range end index 5 out of range for length 4")]
+    fn test_fold_out_of_range() {
+        let seg = ["a", "b", "c", "d"]
+            .iter()
+            .map(|s| s.to_string())
+            .collect::<Seg<O>>();
+        seg.fold(..=4);
+    }
+
     #[test]
     fn test_seg_insert_remove() {
         const LEN_LIM: usize = 60;
@@ -90,6 +90,7 @@ mod nonempty;
 
 use nonempty::Nil;
 use nonempty::Nonempty;
+use open::resolve_range;
 use std::fmt::Debug;
 use std::fmt::{self};
 use std::hash::Hash;
@@ -97,7 +98,6 @@ use std::hash::Hasher;
 use std::iter::FromIterator;
 use std::marker::PhantomData;
 use std::mem::take;
-use std::ops::Bound;
 use std::ops::Range;
 use std::ops::RangeBounds;
 
@@ -277,8 +277,7 @@ impl<T, O: Op<Value = T>> RbTree<T, O> {
     ///
     /// 範囲外のとき
     pub fn fold(&self, range: impl RangeBounds<usize>) -> Option<O::Summary> {
-        let Range { start, end } = open(range, self.len());
-        assert!(start <= end && end <= self.len());
+        let Range { start, end } = resolve_range(self.len(), range);
         if start == end {
             None
         } else {
@@ -406,18 +405,6 @@ impl<T, O: Op<Value = T>> Default for RbTree<T, O> {
     }
 }
 
-fn open(range: impl RangeBounds<usize>, len: usize) -> Range<usize> {
-    (match range.start_bound() {
-        Bound::Unbounded => 0,
-        Bound::Included(&x) => x,
-        Bound::Excluded(&x) => x + 1,
-    })..match range.end_bound() {
-        Bound::Excluded(&x) => x,
-        Bound::Included(&x) => x + 1,
-        Bound::Unbounded => len,
-    }
-}
-
 #[cfg(test)]
 mod tests {
     use super::Nonempty;
@@ -538,6 +525,21 @@ mod tests {
         }
     }
 
+    #[test]
+    #[should_panic(expected = "range starts at 3 but ends at 2")]
+    fn test_fold_reversed_range() {
+        let tree = "abcd".chars().collect::<RbTree<_, O>>();
+        let (start, end) = (3, 2);
+        tree.fold(start..end);
+    }
+
+    #[test]
+    #[should_panic(expected = "range end index 5 out of range for length 4")]
+    fn test_fold_out_of_range() {
+        let tree = "abcd".chars().collect::<RbTree<_, O>>();
+        tree.fold(2..5);
+    }
+
     #[test]
     fn test_insert_delete_fold_get() {
         let mut rng = StdRng::seed_from_u64(42);
@@ -0,0 +1,144 @@
+//! Random salts shared by the hashing structures.
+//!
+//! Each thread has its own [`SaltSource`], seeded from the current time and an address, from
+//! which the hashing structures draw their seeds by [`next_seed`]. [`set_seed`] overrides it for
+//! reproducible tests, and [`retry_on_collision`] reruns a computation with fresh seeds when the
+//! caller detects an inconsistency.
+//!
+//! # Examples
+//!
+//! ```
+//! use salt::next_seed;
+//! use salt::set_seed;
+//! set_seed(42);
+//! let a = [next_seed(), next_seed()];
+//! set_seed(42);
+//! let b = [next_seed(), next_seed()];
+//! assert_eq!(a, b);
+//! assert_ne!(a[0], a[1]);
+//! ```
+use std::cell::Cell;
+use std::time::SystemTime;
+use std::time::UNIX_EPOCH;
+
+/// A stream of salts by splitmix64.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SaltSource {
+    state: u64,
+}
+impl SaltSource {
+    /// Returns a source seeded from the current time and the address of a local variable.
+    pub fn from_entropy() -> Self {
+        let time = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map_or(0, |d| d.as_nanos() as u64);
+        let local = 0_u8;
+        let address = &local as *const u8 as u64;
+        Self::with_seed(time ^ address.rotate_left(32))
+    }
+
+    /// Returns a source seeded with `seed`.
+    pub fn with_seed(seed: u64) -> Self {
+        Self { state: seed }
+    }
+
+    /// Returns the next salt.
+    pub fn next_u64(&mut self) -> u64 {
+        self.state = self.state.wrapping_add(0x9e37_79b9_7f4a_7c15);
+        let mut x = self.state;
+        x = (x ^ (x >> 30)).wrapping_mul(0xbf58_476d_1ce4_e5b9);
+        x = (x ^ (x >> 27)).wrapping_mul(0x94d0_49bb_1331_11eb);
+        x ^ (x >> 31)
+    }
+}
+
+thread_local! {
+    static SOURCE: Cell<Option<SaltSource>> = Cell::new(None);
+}
+
+/// Returns the next seed from the source of the current thread, seeding it from entropy on the
+/// first call.
+pub fn next_seed() -> u64 {
+    SOURCE.with(|source| {
+        let mut s = source.take().unwrap_or_else(SaltSource::from_entropy);
+        let seed = s.next_u64();
+        source.set(Some(s));
+        seed
+    })
+}
+
+/// Overrides the source of the current thread with a fixed seed.
+pub fn set_seed(seed: u64) {
+    SOURCE.with(|source| source.set(Some(SaltSource::with_seed(seed))));
+}
+
+/// Calls `f` with fresh seeds from [`next_seed`] until it returns `Some`, at most `attempts`
+/// times.
+///
+/// # Examples
+///
+/// ```
+/// use salt::retry_on_collision;
+/// let mut seeds = Vec::new();
+/// let result = retry_on_collision(5, |seed| {
+///     seeds.push(seed);
+///     (seeds.len() == 3).then_some(seed)
+/// });
+/// assert_eq!(result, Some(seeds[2]));
+/// assert_eq!(seeds.len(), 3);
+/// assert_eq!(retry_on_collision(2, |_| None::<()>), None);
+/// ```
+pub fn retry_on_collision<T>(attempts: usize, mut f: impl FnMut(u64) -> Option<T>) -> Option<T> {
+    (0..attempts).find_map(|_| f(next_seed()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::HashSet;
+
+    #[test]
+    fn test_override() {
+        set_seed(42);
+        let a = (0..100).map(|_| next_seed()).collect::<Vec<_>>();
+        set_seed(42);
+        let b = (0..100).map(|_| next_seed()).collect::<Vec<_>>();
+        assert_eq!(a, b);
+        assert_eq!(a.iter().collect::<HashSet<_>>().len(), 100);
+        set_seed(43);
+        let c = (0..100).map(|_| next_seed()).collect::<Vec<_>>();
+        assert!(a.iter().zip(&c).all(|(a, c)| a != c));
+    }
+
+    #[test]
+    fn test_entropy() {
+        let a = SaltSource::from_entropy().next_u64();
+        std::thread::sleep(std::time::Duration::from_millis(1));
+        let b = SaltSource::from_entropy().next_u64();
+        assert_ne!(a, b);
+        // The first call seeds from entropy.
+        let seeds = std::thread::spawn(|| [next_seed(), next_seed()])
+            .join()
+            .unwrap();
+        assert_ne!(seeds[0], seeds[1]);
+    }
+
+    #[test]
+    fn test_retry_on_collision() {
+        set_seed(42);
+        let mut seeds = Vec::new();
+        assert_eq!(
+            retry_on_collision(10, |seed| {
+                seeds.push(seed);
+                None::<()>
+            }),
+            None
+        );
+        assert_eq!(seeds.len(), 10);
+        assert_eq!(seeds.iter().collect::<HashSet<_>>().len(), 10);
+        set_seed(42);
+        let again = (0..10).map(|_| next_seed()).collect::<Vec<_>>();
+        assert_eq!(seeds, again);
+        assert_eq!(retry_on_collision(0, |_| Some(())), None);
+    }
+}
@@ -0,0 +1,329 @@
+use std::cmp::Reverse;
+use std::collections::BinaryHeap;
+
+/// Whether the intervals `(l, r)` include their right endpoints, which decides if two intervals
+/// sharing an endpoint overlap.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Endpoints {
+    /// $\[l, r)$, so that `(0, 1)` and `(1, 2)` do not overlap. Every interval must satisfy
+    /// $l < r$.
+    HalfOpen,
+    /// $\[l, r\]$, so that `(0, 1)` and `(1, 2)` overlap. Every interval must satisfy $l \le r$.
+    Closed,
+}
+impl Endpoints {
+    /// Returns `true` if an interval ending at `r` can be followed by one starting at `l`.
+    fn precedes(self, r: i64, l: i64) -> bool {
+        match self {
+            Self::HalfOpen => r <= l,
+            Self::Closed => r < l,
+        }
+    }
+
+    fn validate(self, intervals: &[(i64, i64)]) {
+        for &(l, r) in intervals {
+            assert!(
+                l < r || (l == r && self == Self::Closed),
+                "invalid interval ({}, {}) for {:?}",
+                l,
+                r,
+                self
+            );
+        }
+    }
+}
+
+/// Chooses as many pairwise nonoverlapping intervals as possible, greedily by right endpoints.
+///
+/// It returns the indices of the chosen intervals from left to right.
+///
+/// # Panics
+///
+/// If some interval is empty under `endpoints`.
+///
+/// # Complexity
+///
+/// $O(n \log n)$
+///
+/// # Examples
+///
+/// ```
+/// use scheduling::max_nonoverlapping;
+/// use scheduling::Endpoints;
+/// let intervals = [(0, 2), (2, 4), (1, 3)];
+/// assert_eq!(max_nonoverlapping(&intervals, Endpoints::HalfOpen), [0, 1]);
+/// assert_eq!(max_nonoverlapping(&intervals, Endpoints::Closed).len(), 1);
+/// ```
+pub fn max_nonoverlapping(intervals: &[(i64, i64)], endpoints: Endpoints) -> Vec<usize> {
+    endpoints.validate(intervals);
+    let mut order = (0..intervals.len()).collect::<Vec<_>>();
+    order.sort_by_key(|&i| intervals[i].1);
+    let mut chosen = Vec::new();
+    let mut last = None;
+    for i in order {
+        let (l, r) = intervals[i];
+        if last.map_or(true, |last| endpoints.precedes(last, l)) {
+            chosen.push(i);
+            last = Some(r);
+        }
+    }
+    chosen
+}
+
+/// Chooses pairwise nonoverlapping intervals to maximize the total weight.
+///
+/// It returns the total weight and the indices of the chosen intervals from left to right.
+/// An interval with a nonpositive weight is never chosen.
+///
+/// # Panics
+///
+/// * If `intervals` and `weights` have different lengths.
+/// * If some interval is empty under `endpoints`.
+///
+/// # Complexity
+///
+/// $O(n \log n)$
+///
+/// # Examples
+///
+/// ```
+/// use scheduling::weighted_interval_scheduling;
+/// use scheduling::Endpoints;
+/// let intervals = [(0, 2), (2, 4), (1, 3)];
+/// let weights = [2, 2, 3];
+/// assert_eq!(
+///     weighted_interval_scheduling(&intervals, &weights, Endpoints::HalfOpen),
+///     (4, vec![0, 1])
+/// );
+/// assert_eq!(
+///     weighted_interval_scheduling(&intervals, &weights, Endpoints::Closed),
+///     (3, vec![2])
+/// );
+/// ```
+pub fn weighted_interval_scheduling(
+    intervals: &[(i64, i64)],
+    weights: &[i64],
+    endpoints: Endpoints,
+) -> (i64, Vec<usize>) {
+    assert_eq!(
+        intervals.len(),
+        weights.len(),
+        "intervals and weights have different lengths"
+    );
+    endpoints.validate(intervals);
+    let n = intervals.len();
+    let mut order = (0..n).collect::<Vec<_>>();
+    order.sort_by_key(|&i| intervals[i].1);
+    // `dp[j]` is the best among the first `j` intervals in `order`, and `prev[j]` is the number of
+    // the intervals that can precede `order[j]`.
+    let mut dp = vec![0; n + 1];
+    let mut prev = vec![0; n];
+    for (j, &i) in order.iter().enumerate() {
+        let l = intervals[i].0;
+        prev[j] = order[..j].partition_point(|&k| endpoints.precedes(intervals[k].1, l));
+        dp[j + 1] = dp[j].max(dp[prev[j]] + weights[i]);
+    }
+    let mut chosen = Vec::new();
+    let mut j = n;
+    while j > 0 {
+        if dp[j] == dp[j - 1] {
+            j -= 1;
+        } else {
+            chosen.push(order[j - 1]);
+            j = prev[j - 1];
+        }
+    }
+    chosen.reverse();
+    (dp[n], chosen)
+}
+
+/// Assigns every interval to a machine so that the intervals on each machine are pairwise
+/// nonoverlapping, with as few machines as possible.
+///
+/// It returns the number of the machines, which equals the maximum number of the intervals
+/// sharing a point, and the machine of each interval.
+///
+/// # Panics
+///
+/// If some interval is empty under `endpoints`.
+///
+/// # Complexity
+///
+/// $O(n \log n)$
+///
+/// # Examples
+///
+/// ```
+/// use scheduling::min_machines;
+/// use scheduling::Endpoints;
+/// let intervals = [(0, 2), (2, 4), (1, 3)];
+/// assert_eq!(
+///     min_machines(&intervals, Endpoints::HalfOpen),
+///     (2, vec![0, 0, 1])
+/// );
+/// assert_eq!(
+///     min_machines(&intervals, Endpoints::Closed),
+///     (3, vec![0, 2, 1])
+/// );
+/// ```
+pub fn min_machines(intervals: &[(i64, i64)], endpoints: Endpoints) -> (usize, Vec<usize>) {
+    endpoints.validate(intervals);
+    let mut order = (0..intervals.len()).collect::<Vec<_>>();
+    order.sort_by_key(|&i| intervals[i]);
+    let mut machine = vec![0; intervals.len()];
+    let mut count = 0;
+    // The right endpoints of the last intervals on the machines in use.
+    let mut heap = BinaryHeap::new();
+    for i in order {
+        let (l, r) = intervals[i];
+        machine[i] = match heap.peek() {
+            Some(&Reverse((end, m))) if endpoints.precedes(end, l) => {
+                heap.pop();
+                m
+            }
+            _ => {
+                count += 1;
+                count - 1
+            }
+        };
+        heap.push(Reverse((r, machine[i])));
+    }
+    (count, machine)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rand::rngs::StdRng;
+    use rand::Rng;
+    use rand::SeedableRng;
+
+    const ENDPOINTS: [Endpoints; 2] = [Endpoints::HalfOpen, Endpoints::Closed];
+
+    fn overlap(a: (i64, i64), b: (i64, i64), endpoints: Endpoints) -> bool {
+        !endpoints.precedes(a.1, b.0) && !endpoints.precedes(b.1, a.0)
+    }
+
+    fn random_intervals(rng: &mut StdRng, n: usize, endpoints: Endpoints) -> Vec<(i64, i64)> {
+        (0..n)
+            .map(|_| {
+                let l = rng.gen_range(0..10_i64);
+                let len = match endpoints {
+                    Endpoints::HalfOpen => rng.gen_range(1..=4_i64),
+                    Endpoints::Closed => rng.gen_range(0..=4_i64),
+                };
+                (l, l + len)
+            })
+            .collect()
+    }
+
+    // Checks that `chosen` is sorted from left to right and pairwise nonoverlapping.
+    fn assert_valid(intervals: &[(i64, i64)], chosen: &[usize], endpoints: Endpoints) {
+        for w in chosen.windows(2) {
+            assert!(endpoints.precedes(intervals[w[0]].1, intervals[w[1]].0));
+        }
+    }
+
+    // Returns the best weight over the nonoverlapping subsets.
+    fn brute(intervals: &[(i64, i64)], weights: &[i64], endpoints: Endpoints) -> i64 {
+        let n = intervals.len();
+        (0..1_usize << n)
+            .filter(|&bs| {
+                (0..n).all(|i| {
+                    (0..i).all(|j| {
+                        bs >> i & bs >> j & 1 == 0
+                            || !overlap(intervals[i], intervals[j], endpoints)
+                    })
+                })
+            })
+            .map(|bs| {
+                (0..n)
+                    .filter(|&i| bs >> i & 1 == 1)
+                    .map(|i| weights[i])
+                    .sum()
+            })
+            .max()
+            .unwrap()
+    }
+
+    #[test]
+    fn test_max_nonoverlapping() {
+        let mut rng = StdRng::seed_from_u64(42);
+        for _ in 0..300 {
+            for &endpoints in &ENDPOINTS {
+                let n = rng.gen_range(0..=10_usize);
+                let intervals = random_intervals(&mut rng, n, endpoints);
+                let chosen = max_nonoverlapping(&intervals, endpoints);
+                assert_valid(&intervals, &chosen, endpoints);
+                let expected = brute(&intervals, &vec![1; n], endpoints);
+                assert_eq!(chosen.len() as i64, expected);
+            }
+        }
+    }
+
+    #[test]
+    fn test_weighted_interval_scheduling() {
+        let mut rng = StdRng::seed_from_u64(42);
+        for _ in 0..300 {
+            for &endpoints in &ENDPOINTS {
+                let n = rng.gen_range(0..=10_usize);
+                let intervals = random_intervals(&mut rng, n, endpoints);
+                let weights = (0..n)
+                    .map(|_| rng.gen_range(-3..=10_i64))
+                    .collect::<Vec<_>>();
+                let (weight, chosen) =
+                    weighted_interval_scheduling(&intervals, &weights, endpoints);
+                assert_valid(&intervals, &chosen, endpoints);
+                assert_eq!(chosen.iter().map(|&i| weights[i]).sum::<i64>(), weight);
+                assert!(chosen.iter().all(|&i| weights[i] > 0));
+                assert_eq!(weight, brute(&intervals, &weights, endpoints));
+            }
+        }
+    }
+
+    #[test]
+    fn test_min_machines() {
+        let mut rng = StdRng::seed_from_u64(42);
+        for _ in 0..300 {
+            for &endpoints in &ENDPOINTS {
+                let n = rng.gen_range(0..=30_usize);
+                let intervals = random_intervals(&mut rng, n, endpoints);
+                let (count, machine) = min_machines(&intervals, endpoints);
+                assert!(machine.iter().all(|&m| m < count));
+                for i in 0..n {
+                    for j in 0..i {
+                        assert!(
+                            machine[i] != machine[j]
+                                || !overlap(intervals[i], intervals[j], endpoints)
+                        );
+                    }
+                }
+                // The maximum overlap is attained at some left endpoint.
+                let depth = intervals
+                    .iter()
+                    .map(|&(x, _)| {
+                        intervals
+                            .iter()
+                            .filter(|&&interval| overlap(interval, (x, x), Endpoints::Closed))
+                            .filter(|&&(_, r)| endpoints == Endpoints::Closed || x < r)
+                            .count()
+                    })
+                    .max()
+                    .unwrap_or(0);
+                assert_eq!(count, depth);
+            }
+        }
+    }
+
+    #[test]
+    #[should_panic(expected = "invalid interval (3, 3) for HalfOpen")]
+    fn test_empty_half_open_interval() {
+        max_nonoverlapping(&[(0, 1), (3, 3)], Endpoints::HalfOpen);
+    }
+
+    #[test]
+    #[should_panic(expected = "invalid interval (3, 2) for Closed")]
+    fn test_reversed_interval() {
+        min_machines(&[(3, 2)], Endpoints::Closed);
+    }
+}
@@ -0,0 +1,406 @@
+//! Greedy scheduling of unit jobs with deadlines, and sorting by an exchange argument.
+//!
+//! # Jobs with deadlines
+//!
+//! Each job takes a unit time, and the job `(deadline, profit)` gains `profit` if it finishes by
+//! the time `deadline`, that is, if it runs in one of the slots $1, \dots, \mathrm{deadline}$.
+//! Both solvers maximize the total profit.
+//!
+//! * [`schedule_with_heap`] scans the jobs in the order of deadlines and drops the least
+//!   profitable one whenever the chosen jobs overflow. It takes $O(n \log n)$ time.
+//! * [`schedule_with_slots`] scans the jobs in the decreasing order of profits and puts each one
+//!   into the latest free slot by its deadline, found by a union-find over the slots. Besides the
+//!   sort, it takes almost linear time, and it also gives the slot of each job.
+//!
+//! # Exchange arguments
+//!
+//! [`sort_by_exchange`] sorts by a predicate "`a` should come before `b`", which typically comes
+//! from comparing `a, b` and `b, a` as adjacent elements. The predicate must be a strict weak order,
+//! and an inconsistent one is caught in debug builds.
+//!
+//! # Intervals
+//!
+//! [`max_nonoverlapping`], [`weighted_interval_scheduling`] and [`min_machines`] handle intervals
+//! `(l, r)`. Whether two intervals sharing an endpoint overlap is given by [`Endpoints`] every
+//! time, since the half-open and the closed conventions give different answers.
+//!
+//! # Examples
+//!
+//! ```
+//! use scheduling::schedule_with_heap;
+//! use scheduling::schedule_with_slots;
+//! let jobs = [(2, 100), (1, 19), (2, 27), (1, 25), (3, 15)];
+//! assert_eq!(schedule_with_heap(&jobs).profit, 142);
+//! let slots = schedule_with_slots(&jobs);
+//! assert_eq!(slots.profit, 142);
+//! assert_eq!(slots.jobs, [2, 0, 4]);
+//! ```
+
+mod interval;
+
+pub use interval::max_nonoverlapping;
+pub use interval::min_machines;
+pub use interval::weighted_interval_scheduling;
+pub use interval::Endpoints;
+use std::cmp::Ordering;
+use std::cmp::Reverse;
+use std::collections::BinaryHeap;
+
+/// A set of the jobs run in slots $1, 2, \dots$ in this order.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct Schedule {
+    /// The indices of the chosen jobs in the order of execution, so that the $t$-th one
+    /// (0-indexed) meets its deadline, $t < \mathrm{deadline}$.
+    pub jobs: Vec<usize>,
+    /// The total profit of the chosen jobs.
+    pub profit: i64,
+}
+
+/// Chooses the jobs `(deadline, profit)` to maximize the total profit, with a heap.
+///
+/// A job with a nonpositive profit is never chosen.
+///
+/// # Complexity
+///
+/// $O(n \log n)$
+///
+/// # Examples
+///
+/// ```
+/// use scheduling::schedule_with_heap;
+/// let schedule = schedule_with_heap(&[(1, 3), (1, 5), (2, 1)]);
+/// assert_eq!(schedule.jobs, [1, 2]);
+/// assert_eq!(schedule.profit, 6);
+/// ```
+pub fn schedule_with_heap(jobs: &[(usize, i64)]) -> Schedule {
+    let mut order = (0..jobs.len()).collect::<Vec<_>>();
+    order.sort_by_key(|&i| jobs[i].0);
+    // The chosen jobs among the scanned ones, which are always feasible.
+    let mut heap = BinaryHeap::new();
+    for i in order {
+        let (deadline, profit) = jobs[i];
+        if profit <= 0 {
+            continue;
+        }
+        heap.push(Reverse((profit, i)));
+        if heap.len() > deadline {
+            heap.pop();
+        }
+    }
+    let mut chosen = heap
+        .into_iter()
+        .map(|Reverse((_, i))| i)
+        .collect::<Vec<_>>();
+    // Earliest deadline first
+    chosen.sort_by_key(|&i| (jobs[i].0, i));
+    Schedule {
+        profit: chosen.iter().map(|&i| jobs[i].1).sum(),
+        jobs: chosen,
+    }
+}
+
+/// Chooses the jobs `(deadline, profit)` to maximize the total profit, putting each job into the
+/// latest free slot.
+///
+/// A job with a nonpositive profit is never chosen. The slot of the job `jobs[i]` is its position
+/// in [`Schedule::jobs`] plus one.
+///
+/// # Complexity
+///
+/// $O(n \log n)$ for the sort, and $O(n \alpha(n))$ for the rest.
+///
+/// # Examples
+///
+/// ```
+/// use scheduling::schedule_with_slots;
+/// let schedule = schedule_with_slots(&[(1, 3), (1, 5), (2, 1)]);
+/// assert_eq!(schedule.jobs, [1, 2]);
+/// assert_eq!(schedule.profit, 6);
+/// ```
+pub fn schedule_with_slots(jobs: &[(usize, i64)]) -> Schedule {
+    let n = jobs.len();
+    let mut order = (0..n).filter(|&i| jobs[i].1 > 0).collect::<Vec<_>>();
+    order.sort_by_key(|&i| Reverse(jobs[i].1));
+    // `free[s]` leads to the latest free slot at or before `s`, where `0` means none.
+    let mut free = (0..=n).collect::<Vec<_>>();
+    let mut slots = vec![None; n + 1];
+    for i in order {
+        let slot = find(&mut free, jobs[i].0.min(n));
+        if slot != 0 {
+            slots[slot] = Some(i);
+            free[slot] = slot - 1;
+        }
+    }
+    let chosen = slots.into_iter().flatten().collect::<Vec<_>>();
+    Schedule {
+        profit: chosen.iter().map(|&i| jobs[i].1).sum(),
+        jobs: chosen,
+    }
+}
+
+fn find(free: &mut [usize], mut s: usize) -> usize {
+    let mut root = s;
+    while free[root] != root {
+        root = free[root];
+    }
+    while free[s] != root {
+        s = std::mem::replace(&mut free[s], root);
+    }
+    root
+}
+
+/// Sorts `items` so that `better_first(a, b)` implies that `a` comes before `b`.
+///
+/// # Requirements
+///
+/// `better_first` must be a strict weak order, as for [`slice::sort_by`]:
+///
+/// * it is irreflexive and transitive, and
+/// * the incomparability, neither `better_first(a, b)` nor `better_first(b, a)`, is transitive.
+///
+/// An exchange argument often only shows that swapping adjacent elements does not improve, which
+/// is not enough, and a comparator violating the requirements sorts into an arbitrary order
+/// without any error. In debug builds, this function checks the requirements on sampled triples
+/// and the order of adjacent elements after sorting, and panics if it finds a violation.
+///
+/// # Examples
+///
+/// ```
+/// use scheduling::sort_by_exchange;
+/// // The largest number by concatenation
+/// let mut a = vec!["3", "30", "34", "5", "9"];
+/// sort_by_exchange(&mut a, |x, y| format!("{}{}", x, y) > format!("{}{}", y, x));
+/// assert_eq!(a.concat(), "9534330");
+/// ```
+pub fn sort_by_exchange<T>(items: &mut [T], better_first: impl Fn(&T, &T) -> bool) {
+    #[cfg(debug_assertions)]
+    validate_sample(items, &better_first);
+    items.sort_by(|a, b| {
+        if better_first(a, b) {
+            Ordering::Less
+        } else if better_first(b, a) {
+            Ordering::Greater
+        } else {
+            Ordering::Equal
+        }
+    });
+    #[cfg(debug_assertions)]
+    for w in items.windows(2) {
+        assert!(
+            !better_first(&w[1], &w[0]),
+            "the comparator is not a strict weak order: the sorted elements are out of order"
+        );
+    }
+}
+
+#[cfg(debug_assertions)]
+fn validate_sample<T>(items: &[T], better_first: &impl Fn(&T, &T) -> bool) {
+    const SAMPLES: usize = 512;
+    let n = items.len();
+    if n == 0 {
+        return;
+    }
+    let cmp = |i: usize, j: usize| {
+        let lt = better_first(&items[i], &items[j]);
+        let gt = better_first(&items[j], &items[i]);
+        assert!(
+            !(lt && gt),
+            "the comparator is not a strict weak order: two elements come before each other"
+        );
+        match (lt, gt) {
+            (true, _) => Ordering::Less,
+            (_, true) => Ordering::Greater,
+            _ => Ordering::Equal,
+        }
+    };
+    let check = |i: usize, j: usize, k: usize| {
+        let (x, y) = (cmp(i, j), cmp(j, k));
+        let expected = match (x, y) {
+            (Ordering::Equal, _) => Some(y),
+            (_, Ordering::Equal) => Some(x),
+            _ if x == y => Some(x),
+            _ => None,
+        };
+        if let Some(expected) = expected {
+            assert_eq!(
+                cmp(i, k),
+                expected,
+                "the comparator is not a strict weak order: transitivity fails"
+            );
+        }
+    };
+    if n.pow(3) <= SAMPLES {
+        for i in 0..n {
+            for j in 0..n {
+                for k in 0..n {
+                    check(i, j, k);
+                }
+            }
+        }
+    } else {
+        // xorshift, deterministic for reproducibility
+        let mut state = 0x2545_f491_4f6c_dd1d_u64;
+        let mut next = || {
+            state ^= state << 13;
+            state ^= state >> 7;
+            state ^= state << 17;
+            (state % n as u64) as usize
+        };
+        for _ in 0..SAMPLES {
+            let (i, j, k) = (next(), next(), next());
+            check(i, j, k);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rand::rngs::StdRng;
+    use rand::Rng;
+    use rand::SeedableRng;
+
+    fn permutations<T: Clone>(items: &[T]) -> Vec<Vec<T>> {
+        if items.is_empty() {
+            return vec![vec![]];
+        }
+        let mut result = Vec::new();
+        for i in 0..items.len() {
+            let mut rest = items.to_vec();
+            let first = rest.remove(i);
+            for mut p in permutations(&rest) {
+                p.insert(0, first.clone());
+                result.push(p);
+            }
+        }
+        result
+    }
+
+    // Tries every subset and every order of it.
+    fn brute_schedule(jobs: &[(usize, i64)]) -> i64 {
+        let n = jobs.len();
+        (0..1 << n)
+            .filter_map(|bs: usize| {
+                let subset = (0..n).filter(|&i| bs >> i & 1 == 1).collect::<Vec<_>>();
+                permutations(&subset)
+                    .iter()
+                    .any(|p| p.iter().enumerate().all(|(t, &i)| t < jobs[i].0))
+                    .then(|| subset.iter().map(|&i| jobs[i].1).sum::<i64>())
+            })
+            .max()
+            .unwrap()
+    }
+
+    fn assert_valid(jobs: &[(usize, i64)], schedule: &Schedule) {
+        let mut seen = vec![false; jobs.len()];
+        for (t, &i) in schedule.jobs.iter().enumerate() {
+            assert!(!seen[i]);
+            seen[i] = true;
+            assert!(t < jobs[i].0);
+        }
+        assert_eq!(
+            schedule.profit,
+            schedule.jobs.iter().map(|&i| jobs[i].1).sum::<i64>()
+        );
+    }
+
+    #[test]
+    fn test_schedule() {
+        let mut rng = StdRng::seed_from_u64(42);
+        for _ in 0..300 {
+            let n = rng.gen_range(0..=6);
+            let jobs = (0..n)
+                .map(|_| (rng.gen_range(0..=n + 1), rng.gen_range(-3..=10)))
+                .collect::<Vec<_>>();
+            let expected = brute_schedule(&jobs);
+            for schedule in [schedule_with_heap(&jobs), schedule_with_slots(&jobs)] {
+                assert_valid(&jobs, &schedule);
+                assert_eq!(schedule.profit, expected, "{:?}", &jobs);
+            }
+        }
+    }
+
+    #[test]
+    fn test_schedule_large() {
+        let mut rng = StdRng::seed_from_u64(42);
+        let n = 100_000;
+        let jobs = (0..n)
+            .map(|_| (rng.gen_range(0..=n), rng.gen_range(1..=1_000_000_000)))
+            .collect::<Vec<_>>();
+        let heap = schedule_with_heap(&jobs);
+        let slots = schedule_with_slots(&jobs);
+        assert_valid(&jobs, &heap);
+        assert_valid(&jobs, &slots);
+        assert_eq!(heap.profit, slots.profit);
+    }
+
+    #[test]
+    fn test_largest_concatenation() {
+        let mut rng = StdRng::seed_from_u64(42);
+        for _ in 0..300 {
+            let n = rng.gen_range(0..=6);
+            let mut a = (0..n)
+                .map(|_| match rng.gen_range(0..3) {
+                    0 => rng.gen_range(0..10),
+                    1 => rng.gen_range(0..100),
+                    _ => rng.gen_range(0..1000),
+                })
+                .map(|x: u32| x.to_string())
+                .collect::<Vec<_>>();
+            let expected = permutations(&a).iter().map(|p| p.concat()).max().unwrap();
+            sort_by_exchange(&mut a, |x, y| x.clone() + y > y.clone() + x);
+            assert_eq!(a.concat(), expected);
+        }
+    }
+
+    #[test]
+    fn test_weighted_completion_time() {
+        // Minimize $\sum w _ i C _ i$, where $C _ i$ is the completion time of the job $(p, w)$.
+        fn cost(jobs: &[(u64, u64)]) -> u64 {
+            let mut time = 0;
+            jobs.iter()
+                .map(|&(p, w)| {
+                    time += p;
+                    w * time
+                })
+                .sum()
+        }
+        let mut rng = StdRng::seed_from_u64(42);
+        for _ in 0..300 {
+            let n = rng.gen_range(0..=6);
+            let mut jobs = (0..n)
+                .map(|_| (rng.gen_range(1..=5), rng.gen_range(0..=5)))
+                .collect::<Vec<_>>();
+            let expected = permutations(&jobs).iter().map(|p| cost(p)).min().unwrap();
+            // Smith's rule: in the increasing order of $p / w$
+            sort_by_exchange(&mut jobs, |&(p0, w0), &(p1, w1)| p0 * w1 < p1 * w0);
+            assert_eq!(cost(&jobs), expected);
+        }
+    }
+
+    #[test]
+    #[cfg(debug_assertions)]
+    #[should_panic(expected = "the comparator is not a strict weak order")]
+    fn test_cyclic_comparator() {
+        // Rock-paper-scissors
+        let mut a = vec![0, 1, 2];
+        sort_by_exchange(&mut a, |&x, &y| (y + 3 - x) % 3 == 1);
+    }
+
+    #[test]
+    #[cfg(debug_assertions)]
+    #[should_panic(expected = "the comparator is not a strict weak order")]
+    fn test_non_strict_comparator() {
+        let mut a = (0..100).collect::<Vec<_>>();
+        sort_by_exchange(&mut a, |x, y| x <= y);
+    }
+
+    #[test]
+    #[cfg(debug_assertions)]
+    #[should_panic(expected = "the comparator is not a strict weak order")]
+    fn test_intransitive_incomparability() {
+        // Close numbers are incomparable, which is not transitive.
+        let mut a = (0..100).rev().collect::<Vec<i32>>();
+        sort_by_exchange(&mut a, |x, y| x + 30 < *y);
+    }
+}
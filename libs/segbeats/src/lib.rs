@@ -1,4 +1,4 @@
-use open::open;
+use open::resolve_range;
 use std::cell::RefCell;
 use std::fmt::Debug;
 use std::ops::Add;
@@ -11,50 +11,53 @@ use std::ops::SubAssign;
 #[derive(Debug, Clone, PartialEq, Eq)]
 pub struct Segbeats<T> {
     len: usize,
+    size: usize,
     lg: u32,
     table: RefCell<Vec<Node<T>>>,
 }
 
 impl<T: Elm> Segbeats<T> {
     pub fn new(src: &[T]) -> Self {
-        let len = src.len().next_power_of_two();
-        let lg = len.trailing_zeros();
-        let mut table = vec![Node::new(); 2 * len];
+        let len = src.len();
+        let size = len.next_power_of_two();
+        let lg = size.trailing_zeros();
+        let mut table = vec![Node::new(); 2 * size];
         for (i, &x) in src.iter().enumerate() {
-            table[len + i] = Node::single(x);
+            table[size + i] = Node::single(x);
         }
-        (1..len)
+        (1..size)
             .rev()
             .for_each(|i| table[i] = Node::merge(table[2 * i], table[2 * i + 1]));
         Self {
             len,
+            size,
             lg,
             table: RefCell::new(table),
         }
     }
 
     pub fn change_min(&mut self, range: impl Clone + RangeBounds<usize>, x: T) {
-        let range = open(self.len, range);
+        let range = resolve_range(self.len, range);
         self.dfs::<ChangeMin<T>>(range, x)
     }
 
     pub fn change_max(&mut self, range: impl Clone + RangeBounds<usize>, x: T) {
-        let range = open(self.len, range);
+        let range = resolve_range(self.len, range);
         self.dfs::<ChangeMax<T>>(range, x)
     }
 
     pub fn query_min(&self, range: impl RangeBounds<usize>) -> T {
-        let range = open(self.len, range);
+        let range = resolve_range(self.len, range);
         self.dfs::<QueryMin<T>>(range, ())
     }
 
     pub fn query_max(&self, range: impl RangeBounds<usize>) -> T {
-        let range = open(self.len, range);
+        let range = resolve_range(self.len, range);
         self.dfs::<QueryMax<T>>(range, ())
     }
 
     pub fn query_sum(&self, range: impl RangeBounds<usize>) -> T {
-        let range = open(self.len, range);
+        let range = resolve_range(self.len, range);
         self.dfs::<QuerySum<T>>(range, ())
     }
 
@@ -76,7 +79,7 @@ impl<T: Elm> Segbeats<T> {
     }
 
     fn dfs<D: Dfs<Value = T>>(&self, range: Range<usize>, x: D::Param) -> D::Output {
-        self.dfs_impl::<D>(1, 0..self.len, range, x)
+        self.dfs_impl::<D>(1, 0..self.size, range, x)
     }
 
     fn dfs_impl<D: Dfs<Value = T>>(
@@ -354,40 +357,104 @@ impl_elm! {
     i8; i16; i32; i64; i128; isize;
 }
 
-// #[cfg(test)]
-// mod tests {
-//     mod impl_query;
-//     mod queries;
-//     mod vector;
-//
-//     use super::Segbeats;
-//     use queries::{ChangeMax, ChangeMin, QueryMax, QuerySum};
-//     use query_test::{impl_help, Config};
-//     use rand::prelude::*;
-//     use vector::{Len, Value, Vector};
-//
-//     type Tester<T, G> = query_test::Tester<StdRng, Vector<T>, Segbeats<T>, G>;
-//
-//     #[test]
-//     fn test_i64() {
-//         #[derive(Debug, Clone, PartialEq, Copy, Eq)]
-//         struct G {}
-//         impl_help! {Len, |rng| rng.gen_range(1..100); }
-//         impl_help! {Value<i64>, |rng| rng.gen_range(-1_000_000_000, 1_000_000_000); }
-//
-//         let mut tester = Tester::<i64, G>::new(StdRng::seed_from_u64(42), Config::Short);
-//         for _ in 0..10 {
-//             tester.initialize();
-//             for _ in 0..100 {
-//                 let command = tester.rng_mut().gen_range(0..4);
-//                 match command {
-//                     0 => tester.mutate::<ChangeMin<_>>(),
-//                     1 => tester.mutate::<ChangeMax<_>>(),
-//                     2 => tester.compare::<QueryMax<_>>(),
-//                     3 => tester.compare::<QuerySum<_>>(),
-//                     _ => unreachable!(),
-//                 }
-//             }
-//         }
-//     }
-// }
+#[cfg(test)]
+mod tests {
+    use super::Segbeats;
+    use rand::prelude::*;
+    use randtools::SubRange;
+
+    // 愚直と全区間クエリで比較します。
+    fn check(segbeats: &Segbeats<i64>, vec: &[i64]) {
+        let n = vec.len();
+        for l in 0..=n {
+            for r in l..=n {
+                let a = &vec[l..r];
+                assert_eq!(
+                    segbeats.query_max(l..r),
+                    a.iter().copied().max().unwrap_or(i64::MIN)
+                );
+                assert_eq!(
+                    segbeats.query_min(l..r),
+                    a.iter().copied().min().unwrap_or(i64::MAX)
+                );
+                assert_eq!(segbeats.query_sum(l..r), a.iter().sum::<i64>());
+            }
+        }
+    }
+
+    #[test]
+    fn test_i64() {
+        let mut rng = StdRng::seed_from_u64(42);
+        for _ in 0..10 {
+            let n = rng.gen_range(1..100);
+            let mut vec = (0..n)
+                .map(|_| rng.gen_range(-1_000_000_000..1_000_000_000))
+                .collect::<Vec<i64>>();
+            let mut segbeats = Segbeats::new(&vec);
+            for _ in 0..100 {
+                let range = rng.sample(SubRange(0..n));
+                match rng.gen_range(0..4) {
+                    0 => {
+                        let x = rng.gen_range(-1_000_000_000..1_000_000_000);
+                        segbeats.change_min(range.clone(), x);
+                        vec[range].iter_mut().for_each(|y| *y = (*y).min(x));
+                    }
+                    1 => {
+                        let x = rng.gen_range(-1_000_000_000..1_000_000_000);
+                        segbeats.change_max(range.clone(), x);
+                        vec[range].iter_mut().for_each(|y| *y = (*y).max(x));
+                    }
+                    2 => {
+                        let expected = vec[range.clone()].iter().copied().max().unwrap_or(i64::MIN);
+                        assert_eq!(segbeats.query_max(range), expected);
+                    }
+                    3 => {
+                        let expected = vec[range.clone()].iter().sum::<i64>();
+                        assert_eq!(segbeats.query_sum(range), expected);
+                    }
+                    _ => unreachable!(),
+                }
+            }
+            check(&segbeats, &vec);
+        }
+    }
+
+    #[test]
+    fn test_equal_max_blocks() {
+        // 最大値と 2 番目の最大値が同じ値のブロックにたくさん並んでいるときに、その間、ちょうど
+        // 2 番目、ちょうど最大値、さらに下への chmin を順に試します。
+        let n = 48;
+        let mut vec = (0..n)
+            .map(|i| [9, 9, 9, 5, 5, 9][i % 6])
+            .collect::<Vec<i64>>();
+        let mut segbeats = Segbeats::new(&vec);
+        let mut ops = Vec::new();
+        for &x in &[9, 7, 5, 5, 4, 2] {
+            for range in [0..n, 1..n - 1, 3..5, 6..30, 0..1, 17..18, 30..48] {
+                ops.push((range, x));
+            }
+        }
+        for (range, x) in ops {
+            segbeats.change_min(range.clone(), x);
+            vec[range].iter_mut().for_each(|y| *y = (*y).min(x));
+            check(&segbeats, &vec);
+        }
+        // chmax で値を散らしてから、また同じ値に揃えます。
+        for (i, x) in [(0, 3), (5, 8), (6, 8), (47, 8)] {
+            segbeats.change_max(i..=i, x);
+            vec[i] = vec[i].max(x);
+        }
+        check(&segbeats, &vec);
+        segbeats.change_min(.., 3);
+        vec.iter_mut().for_each(|y| *y = (*y).min(3));
+        check(&segbeats, &vec);
+        assert_eq!(segbeats.query_sum(..), 2 * 44 + 3 * 4);
+    }
+
+    #[test]
+    #[should_panic(expected = "range end index 8 out of range for length 5")]
+    fn test_range_beyond_len() {
+        // 内部の表の大きさは 8 ですが、長さ 5 で検査します。
+        Segbeats::new(&[0_i64; 5]).query_sum(0..8);
+    }
+}
@@ -1,4 +1,4 @@
-use open::open;
+use open::resolve_range;
 use std::cell::RefCell;
 use std::fmt::Debug;
 use std::mem::replace;
@@ -12,6 +12,7 @@ use std::ops::SubAssign;
 #[derive(Clone, PartialEq, Eq)]
 pub struct Segbeats<T> {
     len: usize,
+    size: usize,
     lg: u32,
     table: RefCell<Vec<Node<T>>>,
 }
@@ -28,56 +29,58 @@ impl<T: Elm> Debug for Segbeats<T> {
 
 impl<T: Elm> Segbeats<T> {
     pub fn new(src: &[T]) -> Self {
-        let len = src.len().next_power_of_two();
-        let lg = len.trailing_zeros();
-        let mut table = vec![Node::new(); 2 * len];
+        let len = src.len();
+        let size = len.next_power_of_two();
+        let lg = size.trailing_zeros();
+        let mut table = vec![Node::new(); 2 * size];
         for (i, &x) in src.iter().enumerate() {
-            table[len + i] = Node::single(x);
+            table[size + i] = Node::single(x);
         }
-        (1..len).rev().for_each(|i| {
+        (1..size).rev().for_each(|i| {
             let x = table[2 * i];
             let y = table[2 * i + 1];
             Node::merge(&mut table[i], x, y);
         });
         Self {
             len,
+            size,
             lg,
             table: RefCell::new(table),
         }
     }
 
     pub fn change_min(&mut self, range: impl Clone + RangeBounds<usize>, x: T) {
-        let range = open(self.len, range);
+        let range = resolve_range(self.len, range);
         self.dfs::<ChangeMin<T>>(range, x)
     }
 
     pub fn change_max(&mut self, range: impl Clone + RangeBounds<usize>, x: T) {
-        let range = open(self.len, range);
+        let range = resolve_range(self.len, range);
         self.dfs::<ChangeMax<T>>(range, x)
     }
 
     pub fn range_add(&mut self, range: impl Clone + RangeBounds<usize>, x: T) {
-        let range = open(self.len, range);
+        let range = resolve_range(self.len, range);
         self.dfs::<RangeAdd<T>>(range, x)
     }
 
     pub fn query_min(&self, range: impl RangeBounds<usize>) -> T {
-        let range = open(self.len, range);
+        let range = resolve_range(self.len, range);
         self.dfs::<QueryMin<T>>(range, ())
     }
 
     pub fn query_max(&self, range: impl RangeBounds<usize>) -> T {
-        let range = open(self.len, range);
+        let range = resolve_range(self.len, range);
         self.dfs::<QueryMax<T>>(range, ())
     }
 
     pub fn query_sum(&self, range: impl RangeBounds<usize>) -> T {
-        let range = open(self.len, range);
+        let range = resolve_range(self.len, range);
         self.dfs::<QuerySum<T>>(range, ())
     }
 
     pub fn count_changes(&self, range: impl RangeBounds<usize>) -> u64 {
-        let range = open(self.len, range);
+        let range = resolve_range(self.len, range);
         self.dfs::<CountChanges<T>>(range, ())
     }
 
@@ -120,7 +123,7 @@ impl<T: Elm> Segbeats<T> {
     }
 
     fn dfs<D: Dfs<Value = T>>(&self, range: Range<usize>, x: D::Param) -> D::Output {
-        self.dfs_impl::<D>(1, 0..self.len, range, x)
+        self.dfs_impl::<D>(1, 0..self.size, range, x)
     }
 
     fn dfs_impl<D: Dfs<Value = T>>(
@@ -495,6 +498,18 @@ impl_elm! {
     i8; i16; i32; i64; i128; isize;
 }
 
+#[cfg(test)]
+mod tests {
+    use super::Segbeats;
+
+    #[test]
+    #[should_panic(expected = "range end index 6 out of range for length 5")]
+    fn test_range_beyond_len() {
+        // 内部の表の大きさは 8 ですが、長さ 5 で検査します。
+        Segbeats::new(&[0_i64; 5]).range_add(..6, 1);
+    }
+}
+
 // #[cfg(test)]
 // mod tests {
 //     mod impl_query;
@@ -0,0 +1,264 @@
+//! Covers intervals with the fewest given segments.
+//!
+//! All the segments and the targets are half-open: $\[l, r)$ contains $x$ iff $l \le x < r$. So
+//! $\[0, 2)$ and $\[2, 5)$ together cover $\[0, 5)$, while a segment with $l \ge r$ is empty and
+//! never used, and an empty target needs no segments.
+//!
+//! Let $f(x) = \max(\\{x\\} \cup \\{r \mid \[l, r) \text{ is a segment with } l \le x\\})$ be the
+//! furthest point reachable from $x$ with one more segment. The greedy answer for $\[a, b)$ is
+//! the number of the iterations of $f$ from $a$ until it reaches $b$.
+//!
+//! # Examples
+//!
+//! ```
+//! use segment_cover::min_segments_to_cover;
+//! use segment_cover::CoverageJump;
+//! let segments = [(0, 3), (2, 6), (3, 4), (6, 8), (7, 10)];
+//! assert_eq!(min_segments_to_cover(&segments, (0, 10)), Some(4));
+//! assert_eq!(min_segments_to_cover(&segments, (1, 7)), Some(3));
+//! assert_eq!(min_segments_to_cover(&segments, (0, 11)), None);
+//!
+//! let jump = CoverageJump::new(&segments);
+//! assert_eq!(jump.query((0, 10)), Some(4));
+//! assert_eq!(jump.query((6, 8)), Some(1));
+//! assert_eq!(jump.query((-1, 2)), None);
+//! assert_eq!(jump.query((5, 5)), Some(0));
+//! ```
+
+/// Returns the minimum number of the segments whose union contains `target`, or `None` if
+/// impossible.
+///
+/// # Complexity
+///
+/// $O(n \log n)$
+pub fn min_segments_to_cover(segments: &[(i64, i64)], target: (i64, i64)) -> Option<usize> {
+    let (a, b) = target;
+    let mut segments = segments.to_vec();
+    segments.sort_unstable();
+    let mut count = 0;
+    let mut current = a;
+    let mut i = 0;
+    while current < b {
+        let mut furthest = current;
+        while i < segments.len() && segments[i].0 <= current {
+            furthest = furthest.max(segments[i].1);
+            i += 1;
+        }
+        if furthest == current {
+            return None;
+        }
+        current = furthest;
+        count += 1;
+    }
+    Some(count)
+}
+
+/// The doubling table of the furthest reach for many cover queries with the same segments.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CoverageJump {
+    // The left ends in ascending order and the prefix maximums of the right ends.
+    lefts: Vec<i64>,
+    prefix_max: Vec<i64>,
+    // The right ends in ascending order, and `reach[k][i]` is the index of $f ^ {2 ^ k}$ of
+    // `coords[i]`.
+    coords: Vec<i64>,
+    reach: Vec<Vec<usize>>,
+}
+impl CoverageJump {
+    /// Precomputes the table.
+    ///
+    /// # Complexity
+    ///
+    /// $O(n \log n)$
+    pub fn new(segments: &[(i64, i64)]) -> Self {
+        let mut segments = segments
+            .iter()
+            .copied()
+            .filter(|&(l, r)| l < r)
+            .collect::<Vec<_>>();
+        segments.sort_unstable();
+        let lefts = segments.iter().map(|&(l, _)| l).collect::<Vec<_>>();
+        let prefix_max = segments
+            .iter()
+            .scan(i64::MIN, |max, &(_, r)| {
+                *max = (*max).max(r);
+                Some(*max)
+            })
+            .collect::<Vec<_>>();
+        let mut coords = segments.iter().map(|&(_, r)| r).collect::<Vec<_>>();
+        coords.sort_unstable();
+        coords.dedup();
+        let mut this = Self {
+            lefts,
+            prefix_max,
+            coords,
+            reach: Vec::new(),
+        };
+        let first = (0..this.coords.len())
+            .map(|i| this.index(this.step(this.coords[i])))
+            .collect::<Vec<_>>();
+        this.reach.push(first);
+        while 1 << this.reach.len() < this.coords.len() {
+            let last = this.reach.last().unwrap();
+            let next = last.iter().map(|&j| last[j]).collect::<Vec<_>>();
+            this.reach.push(next);
+        }
+        this
+    }
+
+    /// Returns the minimum number of the segments whose union contains `target`, or `None` if
+    /// impossible.
+    ///
+    /// # Complexity
+    ///
+    /// $O(\log n)$
+    pub fn query(&self, target: (i64, i64)) -> Option<usize> {
+        let (a, b) = target;
+        if b <= a {
+            return Some(0);
+        }
+        let first = self.step(a);
+        if first == a {
+            return None;
+        }
+        if b <= first {
+            return Some(1);
+        }
+        let mut i = self.index(first);
+        let mut count = 1;
+        for (k, reach) in self.reach.iter().enumerate().rev() {
+            if self.coords[reach[i]] < b {
+                i = reach[i];
+                count += 1 << k;
+            }
+        }
+        // One more step reaches $b$ if possible at all.
+        let i = self.reach.first()?[i];
+        (b <= self.coords[i]).then_some(count + 1)
+    }
+
+    // $f(x)$
+    fn step(&self, x: i64) -> i64 {
+        let k = self.lefts.partition_point(|&l| l <= x);
+        if k == 0 {
+            x
+        } else {
+            x.max(self.prefix_max[k - 1])
+        }
+    }
+
+    fn index(&self, x: i64) -> usize {
+        self.coords.binary_search(&x).unwrap()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rand::rngs::StdRng;
+    use rand::Rng;
+    use rand::SeedableRng;
+    use std::collections::VecDeque;
+
+    // Breadth-first search over the points covered so far.
+    fn brute(segments: &[(i64, i64)], (a, b): (i64, i64)) -> Option<usize> {
+        if b <= a {
+            return Some(0);
+        }
+        let mut points = segments
+            .iter()
+            .flat_map(|&(l, r)| [l, r])
+            .chain([a, b])
+            .collect::<Vec<_>>();
+        points.sort_unstable();
+        points.dedup();
+        let mut dist = vec![usize::MAX; points.len()];
+        let start = points.binary_search(&a).unwrap();
+        dist[start] = 0;
+        let mut queue = VecDeque::from(vec![start]);
+        while let Some(i) = queue.pop_front() {
+            if b <= points[i] {
+                return Some(dist[i]);
+            }
+            for &(l, r) in segments {
+                let j = points.binary_search(&r).unwrap();
+                if l <= points[i] && points[i] < r && dist[j] == usize::MAX {
+                    dist[j] = dist[i] + 1;
+                    queue.push_back(j);
+                }
+            }
+        }
+        None
+    }
+
+    fn random_segments(rng: &mut StdRng, n: usize, max: i64) -> Vec<(i64, i64)> {
+        (0..n)
+            .map(|_| {
+                let l = rng.gen_range(-max..=max);
+                let r = if rng.gen_ratio(1, 10) {
+                    // Empty or reversed
+                    rng.gen_range(-max..=l)
+                } else {
+                    rng.gen_range(l..=max)
+                };
+                (l, r)
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_random() {
+        let mut rng = StdRng::seed_from_u64(42);
+        for _ in 0..300 {
+            let n = rng.gen_range(0..=20);
+            let max = rng.gen_range(1..=20);
+            let segments = random_segments(&mut rng, n, max);
+            let jump = CoverageJump::new(&segments);
+            let mut targets = (0..30)
+                .map(|_| {
+                    (
+                        rng.gen_range(-max - 1..=max + 1),
+                        rng.gen_range(-max - 1..=max + 1),
+                    )
+                })
+                .collect::<Vec<_>>();
+            // Queries matching a single segment
+            targets.extend(segments.iter().copied());
+            for target in targets {
+                let expected = brute(&segments, target);
+                assert_eq!(min_segments_to_cover(&segments, target), expected);
+                assert_eq!(jump.query(target), expected);
+            }
+        }
+    }
+
+    #[test]
+    fn test_touching() {
+        let segments = (0..100).map(|i| (i, i + 1)).collect::<Vec<_>>();
+        let jump = CoverageJump::new(&segments);
+        for a in -1..=101 {
+            for b in a..=101 {
+                let expected = (0 <= a && b <= 100).then_some((b - a) as usize);
+                let expected = if a == b { Some(0) } else { expected };
+                assert_eq!(min_segments_to_cover(&segments, (a, b)), expected);
+                assert_eq!(jump.query((a, b)), expected);
+            }
+        }
+        // A gap of a single point
+        let segments = [(0, 3), (4, 6)];
+        let jump = CoverageJump::new(&segments);
+        assert_eq!(jump.query((0, 3)), Some(1));
+        assert_eq!(jump.query((0, 4)), None);
+        assert_eq!(jump.query((3, 4)), None);
+        assert_eq!(jump.query((4, 6)), Some(1));
+    }
+
+    #[test]
+    fn test_empty() {
+        let jump = CoverageJump::new(&[]);
+        assert_eq!(jump.query((0, 0)), Some(0));
+        assert_eq!(jump.query((0, 1)), None);
+        assert_eq!(min_segments_to_cover(&[], (3, 1)), Some(0));
+        assert_eq!(min_segments_to_cover(&[(1, 1)], (1, 2)), None);
+    }
+}
@@ -11,16 +11,22 @@
 //!
 //! # Modifier APIs
 //!
-//! While [`Segtree`], [`SparseSegtree`], and [`Dense2dSegtree`] have `entry` API, [`Sparse2dSegtree`] does not have it.
+//! While [`Segtree`], [`CircularSegtree`], [`SparseSegtree`], and [`Dense2dSegtree`] have `entry` API, [`Sparse2dSegtree`] does not have it.
 //! Instead, it has `apply` API. You can apply a function $f$ that satisfies $f(x \cdot y) = x \cdot f(y)$ to a single element..
+//!
+//! [`PersistentSegtree`] is immutable, and its `set` returns a new version instead.
 
 use core::fmt;
+use open::resolve_range;
 use std::collections::BTreeMap;
+use std::iter::repeat_with;
 use std::iter::FromIterator;
 use std::ops::Deref;
 use std::ops::DerefMut;
 use std::ops::Index;
+use std::ops::Range;
 use std::ops::RangeBounds;
+use std::rc::Rc;
 
 /// A trait for segment tree operations.
 pub trait Op {
@@ -39,33 +45,58 @@ pub struct Segtree<O: Op> {
 }
 impl<O: Op> Segtree<O> {
     /// Constructs a new segment tree with the specified length.
-    pub fn from_len(n: usize) -> Self
-    where
-        O::Value: Clone,
-    {
-        Self::new(&vec![O::identity(); n])
+    pub fn from_len(n: usize) -> Self {
+        Self::new((0..n).map(|_| O::identity()))
     }
 
-    /// Constructs with the specified values.
-    pub fn new(values: &[O::Value]) -> Self
+    /// Constructs with the values of an [`ExactSizeIterator`].
+    ///
+    /// The leaves are built directly from the iterator, and then the internal nodes.
+    pub fn new<I>(iter: I) -> Self
     where
-        O::Value: Clone,
+        I: IntoIterator<Item = O::Value>,
+        I::IntoIter: ExactSizeIterator,
     {
-        let values_ = values;
-        let n = values.len();
-        let mut values = vec![O::identity(); 2 * n];
-        values[n..].clone_from_slice(values_);
+        let iter = iter.into_iter();
+        let n = iter.len();
+        let mut values = repeat_with(O::identity)
+            .take(n)
+            .chain(iter)
+            .collect::<Vec<_>>();
+        assert_eq!(
+            values.len(),
+            2 * n,
+            "the iterator reported a wrong length {}",
+            n
+        );
         for i in (1..n).rev() {
             values[i] = O::op(&values[i * 2], &values[i * 2 + 1]);
         }
         Self { values }
     }
 
+    /// Constructs with the specified values.
+    pub fn from_slice(values: &[O::Value]) -> Self
+    where
+        O::Value: Clone,
+    {
+        Self::new(values.iter().cloned())
+    }
+
+    /// Returns the number of the elements.
+    pub fn len(&self) -> usize {
+        self.values.len() / 2
+    }
+
+    /// Returns `true` if there are no elements.
+    pub fn is_empty(&self) -> bool {
+        self.values.is_empty()
+    }
+
     /// Returns $x_l \cdot x_{l+1} \cdot \ldots \cdot x_{r-1}$.
     pub fn fold<R: RangeBounds<usize>>(&self, range: R) -> O::Value {
         let n = self.values.len() / 2;
-        let (mut start, mut end) = open(range, n);
-        assert!(start <= end && end <= n);
+        let Range { mut start, mut end } = resolve_range(n, range);
         start += n;
         end += n;
         let mut left = O::identity();
@@ -85,15 +116,201 @@ impl<O: Op> Segtree<O> {
         O::op(&left, &right)
     }
 
+    /// Returns the largest $r$ such that `pred` holds for $x_l \cdot \ldots \cdot x_{r-1}$.
+    ///
+    /// `pred` must hold for $e$, and be monotone: once it fails, it fails for any longer range.
+    /// If it holds up to the end, it returns $n$.
+    ///
+    /// # Panics
+    ///
+    /// - If $l > n$.
+    /// - If `pred` does not hold for $e$.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use segtree::Op;
+    /// use segtree::Segtree;
+    /// enum O {}
+    /// impl Op for O {
+    ///     type Value = u64;
+    ///
+    ///     fn identity() -> u64 {
+    ///         0
+    ///     }
+    ///
+    ///     fn op(lhs: &u64, rhs: &u64) -> u64 {
+    ///         lhs + rhs
+    ///     }
+    /// }
+    /// let segtree = Segtree::<O>::from_slice(&[3, 1, 4, 1, 5]);
+    /// assert_eq!(segtree.max_right(1, |&x| x <= 5), 3);
+    /// assert_eq!(segtree.max_right(1, |&x| x <= 100), 5);
+    /// assert_eq!(segtree.max_right(2, |&x| x < 4), 2);
+    /// ```
+    pub fn max_right(&self, start: usize, mut pred: impl FnMut(&O::Value) -> bool) -> usize {
+        let n = self.values.len() / 2;
+        let Range { start, end } = resolve_range(n, start..);
+        assert!(
+            pred(&O::identity()),
+            "the predicate must hold for the identity"
+        );
+        // The nodes covering `start..end` from left to right.
+        let mut nodes = Vec::new();
+        let mut rights = Vec::new();
+        let (mut l, mut r) = (start + n, end + n);
+        while l < r {
+            if l % 2 == 1 {
+                nodes.push(l);
+                l += 1;
+            }
+            if r % 2 == 1 {
+                r -= 1;
+                rights.push(r);
+            }
+            l /= 2;
+            r /= 2;
+        }
+        nodes.extend(rights.into_iter().rev());
+        let mut acc = O::identity();
+        for mut node in nodes {
+            let next = O::op(&acc, &self.values[node]);
+            if pred(&next) {
+                acc = next;
+                continue;
+            }
+            while node < n {
+                node *= 2;
+                let next = O::op(&acc, &self.values[node]);
+                if pred(&next) {
+                    acc = next;
+                    node += 1;
+                }
+            }
+            return node - n;
+        }
+        n
+    }
+
+    /// Returns the smallest $l$ such that `pred` holds for $x_l \cdot \ldots \cdot x_{r-1}$.
+    ///
+    /// `pred` must hold for $e$, and be monotone: once it fails, it fails for any longer range.
+    /// If it holds down to the beginning, it returns $0$.
+    ///
+    /// # Panics
+    ///
+    /// - If $r > n$.
+    /// - If `pred` does not hold for $e$.
+    pub fn min_left(&self, end: usize, mut pred: impl FnMut(&O::Value) -> bool) -> usize {
+        let n = self.values.len() / 2;
+        let Range { start, end } = resolve_range(n, ..end);
+        assert!(
+            pred(&O::identity()),
+            "the predicate must hold for the identity"
+        );
+        // The nodes covering `start..end` from right to left.
+        let mut nodes = Vec::new();
+        let mut lefts = Vec::new();
+        let (mut l, mut r) = (start + n, end + n);
+        while l < r {
+            if l % 2 == 1 {
+                lefts.push(l);
+                l += 1;
+            }
+            if r % 2 == 1 {
+                r -= 1;
+                nodes.push(r);
+            }
+            l /= 2;
+            r /= 2;
+        }
+        nodes.extend(lefts.into_iter().rev());
+        let mut acc = O::identity();
+        for mut node in nodes {
+            let next = O::op(&self.values[node], &acc);
+            if pred(&next) {
+                acc = next;
+                continue;
+            }
+            while node < n {
+                node = node * 2 + 1;
+                let next = O::op(&self.values[node], &acc);
+                if pred(&next) {
+                    acc = next;
+                    node -= 1;
+                }
+            }
+            return node - n + 1;
+        }
+        0
+    }
+
+    /// Returns the first $i \ge l$ such that `pred` fails for $x_l \cdot \ldots \cdot x_i$, or
+    /// `None` if it holds up to the end.
+    ///
+    /// The same as [`Segtree::max_right`] except that the end is distinguished.
+    pub fn search_forward(
+        &self,
+        start: usize,
+        pred: impl FnMut(&O::Value) -> bool,
+    ) -> Option<usize> {
+        let i = self.max_right(start, pred);
+        (i < self.len()).then_some(i)
+    }
+
+    /// Returns the last $i < r$ such that `pred` fails for $x_i \cdot \ldots \cdot x_{r-1}$, or
+    /// `None` if it holds down to the beginning.
+    ///
+    /// The same as [`Segtree::min_left`] except that the beginning is distinguished.
+    pub fn search_backward(
+        &self,
+        end: usize,
+        pred: impl FnMut(&O::Value) -> bool,
+    ) -> Option<usize> {
+        let i = self.min_left(end, pred);
+        i.checked_sub(1)
+    }
+
     /// Returns the entry of $x_i$.
+    ///
+    /// The ancestors are recomputed when the entry is dropped.
+    ///
+    /// # Panics
+    ///
+    /// If `index` is out of range.
     pub fn entry(&mut self, index: usize) -> Entry<O> {
         let n = self.values.len() / 2;
+        if n <= index {
+            segtree_index_fail(index, n);
+        }
         Entry {
             segtree: self,
             index: n + index,
         }
     }
 
+    /// Returns $x_i$.
+    ///
+    /// # Panics
+    ///
+    /// If `index` is out of range.
+    pub fn get(&self, index: usize) -> &O::Value {
+        let n = self.values.len() / 2;
+        if n <= index {
+            segtree_index_fail(index, n);
+        }
+        &self.values[n + index]
+    }
+
+    /// Updates $x_i$ by `f` and recomputes the ancestors.
+    ///
+    /// # Panics
+    ///
+    /// If `index` is out of range.
+    pub fn update_with(&mut self, index: usize, f: impl FnOnce(&mut O::Value)) {
+        f(&mut self.entry(index));
+    }
+
     /// Returns an iterator of $x_0, x_1, \ldots, x_{n-1}$.
     pub fn iter(&self) -> impl Iterator<Item = &O::Value> {
         self.values[self.values.len() / 2..].iter()
@@ -116,12 +333,15 @@ where
     }
 }
 
-impl<O: Op> FromIterator<O::Value> for Segtree<O>
-where
-    O::Value: Clone,
-{
+impl<O: Op> FromIterator<O::Value> for Segtree<O> {
     fn from_iter<I: IntoIterator<Item = O::Value>>(iter: I) -> Self {
-        Self::new(&iter.into_iter().collect::<Vec<_>>())
+        Self::new(iter.into_iter().collect::<Vec<_>>())
+    }
+}
+
+impl<O: Op> From<Vec<O::Value>> for Segtree<O> {
+    fn from(values: Vec<O::Value>) -> Self {
+        Self::new(values)
     }
 }
 
@@ -129,10 +349,16 @@ impl<O: Op> Index<usize> for Segtree<O> {
     type Output = O::Value;
 
     fn index(&self, index: usize) -> &Self::Output {
-        &self.values[self.values.len() / 2 + index]
+        self.get(index)
     }
 }
 
+#[cold]
+#[inline(never)]
+fn segtree_index_fail(index: usize, len: usize) -> ! {
+    panic!("index {} out of range for length {}", index, len);
+}
+
 /// The result of [`Segtree::entry`].
 pub struct Entry<'a, O: Op> {
     segtree: &'a mut Segtree<O>,
@@ -140,8 +366,9 @@ pub struct Entry<'a, O: Op> {
 }
 impl<'a, O: Op> Drop for Entry<'a, O> {
     fn drop(&mut self) {
+        // The root is 1, and 0 is not a node.
         let mut index = self.index;
-        while index != 0 {
+        while index > 1 {
             index /= 2;
             self.segtree.values[index] = O::op(
                 &self.segtree.values[index * 2],
@@ -171,6 +398,390 @@ where
     }
 }
 
+/// A segment tree on a circle, which can be rotated in $O(1)$.
+///
+/// The indices are logical: after [`rotate`](Self::rotate)`(k)`, the index $i$ refers to the
+/// element that had the index $(i + k) \bmod n$, like [`slice::rotate_left`].
+///
+/// # Examples
+///
+/// ```
+/// use segtree::CircularSegtree;
+/// use segtree::Op;
+///
+/// enum O {}
+/// impl Op for O {
+///     type Value = String;
+///
+///     fn identity() -> Self::Value {
+///         String::new()
+///     }
+///
+///     fn op(lhs: &Self::Value, rhs: &Self::Value) -> Self::Value {
+///         lhs.to_owned() + rhs
+///     }
+/// }
+///
+/// let values = ["a", "b", "c", "d"].map(String::from);
+/// let mut seg = CircularSegtree::<O>::new(&values);
+/// assert_eq!(seg.fold_cyclic(3, 3), "dab");
+/// seg.rotate(1);
+/// assert_eq!(seg.fold_cyclic(0, 4), "bcda");
+/// *seg.entry(3) = "x".to_owned();
+/// assert_eq!(seg.fold_cyclic(2, 3), "dxb");
+/// ```
+pub struct CircularSegtree<O: Op> {
+    segtree: Segtree<O>,
+    offset: usize,
+}
+impl<O: Op> CircularSegtree<O> {
+    /// Constructs with the specified values.
+    pub fn new(values: &[O::Value]) -> Self
+    where
+        O::Value: Clone,
+    {
+        Self {
+            segtree: Segtree::from_slice(values),
+            offset: 0,
+        }
+    }
+
+    /// Returns the number of the elements.
+    pub fn len(&self) -> usize {
+        self.segtree.values.len() / 2
+    }
+
+    /// Returns `true` if there are no elements.
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Rotates the circle to the left by `k` in $O(1)$.
+    pub fn rotate(&mut self, k: usize) {
+        if !self.is_empty() {
+            self.offset = (self.offset + k % self.len()) % self.len();
+        }
+    }
+
+    /// Returns $x_s \cdot x_{s+1} \cdot \ldots \cdot x_{s+l-1}$, where the indices are taken modulo
+    /// $n$.
+    ///
+    /// # Panics
+    ///
+    /// If $l > n$.
+    pub fn fold_cyclic(&self, start: usize, len: usize) -> O::Value {
+        let n = self.len();
+        if n < len {
+            circular_segtree_len_fail(len, n);
+        }
+        if len == 0 {
+            return O::identity();
+        }
+        let start = (self.offset + start % n) % n;
+        if start + len <= n {
+            self.segtree.fold(start..start + len)
+        } else {
+            O::op(
+                &self.segtree.fold(start..),
+                &self.segtree.fold(..start + len - n),
+            )
+        }
+    }
+
+    /// Returns the entry of $x_i$.
+    pub fn entry(&mut self, index: usize) -> Entry<O> {
+        let index = self.physical(index);
+        self.segtree.entry(index)
+    }
+
+    /// Returns an iterator of $x_0, x_1, \ldots, x_{n-1}$.
+    pub fn iter(&self) -> impl Iterator<Item = &O::Value> {
+        let (head, tail) = self.segtree.as_slice().split_at(self.offset);
+        tail.iter().chain(head)
+    }
+
+    fn physical(&self, index: usize) -> usize {
+        let n = self.len();
+        if n <= index {
+            segtree_index_fail(index, n);
+        }
+        (self.offset + index) % n
+    }
+}
+
+impl<O: Op> fmt::Debug for CircularSegtree<O>
+where
+    O::Value: fmt::Debug,
+{
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_list().entries(self.iter()).finish()
+    }
+}
+
+impl<O: Op> Index<usize> for CircularSegtree<O> {
+    type Output = O::Value;
+
+    fn index(&self, index: usize) -> &Self::Output {
+        &self.segtree[self.physical(index)]
+    }
+}
+
+#[cold]
+#[inline(never)]
+fn circular_segtree_len_fail(len: usize, n: usize) -> ! {
+    panic!("cyclic range of length {} is longer than {}", len, n);
+}
+
+/// A persistent segment tree.
+///
+/// [`PersistentSegtree::set`] copies the $O(\log n)$ nodes on the path to the leaf and shares the
+/// rest with the old version, which stays valid. Thus $q$ updates take $O(n + q \log n)$ memory
+/// in total. Cloning a version is $O(1)$.
+///
+/// # Examples
+///
+/// ```
+/// use segtree::Op;
+/// use segtree::PersistentSegtree;
+///
+/// enum O {}
+/// impl Op for O {
+///     type Value = i32;
+///
+///     fn identity() -> i32 {
+///         0
+///     }
+///
+///     fn op(lhs: &i32, rhs: &i32) -> i32 {
+///         lhs + rhs
+///     }
+/// }
+///
+/// let v0 = PersistentSegtree::<O>::from_slice(&[1, 2, 3, 4]);
+/// let v1 = v0.set(1, 20);
+/// let v2 = v1.set(3, 40);
+/// assert_eq!(v0.fold(..), 10);
+/// assert_eq!(v1.fold(..), 28);
+/// assert_eq!(v2.fold(1..), 63);
+/// assert_eq!(v0.get(1), &2);
+/// ```
+pub struct PersistentSegtree<O: Op> {
+    root: Option<Rc<PersistentNode<O::Value>>>,
+    len: usize,
+}
+struct PersistentNode<T> {
+    value: T,
+    // `None` for the leaves.
+    children: Option<[Rc<PersistentNode<T>>; 2]>,
+}
+impl<O: Op> PersistentSegtree<O> {
+    /// Constructs with the values of an [`ExactSizeIterator`].
+    pub fn new<I>(iter: I) -> Self
+    where
+        I: IntoIterator<Item = O::Value>,
+        I::IntoIter: ExactSizeIterator,
+    {
+        fn build<O: Op>(
+            iter: &mut impl Iterator<Item = O::Value>,
+            len: usize,
+        ) -> Rc<PersistentNode<O::Value>> {
+            if len == 1 {
+                let value = iter.next().expect("the iterator reported a wrong length");
+                return Rc::new(PersistentNode {
+                    value,
+                    children: None,
+                });
+            }
+            let left = build::<O>(iter, len / 2);
+            let right = build::<O>(iter, len - len / 2);
+            Rc::new(PersistentNode {
+                value: O::op(&left.value, &right.value),
+                children: Some([left, right]),
+            })
+        }
+        let mut iter = iter.into_iter();
+        let len = iter.len();
+        let root = (len != 0).then(|| build::<O>(&mut iter, len));
+        assert!(
+            iter.next().is_none(),
+            "the iterator reported a wrong length {}",
+            len
+        );
+        Self { root, len }
+    }
+
+    /// Constructs with the specified values.
+    pub fn from_slice(values: &[O::Value]) -> Self
+    where
+        O::Value: Clone,
+    {
+        Self::new(values.iter().cloned())
+    }
+
+    /// Returns the number of the elements.
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    /// Returns `true` if there are no elements.
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    /// Returns $x_l \cdot x_{l+1} \cdot \ldots \cdot x_{r-1}$ of this version.
+    pub fn fold<R: RangeBounds<usize>>(&self, range: R) -> O::Value {
+        fn fold<O: Op>(
+            node: &PersistentNode<O::Value>,
+            len: usize,
+            start: usize,
+            end: usize,
+        ) -> O::Value {
+            if start == 0 && end == len {
+                // A copy without `Clone`.
+                return O::op(&O::identity(), &node.value);
+            }
+            let [left, right] = node.children.as_ref().unwrap();
+            let mid = len / 2;
+            if end <= mid {
+                fold::<O>(left, mid, start, end)
+            } else if mid <= start {
+                fold::<O>(right, len - mid, start - mid, end - mid)
+            } else {
+                O::op(
+                    &fold::<O>(left, mid, start, mid),
+                    &fold::<O>(right, len - mid, 0, end - mid),
+                )
+            }
+        }
+        let Range { start, end } = resolve_range(self.len, range);
+        match &self.root {
+            Some(root) if start < end => fold::<O>(root, self.len, start, end),
+            _ => O::identity(),
+        }
+    }
+
+    /// Returns $x_i$ of this version.
+    ///
+    /// # Panics
+    ///
+    /// If `index` is out of range.
+    pub fn get(&self, index: usize) -> &O::Value {
+        if self.len <= index {
+            segtree_index_fail(index, self.len);
+        }
+        let mut node = self.root.as_ref().unwrap();
+        let (mut len, mut index) = (self.len, index);
+        while let Some([left, right]) = &node.children {
+            let mid = len / 2;
+            if index < mid {
+                node = left;
+                len = mid;
+            } else {
+                node = right;
+                len -= mid;
+                index -= mid;
+            }
+        }
+        &node.value
+    }
+
+    /// Returns a new version with $x_i$ replaced by `value`, leaving this version as it is.
+    ///
+    /// # Panics
+    ///
+    /// If `index` is out of range.
+    pub fn set(&self, index: usize, value: O::Value) -> Self {
+        fn set<O: Op>(
+            node: &PersistentNode<O::Value>,
+            len: usize,
+            index: usize,
+            value: O::Value,
+        ) -> Rc<PersistentNode<O::Value>> {
+            let Some([left, right]) = &node.children else {
+                return Rc::new(PersistentNode {
+                    value,
+                    children: None,
+                });
+            };
+            let mid = len / 2;
+            let (left, right) = if index < mid {
+                (set::<O>(left, mid, index, value), Rc::clone(right))
+            } else {
+                (
+                    Rc::clone(left),
+                    set::<O>(right, len - mid, index - mid, value),
+                )
+            };
+            Rc::new(PersistentNode {
+                value: O::op(&left.value, &right.value),
+                children: Some([left, right]),
+            })
+        }
+        if self.len <= index {
+            segtree_index_fail(index, self.len);
+        }
+        Self {
+            root: Some(set::<O>(
+                self.root.as_ref().unwrap(),
+                self.len,
+                index,
+                value,
+            )),
+            len: self.len,
+        }
+    }
+
+    /// Returns an iterator of $x_0, x_1, \ldots, x_{n-1}$ of this version.
+    pub fn iter(&self) -> impl Iterator<Item = &O::Value> {
+        fn collect<'a, T>(node: &'a PersistentNode<T>, out: &mut Vec<&'a T>) {
+            match &node.children {
+                None => out.push(&node.value),
+                Some([left, right]) => {
+                    collect(left, out);
+                    collect(right, out);
+                }
+            }
+        }
+        let mut out = Vec::with_capacity(self.len);
+        if let Some(root) = &self.root {
+            collect(root, &mut out);
+        }
+        out.into_iter()
+    }
+}
+
+impl<O: Op> Clone for PersistentSegtree<O> {
+    fn clone(&self) -> Self {
+        Self {
+            root: self.root.clone(),
+            len: self.len,
+        }
+    }
+}
+
+impl<O: Op> fmt::Debug for PersistentSegtree<O>
+where
+    O::Value: fmt::Debug,
+{
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_list().entries(self.iter()).finish()
+    }
+}
+
+impl<O: Op> FromIterator<O::Value> for PersistentSegtree<O> {
+    fn from_iter<T: IntoIterator<Item = O::Value>>(iter: T) -> Self {
+        Self::new(iter.into_iter().collect::<Vec<_>>())
+    }
+}
+
+impl<O: Op> Index<usize> for PersistentSegtree<O> {
+    type Output = O::Value;
+
+    fn index(&self, index: usize) -> &Self::Output {
+        self.get(index)
+    }
+}
+
 /// A sparse (compressed) segment tree.
 pub struct SparseSegtree<K, O: Op> {
     inner: Segtree<O>,
@@ -187,7 +798,7 @@ impl<K: Ord, O: Op> SparseSegtree<K, O> {
         keys.sort();
         let values = kv.iter().map(|(_, v)| v.clone()).collect::<Vec<_>>();
         Self {
-            inner: Segtree::new(&values),
+            inner: Segtree::from(values),
             keys,
         }
     }
@@ -438,14 +1049,27 @@ impl<O: Op> Dense2dSegtree<O> {
         Self { values }
     }
 
+    /// Constructs a new segment tree of $h$ rows and $w$ columns filled with $e$.
+    pub fn from_len(h: usize, w: usize) -> Self {
+        Self {
+            values: (0..2 * h)
+                .map(|_| (0..2 * w).map(|_| O::identity()).collect())
+                .collect(),
+        }
+    }
+
     /// Fold $\left \lbrace x_{i, j} \mid i \in \text{{range}}_i, j \in \text{{range}}_j \right \rbrace$.
     pub fn fold(&self, i: impl RangeBounds<usize>, j: impl RangeBounds<usize>) -> O::Value {
         let h = self.values.len() / 2;
         let w = self.values.get(0).map_or(0, |v| v.len() / 2);
-        let (mut i0, mut i1) = open(i, h);
-        assert!(i0 <= i1 && i1 <= h);
-        let (mut j0, mut j1) = open(j, w);
-        assert!(j0 <= j1 && j1 <= w);
+        let Range {
+            start: mut i0,
+            end: mut i1,
+        } = resolve_range(h, i);
+        let Range {
+            start: mut j0,
+            end: mut j1,
+        } = resolve_range(w, j);
         i0 += h;
         i1 += h;
         j0 += w;
@@ -494,9 +1118,19 @@ impl<O: Op> Dense2dSegtree<O> {
     }
 
     /// Returns the entry of $x_{i, j}$.
+    ///
+    /// # Panics
+    ///
+    /// If `i` or `j` is out of range.
     pub fn entry(&mut self, i: usize, j: usize) -> Dense2dEntry<O> {
         let h = self.values.len() / 2;
         let w = self.values.get(0).map_or(0, |v| v.len() / 2);
+        if h <= i {
+            segtree_index_fail(i, h);
+        }
+        if w <= j {
+            segtree_index_fail(j, w);
+        }
         Dense2dEntry {
             segtree: self,
             i: h + i,
@@ -504,6 +1138,32 @@ impl<O: Op> Dense2dSegtree<O> {
         }
     }
 
+    /// Returns $x_{i, j}$.
+    ///
+    /// # Panics
+    ///
+    /// If `i` or `j` is out of range.
+    pub fn get(&self, i: usize, j: usize) -> &O::Value {
+        let h = self.values.len() / 2;
+        let w = self.values.get(0).map_or(0, |v| v.len() / 2);
+        if h <= i {
+            segtree_index_fail(i, h);
+        }
+        if w <= j {
+            segtree_index_fail(j, w);
+        }
+        &self.values[h + i][w + j]
+    }
+
+    /// Replaces $x_{i, j}$ with `value` and recomputes the ancestors.
+    ///
+    /// # Panics
+    ///
+    /// If `i` or `j` is out of range.
+    pub fn set(&mut self, i: usize, j: usize, value: O::Value) {
+        *self.entry(i, j) = value;
+    }
+
     /// Returns an iterator that returns the rows $(x_{i, 0}, x_{i, 1}, \ldots, x_{i, w-1})$.
     pub fn iter(&self) -> impl Iterator<Item = &[O::Value]> {
         self.values[self.values.len() / 2..]
@@ -535,7 +1195,12 @@ impl<O: Op> Index<usize> for Dense2dSegtree<O> {
     type Output = [O::Value];
 
     fn index(&self, index: usize) -> &Self::Output {
-        &self.values[self.values.len() / 2 + index]
+        let h = self.values.len() / 2;
+        if h <= index {
+            segtree_index_fail(index, h);
+        }
+        let row = &self.values[h + index];
+        &row[row.len() / 2..]
     }
 }
 
@@ -583,21 +1248,6 @@ impl<'a, O: Op> DerefMut for Dense2dEntry<'a, O> {
     }
 }
 
-fn open<B: RangeBounds<usize>>(bounds: B, n: usize) -> (usize, usize) {
-    use std::ops::Bound;
-    let start = match bounds.start_bound() {
-        Bound::Unbounded => 0,
-        Bound::Included(&x) => x,
-        Bound::Excluded(&x) => x + 1,
-    };
-    let end = match bounds.end_bound() {
-        Bound::Unbounded => n,
-        Bound::Included(&x) => x + 1,
-        Bound::Excluded(&x) => x,
-    };
-    (start, end)
-}
-
 fn open_key<K: Ord, B: RangeBounds<K>>(bounds: B, keys: &[K]) -> (usize, usize) {
     use std::ops::Bound;
     let start = match bounds.start_bound() {
@@ -650,6 +1300,38 @@ mod tests {
         }
     }
 
+    mod sum {
+        use super::*;
+        pub enum O {}
+        impl Op for O {
+            type Value = u64;
+
+            fn identity() -> Self::Value {
+                0
+            }
+
+            fn op(lhs: &Self::Value, rhs: &Self::Value) -> Self::Value {
+                lhs + rhs
+            }
+        }
+    }
+
+    mod max {
+        use super::*;
+        pub enum O {}
+        impl Op for O {
+            type Value = u64;
+
+            fn identity() -> Self::Value {
+                0
+            }
+
+            fn op(lhs: &Self::Value, rhs: &Self::Value) -> Self::Value {
+                *lhs.max(rhs)
+            }
+        }
+    }
+
     mod xor {
         use super::*;
         pub enum O {}
@@ -678,7 +1360,7 @@ mod tests {
             let mut vec = repeat_with(|| (rng.gen_range(0..BASE), BASE))
                 .take(n)
                 .collect::<Vec<_>>();
-            let mut segtree = Segtree::<O>::new(&vec);
+            let mut segtree = Segtree::<O>::from_slice(&vec);
             for _ in 0..q {
                 match rng.gen_range(0..2) {
                     // fold
@@ -707,9 +1389,9 @@ mod tests {
     fn test_segtree_usability() {
         use rolling_hash::O;
         let _ = Segtree::<O>::from_len(1);
-        let _ = Segtree::<O>::new(&[(0, 1)]);
+        let _ = Segtree::<O>::from_slice(&[(0, 1)]);
         let _ = Segtree::<O>::from_iter(vec![(0, 1)]);
-        let mut segtree = Segtree::<O>::new(&[(0, 1)]);
+        let mut segtree = Segtree::<O>::from_slice(&[(0, 1)]);
         let _ = segtree.fold(0..1);
         let _ = segtree.entry(0);
         assert_eq!(segtree.as_slice()[0], (0, 1));
@@ -866,6 +1548,68 @@ mod tests {
         }
     }
 
+    #[test]
+    fn test_dense_2d_segtree_max() {
+        use max::O;
+
+        // A non-invertible operation, starting from `from_len`.
+        let mut rng = StdRng::seed_from_u64(42);
+        for _ in 0..50 {
+            let h = rng.gen_range(1..=10);
+            let w = rng.gen_range(1..=10);
+            let mut vec = vec![vec![0; w]; h];
+            let mut segtree = Dense2dSegtree::<O>::from_len(h, w);
+            for _ in 0..100 {
+                if rng.gen() {
+                    let i = rng.gen_range(0..h);
+                    let j = rng.gen_range(0..w);
+                    let x = rng.gen_range(1..1000);
+                    vec[i][j] = x;
+                    segtree.set(i, j, x);
+                } else {
+                    // Often empty in one of the dimensions.
+                    let i = random_range(&mut rng, h);
+                    let j = random_range(&mut rng, w);
+                    let expected = vec[i.clone()]
+                        .iter()
+                        .flat_map(|v| v[j.clone()].iter())
+                        .fold(O::identity(), |acc, x| O::op(&acc, x));
+                    assert_eq!(segtree.fold(i, j), expected);
+                }
+            }
+            assert_eq!(segtree.collect_vec(), vec);
+            for (i, row) in vec.iter().enumerate() {
+                assert_eq!(&segtree[i], row.as_slice());
+                for (j, x) in row.iter().enumerate() {
+                    assert_eq!(segtree.get(i, j), x);
+                }
+            }
+            assert_eq!(segtree.fold(0..0, ..), 0);
+            assert_eq!(segtree.fold(.., w..w), 0);
+        }
+    }
+
+    #[test]
+    #[should_panic(expected = "index 2 out of range for length 2")]
+    fn test_dense_2d_set_out_of_range() {
+        use max::O;
+        Dense2dSegtree::<O>::from_len(4, 2).set(1, 2, 1);
+    }
+
+    #[test]
+    #[should_panic(expected = "index 4 out of range for length 4")]
+    fn test_dense_2d_get_out_of_range() {
+        use max::O;
+        Dense2dSegtree::<O>::from_len(4, 2).get(4, 0);
+    }
+
+    #[test]
+    #[should_panic(expected = "index 4 out of range for length 4")]
+    fn test_dense_2d_index_out_of_range() {
+        use max::O;
+        let _ = &Dense2dSegtree::<O>::from_len(4, 2)[4];
+    }
+
     #[test]
     fn test_dense_2d_segtree_usability() {
         use xor::O;
@@ -876,6 +1620,495 @@ mod tests {
         assert_eq!(segtree[0][0], 0);
     }
 
+    #[test]
+    #[should_panic(expected = "range starts at 3 but ends at 2")]
+    fn test_fold_reversed_range() {
+        use xor::O;
+        let (start, end) = (3, 2);
+        Segtree::<O>::from_slice(&[0; 5]).fold(start..end);
+    }
+
+    #[test]
+    #[should_panic(expected = "range end index 6 out of range for length 5")]
+    fn test_fold_out_of_range() {
+        use xor::O;
+        Segtree::<O>::from_slice(&[0; 5]).fold(..=5);
+    }
+
+    #[test]
+    #[should_panic(expected = "attempted to index from after maximum usize")]
+    fn test_fold_excluded_usize_max() {
+        use std::ops::Bound;
+        use xor::O;
+        Segtree::<O>::from_slice(&[0; 5]).fold((Bound::Excluded(usize::MAX), Bound::Unbounded));
+    }
+
+    #[test]
+    #[should_panic(expected = "range end index 3 out of range for length 2")]
+    fn test_dense_2d_fold_out_of_range() {
+        use xor::O;
+        let values = vec![vec![0; 2]; 4];
+        Dense2dSegtree::<O>::new(&values).fold(.., 1..3);
+    }
+
+    #[test]
+    fn test_constructors() {
+        use concat::O;
+
+        // `String` is not `Copy`, and `new` moves the values into the leaves.
+        let values = ["a", "b", "c", "d", "e"].map(String::from);
+        let expected = Segtree::<O>::from_slice(&values);
+        let from_iter = values.iter().cloned().collect::<Segtree<O>>();
+        let from_vec = Segtree::<O>::from(values.to_vec());
+        let new = Segtree::<O>::new(values);
+        for segtree in [from_iter, from_vec, new] {
+            assert_eq!(segtree.len(), 5);
+            assert!(!segtree.is_empty());
+            assert_eq!(segtree.as_slice(), expected.as_slice());
+            for start in 0..=5 {
+                for end in start..=5 {
+                    assert_eq!(segtree.fold(start..end), expected.fold(start..end));
+                }
+            }
+        }
+        let empty = Segtree::<O>::new(Vec::new());
+        assert!(empty.is_empty());
+        assert_eq!(empty.len(), 0);
+        assert_eq!(empty.fold(..), "");
+        assert_eq!(Segtree::<O>::from_len(3).fold(..), "");
+        assert_eq!(Segtree::<O>::from_len(3).len(), 3);
+    }
+
+    #[test]
+    fn test_small() {
+        use concat::O;
+
+        // Every method for n = 0, 1, 2 against `Vec`.
+        for n in 0..=2 {
+            let mut vec = (0..n).map(|i| i.to_string()).collect::<Vec<_>>();
+            let mut segtree = Segtree::<O>::from_slice(&vec);
+            assert_eq!(segtree.len(), n);
+            assert_eq!(segtree.is_empty(), n == 0);
+            for step in 0..=n {
+                for start in 0..=n {
+                    for end in start..=n {
+                        assert_eq!(segtree.fold(start..end), vec[start..end].concat());
+                    }
+                }
+                assert_eq!(segtree.fold(..), vec.concat());
+                assert_eq!(segtree.iter().cloned().collect::<Vec<_>>(), vec);
+                for (i, x) in vec.iter().enumerate() {
+                    assert_eq!(segtree.get(i), x);
+                    assert_eq!(&segtree[i], x);
+                }
+                if step < n {
+                    *segtree.entry(step) += "x";
+                    vec[step] += "x";
+                    segtree.update_with(n - 1 - step, |x| x.insert(0, 'y'));
+                    vec[n - 1 - step].insert(0, 'y');
+                }
+            }
+            // The unused slot 0 is never touched.
+            assert_eq!(segtree.values.first().map_or("", |x| x.as_str()), "");
+        }
+    }
+
+    #[test]
+    #[should_panic(expected = "index 0 out of range for length 0")]
+    fn test_empty_entry() {
+        use xor::O;
+        *Segtree::<O>::new(Vec::new()).entry(0) = 1;
+    }
+
+    #[test]
+    #[should_panic(expected = "index 1 out of range for length 1")]
+    fn test_singleton_update_with_out_of_range() {
+        use xor::O;
+        Segtree::<O>::new(vec![0]).update_with(1, |x| *x = 1);
+    }
+
+    #[test]
+    fn test_update_with() {
+        use xor::O;
+
+        let mut rng = StdRng::seed_from_u64(42);
+        for _ in 0..100 {
+            let n = rng.gen_range(1..=100);
+            let mut vec = repeat_with(|| rng.gen_range(0..16))
+                .take(n)
+                .collect::<Vec<u64>>();
+            let mut segtree = Segtree::<O>::from_slice(&vec);
+            for _ in 0..100 {
+                match rng.gen_range(0..3) {
+                    0 => {
+                        let range = random_range(&mut rng, n);
+                        let expected = vec[range.clone()].iter().fold(0, |acc, x| acc ^ x);
+                        assert_eq!(segtree.fold(range), expected);
+                    }
+                    1 => {
+                        let i = rng.gen_range(0..n);
+                        let x: u64 = rng.gen_range(0..16);
+                        vec[i] = (vec[i] * 3 + x) % 1000;
+                        segtree.update_with(i, |y| *y = (*y * 3 + x) % 1000);
+                    }
+                    2 => {
+                        let i = rng.gen_range(0..n);
+                        assert_eq!(*segtree.get(i), vec[i]);
+                        assert_eq!(segtree[i], vec[i]);
+                    }
+                    _ => unreachable!(),
+                }
+            }
+            assert_eq!(segtree.as_slice(), vec.as_slice());
+        }
+    }
+
+    #[test]
+    #[should_panic(expected = "index 5 out of range for length 5")]
+    fn test_get_out_of_range() {
+        use xor::O;
+        Segtree::<O>::from_slice(&[0; 5]).get(5);
+    }
+
+    #[test]
+    #[should_panic(expected = "index 5 out of range for length 5")]
+    fn test_update_with_out_of_range() {
+        use xor::O;
+        Segtree::<O>::from_slice(&[0; 5]).update_with(5, |x| *x += 1);
+    }
+
+    #[test]
+    #[should_panic(expected = "index 7 out of range for length 5")]
+    fn test_entry_out_of_range() {
+        use xor::O;
+        *Segtree::<O>::from_slice(&[0; 5]).entry(7) = 1;
+    }
+
+    mod concat {
+        use super::*;
+        pub enum O {}
+        impl Op for O {
+            type Value = String;
+
+            fn identity() -> Self::Value {
+                String::new()
+            }
+
+            fn op(lhs: &Self::Value, rhs: &Self::Value) -> Self::Value {
+                lhs.to_owned() + rhs
+            }
+        }
+    }
+
+    #[test]
+    fn test_max_right_min_left() {
+        use concat::O;
+
+        // Monotone predicates that depend on the order: being a prefix or a suffix of `target`.
+        let mut rng = StdRng::seed_from_u64(42);
+        for _ in 0..200 {
+            let n = rng.gen_range(0..=40);
+            let letters: u8 = rng.gen_range(1..=3);
+            let vec = (0..n)
+                .map(|_| {
+                    let len = rng.gen_range(0..=2);
+                    (0..len)
+                        .map(|_| (b'a' + rng.gen_range(0..letters)) as char)
+                        .collect::<String>()
+                })
+                .collect::<Vec<_>>();
+            let segtree = Segtree::<O>::from_slice(&vec);
+            for _ in 0..20 {
+                // Often a substring of `vec`, so that the predicates hold for long ranges.
+                let target = if rng.gen() {
+                    let range = random_range(&mut rng, n);
+                    vec[range].concat()
+                } else {
+                    segtree.fold(rng.gen_range(0..=n)..)
+                };
+                for start in 0..=n {
+                    let expected = (start..=n)
+                        .rev()
+                        .find(|&end| target.starts_with(&vec[start..end].concat()))
+                        .unwrap();
+                    let pred = |s: &String| target.starts_with(s.as_str());
+                    assert_eq!(segtree.max_right(start, pred), expected);
+                    assert_eq!(
+                        segtree.search_forward(start, pred),
+                        (expected < n).then_some(expected)
+                    );
+                }
+                for end in 0..=n {
+                    let expected = (0..=end)
+                        .find(|&start| target.ends_with(&vec[start..end].concat()))
+                        .unwrap();
+                    let pred = |s: &String| target.ends_with(s.as_str());
+                    assert_eq!(segtree.min_left(end, pred), expected);
+                    assert_eq!(segtree.search_backward(end, pred), expected.checked_sub(1));
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn test_max_right_min_left_boundaries() {
+        use xor::O;
+
+        let segtree = Segtree::<O>::from_slice(&[1, 2, 4, 8, 16]);
+        // False immediately.
+        assert_eq!(segtree.max_right(0, |&x| x == 0), 0);
+        assert_eq!(segtree.search_forward(0, |&x| x == 0), Some(0));
+        assert_eq!(segtree.max_right(3, |&x| x == 0), 3);
+        assert_eq!(segtree.search_forward(3, |&x| x == 0), Some(3));
+        assert_eq!(segtree.min_left(5, |&x| x == 0), 5);
+        assert_eq!(segtree.search_backward(5, |&x| x == 0), Some(4));
+        assert_eq!(segtree.min_left(2, |&x| x == 0), 2);
+        assert_eq!(segtree.search_backward(2, |&x| x == 0), Some(1));
+        // True for the entire suffix or prefix.
+        assert_eq!(segtree.max_right(0, |_| true), 5);
+        assert_eq!(segtree.search_forward(0, |_| true), None);
+        assert_eq!(segtree.max_right(2, |&x| x < 32), 5);
+        assert_eq!(segtree.search_forward(2, |&x| x < 32), None);
+        assert_eq!(segtree.min_left(5, |_| true), 0);
+        assert_eq!(segtree.search_backward(5, |_| true), None);
+        assert_eq!(segtree.min_left(3, |&x| x < 8), 0);
+        assert_eq!(segtree.search_backward(3, |&x| x < 8), None);
+        // Failing exactly at the last element, which `max_right` alone cannot tell from the above.
+        assert_eq!(segtree.max_right(2, |&x| x < 16), 4);
+        assert_eq!(segtree.search_forward(2, |&x| x < 16), Some(4));
+        assert_eq!(segtree.min_left(3, |&x| x < 7), 1);
+        assert_eq!(segtree.search_backward(3, |&x| x < 7), Some(0));
+        // Empty ranges at the ends.
+        assert_eq!(segtree.max_right(5, |&x| x == 0), 5);
+        assert_eq!(segtree.search_forward(5, |&x| x == 0), None);
+        assert_eq!(segtree.min_left(0, |&x| x == 0), 0);
+        assert_eq!(segtree.search_backward(0, |&x| x == 0), None);
+        let empty = Segtree::<O>::from_len(0);
+        assert_eq!(empty.max_right(0, |&x| x == 0), 0);
+        assert_eq!(empty.min_left(0, |&x| x == 0), 0);
+    }
+
+    #[test]
+    fn test_max_right_min_left_stateful_predicate() {
+        use concat::O;
+
+        // An `FnMut` that counts its calls: $O(\log n)$ of them.
+        let n = 1000;
+        let segtree = Segtree::<O>::from_slice(&vec!["a".to_owned(); n]);
+        for (start, k) in [(0, 0), (0, 999), (1, 500), (500, 1000), (999, 1)] {
+            let mut calls = 0;
+            let end = segtree.max_right(start, |s| {
+                calls += 1;
+                s.len() <= k
+            });
+            assert_eq!(end, n.min(start + k));
+            assert!(calls <= 4 * 10 + 1, "{} calls", calls);
+            let mut calls = 0;
+            let end = n - start;
+            let start = segtree.min_left(end, |s| {
+                calls += 1;
+                s.len() <= k
+            });
+            assert_eq!(start, end.saturating_sub(k));
+            assert!(calls <= 4 * 10 + 1, "{} calls", calls);
+        }
+    }
+
+    #[test]
+    #[should_panic(expected = "the predicate must hold for the identity")]
+    fn test_max_right_identity() {
+        use xor::O;
+        Segtree::<O>::from_slice(&[1, 2, 3]).max_right(0, |&x| x != 0);
+    }
+
+    #[test]
+    #[should_panic(expected = "range starts at 4 but ends at 3")]
+    fn test_max_right_out_of_range() {
+        use xor::O;
+        Segtree::<O>::from_slice(&[1, 2, 3]).max_right(4, |_| true);
+    }
+
+    #[test]
+    #[should_panic(expected = "range end index 4 out of range for length 3")]
+    fn test_min_left_out_of_range() {
+        use xor::O;
+        Segtree::<O>::from_slice(&[1, 2, 3]).min_left(4, |_| true);
+    }
+
+    #[test]
+    fn test_persistent_segtree() {
+        use rolling_hash::BASE;
+        use rolling_hash::O;
+
+        let mut rng = StdRng::seed_from_u64(42);
+        for _ in 0..50 {
+            let n = rng.gen_range(1..=40);
+            let q = rng.gen_range(1..=40);
+            let mut vecs = vec![repeat_with(|| (rng.gen_range(0..BASE), BASE))
+                .take(n)
+                .collect::<Vec<_>>()];
+            let mut versions = vec![PersistentSegtree::<O>::from_slice(&vecs[0])];
+            // Each version from a random older one.
+            for _ in 0..q {
+                let from = rng.gen_range(0..versions.len());
+                let i = rng.gen_range(0..n);
+                let x = (rng.gen_range(0..BASE), BASE);
+                let mut vec = vecs[from].clone();
+                vec[i] = x;
+                vecs.push(vec);
+                versions.push(versions[from].set(i, x));
+            }
+            for (vec, version) in vecs.iter().zip(&versions) {
+                assert_eq!(version.len(), n);
+                assert_eq!(version.iter().copied().collect::<Vec<_>>(), *vec);
+                for (i, x) in vec.iter().enumerate() {
+                    assert_eq!(version.get(i), x);
+                }
+                for start in 0..=n {
+                    for end in start..=n {
+                        let expected = vec[start..end]
+                            .iter()
+                            .fold(O::identity(), |acc, x| O::op(&acc, x));
+                        assert_eq!(version.fold(start..end), expected);
+                    }
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn test_persistent_segtree_count_less() {
+        use sum::O;
+
+        // The version $r$ counts the values in $a_0, \ldots, a_{r-1}$, so the difference of two
+        // versions gives the number of the values less than $x$ in a subarray.
+        let mut rng = StdRng::seed_from_u64(42);
+        let n = 100;
+        let m = 20;
+        let a = repeat_with(|| rng.gen_range(0..m))
+            .take(n)
+            .collect::<Vec<_>>();
+        let mut versions = vec![PersistentSegtree::<O>::from_slice(&vec![0; m])];
+        for &x in &a {
+            let last = versions.last().unwrap();
+            versions.push(last.set(x, last[x] + 1));
+        }
+        for l in 0..=n {
+            for r in l..=n {
+                let x = rng.gen_range(0..=m);
+                let expected = a[l..r].iter().filter(|&&y| y < x).count() as u64;
+                assert_eq!(versions[r].fold(..x) - versions[l].fold(..x), expected);
+            }
+        }
+    }
+
+    #[test]
+    fn test_persistent_segtree_small() {
+        use concat::O;
+
+        let empty = PersistentSegtree::<O>::new(Vec::new());
+        assert!(empty.is_empty());
+        assert_eq!(empty.fold(..), "");
+        assert_eq!(empty.iter().count(), 0);
+        let one = PersistentSegtree::<O>::from_slice(&["a".to_owned()]);
+        let two = one.set(0, "b".to_owned());
+        assert_eq!(one.fold(..), "a");
+        assert_eq!(two.fold(..), "b");
+        assert_eq!(two.fold(1..), "");
+        assert_eq!(format!("{:?}", two), r#"["b"]"#);
+        let copied = two.clone();
+        drop(two);
+        assert_eq!(copied.fold(..), "b");
+    }
+
+    #[test]
+    #[should_panic(expected = "index 3 out of range for length 3")]
+    fn test_persistent_segtree_set_out_of_range() {
+        use xor::O;
+        PersistentSegtree::<O>::from_slice(&[1, 2, 3]).set(3, 0);
+    }
+
+    #[test]
+    #[should_panic(expected = "range end index 4 out of range for length 3")]
+    fn test_persistent_segtree_fold_out_of_range() {
+        use xor::O;
+        PersistentSegtree::<O>::from_slice(&[1, 2, 3]).fold(..4);
+    }
+
+    fn test_circular_segtree_base<O: Op>(mut gen: impl FnMut(&mut StdRng) -> O::Value)
+    where
+        O::Value: Clone + PartialEq + std::fmt::Debug,
+    {
+        let fold_brute = |a: &[O::Value]| a.iter().fold(O::identity(), |acc, x| O::op(&acc, x));
+        let mut rng = StdRng::seed_from_u64(42);
+        for _ in 0..100 {
+            let n = rng.gen_range(0..=12);
+            let mut vec = repeat_with(|| gen(&mut rng)).take(n).collect::<Vec<_>>();
+            let mut seg = CircularSegtree::<O>::new(&vec);
+            for _ in 0..30 {
+                match rng.gen_range(0..3) {
+                    0 => {
+                        let k = rng.gen_range(0..=2 * n + 1);
+                        seg.rotate(k);
+                        if n != 0 {
+                            vec.rotate_left(k % n);
+                        }
+                    }
+                    1 if n != 0 => {
+                        let i = rng.gen_range(0..n);
+                        let x = gen(&mut rng);
+                        vec[i] = x.clone();
+                        *seg.entry(i) = x;
+                    }
+                    _ => {}
+                }
+                assert_eq!(seg.iter().cloned().collect::<Vec<_>>(), vec);
+                for i in 0..n {
+                    assert_eq!(seg[i], vec[i]);
+                }
+                // Every range, including the empty and the full ones.
+                let doubled = [vec.clone(), vec.clone()].concat();
+                for start in 0..n.max(1) {
+                    for len in 0..=n {
+                        assert_eq!(
+                            seg.fold_cyclic(start, len),
+                            fold_brute(&doubled[start..start + len]),
+                        );
+                    }
+                }
+                if n != 0 {
+                    let start = rng.gen_range(n..4 * n);
+                    assert_eq!(seg.fold_cyclic(start, n), seg.fold_cyclic(start % n, n));
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn test_circular_segtree() {
+        test_circular_segtree_base::<concat::O>(|rng| rng.gen_range('a'..='z').to_string());
+        test_circular_segtree_base::<rolling_hash::O>(|rng| {
+            (rng.gen_range(0..rolling_hash::BASE), rolling_hash::BASE)
+        });
+        test_circular_segtree_base::<xor::O>(|rng| rng.gen_range(0..16));
+    }
+
+    #[test]
+    #[should_panic(expected = "cyclic range of length 6 is longer than 5")]
+    fn test_circular_segtree_too_long() {
+        use xor::O;
+        CircularSegtree::<O>::new(&[0; 5]).fold_cyclic(2, 6);
+    }
+
+    #[test]
+    #[should_panic(expected = "index 5 out of range for length 5")]
+    fn test_circular_segtree_index_out_of_range() {
+        use xor::O;
+        let mut seg = CircularSegtree::<O>::new(&[0; 5]);
+        seg.rotate(3);
+        *seg.entry(5) = 1;
+    }
+
     fn random_range(rng: &mut StdRng, n: usize) -> Range<usize> {
         let start = rng.gen_range(0..=n + 1);
         let end = rng.gen_range(0..=n);
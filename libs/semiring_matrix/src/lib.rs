@@ -0,0 +1,491 @@
+//! Square matrices over a semiring, for walks with a fixed number of edges.
+//!
+//! The $(i, j)$ element of the $k$-th power of an adjacency matrix aggregates the walks from $i$
+//! to $j$ with exactly $k$ edges, where [`Semiring::add`] aggregates walks and [`Semiring::mul`]
+//! concatenates them:
+//!
+//! * [`MinPlus`]: the shortest walk,
+//! * [`MaxTimes`]: the most probable walk,
+//! * [`OrAnd`]: whether there is a walk.
+//! * [`PlusTimes`]: the number of walks in $\mathbb{F} _ p$.
+//!
+//! [`SemiringMatrix::with_self_loops`] changes "exactly $k$" into "at most $k$".
+//!
+//! # Counting sequences
+//!
+//! [`count_sequences_with_local_constraint`] counts the sequences whose adjacent elements satisfy a
+//! predicate, and [`count_walks_in_automaton`] counts the walks in an automaton with explicit
+//! states, such as the KMP automaton of the strings avoiding a pattern.
+//!
+//! # Examples
+//!
+//! ```
+//! use semiring_matrix::MinPlus;
+//! use semiring_matrix::SemiringMatrix;
+//! use semiring_matrix::INF;
+//! // 0 -> 1 -> 2 -> 0 with weights 1, 2, 3, and 0 -> 2 with weight 10.
+//! let mut a = SemiringMatrix::<MinPlus>::zero(3);
+//! a[(0, 1)] = 1;
+//! a[(1, 2)] = 2;
+//! a[(2, 0)] = 3;
+//! a[(0, 2)] = 10;
+//! assert_eq!(a.pow(2)[(0, 2)], 3);
+//! assert_eq!(a.pow(4)[(0, 2)], 16);
+//! assert_eq!(a.with_self_loops().pow(4)[(0, 2)], 3);
+//! assert_eq!(a.pow(0)[(0, 2)], INF);
+//! ```
+
+mod sequences;
+
+use fp::Fp;
+pub use sequences::count_sequences_with_local_constraint;
+pub use sequences::count_walks_in_automaton;
+use std::fmt;
+use std::marker::PhantomData;
+use std::ops::Index;
+use std::ops::IndexMut;
+
+/// A semiring.
+///
+/// * [`Semiring::add`] must be associative and commutative with the identity [`Semiring::zero`].
+/// * [`Semiring::mul`] must be associative with the identity [`Semiring::one`].
+/// * [`Semiring::mul`] must distribute over [`Semiring::add`], and [`Semiring::zero`] must
+///   annihilate it.
+pub trait Semiring {
+    /// The value type.
+    type Value: Copy + PartialEq;
+
+    /// Returns the additive identity.
+    fn zero() -> Self::Value;
+    /// Returns the multiplicative identity.
+    fn one() -> Self::Value;
+    /// Adds two values.
+    fn add(lhs: Self::Value, rhs: Self::Value) -> Self::Value;
+    /// Multiplies two values.
+    fn mul(lhs: Self::Value, rhs: Self::Value) -> Self::Value;
+}
+
+/// The infinity of [`MinPlus`].
+pub const INF: i64 = i64::MAX;
+
+/// $(\min, +)$ over `i64`, where [`INF`] is the infinity.
+///
+/// The multiplication is saturating, so a finite sum that does not fit in `i64` is also [`INF`].
+pub enum MinPlus {}
+impl Semiring for MinPlus {
+    type Value = i64;
+
+    fn zero() -> i64 {
+        INF
+    }
+
+    fn one() -> i64 {
+        0
+    }
+
+    fn add(lhs: i64, rhs: i64) -> i64 {
+        lhs.min(rhs)
+    }
+
+    fn mul(lhs: i64, rhs: i64) -> i64 {
+        // `INF + INF` and `INF + (-1)` must stay `INF`.
+        if lhs == INF || rhs == INF {
+            INF
+        } else {
+            lhs.saturating_add(rhs)
+        }
+    }
+}
+
+/// $(\max, \times)$ over nonnegative `f64`.
+pub enum MaxTimes {}
+impl Semiring for MaxTimes {
+    type Value = f64;
+
+    fn zero() -> f64 {
+        0.0
+    }
+
+    fn one() -> f64 {
+        1.0
+    }
+
+    fn add(lhs: f64, rhs: f64) -> f64 {
+        lhs.max(rhs)
+    }
+
+    fn mul(lhs: f64, rhs: f64) -> f64 {
+        lhs * rhs
+    }
+}
+
+/// $(\lor, \land)$ over `bool`.
+pub enum OrAnd {}
+impl Semiring for OrAnd {
+    type Value = bool;
+
+    fn zero() -> bool {
+        false
+    }
+
+    fn one() -> bool {
+        true
+    }
+
+    fn add(lhs: bool, rhs: bool) -> bool {
+        lhs || rhs
+    }
+
+    fn mul(lhs: bool, rhs: bool) -> bool {
+        lhs && rhs
+    }
+}
+
+/// $(+, \times)$ over [`Fp`].
+pub struct PlusTimes<const P: u64>(PhantomData<Fp<P>>);
+impl<const P: u64> Semiring for PlusTimes<P> {
+    type Value = Fp<P>;
+
+    fn zero() -> Fp<P> {
+        Fp::new(0)
+    }
+
+    fn one() -> Fp<P> {
+        Fp::new(1)
+    }
+
+    fn add(lhs: Fp<P>, rhs: Fp<P>) -> Fp<P> {
+        lhs + rhs
+    }
+
+    fn mul(lhs: Fp<P>, rhs: Fp<P>) -> Fp<P> {
+        lhs * rhs
+    }
+}
+
+/// A square matrix over a semiring `S`.
+pub struct SemiringMatrix<S: Semiring> {
+    n: usize,
+    values: Vec<S::Value>,
+}
+impl<S: Semiring> SemiringMatrix<S> {
+    /// Returns the $n \times n$ matrix filled with [`Semiring::zero`].
+    pub fn zero(n: usize) -> Self {
+        Self {
+            n,
+            values: vec![S::zero(); n * n],
+        }
+    }
+
+    /// Returns the $n \times n$ identity matrix.
+    pub fn identity(n: usize) -> Self {
+        let mut result = Self::zero(n);
+        for i in 0..n {
+            result[(i, i)] = S::one();
+        }
+        result
+    }
+
+    /// Constructs a matrix from its rows.
+    ///
+    /// # Panics
+    ///
+    /// If the matrix is not square.
+    pub fn from_rows(rows: Vec<Vec<S::Value>>) -> Self {
+        let n = rows.len();
+        for row in &rows {
+            assert_eq!(row.len(), n, "the matrix is not square");
+        }
+        Self {
+            n,
+            values: rows.concat(),
+        }
+    }
+
+    /// Returns the dimension.
+    pub fn dim(&self) -> usize {
+        self.n
+    }
+
+    /// Returns the $i$-th row.
+    pub fn row(&self, i: usize) -> &[S::Value] {
+        &self.values[i * self.n..(i + 1) * self.n]
+    }
+
+    /// Returns the product `self * rhs`.
+    ///
+    /// # Panics
+    ///
+    /// If the dimensions differ.
+    ///
+    /// # Complexity
+    ///
+    /// $O(n ^ 3)$
+    pub fn mul(&self, rhs: &Self) -> Self {
+        assert_eq!(self.n, rhs.n, "the dimensions differ");
+        let n = self.n;
+        let mut result = Self::zero(n);
+        for i in 0..n {
+            for k in 0..n {
+                let x = self[(i, k)];
+                // `zero` annihilates.
+                if x == S::zero() {
+                    continue;
+                }
+                let row = &mut result.values[i * n..(i + 1) * n];
+                for (r, &y) in row.iter_mut().zip(rhs.row(k)) {
+                    *r = S::add(*r, S::mul(x, y));
+                }
+            }
+        }
+        result
+    }
+
+    /// Returns the $k$-th power.
+    ///
+    /// # Complexity
+    ///
+    /// $O(n ^ 3 \log k)$
+    pub fn pow(&self, mut k: u64) -> Self {
+        let mut result = Self::identity(self.n);
+        let mut base = self.clone();
+        while k != 0 {
+            if k & 1 == 1 {
+                result = result.mul(&base);
+            }
+            k >>= 1;
+            if k != 0 {
+                base = base.mul(&base);
+            }
+        }
+        result
+    }
+
+    /// Adds [`Semiring::one`] to the diagonal, which is a self-loop of weight zero for
+    /// [`MinPlus`], so the $k$-th power aggregates the walks with at most $k$ edges.
+    pub fn with_self_loops(&self) -> Self {
+        let mut result = self.clone();
+        for i in 0..self.n {
+            result[(i, i)] = S::add(result[(i, i)], S::one());
+        }
+        result
+    }
+}
+
+impl<S: Semiring> Clone for SemiringMatrix<S> {
+    fn clone(&self) -> Self {
+        Self {
+            n: self.n,
+            values: self.values.clone(),
+        }
+    }
+}
+
+impl<S: Semiring> PartialEq for SemiringMatrix<S> {
+    fn eq(&self, other: &Self) -> bool {
+        self.n == other.n && self.values == other.values
+    }
+}
+
+impl<S: Semiring> fmt::Debug for SemiringMatrix<S>
+where
+    S::Value: fmt::Debug,
+{
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_list()
+            .entries((0..self.n).map(|i| self.row(i)))
+            .finish()
+    }
+}
+
+impl<S: Semiring> Index<(usize, usize)> for SemiringMatrix<S> {
+    type Output = S::Value;
+
+    fn index(&self, (i, j): (usize, usize)) -> &S::Value {
+        assert!(i < self.n && j < self.n, "index out of bounds");
+        &self.values[i * self.n + j]
+    }
+}
+
+impl<S: Semiring> IndexMut<(usize, usize)> for SemiringMatrix<S> {
+    fn index_mut(&mut self, (i, j): (usize, usize)) -> &mut S::Value {
+        assert!(i < self.n && j < self.n, "index out of bounds");
+        &mut self.values[i * self.n + j]
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rand::rngs::StdRng;
+    use rand::Rng;
+    use rand::SeedableRng;
+    use std::collections::VecDeque;
+
+    const KS: [u64; 9] = [0, 1, 2, 3, 4, 5, 8, 13, 40];
+
+    // Parallel edges are merged by `add`.
+    fn adjacency<S: Semiring>(n: usize, edges: &[(usize, usize, S::Value)]) -> SemiringMatrix<S> {
+        let mut a = SemiringMatrix::<S>::zero(n);
+        for &(u, v, w) in edges {
+            a[(u, v)] = S::add(a[(u, v)], w);
+        }
+        a
+    }
+
+    fn random_edges<T>(
+        rng: &mut StdRng,
+        n: usize,
+        mut weight: impl FnMut(&mut StdRng) -> T,
+    ) -> Vec<(usize, usize, T)> {
+        let m = rng.gen_range(0..=2 * n);
+        (0..m)
+            .map(|_| (rng.gen_range(0..n), rng.gen_range(0..n), weight(rng)))
+            .collect()
+    }
+
+    // The aggregate over the walks with exactly $k$ edges, layer by layer.
+    fn layered<S: Semiring>(
+        n: usize,
+        edges: &[(usize, usize, S::Value)],
+        k: u64,
+    ) -> Vec<Vec<S::Value>> {
+        (0..n)
+            .map(|s| {
+                let mut dp = vec![S::zero(); n];
+                dp[s] = S::one();
+                for _ in 0..k {
+                    let mut next = vec![S::zero(); n];
+                    for &(u, v, w) in edges {
+                        next[v] = S::add(next[v], S::mul(dp[u], w));
+                    }
+                    dp = next;
+                }
+                dp
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_min_plus() {
+        let mut rng = StdRng::seed_from_u64(42);
+        for _ in 0..100 {
+            let n = rng.gen_range(1..=6);
+            let edges = random_edges(&mut rng, n, |rng| rng.gen_range(-5..=20));
+            let a = adjacency::<MinPlus>(n, &edges);
+            for k in KS {
+                assert_eq!(
+                    a.pow(k),
+                    SemiringMatrix::from_rows(layered::<MinPlus>(n, &edges, k))
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn test_min_plus_saturation() {
+        let big = i64::MAX / 2;
+        let a = SemiringMatrix::<MinPlus>::from_rows(vec![vec![big, INF], vec![INF, -big]]);
+        let b = a.pow(3);
+        assert_eq!(b[(0, 0)], INF);
+        assert_eq!(b[(0, 1)], INF);
+        assert_eq!(b[(1, 0)], INF);
+        assert_eq!(b[(1, 1)], i64::MIN);
+        assert_eq!(
+            SemiringMatrix::<MinPlus>::zero(3).pow(1 << 40),
+            SemiringMatrix::zero(3)
+        );
+    }
+
+    #[test]
+    fn test_max_times() {
+        let mut rng = StdRng::seed_from_u64(42);
+        for _ in 0..100 {
+            let n = rng.gen_range(1..=6);
+            // Powers of two are exact.
+            let edges = random_edges(&mut rng, n, |rng| {
+                [0.0, 0.25, 0.5, 1.0][rng.gen_range(0..4_usize)]
+            });
+            let a = adjacency::<MaxTimes>(n, &edges);
+            for k in KS {
+                assert_eq!(
+                    a.pow(k),
+                    SemiringMatrix::from_rows(layered::<MaxTimes>(n, &edges, k))
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn test_or_and() {
+        let mut rng = StdRng::seed_from_u64(42);
+        for _ in 0..100 {
+            let n = rng.gen_range(1..=8);
+            let edges = random_edges(&mut rng, n, |_| true);
+            let a = adjacency::<OrAnd>(n, &edges);
+            let looped = a.with_self_loops();
+            for s in 0..n {
+                // BFS over the pairs (vertex, steps) up to the largest $k$.
+                let max = *KS.iter().max().unwrap() as usize;
+                let mut exact = vec![vec![false; n]; max + 1];
+                exact[0][s] = true;
+                let mut queue = VecDeque::from([(s, 0)]);
+                while let Some((u, d)) = queue.pop_front() {
+                    if d == max {
+                        continue;
+                    }
+                    for &(x, y, _) in &edges {
+                        if x == u && !exact[d + 1][y] {
+                            exact[d + 1][y] = true;
+                            queue.push_back((y, d + 1));
+                        }
+                    }
+                }
+                for k in KS {
+                    let k_ = k as usize;
+                    assert_eq!(a.pow(k).row(s), exact[k_].as_slice());
+                    let at_most = (0..n)
+                        .map(|v| exact[..=k_].iter().any(|row| row[v]))
+                        .collect::<Vec<_>>();
+                    assert_eq!(looped.pow(k).row(s), at_most.as_slice());
+                }
+            }
+        }
+    }
+
+    fn check_axioms<S: Semiring>(values: &[S::Value])
+    where
+        S::Value: fmt::Debug,
+    {
+        for &x in values {
+            assert_eq!(S::add(x, S::zero()), x);
+            assert_eq!(S::mul(x, S::one()), x);
+            assert_eq!(S::mul(S::one(), x), x);
+            assert_eq!(S::mul(x, S::zero()), S::zero());
+            assert_eq!(S::mul(S::zero(), x), S::zero());
+            for &y in values {
+                assert_eq!(S::add(x, y), S::add(y, x));
+                for &z in values {
+                    assert_eq!(S::add(S::add(x, y), z), S::add(x, S::add(y, z)));
+                    assert_eq!(S::mul(S::mul(x, y), z), S::mul(x, S::mul(y, z)));
+                    assert_eq!(S::mul(x, S::add(y, z)), S::add(S::mul(x, y), S::mul(x, z)));
+                    assert_eq!(S::mul(S::add(y, z), x), S::add(S::mul(y, x), S::mul(z, x)));
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn test_axioms() {
+        check_axioms::<MinPlus>(&[INF, 0, -3, 1, 7, 1 << 20]);
+        check_axioms::<MaxTimes>(&[0.0, 1.0, 0.5, 0.25, 0.75]);
+        check_axioms::<OrAnd>(&[false, true]);
+        check_axioms::<PlusTimes<7>>(&[0, 1, 3, 6].map(Fp::new));
+    }
+
+    #[test]
+    #[should_panic(expected = "the matrix is not square")]
+    fn test_not_square() {
+        SemiringMatrix::<OrAnd>::from_rows(vec![vec![true, false], vec![true]]);
+    }
+}
@@ -0,0 +1,329 @@
+use super::PlusTimes;
+use super::SemiringMatrix;
+use fp::Fp;
+use std::collections::HashMap;
+use std::hash::Hash;
+
+/// Returns $\sum _ {x} \mathrm{initial} _ {x _ 0} \mathrm{accept} _ {x _ {n - 1}}$ over the
+/// sequences $x _ 0, \dots, x _ {n - 1}$ of length $n$ over $\\{0, \dots, m - 1\\}$ such that
+/// `allowed(x[i], x[i + 1])` for every $i$.
+///
+/// A sequence of length $n$ has $n - 1$ transitions. Since the empty sequence has neither the
+/// first nor the last element, the result is zero if $n = 0$.
+///
+/// # Panics
+///
+/// If the lengths of `initial` or `accept` are not $m$.
+///
+/// # Complexity
+///
+/// $O(m ^ 3 \log n)$
+///
+/// # Examples
+///
+/// ```
+/// use fp::Fp;
+/// use semiring_matrix::count_sequences_with_local_constraint;
+/// type F = Fp<998244353>;
+/// // Adjacent digits differ by at most one, and the first one is not zero.
+/// let mut initial = vec![F::new(1); 10];
+/// initial[0] = F::new(0);
+/// let accept = vec![F::new(1); 10];
+/// let count =
+///     count_sequences_with_local_constraint(10, 2, |x, y| x.abs_diff(y) <= 1, &initial, &accept);
+/// assert_eq!(count, F::new(26));
+/// ```
+pub fn count_sequences_with_local_constraint<const P: u64>(
+    m: usize,
+    n: u64,
+    allowed: impl Fn(usize, usize) -> bool,
+    initial: &[Fp<P>],
+    accept: &[Fp<P>],
+) -> Fp<P> {
+    assert_eq!(
+        initial.len(),
+        m,
+        "the length of `initial` is not the alphabet size"
+    );
+    assert_eq!(
+        accept.len(),
+        m,
+        "the length of `accept` is not the alphabet size"
+    );
+    if n == 0 {
+        return Fp::new(0);
+    }
+    let mut a = SemiringMatrix::<PlusTimes<P>>::zero(m);
+    for x in 0..m {
+        for y in 0..m {
+            if allowed(x, y) {
+                a[(x, y)] = Fp::new(1);
+            }
+        }
+    }
+    contract(&a.pow(n - 1), initial, accept)
+}
+
+/// Returns $\sum _ {w} \mathrm{initial} _ {w _ 0} \mathrm{accept} _ {w _ \mathrm{steps}}$ over the
+/// walks $w _ 0, \dots, w _ \mathrm{steps}$ with exactly `steps` transitions in an automaton.
+///
+/// `next(s)` lists the destinations of the transitions from `s`, one for each transition, so a
+/// destination reached by two letters appears twice. `initial` and `accept` are indexed in the
+/// same order as `states`.
+///
+/// # Panics
+///
+/// * If the lengths of `initial` or `accept` differ from that of `states`.
+/// * If a transition leads to a state not in `states`.
+///
+/// # Complexity
+///
+/// $O(m ^ 3 \log \mathrm{steps})$ besides the calls of `next`, where $m$ is the number of states.
+///
+/// # Examples
+///
+/// ```
+/// use fp::Fp;
+/// use semiring_matrix::count_walks_in_automaton;
+/// type F = Fp<998244353>;
+/// // Binary strings of length 5 without "11", where the state is the last letter.
+/// let count = count_walks_in_automaton(
+///     &[0, 1],
+///     5,
+///     |&s| if s == 0 { vec![0, 1] } else { vec![0] },
+///     &[F::new(1), F::new(0)],
+///     &[F::new(1), F::new(1)],
+/// );
+/// assert_eq!(count, F::new(13));
+/// ```
+pub fn count_walks_in_automaton<T: Eq + Hash, const P: u64>(
+    states: &[T],
+    steps: u64,
+    next: impl Fn(&T) -> Vec<T>,
+    initial: &[Fp<P>],
+    accept: &[Fp<P>],
+) -> Fp<P> {
+    let m = states.len();
+    assert_eq!(
+        initial.len(),
+        m,
+        "the length of `initial` is not the number of the states"
+    );
+    assert_eq!(
+        accept.len(),
+        m,
+        "the length of `accept` is not the number of the states"
+    );
+    let index = states
+        .iter()
+        .enumerate()
+        .map(|(i, s)| (s, i))
+        .collect::<HashMap<_, _>>();
+    let mut a = SemiringMatrix::<PlusTimes<P>>::zero(m);
+    for (x, s) in states.iter().enumerate() {
+        for t in next(s) {
+            let y = *index
+                .get(&t)
+                .expect("a transition leads to a state not in the list");
+            a[(x, y)] += Fp::new(1);
+        }
+    }
+    contract(&a.pow(steps), initial, accept)
+}
+
+fn contract<const P: u64>(
+    a: &SemiringMatrix<PlusTimes<P>>,
+    initial: &[Fp<P>],
+    accept: &[Fp<P>],
+) -> Fp<P> {
+    initial
+        .iter()
+        .enumerate()
+        .map(|(x, &c)| {
+            c * a
+                .row(x)
+                .iter()
+                .zip(accept)
+                .map(|(&v, &d)| v * d)
+                .sum::<Fp<P>>()
+        })
+        .sum()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rand::rngs::StdRng;
+    use rand::Rng;
+    use rand::SeedableRng;
+
+    const P: u64 = 998244353;
+    type F = Fp<P>;
+
+    fn random_weights(rng: &mut StdRng, m: usize) -> Vec<F> {
+        (0..m).map(|_| F::new(rng.gen_range(0..3))).collect()
+    }
+
+    // Every sequence of length `n` over `0..m`
+    fn sequences(m: usize, n: usize) -> Vec<Vec<usize>> {
+        let mut result = vec![vec![]];
+        for _ in 0..n {
+            result = result
+                .into_iter()
+                .flat_map(|s| {
+                    (0..m).map(move |x| {
+                        let mut s = s.clone();
+                        s.push(x);
+                        s
+                    })
+                })
+                .collect();
+        }
+        result
+    }
+
+    #[test]
+    fn test_local_constraint() {
+        type Constraint = fn(usize, usize) -> bool;
+        let constraints: [Constraint; 6] = [
+            |x, y| x.abs_diff(y) <= 1,
+            |x, y| x != y,
+            |x, y| x < y,
+            |x, y| (x + y) % 3 != 0,
+            |_, _| true,
+            |_, _| false,
+        ];
+        let mut rng = StdRng::seed_from_u64(42);
+        for _ in 0..100 {
+            let m = rng.gen_range(0..=4);
+            let initial = random_weights(&mut rng, m);
+            let accept = random_weights(&mut rng, m);
+            for allowed in constraints {
+                for n in 0..=6 {
+                    let expected = sequences(m, n)
+                        .iter()
+                        .filter(|s| !s.is_empty())
+                        .filter(|s| s.windows(2).all(|w| allowed(w[0], w[1])))
+                        .map(|s| initial[s[0]] * accept[s[n - 1]])
+                        .sum::<F>();
+                    let result = count_sequences_with_local_constraint(
+                        m, n as u64, allowed, &initial, &accept,
+                    );
+                    assert_eq!(result, expected);
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn test_no_valid_sequence() {
+        let ones = vec![F::new(1); 5];
+        let increasing = |x, y| x < y;
+        assert_eq!(
+            count_sequences_with_local_constraint(5, 5, increasing, &ones, &ones),
+            F::new(1)
+        );
+        assert_eq!(
+            count_sequences_with_local_constraint(5, 6, increasing, &ones, &ones),
+            F::new(0)
+        );
+        assert_eq!(
+            count_sequences_with_local_constraint(5, 1 << 40, increasing, &ones, &ones),
+            F::new(0)
+        );
+    }
+
+    // The KMP automaton of the strings avoiding `pattern`
+    fn kmp_automaton(pattern: &[usize], sigma: usize) -> Vec<Vec<usize>> {
+        let k = pattern.len();
+        let mut fail = vec![0; k + 1];
+        for i in 1..k {
+            let mut j = fail[i];
+            while j > 0 && pattern[i] != pattern[j] {
+                j = fail[j];
+            }
+            if pattern[i] == pattern[j] {
+                j += 1;
+            }
+            fail[i + 1] = j;
+        }
+        (0..k)
+            .map(|s| {
+                (0..sigma)
+                    .map(|c| {
+                        let mut t = s;
+                        while t > 0 && pattern[t] != c {
+                            t = fail[t];
+                        }
+                        if pattern[t] == c {
+                            t + 1
+                        } else {
+                            0
+                        }
+                    })
+                    .collect()
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_pattern_avoidance() {
+        let mut rng = StdRng::seed_from_u64(42);
+        for _ in 0..100 {
+            let sigma = rng.gen_range(1..=3);
+            let k = rng.gen_range(1..=3);
+            let pattern = (0..k).map(|_| rng.gen_range(0..sigma)).collect::<Vec<_>>();
+            let delta = kmp_automaton(&pattern, sigma);
+            let states = (0..k).collect::<Vec<_>>();
+            let mut initial = vec![F::new(0); k];
+            initial[0] = F::new(1);
+            let accept = vec![F::new(1); k];
+            for n in 0..=7 {
+                let expected = sequences(sigma, n)
+                    .iter()
+                    .filter(|s| !s.windows(k).any(|w| w == pattern))
+                    .count();
+                let result = count_walks_in_automaton(
+                    &states,
+                    n as u64,
+                    // Reaching the state $k$ means the pattern appears.
+                    |&s| delta[s].iter().copied().filter(|&t| t < k).collect(),
+                    &initial,
+                    &accept,
+                );
+                assert_eq!(result, F::new(expected as u64));
+            }
+        }
+    }
+
+    #[test]
+    fn test_tuple_states() {
+        // Ternary strings with at most two 2s, where the state is the number of 2s.
+        let states = [(0, false), (1, false), (2, false), (2, true)];
+        let next = |&(c, dead): &(usize, bool)| {
+            if dead {
+                vec![(2, true); 3]
+            } else if c == 2 {
+                vec![(2, false), (2, false), (2, true)]
+            } else {
+                vec![(c, false), (c, false), (c + 1, false)]
+            }
+        };
+        let initial = [F::new(1), F::new(0), F::new(0), F::new(0)];
+        let accept = [F::new(1), F::new(1), F::new(1), F::new(0)];
+        for n in 0..=7 {
+            let expected = sequences(3, n)
+                .iter()
+                .filter(|s| s.iter().filter(|&&x| x == 2).count() <= 2)
+                .count();
+            let result = count_walks_in_automaton(&states, n as u64, next, &initial, &accept);
+            assert_eq!(result, F::new(expected as u64));
+        }
+    }
+
+    #[test]
+    #[should_panic(expected = "a transition leads to a state not in the list")]
+    fn test_unknown_state() {
+        count_walks_in_automaton(&[0], 1, |_| vec![1], &[F::new(1)], &[F::new(1)]);
+    }
+}
@@ -0,0 +1,391 @@
+//! Minimizes a failing sequence of randomly generated operations.
+//!
+//! A randomized test usually fails deep in a long sequence of operations. [`shrink_sequence`]
+//! repeatedly removes operations and replaces them with simpler ones by [`Shrink`] while the
+//! failure persists, and [`reproducer`] prints the result as Rust code.
+//!
+//! # Examples
+//!
+//! ```
+//! use shrink::reproducer;
+//! use shrink::shrink_sequence;
+//!
+//! // A "sort" that forgets the last element.
+//! let sort = |a: &[usize]| {
+//!     let mut a = a.to_vec();
+//!     let n = a.len().saturating_sub(1);
+//!     a[..n].sort();
+//!     a
+//! };
+//! let fails = |a: &[usize]| sort(a).windows(2).any(|w| w[0] > w[1]);
+//! let a = vec![31, 41, 59, 26, 53, 58, 97, 93, 23, 84];
+//! assert!(fails(&a));
+//! let a = shrink_sequence(a, fails);
+//! assert_eq!(a, vec![1, 0]);
+//! assert_eq!(reproducer(&a), "vec![\n    1,\n    0,\n]");
+//! ```
+use std::fmt::Debug;
+use std::fmt::Write;
+use std::ops::Range;
+
+/// A value that has simpler candidates to try in place of it.
+///
+/// Every candidate must be strictly simpler in some well-founded order so that the shrinking
+/// terminates.
+pub trait Shrink: Sized {
+    /// Returns the candidates, the simplest first.
+    fn shrink(&self) -> Vec<Self>;
+}
+
+macro_rules! impl_shrink_unsigned {
+    ($($t:ty),*) => {$(
+        impl Shrink for $t {
+            fn shrink(&self) -> Vec<Self> {
+                let x = *self;
+                let mut result = Vec::new();
+                for y in [0, x / 2, x.saturating_sub(1)] {
+                    if y < x && !result.contains(&y) {
+                        result.push(y);
+                    }
+                }
+                result
+            }
+        }
+    )*};
+}
+impl_shrink_unsigned!(u8, u16, u32, u64, u128, usize);
+
+macro_rules! impl_shrink_signed {
+    ($($t:ty),*) => {$(
+        impl Shrink for $t {
+            fn shrink(&self) -> Vec<Self> {
+                let x = *self;
+                let mut result = Vec::new();
+                // Simpler means closer to zero, and positive for the same absolute value.
+                let simpler = |y: $t| {
+                    y.unsigned_abs() < x.unsigned_abs() || (y == x.saturating_neg() && x < 0)
+                };
+                for y in [0, x.saturating_neg(), x / 2, x - x.signum()] {
+                    if simpler(y) && !result.contains(&y) {
+                        result.push(y);
+                    }
+                }
+                result
+            }
+        }
+    )*};
+}
+impl_shrink_signed!(i8, i16, i32, i64, i128, isize);
+
+impl Shrink for bool {
+    fn shrink(&self) -> Vec<Self> {
+        if *self {
+            vec![false]
+        } else {
+            Vec::new()
+        }
+    }
+}
+
+impl Shrink for Range<usize> {
+    /// Every candidate has a smaller end, or the same end and a smaller length.
+    fn shrink(&self) -> Vec<Self> {
+        let Range { start, end } = self.clone();
+        if start >= end {
+            return if start == 0 { Vec::new() } else { vec![0..0] };
+        }
+        let mut result = Vec::new();
+        let mut push = |range: Range<usize>| {
+            if range != *self && !result.contains(&range) {
+                result.push(range);
+            }
+        };
+        push(0..end - start);
+        push(start..start + (end - start + 1) / 2);
+        push(start..end - 1);
+        if start != 0 {
+            push(start - 1..end - 1);
+        }
+        push(start + 1..end);
+        result
+    }
+}
+
+macro_rules! impl_shrink_tuple {
+    ($(($($i:tt $t:ident),*))*) => {$(
+        impl<$($t: Shrink + Clone),*> Shrink for ($($t,)*) {
+            fn shrink(&self) -> Vec<Self> {
+                let mut result = Vec::new();
+                $(
+                    for x in self.$i.shrink() {
+                        let mut y = self.clone();
+                        y.$i = x;
+                        result.push(y);
+                    }
+                )*
+                result
+            }
+        }
+    )*};
+}
+impl_shrink_tuple! {
+    (0 A)
+    (0 A, 1 B)
+    (0 A, 1 B, 2 C)
+    (0 A, 1 B, 2 C, 3 D)
+}
+
+/// Minimizes the failing sequence `ops`.
+///
+/// It removes chunks of operations of halving sizes, and then replaces each operation with its
+/// [`Shrink`] candidates, until neither makes progress. The result still fails, and removing or
+/// shrinking any single operation of it makes it pass.
+///
+/// # Requirements
+///
+/// - `fails(&ops)` is `true`.
+/// - `fails` is deterministic.
+pub fn shrink_sequence<T: Shrink + Clone>(
+    mut ops: Vec<T>,
+    mut fails: impl FnMut(&[T]) -> bool,
+) -> Vec<T> {
+    assert!(fails(&ops), "the sequence to shrink does not fail");
+    loop {
+        let mut progress = false;
+        let mut chunk = (ops.len() / 2).max(1);
+        while chunk != 0 {
+            let mut i = 0;
+            while i + chunk <= ops.len() {
+                let candidate = ops[..i]
+                    .iter()
+                    .chain(&ops[i + chunk..])
+                    .cloned()
+                    .collect::<Vec<_>>();
+                if fails(&candidate) {
+                    ops = candidate;
+                    progress = true;
+                } else {
+                    i += chunk;
+                }
+            }
+            chunk /= 2;
+        }
+        for i in 0..ops.len() {
+            'retry: loop {
+                for x in ops[i].shrink() {
+                    let mut candidate = ops.clone();
+                    candidate[i] = x;
+                    if fails(&candidate) {
+                        ops = candidate;
+                        progress = true;
+                        continue 'retry;
+                    }
+                }
+                break;
+            }
+        }
+        if !progress {
+            return ops;
+        }
+    }
+}
+
+/// Prints `ops` as a `vec!` literal, one operation per line.
+///
+/// The output is valid Rust if the [`Debug`] output of each operation is, as with integers,
+/// ranges, tuples and derived enums whose variants are in scope.
+pub fn reproducer<T: Debug>(ops: &[T]) -> String {
+    let mut result = "vec![\n".to_owned();
+    for op in ops {
+        writeln!(result, "    {:?},", op).unwrap();
+    }
+    result.push(']');
+    result
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rand::rngs::StdRng;
+    use rand::Rng;
+    use rand::SeedableRng;
+
+    #[derive(Debug, Clone, PartialEq)]
+    enum Query {
+        Set(usize, u64),
+        Sum(Range<usize>),
+    }
+    impl Shrink for Query {
+        fn shrink(&self) -> Vec<Self> {
+            match self {
+                Query::Set(i, x) => (*i, *x)
+                    .shrink()
+                    .into_iter()
+                    .map(|(i, x)| Query::Set(i, x))
+                    .collect(),
+                Query::Sum(range) => range.shrink().into_iter().map(Query::Sum).collect(),
+            }
+        }
+    }
+
+    // A segment tree whose `set` forgets to update the root.
+    struct BuggySegtree {
+        table: Vec<u64>,
+    }
+    impl BuggySegtree {
+        fn new(n: usize) -> Self {
+            assert!(n.is_power_of_two());
+            Self {
+                table: vec![0; 2 * n],
+            }
+        }
+
+        fn set(&mut self, mut i: usize, x: u64) {
+            let n = self.table.len() / 2;
+            i += n;
+            self.table[i] = x;
+            i /= 2;
+            while i > 1 {
+                self.table[i] = self.table[2 * i] + self.table[2 * i + 1];
+                i /= 2;
+            }
+        }
+
+        fn sum(&self, range: Range<usize>) -> u64 {
+            let n = self.table.len() / 2;
+            let mut l = range.start + n;
+            let mut r = range.end + n;
+            let mut result = 0;
+            while l < r {
+                if l % 2 == 1 {
+                    result += self.table[l];
+                    l += 1;
+                }
+                if r % 2 == 1 {
+                    r -= 1;
+                    result += self.table[r];
+                }
+                l /= 2;
+                r /= 2;
+            }
+            result
+        }
+    }
+
+    const N: usize = 8;
+
+    fn fails(queries: &[Query]) -> bool {
+        let mut segtree = BuggySegtree::new(N);
+        let mut brute = vec![0; N];
+        for query in queries {
+            match query {
+                &Query::Set(i, x) => {
+                    segtree.set(i, x);
+                    brute[i] = x;
+                }
+                Query::Sum(range) => {
+                    if segtree.sum(range.clone()) != brute[range.clone()].iter().sum::<u64>() {
+                        return true;
+                    }
+                }
+            }
+        }
+        false
+    }
+
+    #[test]
+    fn test_buggy_segtree() {
+        let mut rng = StdRng::seed_from_u64(42);
+        let mut queries = (0..100)
+            .map(|_| {
+                if rng.gen() {
+                    Query::Set(rng.gen_range(0..N), rng.gen_range(0..1000))
+                } else {
+                    let mut l = rng.gen_range(0..=N);
+                    let mut r = rng.gen_range(0..=N);
+                    if l > r {
+                        std::mem::swap(&mut l, &mut r);
+                    }
+                    Query::Sum(l..r)
+                }
+            })
+            .collect::<Vec<_>>();
+        // Only the sum of the whole range reads the root, so make sure that the sequence fails.
+        queries.push(Query::Set(rng.gen_range(0..N), rng.gen_range(1..1000)));
+        queries.push(Query::Sum(0..N));
+        assert!(fails(&queries));
+        let queries = shrink_sequence(queries, fails);
+        assert!(queries.len() <= 3);
+        assert_eq!(queries, vec![Query::Set(0, 1), Query::Sum(0..8)]);
+        assert_eq!(
+            reproducer(&queries),
+            "vec![\n    Set(0, 1),\n    Sum(0..8),\n]"
+        );
+    }
+
+    #[test]
+    fn test_one_minimal() {
+        let mut rng = StdRng::seed_from_u64(42);
+        for _ in 0..100 {
+            let n = rng.gen_range(1..=30);
+            let a = (0..n)
+                .map(|_| (rng.gen_range(0..20), rng.gen_range(-20..20)))
+                .collect::<Vec<(usize, i32)>>();
+            let threshold = rng.gen_range(0..50);
+            // Fails if some $x$ and $y$ sum up to at least the threshold.
+            let fails =
+                |a: &[(usize, i32)]| a.iter().map(|&(x, y)| x as i32 + y).sum::<i32>() >= threshold;
+            if !fails(&a) {
+                continue;
+            }
+            let a = shrink_sequence(a, fails);
+            assert!(fails(&a));
+            for i in 0..a.len() {
+                let mut b = a.clone();
+                b.remove(i);
+                assert!(!fails(&b));
+                for x in a[i].shrink() {
+                    let mut b = a.clone();
+                    b[i] = x;
+                    assert!(!fails(&b));
+                }
+            }
+        }
+    }
+
+    // Follows the second simplest candidate, which halves integers.
+    fn assert_terminates<T: Shrink + Clone + Debug + PartialEq>(x: T) {
+        let mut x = x;
+        for _ in 0..1000 {
+            let mut candidates = x.shrink();
+            if candidates.is_empty() {
+                return;
+            }
+            let y = candidates.swap_remove(1.min(candidates.len() - 1));
+            assert_ne!(y, x);
+            x = y;
+        }
+        panic!("{:?} keeps shrinking", x);
+    }
+
+    #[test]
+    fn test_shrink_candidates() {
+        assert_eq!(10_usize.shrink(), vec![0, 5, 9]);
+        assert_eq!(1_u64.shrink(), vec![0]);
+        assert!(0_u32.shrink().is_empty());
+        assert_eq!((-10_i64).shrink(), vec![0, 10, -5, -9]);
+        assert_eq!(10_i64.shrink(), vec![0, 5, 9]);
+        assert!(0_i32.shrink().is_empty());
+        assert_eq!(true.shrink(), vec![false]);
+        assert_eq!((3..7).shrink(), vec![0..4, 3..5, 3..6, 2..6, 4..7]);
+        assert_eq!((0..1).shrink(), vec![0..0, 1..1]);
+        assert_eq!((2..2).shrink(), vec![0..0]);
+        assert!((0..0).shrink().is_empty());
+        assert_eq!((1_usize, true).shrink(), vec![(0, true), (1, false)]);
+        assert_terminates(usize::MAX);
+        assert_terminates(i64::MIN);
+        assert_terminates(i64::MAX);
+        assert_terminates(5..100);
+        assert_terminates((3_u8, -4_i8, 2..9, true));
+    }
+}
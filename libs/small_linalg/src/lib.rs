@@ -0,0 +1,445 @@
+//! Vectors and square matrices of dimension 2 and 3 on the stack.
+//!
+//! [`Vec2`], [`Vec3`], [`Mat2`] and [`Mat3`] are `Copy` and work over any [`Scalar`], which is
+//! implemented for the signed integers, `f64` and [`Fp`].
+//!
+//! # Examples
+//!
+//! ```
+//! use fp::Fp;
+//! use small_linalg::Mat2;
+//! use small_linalg::Vec2;
+//! type F = Fp<998244353>;
+//! // Fibonacci numbers.
+//! let a = Mat2::new([[F::new(1), F::new(1)], [F::new(1), F::new(0)]]);
+//! assert_eq!(a.pow(10).0[0][1], F::new(55));
+//!
+//! let u = Vec2::new(3, 4);
+//! let v = Vec2::new(-4, 3);
+//! assert_eq!(u.dot(v), 0);
+//! assert_eq!(u.cross(v), 25);
+//! assert_eq!(u.norm2(), 25);
+//! ```
+use fp::Fp;
+use std::fmt::Debug;
+use std::ops::Add;
+use std::ops::AddAssign;
+use std::ops::Mul;
+use std::ops::MulAssign;
+use std::ops::Neg;
+use std::ops::Sub;
+use std::ops::SubAssign;
+
+/// A commutative ring.
+pub trait Scalar:
+    Copy
+    + PartialEq
+    + Debug
+    + Add<Output = Self>
+    + Sub<Output = Self>
+    + Mul<Output = Self>
+    + Neg<Output = Self>
+{
+    /// The additive identity.
+    const ZERO: Self;
+    /// The multiplicative identity.
+    const ONE: Self;
+}
+macro_rules! impl_scalar {
+    ($($t:ty),*) => {$(
+        impl Scalar for $t {
+            const ZERO: Self = 0 as $t;
+            const ONE: Self = 1 as $t;
+        }
+    )*};
+}
+impl_scalar!(i8, i16, i32, i64, i128, isize, f64);
+impl<const P: u64> Scalar for Fp<P> {
+    const ONE: Self = Fp::new(1);
+    const ZERO: Self = Fp::new(0);
+}
+
+macro_rules! impl_vector {
+    ($name:ident, $($field:ident),*) => {
+        impl<T> $name<T> {
+            /// Constructs a new vector.
+            pub const fn new($($field: T),*) -> Self {
+                Self { $($field),* }
+            }
+        }
+        impl<T: Scalar> $name<T> {
+            /// Returns the zero vector.
+            pub const fn zero() -> Self {
+                Self { $($field: T::ZERO),* }
+            }
+
+            /// Returns the dot product.
+            pub fn dot(self, rhs: Self) -> T {
+                T::ZERO $(+ self.$field * rhs.$field)*
+            }
+
+            /// Returns the squared Euclidean norm.
+            pub fn norm2(self) -> T {
+                self.dot(self)
+            }
+        }
+        impl<T: Scalar> Add for $name<T> {
+            type Output = Self;
+
+            fn add(self, rhs: Self) -> Self {
+                Self { $($field: self.$field + rhs.$field),* }
+            }
+        }
+        impl<T: Scalar> Sub for $name<T> {
+            type Output = Self;
+
+            fn sub(self, rhs: Self) -> Self {
+                Self { $($field: self.$field - rhs.$field),* }
+            }
+        }
+        impl<T: Scalar> Neg for $name<T> {
+            type Output = Self;
+
+            fn neg(self) -> Self {
+                Self { $($field: -self.$field),* }
+            }
+        }
+        impl<T: Scalar> Mul<T> for $name<T> {
+            type Output = Self;
+
+            fn mul(self, rhs: T) -> Self {
+                Self { $($field: self.$field * rhs),* }
+            }
+        }
+        impl<T: Scalar> AddAssign for $name<T> {
+            fn add_assign(&mut self, rhs: Self) {
+                *self = *self + rhs;
+            }
+        }
+        impl<T: Scalar> SubAssign for $name<T> {
+            fn sub_assign(&mut self, rhs: Self) {
+                *self = *self - rhs;
+            }
+        }
+        impl<T: Scalar> MulAssign<T> for $name<T> {
+            fn mul_assign(&mut self, rhs: T) {
+                *self = *self * rhs;
+            }
+        }
+    };
+}
+
+/// A two-dimensional vector.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct Vec2<T> {
+    pub x: T,
+    pub y: T,
+}
+impl_vector!(Vec2, x, y);
+impl<T: Scalar> Vec2<T> {
+    /// Returns $x _ 1 y _ 2 - x _ 2 y _ 1$, which is positive if `rhs` is counterclockwise from
+    /// `self`.
+    pub fn cross(self, rhs: Self) -> T {
+        self.x * rhs.y - self.y * rhs.x
+    }
+}
+
+/// A three-dimensional vector.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct Vec3<T> {
+    pub x: T,
+    pub y: T,
+    pub z: T,
+}
+impl_vector!(Vec3, x, y, z);
+impl<T: Scalar> Vec3<T> {
+    /// Returns the cross product in the right-handed system, that is, $e _ x \times e _ y = e _ z$.
+    pub fn cross(self, rhs: Self) -> Self {
+        Self {
+            x: self.y * rhs.z - self.z * rhs.y,
+            y: self.z * rhs.x - self.x * rhs.z,
+            z: self.x * rhs.y - self.y * rhs.x,
+        }
+    }
+}
+
+macro_rules! impl_matrix {
+    ($name:ident, $vec:ident, $n:literal, $($i:literal $field:ident),*) => {
+        impl<T> $name<T> {
+            /// Constructs a new matrix from the rows.
+            pub const fn new(a: [[T; $n]; $n]) -> Self {
+                Self(a)
+            }
+        }
+        impl<T: Scalar> $name<T> {
+            /// Returns the zero matrix.
+            pub const fn zero() -> Self {
+                Self([[T::ZERO; $n]; $n])
+            }
+
+            /// Returns the identity matrix.
+            pub const fn identity() -> Self {
+                let mut a = [[T::ZERO; $n]; $n];
+                let mut i = 0;
+                while i < $n {
+                    a[i][i] = T::ONE;
+                    i += 1;
+                }
+                Self(a)
+            }
+
+            /// Returns the transpose.
+            pub fn transpose(self) -> Self {
+                let mut a = self.0;
+                for i in 0..$n {
+                    for j in 0..$n {
+                        a[i][j] = self.0[j][i];
+                    }
+                }
+                Self(a)
+            }
+
+            /// Returns $A ^ e$.
+            ///
+            /// # Complexity
+            ///
+            /// $O(\log e)$ multiplications
+            pub fn pow(self, mut exp: u64) -> Self {
+                let mut result = Self::identity();
+                let mut base = self;
+                while exp != 0 {
+                    if exp & 1 == 1 {
+                        result *= base;
+                    }
+                    base *= base;
+                    exp >>= 1;
+                }
+                result
+            }
+        }
+        impl<T: Scalar> Add for $name<T> {
+            type Output = Self;
+
+            fn add(mut self, rhs: Self) -> Self {
+                for (x, y) in self.0.iter_mut().flatten().zip(rhs.0.iter().flatten()) {
+                    *x = *x + *y;
+                }
+                self
+            }
+        }
+        impl<T: Scalar> Sub for $name<T> {
+            type Output = Self;
+
+            fn sub(mut self, rhs: Self) -> Self {
+                for (x, y) in self.0.iter_mut().flatten().zip(rhs.0.iter().flatten()) {
+                    *x = *x - *y;
+                }
+                self
+            }
+        }
+        impl<T: Scalar> Mul for $name<T> {
+            type Output = Self;
+
+            fn mul(self, rhs: Self) -> Self {
+                let mut a = [[T::ZERO; $n]; $n];
+                for i in 0..$n {
+                    for k in 0..$n {
+                        for j in 0..$n {
+                            a[i][j] = a[i][j] + self.0[i][k] * rhs.0[k][j];
+                        }
+                    }
+                }
+                Self(a)
+            }
+        }
+        impl<T: Scalar> Mul<$vec<T>> for $name<T> {
+            type Output = $vec<T>;
+
+            fn mul(self, rhs: $vec<T>) -> $vec<T> {
+                let row = |i: usize| $vec::new($(self.0[i][$i]),*);
+                $vec { $($field: row($i).dot(rhs)),* }
+            }
+        }
+        impl<T: Scalar> Mul<T> for $name<T> {
+            type Output = Self;
+
+            fn mul(mut self, rhs: T) -> Self {
+                for x in self.0.iter_mut().flatten() {
+                    *x = *x * rhs;
+                }
+                self
+            }
+        }
+        impl<T: Scalar> MulAssign for $name<T> {
+            fn mul_assign(&mut self, rhs: Self) {
+                *self = *self * rhs;
+            }
+        }
+        impl<T: Scalar> Default for $name<T> {
+            fn default() -> Self {
+                Self::zero()
+            }
+        }
+    };
+}
+
+/// A $2 \times 2$ matrix, stored as the array of the rows.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct Mat2<T>(pub [[T; 2]; 2]);
+impl_matrix!(Mat2, Vec2, 2, 0 x, 1 y);
+impl<T: Scalar> Mat2<T> {
+    /// Returns the determinant.
+    pub fn det(self) -> T {
+        let [[a, b], [c, d]] = self.0;
+        a * d - b * c
+    }
+}
+
+/// A $3 \times 3$ matrix, stored as the array of the rows.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct Mat3<T>(pub [[T; 3]; 3]);
+impl_matrix!(Mat3, Vec3, 3, 0 x, 1 y, 2 z);
+impl<T: Scalar> Mat3<T> {
+    /// Returns the determinant, which is the scalar triple product of the rows.
+    pub fn det(self) -> T {
+        let [a, b, c] = self.0.map(|[x, y, z]| Vec3::new(x, y, z));
+        a.dot(b.cross(c))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rand::rngs::StdRng;
+    use rand::Rng;
+    use rand::SeedableRng;
+
+    type F = Fp<998244353>;
+
+    fn mat2(rng: &mut StdRng) -> Mat2<i128> {
+        Mat2::new([[(); 2]; 2].map(|row| row.map(|()| rng.gen_range(-100..=100))))
+    }
+
+    fn mat3(rng: &mut StdRng) -> Mat3<i128> {
+        Mat3::new([[(); 3]; 3].map(|row| row.map(|()| rng.gen_range(-100..=100))))
+    }
+
+    fn vec3(rng: &mut StdRng) -> Vec3<i128> {
+        Vec3::new(
+            rng.gen_range(-100..=100),
+            rng.gen_range(-100..=100),
+            rng.gen_range(-100..=100),
+        )
+    }
+
+    #[test]
+    fn test_matrix_identities() {
+        let mut rng = StdRng::seed_from_u64(42);
+        for _ in 0..200 {
+            let (a, b, c) = (mat2(&mut rng), mat2(&mut rng), mat2(&mut rng));
+            assert_eq!((a * b) * c, a * (b * c));
+            assert_eq!(a * (b + c), a * b + a * c);
+            assert_eq!((a * b).det(), a.det() * b.det());
+            assert_eq!((a * b).transpose(), b.transpose() * a.transpose());
+            assert_eq!(a * Mat2::identity(), a);
+            assert_eq!(a - a, Mat2::zero());
+            let v = Vec2::new(rng.gen_range(-100..=100), rng.gen_range(-100..=100));
+            assert_eq!((a * b) * v, a * (b * v));
+            assert_eq!(a.pow(3), a * a * a);
+
+            let (a, b, c) = (mat3(&mut rng), mat3(&mut rng), mat3(&mut rng));
+            assert_eq!((a * b) * c, a * (b * c));
+            assert_eq!(a * (b + c), a * b + a * c);
+            assert_eq!((a * b).det(), a.det() * b.det());
+            assert_eq!(a.transpose().det(), a.det());
+            assert_eq!((a * 3).det(), a.det() * 27);
+            assert_eq!(Mat3::identity() * a, a);
+            let v = vec3(&mut rng);
+            assert_eq!((a * b) * v, a * (b * v));
+            assert_eq!(a.pow(4), a * a * a * a);
+            assert_eq!(a.pow(0), Mat3::identity());
+        }
+    }
+
+    #[test]
+    fn test_fibonacci() {
+        let a = Mat2::new([[F::new(1), F::new(1)], [F::new(1), F::new(0)]]);
+        let mut fib = vec![F::new(0), F::new(1)];
+        for i in 2..=1000 {
+            fib.push(fib[i - 1] + fib[i - 2]);
+        }
+        for n in 0..1000 {
+            let result = a.pow(n as u64);
+            assert_eq!(
+                result,
+                Mat2::new([[fib[n + 1], fib[n]], [fib[n], fib[n + 1] - fib[n]]])
+            );
+            assert_eq!(
+                result * Vec2::new(F::new(1), F::new(0)),
+                Vec2::new(fib[n + 1], fib[n])
+            );
+        }
+        // $A ^ {m + n} = A ^ m A ^ n$, and $\det A ^ n = (-1) ^ n$.
+        let mut rng = StdRng::seed_from_u64(42);
+        for _ in 0..100 {
+            let m = rng.gen_range(0..1 << 62);
+            let n = rng.gen_range(0..1 << 62);
+            assert_eq!(a.pow(m + n), a.pow(m) * a.pow(n));
+            assert_eq!(a.pow(n).det(), F::sign(n as usize));
+        }
+    }
+
+    #[test]
+    fn test_cross_orientation() {
+        let ex = Vec3::new(1, 0, 0);
+        let ey = Vec3::new(0, 1, 0);
+        let ez = Vec3::new(0, 0, 1);
+        assert_eq!(ex.cross(ey), ez);
+        assert_eq!(ey.cross(ez), ex);
+        assert_eq!(ez.cross(ex), ey);
+        assert_eq!(ey.cross(ex), -ez);
+        assert_eq!(
+            Vec3::new(1, 2, 3).cross(Vec3::new(4, 5, 6)),
+            Vec3::new(-3, 6, -3)
+        );
+        assert_eq!(Vec2::new(1, 0).cross(Vec2::new(0, 1)), 1);
+        assert_eq!(Vec2::new(0, 1).cross(Vec2::new(1, 0)), -1);
+        assert_eq!(Vec2::new(2, 3).cross(Vec2::new(4, 6)), 0);
+
+        let mut rng = StdRng::seed_from_u64(42);
+        for _ in 0..200 {
+            let (a, b, c) = (vec3(&mut rng), vec3(&mut rng), vec3(&mut rng));
+            assert_eq!(a.cross(b), -b.cross(a));
+            assert_eq!(a.dot(a.cross(b)), 0);
+            assert_eq!(b.dot(a.cross(b)), 0);
+            // Lagrange's identity and the triple product.
+            assert_eq!(
+                a.cross(b).norm2(),
+                a.norm2() * b.norm2() - a.dot(b) * a.dot(b)
+            );
+            assert_eq!(
+                a.dot(b.cross(c)),
+                Mat3::new([[a.x, a.y, a.z], [b.x, b.y, b.z], [c.x, c.y, c.z]]).det()
+            );
+            assert_eq!(a.cross(b.cross(c)), b * a.dot(c) - c * a.dot(b));
+        }
+    }
+
+    #[test]
+    fn test_vector_arithmetic() {
+        let mut u = Vec2::new(1_i64, 2);
+        u += Vec2::new(3, 4);
+        assert_eq!(u, Vec2::new(4, 6));
+        u -= Vec2::new(1, 1);
+        assert_eq!(u, Vec2::new(3, 5));
+        u *= 2;
+        assert_eq!(u, Vec2::new(6, 10));
+        assert_eq!(-u, Vec2::new(-6, -10));
+        assert_eq!(Vec3::<i64>::zero(), Vec3::default());
+        const ORIGIN: Vec2<F> = Vec2::zero();
+        const I: Mat3<i64> = Mat3::identity();
+        assert_eq!(ORIGIN, Vec2::new(F::new(0), F::new(0)));
+        assert_eq!(I.det(), 1);
+        assert_eq!(Vec2::new(0.5, 1.5).dot(Vec2::new(2.0, 2.0)), 4.0);
+    }
+}
@@ -0,0 +1,451 @@
+//! A sorted multiset as a list of sorted blocks.
+//!
+//! The elements are kept in blocks of size about $B = \Theta(\sqrt n)$. A block longer than $2B$
+//! is split into halves, an emptied block is dropped, and the whole list is rebuilt into blocks of
+//! size $B$ when the number of blocks exceeds $4 (n / B + 1)$. Each operation moves $O(B)$
+//! contiguous elements, which is fast in practice for up to about $10 ^ 6$ elements.
+//!
+//! # Examples
+//!
+//! ```
+//! use sorted_list::SortedList;
+//! let mut list = SortedList::new();
+//! list.insert(3);
+//! list.insert(1);
+//! list.insert(4);
+//! list.insert(1);
+//! assert_eq!(list.iter().copied().collect::<Vec<_>>(), vec![1, 1, 3, 4]);
+//! assert_eq!(list.kth(2), Some(&3));
+//! assert_eq!(list.bisect_left(&3), 2);
+//! assert_eq!(list.bisect_right(&1), 2);
+//! assert!(list.remove(&1));
+//! assert!(!list.remove(&5));
+//! assert_eq!(list.count(&1), 1);
+//! ```
+use std::fmt::Debug;
+use std::iter::FromIterator;
+
+const MIN_BLOCK_SIZE: usize = 32;
+
+#[cfg(test)]
+thread_local! {
+    static REBUILDS: std::cell::Cell<usize> = std::cell::Cell::new(0);
+}
+
+/// A sorted multiset.
+#[derive(Clone, PartialEq, Eq, Hash)]
+pub struct SortedList<T> {
+    blocks: Vec<Vec<T>>,
+    len: usize,
+}
+impl<T: Ord> SortedList<T> {
+    /// Constructs an empty list.
+    pub fn new() -> Self {
+        Self {
+            blocks: Vec::new(),
+            len: 0,
+        }
+    }
+
+    /// Returns the number of the elements.
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    /// Returns `true` if the list is empty.
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    /// Inserts `value`.
+    ///
+    /// # Complexity
+    ///
+    /// $O(\sqrt n)$ amortized
+    pub fn insert(&mut self, value: T) {
+        self.len += 1;
+        if self.blocks.is_empty() {
+            self.blocks.push(vec![value]);
+            return;
+        }
+        let i = self
+            .blocks
+            .partition_point(|block| *block.last().unwrap() < value)
+            .min(self.blocks.len() - 1);
+        let block = &mut self.blocks[i];
+        let j = block.partition_point(|x| *x < value);
+        block.insert(j, value);
+        if block.len() > 2 * block_size(self.len) {
+            let upper = block.split_off(block.len() / 2);
+            self.blocks.insert(i + 1, upper);
+            self.rebuild_if_fragmented();
+        }
+    }
+
+    /// Removes an element equal to `value`, and returns `true` if it exists.
+    ///
+    /// # Complexity
+    ///
+    /// $O(\sqrt n)$ amortized
+    pub fn remove(&mut self, value: &T) -> bool {
+        let i = self
+            .blocks
+            .partition_point(|block| block.last().unwrap() < value);
+        let Some(block) = self.blocks.get_mut(i) else {
+            return false;
+        };
+        let j = block.partition_point(|x| x < value);
+        if block[j] != *value {
+            return false;
+        }
+        block.remove(j);
+        self.len -= 1;
+        if block.is_empty() {
+            self.blocks.remove(i);
+        }
+        self.rebuild_if_fragmented();
+        true
+    }
+
+    /// Removes the $k$-th smallest element (0-based).
+    pub fn remove_kth(&mut self, k: usize) -> Option<T> {
+        let (i, j) = self.locate(k)?;
+        let value = self.blocks[i].remove(j);
+        self.len -= 1;
+        if self.blocks[i].is_empty() {
+            self.blocks.remove(i);
+        }
+        self.rebuild_if_fragmented();
+        Some(value)
+    }
+
+    /// Returns the $k$-th smallest element (0-based).
+    ///
+    /// # Complexity
+    ///
+    /// $O(\sqrt n)$
+    pub fn kth(&self, k: usize) -> Option<&T> {
+        self.locate(k).map(|(i, j)| &self.blocks[i][j])
+    }
+
+    /// Returns the smallest element.
+    pub fn first(&self) -> Option<&T> {
+        self.blocks.first().map(|block| &block[0])
+    }
+
+    /// Returns the largest element.
+    pub fn last(&self) -> Option<&T> {
+        self.blocks.last().map(|block| block.last().unwrap())
+    }
+
+    /// Returns the number of the elements less than `value`.
+    ///
+    /// # Complexity
+    ///
+    /// $O(\sqrt n)$
+    pub fn bisect_left(&self, value: &T) -> usize {
+        self.partition_point(|x| x < value)
+    }
+
+    /// Returns the number of the elements less than or equal to `value`.
+    ///
+    /// # Complexity
+    ///
+    /// $O(\sqrt n)$
+    pub fn bisect_right(&self, value: &T) -> usize {
+        self.partition_point(|x| x <= value)
+    }
+
+    /// Returns the number of the elements equal to `value`.
+    pub fn count(&self, value: &T) -> usize {
+        self.bisect_right(value) - self.bisect_left(value)
+    }
+
+    /// Returns `true` if the list contains `value`.
+    pub fn contains(&self, value: &T) -> bool {
+        let i = self
+            .blocks
+            .partition_point(|block| block.last().unwrap() < value);
+        self.blocks
+            .get(i)
+            .map_or(false, |block| block.binary_search(value).is_ok())
+    }
+
+    /// Returns the number of the elements satisfying `pred`, which must be monotone.
+    pub fn partition_point(&self, mut pred: impl FnMut(&T) -> bool) -> usize {
+        let i = self
+            .blocks
+            .partition_point(|block| pred(block.last().unwrap()));
+        let before = self.blocks[..i].iter().map(Vec::len).sum::<usize>();
+        before
+            + self
+                .blocks
+                .get(i)
+                .map_or(0, |block| block.partition_point(pred))
+    }
+
+    /// Returns an iterator of the elements in ascending order.
+    pub fn iter(&self) -> impl DoubleEndedIterator<Item = &T> {
+        self.blocks.iter().flatten()
+    }
+
+    fn locate(&self, mut k: usize) -> Option<(usize, usize)> {
+        for (i, block) in self.blocks.iter().enumerate() {
+            if k < block.len() {
+                return Some((i, k));
+            }
+            k -= block.len();
+        }
+        None
+    }
+
+    fn rebuild_if_fragmented(&mut self) {
+        let size = block_size(self.len);
+        if self.blocks.len() > 4 * (self.len / size + 1) {
+            #[cfg(test)]
+            REBUILDS.with(|c| c.set(c.get() + 1));
+            let all = std::mem::take(&mut self.blocks)
+                .into_iter()
+                .flatten()
+                .collect::<Vec<_>>();
+            self.blocks = chunks(all, size);
+        }
+    }
+}
+impl<T: Ord> Default for SortedList<T> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+impl<T: Ord> FromIterator<T> for SortedList<T> {
+    fn from_iter<I: IntoIterator<Item = T>>(iter: I) -> Self {
+        let mut all = iter.into_iter().collect::<Vec<_>>();
+        all.sort();
+        let len = all.len();
+        Self {
+            blocks: chunks(all, block_size(len)),
+            len,
+        }
+    }
+}
+impl<T: Ord> Extend<T> for SortedList<T> {
+    fn extend<I: IntoIterator<Item = T>>(&mut self, iter: I) {
+        for value in iter {
+            self.insert(value);
+        }
+    }
+}
+impl<T: Debug> Debug for SortedList<T> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_list()
+            .entries(self.blocks.iter().flatten())
+            .finish()
+    }
+}
+
+fn block_size(len: usize) -> usize {
+    ((len as f64).sqrt() as usize).max(MIN_BLOCK_SIZE)
+}
+
+fn chunks<T>(all: Vec<T>, size: usize) -> Vec<Vec<T>> {
+    let mut blocks = Vec::with_capacity((all.len() + size - 1) / size);
+    let mut all = all.into_iter().peekable();
+    while all.peek().is_some() {
+        blocks.push(all.by_ref().take(size).collect());
+    }
+    blocks
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rand::rngs::StdRng;
+    use rand::Rng;
+    use rand::SeedableRng;
+    use std::collections::BTreeMap;
+
+    fn validate<T: Ord + Debug>(list: &SortedList<T>) {
+        assert!(list.blocks.iter().all(|block| !block.is_empty()));
+        assert_eq!(list.blocks.iter().map(Vec::len).sum::<usize>(), list.len);
+        assert!(list.iter().zip(list.iter().skip(1)).all(|(x, y)| x <= y));
+        assert!(list.blocks.len() <= 4 * (list.len / block_size(list.len) + 1));
+    }
+
+    struct Multiset(BTreeMap<u64, usize>);
+    impl Multiset {
+        fn insert(&mut self, x: u64) {
+            *self.0.entry(x).or_insert(0) += 1;
+        }
+
+        fn remove(&mut self, x: u64) -> bool {
+            match self.0.get_mut(&x) {
+                None => false,
+                Some(c) => {
+                    *c -= 1;
+                    if *c == 0 {
+                        self.0.remove(&x);
+                    }
+                    true
+                }
+            }
+        }
+
+        fn kth(&self, mut k: usize) -> Option<u64> {
+            for (&x, &c) in &self.0 {
+                if k < c {
+                    return Some(x);
+                }
+                k -= c;
+            }
+            None
+        }
+
+        fn less(&self, x: u64) -> usize {
+            self.0.range(..x).map(|(_, &c)| c).sum()
+        }
+
+        fn len(&self) -> usize {
+            self.0.values().sum()
+        }
+    }
+
+    fn random_workload(rng: &mut StdRng, q: usize, max: u64) {
+        let mut list = SortedList::new();
+        let mut reference = Multiset(BTreeMap::new());
+        for _ in 0..q {
+            let x = rng.gen_range(0..max);
+            match rng.gen_range(0..7) {
+                0 | 1 => {
+                    list.insert(x);
+                    reference.insert(x);
+                }
+                2 => assert_eq!(list.remove(&x), reference.remove(x)),
+                3 => {
+                    let k = rng.gen_range(0..=list.len());
+                    let expected = reference.kth(k);
+                    assert_eq!(list.kth(k).copied(), expected);
+                    if rng.gen_ratio(1, 4) {
+                        assert_eq!(list.remove_kth(k), expected);
+                        if let Some(x) = expected {
+                            reference.remove(x);
+                        }
+                    }
+                }
+                4 => {
+                    assert_eq!(list.bisect_left(&x), reference.less(x));
+                    assert_eq!(list.bisect_right(&x), reference.less(x + 1));
+                }
+                5 => {
+                    assert_eq!(list.contains(&x), reference.0.contains_key(&x));
+                    assert_eq!(list.count(&x), reference.0.get(&x).copied().unwrap_or(0));
+                }
+                6 => {
+                    assert_eq!(list.first(), reference.0.keys().next());
+                    assert_eq!(list.last(), reference.0.keys().next_back());
+                }
+                _ => unreachable!(),
+            }
+        }
+        assert_eq!(list.len(), reference.len());
+        validate(&list);
+        let expected = reference
+            .0
+            .iter()
+            .flat_map(|(&x, &c)| std::iter::repeat(x).take(c))
+            .collect::<Vec<_>>();
+        assert!(list.iter().copied().eq(expected.iter().copied()));
+        assert!(list
+            .iter()
+            .rev()
+            .copied()
+            .eq(expected.iter().rev().copied()));
+    }
+
+    #[test]
+    fn test_random_small_values() {
+        let mut rng = StdRng::seed_from_u64(42);
+        for _ in 0..10 {
+            random_workload(&mut rng, 20000, 1000);
+        }
+    }
+
+    #[test]
+    fn test_random_large_values() {
+        let mut rng = StdRng::seed_from_u64(42);
+        for _ in 0..10 {
+            random_workload(&mut rng, 3000, 1_000_000_000);
+        }
+        for _ in 0..1000 {
+            random_workload(&mut rng, 20, 10);
+        }
+    }
+
+    #[test]
+    fn test_insert_at_one_end() {
+        let n = 100000;
+        let before = REBUILDS.with(|c| c.get());
+        let mut list = SortedList::new();
+        for i in 0..n {
+            list.insert(i);
+        }
+        validate(&list);
+        for i in (n..2 * n).rev() {
+            list.insert(i);
+        }
+        validate(&list);
+        for i in (0..n).map(|i| n - 1 - i) {
+            list.insert(i);
+        }
+        validate(&list);
+        assert!(list
+            .iter()
+            .copied()
+            .eq((0..n).flat_map(|i| [i, i]).chain(n..2 * n)));
+        assert_eq!(REBUILDS.with(|c| c.get()), before);
+        // Removing from one end leaves few blocks, and removing every other element fragments.
+        for i in 0..n {
+            assert!(list.remove(&i));
+            if i % 1000 == 0 {
+                validate(&list);
+            }
+        }
+        for i in (n..2 * n).step_by(2) {
+            assert!(list.remove(&i));
+        }
+        validate(&list);
+        assert_eq!(list.len(), n + n / 2);
+        assert_eq!(list.kth(n + n / 2 - 1), Some(&(2 * n - 1)));
+    }
+
+    #[test]
+    fn test_alternating_same_value() {
+        let mut list = (0..1000).collect::<SortedList<_>>();
+        for _ in 0..100000 {
+            list.insert(500);
+            assert!(list.remove(&500));
+        }
+        for _ in 0..100000 {
+            assert!(list.remove(&0));
+            list.insert(0);
+        }
+        validate(&list);
+        assert!(list.iter().copied().eq(0..1000));
+    }
+
+    #[test]
+    fn test_rebuild_is_amortized() {
+        let mut rng = StdRng::seed_from_u64(42);
+        let before = REBUILDS.with(|c| c.get());
+        let mut list = (0..100000).collect::<SortedList<u32>>();
+        // Emptying blocks one by one makes the list fragmented.
+        while list.len() > 100 {
+            let k = rng.gen_range(0..list.len());
+            list.remove_kth(k);
+            if list.len() % 1000 == 0 {
+                validate(&list);
+            }
+        }
+        let rebuilds = REBUILDS.with(|c| c.get()) - before;
+        assert!(0 < rebuilds && rebuilds <= 20, "{} rebuilds", rebuilds);
+    }
+}
@@ -7,9 +7,11 @@
 //!
 //! [`Op::mul`] must be associative and idempotent.
 
+use open::resolve_range;
 use std::fmt::Debug;
 use std::iter::FromIterator;
 use std::ops::Index;
+use std::ops::Range;
 use std::ops::RangeBounds;
 
 /// A trait for the operation used in sparse tables.
@@ -46,8 +48,7 @@ impl<O: Op> SparseTable<O> {
 
     /// Returns $x_l \cdot x_{l+1} \cdot \ldots \cdot x_{r-1}$, or `None` if $l = r$.
     pub fn fold(&self, range: impl RangeBounds<usize>) -> Option<O::Value> {
-        let (start, end) = open(range, self.table[0].len());
-        assert!(start <= end);
+        let Range { start, end } = resolve_range(self.table[0].len(), range);
         (start < end).then_some(())?;
         let p = (end - start).ilog2() as usize;
         let row = &self.table[p];
@@ -154,10 +155,14 @@ impl<O: Op> SparseTable2d<O> {
 
     /// Returns $(x_{i_0, j_0} \cdot \dots \cdot x_{i_1-1, j_0}) \cdot \dots \cdot (x_{i_0, j_1-1} \cdot \dots \cdot x_{i_1-1, j_1-1})$, or `None` if $i_0 = i_1$ or $j_0 = j_1$.
     pub fn fold(&self, i: impl RangeBounds<usize>, j: impl RangeBounds<usize>) -> Option<O::Value> {
-        let (i0, mut i1) = open(i, self.table[0][0].len());
-        let (j0, mut j1) = open(j, self.table[0][0].first().map_or(0, Vec::len));
-        assert!(i0 <= i1);
-        assert!(j0 <= j1);
+        let Range {
+            start: i0,
+            end: mut i1,
+        } = resolve_range(self.table[0][0].len(), i);
+        let Range {
+            start: j0,
+            end: mut j1,
+        } = resolve_range(self.table[0][0].first().map_or(0, Vec::len), j);
         (i0 < i1 && j0 < j1).then_some(())?;
         let p = (i1 - i0).ilog2() as usize;
         let q = (j1 - j0).ilog2() as usize;
@@ -258,28 +263,12 @@ where
     }
 }
 
-fn open<B: RangeBounds<usize>>(bounds: B, n: usize) -> (usize, usize) {
-    use std::ops::Bound;
-    let start = match bounds.start_bound() {
-        Bound::Unbounded => 0,
-        Bound::Included(&x) => x,
-        Bound::Excluded(&x) => x + 1,
-    };
-    let end = match bounds.end_bound() {
-        Bound::Unbounded => n,
-        Bound::Included(&x) => x + 1,
-        Bound::Excluded(&x) => x,
-    };
-    (start, end)
-}
-
 #[cfg(test)]
 mod tests {
     use super::*;
     use rand::rngs::StdRng;
     use rand::Rng;
     use rand::SeedableRng;
-    use std::ops::Range;
 
     enum O {}
     impl Op for O {
@@ -347,4 +336,23 @@ mod tests {
             end..start - 1
         }
     }
+
+    #[test]
+    #[should_panic(expected = "range starts at 3 but ends at 2")]
+    fn test_fold_reversed_range() {
+        let (start, end) = (3, 2);
+        SparseTable::<O>::from(vec![0; 5]).fold(start..end);
+    }
+
+    #[test]
+    #[should_panic(expected = "range end index 6 out of range for length 5")]
+    fn test_fold_out_of_range() {
+        SparseTable::<O>::from(vec![0; 5]).fold(..6);
+    }
+
+    #[test]
+    #[should_panic(expected = "attempted to index up to maximum usize")]
+    fn test_fold_2d_included_usize_max() {
+        SparseTable2d::<O>::from(vec![vec![0; 2]; 3]).fold(.., ..=usize::MAX);
+    }
 }
@@ -42,21 +42,29 @@ mod test_dynamic_sequence_range_affine_range;
 #[cfg(test)]
 mod test_hash_swapping;
 
+#[cfg(test)]
+mod test_cat;
+
+#[cfg(test)]
+mod test_snapshot;
+
 #[cfg(test)]
 mod test_trivial;
 
 use self::node::access_index;
 use self::node::deep_free;
+use self::node::free_snapshot;
 use self::node::merge;
 use self::node::split_at;
+use self::node::visit_frozen;
 use self::node::Node;
+use open::resolve_range;
 use std::cell::Cell;
 use std::cmp::Ordering;
 use std::fmt::Debug;
 use std::hash::Hash;
 use std::iter::FromIterator;
 use std::marker::PhantomData;
-use std::ops::Bound;
 use std::ops::Deref;
 use std::ops::DerefMut;
 use std::ops::Index;
@@ -95,6 +103,10 @@ pub trait Ops {
     fn proj(value: &Self::Value) -> Self::Acc;
     /// 集約演算
     fn op(lhs: &Self::Acc, rhs: &Self::Acc) -> Self::Acc;
+    /// 列を反転したときの集約値の変化 (既定では何もしません)
+    ///
+    /// 集約演算が可換でないときに [`SplayTree::reverse`] を使うならば、集約値に逆順の集約値も持たせて、ここで入れ替えます。
+    fn reverse_acc(_acc: &mut Self::Acc) {}
 }
 /// [`Ops`] を実装する型をラップして [`LazyOps`] を実装する型
 pub struct NoLazy<O>(PhantomData<fn(O) -> O>);
@@ -111,6 +123,10 @@ impl<O: Ops> LazyOps for NoLazy<O> {
         O::op(lhs, rhs)
     }
 
+    fn reverse_acc(acc: &mut Self::Acc) {
+        O::reverse_acc(acc)
+    }
+
     fn act_value(&(): &Self::Lazy, _value: &mut Self::Value) {}
 
     fn act_acc(&(): &Self::Lazy, _acc: &mut Self::Acc) {}
@@ -119,6 +135,52 @@ impl<O: Ops> LazyOps for NoLazy<O> {
 }
 
 /// 集約と作用のトレイト
+///
+/// 作用がないことは `Option<Self::Lazy>` の `None` で表すので、単位元は要りません。
+///
+/// # Examples
+///
+/// 区間アフィン変換、区間和です。
+///
+/// ```
+/// # use splay_tree::{LazyOps, SplayTree};
+/// enum Affine {}
+/// impl LazyOps for Affine {
+///     // (和, 長さ)
+///     type Acc = (i64, i64);
+///     // x ↦ ax + b
+///     type Lazy = (i64, i64);
+///     type Value = i64;
+///
+///     fn proj(&x: &i64) -> (i64, i64) {
+///         (x, 1)
+///     }
+///
+///     fn op(&(x, n): &(i64, i64), &(y, m): &(i64, i64)) -> (i64, i64) {
+///         (x + y, n + m)
+///     }
+///
+///     fn act_value(&(a, b): &(i64, i64), x: &mut i64) {
+///         *x = a * *x + b;
+///     }
+///
+///     fn act_acc(&(a, b): &(i64, i64), (x, n): &mut (i64, i64)) {
+///         *x = a * *x + b * *n;
+///     }
+///
+///     fn compose(&(a, b): &(i64, i64), (c, d): &mut (i64, i64)) {
+///         *d = a * *d + b;
+///         *c *= a;
+///     }
+/// }
+/// let mut splay = (1..=5).collect::<SplayTree<Affine>>();
+/// splay.act(1..4, (2, 1));
+/// splay.insert(2, 10);
+/// assert_eq!(splay.iter().copied().collect::<Vec<_>>(), vec![
+///     1, 5, 10, 7, 9, 5
+/// ]);
+/// assert_eq!(splay.fold(1..4), Some((22, 3)));
+/// ```
 pub trait LazyOps {
     /// 頂点重み型
     type Value: Value;
@@ -136,6 +198,10 @@ pub trait LazyOps {
     fn act_acc(lazy: &Self::Lazy, acc: &mut Self::Acc);
     /// 作用の合成
     fn compose(upper: &Self::Lazy, lower: &mut Self::Lazy);
+    /// 列を反転したときの集約値の変化 (既定では何もしません)
+    ///
+    /// 集約演算が可換でないときに [`SplayTree::reverse`] を使うならば、集約値に逆順の集約値も持たせて、ここで入れ替えます。
+    fn reverse_acc(_acc: &mut Self::Acc) {}
     /// Option へ作用の合成
     fn compose_to_option(upper: &Self::Lazy, lower: &mut Option<Self::Lazy>) {
         match lower {
@@ -146,7 +212,9 @@ pub trait LazyOps {
 }
 
 /// スプレー木
-pub struct SplayTree<O: LazyOps>(Cell<*mut Node<O>>);
+///
+/// [`SplayTree::snapshot`] でスナップショットを取ると、それ以降はスナップショットと共有している頂点を書き換える前に複製します。
+pub struct SplayTree<O: LazyOps>(Cell<*mut Node<O>>, Vec<*mut Node<O>>);
 impl<O: LazyOps> SplayTree<O> {
     /// 空のスプレー木を構築します。
     ///
@@ -158,7 +226,7 @@ impl<O: LazyOps> SplayTree<O> {
     /// assert!(splay.is_empty());
     /// ```
     pub fn new() -> Self {
-        Self(Cell::new(null_mut()))
+        Self(Cell::new(null_mut()), Vec::new())
     }
 
     /// 空ならば `true` を返します。
@@ -220,9 +288,10 @@ impl<O: LazyOps> SplayTree<O> {
         if self.len() < at {
             splay_tree_index_out_of_range_fail(at, self.len());
         }
-        let [left, right] = split_at(self.0.get(), at);
-        let node = Box::leak(Box::new(Node::new(value)));
-        self.0.set(merge(merge(left, node), right));
+        let epoch = self.epoch();
+        let [left, right] = split_at(self.0.get(), at, epoch);
+        let node = Box::leak(Box::new(Node::new(value, epoch)));
+        self.0.set(merge(merge(left, node, epoch), right, epoch));
     }
 
     /// 指定した場所の要素を削除します。
@@ -249,15 +318,18 @@ impl<O: LazyOps> SplayTree<O> {
         if self.len() <= at {
             splay_tree_index_out_of_range_fail(at, self.len());
         }
-        let [lc, r] = split_at(self.0.get(), at + 1);
-        let [l, c] = split_at(lc, at);
+        let epoch = self.epoch();
+        let [lc, r] = split_at(self.0.get(), at + 1, epoch);
+        let [l, c] = split_at(lc, at, epoch);
         let ans = unsafe { Box::from_raw(c) }.value;
-        self.0.set(merge(l, r));
+        self.0.set(merge(l, r, epoch));
         ans
     }
 
     /// 指定した範囲の要素を逆順にします。
     ///
+    /// 集約演算が可換でないときには [`LazyOps::reverse_acc`] を実装する必要があります。
+    ///
     /// # Panics
     ///
     /// - 範囲外
@@ -279,14 +351,15 @@ impl<O: LazyOps> SplayTree<O> {
     /// ]);
     /// ```
     pub fn reverse(&mut self, range: impl RangeBounds<usize>) {
-        let Range { start, end } = into_range(self.len(), range);
-        let [lc, r] = split_at(self.0.get(), end);
-        let [l, c] = split_at(lc, start);
+        let Range { start, end } = resolve_range(self.len(), range);
+        let epoch = self.epoch();
+        let [lc, r] = split_at(self.0.get(), end, epoch);
+        let [l, c] = split_at(lc, start, epoch);
         if let Some(c) = unsafe { c.as_mut() } {
             c.rev ^= true;
-            c.push();
+            c.push(epoch);
         }
-        self.0.set(merge(merge(l, c), r));
+        self.0.set(merge(merge(l, c, epoch), r, epoch));
     }
 
     /// 指定した範囲の要素を畳み込みます。
@@ -319,14 +392,15 @@ impl<O: LazyOps> SplayTree<O> {
     /// assert_eq!(splay.fold(2..2), None);
     /// ```
     pub fn fold(&self, range: impl RangeBounds<usize>) -> Option<O::Acc> {
-        let Range { start, end } = into_range(self.len(), range);
-        let [lc, r] = split_at(self.0.get(), end);
-        let [l, c] = split_at(lc, start);
+        let Range { start, end } = resolve_range(self.len(), range);
+        let epoch = self.epoch();
+        let [lc, r] = split_at(self.0.get(), end, epoch);
+        let [l, c] = split_at(lc, start, epoch);
         let ans = unsafe { c.as_mut() }.map(|c| {
-            c.update();
+            c.update(epoch);
             c.acc.clone()
         });
-        self.0.set(merge(merge(l, c), r));
+        self.0.set(merge(merge(l, c, epoch), r, epoch));
         ans
     }
 
@@ -370,14 +444,15 @@ impl<O: LazyOps> SplayTree<O> {
     /// ]);
     /// ```
     pub fn act(&mut self, range: impl RangeBounds<usize>, lazy: O::Lazy) {
-        let Range { start, end } = into_range(self.len(), range);
-        let [lc, r] = split_at(self.0.get(), end);
-        let [l, c] = split_at(lc, start);
+        let Range { start, end } = resolve_range(self.len(), range);
+        let epoch = self.epoch();
+        let [lc, r] = split_at(self.0.get(), end, epoch);
+        let [l, c] = split_at(lc, start, epoch);
         if let Some(c) = unsafe { c.as_mut() } {
             c.lazy = Some(lazy);
-            c.push();
+            c.push(epoch);
         }
-        self.0.set(merge(merge(l, c), r));
+        self.0.set(merge(merge(l, c, epoch), r, epoch));
     }
 
     /// 指定した場所の要素への参照を返します。範囲外のときには `None` を返します。
@@ -398,8 +473,7 @@ impl<O: LazyOps> SplayTree<O> {
         if self.len() <= i {
             return None;
         }
-        let mut root = unsafe { self.0.get().as_mut() }.unwrap();
-        root = access_index(root, i);
+        let root = access_index(self.0.get(), i, self.epoch());
         self.0.set(root);
         let ans = &root.value;
         Some(ans)
@@ -423,14 +497,15 @@ impl<O: LazyOps> SplayTree<O> {
         if self.len() <= i {
             return None;
         }
-        let mut root = unsafe { self.0.get().as_mut() }.unwrap();
-        root = access_index(root, i);
+        let root = access_index(self.0.get(), i, self.epoch());
         self.0.set(root);
         Some(Entry(self))
     }
 
     /// 指定した場所以降を切り離して返します。
     ///
+    /// スナップショットがある場合は、切り離した要素を新しい頂点に複製するので、その個数の時間がかかります。
+    ///
     ///
     /// # Panics
     ///
@@ -452,13 +527,25 @@ impl<O: LazyOps> SplayTree<O> {
         if self.len() < at {
             splay_tree_index_out_of_range_fail(at, self.len());
         }
-        let [left, right] = split_at(self.0.get(), at);
+        let epoch = self.epoch();
+        let [left, right] = split_at(self.0.get(), at, epoch);
         self.0.set(left);
-        Self(Cell::new(right))
+        if epoch == 0 {
+            Self(Cell::new(right), Vec::new())
+        } else {
+            let ans = collect_values(right, 0..self.len_of(right))
+                .into_iter()
+                .collect();
+            deep_free(right, epoch);
+            ans
+        }
     }
 
     /// 受け取ったスプレー木の値をすべて後ろにつなげます。
     ///
+    /// どちらかにスナップショットがある場合は、`right` の要素を新しい頂点に複製するので、その個数の時間がかかります。
+    /// `right` のスナップショットは引き続き使えます。
+    ///
     ///
     /// # Examples
     ///
@@ -474,7 +561,14 @@ impl<O: LazyOps> SplayTree<O> {
     /// ]);
     /// ```
     pub fn append(&mut self, right: &Self) {
-        let root = merge(self.0.get(), right.0.get());
+        let epoch = self.epoch();
+        let mut right_root = right.0.get();
+        if epoch != 0 || right.epoch() != 0 {
+            let values = collect_values(right_root, 0..right.len());
+            deep_free(right_root, right.epoch());
+            right_root = build(values, epoch);
+        }
+        let root = merge(self.0.get(), right_root, epoch);
         self.0.set(root);
         right.0.set(null_mut());
     }
@@ -520,7 +614,7 @@ impl<O: LazyOps> SplayTree<O> {
     /// assert_eq!(iter.next_back(), None);
     /// ```
     pub fn range(&self, range: impl RangeBounds<usize>) -> Iter<'_, O> {
-        let Range { start, end } = into_range(self.len(), range);
+        let Range { start, end } = resolve_range(self.len(), range);
         Iter {
             splay: self,
             start,
@@ -528,6 +622,146 @@ impl<O: LazyOps> SplayTree<O> {
         }
     }
 
+    /// 現在の列のスナップショットを取ります。スナップショットは変更できず、`*_at` で始まるメソッドで読むことができます。
+    ///
+    /// スナップショットを取ったあとの変更では、スナップショットと共有している頂点を触るたびに複製するので、
+    /// 変更のたびに償却 O ( log N ) 個の頂点が増えます。スナップショットはスプレー木を破棄するまで残ります。
+    ///
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use splay_tree::{SplayTree, Nop};
+    /// let mut splay = (10..13).collect::<SplayTree<Nop<i32>>>();
+    /// let id = splay.snapshot();
+    /// splay.insert(1, 20);
+    /// splay.delete(3);
+    ///
+    /// assert_eq!(splay.iter().copied().collect::<Vec<_>>(), vec![10, 20, 11]);
+    /// assert_eq!(splay.to_vec_at(id), vec![10, 11, 12]);
+    /// assert_eq!(splay.get_at(id, 2), Some(12));
+    /// ```
+    pub fn snapshot(&mut self) -> SnapshotId {
+        self.1.push(self.0.get());
+        SnapshotId(self.1.len() - 1)
+    }
+
+    /// スナップショットの要素数を返します。
+    ///
+    /// # Panics
+    ///
+    /// - 存在しないスナップショット
+    pub fn len_at(&self, id: SnapshotId) -> usize {
+        self.len_of(self.snapshot_root(id))
+    }
+
+    /// スナップショットの指定した場所の要素を返します。範囲外のときには `None` を返します。
+    ///
+    /// スプレー操作をしないので、計算量はスナップショットを取ったときの木の高さに比例します。
+    ///
+    /// # Panics
+    ///
+    /// - 存在しないスナップショット
+    pub fn get_at(&self, id: SnapshotId, i: usize) -> Option<O::Value> {
+        let root = self.snapshot_root(id);
+        if self.len_of(root) <= i {
+            return None;
+        }
+        collect_values(root, i..i + 1).pop()
+    }
+
+    /// スナップショットの指定した範囲の要素を畳み込みます。
+    ///
+    /// スプレー操作をしないので、計算量はスナップショットを取ったときの木の高さに比例します。
+    ///
+    /// # Panics
+    ///
+    /// - 存在しないスナップショット
+    /// - 範囲外
+    ///
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use splay_tree::{SplayTree, NoLazy, Ops};
+    /// enum Sum {}
+    /// impl Ops for Sum {
+    ///     type Acc = i32;
+    ///     type Value = i32;
+    ///
+    ///     fn proj(&x: &i32) -> i32 {
+    ///         x
+    ///     }
+    ///
+    ///     fn op(&x: &i32, &y: &i32) -> i32 {
+    ///         x + y
+    ///     }
+    /// }
+    /// let mut splay = (10..15).collect::<SplayTree<NoLazy<Sum>>>();
+    /// let id = splay.snapshot();
+    /// *splay.entry(3).unwrap() = 0;
+    /// assert_eq!(splay.fold(2..), Some(12 + 0 + 14));
+    /// assert_eq!(splay.fold_at(id, 2..), Some(12 + 13 + 14));
+    /// assert_eq!(splay.fold_at(id, 2..2), None);
+    /// ```
+    pub fn fold_at(&self, id: SnapshotId, range: impl RangeBounds<usize>) -> Option<O::Acc> {
+        let root = self.snapshot_root(id);
+        let range = resolve_range(self.len_of(root), range);
+        let mut ans: Option<O::Acc> = None;
+        visit_frozen(root, range, true, |node, lazy, whole, rev| {
+            let acc = if whole {
+                let mut acc = node.acc.clone();
+                if let Some(lazy) = lazy {
+                    O::act_acc(lazy, &mut acc);
+                }
+                if rev {
+                    O::reverse_acc(&mut acc);
+                }
+                acc
+            } else {
+                let mut value = node.value.clone();
+                if let Some(lazy) = lazy {
+                    O::act_value(lazy, &mut value);
+                }
+                O::proj(&value)
+            };
+            ans = Some(match &ans {
+                None => acc,
+                Some(ans) => O::op(ans, &acc),
+            });
+        });
+        ans
+    }
+
+    /// スナップショットの要素を順番に並べた [`Vec`] を返します。
+    ///
+    /// # Panics
+    ///
+    /// - 存在しないスナップショット
+    pub fn to_vec_at(&self, id: SnapshotId) -> Vec<O::Value> {
+        let root = self.snapshot_root(id);
+        collect_values(root, 0..self.len_of(root))
+    }
+
+    fn epoch(&self) -> u32 {
+        self.1.len() as u32
+    }
+
+    fn len_of(&self, root: *mut Node<O>) -> usize {
+        unsafe { root.as_ref() }.map_or(0, |root| root.len)
+    }
+
+    fn snapshot_root(&self, id: SnapshotId) -> *mut Node<O> {
+        match self.1.get(id.0) {
+            Some(&root) => root,
+            None => panic!(
+                "snapshot {} does not exist in splay tree with {} snapshots",
+                id.0,
+                self.1.len()
+            ),
+        }
+    }
+
     /// 内部情報をダンプします。
     pub fn dump(&self) {
         println!("    === start dump ===    ");
@@ -539,24 +773,53 @@ impl<O: LazyOps> SplayTree<O> {
     }
 }
 
+/// [`SplayTree::snapshot`] の戻り値型です。
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct SnapshotId(usize);
+
 impl<O: LazyOps> FromIterator<O::Value> for SplayTree<O> {
     fn from_iter<T: IntoIterator<Item = O::Value>>(iter: T) -> Self {
-        let mut iter = iter.into_iter();
-        let mut root = match iter.next() {
-            None => return Self::new(),
-            Some(value) => Box::leak(Box::new(Node::new(value))),
-        };
-        for value in iter {
-            let node = Box::leak(Box::new(Node::new(value)));
-            root.parent = node;
-            node.left = root;
-            node.update();
-            root = node;
-        }
-        Self(Cell::new(root))
+        Self(Cell::new(build(iter, 0)), Vec::new())
+    }
+}
+
+/// 後ろに要素を追加します。スナップショットがあっても、追加した要素の個数と $O(\log n)$ の時間しかかかりません。
+impl<O: LazyOps> Extend<O::Value> for SplayTree<O> {
+    fn extend<T: IntoIterator<Item = O::Value>>(&mut self, iter: T) {
+        let epoch = self.epoch();
+        let right = build(iter, epoch);
+        self.0.set(merge(self.0.get(), right, epoch));
     }
 }
 
+fn build<O: LazyOps>(iter: impl IntoIterator<Item = O::Value>, epoch: u32) -> *mut Node<O> {
+    let mut iter = iter.into_iter();
+    let mut root = match iter.next() {
+        None => return null_mut(),
+        Some(value) => Box::leak(Box::new(Node::new(value, epoch))),
+    };
+    for value in iter {
+        let node = Box::leak(Box::new(Node::new(value, epoch)));
+        root.parent = node;
+        node.left = root;
+        node.update(epoch);
+        root = node;
+    }
+    root
+}
+
+fn collect_values<O: LazyOps>(root: *mut Node<O>, range: Range<usize>) -> Vec<O::Value> {
+    let mut ans = Vec::with_capacity(range.len());
+    visit_frozen(root, range, false, |node, lazy, _, _| {
+        let mut value = node.value.clone();
+        if let Some(lazy) = lazy {
+            O::act_value(lazy, &mut value);
+        }
+        ans.push(value);
+    });
+    ans
+}
+
 impl<'a, O: LazyOps> IntoIterator for &'a SplayTree<O> {
     type IntoIter = Iter<'a, O>;
     type Item = &'a O::Value;
@@ -578,7 +841,7 @@ impl<O: LazyOps> Clone for SplayTree<O> {
 }
 impl<O: LazyOps> Default for SplayTree<O> {
     fn default() -> Self {
-        Self(Cell::new(null_mut()))
+        Self::new()
     }
 }
 impl<O: LazyOps> PartialEq for SplayTree<O>
@@ -683,33 +946,22 @@ impl<O: LazyOps> DerefMut for Entry<'_, O> {
         &mut unsafe { &mut *self.0 .0.get() }.value
     }
 }
-
-impl<O: LazyOps> Drop for SplayTree<O> {
+impl<O: LazyOps> Drop for Entry<'_, O> {
     fn drop(&mut self) {
-        deep_free(self.0.get());
+        // 値が変わったかもしれないので、根の集約値を計算し直します。
+        let epoch = self.0.epoch();
+        unsafe { &mut *self.0 .0.get() }.update(epoch);
     }
 }
-fn into_range(len: usize, range: impl RangeBounds<usize>) -> Range<usize> {
-    let start = match range.start_bound() {
-        Bound::Included(&start) => start,
-        Bound::Excluded(&start) => start - 1,
-        Bound::Unbounded => 0,
-    };
-    let end = match range.end_bound() {
-        Bound::Included(&end) => end + 1,
-        Bound::Excluded(&end) => end,
-        Bound::Unbounded => len,
-    };
-    if len < start {
-        splay_tree_start_index_len_fail(start, len);
-    }
-    if len < end {
-        splay_tree_end_index_len_fail(end, len);
-    }
-    if start > end {
-        splay_tree_index_order_fail(start, end)
+
+impl<O: LazyOps> Drop for SplayTree<O> {
+    fn drop(&mut self) {
+        // 新しい版から解放しないと、解放済みの頂点の版を読んでしまいます。
+        deep_free(self.0.get(), self.epoch());
+        for (epoch, &root) in self.1.iter().enumerate().rev() {
+            free_snapshot(root, epoch as u32);
+        }
     }
-    start..end
 }
 fn splay_tree_index_out_of_range_fail(index: usize, len: usize) -> ! {
     panic!(
@@ -717,18 +969,3 @@ fn splay_tree_index_out_of_range_fail(index: usize, len: usize) -> ! {
         index, len
     );
 }
-fn splay_tree_start_index_len_fail(index: usize, len: usize) -> ! {
-    panic!(
-        "range start index {} out of range for splay tree of length {}",
-        index, len
-    );
-}
-fn splay_tree_end_index_len_fail(index: usize, len: usize) -> ! {
-    panic!(
-        "range end index {} out of range for splay tree of length {}",
-        index, len
-    );
-}
-fn splay_tree_index_order_fail(index: usize, end: usize) -> ! {
-    panic!("splay tree index starts at {} but ends at {}", index, end);
-}
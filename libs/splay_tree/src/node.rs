@@ -3,34 +3,79 @@ use std::cmp::Ordering;
 use std::fmt::Debug;
 use std::mem::replace;
 use std::mem::swap;
+use std::ops::Range;
 use std::ptr::null_mut;
 use std::ptr::{self};
 
+// `epoch` 未満の版の頂点はスナップショットと共有されているので、解放しません。
 #[allow(unused_must_use)]
-pub fn deep_free<O: LazyOps>(root: *mut Node<O>) {
-    if !root.is_null() {
-        unsafe {
-            deep_free((*root).left);
-            deep_free((*root).right);
-            Box::from_raw(root);
+pub fn deep_free<O: LazyOps>(root: *mut Node<O>, epoch: u32) {
+    if let Some(node) = unsafe { root.as_ref() } {
+        if node.version >= epoch {
+            deep_free(node.left, epoch);
+            deep_free(node.right, epoch);
+            unsafe { Box::from_raw(root) };
         }
     }
 }
 
-pub fn access_index<O: LazyOps>(mut root: &mut Node<O>, mut i: usize) -> &mut Node<O> {
+// スナップショット `epoch` が所有する頂点、すなわち版が `epoch` の頂点を解放します。
+pub fn free_snapshot<O: LazyOps>(root: *mut Node<O>, epoch: u32) {
+    let mut stack = vec![root];
+    while let Some(x) = stack.pop() {
+        if let Some(node) = unsafe { x.as_ref() } {
+            if node.version == epoch {
+                stack.push(node.left);
+                stack.push(node.right);
+                drop(unsafe { Box::from_raw(x) });
+            }
+        }
+    }
+}
+
+// 共有されている頂点ならば複製して返します。
+pub fn own<O: LazyOps>(node: *mut Node<O>, epoch: u32) -> *mut Node<O> {
+    match unsafe { node.as_ref() } {
+        Some(x) if x.version < epoch => {
+            #[cfg(test)]
+            super::test_snapshot::CLONES.with(|c| c.set(c.get() + 1));
+            Box::leak(Box::new(Node {
+                left: x.left,
+                right: x.right,
+                parent: x.parent,
+                len: x.len,
+                rev: x.rev,
+                value: x.value.clone(),
+                acc: x.acc.clone(),
+                lazy: x.lazy.clone(),
+                version: epoch,
+            }))
+        }
+        _ => node,
+    }
+}
+
+pub fn access_index<'a, O: LazyOps>(
+    root: *mut Node<O>,
+    mut i: usize,
+    epoch: u32,
+) -> &'a mut Node<O> {
+    let mut root = unsafe { &mut *own(root, epoch) };
+    root.parent = null_mut();
     loop {
-        root.push();
+        root.own_children(epoch);
+        root.push(epoch);
         if let Some(left) = unsafe { root.left.as_mut() } {
-            left.push();
+            left.push(epoch);
         }
         if let Some(right) = unsafe { root.right.as_mut() } {
-            right.push();
+            right.push(epoch);
         }
         let lsize = unsafe { root.left.as_ref() }.map_or(0, |left| left.len);
         root = match i.cmp(&lsize) {
             Ordering::Less => unsafe { root.left.as_mut() }.unwrap(),
             Ordering::Equal => {
-                root.splay();
+                root.splay(epoch);
                 return root;
             }
             Ordering::Greater => {
@@ -41,14 +86,14 @@ pub fn access_index<O: LazyOps>(mut root: &mut Node<O>, mut i: usize) -> &mut No
     }
 }
 
-pub fn merge<O: LazyOps>(left: *mut Node<O>, right: *mut Node<O>) -> *mut Node<O> {
+pub fn merge<O: LazyOps>(left: *mut Node<O>, right: *mut Node<O>, epoch: u32) -> *mut Node<O> {
     let ans = if let Some(mut left) = unsafe { left.as_mut() } {
         if let Some(right) = unsafe { right.as_mut() } {
-            left = access_index(left, left.len - 1);
-            left.push();
+            left = access_index(left, left.len - 1, epoch);
+            left.push(epoch);
             left.right = right;
             right.parent = left;
-            left.update();
+            left.update(epoch);
         }
         left
     } else {
@@ -57,19 +102,20 @@ pub fn merge<O: LazyOps>(left: *mut Node<O>, right: *mut Node<O>) -> *mut Node<O
     ans
 }
 
-pub fn split_at<O: LazyOps>(root: *mut Node<O>, at: usize) -> [*mut Node<O>; 2] {
-    if let Some(mut root) = unsafe { root.as_mut() } {
+pub fn split_at<O: LazyOps>(root: *mut Node<O>, at: usize, epoch: u32) -> [*mut Node<O>; 2] {
+    if let Some(mut root) = unsafe { own(root, epoch).as_mut() } {
+        root.parent = null_mut();
         if at == root.len {
             [root, null_mut()]
         } else if at == 0 {
             [null_mut(), root]
         } else {
-            root = access_index(root, at);
-            root.push();
+            root = access_index(root, at, epoch);
+            root.push(epoch);
             let left = replace(&mut root.left, null_mut());
             if let Some(left) = unsafe { left.as_mut() } {
                 left.parent = null_mut();
-                root.update();
+                root.update(epoch);
             }
             [left, root]
         }
@@ -78,6 +124,57 @@ pub fn split_at<O: LazyOps>(root: *mut Node<O>, at: usize) -> [*mut Node<O>; 2]
     }
 }
 
+enum Task<O: LazyOps> {
+    Subtree(*const Node<O>, Option<O::Lazy>, bool, Range<usize>),
+    Single(*const Node<O>, Option<O::Lazy>),
+}
+
+// 書き換えずに区間 `range` を左から順に訪問します。`whole` ならば区間に含まれる部分木を、そうでなければ頂点を 1
+// つずつ、その頂点に溜まっている作用とともに `f` に渡します。部分木を渡すときには、その集約値を反転すべきかも渡します。
+pub fn visit_frozen<O: LazyOps>(
+    root: *const Node<O>,
+    range: Range<usize>,
+    whole: bool,
+    mut f: impl FnMut(&Node<O>, Option<&O::Lazy>, bool, bool),
+) {
+    let mut stack = vec![Task::Subtree(root, None, false, range)];
+    while let Some(task) = stack.pop() {
+        match task {
+            Task::Single(node, lazy) => f(unsafe { &*node }, lazy.as_ref(), false, false),
+            Task::Subtree(node, upper, rev, Range { start, end }) => {
+                let Some(node) = (unsafe { node.as_ref() }) else {
+                    continue;
+                };
+                if start == end {
+                    continue;
+                }
+                let mut lazy = node.lazy.clone();
+                if let Some(upper) = &upper {
+                    O::compose_to_option(upper, &mut lazy);
+                }
+                let rev = rev ^ node.rev;
+                if whole && start == 0 && end == node.len {
+                    f(node, lazy.as_ref(), true, rev);
+                    continue;
+                }
+                let (left, right) =
+                    if rev { (node.right, node.left) } else { (node.left, node.right) };
+                let lsize = unsafe { left.as_ref() }.map_or(0, |left| left.len);
+                if lsize + 1 < end {
+                    let range = start.max(lsize + 1) - lsize - 1..end - lsize - 1;
+                    stack.push(Task::Subtree(right, lazy.clone(), rev, range));
+                }
+                if start <= lsize && lsize < end {
+                    stack.push(Task::Single(node, lazy.clone()));
+                }
+                if start < lsize {
+                    stack.push(Task::Subtree(left, lazy, rev, start..end.min(lsize)));
+                }
+            }
+        }
+    }
+}
+
 pub struct Node<O: LazyOps> {
     pub left: *mut Self,
     pub right: *mut Self,
@@ -87,10 +184,13 @@ pub struct Node<O: LazyOps> {
     pub value: O::Value,
     pub acc: O::Acc,
     pub lazy: Option<O::Lazy>,
+    // スナップショットを取るたびに増える版で、スプレー木の現在の版未満ならば共有されています。
+    pub version: u32,
 }
 impl<O: LazyOps> Node<O> {
-    pub fn new(value: O::Value) -> Self {
+    pub fn new(value: O::Value, version: u32) -> Self {
         Node {
+            version,
             left: null_mut(),
             right: null_mut(),
             parent: null_mut(),
@@ -129,22 +229,37 @@ impl<O: LazyOps> Node<O> {
         }
     }
 
-    pub fn update(&mut self) {
+    // 子を書き換える前に呼びます。
+    pub fn own_children(&mut self, epoch: u32) {
+        let this: *mut Self = self;
+        for child in [&mut self.left, &mut self.right] {
+            *child = own(*child, epoch);
+            if let Some(c) = unsafe { child.as_mut() } {
+                c.parent = this;
+            }
+        }
+    }
+
+    pub fn update(&mut self, epoch: u32) {
+        self.own_children(epoch);
         self.len = 1;
         self.acc = O::proj(&self.value);
         if let Some(left) = unsafe { self.left.as_mut() } {
-            left.push();
+            left.push(epoch);
             self.len += left.len;
             self.acc = O::op(&left.acc, &self.acc);
         }
         if let Some(right) = unsafe { self.right.as_mut() } {
-            right.push();
+            right.push(epoch);
             self.len += right.len;
             self.acc = O::op(&self.acc, &right.acc);
         }
     }
 
-    pub fn push(&mut self) {
+    pub fn push(&mut self, epoch: u32) {
+        if self.lazy.is_some() || self.rev {
+            self.own_children(epoch);
+        }
         if let Some(lazy) = self.lazy.take() {
             O::act_value(&lazy, &mut self.value);
             O::act_acc(&lazy, &mut self.acc);
@@ -157,6 +272,7 @@ impl<O: LazyOps> Node<O> {
         }
         if replace(&mut self.rev, false) {
             swap(&mut self.left, &mut self.right);
+            O::reverse_acc(&mut self.acc);
             if let Some(left) = unsafe { self.left.as_mut() } {
                 left.rev ^= true;
             }
@@ -166,10 +282,10 @@ impl<O: LazyOps> Node<O> {
         }
     }
 
-    pub fn rotate(&mut self) {
+    pub fn rotate(&mut self, epoch: u32) {
         let p = unsafe { &mut *self.parent };
         let g = p.parent;
-        self.push();
+        self.push(epoch);
         if ptr::eq(self, p.left) {
             p.left = self.right;
             if let Some(c) = unsafe { p.left.as_mut() } {
@@ -192,20 +308,20 @@ impl<O: LazyOps> Node<O> {
                 g.right = self;
             }
         }
-        p.update();
-        self.update();
+        p.update(epoch);
+        self.update(epoch);
     }
 
-    pub fn splay(&mut self) {
+    pub fn splay(&mut self, epoch: u32) {
         while let Some(p) = unsafe { self.parent.as_mut() } {
             if let Some(g) = unsafe { p.parent.as_mut() } {
                 if ptr::eq(self, p.left) == ptr::eq(p, g.left) {
-                    p.rotate();
+                    p.rotate(epoch);
                 } else {
-                    self.rotate();
+                    self.rotate(epoch);
                 }
             }
-            self.rotate();
+            self.rotate(epoch);
         }
     }
 }
@@ -0,0 +1,140 @@
+use super::NoLazy;
+use super::Ops;
+use super::SplayTree;
+use rand::prelude::StdRng;
+use rand::Rng;
+use rand::SeedableRng;
+use randtools::BiasedSubRange;
+
+// 文字列の連結で、集約値は (順方向, 逆方向) です。
+enum Cat {}
+impl Ops for Cat {
+    type Acc = (String, String);
+    type Value = char;
+
+    fn proj(&value: &Self::Value) -> Self::Acc {
+        (value.to_string(), value.to_string())
+    }
+
+    fn op(lhs: &Self::Acc, rhs: &Self::Acc) -> Self::Acc {
+        (lhs.0.clone() + &rhs.0, rhs.1.clone() + &lhs.1)
+    }
+
+    fn reverse_acc(acc: &mut Self::Acc) {
+        std::mem::swap(&mut acc.0, &mut acc.1);
+    }
+}
+
+fn brute_fold(a: &[char]) -> Option<(String, String)> {
+    (!a.is_empty()).then(|| (a.iter().collect(), a.iter().rev().collect()))
+}
+
+#[test]
+fn test_cat_reverse() {
+    let mut splay = "abcde".chars().collect::<SplayTree<NoLazy<Cat>>>();
+    splay.reverse(1..4);
+    assert_eq!(splay.fold(..).unwrap().0, "adcbe");
+    assert_eq!(splay.fold(1..3).unwrap().0, "dc");
+    splay.reverse(..);
+    assert_eq!(splay.fold(..).unwrap().0, "ebcda");
+}
+
+#[test]
+fn test_cat_random() {
+    let mut rng = StdRng::seed_from_u64(42);
+    for _ in 0..100 {
+        let mut brute = Vec::new();
+        let mut splay = SplayTree::<NoLazy<Cat>>::new();
+        let mut snapshots = Vec::new();
+        for _ in 0..100 {
+            match rng.gen_range(0..5) {
+                0 => {
+                    let i = rng.gen_range(0..=brute.len());
+                    let value = rng.gen_range(b'a'..=b'z') as char;
+                    brute.insert(i, value);
+                    splay.insert(i, value);
+                }
+                1 => {
+                    if brute.is_empty() {
+                        continue;
+                    }
+                    let i = rng.gen_range(0..brute.len());
+                    assert_eq!(splay.delete(i), brute.remove(i));
+                }
+                2 => {
+                    let range = rng.sample(BiasedSubRange(0..brute.len()));
+                    brute[range.clone()].reverse();
+                    splay.reverse(range);
+                }
+                3 => {
+                    let range = rng.sample(BiasedSubRange(0..brute.len()));
+                    assert_eq!(splay.fold(range.clone()), brute_fold(&brute[range]));
+                }
+                4 => snapshots.push((splay.snapshot(), brute.clone())),
+                _ => unreachable!(),
+            }
+            assert_eq!(splay.iter().copied().collect::<Vec<_>>(), brute);
+        }
+        // 反転が溜まったまま凍結された頂点も正しく畳み込めます。
+        for (id, expected) in snapshots {
+            let range = rng.sample(BiasedSubRange(0..expected.len()));
+            assert_eq!(
+                splay.fold_at(id, range.clone()),
+                brute_fold(&expected[range])
+            );
+        }
+    }
+}
+
+#[test]
+fn test_cat_split_off_append() {
+    let mut rng = StdRng::seed_from_u64(42);
+    for _ in 0..100 {
+        let mut brute = vec![Vec::<char>::new(); 3];
+        let mut splay = (0..3)
+            .map(|_| SplayTree::<NoLazy<Cat>>::new())
+            .collect::<Vec<_>>();
+        for _ in 0..100 {
+            let i = rng.gen_range(0..3_usize);
+            let j = (i + rng.gen_range(1..3_usize)) % 3;
+            match rng.gen_range(0..5) {
+                // 空の木との間でも切り貼りします。
+                0 => {
+                    let at = rng.gen_range(0..=brute[i].len());
+                    let mut right = brute[i].split_off(at);
+                    let tail = splay[i].split_off(at);
+                    brute[j].append(&mut right);
+                    splay[j].append(&tail);
+                    assert!(tail.is_empty());
+                }
+                1 => {
+                    let values = (0..rng.gen_range(0..4_usize))
+                        .map(|_| rng.gen_range(b'a'..=b'z') as char)
+                        .collect::<Vec<_>>();
+                    brute[i].extend(values.iter().copied());
+                    splay[i].extend(values);
+                }
+                2 => {
+                    let range = rng.sample(BiasedSubRange(0..brute[i].len()));
+                    brute[i][range.clone()].reverse();
+                    splay[i].reverse(range);
+                }
+                3 => {
+                    let range = rng.sample(BiasedSubRange(0..brute[i].len()));
+                    assert_eq!(splay[i].fold(range.clone()), brute_fold(&brute[i][range]));
+                }
+                4 => {
+                    let mut taken = std::mem::take(&mut brute[j]);
+                    let other = std::mem::take(&mut splay[j]);
+                    brute[i].append(&mut taken);
+                    splay[i].append(&other);
+                }
+                _ => unreachable!(),
+            }
+            for (splay, brute) in splay.iter().zip(&brute) {
+                assert_eq!(splay.len(), brute.len());
+                assert_eq!(splay.fold(..), brute_fold(brute));
+            }
+        }
+    }
+}
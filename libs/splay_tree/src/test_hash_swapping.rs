@@ -5,6 +5,7 @@ use itertools::Itertools;
 use rand::prelude::StdRng;
 use rand::Rng;
 use rand::SeedableRng;
+use randtools::RepetitiveString;
 use std::iter::repeat_with;
 use std::mem::swap;
 
@@ -30,9 +31,20 @@ fn test_hash_swapping() {
     for _ in 0..20 {
         let n = rng.gen_range(2..10);
         let m = rng.gen_range(2..10);
-        let mut brute = repeat_with(|| repeat_with(|| rng.gen_range(0..26)).take(n).collect_vec())
-            .take(m)
-            .collect_vec();
+        // Equal substrings are common, so that the hashes are compared between them.
+        let repetitiveness = rng.gen_range(0.0..1.0);
+        let mut brute = repeat_with(|| {
+            rng.sample(RepetitiveString {
+                len: n,
+                alphabet: 3,
+                repetitiveness,
+            })
+            .into_iter()
+            .map(|c| u64::from(c - b'a'))
+            .collect_vec()
+        })
+        .take(m)
+        .collect_vec();
         let mut splay = brute
             .iter()
             .map(|row| row.iter().copied().collect::<SplayTree<NoLazy<StrHash>>>())
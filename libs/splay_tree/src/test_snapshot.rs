@@ -0,0 +1,280 @@
+use super::LazyOps;
+use super::Nop;
+use super::SnapshotId;
+use super::SplayTree;
+use rand::prelude::StdRng;
+use rand::Rng;
+use rand::SeedableRng;
+use randtools::BiasedSubRange;
+use std::cell::Cell;
+
+thread_local! {
+    // 複製した頂点の個数です。
+    pub static CLONES: Cell<usize> = Cell::new(0);
+    static LIVE: Cell<isize> = Cell::new(0);
+}
+
+const P: i64 = 998_244_353;
+
+enum Affine {}
+impl LazyOps for Affine {
+    type Acc = (i64, usize);
+    type Lazy = [i64; 2];
+    type Value = i64;
+
+    fn proj(&value: &Self::Value) -> Self::Acc {
+        (value, 1)
+    }
+
+    fn op(lhs: &Self::Acc, rhs: &Self::Acc) -> Self::Acc {
+        ((lhs.0 + rhs.0) % P, lhs.1 + rhs.1)
+    }
+
+    fn act_value(lazy: &Self::Lazy, value: &mut Self::Value) {
+        *value = (lazy[0] * *value + lazy[1]) % P;
+    }
+
+    fn act_acc(lazy: &Self::Lazy, acc: &mut Self::Acc) {
+        acc.0 = (lazy[0] * acc.0 + lazy[1] * acc.1 as i64) % P;
+    }
+
+    fn compose(upper: &Self::Lazy, lower: &mut Self::Lazy) {
+        *lower = [
+            (upper[0] * lower[0]) % P,
+            (upper[0] * lower[1] + upper[1]) % P,
+        ];
+    }
+}
+
+fn brute_fold(a: &[i64]) -> Option<(i64, usize)> {
+    (!a.is_empty()).then(|| (a.iter().sum::<i64>() % P, a.len()))
+}
+
+fn verify(rng: &mut StdRng, splay: &SplayTree<Affine>, id: SnapshotId, expected: &[i64]) {
+    assert_eq!(splay.len_at(id), expected.len());
+    assert_eq!(splay.to_vec_at(id), expected);
+    for _ in 0..4 {
+        let range = rng.sample(BiasedSubRange(0..expected.len()));
+        assert_eq!(
+            splay.fold_at(id, range.clone()),
+            brute_fold(&expected[range])
+        );
+        let i = rng.gen_range(0..=expected.len());
+        assert_eq!(splay.get_at(id, i), expected.get(i).copied());
+    }
+}
+
+fn random_operation(rng: &mut StdRng, brute: &mut Vec<i64>, splay: &mut SplayTree<Affine>) {
+    match rng.gen_range(0..6) {
+        0 => {
+            let i = rng.gen_range(0..=brute.len());
+            let value = rng.gen_range(0..P);
+            brute.insert(i, value);
+            splay.insert(i, value);
+        }
+        1 => {
+            if brute.is_empty() {
+                return;
+            }
+            let i = rng.gen_range(0..brute.len());
+            assert_eq!(splay.delete(i), brute.remove(i));
+        }
+        2 => {
+            let range = rng.sample(BiasedSubRange(0..brute.len()));
+            brute[range.clone()].reverse();
+            splay.reverse(range);
+        }
+        3 => {
+            let range = rng.sample(BiasedSubRange(0..brute.len()));
+            let lazy = [rng.gen_range(0..P), rng.gen_range(0..P)];
+            brute[range.clone()]
+                .iter_mut()
+                .for_each(|x| Affine::act_value(&lazy, x));
+            splay.act(range, lazy);
+        }
+        4 => {
+            if brute.is_empty() {
+                return;
+            }
+            let i = rng.gen_range(0..brute.len());
+            let value = rng.gen_range(0..P);
+            brute[i] = value;
+            *splay.entry(i).unwrap() = value;
+        }
+        5 => {
+            let range = rng.sample(BiasedSubRange(0..brute.len()));
+            assert_eq!(splay.fold(range.clone()), brute_fold(&brute[range]));
+            let i = rng.gen_range(0..=brute.len());
+            assert_eq!(splay.get(i), brute.get(i));
+        }
+        _ => unreachable!(),
+    }
+}
+
+#[test]
+fn test_snapshot_random() {
+    let mut rng = StdRng::seed_from_u64(42);
+    for _ in 0..40 {
+        let n = rng.gen_range(0..20);
+        let mut brute = (0..n).map(|_| rng.gen_range(0..P)).collect::<Vec<_>>();
+        let mut splay = brute.iter().copied().collect::<SplayTree<Affine>>();
+        let mut saved = Vec::new();
+        for _ in 0..300 {
+            match rng.gen_range(0..10) {
+                0 => saved.push((splay.snapshot(), brute.clone())),
+                1 => {
+                    // 変更を挟まずに続けて取ります。
+                    for _ in 0..rng.gen_range(2..4) {
+                        saved.push((splay.snapshot(), brute.clone()));
+                    }
+                }
+                _ => random_operation(&mut rng, &mut brute, &mut splay),
+            }
+            if rng.gen_ratio(1, 10) {
+                for (id, expected) in &saved {
+                    verify(&mut rng, &splay, *id, expected);
+                }
+            }
+        }
+        assert_eq!(splay.iter().copied().collect::<Vec<_>>(), brute);
+        for (id, expected) in &saved {
+            verify(&mut rng, &splay, *id, expected);
+        }
+        for w in saved.windows(2) {
+            assert!(w[0].0 < w[1].0);
+        }
+    }
+}
+
+#[test]
+fn test_snapshot_split_off_append() {
+    let mut rng = StdRng::seed_from_u64(42);
+    for _ in 0..100 {
+        let n = rng.gen_range(0..20);
+        let mut brute = (0..n).map(|_| rng.gen_range(0..P)).collect::<Vec<_>>();
+        let mut splay = brute.iter().copied().collect::<SplayTree<Affine>>();
+        let mut saved = Vec::new();
+        for _ in 0..30 {
+            match rng.gen_range(0..4) {
+                0 => saved.push((splay.snapshot(), brute.clone())),
+                1 => {
+                    let at = rng.gen_range(0..=brute.len());
+                    let mut other = splay.split_off(at);
+                    let mut other_brute = brute.split_off(at);
+                    for _ in 0..rng.gen_range(0..5) {
+                        random_operation(&mut rng, &mut other_brute, &mut other);
+                    }
+                    let other_id = rng.gen_bool(0.5).then(|| other.snapshot());
+                    for _ in 0..rng.gen_range(0..5) {
+                        random_operation(&mut rng, &mut brute, &mut splay);
+                    }
+                    splay.append(&other);
+                    assert!(other.is_empty());
+                    if let Some(other_id) = other_id {
+                        verify(&mut rng, &other, other_id, &other_brute);
+                    }
+                    brute.append(&mut other_brute);
+                }
+                _ => random_operation(&mut rng, &mut brute, &mut splay),
+            }
+        }
+        assert_eq!(splay.iter().copied().collect::<Vec<_>>(), brute);
+        for (id, expected) in &saved {
+            verify(&mut rng, &splay, *id, expected);
+        }
+    }
+}
+
+#[test]
+fn test_snapshot_clones_few_nodes() {
+    let mut rng = StdRng::seed_from_u64(42);
+    let n = 1 << 14;
+    let mut brute = (0..n).map(|_| rng.gen_range(0..P)).collect::<Vec<_>>();
+    let mut splay = brute.iter().copied().collect::<SplayTree<Affine>>();
+    // 木を均しておきます。
+    for _ in 0..n {
+        splay.get(rng.gen_range(0..n));
+    }
+    let q = 2000;
+    let start = CLONES.with(Cell::get);
+    let mut saved = Vec::new();
+    for _ in 0..q {
+        let before = CLONES.with(Cell::get);
+        saved.push((splay.snapshot(), brute.clone()));
+        saved.push((splay.snapshot(), brute.clone()));
+        assert_eq!(CLONES.with(Cell::get), before);
+        random_operation(&mut rng, &mut brute, &mut splay);
+    }
+    // 毎回木全体を複製すると q N 個になります。
+    assert!(CLONES.with(Cell::get) - start < q * 200);
+    for (id, expected) in saved.iter().step_by(97) {
+        verify(&mut rng, &splay, *id, expected);
+    }
+}
+
+#[derive(Debug)]
+struct Counted(u32);
+impl Counted {
+    fn new(value: u32) -> Self {
+        LIVE.with(|live| live.set(live.get() + 1));
+        Self(value)
+    }
+}
+impl Clone for Counted {
+    fn clone(&self) -> Self {
+        Self::new(self.0)
+    }
+}
+impl Drop for Counted {
+    fn drop(&mut self) {
+        LIVE.with(|live| live.set(live.get() - 1));
+    }
+}
+
+#[test]
+fn test_snapshot_drop() {
+    let mut rng = StdRng::seed_from_u64(42);
+    for _ in 0..100 {
+        let mut splay = (0..rng.gen_range(0..20))
+            .map(Counted::new)
+            .collect::<SplayTree<Nop<Counted>>>();
+        let mut others = Vec::new();
+        for _ in 0..30 {
+            let len = splay.len();
+            match rng.gen_range(0..6) {
+                0 => {
+                    splay.snapshot();
+                }
+                1 => splay.insert(rng.gen_range(0..=len), Counted::new(rng.gen())),
+                2 => {
+                    if len != 0 {
+                        splay.delete(rng.gen_range(0..len));
+                    }
+                }
+                3 => {
+                    splay.reverse(rng.sample(BiasedSubRange(0..len)));
+                }
+                4 => {
+                    let mut other = splay.split_off(rng.gen_range(0..=len));
+                    if rng.gen_bool(0.5) {
+                        other.snapshot();
+                        other.insert(0, Counted::new(0));
+                    }
+                    if rng.gen_bool(0.5) {
+                        splay.append(&other);
+                    }
+                    others.push(other);
+                }
+                5 => {
+                    if len != 0 {
+                        *splay.entry(rng.gen_range(0..len)).unwrap() = Counted::new(rng.gen());
+                    }
+                }
+                _ => unreachable!(),
+            }
+        }
+        drop(splay);
+        drop(others);
+        assert_eq!(LIVE.with(Cell::get), 0);
+    }
+}
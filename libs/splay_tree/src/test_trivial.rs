@@ -141,3 +141,50 @@ fn test_iter() {
     assert_equal(splay.iter().copied(), 0..10);
     assert_equal(splay.iter().rev().copied(), (0..10).rev());
 }
+
+#[test]
+fn test_entry_then_fold() {
+    let mut splay = (0..3).collect::<SplayTree<I32Add>>();
+    *splay.entry(1).unwrap() = 10;
+    splay.insert(0, 5);
+    assert_eq!(splay.fold(..), Some(5 + 10 + 2));
+    assert_eq!(splay.fold(1..3), Some(10));
+}
+
+#[test]
+fn test_fold_bounds() {
+    use std::ops::Bound;
+    let splay = (0..5).collect::<SplayTree<I32Add>>();
+    assert_eq!(
+        splay.fold((Bound::Excluded(1), Bound::Included(3))),
+        Some(5)
+    );
+    assert_eq!(splay.fold((Bound::Excluded(1), Bound::Unbounded)), Some(9));
+    assert_eq!(splay.fold(..=4), Some(10));
+    assert_eq!(splay.fold(5..), None);
+    assert_equal(
+        splay
+            .range((Bound::Excluded(0), Bound::Excluded(3)))
+            .copied(),
+        1..3,
+    );
+}
+
+#[test]
+#[should_panic(expected = "range starts at 3 but ends at 2")]
+fn test_fold_reversed_range() {
+    let (start, end) = (3, 2);
+    (0..5).collect::<SplayTree<I32Add>>().fold(start..end);
+}
+
+#[test]
+#[should_panic(expected = "range end index 6 out of range for length 5")]
+fn test_act_out_of_range() {
+    (0..5).collect::<SplayTree<I32Add>>().act(..6, ());
+}
+
+#[test]
+#[should_panic(expected = "attempted to index up to maximum usize")]
+fn test_reverse_included_usize_max() {
+    (0..5).collect::<SplayTree<I32Add>>().reverse(..=usize::MAX);
+}
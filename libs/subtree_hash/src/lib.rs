@@ -0,0 +1,307 @@
+//! Hashes every subtree of a rooted forest up to isomorphism.
+//!
+//! The hash of a vertex $x$ of height $h$ (a leaf has height $0$) is
+//!
+//! $$
+//! H(x) = \prod _ { y \in G _ x } \left( s _ h + H(y) \right) \bmod (2 ^ {61} - 1),
+//! $$
+//!
+//! where $s _ 0, s _ 1, \dots$ are random salts. It does not depend on the order of the children,
+//! and two non-isomorphic subtrees collide with probability $O(n / 2 ^ {61})$ by the
+//! Schwartz–Zippel lemma, unlike the xor of the children.
+//!
+//! # Examples
+//!
+//! ```
+//! use subtree_hash::SubtreeHash;
+//! //     0
+//! //    / \
+//! //   1   2
+//! //   |   |
+//! //   3   4
+//! let g = vec![vec![1, 2], vec![0, 3], vec![0, 4], vec![1], vec![2]];
+//! let hash = SubtreeHash::new(&[0], &g);
+//! assert!(hash.equal_subtrees(1, 2));
+//! assert!(!hash.equal_subtrees(0, 1));
+//! assert_eq!(hash.group_identical_subtrees(), vec![0, 1, 1, 2, 2]);
+//! ```
+use std::collections::HashMap;
+
+const MOD: u64 = (1 << 61) - 1;
+
+/// The hashes of all the subtrees.
+#[derive(Debug, Clone, PartialEq)]
+pub struct SubtreeHash {
+    hash: Vec<u64>,
+}
+impl SubtreeHash {
+    /// Hashes the forest with a seed from [`salt::next_seed`].
+    ///
+    /// # Requirements
+    ///
+    /// - `g` is an undirected adjacency list of a forest.
+    /// - `roots` contains exactly one vertex of each component.
+    ///
+    /// # Complexity
+    ///
+    /// $O(n)$
+    pub fn new(roots: &[usize], g: &[Vec<usize>]) -> Self {
+        Self::with_seed(roots, g, salt::next_seed())
+    }
+
+    /// Hashes the forest with a fixed seed.
+    pub fn with_seed(roots: &[usize], g: &[Vec<usize>], seed: u64) -> Self {
+        let n = g.len();
+        let mut parent = vec![usize::MAX; n];
+        let mut order = Vec::with_capacity(n);
+        for &root in roots {
+            assert!(parent[root] == usize::MAX, "root {} is visited twice", root);
+            parent[root] = root;
+            order.push(root);
+            let mut i = order.len() - 1;
+            while i < order.len() {
+                let x = order[i];
+                for &y in &g[x] {
+                    if y != parent[x] {
+                        parent[y] = x;
+                        order.push(y);
+                    }
+                }
+                i += 1;
+            }
+        }
+        assert_eq!(
+            order.len(),
+            n,
+            "some vertex is not reachable from the roots"
+        );
+        let mut height = vec![0; n];
+        for &x in order.iter().rev() {
+            if parent[x] != x {
+                height[parent[x]] = height[parent[x]].max(height[x] + 1);
+            }
+        }
+        let salt = (0..=height.iter().max().map_or(0, |&h| h))
+            .map(|h| 1 + splitmix64(seed.wrapping_add(h as u64)) % (MOD - 1))
+            .collect::<Vec<_>>();
+        let mut hash = vec![1; n];
+        for &x in order.iter().rev() {
+            let p = parent[x];
+            if p != x {
+                hash[p] = mul(hash[p], add(salt[height[p]], hash[x]));
+            }
+        }
+        Self { hash }
+    }
+
+    /// Returns the hash of the subtree rooted at $x$.
+    pub fn subtree_hash(&self, x: usize) -> u64 {
+        self.hash[x]
+    }
+
+    /// Returns `true` if the subtrees rooted at $x$ and $y$ are isomorphic (with high probability).
+    pub fn equal_subtrees(&self, x: usize, y: usize) -> bool {
+        self.hash[x] == self.hash[y]
+    }
+
+    /// Assigns an id to each isomorphism class of subtrees, numbered in order of their first
+    /// appearance.
+    pub fn group_identical_subtrees(&self) -> Vec<usize> {
+        let mut ids = HashMap::new();
+        self.hash
+            .iter()
+            .map(|&h| {
+                let len = ids.len();
+                *ids.entry(h).or_insert(len)
+            })
+            .collect()
+    }
+}
+
+fn splitmix64(x: u64) -> u64 {
+    let mut x = x.wrapping_add(0x9e37_79b9_7f4a_7c15);
+    x = (x ^ (x >> 30)).wrapping_mul(0xbf58_476d_1ce4_e5b9);
+    x = (x ^ (x >> 27)).wrapping_mul(0x94d0_49bb_1331_11eb);
+    x ^ (x >> 31)
+}
+
+fn add(x: u64, y: u64) -> u64 {
+    let z = x + y;
+    if z >= MOD {
+        z - MOD
+    } else {
+        z
+    }
+}
+
+fn mul(x: u64, y: u64) -> u64 {
+    let z = u128::from(x) * u128::from(y);
+    let z = ((z >> 61) + (z & u128::from(MOD))) as u64;
+    if z >= MOD {
+        z - MOD
+    } else {
+        z
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rand::rngs::StdRng;
+    use rand::Rng;
+    use rand::SeedableRng;
+    use randtools::Tree;
+    use std::collections::BTreeMap;
+
+    // Exact ids by sorting the ids of the children.
+    fn brute(roots: &[usize], g: &[Vec<usize>]) -> Vec<usize> {
+        fn dfs(
+            x: usize,
+            p: usize,
+            g: &[Vec<usize>],
+            classes: &mut BTreeMap<Vec<usize>, usize>,
+            canonical: &mut [usize],
+        ) {
+            let mut children = Vec::new();
+            for &y in &g[x] {
+                if y != p {
+                    dfs(y, x, g, classes, canonical);
+                    children.push(canonical[y]);
+                }
+            }
+            children.sort_unstable();
+            let len = classes.len();
+            canonical[x] = *classes.entry(children).or_insert(len);
+        }
+        let mut classes = BTreeMap::new();
+        let mut canonical = vec![0; g.len()];
+        for &root in roots {
+            dfs(root, root, g, &mut classes, &mut canonical);
+        }
+        canonical
+    }
+
+    fn test_forest(roots: &[usize], g: &[Vec<usize>], seed: u64) {
+        let hash = SubtreeHash::with_seed(roots, g, seed);
+        let result = hash.group_identical_subtrees();
+        let expected = brute(roots, g);
+        for x in 0..g.len() {
+            for y in 0..g.len() {
+                assert_eq!(result[x] == result[y], expected[x] == expected[y]);
+                assert_eq!(hash.equal_subtrees(x, y), expected[x] == expected[y]);
+            }
+        }
+        let mut next = 0;
+        for &id in &result {
+            if id == next {
+                next += 1;
+            }
+            assert!(id < next);
+        }
+    }
+
+    fn add_edge(g: &mut [Vec<usize>], u: usize, v: usize) {
+        g[u].push(v);
+        g[v].push(u);
+    }
+
+    #[test]
+    fn test_random_tree() {
+        let mut rng = StdRng::seed_from_u64(42);
+        for _ in 0..200 {
+            let n = rng.gen_range(1..=40);
+            let g = rng.sample(Tree(n));
+            let root = rng.gen_range(0..n);
+            test_forest(&[root], &g, rng.gen());
+        }
+    }
+
+    #[test]
+    fn test_random_forest() {
+        let mut rng = StdRng::seed_from_u64(42);
+        for _ in 0..100 {
+            let mut g = Vec::new();
+            let mut roots = Vec::new();
+            for _ in 0..rng.gen_range(1..=4) {
+                let n = rng.gen_range(1..=10);
+                let offset = g.len();
+                roots.push(offset + rng.gen_range(0..n));
+                g.extend(
+                    rng.sample(Tree(n))
+                        .into_iter()
+                        .map(|gx| gx.into_iter().map(|y| offset + y).collect::<Vec<_>>()),
+                );
+            }
+            test_forest(&roots, &g, rng.gen());
+        }
+    }
+
+    #[test]
+    fn test_caterpillar() {
+        let mut rng = StdRng::seed_from_u64(42);
+        for _ in 0..20 {
+            let spine = rng.gen_range(1..=20);
+            let mut g = vec![Vec::new(); spine];
+            for i in 1..spine {
+                add_edge(&mut g, i - 1, i);
+            }
+            for i in 0..spine {
+                for _ in 0..rng.gen_range(0..=2) {
+                    g.push(Vec::new());
+                    let leaf = g.len() - 1;
+                    add_edge(&mut g, i, leaf);
+                }
+            }
+            test_forest(&[0], &g, rng.gen());
+        }
+    }
+
+    #[test]
+    fn test_perfect_binary_tree() {
+        for depth in 0..=6 {
+            let n = (1 << (depth + 1)) - 1;
+            let mut g = vec![Vec::new(); n];
+            for i in 1..n {
+                add_edge(&mut g, (i - 1) / 2, i);
+            }
+            test_forest(&[0], &g, 42);
+            let result = SubtreeHash::with_seed(&[0], &g, 42).group_identical_subtrees();
+            let expected = (0..n)
+                .map(|i: usize| (usize::BITS - 1 - (i + 1).leading_zeros()) as usize)
+                .collect::<Vec<_>>();
+            assert_eq!(result, expected);
+        }
+    }
+
+    #[test]
+    fn test_xor_collision() {
+        // Vertex 1 has two identical children, so the xor of them would make it look like a leaf.
+        let g = vec![vec![1, 2], vec![0, 3, 4], vec![0], vec![1], vec![1]];
+        test_forest(&[0], &g, 42);
+        test_forest(&[0], &g, 43);
+    }
+
+    #[test]
+    fn test_salt() {
+        let mut rng = StdRng::seed_from_u64(42);
+        let g = rng.sample(Tree(30));
+        salt::set_seed(42);
+        let a = SubtreeHash::new(&[0], &g);
+        salt::set_seed(42);
+        assert_eq!(SubtreeHash::new(&[0], &g), a);
+        salt::set_seed(43);
+        assert_ne!(
+            SubtreeHash::new(&[0], &g).subtree_hash(0),
+            a.subtree_hash(0)
+        );
+        // Every attempt hashes with a different function.
+        let mut roots = Vec::new();
+        salt::retry_on_collision(5, |seed| {
+            roots.push(SubtreeHash::with_seed(&[0], &g, seed).subtree_hash(0));
+            None::<()>
+        });
+        roots.sort_unstable();
+        roots.dedup();
+        assert_eq!(roots.len(), 5);
+    }
+}
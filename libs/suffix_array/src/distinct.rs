@@ -0,0 +1,192 @@
+//! Suffix Array と LCP 配列を使った、相異なる部分文字列に関するクエリです。
+
+use super::lcp_array;
+use super::suffix_array;
+use std::collections::BTreeSet;
+use std::ops::Range;
+
+/// 空でない相異なる部分文字列の個数を返します。
+///
+/// # 計算量
+///
+/// Suffix Array の構築と同じです。
+///
+/// # Examples
+///
+/// ```
+/// use suffix_array::count_distinct_substrings;
+/// // a, b, ab, ba, aba
+/// assert_eq!(count_distinct_substrings(b"aba"), 5);
+/// ```
+pub fn count_distinct_substrings<T: Ord>(s: &[T]) -> u64 {
+    let n = s.len() as u64;
+    if n == 0 {
+        return 0;
+    }
+    let lcp = lcp_array(s, &suffix_array(s));
+    n * (n + 1) / 2 - lcp.iter().map(|&x| x as u64).sum::<u64>()
+}
+
+/// 空でない相異なる部分文字列のうち、辞書順で `k` 番目 (1-indexed) のものの位置を返します。
+/// そのようなものがなければ `None` を返します。
+///
+/// Suffix Array の順に、各接尾辞の接頭辞のうち直前の接尾辞との LCP より長いものが、
+/// 新しい部分文字列として辞書順に現れることを使います。
+///
+/// # Examples
+///
+/// ```
+/// use suffix_array::kth_distinct_substring;
+/// // a, ab, aba, b, ba
+/// let s = b"aba";
+/// assert_eq!(kth_distinct_substring(s, 2), Some(0..2));
+/// assert_eq!(kth_distinct_substring(s, 5), Some(1..3));
+/// assert_eq!(kth_distinct_substring(s, 6), None);
+/// ```
+pub fn kth_distinct_substring<T: Ord>(s: &[T], mut k: u64) -> Option<Range<usize>> {
+    if s.is_empty() || k == 0 {
+        return None;
+    }
+    let n = s.len();
+    let sa = suffix_array(s);
+    let lcp = lcp_array(s, &sa);
+    for (i, &start) in sa.iter().enumerate() {
+        let common = if i == 0 { 0 } else { lcp[i - 1] };
+        let new = (n - start - common) as u64;
+        if k <= new {
+            return Some(start..start + common + k as usize);
+        }
+        k -= new;
+    }
+    None
+}
+
+/// 各接頭辞 `s[..i]` について、空でない相異なる部分文字列の個数を返します。
+///
+/// `s[..=i]` で新しく現れる部分文字列は、`s[i]` で終わるもののうち、より前で終わる出現を持つ最長のものより
+/// 長いものです。その長さは、反転した文字列の Suffix Array で前後の順位の接尾辞との LCP の最大値です。
+///
+/// # 計算量
+///
+/// O ( n log n )
+///
+/// # Examples
+///
+/// ```
+/// use suffix_array::count_distinct_substrings_of_each_prefix;
+/// assert_eq!(count_distinct_substrings_of_each_prefix(b"aba"), [
+///     0, 1, 3, 5
+/// ]);
+/// ```
+pub fn count_distinct_substrings_of_each_prefix<T: Ord + Clone>(s: &[T]) -> Vec<u64> {
+    let n = s.len();
+    let mut result = vec![0; n + 1];
+    if n == 0 {
+        return result;
+    }
+    let rev = s.iter().rev().cloned().collect::<Vec<_>>();
+    let sa = suffix_array(&rev);
+    let lcp = MinTable::new(&lcp_array(&rev, &sa));
+    let mut rank = vec![0; n];
+    for (r, &p) in sa.iter().enumerate() {
+        rank[p] = r;
+    }
+    let mut seen = BTreeSet::new();
+    for i in 0..n {
+        // `s[..=i]` の反転は `rev[n - 1 - i..]` です。
+        let r = rank[n - 1 - i];
+        let left = seen.range(..r).next_back().map_or(0, |&l| lcp.min(l..r));
+        let right = seen.range(r..).next().map_or(0, |&q| lcp.min(r..q));
+        seen.insert(r);
+        result[i + 1] = result[i] + (i + 1 - left.max(right)) as u64;
+    }
+    result
+}
+
+// LCP 配列の区間最小値を返す Sparse Table です。
+struct MinTable(Vec<Vec<usize>>);
+impl MinTable {
+    fn new(a: &[usize]) -> Self {
+        let mut table = vec![a.to_vec()];
+        let mut d = 1;
+        while 2 * d <= a.len() {
+            let last = table.last().unwrap();
+            let next = last
+                .iter()
+                .zip(&last[d..])
+                .map(|(&x, &y)| x.min(y))
+                .collect();
+            table.push(next);
+            d *= 2;
+        }
+        Self(table)
+    }
+
+    // 順位 `l..r` の接尾辞たちの LCP、つまり `lcp[l..r]` の最小値です。
+    fn min(&self, range: Range<usize>) -> usize {
+        let Range { start, end } = range;
+        let p = (usize::BITS - 1 - (end - start).leading_zeros()) as usize;
+        self.0[p][start].min(self.0[p][end - (1 << p)])
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rand::prelude::*;
+
+    fn random_strings(rng: &mut StdRng) -> Vec<Vec<u8>> {
+        let mut strings = vec![
+            b"".to_vec(),
+            b"a".to_vec(),
+            vec![b'a'; 300],
+            b"abracadabra".to_vec(),
+        ];
+        for _ in 0..30 {
+            let n = rng.gen_range(0..=300);
+            let sigma: u8 = rng.gen_range(1..=4);
+            strings.push((0..n).map(|_| b'a' + rng.gen_range(0..sigma)).collect());
+        }
+        strings
+    }
+
+    fn substrings(s: &[u8]) -> BTreeSet<&[u8]> {
+        (0..s.len())
+            .flat_map(|i| (i + 1..=s.len()).map(move |j| &s[i..j]))
+            .collect()
+    }
+
+    #[test]
+    fn test_count_and_kth() {
+        let mut rng = StdRng::seed_from_u64(42);
+        for s in random_strings(&mut rng) {
+            let expected = substrings(&s).into_iter().collect::<Vec<_>>();
+            let total = expected.len() as u64;
+            assert_eq!(count_distinct_substrings(&s), total);
+            assert_eq!(kth_distinct_substring(&s, 0), None);
+            assert_eq!(kth_distinct_substring(&s, total + 1), None);
+            let mut ks = vec![1, total];
+            ks.extend((0..20).map(|_| rng.gen_range(1..=total.max(1))));
+            for k in ks.into_iter().filter(|&k| 1 <= k && k <= total) {
+                let range = kth_distinct_substring(&s, k).unwrap();
+                assert_eq!(&s[range], expected[k as usize - 1]);
+            }
+        }
+    }
+
+    #[test]
+    fn test_each_prefix() {
+        let mut rng = StdRng::seed_from_u64(42);
+        for s in random_strings(&mut rng) {
+            let result = count_distinct_substrings_of_each_prefix(&s);
+            // The substrings of `s[..i]` are those ending at or before `i`.
+            let mut seen = BTreeSet::new();
+            let mut expected = vec![0];
+            for i in 1..=s.len() {
+                seen.extend((0..i).map(|j| &s[j..i]));
+                expected.push(seen.len() as u64);
+            }
+            assert_eq!(result, expected);
+        }
+    }
+}
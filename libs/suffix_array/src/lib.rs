@@ -1,5 +1,11 @@
 //! Suffix Array を計算します。
 
+mod distinct;
+
+pub use distinct::count_distinct_substrings;
+pub use distinct::count_distinct_substrings_of_each_prefix;
+pub use distinct::kth_distinct_substring;
+
 /// Surrix Array を計算します。
 ///
 /// TODO: 実装を短くできるような気がします。
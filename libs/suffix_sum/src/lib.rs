@@ -9,8 +9,10 @@
 //! The multiplication must be associative and invertible (divisible).
 //!
 //! Furthermore, the multiplication must be commutative for [`SuffixSum2d`].
+use open::resolve_range;
 use std::fmt;
 use std::iter::repeat_with;
+use std::ops::Range;
 use std::ops::RangeBounds;
 
 /// A trait for segment tree operations.
@@ -47,8 +49,7 @@ impl<O: Op> SuffixSum<O> {
 
     /// Returns $x_l \cdot x_{l+1} \cdot \ldots \cdot x_{r-1}$.
     pub fn fold(&self, range: impl RangeBounds<usize>) -> O::Value {
-        let (start, end) = open(range, self.values.len() - 1);
-        assert!(start <= end && end < self.values.len());
+        let Range { start, end } = resolve_range(self.values.len() - 1, range);
         O::div(&self.values[start], &self.values[end])
     }
 
@@ -129,10 +130,8 @@ impl<O: Op> SuffixSum2d<O> {
 
     /// Returns $\left ( x_{i_0, j_0} \cdot \dots \cdot x_{i_0, j_1-1} \right ) \cdot \left ( x_{i_1, j_0} \cdot \dots \cdot x_{i_1-1, j_0} \right )$.
     pub fn fold(&self, i: impl RangeBounds<usize>, j: impl RangeBounds<usize>) -> O::Value {
-        let (i0, i1) = open(i, self.values.len() - 1);
-        let (j0, j1) = open(j, self.values[0].len());
-        assert!(i0 <= i1 && i1 < self.values.len());
-        assert!(j0 <= j1 && j1 <= self.values.get(0).map_or(0, Vec::len));
+        let Range { start: i0, end: i1 } = resolve_range(self.values.len() - 1, i);
+        let Range { start: j0, end: j1 } = resolve_range(self.values[0].len() - 1, j);
         O::div(
             &O::mul(&self.values[i0][j0], &self.values[i1][j1]),
             &O::mul(&self.values[i0][j1], &self.values[i1][j0]),
@@ -201,21 +200,6 @@ impl<O: Op> From<Vec<Vec<O::Value>>> for SuffixSum2d<O> {
     }
 }
 
-fn open<B: RangeBounds<usize>>(bounds: B, n: usize) -> (usize, usize) {
-    use std::ops::Bound;
-    let start = match bounds.start_bound() {
-        Bound::Unbounded => 0,
-        Bound::Included(&x) => x,
-        Bound::Excluded(&x) => x + 1,
-    };
-    let end = match bounds.end_bound() {
-        Bound::Unbounded => n,
-        Bound::Included(&x) => x + 1,
-        Bound::Excluded(&x) => x,
-    };
-    (start, end)
-}
-
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -304,7 +288,7 @@ mod tests {
     }
 
     #[test]
-    #[should_panic]
+    #[should_panic(expected = "range starts at 3 but ends at 1")]
     #[allow(clippy::reversed_empty_ranges)]
     fn test_suffix_sum_invalid_range() {
         let values = vec![1, 2, 3, 4, 5];
@@ -313,7 +297,7 @@ mod tests {
     }
 
     #[test]
-    #[should_panic]
+    #[should_panic(expected = "range end index 6 out of range for length 5")]
     fn test_suffix_sum_out_of_range() {
         let values = vec![1, 2, 3, 4, 5];
         let suffix_sum = SuffixSum::<O>::new(&values);
@@ -357,6 +341,22 @@ mod tests {
         }
     }
 
+    #[test]
+    fn test_suffix_sum_2d_unbounded() {
+        let values = vec![vec![1, 2, 3], vec![4, 5, 6]];
+        let suffix_sum = SuffixSum2d::<O>::new(&values);
+        assert_eq!(suffix_sum.fold(.., ..), 21);
+        assert_eq!(suffix_sum.fold(1.., 1..), 11);
+        assert_eq!(suffix_sum.fold(..=0, ..=1), 3);
+    }
+
+    #[test]
+    #[should_panic(expected = "range end index 4 out of range for length 3")]
+    fn test_suffix_sum_2d_out_of_range() {
+        let values = vec![vec![1, 2, 3], vec![4, 5, 6]];
+        SuffixSum2d::<O>::new(&values).fold(.., ..=3);
+    }
+
     fn random_range(rng: &mut StdRng, n: usize) -> Range<usize> {
         let start = rng.gen_range(0..=n + 1);
         let end = rng.gen_range(0..=n);
@@ -1,6 +1,8 @@
 //! # Sliding Window Aggregation (SWAG)
 //!
 //! * [`DequeueSwag`]: A foldable deque.
+//! * [`RingWindow`]: The last $W$ elements of a sequence, indexed by the positions in it.
+//! * [`RingWindowSwag`]: A [`RingWindow`] that also folds the window.
 //!
 //! # Constructors
 //!
@@ -9,6 +11,10 @@
 //! * [`from_iter`](DequeueSwag::from_iter): [`IntoIterator`] -> [`DequeueSwag`].
 //! * [`clone_from_slice`](DequeueSwag::clone_from_slice), [`copy_from_slice`](DequeueSwag::copy_from_slice): [`&[T]`] -> [`DequeueSwag`].
 
+mod ring;
+
+pub use ring::RingWindow;
+pub use ring::RingWindowSwag;
 use std::iter::FromIterator;
 use std::ops::Index;
 
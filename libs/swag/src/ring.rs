@@ -0,0 +1,367 @@
+use crate::Op;
+use std::mem::replace;
+use std::ops::Index;
+use std::ops::Range;
+
+/// A window of the last `capacity` elements of a sequence, in a ring buffer.
+///
+/// Elements are indexed by their positions in the whole sequence: the `i`-th pushed element
+/// (0-based) has the position `i`, which never changes even after older elements are evicted.
+/// [`get_by_age`](RingWindow::get_by_age) indexes from the newest element instead.
+///
+/// # Examples
+///
+/// ```
+/// use swag::RingWindow;
+/// let mut window = RingWindow::new(3);
+/// for x in 10..15 {
+///     window.push(x);
+/// }
+/// assert_eq!(window.range(), 2..5);
+/// assert_eq!(window.get(1), None);
+/// assert_eq!(window.get(2), Some(&12));
+/// assert_eq!(window[4], 14);
+/// assert_eq!(window.get_by_age(0), Some(&14));
+/// assert_eq!(window.iter().copied().collect::<Vec<_>>(), vec![12, 13, 14]);
+/// ```
+#[derive(Clone, Debug)]
+pub struct RingWindow<T> {
+    buf: Vec<T>,
+    capacity: usize,
+    end: usize,
+}
+impl<T> RingWindow<T> {
+    /// Constructs an empty window of the capacity `capacity`.
+    ///
+    /// # Panics
+    ///
+    /// If `capacity` is zero.
+    pub fn new(capacity: usize) -> Self {
+        assert!(capacity > 0, "the capacity must be positive");
+        Self {
+            buf: Vec::with_capacity(capacity),
+            capacity,
+            end: 0,
+        }
+    }
+
+    /// Returns the capacity.
+    pub fn capacity(&self) -> usize {
+        self.capacity
+    }
+
+    /// Returns the number of the elements in the window.
+    pub fn len(&self) -> usize {
+        self.buf.len()
+    }
+
+    /// Returns whether the window is empty.
+    pub fn is_empty(&self) -> bool {
+        self.buf.is_empty()
+    }
+
+    /// Returns the positions of the elements in the window.
+    pub fn range(&self) -> Range<usize> {
+        self.end - self.buf.len()..self.end
+    }
+
+    /// Appends an element, and returns the oldest one if it is evicted.
+    ///
+    /// # Complexity
+    ///
+    /// $O(1)$
+    pub fn push(&mut self, x: T) -> Option<T> {
+        let i = self.end % self.capacity;
+        self.end += 1;
+        if self.buf.len() < self.capacity {
+            self.buf.push(x);
+            None
+        } else {
+            Some(replace(&mut self.buf[i], x))
+        }
+    }
+
+    /// Returns the element at the position `position` if it is in the window.
+    pub fn get(&self, position: usize) -> Option<&T> {
+        if self.range().contains(&position) {
+            Some(&self.buf[position % self.capacity])
+        } else {
+            None
+        }
+    }
+
+    /// Returns the element at the position `position` if it is in the window.
+    pub fn get_mut(&mut self, position: usize) -> Option<&mut T> {
+        if self.range().contains(&position) {
+            Some(&mut self.buf[position % self.capacity])
+        } else {
+            None
+        }
+    }
+
+    /// Returns the `age`-th newest element, where the newest one has the age `0`.
+    pub fn get_by_age(&self, age: usize) -> Option<&T> {
+        age.checked_add(1)
+            .and_then(|a| self.end.checked_sub(a))
+            .and_then(|i| self.get(i))
+    }
+
+    /// Returns the newest element.
+    pub fn newest(&self) -> Option<&T> {
+        self.get_by_age(0)
+    }
+
+    /// Returns the oldest element.
+    pub fn oldest(&self) -> Option<&T> {
+        self.get(self.range().start)
+    }
+
+    /// Returns an iterator from the oldest to the newest.
+    pub fn iter(&self) -> impl DoubleEndedIterator<Item = &T> {
+        let i = self.range().start % self.capacity;
+        let (newer, older) = self.buf.split_at(i);
+        older.iter().chain(newer)
+    }
+}
+
+impl<T> Index<usize> for RingWindow<T> {
+    type Output = T;
+
+    fn index(&self, position: usize) -> &T {
+        let range = self.range();
+        self.get(position).unwrap_or_else(|| {
+            panic!(
+                "position {} out of range {}..{}",
+                position, range.start, range.end
+            )
+        })
+    }
+}
+
+/// A [`RingWindow`] that also folds the window.
+///
+/// It is a two-stack queue laid on the ring buffer: the older part keeps its suffix folds, and the
+/// newer part keeps its total fold. When the older part runs out, the suffix folds of the whole
+/// window are rebuilt, which costs $O(1)$ amortized per push.
+///
+/// # Examples
+///
+/// ```
+/// use swag::RingWindowSwag;
+/// enum O {}
+/// impl swag::Op for O {
+///     type Value = u32;
+///
+///     fn op(a: &Self::Value, b: &Self::Value) -> Self::Value {
+///         *a.max(b)
+///     }
+/// }
+/// let mut window = RingWindowSwag::<O>::new(3);
+/// assert_eq!(window.fold(), None);
+/// let mut max = Vec::new();
+/// for &x in &[3, 1, 4, 1, 5, 9, 2, 6] {
+///     window.push(x);
+///     max.push(window.fold().unwrap());
+/// }
+/// assert_eq!(max, vec![3, 3, 4, 4, 5, 9, 9, 9]);
+/// assert_eq!(window.window().get(6), Some(&2));
+/// ```
+pub struct RingWindowSwag<O: Op> {
+    values: RingWindow<O::Value>,
+    // `suffix.get(i)` is the fold of `i..mid` for `i < mid`, and a placeholder otherwise.
+    suffix: RingWindow<O::Value>,
+    mid: usize,
+    // The fold of `mid..end`.
+    back_sum: Option<O::Value>,
+}
+impl<O: Op> RingWindowSwag<O>
+where
+    O::Value: Clone,
+{
+    /// Constructs an empty window of the capacity `capacity`.
+    ///
+    /// # Panics
+    ///
+    /// If `capacity` is zero.
+    pub fn new(capacity: usize) -> Self {
+        Self {
+            values: RingWindow::new(capacity),
+            suffix: RingWindow::new(capacity),
+            mid: 0,
+            back_sum: None,
+        }
+    }
+
+    /// Returns the underlying window.
+    pub fn window(&self) -> &RingWindow<O::Value> {
+        &self.values
+    }
+
+    /// Returns the number of the elements in the window.
+    pub fn len(&self) -> usize {
+        self.values.len()
+    }
+
+    /// Returns whether the window is empty.
+    pub fn is_empty(&self) -> bool {
+        self.values.is_empty()
+    }
+
+    /// Returns the positions of the elements in the window.
+    pub fn range(&self) -> Range<usize> {
+        self.values.range()
+    }
+
+    /// Returns the element at the position `position` if it is in the window.
+    pub fn get(&self, position: usize) -> Option<&O::Value> {
+        self.values.get(position)
+    }
+
+    /// Appends an element, and returns the oldest one if it is evicted.
+    ///
+    /// # Complexity
+    ///
+    /// $O(1)$ amortized
+    pub fn push(&mut self, x: O::Value) -> Option<O::Value> {
+        let range = self.values.range();
+        if self.values.len() == self.values.capacity() && self.mid == range.start {
+            self.rebuild();
+        }
+        self.back_sum = Some(match self.back_sum.take() {
+            None => x.clone(),
+            Some(s) => O::op(&s, &x),
+        });
+        self.suffix.push(x.clone());
+        self.values.push(x)
+    }
+
+    /// Folds the window from the oldest to the newest. Returns `None` if it is empty.
+    ///
+    /// # Complexity
+    ///
+    /// $O(1)$
+    pub fn fold(&self) -> Option<O::Value> {
+        let start = self.values.range().start;
+        let front_sum = if start < self.mid { self.suffix.get(start) } else { None };
+        match (front_sum, &self.back_sum) {
+            (None, None) => None,
+            (Some(x), None) | (None, Some(x)) => Some(x.clone()),
+            (Some(x), Some(y)) => Some(O::op(x, y)),
+        }
+    }
+
+    fn rebuild(&mut self) {
+        let range = self.values.range();
+        let mut acc: Option<O::Value> = None;
+        for i in range.clone().rev() {
+            let x = self.values.get(i).unwrap();
+            let s = match acc {
+                None => x.clone(),
+                Some(acc) => O::op(x, &acc),
+            };
+            *self.suffix.get_mut(i).unwrap() = s.clone();
+            acc = Some(s);
+        }
+        self.mid = range.end;
+        self.back_sum = None;
+    }
+}
+
+impl<O: Op> std::fmt::Debug for RingWindowSwag<O>
+where
+    O::Value: std::fmt::Debug,
+{
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("RingWindowSwag")
+            .field("values", &self.values)
+            .field("mid", &self.mid)
+            .field("back_sum", &self.back_sum)
+            .finish()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rand::rngs::StdRng;
+    use rand::Rng;
+    use rand::SeedableRng;
+
+    // A non-commutative operation: the composition of `x -> ax + b` modulo a prime.
+    enum Affine {}
+    impl Op for Affine {
+        type Value = (u64, u64);
+
+        fn op(&(a, b): &Self::Value, &(c, d): &Self::Value) -> Self::Value {
+            (a * c % 998244353, (b * c + d) % 998244353)
+        }
+    }
+
+    #[test]
+    fn test_ring_window() {
+        let mut rng = StdRng::seed_from_u64(42);
+        for capacity in (1..=5).chain(vec![64, 1000]) {
+            let mut window = RingWindow::new(capacity);
+            let mut all = Vec::new();
+            for _ in 0..3000 {
+                let x = rng.gen_range(0..1000_u32);
+                let evicted = window.push(x);
+                all.push(x);
+                let start = all.len().saturating_sub(capacity);
+                let expected = &all[start..];
+                assert_eq!(evicted, (all.len() > capacity).then(|| all[start - 1]));
+                assert_eq!(window.len(), expected.len());
+                assert_eq!(window.range(), start..all.len());
+                assert!(window.iter().eq(expected));
+                assert!(window.iter().rev().eq(expected.iter().rev()));
+                assert_eq!(window.oldest(), expected.first());
+                assert_eq!(window.newest(), expected.last());
+                for _ in 0..3 {
+                    let i = rng.gen_range(0..all.len() + 2);
+                    assert_eq!(window.get(i), (start <= i).then(|| all.get(i)).flatten());
+                    let age = rng.gen_range(0..capacity + 2);
+                    assert_eq!(window.get_by_age(age), expected.iter().rev().nth(age));
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn test_ring_window_swag() {
+        let mut rng = StdRng::seed_from_u64(42);
+        for capacity in (1..=5).chain(vec![64, 1000]) {
+            let mut window = RingWindowSwag::<Affine>::new(capacity);
+            let mut all = Vec::new();
+            for _ in 0..3000 {
+                let x = (rng.gen_range(0..4_u64), rng.gen_range(0..4_u64));
+                window.push(x);
+                all.push(x);
+                let start = all.len().saturating_sub(capacity);
+                let expected = all[start..]
+                    .iter()
+                    .fold((1, 0), |acc, x| Affine::op(&acc, x));
+                assert_eq!(window.fold(), Some(expected));
+                assert_eq!(window.range(), start..all.len());
+                assert_eq!(window.get(all.len() - 1), all.last());
+            }
+        }
+    }
+
+    #[test]
+    fn test_get_by_age_max() {
+        let mut window = RingWindow::new(3);
+        assert_eq!(window.get_by_age(usize::MAX), None);
+        window.push(0);
+        assert_eq!(window.get_by_age(usize::MAX), None);
+    }
+
+    #[test]
+    #[should_panic(expected = "position 1 out of range 2..5")]
+    fn test_index_evicted() {
+        let mut window = RingWindow::new(3);
+        for x in 0..5 {
+            window.push(x);
+        }
+        let _ = window[1];
+    }
+}
@@ -0,0 +1,263 @@
+//! Sweep lines with an explicit order of the events.
+//!
+//! An [`Event`] is ordered by its coordinate, then by its phase, then by its sequence number, the
+//! order of pushing it to an [`EventQueue`]. So the events at the same coordinate are processed
+//! phase by phase, as the phase type orders them, and in the order of pushing within a phase.
+//!
+//! # Canned solvers
+//!
+//! Intervals are half-open: $\[l, r)$ contains $x$ iff $l \le x < r$, and an interval with
+//! $l \ge r$ is empty and ignored. At each coordinate, the solvers close intervals before opening
+//! ones, and open intervals before answering queries.
+//!
+//! - [`max_overlap`]: the maximum number of the intervals containing a common point
+//! - [`count_containing_point`]: the numbers of the intervals containing each query point
+//!
+//! # Examples
+//!
+//! ```
+//! use sweep_line::EventQueue;
+//! #[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+//! enum Phase {
+//!     Remove,
+//!     Insert,
+//! }
+//! let mut queue = EventQueue::new();
+//! queue.push(3, Phase::Insert, 0);
+//! queue.push(1, Phase::Insert, 1);
+//! queue.push(3, Phase::Remove, 1);
+//! let order = queue
+//!     .into_sorted()
+//!     .iter()
+//!     .map(|e| (e.coord, e.id))
+//!     .collect::<Vec<_>>();
+//! assert_eq!(order, vec![(1, 1), (3, 1), (3, 0)]);
+//! ```
+//!
+//! ```
+//! use sweep_line::count_containing_point;
+//! use sweep_line::max_overlap;
+//! let intervals = [(0, 3), (3, 5), (1, 4), (2, 2)];
+//! assert_eq!(max_overlap(&intervals), Some((2, 1)));
+//! assert_eq!(count_containing_point(&intervals, &[0, 2, 3, 5]), vec![
+//!     1, 2, 2, 0
+//! ]);
+//! ```
+
+/// An event of a sweep line.
+///
+/// The derived order compares `coord`, `phase` and `seq` in this order, and `seq` is unique in
+/// an [`EventQueue`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Event<K, P> {
+    /// The coordinate.
+    pub coord: K,
+    /// The phase to order the events at the same coordinate.
+    pub phase: P,
+    /// The sequence number, in the order of pushing.
+    pub seq: usize,
+    /// The user data, typically an index to an interval or a query.
+    pub id: usize,
+}
+
+/// A builder of the sorted events.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct EventQueue<K, P> {
+    events: Vec<Event<K, P>>,
+}
+impl<K: Ord, P: Ord> EventQueue<K, P> {
+    /// Constructs an empty queue.
+    pub fn new() -> Self {
+        Self { events: Vec::new() }
+    }
+
+    /// Returns the number of the events.
+    pub fn len(&self) -> usize {
+        self.events.len()
+    }
+
+    /// Returns `true` if there are no events.
+    pub fn is_empty(&self) -> bool {
+        self.events.is_empty()
+    }
+
+    /// Pushes an event.
+    pub fn push(&mut self, coord: K, phase: P, id: usize) {
+        let seq = self.events.len();
+        self.events.push(Event {
+            coord,
+            phase,
+            seq,
+            id,
+        });
+    }
+
+    /// Returns the events in order.
+    ///
+    /// # Complexity
+    ///
+    /// $O(n \log n)$
+    pub fn into_sorted(mut self) -> Vec<Event<K, P>> {
+        self.events.sort_unstable();
+        self.events
+    }
+}
+impl<K: Ord, P: Ord> Default for EventQueue<K, P> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+enum Phase {
+    Close,
+    Open,
+    Query,
+}
+
+fn interval_events<K: Ord + Copy>(intervals: &[(K, K)]) -> EventQueue<K, Phase> {
+    let mut queue = EventQueue::new();
+    for (i, &(l, r)) in intervals.iter().enumerate() {
+        if l < r {
+            queue.push(l, Phase::Open, i);
+            queue.push(r, Phase::Close, i);
+        }
+    }
+    queue
+}
+
+/// Returns the maximum number of the intervals containing a common point, and the smallest such
+/// point, or `None` if every interval is empty.
+///
+/// # Complexity
+///
+/// $O(n \log n)$
+pub fn max_overlap<K: Ord + Copy>(intervals: &[(K, K)]) -> Option<(usize, K)> {
+    let mut active = 0;
+    let mut best = None;
+    for event in interval_events(intervals).into_sorted() {
+        match event.phase {
+            Phase::Close => active -= 1,
+            Phase::Open => {
+                active += 1;
+                if best.map_or(true, |(count, _)| count < active) {
+                    best = Some((active, event.coord));
+                }
+            }
+            Phase::Query => unreachable!(),
+        }
+    }
+    best
+}
+
+/// Returns the numbers of the intervals containing each query point.
+///
+/// # Complexity
+///
+/// $O((n + q) \log (n + q))$
+pub fn count_containing_point<K: Ord + Copy>(intervals: &[(K, K)], queries: &[K]) -> Vec<usize> {
+    let mut queue = interval_events(intervals);
+    for (i, &x) in queries.iter().enumerate() {
+        queue.push(x, Phase::Query, i);
+    }
+    let mut active = 0;
+    let mut result = vec![0; queries.len()];
+    for event in queue.into_sorted() {
+        match event.phase {
+            Phase::Close => active -= 1,
+            Phase::Open => active += 1,
+            Phase::Query => result[event.id] = active,
+        }
+    }
+    result
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rand::rngs::StdRng;
+    use rand::Rng;
+    use rand::SeedableRng;
+
+    fn count(intervals: &[(i32, i32)], x: i32) -> usize {
+        intervals.iter().filter(|&&(l, r)| l <= x && x < r).count()
+    }
+
+    #[test]
+    fn test_random() {
+        let mut rng = StdRng::seed_from_u64(42);
+        for _ in 0..500 {
+            let n = rng.gen_range(0..=20);
+            // Few coordinates, so that many events share one
+            let max = rng.gen_range(1..=8);
+            let intervals = (0..n)
+                .map(|_| (rng.gen_range(0..=max), rng.gen_range(0..=max)))
+                .collect::<Vec<_>>();
+            let expected = (0..=max)
+                .map(|x| (count(&intervals, x), x))
+                .filter(|&(c, _)| c != 0)
+                .min_by_key(|&(c, x)| (std::cmp::Reverse(c), x));
+            assert_eq!(max_overlap(&intervals), expected);
+            let queries = (0..rng.gen_range(0..=20))
+                .map(|_| rng.gen_range(-1..=max + 1))
+                .collect::<Vec<_>>();
+            let expected = queries
+                .iter()
+                .map(|&x| count(&intervals, x))
+                .collect::<Vec<_>>();
+            assert_eq!(count_containing_point(&intervals, &queries), expected);
+        }
+    }
+
+    #[test]
+    fn test_touching() {
+        // Touching intervals never overlap.
+        let intervals = (0..10).map(|i| (i, i + 1)).collect::<Vec<_>>();
+        assert_eq!(max_overlap(&intervals), Some((1, 0)));
+        // Nested intervals sharing endpoints
+        let intervals = [(0, 5), (0, 5), (5, 6), (2, 5), (2, 2)];
+        assert_eq!(max_overlap(&intervals), Some((3, 2)));
+        assert_eq!(count_containing_point(&intervals, &[5, 2, 4, 6, 0]), vec![
+            1, 3, 3, 0, 2
+        ]);
+    }
+
+    #[test]
+    fn test_empty() {
+        assert_eq!(max_overlap::<i32>(&[]), None);
+        assert_eq!(max_overlap(&[(3, 3), (4, 1)]), None);
+        assert_eq!(count_containing_point(&[(3, 3), (4, 1)], &[1, 3, 4]), vec![
+            0, 0, 0
+        ]);
+    }
+
+    #[test]
+    fn test_event_order() {
+        #[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+        enum Phase {
+            A,
+            B,
+            C,
+        }
+        let mut rng = StdRng::seed_from_u64(42);
+        for _ in 0..100 {
+            let mut queue = EventQueue::new();
+            let mut pushed = Vec::new();
+            for id in 0..rng.gen_range(0..30) {
+                let coord = rng.gen_range(0..3);
+                let phase = [Phase::A, Phase::B, Phase::C][rng.gen_range(0..3_usize)];
+                queue.push(coord, phase, id);
+                pushed.push((coord, phase, id));
+            }
+            assert_eq!(queue.len(), pushed.len());
+            // A stable sort keeps the order of pushing.
+            pushed.sort_by_key(|&(coord, phase, _)| (coord, phase));
+            let sorted = queue
+                .into_sorted()
+                .iter()
+                .map(|e| (e.coord, e.phase, e.id))
+                .collect::<Vec<_>>();
+            assert_eq!(sorted, pushed);
+        }
+    }
+}
@@ -0,0 +1,227 @@
+//! Power towers modulo $m$.
+//!
+//! By the generalized Euler's theorem, $a ^ e \equiv a ^ {(e \bmod \varphi(m)) + \varphi(m)}
+//! \pmod m$ holds for every $a$ as long as $e \ge \log _ 2 m$, but not for smaller $e$. So each
+//! level of the tower is evaluated modulo the iterated totient of $m$ together with its true
+//! value capped at $64 > \log _ 2 m$, and the correction $+ \varphi(m)$ is applied only when the
+//! cap is reached.
+//!
+//! The convention is $0 ^ 0 = 1$.
+//!
+//! # Examples
+//!
+//! ```
+//! use tetration::power_tower_mod;
+//! use tetration::tetration_mod;
+//! // $2 ^ {2 ^ {2 ^ 2}} = 65536$
+//! assert_eq!(tetration_mod(2, 4, 1_000_000), 65536);
+//! // $3 ^ {2 ^ 2} = 81$
+//! assert_eq!(power_tower_mod(&[3, 2, 2], 100), 81);
+//! assert_eq!(tetration_mod(0, 3, 10), 0);
+//! assert_eq!(tetration_mod(7, 1 << 60, 1), 0);
+//! ```
+
+use trial::euler_phi;
+
+// A true value at least this is capped.
+const CAP: u64 = 64;
+
+/// Returns $a ^ {a ^ {\cdot ^ {\cdot ^ a}}} \bmod m$ with $a$ repeated `height` times, which is
+/// $1 \bmod m$ if `height` is $0$.
+///
+/// # Panics
+///
+/// If $m = 0$.
+///
+/// # Complexity
+///
+/// $O(\sqrt m \log m)$
+pub fn tetration_mod(a: u64, height: u64, m: u64) -> u64 {
+    assert_ne!(m, 0, "the modulus must be positive");
+    match a {
+        0 => (1 - height % 2) % m,
+        1 => 1 % m,
+        _ => {
+            // The iterated totient reaches $1$ in at most $2 \log _ 2 m$ steps, and above that the
+            // capped value saturates at the height $4$.
+            let height = height.min(2 * u64::BITS as u64 + 4) as usize;
+            power_tower_mod(&vec![a; height], m)
+        }
+    }
+}
+
+/// Returns $b _ 0 ^ {b _ 1 ^ {\cdot ^ {\cdot ^ {b _ {n - 1}}}}} \bmod m$, which is $1 \bmod m$ if
+/// `bases` is empty.
+///
+/// # Panics
+///
+/// If $m = 0$.
+///
+/// # Complexity
+///
+/// $O(\sqrt m \log m + n \log m)$
+pub fn power_tower_mod(bases: &[u64], m: u64) -> u64 {
+    assert_ne!(m, 0, "the modulus must be positive");
+    let mut moduli = vec![m];
+    while moduli.len() < bases.len() && *moduli.last().unwrap() != 1 {
+        moduli.push(euler_phi(*moduli.last().unwrap()));
+    }
+    // `(residue, min(true value, CAP))` from the top.
+    let mut value = (1 % moduli.get(bases.len()).copied().unwrap_or(1), 1);
+    for (i, &a) in bases.iter().enumerate().rev() {
+        let m = moduli.get(i).copied().unwrap_or(1);
+        let (residue, capped) = value;
+        value = if capped < CAP {
+            (pow_mod(a, capped, m), pow_capped(a, capped))
+        } else {
+            let phi = moduli.get(i + 1).copied().unwrap_or(1);
+            // $a ^ {residue + phi}$ without overflow
+            let x = u128::from(pow_mod(a, residue, m)) * u128::from(pow_mod(a, phi, m));
+            ((x % u128::from(m)) as u64, pow_capped(a, CAP))
+        };
+    }
+    value.0
+}
+
+fn pow_mod(a: u64, mut e: u64, m: u64) -> u64 {
+    let m = u128::from(m);
+    let mut a = u128::from(a) % m;
+    let mut result = 1 % m;
+    while e != 0 {
+        if e & 1 == 1 {
+            result = result * a % m;
+        }
+        a = a * a % m;
+        e >>= 1;
+    }
+    result as u64
+}
+
+// $\min(a ^ e, \mathrm{CAP})$
+fn pow_capped(a: u64, e: u64) -> u64 {
+    match a {
+        0 => u64::from(e == 0),
+        1 => 1,
+        _ => {
+            let mut result = 1;
+            for _ in 0..e {
+                result *= a.min(CAP);
+                if result >= CAP {
+                    return CAP;
+                }
+            }
+            result
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rand::rngs::StdRng;
+    use rand::Rng;
+    use rand::SeedableRng;
+
+    // The exact value, or `None` if it overflows.
+    fn exact(bases: &[u64]) -> Option<u64> {
+        bases.iter().rev().try_fold(1_u64, |e, &a| {
+            let e = u32::try_from(e).ok()?;
+            a.checked_pow(e)
+        })
+    }
+
+    // Computes the top exponent exactly.
+    fn brute(bases: &[u64], m: u64) -> Option<u64> {
+        match bases.split_first() {
+            None => Some(1 % m),
+            Some((&a, rest)) => Some(pow_mod(a, exact(rest)?, m)),
+        }
+    }
+
+    #[test]
+    fn test_random_towers() {
+        let mut rng = StdRng::seed_from_u64(42);
+        let mut tested = 0;
+        while tested < 20000 {
+            let n = rng.gen_range(0..=5);
+            let bases = (0..n).map(|_| rng.gen_range(0..=6)).collect::<Vec<u64>>();
+            let m = match rng.gen_range(0..3) {
+                0 => rng.gen_range(1..=100),
+                1 => 1 << rng.gen_range(0..=20_u64),
+                _ => rng.gen_range(1..=1_000_000_000),
+            };
+            if let Some(expected) = brute(&bases, m) {
+                assert_eq!(power_tower_mod(&bases, m), expected, "{:?} {}", bases, m);
+                tested += 1;
+            }
+        }
+    }
+
+    #[test]
+    fn test_tetration() {
+        for a in 0..=10 {
+            for height in 0..=5 {
+                for m in 1..=300 {
+                    let bases = vec![a; height];
+                    if let Some(expected) = brute(&bases, m) {
+                        assert_eq!(tetration_mod(a, height as u64, m), expected);
+                    }
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn test_tricky() {
+        // $2 \uparrow \uparrow 5 = 2 ^ {65536}$
+        for m in [
+            2,
+            3,
+            7,
+            12,
+            64,
+            65536,
+            65537,
+            1 << 20,
+            1_000_000_007,
+            998244353 * 4,
+        ] {
+            assert_eq!(tetration_mod(2, 5, m), pow_mod(2, 65536, m));
+        }
+        // $3 \uparrow \uparrow 3 = 3 ^ {27}$
+        assert_eq!(tetration_mod(3, 3, 1 << 50), 7625597484987);
+        // The bases sharing factors with $m$
+        assert_eq!(power_tower_mod(&[6, 2, 2], 36), pow_mod(6, 16, 36));
+        assert_eq!(power_tower_mod(&[2, 3, 2], 1024), pow_mod(2, 9, 1024));
+        assert_eq!(power_tower_mod(&[10, 1, 5], 1000), 10);
+        assert_eq!(power_tower_mod(&[12, 2], 1 << 60), 144);
+        // A zero in the middle
+        assert_eq!(power_tower_mod(&[5, 0, 3], 7), 1);
+        assert_eq!(power_tower_mod(&[5, 0, 0], 7), 5);
+        assert_eq!(power_tower_mod(&[0, 0], 7), 1);
+        assert_eq!(power_tower_mod(&[], 7), 1);
+        assert_eq!(power_tower_mod(&[3], 1), 0);
+        // Large bases
+        let big = u64::MAX - 58;
+        assert_eq!(power_tower_mod(&[big, 1], u64::MAX), big);
+        assert_eq!(
+            power_tower_mod(&[big, 2], u64::MAX),
+            pow_mod(big, 2, u64::MAX)
+        );
+    }
+
+    #[test]
+    fn test_infinite_height() {
+        let mut rng = StdRng::seed_from_u64(42);
+        for _ in 0..200 {
+            let a = rng.gen_range(2..=1_000_000);
+            let m = rng.gen_range(1..=1_000_000_000);
+            let infinite = tetration_mod(a, u64::MAX, m);
+            assert_eq!(tetration_mod(a, 1 << 40, m), infinite);
+            assert_eq!(power_tower_mod(&vec![a; 200], m), infinite);
+        }
+        assert_eq!(tetration_mod(0, u64::MAX, 10), 0);
+        assert_eq!(tetration_mod(0, u64::MAX - 1, 10), 1);
+        assert_eq!(tetration_mod(1, u64::MAX, 10), 1);
+    }
+}
@@ -0,0 +1,264 @@
+//! Counts the pairs of vertices of a tree by their distance.
+//!
+//! At each centroid $c$ of the centroid decomposition, let $D(x) = \sum _ v x ^ { d(c, v) }$ over
+//! the current component, and $D _ y(x)$ the same over the subtree of each child $y$. Then
+//! $D(x) ^ 2 - \sum _ y D _ y(x) ^ 2$ counts the ordered pairs whose path passes through $c$,
+//! since a pair inside a single child subtree does not.
+//!
+//! # Examples
+//!
+//! ```
+//! use tree_distance::count_pairs_at_distance;
+//! use tree_distance::count_pairs_by_distance;
+//! //   0
+//! //  / \
+//! // 1   2
+//! //     |
+//! //     3
+//! let g = vec![vec![1, 2], vec![0], vec![0, 3], vec![2]];
+//! assert_eq!(count_pairs_by_distance(&g), vec![0, 3, 2, 1]);
+//! assert_eq!(count_pairs_at_distance(&g, 2), 2);
+//! assert_eq!(count_pairs_at_distance(&g, 4), 0);
+//! ```
+use fp::fps_mul;
+use fp::Fp;
+
+const P1: u64 = 998244353;
+const P2: u64 = 924844033;
+
+/// Counts the unordered pairs of distinct vertices at distance $k$.
+///
+/// # Complexity
+///
+/// $O(n \log ^ 2 n)$
+pub fn count_pairs_at_distance(g: &[Vec<usize>], k: usize) -> u64 {
+    count_pairs_by_distance(g).get(k).copied().unwrap_or(0)
+}
+
+/// Counts the unordered pairs of distinct vertices at distance $k$ for each $0 \le k < n$.
+///
+/// # Requirements
+///
+/// `g` is an undirected adjacency list of a tree.
+///
+/// # Complexity
+///
+/// $O(n \log ^ 2 n)$
+pub fn count_pairs_by_distance(g: &[Vec<usize>]) -> Vec<u64> {
+    let n = g.len();
+    let mut result = vec![0; n];
+    if n == 0 {
+        return result;
+    }
+    let mut removed = vec![false; n];
+    let mut parent = vec![usize::MAX; n];
+    let mut size = vec![0; n];
+    let mut depth = vec![0; n];
+    let mut order = Vec::with_capacity(n);
+    let mut stack = vec![0];
+    while let Some(root) = stack.pop() {
+        // Find the centroid of the component of `root`.
+        bfs(g, &removed, root, &mut order, &mut parent, &mut depth);
+        for &x in order.iter().rev() {
+            size[x] = 1;
+            for &y in &g[x] {
+                if !removed[y] && y != parent[x] {
+                    size[x] += size[y];
+                }
+            }
+        }
+        let total = order.len();
+        let mut c = root;
+        while let Some(&y) = g[c]
+            .iter()
+            .find(|&&y| !removed[y] && y != parent[c] && size[y] * 2 > total)
+        {
+            c = y;
+        }
+        removed[c] = true;
+        // Count the pairs through the centroid.
+        let mut all = vec![1];
+        let mut through = Vec::new();
+        for &y in &g[c] {
+            if removed[y] {
+                continue;
+            }
+            bfs(g, &removed, y, &mut order, &mut parent, &mut depth);
+            let mut counts = vec![0; depth[*order.last().unwrap()] + 2];
+            for &x in &order {
+                counts[depth[x] + 1] += 1;
+            }
+            if all.len() < counts.len() {
+                all.resize(counts.len(), 0);
+            }
+            for (a, &b) in all.iter_mut().zip(&counts) {
+                *a += b;
+            }
+            through.push(counts);
+            stack.push(y);
+        }
+        // The coefficients of degree at least $n$ cancel out.
+        for (r, x) in result.iter_mut().zip(square(&all)).skip(1) {
+            *r += x;
+        }
+        for counts in &through {
+            for (r, x) in result.iter_mut().zip(square(counts)) {
+                *r -= x;
+            }
+        }
+    }
+    for x in &mut result {
+        *x /= 2;
+    }
+    result
+}
+
+// Visits the vertices not removed in the BFS order, with the depths from `root`.
+fn bfs(
+    g: &[Vec<usize>],
+    removed: &[bool],
+    root: usize,
+    order: &mut Vec<usize>,
+    parent: &mut [usize],
+    depth: &mut [usize],
+) {
+    order.clear();
+    order.push(root);
+    parent[root] = usize::MAX;
+    depth[root] = 0;
+    let mut i = 0;
+    while i < order.len() {
+        let x = order[i];
+        for &y in &g[x] {
+            if !removed[y] && y != parent[x] {
+                parent[y] = x;
+                depth[y] = depth[x] + 1;
+                order.push(y);
+            }
+        }
+        i += 1;
+    }
+}
+
+// The exact square of a polynomial with coefficients whose sum is less than $\sqrt { p _ 1 p _ 2 }$.
+fn square(a: &[u64]) -> Vec<u64> {
+    if a.len() <= 32 {
+        let mut result = vec![0; 2 * a.len() - 1];
+        for (i, &x) in a.iter().enumerate() {
+            for (j, &y) in a.iter().enumerate() {
+                result[i + j] += x * y;
+            }
+        }
+        return result;
+    }
+    let a1 = a.iter().map(|&x| Fp::<P1>::new(x)).collect::<Vec<_>>();
+    let a2 = a.iter().map(|&x| Fp::<P2>::new(x)).collect::<Vec<_>>();
+    let inv = Fp::<P2>::new(P1).inv();
+    fps_mul(&a1, &a1)
+        .into_iter()
+        .zip(fps_mul(&a2, &a2))
+        .map(|(x1, x2)| {
+            let x1 = x1.value();
+            x1 + P1 * ((x2 - Fp::new(x1)) * inv).value()
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rand::rngs::StdRng;
+    use rand::Rng;
+    use rand::SeedableRng;
+    use randtools::Tree;
+
+    fn brute(g: &[Vec<usize>]) -> Vec<u64> {
+        let n = g.len();
+        let mut result = vec![0; n];
+        let mut order = Vec::new();
+        let mut parent = vec![0; n];
+        let mut depth = vec![0; n];
+        let removed = vec![false; n];
+        for x in 0..n {
+            bfs(g, &removed, x, &mut order, &mut parent, &mut depth);
+            for y in x + 1..n {
+                result[depth[y]] += 1;
+            }
+        }
+        result
+    }
+
+    fn test_tree(g: &[Vec<usize>]) {
+        let result = count_pairs_by_distance(g);
+        assert_eq!(result, brute(g));
+        for k in [0, 1, 2, g.len() / 2, g.len() - 1, g.len()] {
+            assert_eq!(
+                count_pairs_at_distance(g, k),
+                result.get(k).copied().unwrap_or(0)
+            );
+        }
+    }
+
+    #[test]
+    fn test_random_tree() {
+        let mut rng = StdRng::seed_from_u64(42);
+        for _ in 0..100 {
+            let n = rng.gen_range(1..=300);
+            test_tree(&rng.sample(Tree(n)));
+        }
+    }
+
+    #[test]
+    fn test_path() {
+        for n in [1, 2, 3, 10, 64, 65, 300] {
+            let mut g = vec![Vec::new(); n];
+            for i in 1..n {
+                g[i - 1].push(i);
+                g[i].push(i - 1);
+            }
+            test_tree(&g);
+            let result = count_pairs_by_distance(&g);
+            for (k, &x) in result.iter().enumerate().skip(1) {
+                assert_eq!(x, (n - k) as u64);
+            }
+        }
+    }
+
+    #[test]
+    fn test_star() {
+        for n in [1, 2, 3, 10, 300] {
+            let mut g = vec![Vec::new(); n];
+            for i in 1..n {
+                g[0].push(i);
+                g[i].push(0);
+            }
+            test_tree(&g);
+        }
+    }
+
+    #[test]
+    fn test_large_counts() {
+        // Two stars joined at their centers, so most pairs are at distance 3.
+        let n = 100000;
+        let mut g = vec![Vec::new(); 2 * n + 2];
+        let mut add_edge = |u: usize, v: usize| {
+            g[u].push(v);
+            g[v].push(u);
+        };
+        add_edge(0, 1);
+        for i in 0..n {
+            add_edge(0, 2 + i);
+            add_edge(1, 2 + n + i);
+        }
+        let result = count_pairs_by_distance(&g);
+        let n = n as u64;
+        assert_eq!(&result[..4], &[0, 2 * n + 1, n * (n - 1) + 2 * n, n * n]);
+        assert!(result[4..].iter().all(|&x| x == 0));
+    }
+
+    #[test]
+    fn test_empty() {
+        assert!(count_pairs_by_distance(&[]).is_empty());
+        assert_eq!(count_pairs_at_distance(&[], 0), 0);
+    }
+}
@@ -0,0 +1,262 @@
+//! Re-rooted tree DP whose values are arrays indexed by a budget $0, \dots, k$.
+
+/// An operation for budgeted re-rooted tree DP.
+///
+/// Every value is a slice of length $k + 1$, such as the best score with at most $j$ of something
+/// used for each $j$. As in [`Op`](crate::Op), the converted values of the branches of $x$ are
+/// multiplied and then converted by $g$, but $f$ takes the weight of the edge to the branch.
+pub trait BudgetedOp {
+    /// The type of the edge weight.
+    type Edge: Copy;
+
+    /// Returns the identity of `mul` of length $k + 1$.
+    fn identity(k: usize) -> Vec<i64>;
+    /// Multiplies the converted values of two disjoint sets of branches. It must be associative
+    /// and commutative, but need not be invertible.
+    fn mul(lhs: &[i64], rhs: &[i64]) -> Vec<i64>;
+    /// Converts the DP value of $y$ to a branch of $x$ through the edge $xy$.
+    fn f(value: &[i64], edge: Self::Edge) -> Vec<i64>;
+    /// Converts the product of the branches of $x$ to the DP value of $x$.
+    fn g(acc: &[i64]) -> Vec<i64>;
+}
+
+/// Returns $\max _ {i + j = l} (a _ i + b _ j)$ for each $l$ below the common length, where
+/// `i64::MIN` stands for $-\infty$.
+///
+/// # Complexity
+///
+/// $O(k ^ 2)$
+pub fn max_plus_convolution(a: &[i64], b: &[i64]) -> Vec<i64> {
+    assert_eq!(a.len(), b.len(), "the lengths of the budgets differ");
+    let mut c = vec![i64::MIN; a.len()];
+    for (i, &x) in a.iter().enumerate() {
+        if x == i64::MIN {
+            continue;
+        }
+        for (j, &y) in b[..a.len() - i].iter().enumerate() {
+            if y != i64::MIN {
+                c[i + j] = c[i + j].max(x + y);
+            }
+        }
+    }
+    c
+}
+
+/// Given a tree `g` with edge weights, returns the DP value of each vertex of the whole tree
+/// re-rooted at it.
+///
+/// The branches of $x$ other than the one toward a child are multiplied from the prefix and
+/// suffix tables of its children instead of dividing by that child, so each vertex $x$ costs
+/// $O(\deg x)$ operations, and the total is $O(n)$ operations, or $O(n k ^ 2)$ time for
+/// [`max_plus_convolution`].
+pub fn rerooted_budgeted_tree_dp<O: BudgetedOp>(
+    root: usize,
+    g: &[Vec<(usize, O::Edge)>],
+    k: usize,
+) -> Vec<Vec<i64>> {
+    let n = g.len();
+    let mut parent = vec![!0; n];
+    let mut children = vec![Vec::new(); n];
+    let mut sorted = Vec::with_capacity(n);
+    let mut stack = vec![root];
+    while let Some(x) = stack.pop() {
+        sorted.push(x);
+        for &(y, e) in &g[x] {
+            if y != parent[x] {
+                parent[y] = x;
+                children[x].push((y, e));
+                stack.push(y);
+            }
+        }
+    }
+    // `ep[y]` is the converted value of the subtree of $y$ as a branch of its parent.
+    let mut ep = vec![Vec::new(); n];
+    for &x in sorted.iter().rev() {
+        let mut acc = O::identity(k);
+        for &(y, _) in &children[x] {
+            acc = O::mul(&acc, &ep[y]);
+        }
+        if x != root {
+            let e = g[x].iter().find(|&&(y, _)| y == parent[x]).unwrap().1;
+            ep[x] = O::f(&O::g(&acc), e);
+        }
+    }
+    // `fp[x]` is the converted value of the rest of the tree as a branch of $x$.
+    let mut fp = vec![O::identity(k); n];
+    let mut result = vec![Vec::new(); n];
+    for &x in &sorted {
+        let mut suffix = vec![O::identity(k)];
+        for &(y, _) in children[x].iter().rev() {
+            let next = O::mul(&ep[y], suffix.last().unwrap());
+            suffix.push(next);
+        }
+        suffix.reverse();
+        let mut prefix = fp[x].clone();
+        for (i, &(y, e)) in children[x].iter().enumerate() {
+            fp[y] = O::f(&O::g(&O::mul(&prefix, &suffix[i + 1])), e);
+            prefix = O::mul(&prefix, &ep[y]);
+        }
+        result[x] = O::g(&prefix);
+    }
+    result
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rand::rngs::StdRng;
+    use rand::Rng;
+    use rand::SeedableRng;
+
+    // An edge of weight `w`, or `u` if upgraded
+    type Edge = (i64, i64);
+
+    // The longest path from each vertex, with at most $j$ edges upgraded
+    enum LongestPath {}
+    impl BudgetedOp for LongestPath {
+        type Edge = Edge;
+
+        fn identity(k: usize) -> Vec<i64> {
+            vec![i64::MIN; k + 1]
+        }
+
+        fn mul(lhs: &[i64], rhs: &[i64]) -> Vec<i64> {
+            lhs.iter().zip(rhs).map(|(&x, &y)| x.max(y)).collect()
+        }
+
+        fn f(value: &[i64], (w, u): Edge) -> Vec<i64> {
+            (0..value.len())
+                .map(|j| {
+                    let keep = value[j] + w;
+                    if j == 0 {
+                        keep
+                    } else {
+                        keep.max(value[j - 1] + u)
+                    }
+                })
+                .collect()
+        }
+
+        // Stops at $x$.
+        fn g(acc: &[i64]) -> Vec<i64> {
+            acc.iter().map(|&x| x.max(0)).collect()
+        }
+    }
+
+    // The heaviest connected set containing each vertex, with at most $j$ edges upgraded
+    enum HeaviestSubtree {}
+    impl BudgetedOp for HeaviestSubtree {
+        type Edge = Edge;
+
+        fn identity(k: usize) -> Vec<i64> {
+            vec![0; k + 1]
+        }
+
+        fn mul(lhs: &[i64], rhs: &[i64]) -> Vec<i64> {
+            max_plus_convolution(lhs, rhs)
+        }
+
+        fn f(value: &[i64], (w, u): Edge) -> Vec<i64> {
+            (0..value.len())
+                .map(|j| {
+                    let keep = value[j] + w;
+                    let upgrade = if j == 0 { i64::MIN } else { value[j - 1] + u };
+                    0.max(keep).max(upgrade)
+                })
+                .collect()
+        }
+
+        fn g(acc: &[i64]) -> Vec<i64> {
+            acc.to_vec()
+        }
+    }
+
+    fn random_tree(rng: &mut StdRng, n: usize) -> Vec<(usize, usize, Edge)> {
+        (1..n)
+            .map(|i| {
+                let w: i64 = rng.gen_range(-5..=5);
+                (rng.gen_range(0..i), i, (w, w + rng.gen_range(0..=5_i64)))
+            })
+            .collect()
+    }
+
+    fn adjacency(n: usize, edges: &[(usize, usize, Edge)]) -> Vec<Vec<(usize, Edge)>> {
+        let mut g = vec![Vec::new(); n];
+        for &(x, y, e) in edges {
+            g[x].push((y, e));
+            g[y].push((x, e));
+        }
+        g
+    }
+
+    // Tries every set of upgraded edges.
+    fn brute(
+        n: usize,
+        edges: &[(usize, usize, Edge)],
+        k: usize,
+        mut score: impl FnMut(&[Vec<(usize, i64)>], usize) -> i64,
+    ) -> Vec<Vec<i64>> {
+        let mut result = vec![vec![i64::MIN; k + 1]; n];
+        for bs in 0_usize..1 << edges.len() {
+            let j = bs.count_ones() as usize;
+            let mut g = vec![Vec::new(); n];
+            for (i, &(x, y, (w, u))) in edges.iter().enumerate() {
+                let c = if bs >> i & 1 == 1 { u } else { w };
+                g[x].push((y, c));
+                g[y].push((x, c));
+            }
+            for (x, row) in result.iter_mut().enumerate() {
+                let s = score(&g, x);
+                row[j.min(k + 1)..]
+                    .iter_mut()
+                    .for_each(|r| *r = (*r).max(s));
+            }
+        }
+        result
+    }
+
+    fn longest_path(g: &[Vec<(usize, i64)>], x: usize, p: usize) -> i64 {
+        g[x].iter()
+            .filter(|&&(y, _)| y != p)
+            .map(|&(y, c)| c + longest_path(g, y, x))
+            .fold(0, i64::max)
+    }
+
+    fn heaviest_subtree(g: &[Vec<(usize, i64)>], x: usize, p: usize) -> i64 {
+        g[x].iter()
+            .filter(|&&(y, _)| y != p)
+            .map(|&(y, c)| 0.max(c + heaviest_subtree(g, y, x)))
+            .sum()
+    }
+
+    #[test]
+    fn test_budgeted() {
+        let mut rng = StdRng::seed_from_u64(42);
+        for _ in 0..100 {
+            let n = rng.gen_range(1..=8);
+            let edges = random_tree(&mut rng, n);
+            let g = adjacency(n, &edges);
+            let root = rng.gen_range(0..n);
+            for k in [0, 1, 2, n - 1, n + 1] {
+                let expected = brute(n, &edges, k, |g, x| longest_path(g, x, !0));
+                assert_eq!(
+                    rerooted_budgeted_tree_dp::<LongestPath>(root, &g, k),
+                    expected
+                );
+                let expected = brute(n, &edges, k, |g, x| heaviest_subtree(g, x, !0));
+                assert_eq!(
+                    rerooted_budgeted_tree_dp::<HeaviestSubtree>(root, &g, k),
+                    expected
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn test_max_plus_convolution() {
+        let a = [0, i64::MIN, 5];
+        let b = [1, 2, i64::MIN];
+        assert_eq!(max_plus_convolution(&a, &b), vec![1, 2, 6]);
+        assert_eq!(max_plus_convolution(&[], &[]), vec![]);
+    }
+}
@@ -67,6 +67,17 @@
 //! $$
 //! \mathrm{gp}(x) = g ^ { W _ x } \left( \mathrm{fp}(x) \cdot \left( \prod _ { y \in G _ x } \mathrm{ep}(y) \right) \right) \in X
 //! $$
+//!
+//! # Budgeted re-rooted tree DP
+//!
+//! [`rerooted_budgeted_tree_dp`] is a variant whose values are arrays indexed by a budget and
+//! whose conversions take edge weights. See [`BudgetedOp`].
+
+mod budgeted;
+
+pub use budgeted::max_plus_convolution;
+pub use budgeted::rerooted_budgeted_tree_dp;
+pub use budgeted::BudgetedOp;
 
 /// An operation for re-rooted tree DP.
 pub trait Op {
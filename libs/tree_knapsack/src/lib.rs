@@ -0,0 +1,264 @@
+//! Knapsack over connected vertex sets of a rooted tree.
+//!
+//! Each vertex $x$ has a weight $w _ x$ and a value $v _ x$, and the total weight is bounded by
+//! the capacity $C$. The table of $x$ is indexed by the exact total weight:
+//!
+//! $$
+//! \mathrm{dp} _ x \[k\] = \max \left \\{ \sum _ {y \in S} v _ y \mid S \ni x \text{ is connected
+//! in the subtree of } x, \sum _ {y \in S} w _ y = k \right \\}
+//! $$
+//!
+//! # Complexity
+//!
+//! The table of $x$ is cut to the length $\min(W _ x, C) + 1$, where $W _ x$ is the total weight
+//! of the subtree of $x$, and merging two tables costs the product of their lengths. So it takes
+//! $O(W \min(W, C))$ time in total, which is $O(n ^ 2)$ for unit weights.
+//!
+//! # Examples
+//!
+//! ```
+//! use tree_knapsack::TreeKnapsack;
+//! // 0 - 1 - 2
+//! //  \
+//! //   3
+//! let g = vec![vec![1, 3], vec![0, 2], vec![1], vec![0]];
+//! let items = [(1, 1), (1, -5), (1, 10), (2, 3)];
+//! let knapsack = TreeKnapsack::new(&g, 0, &items, 3);
+//! assert_eq!(knapsack.table(0), &[None, Some(1), Some(-4), Some(6)]);
+//! assert_eq!(knapsack.best_containing_root(), Some(6));
+//! assert_eq!(knapsack.best_connected(), Some(10));
+//! ```
+
+#[cfg(test)]
+thread_local! {
+    static STEPS: std::cell::Cell<usize> = std::cell::Cell::new(0);
+}
+
+/// The knapsack tables of all the subtrees.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TreeKnapsack {
+    root: usize,
+    tables: Vec<Vec<Option<i64>>>,
+}
+impl TreeKnapsack {
+    /// Computes the tables of the tree `g` rooted at `root` with `items[x] = (weight, value)`.
+    ///
+    /// `g` is an undirected adjacency list.
+    pub fn new(g: &[Vec<usize>], root: usize, items: &[(usize, i64)], capacity: usize) -> Self {
+        let n = g.len();
+        let mut parent = vec![!0; n];
+        let mut sorted = Vec::with_capacity(n);
+        let mut stack = vec![root];
+        while let Some(x) = stack.pop() {
+            sorted.push(x);
+            for &y in &g[x] {
+                if y != parent[x] {
+                    parent[y] = x;
+                    stack.push(y);
+                }
+            }
+        }
+        let mut tables = vec![Vec::new(); n];
+        for &x in sorted.iter().rev() {
+            let (w, v) = items[x];
+            let mut table = vec![None; w.min(capacity + 1)];
+            if w <= capacity {
+                table.push(Some(v));
+            }
+            for &y in &g[x] {
+                if y != parent[x] {
+                    table = merge(&table, &tables[y], capacity);
+                }
+            }
+            tables[x] = table;
+        }
+        Self { root, tables }
+    }
+
+    /// Returns the table of `x`, whose `k`-th element is the maximum value of a connected set
+    /// containing `x` in the subtree of `x` of the total weight exactly `k`, or `None` if there
+    /// is no such set.
+    pub fn table(&self, x: usize) -> &[Option<i64>] {
+        &self.tables[x]
+    }
+
+    /// Returns the maximum value of a connected set containing the root within the capacity, or
+    /// `None` if the root alone exceeds it.
+    pub fn best_containing_root(&self) -> Option<i64> {
+        self.tables[self.root].iter().flatten().copied().max()
+    }
+
+    /// Returns the maximum value of a nonempty connected set within the capacity, or `None` if
+    /// every vertex alone exceeds it.
+    pub fn best_connected(&self) -> Option<i64> {
+        self.tables.iter().flatten().flatten().copied().max()
+    }
+}
+
+// Merges the table of a child, which may be skipped entirely.
+fn merge(table: &[Option<i64>], child: &[Option<i64>], capacity: usize) -> Vec<Option<i64>> {
+    if table.is_empty() {
+        return Vec::new();
+    }
+    // $\min(W _ x + W _ y, C) + 1$
+    let mut result = table.to_vec();
+    result.resize((table.len() + child.len() - 1).min(capacity + 1), None);
+    for (i, &a) in table.iter().enumerate() {
+        let Some(a) = a else { continue };
+        // $i + j \le C$
+        for (j, &b) in child.iter().enumerate().take(capacity + 1 - i) {
+            #[cfg(test)]
+            STEPS.with(|steps| steps.set(steps.get() + 1));
+            if let Some(b) = b {
+                let c = &mut result[i + j];
+                *c = Some(c.map_or(a + b, |c| c.max(a + b)));
+            }
+        }
+    }
+    result
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rand::rngs::StdRng;
+    use rand::Rng;
+    use rand::SeedableRng;
+
+    fn random_tree(rng: &mut StdRng, n: usize) -> Vec<Vec<usize>> {
+        let mut g = vec![Vec::new(); n];
+        for i in 1..n {
+            // Sometimes a path or a star
+            let p = match rng.gen_range(0..4) {
+                0 => i - 1,
+                1 => 0,
+                _ => rng.gen_range(0..i),
+            };
+            g[i].push(p);
+            g[p].push(i);
+        }
+        g
+    }
+
+    // Every table, by enumerating the subsets
+    fn brute(
+        g: &[Vec<usize>],
+        root: usize,
+        items: &[(usize, i64)],
+        capacity: usize,
+    ) -> Vec<Vec<Option<i64>>> {
+        let n = g.len();
+        let mut depth = vec![usize::MAX; n];
+        depth[root] = 0;
+        let mut stack = vec![root];
+        let mut subtree_weight = vec![0; n];
+        let mut order = Vec::new();
+        while let Some(x) = stack.pop() {
+            order.push(x);
+            for &y in &g[x] {
+                if depth[y] == usize::MAX {
+                    depth[y] = depth[x] + 1;
+                    stack.push(y);
+                }
+            }
+        }
+        for &x in order.iter().rev() {
+            subtree_weight[x] += items[x].0;
+            for &y in &g[x] {
+                if depth[y] > depth[x] {
+                    subtree_weight[x] += subtree_weight[y];
+                }
+            }
+        }
+        let mut tables = (0..n)
+            .map(|x| vec![None; subtree_weight[x].min(capacity) + 1])
+            .collect::<Vec<Vec<Option<i64>>>>();
+        for bs in 1_usize..1 << n {
+            let edges = (0..n)
+                .flat_map(|x| g[x].iter().map(move |&y| (x, y)))
+                .filter(|&(x, y)| x < y && bs >> x & 1 == 1 && bs >> y & 1 == 1)
+                .count();
+            if edges + 1 != bs.count_ones() as usize {
+                continue;
+            }
+            let top = (0..n)
+                .filter(|&x| bs >> x & 1 == 1)
+                .min_by_key(|&x| depth[x])
+                .unwrap();
+            let weight = (0..n)
+                .filter(|&x| bs >> x & 1 == 1)
+                .map(|x| items[x].0)
+                .sum::<usize>();
+            let value = (0..n)
+                .filter(|&x| bs >> x & 1 == 1)
+                .map(|x| items[x].1)
+                .sum::<i64>();
+            if weight <= capacity {
+                let c = &mut tables[top][weight];
+                *c = Some(c.map_or(value, |c| c.max(value)));
+            }
+        }
+        tables
+    }
+
+    #[test]
+    fn test_random() {
+        let mut rng = StdRng::seed_from_u64(42);
+        for iter in 0..150 {
+            let n = if iter % 30 == 0 { 18 } else { rng.gen_range(1..=12) };
+            let g = random_tree(&mut rng, n);
+            let root = rng.gen_range(0..n);
+            let max_weight: usize = rng.gen_range(0..=3);
+            let items = (0..n)
+                .map(|_| (rng.gen_range(0..=max_weight), rng.gen_range(-10..=10)))
+                .collect::<Vec<_>>();
+            let capacity = rng.gen_range(0..=n * max_weight + 1);
+            let knapsack = TreeKnapsack::new(&g, root, &items, capacity);
+            let expected = brute(&g, root, &items, capacity);
+            for (x, expected) in expected.iter().enumerate() {
+                assert_eq!(knapsack.table(x), expected.as_slice());
+            }
+            assert_eq!(
+                knapsack.best_containing_root(),
+                expected[root].iter().flatten().copied().max()
+            );
+            assert_eq!(
+                knapsack.best_connected(),
+                expected.iter().flatten().flatten().copied().max()
+            );
+        }
+    }
+
+    #[test]
+    fn test_too_heavy() {
+        let g = vec![vec![1], vec![0]];
+        let knapsack = TreeKnapsack::new(&g, 0, &[(5, 1), (1, 2)], 3);
+        assert_eq!(knapsack.table(0), &[None, None, None, None]);
+        assert_eq!(knapsack.table(1), &[None, Some(2)]);
+        assert_eq!(knapsack.best_containing_root(), None);
+        assert_eq!(knapsack.best_connected(), Some(2));
+    }
+
+    fn steps(g: &[Vec<usize>], items: &[(usize, i64)], capacity: usize) -> usize {
+        STEPS.with(|steps| steps.set(0));
+        TreeKnapsack::new(g, 0, items, capacity);
+        STEPS.with(|steps| steps.get())
+    }
+
+    #[test]
+    fn test_steps() {
+        let mut rng = StdRng::seed_from_u64(42);
+        for _ in 0..20 {
+            let n = rng.gen_range(1..=2000);
+            let g = random_tree(&mut rng, n);
+            let items = (0..n)
+                .map(|_| (1, rng.gen_range(-10..=10)))
+                .collect::<Vec<_>>();
+            // $O(n ^ 2)$ without the capacity
+            assert!(steps(&g, &items, n) <= n * n);
+            // $O(n C)$ with it
+            let capacity = rng.gen_range(0..=50);
+            assert!(steps(&g, &items, capacity) <= 2 * n * (capacity + 1));
+        }
+    }
+}
@@ -0,0 +1,41 @@
+use super::prime_factors;
+use super::Value;
+
+/// Returns Euler's totient function $\varphi(n)$, the number of the integers in $1, \dots, n$
+/// coprime to $n$.
+///
+/// # Panics
+///
+/// If `n` is `0`.
+///
+/// # Example
+///
+/// Basic usage:
+/// ```
+/// use trial::euler_phi;
+///
+/// assert_eq!(euler_phi(1u32), 1);
+/// assert_eq!(euler_phi(12u32), 4);
+/// assert_eq!(euler_phi(13u32), 12);
+/// ```
+pub fn euler_phi<T: Value>(n: T) -> T {
+    assert_ne!(n, T::zero(), "Cannot call `euler_phi` by `0`.");
+    prime_factors(n).fold(n, |phi, p| phi / p * (p - T::one()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::euler_phi;
+    use test_case::test_case;
+
+    #[test_case(1 => 1)]
+    #[test_case(2 => 1)]
+    #[test_case(6 => 2)]
+    #[test_case(9 => 6)]
+    #[test_case(36 => 12)]
+    #[test_case(97 => 96)]
+    #[test_case(1024 => 512)]
+    fn test_euler_phi(n: u32) -> u32 {
+        euler_phi(n)
+    }
+}
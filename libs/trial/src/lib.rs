@@ -3,14 +3,17 @@
 //! # Items
 //!
 //! - [`fn@divisors`]: enumerate all the divisor of an integer.
+//! - [`fn@euler_phi`]: compute Euler's totient function of an integer.
 //! - [`fn@prime_factors`]: enumerate all the prime divisor of an integer and the multiplicity of it.
 
 mod divisors;
+mod euler_phi;
 mod prime_factors;
 
 pub use divisors::divisors;
 pub use divisors::divisors_unordered;
 pub use divisors::Divisors;
+pub use euler_phi::euler_phi;
 pub use prime_factors::prime_factors;
 pub use prime_factors::prime_factors_rle;
 pub use prime_factors::PrimeFactors;
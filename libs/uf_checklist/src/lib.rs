@@ -76,7 +76,7 @@
 //! ]);
 //! ```
 
-use std::ops::Bound;
+use open::resolve_range;
 use std::ops::Range;
 use std::ops::RangeBounds;
 use union_find::UnionFind;
@@ -127,14 +127,7 @@ impl UfChecklist {
     /// ```
     pub fn range_check(&mut self, range: impl RangeBounds<usize>) -> Iter<'_> {
         let n = self.rightmost.len() - 1;
-        let Range { mut start, end } = open(range, n);
-        assert!(
-            start <= end && end <= n,
-            "範囲外です。start = {}, end = {}, n = {}",
-            start,
-            end,
-            n
-        );
+        let Range { mut start, end } = resolve_range(n, range);
         start = __next_unckecked_cell(self, start);
         Iter {
             range_check: self,
@@ -295,18 +288,6 @@ impl Iterator for Iter<'_> {
     }
 }
 
-fn open(range: impl RangeBounds<usize>, len: usize) -> Range<usize> {
-    (match range.start_bound() {
-        Bound::Unbounded => 0,
-        Bound::Included(&x) => x,
-        Bound::Excluded(&x) => x + 1,
-    })..match range.end_bound() {
-        Bound::Excluded(&x) => x,
-        Bound::Included(&x) => x + 1,
-        Bound::Unbounded => len,
-    }
-}
-
 #[cfg(test)]
 mod tests {
     use super::UfChecklist;
@@ -357,4 +338,17 @@ mod tests {
             }
         }
     }
+
+    #[test]
+    #[should_panic(expected = "range starts at 3 but ends at 2")]
+    fn test_range_check_reversed_range() {
+        let (start, end) = (3, 2);
+        UfChecklist::new(5).range_check(start..end);
+    }
+
+    #[test]
+    #[should_panic(expected = "range end index 6 out of range for length 5")]
+    fn test_range_check_out_of_range() {
+        UfChecklist::new(5).range_check(..=5);
+    }
 }
@@ -0,0 +1,344 @@
+//! Bases of $\mathbb{F}_2^{64}$ spanned by `u64` values, and their range queries.
+//!
+//! - [`XorBasis`]: a basis with insertions, maximum xor and rank
+//! - [`PrefixXorBasis`]: the basis of any subarray of a fixed array in $O(64)$
+//! - [`XorBasisOp`]: the bases as a monoid for [`segtree::Segtree`], for subarrays with point
+//!   updates
+//!
+//! # Examples
+//!
+//! ```
+//! use segtree::Segtree;
+//! use xor_basis::PrefixXorBasis;
+//! use xor_basis::XorBasis;
+//! use xor_basis::XorBasisOp;
+//!
+//! let a = [0b110, 0b011, 0b101, 0b1000];
+//! let prefix = PrefixXorBasis::new(&a);
+//! assert_eq!(prefix.max_xor(0..3), 0b110);
+//! assert_eq!(prefix.rank(0..3), 2);
+//! assert_eq!(prefix.max_xor(1..), 0b1110);
+//!
+//! let mut segtree = a
+//!     .iter()
+//!     .map(|&x| XorBasis::from_iter([x]))
+//!     .collect::<Segtree<XorBasisOp>>();
+//! *segtree.entry(0) = XorBasis::from_iter([0b001]);
+//! assert_eq!(segtree.fold(0..3).max_xor(), 0b111);
+//! ```
+use open::resolve_range;
+use std::iter::FromIterator;
+use std::ops::Range;
+use std::ops::RangeBounds;
+
+/// A basis in the row echelon form: the `b`-th vector is zero or has the highest bit `b`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct XorBasis {
+    basis: [u64; 64],
+}
+impl XorBasis {
+    /// Constructs the basis of the zero space.
+    pub fn new() -> Self {
+        Self { basis: [0; 64] }
+    }
+
+    /// Adds `x` to the space, and returns `true` if the rank increases.
+    ///
+    /// # Complexity
+    ///
+    /// $O(64)$
+    pub fn insert(&mut self, mut x: u64) -> bool {
+        while x != 0 {
+            let b = 63 - x.leading_zeros() as usize;
+            if self.basis[b] == 0 {
+                self.basis[b] = x;
+                return true;
+            }
+            x ^= self.basis[b];
+        }
+        false
+    }
+
+    /// Returns `true` if `x` is in the space.
+    pub fn contains(&self, mut x: u64) -> bool {
+        while x != 0 {
+            let b = 63 - x.leading_zeros() as usize;
+            if self.basis[b] == 0 {
+                return false;
+            }
+            x ^= self.basis[b];
+        }
+        true
+    }
+
+    /// Returns the maximum of the space.
+    pub fn max_xor(&self) -> u64 {
+        self.basis.iter().rev().fold(0, |acc, &v| acc.max(acc ^ v))
+    }
+
+    /// Returns the dimension of the space.
+    pub fn rank(&self) -> usize {
+        self.basis.iter().filter(|&&v| v != 0).count()
+    }
+
+    /// Returns the vectors of the basis in the descending order of their highest bits.
+    pub fn iter(&self) -> impl Iterator<Item = u64> + '_ {
+        self.basis.iter().rev().copied().filter(|&v| v != 0)
+    }
+
+    /// Adds all the vectors of `other`.
+    ///
+    /// # Complexity
+    ///
+    /// $O(64 ^ 2)$
+    pub fn merge(&mut self, other: &Self) {
+        for v in other.iter() {
+            self.insert(v);
+        }
+    }
+}
+impl Default for XorBasis {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+impl FromIterator<u64> for XorBasis {
+    fn from_iter<T: IntoIterator<Item = u64>>(iter: T) -> Self {
+        let mut basis = Self::new();
+        for x in iter {
+            basis.insert(x);
+        }
+        basis
+    }
+}
+
+/// [`XorBasis`] with the sum of the spaces, for [`segtree::Segtree`].
+pub enum XorBasisOp {}
+impl segtree::Op for XorBasisOp {
+    type Value = XorBasis;
+
+    fn identity() -> Self::Value {
+        XorBasis::new()
+    }
+
+    fn op(lhs: &Self::Value, rhs: &Self::Value) -> Self::Value {
+        let mut result = *lhs;
+        result.merge(rhs);
+        result
+    }
+}
+
+/// The bases of all the prefixes of an array, with the insertion times.
+///
+/// The basis of the prefix $a _ 0, \dots, a _ {r - 1}$ prefers later elements: inserting $a _ i$,
+/// whenever the vector at a bit is older than the one being reduced, they are swapped. Then the
+/// vectors of the time at least $l$ form a basis of $a _ l, \dots, a _ {r - 1}$.
+///
+/// It takes about $768n$ bytes of memory.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PrefixXorBasis {
+    // `prefixes[r]` is the basis of the first `r` elements.
+    prefixes: Vec<([u64; 64], [u32; 64])>,
+}
+impl PrefixXorBasis {
+    /// Constructs the bases of the prefixes of `a`.
+    ///
+    /// # Panics
+    ///
+    /// If $n \ge 2 ^ {32}$.
+    ///
+    /// # Complexity
+    ///
+    /// $O(64n)$
+    pub fn new(a: &[u64]) -> Self {
+        assert!(
+            (a.len() as u64) < 1 << 32,
+            "length {} is too large",
+            a.len()
+        );
+        let mut prefixes = Vec::with_capacity(a.len() + 1);
+        let mut current = ([0; 64], [0; 64]);
+        prefixes.push(current);
+        for (i, &x) in a.iter().enumerate() {
+            let (basis, time) = &mut current;
+            let mut x = x;
+            let mut t = i as u32;
+            while x != 0 {
+                let b = 63 - x.leading_zeros() as usize;
+                if basis[b] == 0 {
+                    basis[b] = x;
+                    time[b] = t;
+                    break;
+                }
+                if time[b] < t {
+                    std::mem::swap(&mut basis[b], &mut x);
+                    std::mem::swap(&mut time[b], &mut t);
+                }
+                x ^= basis[b];
+            }
+            prefixes.push(current);
+        }
+        Self { prefixes }
+    }
+
+    /// Returns the number of the elements.
+    pub fn len(&self) -> usize {
+        self.prefixes.len() - 1
+    }
+
+    /// Returns `true` if there are no elements.
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Returns the basis of the elements in `range`.
+    ///
+    /// # Complexity
+    ///
+    /// $O(64)$
+    pub fn basis(&self, range: impl RangeBounds<usize>) -> XorBasis {
+        let Range { start, end } = resolve_range(self.len(), range);
+        let (basis, time) = &self.prefixes[end];
+        let mut result = XorBasis::new();
+        for b in 0..64 {
+            if basis[b] != 0 && time[b] as usize >= start {
+                result.basis[b] = basis[b];
+            }
+        }
+        result
+    }
+
+    /// Returns the maximum xor of a subset of the elements in `range`.
+    ///
+    /// # Complexity
+    ///
+    /// $O(64)$
+    pub fn max_xor(&self, range: impl RangeBounds<usize>) -> u64 {
+        self.basis(range).max_xor()
+    }
+
+    /// Returns the dimension of the space spanned by the elements in `range`.
+    ///
+    /// # Complexity
+    ///
+    /// $O(64)$
+    pub fn rank(&self, range: impl RangeBounds<usize>) -> usize {
+        self.basis(range).rank()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rand::rngs::StdRng;
+    use rand::Rng;
+    use rand::SeedableRng;
+    use segtree::Segtree;
+
+    // Few bits so that the ranks vary.
+    fn random_vec(rng: &mut StdRng, n: usize) -> Vec<u64> {
+        let bits = rng.gen_range(1..=64);
+        let mask = u64::MAX >> (64 - bits);
+        let sparse = rng.gen_bool(0.5);
+        (0..n)
+            .map(|_| {
+                let x = rng.gen::<u64>() & mask;
+                if sparse {
+                    x & rng.gen::<u64>() & rng.gen::<u64>()
+                } else {
+                    x
+                }
+            })
+            .collect()
+    }
+
+    // The maximum over the span by brute force, for few vectors.
+    fn brute_max_xor(a: &[u64]) -> u64 {
+        (0..1_u32 << a.len())
+            .map(|s| {
+                (0..a.len())
+                    .filter(|&i| s >> i & 1 == 1)
+                    .fold(0, |acc, i| acc ^ a[i])
+            })
+            .max()
+            .unwrap()
+    }
+
+    #[test]
+    fn test_xor_basis() {
+        let mut rng = StdRng::seed_from_u64(42);
+        for _ in 0..300 {
+            let n = rng.gen_range(0..=10);
+            let a = random_vec(&mut rng, n);
+            let basis = a.iter().copied().collect::<XorBasis>();
+            assert_eq!(basis.max_xor(), brute_max_xor(&a));
+            let span = (0..1_u32 << n)
+                .map(|s| {
+                    (0..n)
+                        .filter(|&i| s >> i & 1 == 1)
+                        .fold(0, |acc, i| acc ^ a[i])
+                })
+                .collect::<std::collections::HashSet<_>>();
+            assert_eq!(1 << basis.rank(), span.len());
+            for &x in &span {
+                assert!(basis.contains(x));
+            }
+            let x = rng.gen();
+            assert_eq!(basis.contains(x), span.contains(&x));
+        }
+    }
+
+    #[test]
+    fn test_prefix_xor_basis() {
+        let mut rng = StdRng::seed_from_u64(42);
+        for _ in 0..200 {
+            let n = rng.gen_range(0..=60);
+            let a = random_vec(&mut rng, n);
+            let prefix = PrefixXorBasis::new(&a);
+            assert_eq!(prefix.len(), n);
+            // Every range, including the single elements and the whole array.
+            for l in 0..=n {
+                for r in l..=n {
+                    let expected = a[l..r].iter().copied().collect::<XorBasis>();
+                    assert_eq!(prefix.max_xor(l..r), expected.max_xor());
+                    assert_eq!(prefix.rank(l..r), expected.rank());
+                    let basis = prefix.basis(l..r);
+                    assert!(a[l..r].iter().all(|&x| basis.contains(x)));
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn test_segtree() {
+        let mut rng = StdRng::seed_from_u64(42);
+        for _ in 0..100 {
+            let n = rng.gen_range(1..=40);
+            let mut a = random_vec(&mut rng, n);
+            let mut segtree = a
+                .iter()
+                .map(|&x| XorBasis::from_iter([x]))
+                .collect::<Segtree<XorBasisOp>>();
+            for _ in 0..40 {
+                if rng.gen() {
+                    let i = rng.gen_range(0..n);
+                    a[i] = random_vec(&mut rng, 1)[0];
+                    *segtree.entry(i) = XorBasis::from_iter([a[i]]);
+                } else {
+                    let l = rng.gen_range(0..=n);
+                    let r = rng.gen_range(l..=n);
+                    let expected = a[l..r].iter().copied().collect::<XorBasis>();
+                    let result = segtree.fold(l..r);
+                    assert_eq!(result.max_xor(), expected.max_xor());
+                    assert_eq!(result.rank(), expected.rank());
+                }
+            }
+        }
+    }
+
+    #[test]
+    #[should_panic(expected = "range end index 4 out of range for length 3")]
+    fn test_out_of_range() {
+        PrefixXorBasis::new(&[1, 2, 3]).max_xor(1..4);
+    }
+}
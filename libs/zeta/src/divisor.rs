@@ -0,0 +1,368 @@
+//! 1 つの整数 $N$ の約数束上の高速ゼータ変換、高速メビウス変換です。
+//!
+//! $N = p _ 0 ^ {k _ 0} \cdots p _ {m - 1} ^ {k _ {m - 1}}$ の素因数分解 `[(p_0, k_0), ...]`
+//! を受け取ります。約数 $d = p _ 0 ^ {e _ 0} \cdots p _ {m - 1} ^ {e _ {m - 1}}$ には、
+//! 混合基数表記の添字
+//!
+//! $$
+//! e _ 0 + (k _ 0 + 1) \left( e _ 1 + (k _ 1 + 1) \left( e _ 2 + \cdots \right) \right)
+//! $$
+//!
+//! を割り当てます。添字 $0$ は $1$、最後の添字は $N$ です。添字と約数の対応は
+//! [`divisor_index`], [`divisor_value`] で、添字と指数の組の対応は [`encode_exponents`],
+//! [`decode_index`] で変換できます。
+//!
+//! 変換はどれも $O(\sigma _ 0(N) m)$ です。ここで $\sigma _ 0(N)$ は約数の個数、$m$ は素因数の
+//! 種類数です。
+
+use std::ops::Add;
+use std::ops::Range;
+use std::ops::Sub;
+
+/// 約数の個数 $\prod (k _ i + 1)$ を返します。
+///
+/// # Examples
+///
+/// ```
+/// use zeta::divisor_count;
+/// assert_eq!(divisor_count(&[(2, 2), (3, 1)]), 6);
+/// assert_eq!(divisor_count(&[]), 1);
+/// ```
+pub fn divisor_count(factors: &[(u64, u32)]) -> usize {
+    factors.iter().map(|&(_, k)| k as usize + 1).product()
+}
+
+/// 指数の組 `exponents` から添字を返します。
+///
+/// # Panics
+///
+/// 長さが素因数の種類数と違うときや、指数が大きすぎるとき
+///
+/// # Examples
+///
+/// ```
+/// use zeta::encode_exponents;
+/// // 12 = 2^2 * 3
+/// assert_eq!(encode_exponents(&[(2, 2), (3, 1)], &[1, 1]), 4);
+/// ```
+pub fn encode_exponents(factors: &[(u64, u32)], exponents: &[u32]) -> usize {
+    assert_eq!(
+        factors.len(),
+        exponents.len(),
+        "the number of the exponents differs from that of the primes"
+    );
+    let mut index = 0;
+    for (&(p, k), &e) in factors.iter().zip(exponents).rev() {
+        assert!(
+            e <= k,
+            "the exponent of {} is {}, but should be at most {}",
+            p,
+            e,
+            k
+        );
+        index = index * (k as usize + 1) + e as usize;
+    }
+    index
+}
+
+/// 添字から指数の組を返します。
+///
+/// # Panics
+///
+/// 添字が約数の個数以上のとき
+///
+/// # Examples
+///
+/// ```
+/// use zeta::decode_index;
+/// assert_eq!(decode_index(&[(2, 2), (3, 1)], 4), vec![1, 1]);
+/// ```
+pub fn decode_index(factors: &[(u64, u32)], mut index: usize) -> Vec<u32> {
+    let len = divisor_count(factors);
+    assert!(
+        index < len,
+        "index {} out of range for length {}",
+        index,
+        len
+    );
+    factors
+        .iter()
+        .map(|&(_, k)| {
+            let radix = k as usize + 1;
+            let e = index % radix;
+            index /= radix;
+            e as u32
+        })
+        .collect()
+}
+
+/// 添字の表す約数を返します。
+///
+/// # Panics
+///
+/// 添字が約数の個数以上のとき
+///
+/// # Examples
+///
+/// ```
+/// use zeta::divisor_value;
+/// assert_eq!(divisor_value(&[(2, 2), (3, 1)], 4), 6);
+/// ```
+pub fn divisor_value(factors: &[(u64, u32)], index: usize) -> u64 {
+    factors
+        .iter()
+        .zip(decode_index(factors, index))
+        .map(|(&(p, _), e)| p.pow(e))
+        .product()
+}
+
+/// 約数 `d` の添字を返します。`d` が約数でなければ `None` を返します。
+///
+/// # Examples
+///
+/// ```
+/// use zeta::divisor_index;
+/// assert_eq!(divisor_index(&[(2, 2), (3, 1)], 6), Some(4));
+/// assert_eq!(divisor_index(&[(2, 2), (3, 1)], 8), None);
+/// ```
+pub fn divisor_index(factors: &[(u64, u32)], mut d: u64) -> Option<usize> {
+    if d == 0 {
+        return None;
+    }
+    let mut index = 0;
+    let mut stride = 1;
+    for &(p, k) in factors {
+        let mut e = 0;
+        while d % p == 0 {
+            d /= p;
+            e += 1;
+        }
+        if e > k {
+            return None;
+        }
+        index += e as usize * stride;
+        stride *= k as usize + 1;
+    }
+    (d == 1).then_some(index)
+}
+
+/// 約数を添字の順に返します。
+///
+/// # Examples
+///
+/// ```
+/// use zeta::divisors_by_index;
+/// assert_eq!(divisors_by_index(&[(2, 2), (3, 1)]), vec![
+///     1, 2, 4, 3, 6, 12
+/// ]);
+/// ```
+pub fn divisors_by_index(factors: &[(u64, u32)]) -> Vec<u64> {
+    let mut divisors = vec![1];
+    for &(p, k) in factors {
+        let len = divisors.len();
+        for i in len..len * (k as usize + 1) {
+            divisors.push(divisors[i - len] * p);
+        }
+    }
+    divisors
+}
+
+/// 約数を昇順に返します。
+///
+/// # Examples
+///
+/// ```
+/// use zeta::enumerate_divisors_sorted;
+/// assert_eq!(enumerate_divisors_sorted(&[(2, 2), (3, 1)]), vec![
+///     1, 2, 3, 4, 6, 12
+/// ]);
+/// ```
+pub fn enumerate_divisors_sorted(factors: &[(u64, u32)]) -> Vec<u64> {
+    let mut divisors = divisors_by_index(factors);
+    divisors.sort_unstable();
+    divisors
+}
+
+/// 約数束上のゼータ変換 $b(d) = \sum _ {e \mid d} a(e)$ をします。
+///
+/// # Panics
+///
+/// `a` の長さが約数の個数と違うとき
+///
+/// # Examples
+///
+/// ```
+/// use zeta::divisor_zeta_single;
+/// // 12 の約数 1, 2, 4, 3, 6, 12 の個数
+/// let mut a = [1; 6];
+/// divisor_zeta_single(&[(2, 2), (3, 1)], &mut a);
+/// assert_eq!(a, [1, 2, 3, 2, 4, 6]);
+/// ```
+pub fn divisor_zeta_single<T: Copy + Add<Output = T>>(factors: &[(u64, u32)], a: &mut [T]) {
+    for_each_axis(factors, a.len(), |stride, range| {
+        for i in range {
+            a[i] = a[i] + a[i - stride];
+        }
+    });
+}
+
+/// 約数束上のメビウス変換 ([`divisor_zeta_single`] の逆変換) をします。
+///
+/// # Panics
+///
+/// `a` の長さが約数の個数と違うとき
+///
+/// # Examples
+///
+/// ```
+/// use zeta::divisor_moebius_single;
+/// let mut a = [1, 2, 3, 2, 4, 6];
+/// divisor_moebius_single(&[(2, 2), (3, 1)], &mut a);
+/// assert_eq!(a, [1; 6]);
+/// ```
+pub fn divisor_moebius_single<T: Copy + Sub<Output = T>>(factors: &[(u64, u32)], a: &mut [T]) {
+    for_each_axis(factors, a.len(), |stride, range| {
+        for i in range.rev() {
+            a[i] = a[i] - a[i - stride];
+        }
+    });
+}
+
+// 素因数ごとに、その指数が正の添字を、刻み幅 stride とともに f に渡します。
+// 指数が正の添字は、長さ stride * (k + 1) のブロックの後ろ stride * k 個です。
+fn for_each_axis(factors: &[(u64, u32)], len: usize, mut f: impl FnMut(usize, Range<usize>)) {
+    assert_eq!(
+        len,
+        divisor_count(factors),
+        "the length differs from the number of the divisors"
+    );
+    let mut stride = 1;
+    for &(_, k) in factors {
+        let block = stride * (k as usize + 1);
+        for start in (0..len).step_by(block) {
+            f(stride, start + stride..start + block);
+        }
+        stride = block;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rand::rngs::StdRng;
+    use rand::Rng;
+    use rand::SeedableRng;
+
+    const PRIMES: [u64; 6] = [2, 3, 5, 7, 11, 13];
+
+    fn random_factors(rng: &mut StdRng) -> Vec<(u64, u32)> {
+        let mut factors = Vec::new();
+        for &p in &PRIMES {
+            if rng.gen_bool(0.6) {
+                factors.push((p, rng.gen_range(1..=3_u32)));
+            }
+        }
+        factors
+    }
+
+    fn brute_divisors(n: u64) -> Vec<u64> {
+        (1..=n).filter(|&d| n % d == 0).collect()
+    }
+
+    #[test]
+    fn test_encoding() {
+        let mut rng = StdRng::seed_from_u64(42);
+        for _ in 0..100 {
+            let factors = random_factors(&mut rng);
+            let n = factors.iter().map(|&(p, k)| p.pow(k)).product::<u64>();
+            let len = divisor_count(&factors);
+            let divisors = divisors_by_index(&factors);
+            assert_eq!(divisors.len(), len);
+            assert_eq!(divisors[0], 1);
+            assert_eq!(divisors[len - 1], n);
+            for (i, &d) in divisors.iter().enumerate() {
+                let exponents = decode_index(&factors, i);
+                assert_eq!(encode_exponents(&factors, &exponents), i);
+                assert_eq!(divisor_value(&factors, i), d);
+                assert_eq!(divisor_index(&factors, d), Some(i));
+            }
+            if n < 100_000 {
+                let expected = brute_divisors(n);
+                assert_eq!(enumerate_divisors_sorted(&factors), expected);
+                for d in 0..=n + 1 {
+                    let is_divisor = d != 0 && n % d == 0;
+                    assert_eq!(divisor_index(&factors, d).is_some(), is_divisor);
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn test_zeta_moebius() {
+        let mut rng = StdRng::seed_from_u64(42);
+        for _ in 0..100 {
+            let factors = random_factors(&mut rng);
+            let divisors = divisors_by_index(&factors);
+            let len = divisors.len();
+            let a = (0..len)
+                .map(|_| rng.gen_range(-100..=100_i64))
+                .collect::<Vec<_>>();
+            let expected = divisors
+                .iter()
+                .map(|&d| {
+                    (0..len)
+                        .filter(|&e| d % divisors[e] == 0)
+                        .map(|e| a[e])
+                        .sum::<i64>()
+                })
+                .collect::<Vec<_>>();
+            let mut result = a.clone();
+            divisor_zeta_single(&factors, &mut result);
+            assert_eq!(result, expected);
+            divisor_moebius_single(&factors, &mut result);
+            assert_eq!(result, a);
+        }
+    }
+
+    #[test]
+    fn test_one_and_prime_powers() {
+        assert_eq!(divisors_by_index(&[]), vec![1]);
+        assert_eq!(decode_index(&[], 0), Vec::<u32>::new());
+        assert_eq!(divisor_index(&[], 1), Some(0));
+        assert_eq!(divisor_index(&[], 2), None);
+        let mut a = [5];
+        divisor_zeta_single(&[], &mut a);
+        assert_eq!(a, [5]);
+
+        // 素数冪では累積和です。
+        let factors = [(2, 10)];
+        let mut a = (0..11_i64).collect::<Vec<_>>();
+        divisor_zeta_single(&factors, &mut a);
+        assert_eq!(a, (0..11).map(|i| i * (i + 1) / 2).collect::<Vec<_>>());
+        divisor_moebius_single(&factors, &mut a);
+        assert_eq!(a, (0..11).collect::<Vec<_>>());
+        assert_eq!(divisor_index(&factors, 1024), Some(10));
+        assert_eq!(divisor_index(&factors, 2048), None);
+
+        // 大きな N
+        let factors = [(1_000_000_007, 1), (998_244_353, 1)];
+        assert_eq!(enumerate_divisors_sorted(&factors), vec![
+            1,
+            998_244_353,
+            1_000_000_007,
+            998_244_353 * 1_000_000_007
+        ]);
+    }
+
+    #[test]
+    #[should_panic(expected = "the length differs from the number of the divisors")]
+    fn test_wrong_length() {
+        divisor_zeta_single(&[(2, 2)], &mut [0; 4]);
+    }
+
+    #[test]
+    #[should_panic(expected = "the exponent of 3 is 2, but should be at most 1")]
+    fn test_exponent_too_large() {
+        encode_exponents(&[(2, 2), (3, 1)], &[0, 2]);
+    }
+}
@@ -24,6 +24,24 @@
 //! | &     | [`bitand`]   | [`rbitand`]       | 非可逆         | 非可逆            |
 //!
 //!
+//! # マスクの集約
+//!
+//! 重みつきのビットマスクの列に関する、よく使う集約も用意しています。
+//!
+//! - 共通部分が空なペアの個数、重みの積の総和: [`count_disjoint_pairs`], [`sum_disjoint_pair_products`]
+//! - 部分集合・上位集合の重みの最大値、最小値: [`submask_max`], [`submask_min`], [`supermask_max`], [`supermask_min`]
+//! - 部分集合の重みの上位 2 つと、共通部分が空なペアの重みの和の最大化: [`submask_top2`], [`best_disjoint_pair`]
+//!
+//!
+//! # 約数束
+//!
+//! 1 つの整数の約数全体の上での変換です。添字の付け方は [`divisor_index`] などで変換できます。
+//!
+//! - ゼータ変換、メビウス変換: [`divisor_zeta_single`], [`divisor_moebius_single`]
+//! - 添字の変換: [`divisor_index`], [`divisor_value`], [`encode_exponents`], [`decode_index`]
+//! - 約数の列挙: [`divisors_by_index`], [`enumerate_divisors_sorted`]
+//!
+//!
 //! # Examples
 //!
 //! [`add`] で + に関するゼータ変換ができます。
@@ -46,6 +64,26 @@
 //! assert_eq!(a, [1, 2, 4, 8]);
 //! ```
 
+mod divisor;
+mod mask_aggr;
+
+pub use divisor::decode_index;
+pub use divisor::divisor_count;
+pub use divisor::divisor_index;
+pub use divisor::divisor_moebius_single;
+pub use divisor::divisor_value;
+pub use divisor::divisor_zeta_single;
+pub use divisor::divisors_by_index;
+pub use divisor::encode_exponents;
+pub use divisor::enumerate_divisors_sorted;
+pub use mask_aggr::best_disjoint_pair;
+pub use mask_aggr::count_disjoint_pairs;
+pub use mask_aggr::submask_max;
+pub use mask_aggr::submask_min;
+pub use mask_aggr::submask_top2;
+pub use mask_aggr::sum_disjoint_pair_products;
+pub use mask_aggr::supermask_max;
+pub use mask_aggr::supermask_min;
 use std::cmp::Ord;
 use std::cmp::{self};
 use std::ops::Add;
@@ -0,0 +1,332 @@
+//! ビットマスクに重みのついた要素の列に関する、部分集合・上位集合上の集約です。
+//!
+//! 幅 `width` のマスクについて、長さ `1 << width` のテーブルを作って [`zeta`] します。
+//! 幅 22 くらいまでを想定しています。
+
+use crate::for_each;
+use crate::zeta;
+
+/// `a[i] & a[j] == 0` なる `i < j` の個数を返します。
+///
+/// # 計算量
+///
+/// O ( w 2 ^ w + n )
+///
+///
+/// # Examples
+///
+/// ```
+/// use zeta::count_disjoint_pairs;
+/// assert_eq!(count_disjoint_pairs(3, &[0b001, 0b010, 0b011, 0b000]), 4);
+/// ```
+pub fn count_disjoint_pairs(width: usize, masks: &[usize]) -> u64 {
+    let full = (1 << width) - 1;
+    let mut count = vec![0_u64; 1 << width];
+    for &mask in masks {
+        check(width, mask);
+        count[mask] += 1;
+    }
+    let zeros = count[0];
+    zeta(&mut count, |x, y| x + y);
+    let total = masks.iter().map(|&mask| count[full & !mask]).sum::<u64>();
+    // 空集合どうしの自己ペアを除きます。
+    (total - zeros) / 2
+}
+
+/// `a[i] & a[j] == 0` なる `i < j` について、重みの積 `w[i] * w[j]` の総和を返します。
+///
+/// # 計算量
+///
+/// O ( w 2 ^ w + n )
+///
+///
+/// # Examples
+///
+/// ```
+/// use zeta::sum_disjoint_pair_products;
+/// let items = [(0b01, 2), (0b10, 3), (0b11, 5), (0b00, -1)];
+/// assert_eq!(sum_disjoint_pair_products(2, &items), 6 - 2 - 3 - 5);
+/// ```
+pub fn sum_disjoint_pair_products(width: usize, items: &[(usize, i64)]) -> i128 {
+    let full = (1 << width) - 1;
+    let mut sum = vec![0_i128; 1 << width];
+    for &(mask, w) in items {
+        check(width, mask);
+        sum[mask] += i128::from(w);
+    }
+    let zeros = items
+        .iter()
+        .filter(|&&(mask, _)| mask == 0)
+        .map(|&(_, w)| i128::from(w) * i128::from(w))
+        .sum::<i128>();
+    zeta(&mut sum, |x, y| x + y);
+    let total = items
+        .iter()
+        .map(|&(mask, w)| i128::from(w) * sum[full & !mask])
+        .sum::<i128>();
+    (total - zeros) / 2
+}
+
+/// 各マスク `S` について、`a[i] ⊆ S` なる `w[i]` の最大値を返します。
+///
+/// # Examples
+///
+/// ```
+/// use zeta::submask_max;
+/// let items = [(0b01, 2), (0b10, 3), (0b01, -1)];
+/// assert_eq!(submask_max(2, &items), [None, Some(2), Some(3), Some(3)]);
+/// ```
+pub fn submask_max(width: usize, items: &[(usize, i64)]) -> Vec<Option<i64>> {
+    let mut a = table(width, items, i64::max);
+    zeta(&mut a, |x, y| merge(x, y, i64::max));
+    a
+}
+
+/// 各マスク `S` について、`a[i] ⊆ S` なる `w[i]` の最小値を返します。
+pub fn submask_min(width: usize, items: &[(usize, i64)]) -> Vec<Option<i64>> {
+    let mut a = table(width, items, i64::min);
+    zeta(&mut a, |x, y| merge(x, y, i64::min));
+    a
+}
+
+/// 各マスク `S` について、`S ⊆ a[i]` なる `w[i]` の最大値を返します。
+pub fn supermask_max(width: usize, items: &[(usize, i64)]) -> Vec<Option<i64>> {
+    let mut a = table(width, items, i64::max);
+    crate::rzeta(&mut a, |x, y| merge(x, y, i64::max));
+    a
+}
+
+/// 各マスク `S` について、`S ⊆ a[i]` なる `w[i]` の最小値を返します。
+pub fn supermask_min(width: usize, items: &[(usize, i64)]) -> Vec<Option<i64>> {
+    let mut a = table(width, items, i64::min);
+    crate::rzeta(&mut a, |x, y| merge(x, y, i64::min));
+    a
+}
+
+/// 各マスク `S` について、`a[i] ⊆ S` なる添字 `i` のうち重みの大きい 2 つを返します。
+///
+/// 重みが大きい順、同じならば添字が小さい順です。足りないところは `None` です。
+/// 同じ要素が 2 回入ることはありません。
+///
+///
+/// # 計算量
+///
+/// O ( w 2 ^ w + n )
+///
+///
+/// # Examples
+///
+/// ```
+/// use zeta::submask_top2;
+/// let items = [(0b01, 2), (0b10, 3), (0b01, 3)];
+/// assert_eq!(submask_top2(2, &items), [
+///     [None, None],
+///     [Some(2), Some(0)],
+///     [Some(1), None],
+///     [Some(1), Some(2)],
+/// ]);
+/// ```
+pub fn submask_top2(width: usize, items: &[(usize, i64)]) -> Vec<[Option<usize>; 2]> {
+    let mut a = vec![[None; 2]; 1 << width];
+    for (i, &(mask, _)) in items.iter().enumerate() {
+        check(width, mask);
+        a[mask] = top2(items, a[mask], [Some(i), None]);
+    }
+    for_each(&mut a, |x, y| *y = top2(items, *x, *y));
+    a
+}
+
+/// `a[i] & a[j] == 0` なる `i < j` のうち、`w[i] + w[j]` が最大のものを返します。
+///
+/// 最大のものが複数あるときは、どれを返すかは未規定です。
+///
+///
+/// # 計算量
+///
+/// O ( w 2 ^ w + n )
+///
+///
+/// # Examples
+///
+/// ```
+/// use zeta::best_disjoint_pair;
+/// let items = [(0b01, 2), (0b11, 10), (0b10, 3), (0b00, -1)];
+/// assert_eq!(best_disjoint_pair(2, &items), Some((1, 3)));
+/// assert_eq!(best_disjoint_pair(2, &[(0b11, 1)]), None);
+/// ```
+pub fn best_disjoint_pair(width: usize, items: &[(usize, i64)]) -> Option<(usize, usize)> {
+    let full = (1 << width) - 1;
+    let top = submask_top2(width, items);
+    let mut best: Option<(usize, usize)> = None;
+    for (i, &(mask, w)) in items.iter().enumerate() {
+        // 空集合は自分自身と素なので、自分以外で最大のものを取ります。
+        let Some(j) = top[full & !mask]
+            .iter()
+            .flatten()
+            .copied()
+            .find(|&j| j != i)
+        else {
+            continue;
+        };
+        if best.map_or(true, |(k, l)| items[k].1 + items[l].1 < w + items[j].1) {
+            best = Some((i.min(j), i.max(j)));
+        }
+    }
+    best
+}
+
+fn check(width: usize, mask: usize) {
+    assert!(
+        mask >> width == 0,
+        "mask {:#b} is out of the width {}",
+        mask,
+        width
+    );
+}
+
+fn table(width: usize, items: &[(usize, i64)], f: fn(i64, i64) -> i64) -> Vec<Option<i64>> {
+    let mut a = vec![None; 1 << width];
+    for &(mask, w) in items {
+        check(width, mask);
+        a[mask] = merge(a[mask], Some(w), f);
+    }
+    a
+}
+
+fn merge(x: Option<i64>, y: Option<i64>, f: fn(i64, i64) -> i64) -> Option<i64> {
+    match (x, y) {
+        (Some(x), Some(y)) => Some(f(x, y)),
+        _ => x.or(y),
+    }
+}
+
+// 2 つの上位 2 つから、重複を除いて上位 2 つを選びます。
+fn top2(
+    items: &[(usize, i64)],
+    x: [Option<usize>; 2],
+    y: [Option<usize>; 2],
+) -> [Option<usize>; 2] {
+    let key = |i: usize| (std::cmp::Reverse(items[i].1), i);
+    let mut result = [None; 2];
+    for &i in x.iter().chain(&y).flatten() {
+        if result.contains(&Some(i)) {
+            continue;
+        }
+        match result {
+            [None, _] => result[0] = Some(i),
+            [Some(a), _] if key(i) < key(a) => result = [Some(i), result[0]],
+            [_, None] => result[1] = Some(i),
+            [_, Some(b)] if key(i) < key(b) => result[1] = Some(i),
+            _ => {}
+        }
+    }
+    result
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rand::prelude::StdRng;
+    use rand::Rng;
+    use rand::SeedableRng;
+
+    fn random_items(rng: &mut StdRng, width: usize) -> Vec<(usize, i64)> {
+        let n = rng.gen_range(0..=40);
+        // 重みの重複が起きやすいように、幅を狭くすることがあります。
+        let max: i64 = if rng.gen_bool(0.5) { 3 } else { 1_000_000_000_000 };
+        // 空集合や密なマスクも混ぜます。
+        let density = rng.gen_range(0.0..1.0);
+        (0..n)
+            .map(|_| {
+                let mask = (0..width)
+                    .filter(|_| rng.gen_bool(density))
+                    .fold(0, |mask, j| mask | 1 << j);
+                (mask, rng.gen_range(-max..=max))
+            })
+            .collect()
+    }
+
+    fn disjoint_pairs(items: &[(usize, i64)]) -> Vec<(usize, usize)> {
+        let mut pairs = Vec::new();
+        for i in 0..items.len() {
+            for j in i + 1..items.len() {
+                if items[i].0 & items[j].0 == 0 {
+                    pairs.push((i, j));
+                }
+            }
+        }
+        pairs
+    }
+
+    #[test]
+    fn test_random() {
+        let mut rng = StdRng::seed_from_u64(42);
+        for _ in 0..300 {
+            let width = rng.gen_range(0..=14);
+            let items = random_items(&mut rng, width);
+            let masks = items.iter().map(|&(mask, _)| mask).collect::<Vec<_>>();
+            let pairs = disjoint_pairs(&items);
+
+            assert_eq!(count_disjoint_pairs(width, &masks), pairs.len() as u64);
+            assert_eq!(
+                sum_disjoint_pair_products(width, &items),
+                pairs
+                    .iter()
+                    .map(|&(i, j)| i128::from(items[i].1) * i128::from(items[j].1))
+                    .sum::<i128>()
+            );
+
+            let sub = |s: usize| items.iter().filter(move |&&(mask, _)| mask & !s == 0);
+            let sup = |s: usize| items.iter().filter(move |&&(mask, _)| s & !mask == 0);
+            let all = 0..1_usize << width;
+            assert_eq!(
+                submask_max(width, &items),
+                all.clone()
+                    .map(|s| sub(s).map(|x| x.1).max())
+                    .collect::<Vec<_>>()
+            );
+            assert_eq!(
+                submask_min(width, &items),
+                all.clone()
+                    .map(|s| sub(s).map(|x| x.1).min())
+                    .collect::<Vec<_>>()
+            );
+            assert_eq!(
+                supermask_max(width, &items),
+                all.clone()
+                    .map(|s| sup(s).map(|x| x.1).max())
+                    .collect::<Vec<_>>()
+            );
+            assert_eq!(
+                supermask_min(width, &items),
+                all.clone()
+                    .map(|s| sup(s).map(|x| x.1).min())
+                    .collect::<Vec<_>>()
+            );
+
+            let top = submask_top2(width, &items);
+            for s in all {
+                let mut expected = (0..items.len())
+                    .filter(|&i| items[i].0 & !s == 0)
+                    .collect::<Vec<_>>();
+                expected.sort_by_key(|&i| (std::cmp::Reverse(items[i].1), i));
+                expected.resize(2.max(expected.len()), !0);
+                let expected = [expected[0], expected[1]].map(|i| (i != !0).then_some(i));
+                assert_eq!(top[s], expected);
+            }
+
+            let result = best_disjoint_pair(width, &items);
+            let expected = pairs.iter().map(|&(i, j)| items[i].1 + items[j].1).max();
+            assert_eq!(result.map(|(i, j)| items[i].1 + items[j].1), expected);
+            if let Some((i, j)) = result {
+                assert!(i < j && items[i].0 & items[j].0 == 0);
+            }
+        }
+    }
+
+    #[test]
+    #[should_panic(expected = "mask 0b100 is out of the width 2")]
+    fn test_out_of_width() {
+        count_disjoint_pairs(2, &[0b100]);
+    }
+}